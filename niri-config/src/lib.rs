@@ -14,8 +14,9 @@ use knuffel::Decode as _;
 use layer_rule::LayerRule;
 use miette::{miette, Context, IntoDiagnostic};
 use niri_ipc::{
-    ColumnDisplay, ConfiguredMode, LayoutSwitchTarget, PositionChange, SizeChange, Transform,
-    WorkspaceReferenceArg,
+    ColumnDisplay, ConfiguredMode, FloatingTileArea, LayoutKind, LayoutSwitchTarget,
+    PositionChange, SizeChange, Transform, WindowHintAction, WindowSwitcherFilter,
+    WindowSwitcherScope, WorkspaceReferenceArg,
 };
 use smithay::backend::renderer::Color32F;
 use smithay::input::keyboard::keysyms::KEY_NoSymbol;
@@ -54,10 +55,24 @@ pub struct Config {
     ]
     pub screenshot_path: Option<String>,
     #[knuffel(child, default)]
+    pub screenshot_annotations: ScreenshotAnnotations,
+    #[knuffel(child, default)]
     pub clipboard: Clipboard,
     #[knuffel(child, default)]
     pub hotkey_overlay: HotkeyOverlay,
     #[knuffel(child, default)]
+    pub bell: Bell,
+    #[knuffel(child, default)]
+    pub remote_desktop: RemoteDesktop,
+    #[knuffel(child, default)]
+    pub screencast_indicator: ScreencastIndicator,
+    #[knuffel(child, default)]
+    pub recording: Recording,
+    #[knuffel(child, default)]
+    pub night_light: NightLight,
+    #[knuffel(child, default)]
+    pub idle: Idle,
+    #[knuffel(child, default)]
     pub animations: Animations,
     #[knuffel(child, default)]
     pub gestures: Gestures,
@@ -187,6 +202,17 @@ pub enum NewColumnLocation {
     LastOfWorkspace,
 }
 
+#[derive(knuffel::DecodeScalar, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum ScrollOrientation {
+    /// Columns go left-to-right and the view scrolls horizontally.
+    #[default]
+    Horizontal,
+    /// Columns become rows going top-to-bottom and the view scrolls vertically.
+    Vertical,
+    /// Vertical on outputs taller than they are wide, horizontal otherwise.
+    Auto,
+}
+
 #[derive(knuffel::DecodeScalar, Debug, Default, PartialEq, Eq)]
 pub enum TrackLayout {
     /// The layout change is global.
@@ -471,6 +497,12 @@ pub struct Output {
     pub background_color: Option<Color>,
     #[knuffel(child)]
     pub backdrop_color: Option<Color>,
+    #[knuffel(child)]
+    pub hdr: Option<OutputHdr>,
+    #[knuffel(child, unwrap(argument))]
+    pub bit_depth: Option<u8>,
+    #[knuffel(child, unwrap(argument))]
+    pub scroll_orientation: Option<ScrollOrientation>,
 }
 
 impl Output {
@@ -485,6 +517,11 @@ impl Output {
     pub fn is_vrr_always_off(&self) -> bool {
         self.variable_refresh_rate.is_none()
     }
+
+    /// Returns whether the output should use 10-bit color formats.
+    pub fn is_10_bit(&self) -> bool {
+        self.hdr.is_some() || self.bit_depth.is_some_and(|depth| depth >= 10)
+    }
 }
 
 impl Default for Output {
@@ -500,10 +537,41 @@ impl Default for Output {
             variable_refresh_rate: None,
             background_color: None,
             backdrop_color: None,
+            hdr: None,
+            bit_depth: None,
+            scroll_orientation: None,
+        }
+    }
+}
+
+#[derive(knuffel::Decode, Debug, Clone, Copy, PartialEq)]
+pub struct OutputHdr {
+    #[knuffel(child, unwrap(argument, str), default)]
+    pub transfer_function: HdrTransferFunction,
+    #[knuffel(child, unwrap(argument), default = Self::default().reference_luminance)]
+    pub reference_luminance: u32,
+    #[knuffel(child, unwrap(argument), default = Self::default().max_luminance)]
+    pub max_luminance: u32,
+}
+
+impl Default for OutputHdr {
+    fn default() -> Self {
+        Self {
+            transfer_function: HdrTransferFunction::default(),
+            // Reference white from ITU-R BT.2408.
+            reference_luminance: 203,
+            max_luminance: 1000,
         }
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HdrTransferFunction {
+    #[default]
+    Pq,
+    Hlg,
+}
+
 #[derive(Debug, Clone)]
 pub struct OutputName {
     pub connector: String,
@@ -542,6 +610,8 @@ pub struct Layout {
     pub tab_indicator: TabIndicator,
     #[knuffel(child, default)]
     pub insert_hint: InsertHint,
+    #[knuffel(child, default)]
+    pub floating_snap: FloatingSnap,
     #[knuffel(child, unwrap(children), default)]
     pub preset_column_widths: Vec<PresetSize>,
     #[knuffel(child)]
@@ -552,6 +622,8 @@ pub struct Layout {
     pub center_focused_column: CenterFocusedColumn,
     #[knuffel(child, unwrap(argument), default)]
     pub new_column_location: NewColumnLocation,
+    #[knuffel(child, unwrap(argument), default)]
+    pub scroll_orientation: ScrollOrientation,
     #[knuffel(child)]
     pub always_center_single_column: bool,
     #[knuffel(child)]
@@ -574,10 +646,12 @@ impl Default for Layout {
             shadow: Default::default(),
             tab_indicator: Default::default(),
             insert_hint: Default::default(),
+            floating_snap: Default::default(),
             preset_column_widths: Default::default(),
             default_column_width: Default::default(),
             center_focused_column: Default::default(),
             new_column_location: Default::default(),
+            scroll_orientation: Default::default(),
             always_center_single_column: false,
             empty_workspace_above_first: false,
             default_column_display: ColumnDisplay::Normal,
@@ -846,6 +920,8 @@ pub struct TabIndicator {
     pub hide_when_single_tab: bool,
     #[knuffel(child)]
     pub place_within_column: bool,
+    #[knuffel(child)]
+    pub show_icons: bool,
     #[knuffel(child, unwrap(argument), default = Self::default().gap)]
     pub gap: FloatOrInt<-65535, 65535>,
     #[knuffel(child, unwrap(argument), default = Self::default().width)]
@@ -878,6 +954,7 @@ impl Default for TabIndicator {
             off: false,
             hide_when_single_tab: false,
             place_within_column: false,
+            show_icons: false,
             gap: FloatOrInt(5.),
             width: FloatOrInt(4.),
             length: TabIndicatorLength {
@@ -930,6 +1007,26 @@ impl Default for InsertHint {
     }
 }
 
+#[derive(knuffel::Decode, Debug, Clone, Copy, PartialEq)]
+pub struct FloatingSnap {
+    #[knuffel(child)]
+    pub off: bool,
+    #[knuffel(child, unwrap(argument), default = Self::default().distance)]
+    pub distance: FloatOrInt<0, 65535>,
+    #[knuffel(child)]
+    pub disable_edge_tiling: bool,
+}
+
+impl Default for FloatingSnap {
+    fn default() -> Self {
+        Self {
+            off: false,
+            distance: FloatOrInt(16.),
+            disable_edge_tiling: false,
+        }
+    }
+}
+
 /// RGB color in [0, 1] with unpremultiplied alpha.
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Color {
@@ -1063,6 +1160,144 @@ pub struct Clipboard {
     pub disable_primary: bool,
 }
 
+#[derive(knuffel::Decode, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Bell {
+    #[knuffel(child)]
+    pub off: bool,
+    #[knuffel(child)]
+    pub skip_urgent: bool,
+    #[knuffel(child)]
+    pub flash: bool,
+    #[knuffel(child)]
+    pub flash_window: bool,
+    #[knuffel(child)]
+    pub spawn: Option<BellSpawn>,
+}
+
+#[derive(knuffel::Decode, Debug, Clone, PartialEq, Eq)]
+pub struct BellSpawn {
+    #[knuffel(arguments)]
+    pub command: Vec<String>,
+}
+
+#[derive(knuffel::Decode, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteDesktop {
+    #[knuffel(child)]
+    pub on: bool,
+}
+
+#[derive(knuffel::Decode, Debug, Clone, Copy, PartialEq)]
+pub struct ScreencastIndicator {
+    #[knuffel(child)]
+    pub on: bool,
+    #[knuffel(child, unwrap(argument), default = Self::default().width)]
+    pub width: FloatOrInt<0, 65535>,
+    #[knuffel(child, default = Self::default().color)]
+    pub color: Color,
+}
+
+impl Default for ScreencastIndicator {
+    fn default() -> Self {
+        Self {
+            on: false,
+            width: FloatOrInt(4.),
+            color: Color::from_rgba8_unpremul(224, 27, 36, 255),
+        }
+    }
+}
+
+#[derive(knuffel::Decode, Debug, Clone, PartialEq)]
+pub struct ScreenshotAnnotations {
+    #[knuffel(child, unwrap(argument), default = Self::default().width)]
+    pub width: FloatOrInt<0, 65535>,
+    #[knuffel(child, default = Self::default().color)]
+    pub color: Color,
+}
+
+impl Default for ScreenshotAnnotations {
+    fn default() -> Self {
+        Self {
+            width: FloatOrInt(4.),
+            color: Color::from_rgba8_unpremul(224, 27, 36, 255),
+        }
+    }
+}
+
+#[derive(knuffel::Decode, Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    #[knuffel(child, unwrap(argument), default = Self::default().path)]
+    pub path: String,
+    #[knuffel(child, unwrap(argument), default = Self::default().max_fps)]
+    pub max_fps: u32,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self {
+            path: String::from("~/Videos/Recordings/Recording from %Y-%m-%d %H-%M-%S"),
+            max_fps: 30,
+        }
+    }
+}
+
+#[derive(knuffel::Decode, Debug, Default, Clone, PartialEq)]
+pub struct Idle {
+    #[knuffel(children(name = "timeout"))]
+    pub timeouts: Vec<IdleTimeout>,
+}
+
+#[derive(knuffel::Decode, Debug, Clone, PartialEq)]
+pub struct IdleTimeout {
+    /// Idle time in seconds after which the actions run.
+    #[knuffel(argument)]
+    pub seconds: u32,
+    #[knuffel(children)]
+    pub actions: Vec<Action>,
+}
+
+#[derive(knuffel::Decode, Debug, Clone, Copy, PartialEq)]
+pub struct NightLight {
+    #[knuffel(child)]
+    pub on: bool,
+    #[knuffel(child, unwrap(argument), default = Self::default().temperature)]
+    pub temperature: u32,
+    #[knuffel(child, unwrap(argument, str))]
+    pub from: Option<TimeOfDay>,
+    #[knuffel(child, unwrap(argument, str))]
+    pub to: Option<TimeOfDay>,
+    #[knuffel(child, unwrap(argument))]
+    pub latitude: Option<FloatOrInt<-90, 90>>,
+    #[knuffel(child, unwrap(argument))]
+    pub longitude: Option<FloatOrInt<-180, 180>>,
+}
+
+impl Default for NightLight {
+    fn default() -> Self {
+        Self {
+            on: false,
+            temperature: 4000,
+            from: None,
+            to: None,
+            latitude: None,
+            longitude: None,
+        }
+    }
+}
+
+/// Local time of day, as written in `HH:MM` format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeOfDay {
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl TimeOfDay {
+    /// Returns the number of minutes since midnight.
+    pub fn minutes(self) -> u32 {
+        u32::from(self.hour) * 60 + u32::from(self.minute)
+    }
+}
+
 #[derive(knuffel::Decode, Debug, Clone, PartialEq)]
 pub struct Animations {
     #[knuffel(child)]
@@ -1087,6 +1322,8 @@ pub struct Animations {
     pub screenshot_ui_open: ScreenshotUiOpenAnim,
     #[knuffel(child, default)]
     pub overview_open_close: OverviewOpenCloseAnim,
+    #[knuffel(child, default)]
+    pub night_light: NightLightAnim,
 }
 
 impl Default for Animations {
@@ -1103,6 +1340,7 @@ impl Default for Animations {
             config_notification_open_close: Default::default(),
             screenshot_ui_open: Default::default(),
             overview_open_close: Default::default(),
+            night_light: Default::default(),
         }
     }
 }
@@ -1253,6 +1491,21 @@ impl Default for ScreenshotUiOpenAnim {
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct OverviewOpenCloseAnim(pub Animation);
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NightLightAnim(pub Animation);
+
+impl Default for NightLightAnim {
+    fn default() -> Self {
+        Self(Animation {
+            off: false,
+            kind: AnimationKind::Easing(EasingParams {
+                duration_ms: 3000,
+                curve: AnimationCurve::Linear,
+            }),
+        })
+    }
+}
+
 impl Default for OverviewOpenCloseAnim {
     fn default() -> Self {
         Self(Animation {
@@ -1299,7 +1552,7 @@ pub struct SpringParams {
     pub epsilon: f64,
 }
 
-#[derive(knuffel::Decode, Debug, Default, Clone, Copy, PartialEq)]
+#[derive(knuffel::Decode, Debug, Default, Clone, PartialEq)]
 pub struct Gestures {
     #[knuffel(child, default)]
     pub dnd_edge_view_scroll: DndEdgeViewScroll,
@@ -1349,10 +1602,109 @@ impl Default for DndEdgeWorkspaceSwitch {
     }
 }
 
-#[derive(knuffel::Decode, Debug, Default, Clone, Copy, PartialEq)]
+#[derive(knuffel::Decode, Debug, Clone, PartialEq)]
 pub struct HotCorners {
     #[knuffel(child)]
     pub off: bool,
+    #[knuffel(child, unwrap(argument), default = Self::default().size)]
+    pub size: FloatOrInt<1, 64>,
+    #[knuffel(child, unwrap(argument), default = Self::default().delay_ms)]
+    pub delay_ms: u16,
+    #[knuffel(child, unwrap(argument), default = Self::default().pressure)]
+    pub pressure: FloatOrInt<0, 65535>,
+    #[knuffel(child)]
+    pub top_left: Option<HotCornerAction>,
+    #[knuffel(child)]
+    pub top_right: Option<HotCornerAction>,
+    #[knuffel(child)]
+    pub bottom_left: Option<HotCornerAction>,
+    #[knuffel(child)]
+    pub bottom_right: Option<HotCornerAction>,
+    #[knuffel(children(name = "output"))]
+    pub outputs: Vec<HotCornersOutput>,
+}
+
+impl Default for HotCorners {
+    fn default() -> Self {
+        Self {
+            off: false,
+            size: FloatOrInt(1.),
+            delay_ms: 0,
+            pressure: FloatOrInt(0.),
+            top_left: None,
+            top_right: None,
+            bottom_left: None,
+            bottom_right: None,
+            outputs: Vec::new(),
+        }
+    }
+}
+
+/// Hot corner settings overriding the global ones on a specific output.
+#[derive(knuffel::Decode, Debug, Clone, PartialEq)]
+pub struct HotCornersOutput {
+    #[knuffel(argument)]
+    pub name: String,
+    #[knuffel(child)]
+    pub off: bool,
+    #[knuffel(child)]
+    pub top_left: Option<HotCornerAction>,
+    #[knuffel(child)]
+    pub top_right: Option<HotCornerAction>,
+    #[knuffel(child)]
+    pub bottom_left: Option<HotCornerAction>,
+    #[knuffel(child)]
+    pub bottom_right: Option<HotCornerAction>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotCornerAction(pub Action);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl HotCorners {
+    /// Returns the action of the corner, taking the output overrides into account.
+    ///
+    /// When no corner is configured, the top-left corner toggles the overview.
+    pub fn action(&self, output: Option<&HotCornersOutput>, corner: HotCorner) -> Option<Action> {
+        if self.off {
+            return None;
+        }
+
+        if let Some(output) = output {
+            if output.off {
+                return None;
+            }
+
+            let corners = [
+                &output.top_left,
+                &output.top_right,
+                &output.bottom_left,
+                &output.bottom_right,
+            ];
+            if corners.iter().any(|x| x.is_some()) {
+                return corners[corner as usize].clone().map(|x| x.0);
+            }
+        }
+
+        let corners = [
+            &self.top_left,
+            &self.top_right,
+            &self.bottom_left,
+            &self.bottom_right,
+        ];
+        if corners.iter().all(|x| x.is_none()) {
+            return (corner == HotCorner::TopLeft).then_some(Action::ToggleOverview);
+        }
+
+        corners[corner as usize].clone().map(|x| x.0)
+    }
 }
 
 #[derive(knuffel::Decode, Debug, Clone, Copy, PartialEq)]
@@ -1409,6 +1761,10 @@ pub struct Workspace {
     pub name: WorkspaceName,
     #[knuffel(child, unwrap(argument))]
     pub open_on_output: Option<String>,
+    #[knuffel(child, unwrap(argument, str))]
+    pub layout_kind: Option<LayoutKind>,
+    #[knuffel(child, unwrap(argument))]
+    pub scroll_orientation: Option<ScrollOrientation>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1437,6 +1793,12 @@ pub struct WindowRule {
     #[knuffel(child, unwrap(argument))]
     pub open_floating: Option<bool>,
     #[knuffel(child, unwrap(argument))]
+    pub open_pinned: Option<bool>,
+    #[knuffel(child, unwrap(argument))]
+    pub open_keep_above: Option<bool>,
+    #[knuffel(child, unwrap(argument))]
+    pub open_keep_below: Option<bool>,
+    #[knuffel(child, unwrap(argument))]
     pub open_focused: Option<bool>,
 
     // Rules applied dynamically.
@@ -1500,6 +1862,8 @@ pub struct Match {
     #[knuffel(property)]
     pub is_urgent: Option<bool>,
     #[knuffel(property)]
+    pub is_modal: Option<bool>,
+    #[knuffel(property)]
     pub at_startup: Option<bool>,
 }
 
@@ -1711,7 +2075,22 @@ pub enum Action {
     CancelScreenshot,
     #[knuffel(skip)]
     ScreenshotTogglePointer,
-    Screenshot(#[knuffel(property(name = "show-pointer"), default = true)] bool),
+    #[knuffel(skip)]
+    ScreenshotSetTool(ScreenshotTool),
+    #[knuffel(skip)]
+    ScreenshotUndo,
+    #[knuffel(skip)]
+    ScreenshotInsertText(char),
+    #[knuffel(skip)]
+    ScreenshotDeleteText,
+    #[knuffel(skip)]
+    ScreenshotFinishText,
+    #[knuffel(skip)]
+    ScreenshotSetMode(ScreenshotMode),
+    Screenshot(
+        #[knuffel(property(name = "show-pointer"), default = true)] bool,
+        #[knuffel(property(name = "delay-ms"))] Option<u16>,
+    ),
     ScreenshotScreen(
         #[knuffel(property(name = "write-to-disk"), default = true)] bool,
         #[knuffel(property(name = "show-pointer"), default = true)] bool,
@@ -1736,6 +2115,15 @@ pub enum Action {
     FocusWindow(u64),
     FocusWindowInColumn(#[knuffel(argument)] u8),
     FocusWindowPrevious,
+    SwitchWindowNext(
+        #[knuffel(property(name = "scope"), str, default)] WindowSwitcherScope,
+        #[knuffel(property(name = "filter"), str, default)] WindowSwitcherFilter,
+    ),
+    SwitchWindowPrevious(
+        #[knuffel(property(name = "scope"), str, default)] WindowSwitcherScope,
+        #[knuffel(property(name = "filter"), str, default)] WindowSwitcherFilter,
+    ),
+    ShowWindowHints(#[knuffel(property(name = "action"), str, default)] WindowHintAction),
     FocusColumnLeft,
     #[knuffel(skip)]
     FocusColumnLeftUnderMouse,
@@ -1840,6 +2228,12 @@ pub enum Action {
     UnsetWorkspaceName,
     #[knuffel(skip)]
     UnsetWorkSpaceNameByRef(#[knuffel(argument)] WorkspaceReference),
+    SetWorkspaceLayoutKind(#[knuffel(argument, str)] LayoutKind),
+    #[knuffel(skip)]
+    SetWorkspaceLayoutKindByRef {
+        kind: LayoutKind,
+        reference: WorkspaceReference,
+    },
     FocusMonitorLeft,
     FocusMonitorRight,
     FocusMonitorDown,
@@ -1908,6 +2302,21 @@ pub enum Action {
     MoveWindowToTiling,
     #[knuffel(skip)]
     MoveWindowToTilingById(u64),
+    TogglePinWindow,
+    #[knuffel(skip)]
+    TogglePinWindowById(u64),
+    ToggleWindowKeepAbove,
+    #[knuffel(skip)]
+    ToggleWindowKeepAboveById(u64),
+    ToggleWindowKeepBelow,
+    #[knuffel(skip)]
+    ToggleWindowKeepBelowById(u64),
+    MinimizeWindow,
+    #[knuffel(skip)]
+    MinimizeWindowById(u64),
+    RestoreMinimized,
+    #[knuffel(skip)]
+    RestoreMinimizedById(u64),
     FocusFloating,
     FocusTiling,
     SwitchFocusBetweenFloatingAndTiling,
@@ -1917,6 +2326,12 @@ pub enum Action {
         x: PositionChange,
         y: PositionChange,
     },
+    TileFloatingWindow(#[knuffel(argument, str)] FloatingTileArea),
+    #[knuffel(skip)]
+    TileFloatingWindowById {
+        id: u64,
+        area: FloatingTileArea,
+    },
     ToggleWindowRuleOpacity,
     #[knuffel(skip)]
     ToggleWindowRuleOpacityById(u64),
@@ -1924,7 +2339,15 @@ pub enum Action {
     #[knuffel(skip)]
     SetDynamicCastWindowById(u64),
     SetDynamicCastMonitor(#[knuffel(argument)] Option<String>),
+    SetDynamicCastWorkspace(#[knuffel(argument)] Option<WorkspaceReference>),
+    SetDynamicCastArea,
     ClearDynamicCastTarget,
+    RecordScreen(#[knuffel(argument)] Option<String>),
+    RecordWindow,
+    #[knuffel(skip)]
+    RecordWindowById(u64),
+    RecordArea,
+    StopRecording,
     ToggleOverview,
     OpenOverview,
     CloseOverview,
@@ -1934,6 +2357,12 @@ pub enum Action {
     SetWindowUrgent(u64),
     #[knuffel(skip)]
     UnsetWindowUrgent(u64),
+    GlobalShortcut(#[knuffel(argument)] String),
+    ToggleNightLight,
+    SetNightLightTemperature(#[knuffel(argument)] u32),
+    ResetNightLight,
+    #[knuffel(skip)]
+    StopCastSession(u64),
 }
 
 impl From<niri_ipc::Action> for Action {
@@ -1944,7 +2373,10 @@ impl From<niri_ipc::Action> for Action {
             niri_ipc::Action::PowerOnMonitors {} => Self::PowerOnMonitors,
             niri_ipc::Action::Spawn { command } => Self::Spawn(command),
             niri_ipc::Action::DoScreenTransition { delay_ms } => Self::DoScreenTransition(delay_ms),
-            niri_ipc::Action::Screenshot { show_pointer } => Self::Screenshot(show_pointer),
+            niri_ipc::Action::Screenshot {
+                show_pointer,
+                delay_ms,
+            } => Self::Screenshot(show_pointer, delay_ms),
             niri_ipc::Action::ScreenshotScreen {
                 write_to_disk,
                 show_pointer,
@@ -1973,6 +2405,15 @@ impl From<niri_ipc::Action> for Action {
             niri_ipc::Action::FocusWindow { id } => Self::FocusWindow(id),
             niri_ipc::Action::FocusWindowInColumn { index } => Self::FocusWindowInColumn(index),
             niri_ipc::Action::FocusWindowPrevious {} => Self::FocusWindowPrevious,
+            niri_ipc::Action::SwitchWindowNext { scope, filter } => {
+                Self::SwitchWindowNext(scope.unwrap_or_default(), filter.unwrap_or_default())
+            }
+            niri_ipc::Action::SwitchWindowPrevious { scope, filter } => {
+                Self::SwitchWindowPrevious(scope.unwrap_or_default(), filter.unwrap_or_default())
+            }
+            niri_ipc::Action::ShowWindowHints { action } => {
+                Self::ShowWindowHints(action.unwrap_or_default())
+            }
             niri_ipc::Action::FocusColumnLeft {} => Self::FocusColumnLeft,
             niri_ipc::Action::FocusColumnRight {} => Self::FocusColumnRight,
             niri_ipc::Action::FocusColumnFirst {} => Self::FocusColumnFirst,
@@ -2083,6 +2524,17 @@ impl From<niri_ipc::Action> for Action {
             niri_ipc::Action::UnsetWorkspaceName {
                 reference: Some(reference),
             } => Self::UnsetWorkSpaceNameByRef(WorkspaceReference::from(reference)),
+            niri_ipc::Action::SetWorkspaceLayoutKind {
+                kind,
+                workspace: None,
+            } => Self::SetWorkspaceLayoutKind(kind),
+            niri_ipc::Action::SetWorkspaceLayoutKind {
+                kind,
+                workspace: Some(reference),
+            } => Self::SetWorkspaceLayoutKindByRef {
+                kind,
+                reference: WorkspaceReference::from(reference),
+            },
             niri_ipc::Action::FocusMonitorLeft {} => Self::FocusMonitorLeft,
             niri_ipc::Action::FocusMonitorRight {} => Self::FocusMonitorRight,
             niri_ipc::Action::FocusMonitorDown {} => Self::FocusMonitorDown,
@@ -2183,6 +2635,20 @@ impl From<niri_ipc::Action> for Action {
             niri_ipc::Action::MoveWindowToTiling { id: Some(id) } => {
                 Self::MoveWindowToTilingById(id)
             }
+            niri_ipc::Action::TogglePinWindow { id: None } => Self::TogglePinWindow,
+            niri_ipc::Action::TogglePinWindow { id: Some(id) } => Self::TogglePinWindowById(id),
+            niri_ipc::Action::ToggleWindowKeepAbove { id: None } => Self::ToggleWindowKeepAbove,
+            niri_ipc::Action::ToggleWindowKeepAbove { id: Some(id) } => {
+                Self::ToggleWindowKeepAboveById(id)
+            }
+            niri_ipc::Action::ToggleWindowKeepBelow { id: None } => Self::ToggleWindowKeepBelow,
+            niri_ipc::Action::ToggleWindowKeepBelow { id: Some(id) } => {
+                Self::ToggleWindowKeepBelowById(id)
+            }
+            niri_ipc::Action::MinimizeWindow { id: None } => Self::MinimizeWindow,
+            niri_ipc::Action::MinimizeWindow { id: Some(id) } => Self::MinimizeWindowById(id),
+            niri_ipc::Action::RestoreMinimized { id: None } => Self::RestoreMinimized,
+            niri_ipc::Action::RestoreMinimized { id: Some(id) } => Self::RestoreMinimizedById(id),
             niri_ipc::Action::FocusFloating {} => Self::FocusFloating,
             niri_ipc::Action::FocusTiling {} => Self::FocusTiling,
             niri_ipc::Action::SwitchFocusBetweenFloatingAndTiling {} => {
@@ -2191,6 +2657,12 @@ impl From<niri_ipc::Action> for Action {
             niri_ipc::Action::MoveFloatingWindow { id, x, y } => {
                 Self::MoveFloatingWindowById { id, x, y }
             }
+            niri_ipc::Action::TileFloatingWindow { id: None, area } => {
+                Self::TileFloatingWindow(area)
+            }
+            niri_ipc::Action::TileFloatingWindow { id: Some(id), area } => {
+                Self::TileFloatingWindowById { id, area }
+            }
             niri_ipc::Action::ToggleWindowRuleOpacity { id: None } => Self::ToggleWindowRuleOpacity,
             niri_ipc::Action::ToggleWindowRuleOpacity { id: Some(id) } => {
                 Self::ToggleWindowRuleOpacityById(id)
@@ -2202,6 +2674,15 @@ impl From<niri_ipc::Action> for Action {
             niri_ipc::Action::SetDynamicCastMonitor { output } => {
                 Self::SetDynamicCastMonitor(output)
             }
+            niri_ipc::Action::SetDynamicCastWorkspace { reference } => {
+                Self::SetDynamicCastWorkspace(reference.map(WorkspaceReference::from))
+            }
+            niri_ipc::Action::SetDynamicCastArea {} => Self::SetDynamicCastArea,
+            niri_ipc::Action::RecordScreen { output } => Self::RecordScreen(output),
+            niri_ipc::Action::RecordWindow { id: None } => Self::RecordWindow,
+            niri_ipc::Action::RecordWindow { id: Some(id) } => Self::RecordWindowById(id),
+            niri_ipc::Action::RecordArea {} => Self::RecordArea,
+            niri_ipc::Action::StopRecording {} => Self::StopRecording,
             niri_ipc::Action::ClearDynamicCastTarget {} => Self::ClearDynamicCastTarget,
             niri_ipc::Action::ToggleOverview {} => Self::ToggleOverview,
             niri_ipc::Action::OpenOverview {} => Self::OpenOverview,
@@ -2209,10 +2690,40 @@ impl From<niri_ipc::Action> for Action {
             niri_ipc::Action::ToggleWindowUrgent { id } => Self::ToggleWindowUrgent(id),
             niri_ipc::Action::SetWindowUrgent { id } => Self::SetWindowUrgent(id),
             niri_ipc::Action::UnsetWindowUrgent { id } => Self::UnsetWindowUrgent(id),
+            niri_ipc::Action::GlobalShortcut { shortcut } => Self::GlobalShortcut(shortcut),
+            niri_ipc::Action::ToggleNightLight {} => Self::ToggleNightLight,
+            niri_ipc::Action::SetNightLightTemperature { temperature } => {
+                Self::SetNightLightTemperature(temperature)
+            }
+            niri_ipc::Action::ResetNightLight {} => Self::ResetNightLight,
+            niri_ipc::Action::StopCastSession { session_id } => Self::StopCastSession(session_id),
         }
     }
 }
 
+/// Tool used for dragging in the screenshot UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotTool {
+    /// Select the area to capture.
+    Select,
+    Rectangle,
+    Arrow,
+    Freehand,
+    Text,
+    Pixelate,
+}
+
+/// How the screenshot UI selects the area to capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotMode {
+    /// Drag to select an area.
+    Area,
+    /// Click to select a window.
+    Window,
+    /// Select a whole output.
+    Output,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum WorkspaceReference {
     Id(u64),
@@ -3234,6 +3745,21 @@ where
     }
 }
 
+impl<S> knuffel::Decode<S> for NightLightAnim
+where
+    S: knuffel::traits::ErrorSpan,
+{
+    fn decode_node(
+        node: &knuffel::ast::SpannedNode<S>,
+        ctx: &mut knuffel::decode::Context<S>,
+    ) -> Result<Self, DecodeError<S>> {
+        let default = Self::default().0;
+        Ok(Self(Animation::decode_node(node, ctx, default, |_, _| {
+            Ok(false)
+        })?))
+    }
+}
+
 impl Animation {
     pub fn new_off() -> Self {
         Self {
@@ -3771,6 +4297,36 @@ where
     }
 }
 
+impl<S> knuffel::Decode<S> for HotCornerAction
+where
+    S: knuffel::traits::ErrorSpan,
+{
+    fn decode_node(
+        node: &knuffel::ast::SpannedNode<S>,
+        ctx: &mut knuffel::decode::Context<S>,
+    ) -> Result<Self, DecodeError<S>> {
+        expect_only_children(node, ctx);
+
+        let mut children = node.children();
+        let Some(child) = children.next() else {
+            return Err(DecodeError::missing(
+                node,
+                "expected an action for this hot corner",
+            ));
+        };
+
+        for unwanted_child in children {
+            ctx.emit_error(DecodeError::unexpected(
+                unwanted_child,
+                "node",
+                "only one action is allowed per hot corner",
+            ));
+        }
+
+        Action::decode_node(child, ctx).map(Self)
+    }
+}
+
 impl FromStr for ModKey {
     type Err = miette::Error;
 
@@ -3859,6 +4415,20 @@ impl FromStr for Key {
     }
 }
 
+impl FromStr for HdrTransferFunction {
+    type Err = miette::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pq" => Ok(Self::Pq),
+            "hlg" => Ok(Self::Hlg),
+            _ => Err(miette!(
+                r#"invalid transfer function, can be "pq" or "hlg""#
+            )),
+        }
+    }
+}
+
 impl FromStr for ClickMethod {
     type Err = miette::Error;
 
@@ -3917,6 +4487,30 @@ impl FromStr for TapButtonMap {
     }
 }
 
+impl FromStr for TimeOfDay {
+    type Err = miette::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((hour, minute)) = s.split_once(':') else {
+            return Err(miette!("time must be in HH:MM format"));
+        };
+
+        let hour: u8 = hour.parse().map_err(|_| miette!("error parsing hour"))?;
+        let minute: u8 = minute
+            .parse()
+            .map_err(|_| miette!("error parsing minute"))?;
+
+        if hour > 23 {
+            return Err(miette!("hour must be between 0 and 23"));
+        }
+        if minute > 59 {
+            return Err(miette!("minute must be between 0 and 59"));
+        }
+
+        Ok(Self { hour, minute })
+    }
+}
+
 impl FromStr for Percent {
     type Err = miette::Error;
 
@@ -4039,6 +4633,11 @@ mod tests {
                 mode "1920x1080@144"
                 variable-refresh-rate on-demand=true
                 background-color "rgba(25, 25, 102, 1.0)"
+                scroll-orientation "vertical"
+                hdr {
+                    transfer-function "pq"
+                    max-luminance 600
+                }
             }
 
             layout {
@@ -4095,6 +4694,11 @@ mod tests {
                     color "rgb(255, 200, 127)"
                     gradient from="rgba(10, 20, 30, 1.0)" to="#0080ffff" relative-to="workspace-view"
                 }
+
+                floating-snap {
+                    distance 24
+                    disable-edge-tiling
+                }
             }
 
             spawn-at-startup "alacritty" "-e" "fish"
@@ -4110,6 +4714,11 @@ mod tests {
 
             screenshot-path "~/Screenshots/screenshot.png"
 
+            screenshot-annotations {
+                width 3
+                color "#00ff00"
+            }
+
             clipboard {
                 disable-primary
             }
@@ -4118,6 +4727,38 @@ mod tests {
                 skip-at-startup
             }
 
+            bell {
+                flash
+                spawn "paplay" "bell.oga"
+            }
+
+            screencast-indicator {
+                on
+                width 2
+                color "#ff0000"
+            }
+
+            recording {
+                path "~/Recordings/%Y-%m-%d"
+                max-fps 60
+            }
+
+            night-light {
+                on
+                temperature 3500
+                from "21:30"
+                to "06:45"
+            }
+
+            idle {
+                timeout 300 {
+                    spawn "swaylock"
+                }
+                timeout 600 {
+                    power-off-monitors
+                }
+            }
+
             animations {
                 slowdown 2.0
 
@@ -4154,6 +4795,9 @@ mod tests {
                 open-maximized true
                 open-fullscreen false
                 open-floating false
+                open-pinned true
+                open-keep-above true
+                open-keep-below false
                 open-focused true
                 default-window-height { fixed 500; }
                 default-column-display "tabbed"
@@ -4207,6 +4851,8 @@ mod tests {
 
             workspace "workspace-1" {
                 open-on-output "eDP-1"
+                layout-kind "grid"
+                scroll-orientation "auto"
             }
             workspace "workspace-2"
             workspace "workspace-3"
@@ -4415,6 +5061,17 @@ mod tests {
                             },
                         ),
                         backdrop_color: None,
+                        hdr: Some(
+                            OutputHdr {
+                                transfer_function: Pq,
+                                reference_luminance: 203,
+                                max_luminance: 600,
+                            },
+                        ),
+                        bit_depth: None,
+                        scroll_orientation: Some(
+                            Vertical,
+                        ),
                     },
                 ],
             ),
@@ -4532,6 +5189,7 @@ mod tests {
                     off: false,
                     hide_when_single_tab: false,
                     place_within_column: false,
+                    show_icons: false,
                     gap: FloatOrInt(
                         5.0,
                     ),
@@ -4588,6 +5246,13 @@ mod tests {
                         },
                     ),
                 },
+                floating_snap: FloatingSnap {
+                    off: false,
+                    distance: FloatOrInt(
+                        24.0,
+                    ),
+                    disable_edge_tiling: true,
+                },
                 preset_column_widths: [
                     Proportion(
                         0.25,
@@ -4626,6 +5291,8 @@ mod tests {
                     ),
                 ],
                 center_focused_column: OnOverflow,
+                new_column_location: RightOfActive,
+                scroll_orientation: Horizontal,
                 always_center_single_column: false,
                 empty_workspace_above_first: false,
                 default_column_display: Tabbed,
@@ -4665,6 +5332,17 @@ mod tests {
             screenshot_path: Some(
                 "~/Screenshots/screenshot.png",
             ),
+            screenshot_annotations: ScreenshotAnnotations {
+                width: FloatOrInt(
+                    3.0,
+                ),
+                color: Color {
+                    r: 0.0,
+                    g: 1.0,
+                    b: 0.0,
+                    a: 1.0,
+                },
+            },
             clipboard: Clipboard {
                 disable_primary: true,
             },
@@ -4672,6 +5350,77 @@ mod tests {
                 skip_at_startup: true,
                 hide_not_bound: false,
             },
+            bell: Bell {
+                off: false,
+                skip_urgent: false,
+                flash: true,
+                flash_window: false,
+                spawn: Some(
+                    BellSpawn {
+                        command: [
+                            "paplay",
+                            "bell.oga",
+                        ],
+                    },
+                ),
+            },
+            remote_desktop: RemoteDesktop {
+                on: false,
+            },
+            screencast_indicator: ScreencastIndicator {
+                on: true,
+                width: FloatOrInt(
+                    2.0,
+                ),
+                color: Color {
+                    r: 1.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 1.0,
+                },
+            },
+            recording: Recording {
+                path: "~/Recordings/%Y-%m-%d",
+                max_fps: 60,
+            },
+            night_light: NightLight {
+                on: true,
+                temperature: 3500,
+                from: Some(
+                    TimeOfDay {
+                        hour: 21,
+                        minute: 30,
+                    },
+                ),
+                to: Some(
+                    TimeOfDay {
+                        hour: 6,
+                        minute: 45,
+                    },
+                ),
+                latitude: None,
+                longitude: None,
+            },
+            idle: Idle {
+                timeouts: [
+                    IdleTimeout {
+                        seconds: 300,
+                        actions: [
+                            Spawn(
+                                [
+                                    "swaylock",
+                                ],
+                            ),
+                        ],
+                    },
+                    IdleTimeout {
+                        seconds: 600,
+                        actions: [
+                            PowerOffMonitors,
+                        ],
+                    },
+                ],
+            },
             animations: Animations {
                 off: false,
                 slowdown: FloatOrInt(
@@ -4784,6 +5533,17 @@ mod tests {
                         ),
                     },
                 ),
+                night_light: NightLightAnim(
+                    Animation {
+                        off: false,
+                        kind: Easing(
+                            EasingParams {
+                                duration_ms: 3000,
+                                curve: Linear,
+                            },
+                        ),
+                    },
+                ),
             },
             gestures: Gestures {
                 dnd_edge_view_scroll: DndEdgeViewScroll {
@@ -4806,6 +5566,18 @@ mod tests {
                 },
                 hot_corners: HotCorners {
                     off: false,
+                    size: FloatOrInt(
+                        1.0,
+                    ),
+                    delay_ms: 0,
+                    pressure: FloatOrInt(
+                        0.0,
+                    ),
+                    top_left: None,
+                    top_right: None,
+                    bottom_left: None,
+                    bottom_right: None,
+                    outputs: [],
                 },
             },
             overview: Overview {
@@ -4878,6 +5650,7 @@ mod tests {
                             is_floating: None,
                             is_window_cast_target: None,
                             is_urgent: None,
+                            is_modal: None,
                             at_startup: None,
                         },
                     ],
@@ -4897,6 +5670,7 @@ mod tests {
                             is_floating: None,
                             is_window_cast_target: None,
                             is_urgent: None,
+                            is_modal: None,
                             at_startup: None,
                         },
                         Match {
@@ -4912,6 +5686,7 @@ mod tests {
                             is_floating: None,
                             is_window_cast_target: None,
                             is_urgent: None,
+                            is_modal: None,
                             at_startup: None,
                         },
                     ],
@@ -4938,6 +5713,15 @@ mod tests {
                     open_floating: Some(
                         false,
                     ),
+                    open_pinned: Some(
+                        true,
+                    ),
+                    open_keep_above: Some(
+                        true,
+                    ),
+                    open_keep_below: Some(
+                        false,
+                    ),
                     open_focused: Some(
                         true,
                     ),
@@ -5366,18 +6150,28 @@ mod tests {
                     open_on_output: Some(
                         "eDP-1",
                     ),
+                    layout_kind: Some(
+                        Grid,
+                    ),
+                    scroll_orientation: Some(
+                        Auto,
+                    ),
                 },
                 Workspace {
                     name: WorkspaceName(
                         "workspace-2",
                     ),
                     open_on_output: None,
+                    layout_kind: None,
+                    scroll_orientation: None,
                 },
                 Workspace {
                     name: WorkspaceName(
                         "workspace-3",
                     ),
                     open_on_output: None,
+                    layout_kind: None,
+                    scroll_orientation: None,
                 },
             ],
         }
@@ -5583,6 +6377,57 @@ mod tests {
         assert_eq!(config.input.keyboard.repeat_rate, 25);
     }
 
+    #[test]
+    fn parse_hot_corners() {
+        let config = do_parse("");
+        let hot_corners = &config.gestures.hot_corners;
+        assert_eq!(
+            hot_corners.action(None, HotCorner::TopLeft),
+            Some(Action::ToggleOverview)
+        );
+        assert_eq!(hot_corners.action(None, HotCorner::TopRight), None);
+
+        let config = do_parse(
+            r#"
+            gestures {
+                hot-corners {
+                    size 4
+                    delay-ms 150
+                    pressure 50
+                    top-right { focus-workspace-down; }
+
+                    output "eDP-1" {
+                        bottom-left { toggle-overview; }
+                    }
+
+                    output "DP-1" {
+                        off
+                    }
+                }
+            }
+            "#,
+        );
+        let hot_corners = &config.gestures.hot_corners;
+        assert_eq!(hot_corners.size, FloatOrInt(4.));
+        assert_eq!(hot_corners.delay_ms, 150);
+        assert_eq!(hot_corners.pressure, FloatOrInt(50.));
+        assert_eq!(hot_corners.action(None, HotCorner::TopLeft), None);
+        assert_eq!(
+            hot_corners.action(None, HotCorner::TopRight),
+            Some(Action::FocusWorkspaceDown)
+        );
+
+        let edp = &hot_corners.outputs[0];
+        assert_eq!(hot_corners.action(Some(edp), HotCorner::TopRight), None);
+        assert_eq!(
+            hot_corners.action(Some(edp), HotCorner::BottomLeft),
+            Some(Action::ToggleOverview)
+        );
+
+        let dp = &hot_corners.outputs[1];
+        assert_eq!(hot_corners.action(Some(dp), HotCorner::TopRight), None);
+    }
+
     fn make_output_name(
         connector: &str,
         make: Option<&str>,
@@ -116,6 +116,22 @@ pub enum Request {
     ReturnError,
     /// Request information about the overview.
     OverviewState,
+    /// Request the icon of a window, optionally saving it as a PNG image.
+    WindowIcon {
+        /// Id of the window.
+        id: u64,
+        /// Path to save the icon to as a PNG image.
+        ///
+        /// The path is interpreted by the compositor, so it should be absolute.
+        path: Option<String>,
+        /// Preferred icon size in pixels.
+        ///
+        /// The closest icon buffer of at least this size is picked, falling back to the largest
+        /// one. If `None`, the largest icon buffer is picked.
+        size: Option<u32>,
+    },
+    /// Request information about active screencasts.
+    Casts,
 }
 
 /// Reply from niri to client.
@@ -160,6 +176,24 @@ pub enum Response {
     OutputConfigChanged(OutputConfigChanged),
     /// Information about the overview.
     OverviewState(Overview),
+    /// Information about the window icon.
+    ///
+    /// `None` if the window did not set an icon.
+    WindowIcon(Option<WindowIcon>),
+    /// Information about active screencasts.
+    Casts(Vec<Cast>),
+}
+
+/// Window icon set through the xdg-toplevel-icon protocol.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct WindowIcon {
+    /// Name of the icon in the XDG icon theme, if set.
+    pub name: Option<String>,
+    /// Sizes in pixels of the icon buffers provided by the window.
+    pub buffer_sizes: Vec<u32>,
+    /// Size in pixels of the icon buffer saved as a PNG image, if any was saved.
+    pub saved_size: Option<u32>,
 }
 
 /// Overview information.
@@ -170,6 +204,81 @@ pub struct Overview {
     pub is_open: bool,
 }
 
+/// Screencast stream.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct Cast {
+    /// Id of the stream.
+    ///
+    /// Unique among the active casts.
+    pub stream_id: u64,
+    /// Id of the screencast session that this stream belongs to.
+    ///
+    /// A session can have multiple streams. Casts can only be stopped a whole session at a time.
+    pub session_id: u64,
+    /// What is being cast.
+    pub target: CastTarget,
+    /// Whether the cast target can be changed while casting.
+    ///
+    /// This is the case for casts of the "niri dynamic cast target" window in the screencast
+    /// dialog.
+    pub is_dynamic_target: bool,
+    /// Whether the stream is currently running.
+    ///
+    /// Casts are inactive while the client has them paused, or before it starts the stream.
+    pub is_active: bool,
+    /// Size of the stream in physical pixels.
+    pub size: (u32, u32),
+    /// Unique D-Bus name of the client that started the screencast session.
+    ///
+    /// This is usually xdg-desktop-portal-gnome rather than the application itself.
+    pub client_bus_name: Option<String>,
+    /// Process ID of the client that started the screencast session, if known.
+    pub client_pid: Option<i32>,
+    /// App ID of the client that started the screencast session, if known.
+    ///
+    /// Only known when the client has a window open.
+    pub client_app_id: Option<String>,
+}
+
+/// Target of a screencast.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum CastTarget {
+    /// Nothing is being cast.
+    ///
+    /// Dynamic casts start in this state until a target is selected.
+    Nothing {},
+    /// A whole output.
+    Output {
+        /// Name of the output.
+        name: String,
+    },
+    /// An area of an output.
+    Area {
+        /// Name of the output.
+        output: String,
+        /// X position of the area in output-local logical coordinates.
+        x: i32,
+        /// Y position of the area in output-local logical coordinates.
+        y: i32,
+        /// Width of the area in logical pixels.
+        width: i32,
+        /// Height of the area in logical pixels.
+        height: i32,
+    },
+    /// A single window.
+    Window {
+        /// Id of the window.
+        id: u64,
+    },
+    /// A whole workspace, wherever it is.
+    Workspace {
+        /// Id of the workspace.
+        id: u64,
+    },
+}
+
 /// Color picked from the screen.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
@@ -214,6 +323,12 @@ pub enum Action {
         ///  Whether to show the mouse pointer by default in the screenshot UI.
         #[cfg_attr(feature = "clap", arg(short = 'p', long, action = clap::ArgAction::Set, default_value_t = true))]
         show_pointer: bool,
+
+        /// Delay in milliseconds before opening the screenshot UI.
+        ///
+        /// Useful for capturing menus and popups that need the pointer to hover them.
+        #[cfg_attr(feature = "clap", arg(long))]
+        delay_ms: Option<u16>,
     },
     /// Screenshot the focused screen.
     ScreenshotScreen {
@@ -292,6 +407,41 @@ pub enum Action {
     },
     /// Focus the previously focused window.
     FocusWindowPrevious {},
+    /// Open the window switcher, or select the next window in it.
+    ///
+    /// Windows are listed in most-recently-used order. The selected window is focused once the
+    /// modifiers that were held to open the switcher are released.
+    SwitchWindowNext {
+        /// Which windows to list.
+        ///
+        /// If `None`, lists windows from all workspaces.
+        #[cfg_attr(feature = "clap", arg(long))]
+        scope: Option<WindowSwitcherScope>,
+
+        /// Further filter for the listed windows.
+        #[cfg_attr(feature = "clap", arg(long))]
+        filter: Option<WindowSwitcherFilter>,
+    },
+    /// Open the window switcher, or select the previous window in it.
+    SwitchWindowPrevious {
+        /// Which windows to list.
+        ///
+        /// If `None`, lists windows from all workspaces.
+        #[cfg_attr(feature = "clap", arg(long))]
+        scope: Option<WindowSwitcherScope>,
+
+        /// Further filter for the listed windows.
+        #[cfg_attr(feature = "clap", arg(long))]
+        filter: Option<WindowSwitcherFilter>,
+    },
+    /// Show letter hints on the visible windows and act on the chosen one.
+    ShowWindowHints {
+        /// What to do with the chosen window.
+        ///
+        /// If `None`, focuses the window.
+        #[cfg_attr(feature = "clap", arg(long))]
+        action: Option<WindowHintAction>,
+    },
     /// Focus the column to the left.
     FocusColumnLeft {},
     /// Focus the column to the right.
@@ -547,6 +697,22 @@ pub enum Action {
         #[cfg_attr(feature = "clap", arg())]
         reference: Option<WorkspaceReferenceArg>,
     },
+    /// Set the layout kind of a workspace.
+    #[cfg_attr(
+        feature = "clap",
+        clap(about = "Set the layout kind of the focused workspace")
+    )]
+    SetWorkspaceLayoutKind {
+        /// Layout kind to set.
+        #[cfg_attr(feature = "clap", arg())]
+        kind: LayoutKind,
+
+        /// Reference (index or name) of the workspace to change.
+        ///
+        /// If `None`, uses the focused workspace.
+        #[cfg_attr(feature = "clap", arg(long))]
+        workspace: Option<WorkspaceReferenceArg>,
+    },
     /// Focus the monitor to the left.
     FocusMonitorLeft {},
     /// Focus the monitor to the right.
@@ -749,6 +915,52 @@ pub enum Action {
         #[cfg_attr(feature = "clap", arg(long))]
         id: Option<u64>,
     },
+    /// Pin or unpin a window to keep it visible on all workspaces of its output.
+    ///
+    /// Pinning a tiled window moves it to the floating layout first.
+    TogglePinWindow {
+        /// Id of the window to pin or unpin.
+        ///
+        /// If `None`, uses the focused window.
+        #[cfg_attr(feature = "clap", arg(long))]
+        id: Option<u64>,
+    },
+    /// Toggle keeping a window above the other floating windows.
+    ///
+    /// Toggling a tiled window moves it to the floating layout first.
+    ToggleWindowKeepAbove {
+        /// Id of the window to keep above.
+        ///
+        /// If `None`, uses the focused window.
+        #[cfg_attr(feature = "clap", arg(long))]
+        id: Option<u64>,
+    },
+    /// Toggle keeping a window below the other floating windows.
+    ///
+    /// Toggling a tiled window moves it to the floating layout first.
+    ToggleWindowKeepBelow {
+        /// Id of the window to keep below.
+        ///
+        /// If `None`, uses the focused window.
+        #[cfg_attr(feature = "clap", arg(long))]
+        id: Option<u64>,
+    },
+    /// Minimize a window, removing it from the layout until it is restored.
+    MinimizeWindow {
+        /// Id of the window to minimize.
+        ///
+        /// If `None`, uses the focused window.
+        #[cfg_attr(feature = "clap", arg(long))]
+        id: Option<u64>,
+    },
+    /// Restore a minimized window to where it was before minimizing.
+    RestoreMinimized {
+        /// Id of the window to restore.
+        ///
+        /// If `None`, restores the most recently minimized window on the focused workspace.
+        #[cfg_attr(feature = "clap", arg(long))]
+        id: Option<u64>,
+    },
     /// Switches focus to the floating layout.
     FocusFloating {},
     /// Switches focus to the tiling layout.
@@ -778,6 +990,24 @@ pub enum Action {
         )]
         y: PositionChange,
     },
+    /// Tile a floating window to a half or a quarter of the working area.
+    ///
+    /// Tiling a tiled window moves it to the floating layout first.
+    #[cfg_attr(
+        feature = "clap",
+        clap(about = "Tile the focused floating window to a half or a quarter of the screen")
+    )]
+    TileFloatingWindow {
+        /// Id of the window to tile.
+        ///
+        /// If `None`, uses the focused window.
+        #[cfg_attr(feature = "clap", arg(long))]
+        id: Option<u64>,
+
+        /// Area to tile the window to.
+        #[cfg_attr(feature = "clap", arg())]
+        area: FloatingTileArea,
+    },
     /// Toggle the opacity of a window.
     #[cfg_attr(
         feature = "clap",
@@ -814,8 +1044,63 @@ pub enum Action {
         #[cfg_attr(feature = "clap", arg())]
         output: Option<String>,
     },
+    /// Set the dynamic cast target to a workspace.
+    ///
+    /// The cast follows the workspace across monitors, and keeps showing it when it isn't visible.
+    #[cfg_attr(
+        feature = "clap",
+        clap(about = "Set the dynamic cast target to the focused workspace")
+    )]
+    SetDynamicCastWorkspace {
+        /// Reference (index or name) of the workspace to target.
+        ///
+        /// If `None`, uses the focused workspace.
+        #[cfg_attr(feature = "clap", arg())]
+        reference: Option<WorkspaceReferenceArg>,
+    },
+    /// Set the dynamic cast target to an area of a monitor.
+    ///
+    /// Opens the screenshot UI to interactively select the area.
+    #[cfg_attr(
+        feature = "clap",
+        clap(about = "Set the dynamic cast target to an interactively selected area")
+    )]
+    SetDynamicCastArea {},
     /// Clear the dynamic cast target, making it show nothing.
     ClearDynamicCastTarget {},
+    /// Start recording a monitor to files.
+    ///
+    /// Stops the ongoing recording, if any.
+    #[cfg_attr(feature = "clap", clap(about = "Start recording the focused monitor"))]
+    RecordScreen {
+        /// Name of the output to record.
+        ///
+        /// If `None`, uses the focused output.
+        #[cfg_attr(feature = "clap", arg())]
+        output: Option<String>,
+    },
+    /// Start recording a window to files.
+    ///
+    /// Stops the ongoing recording, if any.
+    #[cfg_attr(feature = "clap", clap(about = "Start recording the focused window"))]
+    RecordWindow {
+        /// Id of the window to record.
+        ///
+        /// If `None`, uses the focused window.
+        #[cfg_attr(feature = "clap", arg(long))]
+        id: Option<u64>,
+    },
+    /// Start recording an area of a monitor to files.
+    ///
+    /// Opens the screenshot UI to interactively select the area. Stops the ongoing recording, if
+    /// any.
+    #[cfg_attr(
+        feature = "clap",
+        clap(about = "Start recording an interactively selected area")
+    )]
+    RecordArea {},
+    /// Stop the ongoing recording.
+    StopRecording {},
     /// Toggle (open/close) the Overview.
     ToggleOverview {},
     /// Open the Overview.
@@ -840,6 +1125,32 @@ pub enum Action {
         #[cfg_attr(feature = "clap", arg(long))]
         id: u64,
     },
+    /// Trigger the global shortcuts that applications registered for a key combination.
+    ///
+    /// The shortcuts are activated and immediately deactivated.
+    GlobalShortcut {
+        /// Key combination, for example `Ctrl+Alt+M`.
+        #[cfg_attr(feature = "clap", arg())]
+        shortcut: String,
+    },
+    /// Toggle the night light.
+    ///
+    /// Overrides the night light schedule until its next scheduled change.
+    ToggleNightLight {},
+    /// Override the night light color temperature.
+    SetNightLightTemperature {
+        /// Color temperature in Kelvin, from 1000 to 10000.
+        #[cfg_attr(feature = "clap", arg())]
+        temperature: u32,
+    },
+    /// Reset the night light to follow the config and schedule.
+    ResetNightLight {},
+    /// Stop a screencast session, including all of its streams.
+    StopCastSession {
+        /// Id of the screencast session to stop.
+        #[cfg_attr(feature = "clap", arg(long))]
+        session_id: u64,
+    },
 }
 
 /// Change in window or column size.
@@ -900,6 +1211,78 @@ pub enum ColumnDisplay {
     Tabbed,
 }
 
+/// How a workspace arranges its tiled windows.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum LayoutKind {
+    /// Columns on an infinite strip that scrolls to the focused column.
+    #[default]
+    Scrolling,
+    /// The first column fills the left half, and the rest stack on the right half.
+    MasterStack,
+    /// Columns are evenly arranged in a grid of rows.
+    Grid,
+}
+
+/// Which windows the window switcher lists.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum WindowSwitcherScope {
+    /// Windows from all workspaces.
+    #[default]
+    All,
+    /// Windows from the focused workspace.
+    Workspace,
+    /// Windows from the workspaces of the focused output.
+    Output,
+}
+
+/// Additional filter for the windows the window switcher lists.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum WindowSwitcherFilter {
+    /// No additional filtering.
+    #[default]
+    None,
+    /// Only windows with the same app ID as the focused window.
+    AppId,
+}
+
+/// What to do with the window chosen through window hints.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum WindowHintAction {
+    /// Focus the window.
+    #[default]
+    Focus,
+    /// Close the window.
+    Close,
+    /// Move the window to the focused workspace and focus it.
+    Move,
+}
+
+/// Part of the working area that a floating window can be tiled to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub enum FloatingTileArea {
+    /// Left half.
+    Left,
+    /// Right half.
+    Right,
+    /// Top half.
+    Top,
+    /// Bottom half.
+    Bottom,
+    /// Top-left quarter.
+    TopLeft,
+    /// Top-right quarter.
+    TopRight,
+    /// Bottom-left quarter.
+    BottomLeft,
+    /// Bottom-right quarter.
+    BottomRight,
+}
+
 /// Output actions that niri can perform.
 // Variants in this enum should match the spelling of the ones in niri-config. Most thigs from
 // niri-config should be present here.
@@ -1148,8 +1531,26 @@ pub struct Window {
     ///
     /// If the window isn't floating then it is in the tiling layout.
     pub is_floating: bool,
+    /// Whether this window is pinned.
+    ///
+    /// Pinned windows are floating and stay visible on all workspaces of their output, so they
+    /// have no workspace id.
+    pub is_pinned: bool,
+    /// Whether this window is kept above the other floating windows.
+    pub is_kept_above: bool,
+    /// Whether this window is kept below the other floating windows.
+    pub is_kept_below: bool,
+    /// Whether this window is minimized.
+    ///
+    /// Minimized windows keep their workspace id but are not shown in the layout.
+    pub is_minimized: bool,
     /// Whether this window requests your attention.
     pub is_urgent: bool,
+    /// Name of the window icon in the XDG icon theme, if set.
+    ///
+    /// Windows can also provide icon images rather than names; use [`Request::WindowIcon`] to
+    /// retrieve them.
+    pub icon_name: Option<String>,
 }
 
 /// Output configuration change result.
@@ -1201,6 +1602,8 @@ pub struct Workspace {
     pub is_focused: bool,
     /// Id of the active window on this workspace, if any.
     pub active_window_id: Option<u64>,
+    /// How the workspace arranges its tiled windows.
+    pub layout_kind: LayoutKind,
 }
 
 /// Configured keyboard layouts.
@@ -1341,6 +1744,34 @@ pub enum Event {
         /// The new state of the overview.
         is_open: bool,
     },
+    /// A client rang the system bell.
+    ///
+    /// This event is sent for every bell, regardless of the bell config.
+    Bell {
+        /// Id of the window that rang the bell, if any.
+        ///
+        /// This is `None` if the bell wasn't associated with any surface, or if it was associated
+        /// with a surface that isn't a window, like a layer-shell surface.
+        window_id: Option<u64>,
+    },
+    /// The screencast configuration has changed.
+    CastsChanged {
+        /// The new screencast configuration.
+        ///
+        /// This configuration completely replaces the previous configuration. I.e. if any casts
+        /// are missing from here, then they were stopped.
+        casts: Vec<Cast>,
+    },
+    /// A new screencast was started, or an existing screencast changed.
+    CastStartedOrChanged {
+        /// The new or updated screencast.
+        cast: Cast,
+    },
+    /// A screencast was stopped.
+    CastStopped {
+        /// Id of the stopped stream.
+        stream_id: u64,
+    },
 }
 
 impl FromStr for WorkspaceReferenceArg {
@@ -1447,6 +1878,78 @@ impl FromStr for ColumnDisplay {
     }
 }
 
+impl FromStr for LayoutKind {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scrolling" => Ok(Self::Scrolling),
+            "master-stack" => Ok(Self::MasterStack),
+            "grid" => Ok(Self::Grid),
+            _ => Err(r#"invalid layout kind, can be "scrolling", "master-stack" or "grid""#),
+        }
+    }
+}
+
+impl FromStr for WindowSwitcherScope {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            "workspace" => Ok(Self::Workspace),
+            "output" => Ok(Self::Output),
+            _ => Err(r#"invalid scope, can be "all", "workspace" or "output""#),
+        }
+    }
+}
+
+impl FromStr for WindowSwitcherFilter {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "app-id" => Ok(Self::AppId),
+            _ => Err(r#"invalid filter, can be "none" or "app-id""#),
+        }
+    }
+}
+
+impl FromStr for WindowHintAction {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "focus" => Ok(Self::Focus),
+            "close" => Ok(Self::Close),
+            "move" => Ok(Self::Move),
+            _ => Err(r#"invalid action, can be "focus", "close" or "move""#),
+        }
+    }
+}
+
+impl FromStr for FloatingTileArea {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "top" => Ok(Self::Top),
+            "bottom" => Ok(Self::Bottom),
+            "top-left" => Ok(Self::TopLeft),
+            "top-right" => Ok(Self::TopRight),
+            "bottom-left" => Ok(Self::BottomLeft),
+            "bottom-right" => Ok(Self::BottomRight),
+            _ => Err(concat!(
+                r#"invalid floating tile area, can be "left", "right", "top", "bottom", "#,
+                r#""top-left", "top-right", "bottom-left" or "bottom-right""#
+            )),
+        }
+    }
+}
+
 impl FromStr for Transform {
     type Err = &'static str;
 
@@ -9,7 +9,7 @@
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
-use crate::{Event, KeyboardLayouts, Window, Workspace};
+use crate::{Cast, Event, KeyboardLayouts, Window, Workspace};
 
 /// Part of the state communicated via the event stream.
 pub trait EventStreamStatePart {
@@ -43,6 +43,9 @@ pub struct EventStreamState {
 
     /// State of the overview.
     pub overview: OverviewState,
+
+    /// State of the screencasts.
+    pub casts: CastsState,
 }
 
 /// The workspaces state communicated over the event stream.
@@ -73,6 +76,13 @@ pub struct OverviewState {
     pub is_open: bool,
 }
 
+/// The screencasts state communicated over the event stream.
+#[derive(Debug, Default)]
+pub struct CastsState {
+    /// Map from a stream id to the cast.
+    pub casts: HashMap<u64, Cast>,
+}
+
 impl EventStreamStatePart for EventStreamState {
     fn replicate(&self) -> Vec<Event> {
         let mut events = Vec::new();
@@ -80,6 +90,7 @@ impl EventStreamStatePart for EventStreamState {
         events.extend(self.windows.replicate());
         events.extend(self.keyboard_layouts.replicate());
         events.extend(self.overview.replicate());
+        events.extend(self.casts.replicate());
         events
     }
 
@@ -88,6 +99,7 @@ impl EventStreamStatePart for EventStreamState {
         let event = self.windows.apply(event)?;
         let event = self.keyboard_layouts.apply(event)?;
         let event = self.overview.apply(event)?;
+        let event = self.casts.apply(event)?;
         Some(event)
     }
 }
@@ -237,3 +249,139 @@ impl EventStreamStatePart for OverviewState {
         None
     }
 }
+
+impl EventStreamStatePart for CastsState {
+    fn replicate(&self) -> Vec<Event> {
+        let casts = self.casts.values().cloned().collect();
+        vec![Event::CastsChanged { casts }]
+    }
+
+    fn apply(&mut self, event: Event) -> Option<Event> {
+        match event {
+            Event::CastsChanged { casts } => {
+                self.casts = casts
+                    .into_iter()
+                    .map(|cast| (cast.stream_id, cast))
+                    .collect();
+            }
+            Event::CastStartedOrChanged { cast } => {
+                self.casts.insert(cast.stream_id, cast);
+            }
+            Event::CastStopped { stream_id } => {
+                let cast = self.casts.remove(&stream_id);
+                cast.expect("stopped cast was missing from the map");
+            }
+            event => return Some(event),
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CastTarget;
+
+    fn cast(stream_id: u64, target: CastTarget) -> Cast {
+        Cast {
+            stream_id,
+            session_id: 1,
+            target,
+            is_dynamic_target: false,
+            is_active: false,
+            size: (1920, 1080),
+            client_bus_name: Some(String::from(":1.23")),
+            client_pid: Some(1234),
+            client_app_id: None,
+        }
+    }
+
+    fn output_target() -> CastTarget {
+        CastTarget::Output {
+            name: String::from("DP-1"),
+        }
+    }
+
+    #[test]
+    fn cast_started_and_stopped() {
+        let mut state = CastsState::default();
+
+        let cast = cast(5, output_target());
+        assert!(state
+            .apply(Event::CastStartedOrChanged { cast: cast.clone() })
+            .is_none());
+        assert_eq!(state.casts.len(), 1);
+        assert_eq!(state.casts[&5], cast);
+
+        assert!(state.apply(Event::CastStopped { stream_id: 5 }).is_none());
+        assert!(state.casts.is_empty());
+    }
+
+    #[test]
+    fn event_stream_state_tracks_casts() {
+        let mut state = EventStreamState::default();
+
+        let cast = cast(5, output_target());
+        assert!(state.apply(Event::CastStartedOrChanged { cast }).is_none());
+        assert_eq!(state.casts.casts.len(), 1);
+
+        assert!(state.apply(Event::CastStopped { stream_id: 5 }).is_none());
+        assert!(state.casts.casts.is_empty());
+    }
+
+    #[test]
+    fn cast_changed() {
+        let mut state = CastsState::default();
+        state.apply(Event::CastStartedOrChanged {
+            cast: cast(5, output_target()),
+        });
+        state.apply(Event::CastStartedOrChanged {
+            cast: cast(6, CastTarget::Window { id: 2 }),
+        });
+
+        let mut changed = cast(5, CastTarget::Workspace { id: 3 });
+        changed.is_active = true;
+        state.apply(Event::CastStartedOrChanged {
+            cast: changed.clone(),
+        });
+
+        assert_eq!(state.casts.len(), 2);
+        assert_eq!(state.casts[&5], changed);
+        assert_eq!(state.casts[&6].target, CastTarget::Window { id: 2 });
+    }
+
+    #[test]
+    fn casts_changed_replaces_casts() {
+        let mut state = CastsState::default();
+        state.apply(Event::CastStartedOrChanged {
+            cast: cast(5, output_target()),
+        });
+
+        let casts = vec![cast(6, output_target()), cast(7, CastTarget::Nothing {})];
+        state.apply(Event::CastsChanged {
+            casts: casts.clone(),
+        });
+        assert_eq!(state.casts.len(), 2);
+        assert!(!state.casts.contains_key(&5));
+
+        let mut replicated = match state.replicate().pop() {
+            Some(Event::CastsChanged { casts }) => casts,
+            _ => unreachable!(),
+        };
+        replicated.sort_by_key(|cast| cast.stream_id);
+        assert_eq!(replicated, casts);
+
+        state.apply(Event::CastsChanged { casts: Vec::new() });
+        assert!(state.casts.is_empty());
+    }
+
+    #[test]
+    fn other_events_pass_through() {
+        let mut state = CastsState::default();
+        let event = state.apply(Event::OverviewOpenedOrClosed { is_open: true });
+        assert!(matches!(
+            event,
+            Some(Event::OverviewOpenedOrClosed { is_open: true })
+        ));
+    }
+}
@@ -17,6 +17,7 @@ use smithay::wayland::presentation::Refresh;
 
 use super::{IpcOutputMap, OutputId, RenderResult};
 use crate::niri::{Niri, RedrawState};
+use crate::render_helpers::color::ColorDescription;
 use crate::utils::{get_monotonic_time, logical_output};
 
 pub struct Headless {
@@ -55,12 +56,13 @@ impl Headless {
         output.change_current_state(Some(mode), None, None, None);
         output.set_preferred(mode);
 
-        output.user_data().insert_if_missing(|| OutputName {
+        let output_name = OutputName {
             connector,
             make: Some(make),
             model: Some(model),
             serial: Some(serial),
-        });
+        };
+        output.user_data().insert_if_missing(|| output_name.clone());
 
         let physical_properties = output.physical_properties();
         self.ipc_outputs.lock().unwrap().insert(
@@ -84,7 +86,17 @@ impl Headless {
             },
         );
 
-        niri.add_output(output, None, false);
+        // There's no rendering here, but keep track of the color description for tests.
+        let hdr = niri
+            .config
+            .borrow()
+            .outputs
+            .find(&output_name)
+            .and_then(|config| config.hdr);
+        let description = ColorDescription::for_output(hdr.as_ref());
+
+        niri.add_output(output.clone(), None, false);
+        niri.set_output_color_description(&output, description);
     }
 
     pub fn seat_name(&self) -> String {
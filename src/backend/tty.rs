@@ -13,7 +13,7 @@ use std::{io, mem};
 use anyhow::{anyhow, bail, ensure, Context};
 use bytemuck::cast_slice_mut;
 use libc::dev_t;
-use niri_config::{Config, OutputName};
+use niri_config::{Config, HdrTransferFunction, OutputHdr, OutputName};
 use smithay::backend::allocator::dmabuf::Dmabuf;
 use smithay::backend::allocator::format::FormatSet;
 use smithay::backend::allocator::gbm::{GbmAllocator, GbmBufferFlags, GbmDevice};
@@ -46,7 +46,7 @@ use smithay::reexports::input::Libinput;
 use smithay::reexports::rustix::fs::OFlags;
 use smithay::reexports::wayland_protocols;
 use smithay::reexports::wayland_server::protocol::wl_surface::WlSurface;
-use smithay::utils::DeviceFd;
+use smithay::utils::{DeviceFd, Scale};
 use smithay::wayland::dmabuf::{DmabufFeedback, DmabufFeedbackBuilder, DmabufGlobal};
 use smithay::wayland::drm_lease::{
     DrmLease, DrmLeaseBuilder, DrmLeaseRequest, DrmLeaseState, LeaseRejected,
@@ -59,9 +59,14 @@ use wayland_protocols::wp::presentation_time::server::wp_presentation_feedback;
 use super::{IpcOutputMap, RenderResult};
 use crate::backend::OutputId;
 use crate::frame_clock::FrameClock;
+use crate::niri::OutputRenderElements;
 use crate::niri::{Niri, RedrawState, State};
+use crate::render_helpers::color::ColorDescription;
+use crate::render_helpers::color_transform::ColorTransformRenderElement;
 use crate::render_helpers::debug::draw_damage;
+use crate::render_helpers::offscreen::{OffscreenBuffer, OffscreenData};
 use crate::render_helpers::renderer::AsGlesRenderer;
+use crate::render_helpers::shaders::Shaders;
 use crate::render_helpers::{resources, shaders, RenderTarget};
 use crate::utils::{get_monotonic_time, is_laptop_panel, logical_output};
 
@@ -72,6 +77,18 @@ const SUPPORTED_COLOR_FORMATS: [Fourcc; 4] = [
     Fourcc::Abgr8888,
 ];
 
+/// Formats for outputs with a higher bit depth, falling back to the 8-bit ones.
+const SUPPORTED_COLOR_FORMATS_10_BIT: [Fourcc; 8] = [
+    Fourcc::Xrgb2101010,
+    Fourcc::Xbgr2101010,
+    Fourcc::Argb2101010,
+    Fourcc::Abgr2101010,
+    Fourcc::Xrgb8888,
+    Fourcc::Xbgr8888,
+    Fourcc::Argb8888,
+    Fourcc::Abgr8888,
+];
+
 pub struct Tty {
     config: Rc<RefCell<Config>>,
     session: LibSeatSession,
@@ -126,6 +143,8 @@ pub struct OutputDevice {
     drm_scanner: DrmScanner,
     surfaces: HashMap<crtc::Handle, Surface>,
     known_crtcs: HashMap<crtc::Handle, CrtcInfo>,
+    /// HDR_OUTPUT_METADATA blobs that we created for connectors, to destroy once replaced.
+    hdr_metadata_blobs: HashMap<connector::Handle, NonZeroU64>,
     // SAFETY: drop after all the objects used with them are dropped.
     // See https://github.com/Smithay/smithay/issues/1102.
     drm: DrmDevice,
@@ -225,6 +244,12 @@ struct Surface {
     connector: connector::Handle,
     dmabuf_feedback: Option<SurfaceDmabufFeedback>,
     gamma_props: Option<GammaProps>,
+    /// HDR config that the surface was set up with.
+    hdr: Option<OutputHdr>,
+    /// Whether the surface was set up with a 10-bit format.
+    is_10_bit: bool,
+    /// Buffer for rendering HDR outputs before the final conversion.
+    hdr_buffer: Option<OffscreenBuffer>,
     /// Gamma change to apply upon session resume.
     pending_gamma_change: Option<Option<Vec<u16>>>,
     /// Tracy frame that goes from vblank to vblank.
@@ -611,6 +636,7 @@ impl Tty {
             drm_scanner: DrmScanner::new(),
             surfaces: HashMap::new(),
             known_crtcs: HashMap::new(),
+            hdr_metadata_blobs: HashMap::new(),
             drm_lease_state,
             active_leases: Vec::new(),
             non_desktop_connectors: HashSet::new(),
@@ -857,12 +883,36 @@ impl Tty {
         }
         debug!("picking mode: {mode:?}");
 
-        // We only use 8888 RGB formats, so set max bpc to 8 to allow more types of links to run.
-        match set_max_bpc(&device.drm, connector.handle(), 8) {
+        // HDR needs the final conversion to happen on the GPU that renders everything else.
+        let mut hdr = config.hdr;
+        if hdr.is_some() && device.render_node != self.primary_render_node {
+            warn!("HDR is only supported on outputs connected to the primary GPU");
+            hdr = None;
+        }
+        let is_10_bit = hdr.is_some() || config.is_10_bit();
+
+        // Unless configured otherwise, we only use 8888 RGB formats, so set max bpc to 8 to allow
+        // more types of links to run.
+        let max_bpc = if is_10_bit { 10 } else { 8 };
+        match set_max_bpc(&device.drm, connector.handle(), max_bpc) {
             Ok(bpc) => debug!("set max bpc to {bpc}"),
             Err(err) => debug!("error setting max bpc: {err:?}"),
         }
 
+        // Also resets the metadata in case it was set before.
+        let mut blob = device.hdr_metadata_blobs.remove(&connector.handle());
+        let res = set_hdr_metadata(&device.drm, connector.handle(), hdr.as_ref(), &mut blob);
+        if let Some(blob) = blob {
+            device.hdr_metadata_blobs.insert(connector.handle(), blob);
+        }
+        if let Err(err) = res {
+            if hdr.is_some() {
+                warn!("error setting HDR metadata: {err:?}");
+            } else {
+                debug!("error resetting HDR metadata: {err:?}");
+            }
+        }
+
         let mut gamma_props = GammaProps::new(&device.drm, crtc)
             .map_err(|err| debug!("error getting gamma properties: {err:?}"))
             .ok();
@@ -966,6 +1016,12 @@ impl Tty {
             })
             .collect::<FormatSet>();
 
+        let color_formats: &[Fourcc] = if is_10_bit {
+            &SUPPORTED_COLOR_FORMATS_10_BIT
+        } else {
+            &SUPPORTED_COLOR_FORMATS
+        };
+
         // Create the compositor.
         let res = DrmCompositor::new(
             OutputModeSource::Auto(output.clone()),
@@ -973,7 +1029,7 @@ impl Tty {
             None,
             allocator.clone(),
             GbmFramebufferExporter::new(device.gbm.clone(), Some(device.render_node)),
-            SUPPORTED_COLOR_FORMATS,
+            color_formats.iter().copied(),
             // This is only used to pick a good internal format, so it can use the surface's render
             // formats, even though we only ever render on the primary GPU.
             render_formats.clone(),
@@ -1003,7 +1059,7 @@ impl Tty {
                     None,
                     allocator,
                     GbmFramebufferExporter::new(device.gbm.clone(), Some(device.render_node)),
-                    SUPPORTED_COLOR_FORMATS,
+                    color_formats.iter().copied(),
                     render_formats,
                     device.drm.cursor_size(),
                     Some(device.gbm.clone()),
@@ -1062,6 +1118,11 @@ impl Tty {
             compositor,
             dmabuf_feedback,
             gamma_props,
+            hdr,
+            is_10_bit,
+            // Blending happens in extended sRGB, which needs a floating point buffer to keep the
+            // values outside of the [0, 1] range.
+            hdr_buffer: hdr.map(|_| OffscreenBuffer::with_format(Fourcc::Abgr16161616f)),
             pending_gamma_change: None,
             vblank_frame: None,
             vblank_frame_name,
@@ -1074,6 +1135,7 @@ impl Tty {
         assert!(res.is_none(), "crtc must not have already existed");
 
         niri.add_output(output.clone(), Some(refresh_interval(mode)), vrr_enabled);
+        niri.set_output_color_description(&output, ColorDescription::for_output(hdr.as_ref()));
 
         if niri.monitors_active {
             // Redraw the new monitor.
@@ -1120,6 +1182,12 @@ impl Tty {
 
         debug!("disconnecting connector: {:?}", surface.name.connector);
 
+        if let Some(blob) = device.hdr_metadata_blobs.remove(&surface.connector) {
+            if let Err(err) = device.drm.destroy_property_blob(blob.get()) {
+                warn!("error destroying HDR_OUTPUT_METADATA blob: {err:?}");
+            }
+        }
+
         let output = niri
             .global_space
             .outputs()
@@ -1391,8 +1459,23 @@ impl Tty {
         };
 
         // Render the elements.
-        let mut elements =
-            niri.render::<TtyRenderer>(&mut renderer, output, true, RenderTarget::Output);
+        let mut hdr_data = None;
+        let mut elements = if let Some(hdr_buffer) = &surface.hdr_buffer {
+            let output_state = niri.output_state.get(output).unwrap();
+            let output_description = output_state.color_description;
+            match render_hdr(niri, &mut renderer, output, hdr_buffer, output_description) {
+                Ok((elem, data)) => {
+                    hdr_data = Some(data);
+                    vec![elem]
+                }
+                Err(err) => {
+                    warn!("error rendering HDR output: {err:?}");
+                    return rv;
+                }
+            }
+        } else {
+            niri.render::<TtyRenderer>(&mut renderer, output, true, RenderTarget::Output)
+        };
 
         // Visualize the damage, if enabled.
         if niri.debug_draw_damage {
@@ -1435,7 +1518,13 @@ impl Tty {
         // Hand them over to the DRM.
         let drm_compositor = &mut surface.compositor;
         match drm_compositor.render_frame::<_, _>(&mut renderer, &elements, [0.; 4], flags) {
-            Ok(res) => {
+            Ok(mut res) => {
+                // Surfaces on HDR outputs are rendered into the offscreen buffer, so their states
+                // are there rather than in the DRM compositor result.
+                if let Some(data) = hdr_data {
+                    res.states.states.extend(data.states.states);
+                }
+
                 let needs_sync = res.needs_sync()
                     || self
                         .config
@@ -1823,6 +1912,19 @@ impl Tty {
                     continue;
                 };
 
+                // Formats and HDR metadata are set up when connecting, so reconnect to change
+                // them.
+                let mut hdr = config.hdr;
+                if device.render_node != self.primary_render_node {
+                    hdr = None;
+                }
+                let is_10_bit = hdr.is_some() || config.is_10_bit();
+                if surface.hdr != hdr || surface.is_10_bit != is_10_bit {
+                    to_disconnect.push((node, crtc));
+                    to_connect.push((node, connector.clone(), crtc, surface.name.clone()));
+                    continue;
+                }
+
                 let Some((mode, fallback)) = pick_mode(connector, config.mode) else {
                     warn!("couldn't pick mode for enabled connector");
                     continue;
@@ -2478,6 +2580,136 @@ fn set_max_bpc(device: &DrmDevice, connector: connector::Handle, bpc: u64) -> an
     Err(anyhow!("couldn't find max bpc property"))
 }
 
+/// Sets the colorimetry and HDR metadata properties of the connector.
+///
+/// With `None`, resets them to the SDR defaults. `previous_blob` holds the metadata blob that we
+/// set before, which is destroyed and replaced once the new one is set.
+fn set_hdr_metadata(
+    device: &DrmDevice,
+    connector: connector::Handle,
+    hdr: Option<&OutputHdr>,
+    previous_blob: &mut Option<NonZeroU64>,
+) -> anyhow::Result<()> {
+    let (prop, info, _) =
+        find_drm_property(device, connector, "Colorspace").context("no Colorspace property")?;
+    let property::ValueType::Enum(values) = info.value_type() else {
+        bail!("wrong Colorspace property type")
+    };
+    let name = if hdr.is_some() {
+        "BT2020_RGB"
+    } else {
+        "Default"
+    };
+    let value = values
+        .values()
+        .1
+        .iter()
+        .find(|value| value.name().to_str() == Ok(name))
+        .with_context(|| format!("no {name} colorspace"))?;
+    device
+        .set_property(connector, prop, value.value())
+        .context("error setting Colorspace")?;
+
+    let (prop, _, _) = find_drm_property(device, connector, "HDR_OUTPUT_METADATA")
+        .context("no HDR_OUTPUT_METADATA property")?;
+
+    let blob = match hdr {
+        Some(hdr) => {
+            let value = device
+                .create_property_blob(&hdr_output_metadata(hdr))
+                .context("error creating HDR metadata blob")?;
+            let property::Value::Blob(blob) = value else {
+                unreachable!()
+            };
+            NonZeroU64::new(blob)
+        }
+        None => None,
+    };
+
+    let value = property::Value::Blob(blob.map_or(0, NonZeroU64::get));
+    device
+        .set_property(connector, prop, value.into())
+        .context("error setting HDR_OUTPUT_METADATA")
+        .inspect_err(|_| {
+            if let Some(blob) = blob {
+                // Destroy the blob we just allocated.
+                if let Err(err) = device.destroy_property_blob(blob.get()) {
+                    warn!("error destroying HDR_OUTPUT_METADATA blob: {err:?}");
+                }
+            }
+        })?;
+
+    if let Some(previous) = mem::replace(previous_blob, blob) {
+        if let Err(err) = device.destroy_property_blob(previous.get()) {
+            warn!("error destroying previous HDR_OUTPUT_METADATA blob: {err:?}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds a `struct hdr_output_metadata` for the HDR_OUTPUT_METADATA connector property.
+fn hdr_output_metadata(hdr: &OutputHdr) -> [u8; 32] {
+    // CTA-861-G EOTF values.
+    let eotf: u8 = match hdr.transfer_function {
+        HdrTransferFunction::Pq => 2,
+        HdrTransferFunction::Hlg => 3,
+    };
+
+    // BT.2020 primaries and D65 white point in units of 0.00002.
+    let primaries: [u16; 8] = [35400, 14600, 8500, 39850, 6550, 2300, 15635, 16450];
+    let max_luminance = u16::try_from(hdr.max_luminance).unwrap_or(u16::MAX);
+
+    let mut blob = [0u8; 32];
+    // metadata_type: HDMI_STATIC_METADATA_TYPE1.
+    blob[0..4].copy_from_slice(&0u32.to_ne_bytes());
+    // hdmi_metadata_type1.
+    blob[4] = eotf;
+    blob[5] = 0;
+    let mut fields = Vec::from(primaries);
+    fields.extend([
+        // max_display_mastering_luminance in cd/m².
+        max_luminance,
+        // min_display_mastering_luminance in 0.0001 cd/m².
+        0,
+        // max_cll.
+        max_luminance,
+        // max_fall, unknown.
+        0,
+    ]);
+    for (i, field) in fields.into_iter().enumerate() {
+        let offset = 6 + i * 2;
+        blob[offset..offset + 2].copy_from_slice(&field.to_ne_bytes());
+    }
+
+    blob
+}
+
+/// Renders the output into the HDR buffer and wraps it in the final conversion.
+fn render_hdr<'render>(
+    niri: &mut Niri,
+    renderer: &mut TtyRenderer<'render>,
+    output: &Output,
+    buffer: &OffscreenBuffer,
+    description: ColorDescription,
+) -> anyhow::Result<(OutputRenderElements<TtyRenderer<'render>>, OffscreenData)> {
+    let _span = tracy_client::span!("render_hdr");
+
+    let renderer = renderer.as_gles_renderer();
+    let program = Shaders::get(renderer)
+        .color_transform
+        .clone()
+        .context("the color transform shader is missing")?;
+
+    let scale = Scale::from(output.current_scale().fractional_scale());
+    let elements = niri.render::<GlesRenderer>(renderer, output, true, RenderTarget::Output);
+    let (elem, _sync, data) = buffer.render(renderer, scale, &elements)?;
+
+    // Blending happened in sRGB, convert the result into the output encoding.
+    let elem = ColorTransformRenderElement::new(elem, program, ColorDescription::SRGB, description);
+    Ok((elem.into(), data))
+}
+
 fn is_vrr_capable(device: &DrmDevice, connector: connector::Handle) -> Option<bool> {
     let (_, info, value) = find_drm_property(device, connector, "vrr_capable")?;
     info.value_type().convert_value(value).as_boolean()
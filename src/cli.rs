@@ -107,6 +107,22 @@ pub enum Msg {
     RequestError,
     /// Print the overview state.
     OverviewState,
+    /// Print the icon of a window and optionally save it as a PNG image.
+    WindowIcon {
+        /// Id of the window.
+        ///
+        /// Run `niri msg windows` to see the window ids.
+        #[arg(long)]
+        id: u64,
+        /// Path to save the icon to as a PNG image.
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+        /// Preferred icon size in pixels.
+        #[arg(short, long)]
+        size: Option<u32>,
+    },
+    /// List active screencasts.
+    Casts,
 }
 
 #[derive(Clone, Debug, clap::ValueEnum)]
@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use anyhow::Context;
+use futures_util::StreamExt;
+use niri_config::{Key, Modifiers, Trigger};
+use smithay::input::keyboard::keysyms::KEY_NoSymbol;
+use smithay::input::keyboard::xkb::{keysym_from_name, KEYSYM_CASE_INSENSITIVE};
+use zbus::fdo::{self, RequestNameFlags};
+use zbus::message::Header;
+use zbus::names::{UniqueName, WellKnownName};
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{NoneValue, Value};
+use zbus::{interface, Task};
+
+use super::Start;
+use crate::niri::ActionModes;
+
+/// Well-known names of the clients allowed to grab accelerators.
+///
+/// Matches the sender check of GNOME Shell: grabs are meant for the GlobalShortcuts portal and
+/// for media keys, not for arbitrary session bus clients.
+const ALLOWED_SENDERS: &[&str] = &[
+    "org.freedesktop.impl.portal.desktop.gnome",
+    "org.gnome.SettingsDaemon.MediaKeys",
+];
+
+/// Accelerator grab API of org.gnome.Shell, used by xdg-desktop-portal-gnome to implement the
+/// GlobalShortcuts portal.
+pub struct Shell {
+    to_niri: calloop::channel::Sender<ShellToNiri>,
+    monitor_task: Arc<OnceLock<Task<()>>>,
+}
+
+pub enum ShellToNiri {
+    GrabAccelerators {
+        /// Unique bus name of the client grabbing the accelerators.
+        sender: String,
+        /// Parsed accelerators and their action modes, `None` for ones that we failed to parse or
+        /// refused.
+        keys: Vec<Option<(Key, ActionModes)>>,
+        tx: async_channel::Sender<Vec<u32>>,
+    },
+    UngrabAccelerators {
+        sender: String,
+        ids: Vec<u32>,
+        tx: async_channel::Sender<bool>,
+    },
+    /// A client disconnected from the bus, so its grabs should go away.
+    ClientDisappeared { sender: String },
+}
+
+#[interface(name = "org.gnome.Shell")]
+impl Shell {
+    async fn grab_accelerator(
+        &self,
+        accelerator: String,
+        mode_flags: u32,
+        grab_flags: u32,
+        #[zbus(connection)] conn: &zbus::Connection,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> fdo::Result<u32> {
+        let ids = self
+            .grab_accelerators(vec![(accelerator, mode_flags, grab_flags)], conn, hdr)
+            .await?;
+        Ok(ids.first().copied().unwrap_or(0))
+    }
+
+    async fn grab_accelerators(
+        &self,
+        accelerators: Vec<(String, u32, u32)>,
+        #[zbus(connection)] conn: &zbus::Connection,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> fdo::Result<Vec<u32>> {
+        let sender = check_sender(conn, &hdr).await?;
+
+        let keys = accelerators
+            .into_iter()
+            .map(|(accelerator, mode_flags, _)| {
+                let Some(key) = parse_accelerator(&accelerator) else {
+                    debug!("failed to parse accelerator: {accelerator:?}");
+                    return None;
+                };
+                if !is_grabbable(key) {
+                    debug!("refusing to grab accelerator without modifiers: {accelerator:?}");
+                    return None;
+                }
+                let Some(modes) = parse_action_modes(mode_flags) else {
+                    debug!(
+                        "refusing to grab accelerator with unsupported action modes \
+                         {mode_flags:#x}: {accelerator:?}"
+                    );
+                    return None;
+                };
+                Some((key, modes))
+            })
+            .collect();
+
+        let (tx, rx) = async_channel::bounded(1);
+        let msg = ShellToNiri::GrabAccelerators { sender, keys, tx };
+        if let Err(err) = self.to_niri.send(msg) {
+            warn!("error sending grab accelerators message to niri: {err:?}");
+            return Err(fdo::Error::Failed("internal error".to_owned()));
+        }
+
+        rx.recv().await.map_err(|err| {
+            warn!("error receiving message from niri: {err:?}");
+            fdo::Error::Failed("internal error".to_owned())
+        })
+    }
+
+    async fn ungrab_accelerator(
+        &self,
+        action: u32,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> fdo::Result<bool> {
+        self.ungrab_accelerators(vec![action], hdr).await
+    }
+
+    async fn ungrab_accelerators(
+        &self,
+        actions: Vec<u32>,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> fdo::Result<bool> {
+        // Grabs are tied to the sender, so there's nothing to ungrab without one.
+        let Some(sender) = hdr.sender() else {
+            return Ok(false);
+        };
+
+        let (tx, rx) = async_channel::bounded(1);
+        let msg = ShellToNiri::UngrabAccelerators {
+            sender: sender.to_string(),
+            ids: actions,
+            tx,
+        };
+        if let Err(err) = self.to_niri.send(msg) {
+            warn!("error sending ungrab accelerators message to niri: {err:?}");
+            return Err(fdo::Error::Failed("internal error".to_owned()));
+        }
+
+        rx.recv().await.map_err(|err| {
+            warn!("error receiving message from niri: {err:?}");
+            fdo::Error::Failed("internal error".to_owned())
+        })
+    }
+
+    #[zbus(signal)]
+    pub async fn accelerator_activated(
+        ctxt: &SignalEmitter<'_>,
+        action: u32,
+        parameters: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    pub async fn accelerator_deactivated(
+        ctxt: &SignalEmitter<'_>,
+        action: u32,
+        parameters: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<()>;
+}
+
+impl Shell {
+    pub fn new(to_niri: calloop::channel::Sender<ShellToNiri>) -> Self {
+        Self {
+            to_niri,
+            monitor_task: Arc::new(OnceLock::new()),
+        }
+    }
+}
+
+/// Returns the unique name of the sender if it's allowed to grab accelerators.
+async fn check_sender(conn: &zbus::Connection, hdr: &Header<'_>) -> fdo::Result<String> {
+    let Some(sender) = hdr.sender() else {
+        return Err(fdo::Error::AccessDenied(String::from("no sender")));
+    };
+
+    let proxy = fdo::DBusProxy::new(conn).await?;
+    for name in ALLOWED_SENDERS {
+        let name = WellKnownName::from_static_str_unchecked(name);
+        if let Ok(owner) = proxy.get_name_owner(name.into()).await {
+            if *owner == *sender {
+                return Ok(sender.to_string());
+            }
+        }
+    }
+
+    debug!("refusing accelerator grab from {sender}");
+    Err(fdo::Error::AccessDenied(format!(
+        "{sender} is not allowed to grab accelerators"
+    )))
+}
+
+/// Returns whether the accelerator may be grabbed.
+///
+/// Grabs of keys that are normally typed, like bare letters or Shift+letters, would break typing
+/// everywhere, so they require a modifier. Media and other XF86 keys don't produce text, so
+/// gnome-settings-daemon can grab them on their own.
+fn is_grabbable(key: Key) -> bool {
+    let Trigger::Keysym(keysym) = key.trigger else {
+        return false;
+    };
+
+    !(key.modifiers - Modifiers::SHIFT).is_empty()
+        || (0x1008_ff00..=0x1008_ffff).contains(&keysym.raw())
+}
+
+/// Parses Shell.ActionMode flags, keeping the modes that niri has.
+///
+/// Returns `None` when none of the modes are supported, since such a grab would never fire.
+fn parse_action_modes(mode_flags: u32) -> Option<ActionModes> {
+    let modes = ActionModes::from_bits_truncate(mode_flags);
+    (!modes.is_empty()).then_some(modes)
+}
+
+async fn monitor_disappeared_clients(
+    conn: &zbus::Connection,
+    to_niri: calloop::channel::Sender<ShellToNiri>,
+) -> anyhow::Result<()> {
+    let proxy = fdo::DBusProxy::new(conn)
+        .await
+        .context("error creating a DBusProxy")?;
+
+    let mut stream = proxy
+        .receive_name_owner_changed_with_args(&[(2, UniqueName::null_value())])
+        .await
+        .context("error creating a NameOwnerChanged stream")?;
+
+    while let Some(signal) = stream.next().await {
+        let args = signal
+            .args()
+            .context("error retrieving NameOwnerChanged args")?;
+
+        let Some(name) = &**args.old_owner() else {
+            continue;
+        };
+
+        if args.new_owner().is_none() {
+            let msg = ShellToNiri::ClientDisappeared {
+                sender: name.to_string(),
+            };
+            if let Err(err) = to_niri.send(msg) {
+                warn!("error sending client disappeared message to niri: {err:?}");
+            }
+        } else {
+            error!("non-null new_owner should've been filtered out");
+        }
+    }
+
+    Ok(())
+}
+
+impl Start for Shell {
+    fn start(self) -> anyhow::Result<zbus::blocking::Connection> {
+        let to_niri = self.to_niri.clone();
+        let monitor_task = self.monitor_task.clone();
+
+        let conn = zbus::blocking::Connection::session()?;
+        let flags = RequestNameFlags::AllowReplacement
+            | RequestNameFlags::ReplaceExisting
+            | RequestNameFlags::DoNotQueue;
+
+        conn.object_server().at("/org/gnome/Shell", self)?;
+        conn.request_name_with_flags("org.gnome.Shell", flags)?;
+
+        let async_conn = conn.inner();
+        let future = {
+            let conn = async_conn.clone();
+            async move {
+                if let Err(err) = monitor_disappeared_clients(&conn, to_niri).await {
+                    warn!("error monitoring org.gnome.Shell clients: {err:?}");
+                }
+            }
+        };
+        let task = async_conn
+            .executor()
+            .spawn(future, "monitor disappearing clients");
+        monitor_task.set(task).unwrap();
+
+        Ok(conn)
+    }
+}
+
+/// Parses a GTK accelerator string like `<Control><Alt>m`.
+fn parse_accelerator(accelerator: &str) -> Option<Key> {
+    let mut modifiers = Modifiers::empty();
+
+    let mut rest = accelerator.trim();
+    while let Some(stripped) = rest.strip_prefix('<') {
+        let (name, tail) = stripped.split_once('>')?;
+        modifiers |= match &*name.to_ascii_lowercase() {
+            "control" | "ctrl" | "ctl" | "primary" => Modifiers::CTRL,
+            "shift" | "shft" => Modifiers::SHIFT,
+            "alt" | "mod1" => Modifiers::ALT,
+            "super" | "mod4" => Modifiers::SUPER,
+            _ => return None,
+        };
+        rest = tail;
+    }
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    let keysym = keysym_from_name(rest, KEYSYM_CASE_INSENSITIVE);
+    if keysym.raw() == KEY_NoSymbol {
+        return None;
+    }
+
+    Some(Key {
+        trigger: Trigger::Keysym(keysym),
+        modifiers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[track_caller]
+    fn check(accelerator: &str, expected: &str) {
+        let expected = Key::from_str(expected).unwrap();
+        assert_eq!(parse_accelerator(accelerator), Some(expected));
+    }
+
+    #[test]
+    fn test_parse_accelerator() {
+        check("<Control><Alt>m", "Ctrl+Alt+M");
+        check("<Primary><Shift>F5", "Ctrl+Shift+F5");
+        check("<Super>Return", "Super+Return");
+        check("<Mod4><Mod1>space", "Super+Alt+Space");
+        check("Pause", "Pause");
+
+        assert_eq!(parse_accelerator(""), None);
+        assert_eq!(parse_accelerator("<Control>"), None);
+        assert_eq!(parse_accelerator("<Hyper>x"), None);
+        assert_eq!(parse_accelerator("<Control>notakey"), None);
+        assert_eq!(parse_accelerator("<Control"), None);
+    }
+
+    #[test]
+    fn test_is_grabbable() {
+        let grabbable = |accelerator| is_grabbable(parse_accelerator(accelerator).unwrap());
+
+        assert!(grabbable("<Control><Alt>m"));
+        assert!(grabbable("<Super>a"));
+        assert!(grabbable("XF86AudioPlay"));
+        assert!(grabbable("<Shift>XF86AudioNext"));
+
+        assert!(!grabbable("a"));
+        assert!(!grabbable("<Shift>a"));
+        assert!(!grabbable("space"));
+        assert!(!grabbable("Pause"));
+    }
+
+    #[test]
+    fn test_parse_action_modes() {
+        assert_eq!(parse_action_modes(1), Some(ActionModes::NORMAL));
+        assert_eq!(parse_action_modes(2), Some(ActionModes::OVERVIEW));
+        // Shell.ActionMode.ALL.
+        assert_eq!(parse_action_modes(!0), Some(ActionModes::all()));
+        // NORMAL | LOCK_SCREEN.
+        assert_eq!(parse_action_modes(0b101), Some(ActionModes::NORMAL));
+
+        assert_eq!(parse_action_modes(0), None);
+        // LOCK_SCREEN | UNLOCK_SCREEN.
+        assert_eq!(parse_action_modes(0b1100), None);
+    }
+}
@@ -5,19 +5,27 @@ use crate::niri::State;
 
 pub mod freedesktop_locale1;
 pub mod freedesktop_screensaver;
+pub mod gnome_shell;
 pub mod gnome_shell_introspect;
 pub mod gnome_shell_screenshot;
 pub mod mutter_display_config;
+pub mod mutter_idle_monitor;
 pub mod mutter_service_channel;
 
+#[cfg(feature = "xdp-gnome-screencast")]
+pub mod mutter_remote_desktop;
 #[cfg(feature = "xdp-gnome-screencast")]
 pub mod mutter_screen_cast;
 #[cfg(feature = "xdp-gnome-screencast")]
+use mutter_remote_desktop::{RemoteDesktop, RemoteDesktopSessions};
+#[cfg(feature = "xdp-gnome-screencast")]
 use mutter_screen_cast::ScreenCast;
 
 use self::freedesktop_screensaver::ScreenSaver;
+use self::gnome_shell::Shell;
 use self::gnome_shell_introspect::Introspect;
 use self::mutter_display_config::DisplayConfig;
+use self::mutter_idle_monitor::IdleMonitor;
 use self::mutter_service_channel::ServiceChannel;
 
 trait Start: Interface {
@@ -29,10 +37,14 @@ pub struct DBusServers {
     pub conn_service_channel: Option<Connection>,
     pub conn_display_config: Option<Connection>,
     pub conn_screen_saver: Option<Connection>,
+    pub conn_idle_monitor: Option<Connection>,
     pub conn_screen_shot: Option<Connection>,
     pub conn_introspect: Option<Connection>,
+    pub conn_shell: Option<Connection>,
     #[cfg(feature = "xdp-gnome-screencast")]
     pub conn_screen_cast: Option<Connection>,
+    #[cfg(feature = "xdp-gnome-screencast")]
+    pub conn_remote_desktop: Option<Connection>,
     pub conn_locale1: Option<Connection>,
 }
 
@@ -62,7 +74,11 @@ impl DBusServers {
 
         if is_session_instance || config.debug.dbus_interfaces_in_non_session_instances {
             let (to_niri, from_display_config) = calloop::channel::channel();
-            let display_config = DisplayConfig::new(to_niri, backend.ipc_outputs());
+            let display_config = DisplayConfig::new(
+                to_niri,
+                backend.ipc_outputs(),
+                niri.is_night_light_supported.clone(),
+            );
             niri.event_loop
                 .insert_source(from_display_config, move |event, _, state| match event {
                     calloop::channel::Event::Msg(new_conf) => {
@@ -85,6 +101,16 @@ impl DBusServers {
             let screen_saver = ScreenSaver::new(niri.is_fdo_idle_inhibited.clone());
             dbus.conn_screen_saver = try_start(screen_saver);
 
+            let (to_niri, from_idle_monitor) = calloop::channel::channel();
+            niri.event_loop
+                .insert_source(from_idle_monitor, move |event, _, state| match event {
+                    calloop::channel::Event::Msg(msg) => state.on_idle_monitor_msg(msg),
+                    calloop::channel::Event::Closed => (),
+                })
+                .unwrap();
+            let idle_monitor = IdleMonitor::new(to_niri);
+            dbus.conn_idle_monitor = try_start(idle_monitor);
+
             let (to_niri, from_screenshot) = calloop::channel::channel();
             let (to_screenshot, from_niri) = async_channel::unbounded();
             niri.event_loop
@@ -111,6 +137,16 @@ impl DBusServers {
             let introspect = Introspect::new(to_niri, from_niri);
             dbus.conn_introspect = try_start(introspect);
 
+            let (to_niri, from_shell) = calloop::channel::channel();
+            niri.event_loop
+                .insert_source(from_shell, move |event, _, state| match event {
+                    calloop::channel::Event::Msg(msg) => state.on_shell_msg(msg),
+                    calloop::channel::Event::Closed => (),
+                })
+                .unwrap();
+            let shell = Shell::new(to_niri);
+            dbus.conn_shell = try_start(shell);
+
             #[cfg(feature = "xdp-gnome-screencast")]
             {
                 let (to_niri, from_screen_cast) = calloop::channel::channel();
@@ -122,8 +158,30 @@ impl DBusServers {
                         }
                     })
                     .unwrap();
-                let screen_cast = ScreenCast::new(backend.ipc_outputs(), to_niri);
+                let remote_desktop_sessions = RemoteDesktopSessions::default();
+                let screen_cast = ScreenCast::new(
+                    backend.ipc_outputs(),
+                    to_niri,
+                    remote_desktop_sessions.clone(),
+                );
                 dbus.conn_screen_cast = try_start(screen_cast);
+
+                // Remote desktop allows injecting arbitrary input, so only expose it on request.
+                if config.remote_desktop.on {
+                    let (to_niri, from_remote_desktop) = calloop::channel::channel();
+                    niri.event_loop
+                        .insert_source(from_remote_desktop, {
+                            move |event, _, state| match event {
+                                calloop::channel::Event::Msg(msg) => {
+                                    state.on_remote_desktop_msg(msg)
+                                }
+                                calloop::channel::Event::Closed => (),
+                            }
+                        })
+                        .unwrap();
+                    let remote_desktop = RemoteDesktop::new(to_niri, remote_desktop_sessions);
+                    dbus.conn_remote_desktop = try_start(remote_desktop);
+                }
             }
         }
 
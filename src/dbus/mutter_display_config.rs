@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use serde::{Deserialize, Serialize};
@@ -17,6 +18,7 @@ use crate::utils::scale::supported_scales;
 pub struct DisplayConfig {
     to_niri: calloop::channel::Sender<HashMap<String, Option<niri_config::Output>>>,
     ipc_outputs: Arc<Mutex<IpcOutputMap>>,
+    is_night_light_supported: Arc<AtomicBool>,
 }
 
 #[derive(Serialize, Type)]
@@ -272,7 +274,7 @@ impl DisplayConfig {
 
     #[zbus(property)]
     fn night_light_supported(&self) -> bool {
-        false
+        self.is_night_light_supported.load(Ordering::SeqCst)
     }
 }
 
@@ -280,10 +282,12 @@ impl DisplayConfig {
     pub fn new(
         to_niri: calloop::channel::Sender<HashMap<String, Option<niri_config::Output>>>,
         ipc_outputs: Arc<Mutex<IpcOutputMap>>,
+        is_night_light_supported: Arc<AtomicBool>,
     ) -> Self {
         Self {
             to_niri,
             ipc_outputs,
+            is_night_light_supported,
         }
     }
 }
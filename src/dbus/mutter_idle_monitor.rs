@@ -0,0 +1,195 @@
+use std::sync::{Arc, OnceLock};
+
+use anyhow::Context;
+use futures_util::StreamExt;
+use zbus::fdo::{self, ObjectManager, RequestNameFlags};
+use zbus::message::Header;
+use zbus::names::UniqueName;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::NoneValue;
+use zbus::{interface, Task};
+
+use super::Start;
+
+/// Idle time API of Mutter, used by GNOME components (for example, gnome-settings-daemon through
+/// GnomeIdleMonitor) to react to the user going idle or becoming active.
+pub struct IdleMonitor {
+    to_niri: calloop::channel::Sender<IdleMonitorToNiri>,
+    monitor_task: Arc<OnceLock<Task<()>>>,
+}
+
+pub enum IdleMonitorToNiri {
+    GetIdletime {
+        tx: async_channel::Sender<u64>,
+    },
+    AddIdleWatch {
+        /// Unique bus name of the client adding the watch.
+        sender: String,
+        interval_ms: u64,
+        tx: async_channel::Sender<u32>,
+    },
+    AddUserActiveWatch {
+        sender: String,
+        tx: async_channel::Sender<u32>,
+    },
+    RemoveWatch {
+        sender: String,
+        id: u32,
+    },
+    /// The client left the bus, so its watches should go away.
+    ClientDisappeared {
+        sender: String,
+    },
+}
+
+#[interface(name = "org.gnome.Mutter.IdleMonitor")]
+impl IdleMonitor {
+    async fn get_idletime(&self) -> fdo::Result<u64> {
+        let (tx, rx) = async_channel::bounded(1);
+        self.send(IdleMonitorToNiri::GetIdletime { tx })?;
+        recv(rx).await
+    }
+
+    async fn add_idle_watch(
+        &self,
+        interval: u64,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> fdo::Result<u32> {
+        if interval == 0 {
+            return Err(fdo::Error::InvalidArgs(String::from(
+                "interval must be greater than zero",
+            )));
+        }
+
+        let (tx, rx) = async_channel::bounded(1);
+        self.send(IdleMonitorToNiri::AddIdleWatch {
+            sender: sender(&hdr)?,
+            interval_ms: interval,
+            tx,
+        })?;
+        recv(rx).await
+    }
+
+    async fn add_user_active_watch(&self, #[zbus(header)] hdr: Header<'_>) -> fdo::Result<u32> {
+        let (tx, rx) = async_channel::bounded(1);
+        self.send(IdleMonitorToNiri::AddUserActiveWatch {
+            sender: sender(&hdr)?,
+            tx,
+        })?;
+        recv(rx).await
+    }
+
+    async fn remove_watch(&self, id: u32, #[zbus(header)] hdr: Header<'_>) -> fdo::Result<()> {
+        self.send(IdleMonitorToNiri::RemoveWatch {
+            sender: sender(&hdr)?,
+            id,
+        })
+    }
+
+    #[zbus(signal)]
+    pub async fn watch_fired(ctxt: &SignalEmitter<'_>, id: u32) -> zbus::Result<()>;
+}
+
+impl IdleMonitor {
+    pub fn new(to_niri: calloop::channel::Sender<IdleMonitorToNiri>) -> Self {
+        Self {
+            to_niri,
+            monitor_task: Arc::new(OnceLock::new()),
+        }
+    }
+
+    fn send(&self, msg: IdleMonitorToNiri) -> fdo::Result<()> {
+        self.to_niri.send(msg).map_err(|err| {
+            warn!("error sending idle monitor message to niri: {err:?}");
+            fdo::Error::Failed("internal error".to_owned())
+        })
+    }
+}
+
+/// Returns the unique name of the sender, which owns the watches that it adds.
+fn sender(hdr: &Header<'_>) -> fdo::Result<String> {
+    // Watches are tied to the sender, and their signals go only to it.
+    let sender = hdr
+        .sender()
+        .ok_or_else(|| fdo::Error::AccessDenied(String::from("no sender")))?;
+    Ok(sender.to_string())
+}
+
+async fn recv<T>(rx: async_channel::Receiver<T>) -> fdo::Result<T> {
+    rx.recv().await.map_err(|err| {
+        warn!("error receiving message from niri: {err:?}");
+        fdo::Error::Failed("internal error".to_owned())
+    })
+}
+
+async fn monitor_disappeared_clients(
+    conn: &zbus::Connection,
+    to_niri: calloop::channel::Sender<IdleMonitorToNiri>,
+) -> anyhow::Result<()> {
+    let proxy = fdo::DBusProxy::new(conn)
+        .await
+        .context("error creating a DBusProxy")?;
+
+    let mut stream = proxy
+        .receive_name_owner_changed_with_args(&[(2, UniqueName::null_value())])
+        .await
+        .context("error creating a NameOwnerChanged stream")?;
+
+    while let Some(signal) = stream.next().await {
+        let args = signal
+            .args()
+            .context("error retrieving NameOwnerChanged args")?;
+
+        let Some(name) = &**args.old_owner() else {
+            continue;
+        };
+
+        if args.new_owner().is_none() {
+            let msg = IdleMonitorToNiri::ClientDisappeared {
+                sender: name.to_string(),
+            };
+            if let Err(err) = to_niri.send(msg) {
+                warn!("error sending client disappeared message to niri: {err:?}");
+            }
+        } else {
+            error!("non-null new_owner should've been filtered out");
+        }
+    }
+
+    Ok(())
+}
+
+impl Start for IdleMonitor {
+    fn start(self) -> anyhow::Result<zbus::blocking::Connection> {
+        let to_niri = self.to_niri.clone();
+        let monitor_task = self.monitor_task.clone();
+
+        let conn = zbus::blocking::Connection::session()?;
+        let flags = RequestNameFlags::AllowReplacement
+            | RequestNameFlags::ReplaceExisting
+            | RequestNameFlags::DoNotQueue;
+
+        // GnomeIdleMonitor finds the monitor through the object manager.
+        conn.object_server()
+            .at("/org/gnome/Mutter/IdleMonitor", ObjectManager)?;
+        conn.object_server()
+            .at("/org/gnome/Mutter/IdleMonitor/Core", self)?;
+        conn.request_name_with_flags("org.gnome.Mutter.IdleMonitor", flags)?;
+
+        let async_conn = conn.inner();
+        let future = {
+            let conn = async_conn.clone();
+            async move {
+                if let Err(err) = monitor_disappeared_clients(&conn, to_niri).await {
+                    warn!("error monitoring org.gnome.Mutter.IdleMonitor clients: {err:?}");
+                }
+            }
+        };
+        let task = async_conn
+            .executor()
+            .spawn(future, "monitor disappearing clients");
+        monitor_task.set(task).unwrap();
+
+        Ok(conn)
+    }
+}
@@ -0,0 +1,416 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use smithay::backend::input::AxisSource;
+use zbus::fdo::RequestNameFlags;
+use zbus::object_server::{InterfaceRef, SignalEmitter};
+use zbus::zvariant::OwnedObjectPath;
+use zbus::{fdo, interface, ObjectServer};
+
+use super::mutter_screen_cast::{self, StreamTargetId};
+use super::Start;
+
+// Device types from the Mutter D-Bus interface.
+const DEVICE_TYPE_KEYBOARD: u32 = 1 << 0;
+const DEVICE_TYPE_POINTER: u32 = 1 << 1;
+const DEVICE_TYPE_TOUCHSCREEN: u32 = 1 << 2;
+
+// Flags of NotifyPointerAxis.
+const AXIS_FLAG_FINISH: u32 = 1 << 0;
+const AXIS_FLAG_SOURCE_WHEEL: u32 = 1 << 1;
+const AXIS_FLAG_SOURCE_FINGER: u32 = 1 << 2;
+const AXIS_FLAG_SOURCE_CONTINUOUS: u32 = 1 << 3;
+
+pub type RemoteDesktopSessions = Arc<Mutex<Vec<(Session, InterfaceRef<Session>)>>>;
+
+#[derive(Clone)]
+pub struct RemoteDesktop {
+    to_niri: calloop::channel::Sender<RemoteDesktopToNiri>,
+    sessions: RemoteDesktopSessions,
+}
+
+#[derive(Clone)]
+pub struct Session {
+    id: usize,
+    to_niri: calloop::channel::Sender<RemoteDesktopToNiri>,
+    /// Screencast session created with our session ID, if any.
+    screen_cast: Arc<Mutex<Option<PairedScreenCast>>>,
+    started: Arc<AtomicBool>,
+    stopped: Arc<AtomicBool>,
+    /// Stops the session in niri once the last handle to it is gone.
+    _teardown: Arc<SessionTeardown>,
+}
+
+struct SessionTeardown {
+    id: usize,
+    to_niri: calloop::channel::Sender<RemoteDesktopToNiri>,
+}
+
+type PairedScreenCast = (
+    mutter_screen_cast::Session,
+    InterfaceRef<mutter_screen_cast::Session>,
+);
+
+pub enum RemoteDesktopToNiri {
+    StartSession {
+        session_id: usize,
+        screen_cast_session_id: Option<usize>,
+    },
+    StopSession {
+        session_id: usize,
+        screen_cast_session_id: Option<usize>,
+    },
+    Input {
+        session_id: usize,
+        event: RemoteDesktopInput,
+    },
+}
+
+#[derive(Debug)]
+pub enum RemoteDesktopInput {
+    /// Evdev keycode.
+    KeyboardKeycode {
+        keycode: u32,
+        pressed: bool,
+    },
+    KeyboardKeysym {
+        keysym: u32,
+        pressed: bool,
+    },
+    /// Evdev button code.
+    PointerButton {
+        button: u32,
+        pressed: bool,
+    },
+    PointerAxis {
+        dx: f64,
+        dy: f64,
+        source: AxisSource,
+        finish: bool,
+    },
+    PointerAxisDiscrete {
+        horizontal: bool,
+        steps: i32,
+    },
+    PointerMotionRelative {
+        dx: f64,
+        dy: f64,
+    },
+    /// Position in stream buffer coordinates.
+    PointerMotionAbsolute {
+        stream: StreamTargetId,
+        x: f64,
+        y: f64,
+    },
+    TouchDown {
+        stream: StreamTargetId,
+        slot: u32,
+        x: f64,
+        y: f64,
+    },
+    TouchMotion {
+        stream: StreamTargetId,
+        slot: u32,
+        x: f64,
+        y: f64,
+    },
+    TouchUp {
+        slot: u32,
+    },
+}
+
+#[interface(name = "org.gnome.Mutter.RemoteDesktop")]
+impl RemoteDesktop {
+    async fn create_session(
+        &self,
+        #[zbus(object_server)] server: &ObjectServer,
+    ) -> fdo::Result<OwnedObjectPath> {
+        static NUMBER: AtomicUsize = AtomicUsize::new(0);
+        let session_id = NUMBER.fetch_add(1, Ordering::SeqCst);
+        let path = format!("/org/gnome/Mutter/RemoteDesktop/Session/u{session_id}");
+        let path = OwnedObjectPath::try_from(path).unwrap();
+
+        let session = Session::new(session_id, self.to_niri.clone());
+        match server.at(&path, session.clone()).await {
+            Ok(true) => {
+                let iface = server.interface(&path).await.unwrap();
+                let mut sessions = self.sessions.lock().unwrap();
+                sessions.retain(|(session, _)| !session.stopped.load(Ordering::SeqCst));
+                sessions.push((session, iface));
+            }
+            Ok(false) => return Err(fdo::Error::Failed("session path already exists".to_owned())),
+            Err(err) => {
+                return Err(fdo::Error::Failed(format!(
+                    "error creating session object: {err:?}"
+                )))
+            }
+        }
+
+        Ok(path)
+    }
+
+    #[zbus(property)]
+    async fn supported_device_types(&self) -> u32 {
+        DEVICE_TYPE_KEYBOARD | DEVICE_TYPE_POINTER | DEVICE_TYPE_TOUCHSCREEN
+    }
+
+    #[zbus(property)]
+    async fn version(&self) -> i32 {
+        1
+    }
+}
+
+#[interface(name = "org.gnome.Mutter.RemoteDesktop.Session")]
+impl Session {
+    #[zbus(property)]
+    async fn session_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    async fn start(&self) -> fdo::Result<()> {
+        debug!("start");
+
+        if self.stopped.load(Ordering::SeqCst) {
+            return Err(fdo::Error::Failed("session is stopped".to_owned()));
+        }
+        if self.started.swap(true, Ordering::SeqCst) {
+            return Err(fdo::Error::Failed("session is already started".to_owned()));
+        }
+
+        let screen_cast = self.screen_cast.lock().unwrap();
+        if let Some((session, _)) = &*screen_cast {
+            session.start_streams();
+        }
+
+        let msg = RemoteDesktopToNiri::StartSession {
+            session_id: self.id,
+            screen_cast_session_id: screen_cast.as_ref().map(|(session, _)| session.id()),
+        };
+        if let Err(err) = self.to_niri.send(msg) {
+            warn!("error sending StartSession to niri: {err:?}");
+        }
+
+        Ok(())
+    }
+
+    pub async fn stop(
+        &self,
+        #[zbus(object_server)] server: &ObjectServer,
+        #[zbus(signal_context)] ctxt: SignalEmitter<'_>,
+    ) {
+        debug!("stop");
+
+        if self.stopped.swap(true, Ordering::SeqCst) {
+            // Already stopped.
+            return;
+        }
+
+        Session::closed(&ctxt).await.unwrap();
+
+        let screen_cast = self.screen_cast.lock().unwrap().take();
+        let msg = RemoteDesktopToNiri::StopSession {
+            session_id: self.id,
+            screen_cast_session_id: screen_cast.map(|(session, _)| session.id()),
+        };
+        if let Err(err) = self.to_niri.send(msg) {
+            warn!("error sending StopSession to niri: {err:?}");
+        }
+
+        server.remove::<Session, _>(ctxt.path()).await.unwrap();
+    }
+
+    async fn notify_keyboard_keycode(&self, keycode: u32, state: bool) -> fdo::Result<()> {
+        // Evdev keycodes get offset by 8 into XKB keycodes when injected.
+        if keycode.checked_add(8).is_none() {
+            return Err(fdo::Error::InvalidArgs("invalid keycode".to_owned()));
+        }
+
+        self.send_input(RemoteDesktopInput::KeyboardKeycode {
+            keycode,
+            pressed: state,
+        })
+    }
+
+    async fn notify_keyboard_keysym(&self, keysym: u32, state: bool) -> fdo::Result<()> {
+        self.send_input(RemoteDesktopInput::KeyboardKeysym {
+            keysym,
+            pressed: state,
+        })
+    }
+
+    async fn notify_pointer_button(&self, button: i32, state: bool) -> fdo::Result<()> {
+        let Ok(button) = u32::try_from(button) else {
+            return Err(fdo::Error::InvalidArgs("invalid button".to_owned()));
+        };
+
+        self.send_input(RemoteDesktopInput::PointerButton {
+            button,
+            pressed: state,
+        })
+    }
+
+    async fn notify_pointer_axis(&self, dx: f64, dy: f64, flags: u32) -> fdo::Result<()> {
+        let source = if flags & AXIS_FLAG_SOURCE_WHEEL != 0 {
+            AxisSource::Wheel
+        } else if flags & AXIS_FLAG_SOURCE_FINGER != 0 {
+            AxisSource::Finger
+        } else if flags & AXIS_FLAG_SOURCE_CONTINUOUS != 0 {
+            AxisSource::Continuous
+        } else {
+            AxisSource::Finger
+        };
+
+        self.send_input(RemoteDesktopInput::PointerAxis {
+            dx,
+            dy,
+            source,
+            finish: flags & AXIS_FLAG_FINISH != 0,
+        })
+    }
+
+    async fn notify_pointer_axis_discrete(&self, axis: u32, steps: i32) -> fdo::Result<()> {
+        let horizontal = match axis {
+            0 => false,
+            1 => true,
+            _ => return Err(fdo::Error::InvalidArgs("invalid axis".to_owned())),
+        };
+
+        self.send_input(RemoteDesktopInput::PointerAxisDiscrete { horizontal, steps })
+    }
+
+    async fn notify_pointer_motion_relative(&self, dx: f64, dy: f64) -> fdo::Result<()> {
+        self.send_input(RemoteDesktopInput::PointerMotionRelative { dx, dy })
+    }
+
+    async fn notify_pointer_motion_absolute(
+        &self,
+        stream: &str,
+        x: f64,
+        y: f64,
+    ) -> fdo::Result<()> {
+        let stream = self.stream_target(stream)?;
+        self.send_input(RemoteDesktopInput::PointerMotionAbsolute { stream, x, y })
+    }
+
+    async fn notify_touch_down(&self, stream: &str, slot: u32, x: f64, y: f64) -> fdo::Result<()> {
+        let stream = self.stream_target(stream)?;
+        self.send_input(RemoteDesktopInput::TouchDown { stream, slot, x, y })
+    }
+
+    async fn notify_touch_motion(
+        &self,
+        stream: &str,
+        slot: u32,
+        x: f64,
+        y: f64,
+    ) -> fdo::Result<()> {
+        let stream = self.stream_target(stream)?;
+        self.send_input(RemoteDesktopInput::TouchMotion { stream, slot, x, y })
+    }
+
+    async fn notify_touch_up(&self, slot: u32) -> fdo::Result<()> {
+        self.send_input(RemoteDesktopInput::TouchUp { slot })
+    }
+
+    #[zbus(signal)]
+    async fn closed(ctxt: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+impl RemoteDesktop {
+    pub fn new(
+        to_niri: calloop::channel::Sender<RemoteDesktopToNiri>,
+        sessions: RemoteDesktopSessions,
+    ) -> Self {
+        Self { to_niri, sessions }
+    }
+}
+
+impl Start for RemoteDesktop {
+    fn start(self) -> anyhow::Result<zbus::blocking::Connection> {
+        let conn = zbus::blocking::Connection::session()?;
+        let flags = RequestNameFlags::AllowReplacement
+            | RequestNameFlags::ReplaceExisting
+            | RequestNameFlags::DoNotQueue;
+
+        conn.object_server()
+            .at("/org/gnome/Mutter/RemoteDesktop", self)?;
+        conn.request_name_with_flags("org.gnome.Mutter.RemoteDesktop", flags)?;
+
+        Ok(conn)
+    }
+}
+
+impl Session {
+    pub fn new(id: usize, to_niri: calloop::channel::Sender<RemoteDesktopToNiri>) -> Self {
+        Self {
+            id,
+            to_niri: to_niri.clone(),
+            screen_cast: Arc::new(Mutex::new(None)),
+            started: Arc::new(AtomicBool::new(false)),
+            stopped: Arc::new(AtomicBool::new(false)),
+            _teardown: Arc::new(SessionTeardown { id, to_niri }),
+        }
+    }
+
+    pub fn has_session_id(&self, session_id: &str) -> bool {
+        self.id.to_string() == session_id
+    }
+
+    /// Pairs a screencast session with this remote desktop session.
+    ///
+    /// The screencast session is then started and stopped together with this session.
+    pub fn set_screen_cast(
+        &self,
+        session: mutter_screen_cast::Session,
+        iface: InterfaceRef<mutter_screen_cast::Session>,
+    ) -> fdo::Result<()> {
+        if self.started.load(Ordering::SeqCst) || self.stopped.load(Ordering::SeqCst) {
+            return Err(fdo::Error::Failed(
+                "remote desktop session is already started".to_owned(),
+            ));
+        }
+
+        let mut screen_cast = self.screen_cast.lock().unwrap();
+        if screen_cast.is_some() {
+            return Err(fdo::Error::Failed(
+                "remote desktop session already has a screencast session".to_owned(),
+            ));
+        }
+
+        *screen_cast = Some((session, iface));
+        Ok(())
+    }
+
+    fn stream_target(&self, path: &str) -> fdo::Result<StreamTargetId> {
+        let screen_cast = self.screen_cast.lock().unwrap();
+        screen_cast
+            .as_ref()
+            .and_then(|(session, _)| session.stream_target(path))
+            .ok_or_else(|| fdo::Error::InvalidArgs("unknown stream".to_owned()))
+    }
+
+    fn send_input(&self, event: RemoteDesktopInput) -> fdo::Result<()> {
+        if !self.started.load(Ordering::SeqCst) || self.stopped.load(Ordering::SeqCst) {
+            return Err(fdo::Error::Failed("session is not running".to_owned()));
+        }
+
+        let msg = RemoteDesktopToNiri::Input {
+            session_id: self.id,
+            event,
+        };
+        if let Err(err) = self.to_niri.send(msg) {
+            warn!("error sending input to niri: {err:?}");
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SessionTeardown {
+    fn drop(&mut self) {
+        let _ = self.to_niri.send(RemoteDesktopToNiri::StopSession {
+            session_id: self.id,
+            screen_cast_session_id: None,
+        });
+    }
+}
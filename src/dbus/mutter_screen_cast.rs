@@ -4,11 +4,14 @@ use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
 use serde::Deserialize;
+use smithay::utils::{Logical, Rectangle};
 use zbus::fdo::RequestNameFlags;
+use zbus::message::Header;
 use zbus::object_server::{InterfaceRef, SignalEmitter};
 use zbus::zvariant::{DeserializeDict, OwnedObjectPath, SerializeDict, Type, Value};
 use zbus::{fdo, interface, ObjectServer};
 
+use super::mutter_remote_desktop::RemoteDesktopSessions;
 use super::Start;
 use crate::backend::IpcOutputMap;
 
@@ -18,6 +21,7 @@ pub struct ScreenCast {
     to_niri: calloop::channel::Sender<ScreenCastToNiri>,
     #[allow(clippy::type_complexity)]
     sessions: Arc<Mutex<Vec<(Session, InterfaceRef<Session>)>>>,
+    remote_desktop_sessions: RemoteDesktopSessions,
 }
 
 #[derive(Clone)]
@@ -28,6 +32,22 @@ pub struct Session {
     #[allow(clippy::type_complexity)]
     streams: Arc<Mutex<Vec<(Stream, InterfaceRef<Stream>)>>>,
     stopped: Arc<AtomicBool>,
+    /// Whether this session is started by a remote desktop session.
+    remote_desktop: bool,
+    client: CastClient,
+}
+
+/// Client that created a screencast session.
+#[derive(Debug, Clone, Default)]
+pub struct CastClient {
+    /// Unique bus name of the client.
+    ///
+    /// This is usually xdg-desktop-portal-gnome rather than the application itself.
+    pub sender: Option<String>,
+    /// Process ID of the client, if the bus could tell it.
+    pub pid: Option<i32>,
+    /// App ID of the client, resolved from its windows by niri.
+    pub app_id: Option<String>,
 }
 
 #[derive(Debug, Default, Deserialize, Type, Clone, Copy)]
@@ -58,6 +78,15 @@ struct RecordWindowProperties {
     _is_recording: Option<bool>,
 }
 
+#[derive(Debug, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+struct RecordAreaProperties {
+    #[zvariant(rename = "cursor-mode")]
+    cursor_mode: Option<CursorMode>,
+    #[zvariant(rename = "is-recording")]
+    _is_recording: Option<bool>,
+}
+
 static STREAM_ID: AtomicUsize = AtomicUsize::new(0);
 
 #[derive(Clone)]
@@ -66,6 +95,7 @@ pub struct Stream {
     session_id: usize,
     target: StreamTarget,
     cursor_mode: CursorMode,
+    client: CastClient,
     was_started: Arc<AtomicBool>,
     to_niri: calloop::channel::Sender<ScreenCastToNiri>,
 }
@@ -74,13 +104,28 @@ pub struct Stream {
 enum StreamTarget {
     // FIXME: update on scale changes and whatnot.
     Output(niri_ipc::Output),
-    Window { id: u64 },
+    Window {
+        id: u64,
+    },
+    Area {
+        output: niri_ipc::Output,
+        /// Area in output-local logical coordinates.
+        rect: Rectangle<i32, Logical>,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub enum StreamTargetId {
-    Output { name: String },
-    Window { id: u64 },
+    Output {
+        name: String,
+    },
+    Window {
+        id: u64,
+    },
+    Area {
+        output: String,
+        rect: Rectangle<i32, Logical>,
+    },
 }
 
 #[derive(Debug, SerializeDict, Type, Value)]
@@ -98,6 +143,7 @@ pub enum ScreenCastToNiri {
         stream_id: usize,
         target: StreamTargetId,
         cursor_mode: CursorMode,
+        client: CastClient,
         signal_ctx: SignalEmitter<'static>,
     },
     StopCast {
@@ -110,23 +156,51 @@ impl ScreenCast {
     async fn create_session(
         &self,
         #[zbus(object_server)] server: &ObjectServer,
+        #[zbus(connection)] conn: &zbus::Connection,
+        #[zbus(header)] hdr: Header<'_>,
         properties: HashMap<&str, Value<'_>>,
     ) -> fdo::Result<OwnedObjectPath> {
-        if properties.contains_key("remote-desktop-session-id") {
-            return Err(fdo::Error::Failed(
-                "there are no remote desktop sessions".to_owned(),
-            ));
-        }
+        let remote_desktop_session = match properties.get("remote-desktop-session-id") {
+            Some(Value::Str(id)) => {
+                let sessions = self.remote_desktop_sessions.lock().unwrap();
+                let session = sessions
+                    .iter()
+                    .find(|(session, _)| session.has_session_id(id.as_str()))
+                    .map(|(session, _)| session.clone());
+                let Some(session) = session else {
+                    return Err(fdo::Error::Failed(
+                        "no such remote desktop session".to_owned(),
+                    ));
+                };
+                Some(session)
+            }
+            Some(_) => {
+                return Err(fdo::Error::InvalidArgs(
+                    "remote-desktop-session-id must be a string".to_owned(),
+                ))
+            }
+            None => None,
+        };
 
         static NUMBER: AtomicUsize = AtomicUsize::new(0);
         let session_id = NUMBER.fetch_add(1, Ordering::SeqCst);
         let path = format!("/org/gnome/Mutter/ScreenCast/Session/u{session_id}");
         let path = OwnedObjectPath::try_from(path).unwrap();
 
-        let session = Session::new(session_id, self.ipc_outputs.clone(), self.to_niri.clone());
+        let mut session = Session::new(session_id, self.ipc_outputs.clone(), self.to_niri.clone());
+        session.remote_desktop = remote_desktop_session.is_some();
+        session.client = cast_client(conn, &hdr).await;
         match server.at(&path, session.clone()).await {
             Ok(true) => {
                 let iface = server.interface(&path).await.unwrap();
+                if let Some(remote_desktop_session) = remote_desktop_session {
+                    let res =
+                        remote_desktop_session.set_screen_cast(session.clone(), iface.clone());
+                    if let Err(err) = res {
+                        server.remove::<Session, _>(&path).await.unwrap();
+                        return Err(err);
+                    }
+                }
                 self.sessions.lock().unwrap().push((session, iface));
             }
             Ok(false) => return Err(fdo::Error::Failed("session path already exists".to_owned())),
@@ -148,12 +222,17 @@ impl ScreenCast {
 
 #[interface(name = "org.gnome.Mutter.ScreenCast.Session")]
 impl Session {
-    async fn start(&self) {
+    async fn start(&self) -> fdo::Result<()> {
         debug!("start");
 
-        for (stream, iface) in &*self.streams.lock().unwrap() {
-            stream.start(iface.signal_emitter().clone());
+        if self.remote_desktop {
+            return Err(fdo::Error::Failed(
+                "session must be started through its remote desktop session".to_owned(),
+            ));
         }
+
+        self.start_streams();
+        Ok(())
     }
 
     pub async fn stop(
@@ -219,6 +298,7 @@ impl Session {
             self.id,
             target,
             cursor_mode,
+            self.client.clone(),
             self.to_niri.clone(),
         );
         match server.at(&path, stream.clone()).await {
@@ -258,6 +338,80 @@ impl Session {
             self.id,
             target,
             cursor_mode,
+            self.client.clone(),
+            self.to_niri.clone(),
+        );
+        match server.at(&path, stream.clone()).await {
+            Ok(true) => {
+                let iface = server.interface(&path).await.unwrap();
+                self.streams.lock().unwrap().push((stream, iface));
+            }
+            Ok(false) => return Err(fdo::Error::Failed("stream path already exists".to_owned())),
+            Err(err) => {
+                return Err(fdo::Error::Failed(format!(
+                    "error creating stream object: {err:?}"
+                )))
+            }
+        }
+
+        Ok(path)
+    }
+
+    async fn record_area(
+        &mut self,
+        #[zbus(object_server)] server: &ObjectServer,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        properties: RecordAreaProperties,
+    ) -> fdo::Result<OwnedObjectPath> {
+        debug!(x, y, width, height, ?properties, "record_area");
+
+        if width <= 0 || height <= 0 {
+            return Err(fdo::Error::InvalidArgs("area is empty".to_owned()));
+        }
+
+        // The area must be fully contained within a single monitor.
+        let area = Rectangle::new((x, y).into(), (width, height).into());
+        let output = {
+            let ipc_outputs = self.ipc_outputs.lock().unwrap();
+            ipc_outputs
+                .values()
+                .find(|o| {
+                    o.logical.as_ref().is_some_and(|logical| {
+                        let rect = Rectangle::<i32, Logical>::new(
+                            (logical.x, logical.y).into(),
+                            (logical.width as i32, logical.height as i32).into(),
+                        );
+                        rect.contains_rect(area)
+                    })
+                })
+                .cloned()
+        };
+        let Some(output) = output else {
+            return Err(fdo::Error::Failed(
+                "area is not contained within a single monitor".to_owned(),
+            ));
+        };
+
+        let logical = output.logical.as_ref().unwrap();
+        let mut rect = area;
+        rect.loc -= (logical.x, logical.y).into();
+
+        let stream_id = STREAM_ID.fetch_add(1, Ordering::SeqCst);
+        let path = format!("/org/gnome/Mutter/ScreenCast/Stream/u{stream_id}");
+        let path = OwnedObjectPath::try_from(path).unwrap();
+
+        let cursor_mode = properties.cursor_mode.unwrap_or_default();
+
+        let target = StreamTarget::Area { output, rect };
+        let stream = Stream::new(
+            stream_id,
+            self.id,
+            target,
+            cursor_mode,
+            self.client.clone(),
             self.to_niri.clone(),
         );
         match server.at(&path, stream.clone()).await {
@@ -303,6 +457,13 @@ impl Stream {
                     size: (1, 1),
                 }
             }
+            StreamTarget::Area { output, rect } => {
+                let logical = output.logical.as_ref().unwrap();
+                StreamParameters {
+                    position: (logical.x + rect.loc.x, logical.y + rect.loc.y),
+                    size: (rect.size.w, rect.size.h),
+                }
+            }
         }
     }
 }
@@ -311,15 +472,47 @@ impl ScreenCast {
     pub fn new(
         ipc_outputs: Arc<Mutex<IpcOutputMap>>,
         to_niri: calloop::channel::Sender<ScreenCastToNiri>,
+        remote_desktop_sessions: RemoteDesktopSessions,
     ) -> Self {
         Self {
             ipc_outputs,
             to_niri,
             sessions: Arc::new(Mutex::new(vec![])),
+            remote_desktop_sessions,
         }
     }
 }
 
+/// Looks up the client that sent the message.
+async fn cast_client(conn: &zbus::Connection, hdr: &Header<'_>) -> CastClient {
+    let Some(sender) = hdr.sender() else {
+        return CastClient::default();
+    };
+
+    let pid = match fdo::DBusProxy::new(conn).await {
+        Ok(proxy) => match proxy
+            .get_connection_unix_process_id(sender.clone().into())
+            .await
+        {
+            Ok(pid) => i32::try_from(pid).ok(),
+            Err(err) => {
+                debug!("error getting the pid of {sender}: {err:?}");
+                None
+            }
+        },
+        Err(err) => {
+            debug!("error creating a DBusProxy: {err:?}");
+            None
+        }
+    };
+
+    CastClient {
+        sender: Some(sender.to_string()),
+        pid,
+        app_id: None,
+    }
+}
+
 impl Start for ScreenCast {
     fn start(self) -> anyhow::Result<zbus::blocking::Connection> {
         let conn = zbus::blocking::Connection::session()?;
@@ -347,8 +540,29 @@ impl Session {
             streams: Arc::new(Mutex::new(vec![])),
             to_niri,
             stopped: Arc::new(AtomicBool::new(false)),
+            remote_desktop: false,
+            client: CastClient::default(),
         }
     }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn start_streams(&self) {
+        for (stream, iface) in &*self.streams.lock().unwrap() {
+            stream.start(iface.signal_emitter().clone());
+        }
+    }
+
+    /// Returns the target of the stream at the given object path.
+    pub fn stream_target(&self, path: &str) -> Option<StreamTargetId> {
+        let streams = self.streams.lock().unwrap();
+        streams
+            .iter()
+            .find(|(_, iface)| iface.signal_emitter().path().as_str() == path)
+            .map(|(stream, _)| stream.target.make_id())
+    }
 }
 
 impl Drop for Session {
@@ -365,6 +579,7 @@ impl Stream {
         session_id: usize,
         target: StreamTarget,
         cursor_mode: CursorMode,
+        client: CastClient,
         to_niri: calloop::channel::Sender<ScreenCastToNiri>,
     ) -> Self {
         Self {
@@ -372,6 +587,7 @@ impl Stream {
             session_id,
             target,
             cursor_mode,
+            client,
             was_started: Arc::new(AtomicBool::new(false)),
             to_niri,
         }
@@ -387,6 +603,7 @@ impl Stream {
             stream_id: self.id,
             target: self.target.make_id(),
             cursor_mode: self.cursor_mode,
+            client: self.client.clone(),
             signal_ctx: ctxt,
         };
 
@@ -403,6 +620,10 @@ impl StreamTarget {
                 name: output.name.clone(),
             },
             StreamTarget::Window { id } => StreamTargetId::Window { id: *id },
+            StreamTarget::Area { output, rect } => StreamTargetId::Area {
+                output: output.name.clone(),
+                rect: *rect,
+            },
         }
     }
 }
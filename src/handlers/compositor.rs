@@ -22,6 +22,7 @@ use super::xdg_shell::add_mapped_toplevel_pre_commit_hook;
 use crate::handlers::XDG_ACTIVATION_TOKEN_TIMEOUT;
 use crate::layout::{ActivateWindow, AddWindowTarget};
 use crate::niri::{CastTarget, ClientState, LockState, State};
+use crate::protocols::xdg_dialog;
 use crate::utils::transaction::Transaction;
 use crate::utils::{is_mapped, send_scale_transform};
 use crate::window::{InitialConfigureState, Mapped, ResolvedWindowRules, Unmapped};
@@ -149,17 +150,24 @@ impl CompositorHandler for State {
                         }
                     });
 
+                    let is_modal =
+                        toplevel.parent().is_some() && xdg_dialog::is_modal(toplevel.wl_surface());
                     let parent = toplevel
                         .parent()
                         .and_then(|parent| self.niri.layout.find_window_and_output(&parent))
+                        // Minimized windows are not in the layout to open next to.
+                        .filter(|(mapped, _)| !mapped.is_minimized())
                         // Only consider the parent if we configured the window for the same
                         // output.
                         //
                         // Normally when we're following the parent, the configured output will be
                         // None. If the configured output is set, that means it was set explicitly
                         // by a window rule or a fullscreen request.
+                        //
+                        // Modal dialogs always open over their parent.
                         .filter(|(_, parent_output)| {
-                            parent_output.is_none()
+                            is_modal
+                                || parent_output.is_none()
                                 || output.is_none()
                                 || output.as_ref() == *parent_output
                         })
@@ -194,6 +202,14 @@ impl CompositorHandler for State {
                     if let Some(output) = output.cloned() {
                         self.niri.layout.start_open_animation_for_window(&window);
 
+                        // If the window is attached to an ongoing drag, start moving it.
+                        if let Some(drag) = &self.niri.toplevel_drag {
+                            if drag.surface.as_ref() == Some(surface) {
+                                let pointer = self.niri.seat.get_pointer().unwrap();
+                                self.toplevel_drag_motion(pointer.current_location());
+                            }
+                        }
+
                         let new_focus = self.niri.layout.focus().map(|m| &m.window);
                         if new_focus == Some(&window) {
                             // We activated the newly opened window.
@@ -293,7 +309,21 @@ impl CompositorHandler for State {
                 }
 
                 // The toplevel remains mapped.
+                let icon_changed = self
+                    .niri
+                    .layout
+                    .find_window_and_output_mut(surface)
+                    .is_some_and(|(mapped, _)| mapped.update_icon());
                 self.niri.layout.update_window(&window, serial);
+                if let Some(output) = self.niri.window_switcher.window_committed(&window) {
+                    let output = output.clone();
+                    self.niri.queue_redraw(&output);
+                }
+
+                // The icon name might stay the same, so the regular IPC refresh can miss it.
+                if icon_changed {
+                    self.ipc_window_changed(&window);
+                }
 
                 // Move the toplevel according to the attach offset.
                 if let Some(delta) = buffer_delta {
@@ -327,6 +357,10 @@ impl CompositorHandler for State {
             let output = output.cloned();
             window.on_commit();
             self.niri.layout.update_window(&window, None);
+            if let Some(output) = self.niri.window_switcher.window_committed(&window) {
+                let output = output.clone();
+                self.niri.queue_redraw(&output);
+            }
             if let Some(output) = output {
                 self.niri.queue_redraw(&output);
             }
@@ -78,7 +78,8 @@ use smithay::{
 pub use crate::handlers::xdg_shell::KdeDecorationsModeState;
 use crate::layout::workspace::WorkspaceId;
 use crate::layout::ActivateWindow;
-use crate::niri::{DndIcon, NewClient, State};
+use crate::niri::{DndIcon, NewClient, State, ToplevelDrag};
+use crate::protocols::color_management::{ColorManagementHandler, ColorManagementState};
 use crate::protocols::ext_workspace::{self, ExtWorkspaceHandler, ExtWorkspaceManagerState};
 use crate::protocols::foreign_toplevel::{
     self, ForeignToplevelHandler, ForeignToplevelManagerState,
@@ -92,14 +93,23 @@ use crate::protocols::virtual_pointer::{
     VirtualPointerInputBackend, VirtualPointerManagerState, VirtualPointerMotionAbsoluteEvent,
     VirtualPointerMotionEvent,
 };
-use crate::utils::{output_size, send_scale_transform, with_toplevel_role};
+use crate::protocols::xdg_dialog::{XdgDialogHandler, XdgDialogState};
+use crate::protocols::xdg_system_bell::{XdgSystemBellHandler, XdgSystemBellState};
+use crate::protocols::xdg_toplevel_drag::{XdgToplevelDragHandler, XdgToplevelDragManagerState};
+use crate::protocols::xdg_toplevel_icon::{XdgToplevelIconHandler, XdgToplevelIconManagerState};
+use crate::render_helpers::color::ColorDescription;
+use crate::utils::spawning::spawn;
+use crate::utils::{get_monotonic_time, output_size, send_scale_transform, with_toplevel_role};
 use crate::{
-    delegate_ext_workspace, delegate_foreign_toplevel, delegate_gamma_control,
-    delegate_mutter_x11_interop, delegate_output_management, delegate_screencopy,
-    delegate_virtual_pointer,
+    delegate_color_management, delegate_ext_workspace, delegate_foreign_toplevel,
+    delegate_gamma_control, delegate_mutter_x11_interop, delegate_output_management,
+    delegate_screencopy, delegate_virtual_pointer, delegate_xdg_dialog, delegate_xdg_system_bell,
+    delegate_xdg_toplevel_drag, delegate_xdg_toplevel_icon,
 };
 
 pub const XDG_ACTIVATION_TOKEN_TIMEOUT: Duration = Duration::from_secs(10);
+/// Minimum interval between two runs of the bell `spawn` command.
+const BELL_SPAWN_INTERVAL: Duration = Duration::from_millis(500);
 
 impl SeatHandler for State {
     type KeyboardFocus = WlSurface;
@@ -320,7 +330,7 @@ impl DataDeviceHandler for State {
 impl ClientDndGrabHandler for State {
     fn started(
         &mut self,
-        _source: Option<WlDataSource>,
+        source: Option<WlDataSource>,
         icon: Option<WlSurface>,
         _seat: Seat<Self>,
     ) {
@@ -341,6 +351,34 @@ impl ClientDndGrabHandler for State {
             (0, 0).into()
         };
         self.niri.dnd_icon = icon.map(|surface| DndIcon { surface, offset });
+
+        // Check if the client wants to move a toplevel along with the drag. The move itself will
+        // start on the next motion, since we cannot get the pointer location in here.
+        self.niri.toplevel_drag = source
+            .filter(|source| {
+                self.niri
+                    .xdg_toplevel_drag_manager_state
+                    .is_toplevel_drag(source)
+            })
+            .map(|source| {
+                let manager_state = &mut self.niri.xdg_toplevel_drag_manager_state;
+                manager_state.set_drag_ongoing(&source, true);
+                let attached = manager_state.attached_toplevel(&source);
+                let (surface, offset) = attached
+                    .and_then(|(toplevel, offset)| {
+                        let toplevel = self.niri.xdg_shell_state.get_toplevel(&toplevel)?;
+                        Some((Some(toplevel.wl_surface().clone()), offset))
+                    })
+                    .unwrap_or_default();
+                ToplevelDrag {
+                    source,
+                    surface,
+                    offset,
+                    last_location: None,
+                    is_moving: false,
+                }
+            });
+
         // FIXME: more granular
         self.niri.queue_redraw_all();
     }
@@ -348,6 +386,14 @@ impl ClientDndGrabHandler for State {
     fn dropped(&mut self, target: Option<WlSurface>, validated: bool, _seat: Seat<Self>) {
         trace!("client dropped, target: {target:?}, validated: {validated}");
 
+        // Drop the attached toplevel into the layout.
+        self.toplevel_drag_end_move();
+        if let Some(drag) = self.niri.toplevel_drag.take() {
+            self.niri
+                .xdg_toplevel_drag_manager_state
+                .set_drag_ongoing(&drag.source, false);
+        }
+
         // End DnD before activating a specific window below so that it takes precedence.
         self.niri.layout.dnd_end();
 
@@ -389,6 +435,73 @@ impl ClientDndGrabHandler for State {
 
 impl ServerDndGrabHandler for State {}
 
+impl State {
+    /// Moves the toplevel attached to the ongoing drag along with the pointer.
+    pub fn toplevel_drag_motion(&mut self, pos: Point<f64, Logical>) {
+        let output_under = self
+            .niri
+            .output_under(pos)
+            .map(|(output, pos_within_output)| (output.clone(), pos_within_output));
+
+        let Some(drag) = &mut self.niri.toplevel_drag else {
+            return;
+        };
+        let last_location = drag.last_location.replace(pos);
+
+        let Some(surface) = &drag.surface else {
+            return;
+        };
+        let Some((mapped, _)) = self.niri.layout.find_window_and_output(surface) else {
+            if drag.is_moving {
+                // The toplevel got unmapped, which detaches it from the drag.
+                drag.surface = None;
+                drag.is_moving = false;
+            }
+            return;
+        };
+        let window = mapped.window.clone();
+
+        let Some((output, pos_within_output)) = output_under else {
+            return;
+        };
+
+        if !drag.is_moving {
+            drag.is_moving = self.niri.layout.interactive_move_begin_at_offset(
+                window.clone(),
+                &output,
+                pos_within_output,
+                drag.offset.to_f64(),
+            );
+        }
+
+        if drag.is_moving {
+            let delta = last_location.map_or_else(Point::default, |last| pos - last);
+            self.niri
+                .layout
+                .interactive_move_update(&window, delta, output, pos_within_output);
+        }
+    }
+
+    /// Ends moving the toplevel attached to the ongoing drag, dropping it into the layout.
+    fn toplevel_drag_end_move(&mut self) {
+        let Some(drag) = &mut self.niri.toplevel_drag else {
+            return;
+        };
+        if !drag.is_moving {
+            return;
+        }
+        drag.is_moving = false;
+
+        let Some(surface) = &drag.surface else {
+            return;
+        };
+        if let Some((mapped, _)) = self.niri.layout.find_window_and_output(surface) {
+            let window = mapped.window.clone();
+            self.niri.layout.interactive_move_end(&window);
+        }
+    }
+}
+
 delegate_data_device!(State);
 
 impl PrimarySelectionHandler for State {
@@ -543,6 +656,11 @@ impl ForeignToplevelHandler for State {
     fn set_fullscreen(&mut self, wl_surface: WlSurface, wl_output: Option<WlOutput>) {
         if let Some((mapped, current_output)) = self.niri.layout.find_window_and_output(&wl_surface)
         {
+            // Minimized windows need to be restored first.
+            if mapped.is_minimized() {
+                return;
+            }
+
             let has_fullscreen_cap = with_toplevel_role(mapped.toplevel(), |role| {
                 role.current
                     .capabilities
@@ -575,6 +693,25 @@ impl ForeignToplevelHandler for State {
             self.niri.layout.set_fullscreen(&window, false);
         }
     }
+
+    fn set_minimized(&mut self, wl_surface: WlSurface) {
+        if let Some((mapped, _)) = self.niri.layout.find_window_and_output(&wl_surface) {
+            let window = mapped.window.clone();
+            self.niri.layout.minimize_window(Some(&window));
+            self.niri.queue_redraw_all();
+        }
+    }
+
+    fn unset_minimized(&mut self, wl_surface: WlSurface) {
+        if let Some((mapped, _)) = self.niri.layout.find_window_and_output(&wl_surface) {
+            if mapped.is_minimized() {
+                let window = mapped.window.clone();
+                self.niri.layout.restore_minimized(Some(&window));
+                self.niri.layer_shell_on_demand_focus = None;
+                self.niri.queue_redraw_all();
+            }
+        }
+    }
 }
 delegate_foreign_toplevel!(State);
 
@@ -736,6 +873,15 @@ impl GammaControlHandler for State {
     }
 
     fn set_gamma(&mut self, output: &Output, ramp: Option<Vec<u16>>) -> Option<()> {
+        // The client overrides the night light gamma, and resetting the gamma lets the night
+        // light apply again on the next redraw.
+        if let Some(state) = self.niri.output_state.get_mut(output) {
+            state.night_light_temperature = None;
+            if ramp.is_none() {
+                self.niri.queue_redraw(output);
+            }
+        }
+
         match self.backend.tty().set_gamma(output, ramp) {
             Ok(()) => Some(()),
             Err(err) => {
@@ -747,6 +893,22 @@ impl GammaControlHandler for State {
 }
 delegate_gamma_control!(State);
 
+impl ColorManagementHandler for State {
+    fn color_management_state(&mut self) -> &mut ColorManagementState {
+        &mut self.niri.color_management_state
+    }
+
+    fn output_description(&mut self, output: &Output) -> Option<ColorDescription> {
+        let state = self.niri.output_state.get(output)?;
+        Some(state.color_description)
+    }
+
+    fn preferred_description(&mut self, surface: &WlSurface) -> ColorDescription {
+        self.niri.preferred_color_description(surface)
+    }
+}
+delegate_color_management!(State);
+
 struct UrgentOnlyMarker;
 
 impl XdgActivationHandler for State {
@@ -839,4 +1001,163 @@ delegate_output_management!(State);
 impl MutterX11InteropHandler for State {}
 delegate_mutter_x11_interop!(State);
 
+impl XdgToplevelIconHandler for State {
+    fn xdg_toplevel_icon_manager_state(&mut self) -> &mut XdgToplevelIconManagerState {
+        &mut self.niri.xdg_toplevel_icon_manager_state
+    }
+
+    fn toplevel_surface(&mut self, toplevel: &xdg_toplevel::XdgToplevel) -> Option<WlSurface> {
+        let toplevel = self.niri.xdg_shell_state.get_toplevel(toplevel)?;
+        Some(toplevel.wl_surface().clone())
+    }
+}
+delegate_xdg_toplevel_icon!(State);
+
+impl XdgSystemBellHandler for State {
+    fn xdg_system_bell_state(&mut self) -> &mut XdgSystemBellState {
+        &mut self.niri.xdg_system_bell_state
+    }
+
+    fn ring(&mut self, surface: Option<WlSurface>) {
+        let mut root_surface = surface;
+        while let Some(parent) = root_surface.as_ref().and_then(get_parent) {
+            root_surface = Some(parent);
+        }
+
+        let bell = self.niri.config.borrow().bell.clone();
+
+        let mut window_id = None;
+        let mut window = None;
+        let mut output = None;
+        if let Some(surface) = &root_surface {
+            if let Some((mapped, mon_output)) = self.niri.layout.find_window_and_output_mut(surface)
+            {
+                window_id = Some(mapped.id().get());
+                window = Some(mapped.window.clone());
+                output = mon_output.cloned();
+
+                // Focused windows will ignore this.
+                if !bell.off && !bell.skip_urgent {
+                    mapped.set_urgent(true);
+                }
+            }
+        }
+
+        // Send the event regardless of the config so that scripts can handle the bell themselves.
+        self.ipc_bell(window_id);
+
+        if bell.off {
+            return;
+        }
+
+        if bell.flash_window {
+            if let (Some(window), Some(output)) = (window, &output) {
+                self.niri.flash_output(output, Some(window));
+            }
+        } else if bell.flash {
+            let output = output.or_else(|| self.niri.layout.active_output().cloned());
+            if let Some(output) = output {
+                self.niri.flash_output(&output, None);
+            }
+        }
+
+        if let Some(spawn_config) = bell.spawn {
+            // Don't let a client ringing the bell in a loop fork processes without bound.
+            let now = get_monotonic_time();
+            let last = self.niri.bell_spawn_time;
+            if last.map_or(true, |last| now.saturating_sub(last) >= BELL_SPAWN_INTERVAL) {
+                self.niri.bell_spawn_time = Some(now);
+                #[cfg(test)]
+                {
+                    self.niri.bell_spawn_count += 1;
+                }
+                spawn(spawn_config.command, None);
+            }
+        }
+
+        self.niri.queue_redraw_all();
+    }
+}
+delegate_xdg_system_bell!(State);
+
+impl XdgDialogHandler for State {
+    fn xdg_dialog_state(&mut self) -> &mut XdgDialogState {
+        &mut self.niri.xdg_dialog_state
+    }
+
+    fn toplevel_surface(&mut self, toplevel: &xdg_toplevel::XdgToplevel) -> Option<WlSurface> {
+        let toplevel = self.niri.xdg_shell_state.get_toplevel(toplevel)?;
+        Some(toplevel.wl_surface().clone())
+    }
+
+    fn modal_changed(&mut self, toplevel: &xdg_toplevel::XdgToplevel) {
+        let Some(toplevel) = self.niri.xdg_shell_state.get_toplevel(toplevel) else {
+            return;
+        };
+
+        self.update_window_rules(&toplevel);
+
+        // The dialog may have started or stopped blocking its parent.
+        if let Some((mapped, _)) = self
+            .niri
+            .layout
+            .find_window_and_output(toplevel.wl_surface())
+        {
+            let window = mapped.window.clone();
+            self.niri.layout.update_modal(&window);
+        }
+        self.niri.queue_redraw_all();
+    }
+}
+delegate_xdg_dialog!(State);
+
+impl XdgToplevelDragHandler for State {
+    fn xdg_toplevel_drag_manager_state(&mut self) -> &mut XdgToplevelDragManagerState {
+        &mut self.niri.xdg_toplevel_drag_manager_state
+    }
+
+    fn toplevel_attached(
+        &mut self,
+        source: &WlDataSource,
+        toplevel: &xdg_toplevel::XdgToplevel,
+        offset: Point<i32, Logical>,
+    ) {
+        // If the drag hasn't started yet, the attached toplevel will be picked up when it does.
+        if self
+            .niri
+            .toplevel_drag
+            .as_ref()
+            .map_or(true, |drag| drag.source != *source)
+        {
+            return;
+        }
+
+        let Some(toplevel) = self.niri.xdg_shell_state.get_toplevel(toplevel) else {
+            return;
+        };
+
+        // Drop the previously attached toplevel, if any.
+        self.toplevel_drag_end_move();
+
+        let drag = self.niri.toplevel_drag.as_mut().unwrap();
+        drag.surface = Some(toplevel.wl_surface().clone());
+        drag.offset = offset;
+
+        // If the toplevel is already mapped, start moving it right away.
+        let pos = self.niri.seat.get_pointer().unwrap().current_location();
+        self.toplevel_drag_motion(pos);
+    }
+
+    fn is_toplevel_mapped(&mut self, toplevel: &xdg_toplevel::XdgToplevel) -> bool {
+        let Some(toplevel) = self.niri.xdg_shell_state.get_toplevel(toplevel) else {
+            return false;
+        };
+        self.niri
+            .layout
+            .find_window_and_output(toplevel.wl_surface())
+            .is_some()
+    }
+}
+delegate_xdg_toplevel_drag!(State);
+
 delegate_single_pixel_buffer!(State);
@@ -438,6 +438,18 @@ impl XdgShellHandler for State {
         // FIXME
     }
 
+    fn minimize_request(&mut self, surface: ToplevelSurface) {
+        if let Some((mapped, _)) = self
+            .niri
+            .layout
+            .find_window_and_output(surface.wl_surface())
+        {
+            let window = mapped.window.clone();
+            self.niri.layout.minimize_window(Some(&window));
+            self.niri.queue_redraw_all();
+        }
+    }
+
     fn fullscreen_request(
         &mut self,
         toplevel: ToplevelSurface,
@@ -454,6 +466,11 @@ impl XdgShellHandler for State {
             // changes.
             mapped.set_needs_configure();
 
+            // Minimized windows need to be restored first.
+            if mapped.is_minimized() {
+                return;
+            }
+
             let window = mapped.window.clone();
 
             if let Some(requested_output) = requested_output {
@@ -715,6 +732,18 @@ impl XdgShellHandler for State {
     }
 
     fn parent_changed(&mut self, toplevel: ToplevelSurface) {
+        // The is-modal matcher depends on the window having a parent.
+        self.update_window_rules(&toplevel);
+
+        if let Some((mapped, _)) = self
+            .niri
+            .layout
+            .find_window_and_output(toplevel.wl_surface())
+        {
+            let window = mapped.window.clone();
+            self.niri.layout.update_modal(&window);
+        }
+
         let Some(parent) = toplevel.parent() else {
             return;
         };
@@ -0,0 +1,290 @@
+//! Built-in idle handling.
+//!
+//! Tracks how long the user has been idle for the `idle` config section and for the
+//! org.gnome.Mutter.IdleMonitor D-Bus interface. While idle is inhibited (by the
+//! org.freedesktop.ScreenSaver interface or by idle-inhibit surfaces), the idle time stays at
+//! zero.
+
+use std::time::Duration;
+
+pub struct IdleTracker {
+    /// Monotonic time of the last user activity.
+    last_activity: Duration,
+    is_inhibited: bool,
+    watches: Vec<Watch>,
+    /// Id of the next D-Bus watch.
+    next_id: u32,
+}
+
+struct Watch {
+    owner: WatchOwner,
+    /// How long the user must be idle for the watch to fire, or `None` for watches that fire on
+    /// the next user activity.
+    interval: Option<Duration>,
+    /// Whether the idle watch already fired since the last user activity.
+    fired: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchOwner {
+    /// Timeout from the config, by its index.
+    Config(usize),
+    /// Watch added over D-Bus.
+    DBus {
+        id: u32,
+        /// Unique bus name of the client that added the watch.
+        sender: String,
+    },
+}
+
+impl IdleTracker {
+    pub fn new(now: Duration) -> Self {
+        Self {
+            last_activity: now,
+            is_inhibited: false,
+            watches: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    pub fn idle_time(&self, now: Duration) -> Duration {
+        if self.is_inhibited {
+            return Duration::ZERO;
+        }
+
+        now.saturating_sub(self.last_activity)
+    }
+
+    /// Records user activity, returning the user-active watches that fired.
+    ///
+    /// User-active watches are one-shot, so they are removed.
+    pub fn notify_activity(&mut self, now: Duration) -> Vec<WatchOwner> {
+        self.last_activity = now;
+
+        let mut fired = Vec::new();
+        self.watches.retain_mut(|watch| {
+            if watch.interval.is_none() {
+                fired.push(watch.owner.clone());
+                return false;
+            }
+
+            watch.fired = false;
+            true
+        });
+        fired
+    }
+
+    /// Sets whether idle is inhibited, returning whether it changed.
+    pub fn set_inhibited(&mut self, is_inhibited: bool, now: Duration) -> bool {
+        if self.is_inhibited == is_inhibited {
+            return false;
+        }
+
+        self.is_inhibited = is_inhibited;
+
+        // Start counting the idle time from the end of the inhibition.
+        if !is_inhibited {
+            self.last_activity = now;
+        }
+
+        true
+    }
+
+    /// Replaces the watches for the config timeouts.
+    pub fn set_config_timeouts(&mut self, timeouts: impl IntoIterator<Item = Duration>) {
+        self.watches
+            .retain(|watch| !matches!(watch.owner, WatchOwner::Config(_)));
+
+        for (idx, interval) in timeouts.into_iter().enumerate() {
+            self.watches.push(Watch {
+                owner: WatchOwner::Config(idx),
+                interval: Some(interval),
+                fired: false,
+            });
+        }
+    }
+
+    pub fn add_idle_watch(&mut self, interval: Duration, sender: String) -> u32 {
+        self.add_dbus_watch(Some(interval), sender)
+    }
+
+    pub fn add_user_active_watch(&mut self, sender: String) -> u32 {
+        self.add_dbus_watch(None, sender)
+    }
+
+    fn add_dbus_watch(&mut self, interval: Option<Duration>, sender: String) -> u32 {
+        let id = self.next_id;
+        // Zero is not a valid watch id.
+        self.next_id = self.next_id.checked_add(1).unwrap_or(1);
+
+        self.watches.push(Watch {
+            owner: WatchOwner::DBus { id, sender },
+            interval,
+            fired: false,
+        });
+        id
+    }
+
+    /// Removes a D-Bus watch, returning whether it existed.
+    pub fn remove_watch(&mut self, id: u32, sender: &str) -> bool {
+        let len = self.watches.len();
+        self.watches.retain(|watch| match &watch.owner {
+            WatchOwner::DBus { id: i, sender: s } => !(*i == id && s == sender),
+            WatchOwner::Config(_) => true,
+        });
+        self.watches.len() != len
+    }
+
+    /// Removes all D-Bus watches of a client, for when it disappears from the bus.
+    pub fn remove_watches_of(&mut self, sender: &str) {
+        self.watches.retain(|watch| match &watch.owner {
+            WatchOwner::DBus { sender: s, .. } => s != sender,
+            WatchOwner::Config(_) => true,
+        });
+    }
+
+    /// Marks idle watches whose interval has passed as fired, and returns them.
+    pub fn fire(&mut self, now: Duration) -> Vec<WatchOwner> {
+        if self.is_inhibited {
+            return Vec::new();
+        }
+
+        let idle_time = self.idle_time(now);
+
+        let mut fired = Vec::new();
+        for watch in &mut self.watches {
+            let Some(interval) = watch.interval else {
+                continue;
+            };
+
+            if !watch.fired && interval <= idle_time {
+                watch.fired = true;
+                fired.push(watch.owner.clone());
+            }
+        }
+        fired
+    }
+
+    /// Returns how long until the next idle watch should fire.
+    pub fn next_deadline(&self, now: Duration) -> Option<Duration> {
+        if self.is_inhibited {
+            return None;
+        }
+
+        let idle_time = self.idle_time(now);
+        self.watches
+            .iter()
+            .filter(|watch| !watch.fired)
+            .filter_map(|watch| watch.interval)
+            .map(|interval| interval.saturating_sub(idle_time))
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn config_timeouts_fire_once() {
+        let mut idle = IdleTracker::new(secs(0));
+        idle.set_config_timeouts([secs(10), secs(20)]);
+
+        assert_eq!(idle.next_deadline(secs(0)), Some(secs(10)));
+        assert!(idle.fire(secs(5)).is_empty());
+
+        assert_eq!(idle.fire(secs(10)), [WatchOwner::Config(0)]);
+        assert!(idle.fire(secs(15)).is_empty());
+        assert_eq!(idle.next_deadline(secs(15)), Some(secs(5)));
+
+        assert_eq!(idle.fire(secs(25)), [WatchOwner::Config(1)]);
+        assert_eq!(idle.next_deadline(secs(25)), None);
+
+        // Activity re-arms the watches.
+        assert!(idle.notify_activity(secs(30)).is_empty());
+        assert_eq!(idle.next_deadline(secs(30)), Some(secs(10)));
+        assert_eq!(idle.fire(secs(40)), [WatchOwner::Config(0)]);
+    }
+
+    #[test]
+    fn user_active_watch_is_one_shot() {
+        let mut idle = IdleTracker::new(secs(0));
+        let id = idle.add_user_active_watch(String::from(":1.1"));
+        assert_ne!(id, 0);
+
+        // User-active watches don't fire on idle.
+        assert!(idle.fire(secs(100)).is_empty());
+        assert_eq!(idle.next_deadline(secs(100)), None);
+
+        let owner = WatchOwner::DBus {
+            id,
+            sender: String::from(":1.1"),
+        };
+        assert_eq!(idle.notify_activity(secs(101)), [owner]);
+        assert!(idle.notify_activity(secs(102)).is_empty());
+    }
+
+    #[test]
+    fn inhibit_holds_idle_time() {
+        let mut idle = IdleTracker::new(secs(0));
+        idle.set_config_timeouts([secs(10)]);
+
+        idle.set_inhibited(true, secs(5));
+        assert_eq!(idle.idle_time(secs(50)), Duration::ZERO);
+        assert!(idle.fire(secs(50)).is_empty());
+        assert_eq!(idle.next_deadline(secs(50)), None);
+
+        // The idle time starts counting from the end of the inhibition.
+        idle.set_inhibited(false, secs(60));
+        assert_eq!(idle.idle_time(secs(65)), secs(5));
+        assert!(idle.fire(secs(65)).is_empty());
+        assert_eq!(idle.fire(secs(70)), [WatchOwner::Config(0)]);
+    }
+
+    #[test]
+    fn remove_watch_checks_sender() {
+        let mut idle = IdleTracker::new(secs(0));
+        let id = idle.add_idle_watch(secs(10), String::from(":1.1"));
+
+        assert!(!idle.remove_watch(id, ":1.2"));
+        assert!(idle.remove_watch(id, ":1.1"));
+        assert!(!idle.remove_watch(id, ":1.1"));
+        assert!(idle.fire(secs(10)).is_empty());
+    }
+
+    #[test]
+    fn disappeared_client_loses_its_watches() {
+        let mut idle = IdleTracker::new(secs(0));
+        idle.set_config_timeouts([secs(10)]);
+        idle.add_idle_watch(secs(5), String::from(":1.1"));
+        idle.add_user_active_watch(String::from(":1.1"));
+        let id = idle.add_idle_watch(secs(5), String::from(":1.2"));
+
+        idle.remove_watches_of(":1.1");
+
+        let owner = WatchOwner::DBus {
+            id,
+            sender: String::from(":1.2"),
+        };
+        assert_eq!(idle.fire(secs(10)), [WatchOwner::Config(0), owner]);
+        assert!(idle.notify_activity(secs(11)).is_empty());
+    }
+
+    #[test]
+    fn config_reload_keeps_dbus_watches() {
+        let mut idle = IdleTracker::new(secs(0));
+        let id = idle.add_idle_watch(secs(5), String::from(":1.1"));
+        idle.set_config_timeouts([secs(10)]);
+        idle.set_config_timeouts([secs(20)]);
+
+        let owner = WatchOwner::DBus {
+            id,
+            sender: String::from(":1.1"),
+        };
+        assert_eq!(idle.fire(secs(30)), [owner, WatchOwner::Config(0)]);
+    }
+}
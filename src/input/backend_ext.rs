@@ -3,6 +3,8 @@ use smithay::backend::input;
 use smithay::backend::winit::WinitVirtualDevice;
 use smithay::output::Output;
 
+#[cfg(feature = "xdp-gnome-screencast")]
+use crate::input::remote_desktop::RemoteDesktopDevice;
 use crate::niri::State;
 use crate::protocols::virtual_pointer::VirtualPointer;
 
@@ -49,3 +51,10 @@ impl NiriInputDevice for VirtualPointer {
         self.output().cloned()
     }
 }
+
+#[cfg(feature = "xdp-gnome-screencast")]
+impl NiriInputDevice for RemoteDesktopDevice {
+    fn output(&self, _: &State) -> Option<Output> {
+        self.output().cloned()
+    }
+}
@@ -2,12 +2,14 @@ use std::any::Any;
 use std::cmp::min;
 use std::collections::hash_map::Entry;
 use std::collections::HashSet;
+use std::mem;
+use std::str::FromStr;
 use std::time::Duration;
 
 use calloop::timer::{TimeoutAction, Timer};
 use input::event::gesture::GestureEventCoordinates as _;
-use niri_config::{Action, Bind, Binds, Key, ModKey, Modifiers, SwitchBinds, Trigger};
-use niri_ipc::LayoutSwitchTarget;
+use niri_config::{Action, Bind, Binds, HotCorner, Key, ModKey, Modifiers, SwitchBinds, Trigger};
+use niri_ipc::{LayoutSwitchTarget, WindowHintAction, WindowSwitcherFilter, WindowSwitcherScope};
 use smithay::backend::input::{
     AbsolutePositionEvent, Axis, AxisSource, ButtonState, Device, DeviceCapability, Event,
     GestureBeginEvent, GestureEndEvent, GesturePinchUpdateEvent as _, GestureSwipeUpdateEvent as _,
@@ -17,6 +19,7 @@ use smithay::backend::input::{
     TabletToolTipState, TouchEvent,
 };
 use smithay::backend::libinput::LibinputInputBackend;
+use smithay::desktop::Window;
 use smithay::input::keyboard::{keysyms, FilterResult, Keysym, Layout, ModifiersState};
 use smithay::input::pointer::{
     AxisFrame, ButtonEvent, CursorIcon, CursorImageStatus, Focus, GestureHoldBeginEvent,
@@ -29,7 +32,7 @@ use smithay::input::touch::{
 };
 use smithay::input::SeatHandler;
 use smithay::output::Output;
-use smithay::utils::{Logical, Point, Rectangle, Size, Transform, SERIAL_COUNTER};
+use smithay::utils::{Logical, Point, Rectangle, Transform, SERIAL_COUNTER};
 use smithay::wayland::keyboard_shortcuts_inhibit::KeyboardShortcutsInhibitor;
 use smithay::wayland::pointer_constraints::{with_pointer_constraint, PointerConstraint};
 use smithay::wayland::selection::data_device::DnDGrab;
@@ -42,15 +45,19 @@ use self::resize_grab::ResizeGrab;
 use self::spatial_movement_grab::SpatialMovementGrab;
 use crate::layout::scrolling::ScrollDirection;
 use crate::layout::{ActivateWindow, LayoutElement as _};
-use crate::niri::{CastTarget, PointerVisibility, State};
-use crate::ui::screenshot_ui::ScreenshotUi;
+use crate::niri::{CastTarget, HotCornerState, KeyboardFocus, PointerVisibility, State};
+use crate::ui::screenshot_ui::{ScreenshotUi, SelectionPurpose};
+use crate::ui::window_switcher;
 use crate::utils::spawning::spawn;
 use crate::utils::{center, get_monotonic_time, ResizeEdge};
+use crate::window::Mapped;
 
 pub mod backend_ext;
 pub mod move_grab;
 pub mod pick_color_grab;
 pub mod pick_window_grab;
+#[cfg(feature = "xdp-gnome-screencast")]
+pub mod remote_desktop;
 pub mod resize_grab;
 pub mod scroll_swipe_gesture;
 pub mod scroll_tracker;
@@ -351,6 +358,10 @@ impl State {
             if let Some(token) = self.niri.bind_repeat_timer.take() {
                 self.niri.event_loop.remove(token);
             }
+
+            if let Some(ids) = self.niri.pressed_global_shortcuts.remove(&event.key_code()) {
+                self.niri.notify_global_shortcuts(&ids, false, time);
+            }
         }
 
         if pressed {
@@ -359,7 +370,10 @@ impl State {
 
         let is_inhibiting_shortcuts = self.is_inhibiting_shortcuts();
 
-        let Some(Some(bind)) = self.niri.seat.get_keyboard().unwrap().input(
+        let mut commit_window_switcher = false;
+        let mut chosen_window_hint = None;
+
+        let res = self.niri.seat.get_keyboard().unwrap().input(
             self,
             event.key_code(),
             event.state(),
@@ -396,6 +410,46 @@ impl State {
                     return FilterResult::Intercept(None);
                 }
 
+                if this.niri.window_switcher.is_open() {
+                    if pressed && raw == Some(Keysym::Escape) {
+                        this.niri.window_switcher.cancel();
+                        this.niri.queue_redraw_all();
+                        this.niri.suppressed_keys.insert(key_code);
+                        return FilterResult::Intercept(None);
+                    }
+
+                    // Releasing the modifiers that opened the switcher commits the selection.
+                    if !this
+                        .niri
+                        .window_switcher
+                        .is_trigger_held(modifiers_from_state(*mods))
+                    {
+                        commit_window_switcher = true;
+                    }
+                }
+
+                // Window hints grab the keyboard, so all key presses go to them.
+                if this.niri.keyboard_focus == KeyboardFocus::WindowHints && pressed {
+                    match raw {
+                        Some(Keysym::Escape) => {
+                            this.niri.window_hints.close();
+                        }
+                        Some(Keysym::BackSpace) => {
+                            this.niri.window_hints.backspace();
+                        }
+                        _ => {
+                            let c = raw.and_then(|raw| raw.key_char());
+                            if let Some(c) = c.filter(char::is_ascii_alphabetic) {
+                                chosen_window_hint = this.niri.window_hints.input(c);
+                            }
+                        }
+                    }
+
+                    this.niri.queue_redraw_all();
+                    this.niri.suppressed_keys.insert(key_code);
+                    return FilterResult::Intercept(None);
+                }
+
                 if let Some(Keysym::space) = raw {
                     this.niri.screenshot_ui.set_space_down(pressed);
                 }
@@ -418,17 +472,57 @@ impl State {
                 if matches!(res, FilterResult::Forward) {
                     // If we didn't find any bind, try other hardcoded keys.
                     if this.niri.keyboard_focus.is_overview() && pressed {
+                        if let Some(raw) = raw {
+                            let layout = &mut this.niri.layout;
+                            if handle_overview_search_key(layout, raw, modified, *mods) {
+                                this.niri.queue_redraw_all();
+                                this.niri.suppressed_keys.insert(key_code);
+                                return FilterResult::Intercept(None);
+                            }
+                        }
+
                         if let Some(bind) = raw.and_then(|raw| hardcoded_overview_bind(raw, *mods))
                         {
                             this.niri.suppressed_keys.insert(key_code);
                             return FilterResult::Intercept(Some(bind));
                         }
                     }
+
+                    // Then, try global shortcuts registered by applications.
+                    if pressed
+                        && !is_inhibiting_shortcuts
+                        && !this.niri.screenshot_ui.is_open()
+                        && !this.niri.is_locked()
+                    {
+                        if let Some(raw) = raw {
+                            let key = Key {
+                                trigger: Trigger::Keysym(raw),
+                                modifiers: modifiers_from_state(*mods),
+                            };
+                            let ids = this.global_shortcut_ids(key);
+                            if !ids.is_empty() {
+                                this.niri.suppressed_keys.insert(key_code);
+                                this.niri.notify_global_shortcuts(&ids, true, time);
+                                this.niri.pressed_global_shortcuts.insert(key_code, ids);
+                                return FilterResult::Intercept(None);
+                            }
+                        }
+                    }
                 }
 
                 res
             },
-        ) else {
+        );
+
+        if commit_window_switcher {
+            self.commit_window_switcher();
+        }
+
+        if let Some((window, action)) = chosen_window_hint {
+            self.do_window_hint_action(&window, action);
+        }
+
+        let Some(Some(bind)) = res else {
             return;
         };
 
@@ -436,6 +530,22 @@ impl State {
             return;
         }
 
+        // Keep keyboard-triggered global shortcuts active until the key is released.
+        if let Action::GlobalShortcut(shortcut) = &bind.action {
+            if self.niri.is_locked() && !bind.allow_when_locked {
+                return;
+            }
+
+            if let Some(key) = parse_global_shortcut(shortcut) {
+                let ids = self.global_shortcut_ids(key);
+                self.niri.notify_global_shortcuts(&ids, true, time);
+                self.niri
+                    .pressed_global_shortcuts
+                    .insert(event.key_code(), ids);
+            }
+            return;
+        }
+
         self.handle_bind(bind.clone());
 
         self.start_key_repeat(bind);
@@ -475,6 +585,24 @@ impl State {
         self.niri.bind_repeat_timer = Some(token);
     }
 
+    /// Returns ids of the global shortcuts grabbed for this key.
+    fn global_shortcut_ids(&self, key: Key) -> Vec<u32> {
+        let mod_key = self.backend.mod_key(&self.niri.config.borrow());
+        let modifiers = normalize_modifiers(key.modifiers, mod_key);
+        let action_mode = self.niri.action_mode();
+
+        self.niri
+            .global_shortcuts
+            .iter()
+            .filter(|shortcut| {
+                shortcut.key.trigger == key.trigger
+                    && normalize_modifiers(shortcut.key.modifiers, mod_key) == modifiers
+                    && shortcut.modes.contains(action_mode)
+            })
+            .map(|shortcut| shortcut.id)
+            .collect()
+    }
+
     fn hide_cursor_if_needed(&mut self) {
         // If the pointer is already invisible, don't reset it back to Hidden causing one frame
         // of hover.
@@ -620,8 +748,33 @@ impl State {
                 self.niri.screenshot_ui.toggle_pointer();
                 self.niri.queue_redraw_all();
             }
-            Action::Screenshot(show_cursor) => {
-                self.open_screenshot_ui(show_cursor);
+            Action::ScreenshotSetTool(tool) => {
+                self.niri.screenshot_ui.set_tool(tool);
+                self.niri.queue_redraw_all();
+            }
+            Action::ScreenshotUndo => {
+                self.niri.screenshot_ui.undo();
+                self.niri.queue_redraw_all();
+            }
+            Action::ScreenshotInsertText(c) => {
+                self.niri.screenshot_ui.insert_text(c);
+                self.niri.queue_redraw_all();
+            }
+            Action::ScreenshotDeleteText => {
+                self.niri.screenshot_ui.delete_text();
+                self.niri.queue_redraw_all();
+            }
+            Action::ScreenshotFinishText => {
+                self.niri.screenshot_ui.finish_text();
+                self.niri.queue_redraw_all();
+            }
+            Action::ScreenshotSetMode(mode) => {
+                self.niri.screenshot_ui.set_mode(mode);
+                self.niri.queue_redraw_all();
+            }
+            Action::Screenshot(show_cursor, delay_ms) => {
+                let delay = Duration::from_millis(u64::from(delay_ms.unwrap_or(0)));
+                self.open_screenshot_ui_delayed(show_cursor, delay);
             }
             Action::ScreenshotWindow(write_to_disk) => {
                 let focus = self.niri.layout.focus_with_output();
@@ -728,6 +881,20 @@ impl State {
                     self.focus_window(&window);
                 }
             }
+            Action::SwitchWindowNext(scope, filter) => {
+                self.switch_window(true, scope, filter);
+            }
+            Action::SwitchWindowPrevious(scope, filter) => {
+                self.switch_window(false, scope, filter);
+            }
+            Action::ShowWindowHints(action) => {
+                if !self.niri.screenshot_ui.is_open() {
+                    let windows = self.niri.window_hint_windows();
+                    if self.niri.window_hints.open(action, windows) {
+                        self.niri.queue_redraw_all();
+                    }
+                }
+            }
             Action::SwitchLayout(action) => {
                 let keyboard = &self.niri.seat.get_keyboard().unwrap();
                 keyboard.with_xkb_state(self, |mut state| match action {
@@ -1384,6 +1551,18 @@ impl State {
             Action::UnsetWorkSpaceNameByRef(reference) => {
                 self.niri.layout.unset_workspace_name(Some(reference));
             }
+            Action::SetWorkspaceLayoutKind(kind) => {
+                self.niri.layout.set_workspace_layout_kind(kind, None);
+                // FIXME: granular
+                self.niri.queue_redraw_all();
+            }
+            Action::SetWorkspaceLayoutKindByRef { kind, reference } => {
+                self.niri
+                    .layout
+                    .set_workspace_layout_kind(kind, Some(reference));
+                // FIXME: granular
+                self.niri.queue_redraw_all();
+            }
             Action::ConsumeWindowIntoColumn => {
                 self.niri.layout.consume_into_column();
                 // This does not cause immediate focus or window size change, so warping mouse to
@@ -1960,6 +2139,82 @@ impl State {
                     self.niri.queue_redraw_all();
                 }
             }
+            Action::TogglePinWindow => {
+                self.niri.layout.toggle_window_pinned(None);
+                // FIXME: granular
+                self.niri.queue_redraw_all();
+            }
+            Action::TogglePinWindowById(id) => {
+                let window = self.niri.layout.windows().find(|(_, m)| m.id().get() == id);
+                let window = window.map(|(_, m)| m.window.clone());
+                if let Some(window) = window {
+                    self.niri.layout.toggle_window_pinned(Some(&window));
+                    // FIXME: granular
+                    self.niri.queue_redraw_all();
+                }
+            }
+            Action::ToggleWindowKeepAbove => {
+                self.niri.layout.toggle_window_keep_above(None);
+                // FIXME: granular
+                self.niri.queue_redraw_all();
+            }
+            Action::ToggleWindowKeepAboveById(id) => {
+                let window = self.niri.layout.windows().find(|(_, m)| m.id().get() == id);
+                let window = window.map(|(_, m)| m.window.clone());
+                if let Some(window) = window {
+                    self.niri.layout.toggle_window_keep_above(Some(&window));
+                    // FIXME: granular
+                    self.niri.queue_redraw_all();
+                }
+            }
+            Action::ToggleWindowKeepBelow => {
+                self.niri.layout.toggle_window_keep_below(None);
+                // FIXME: granular
+                self.niri.queue_redraw_all();
+            }
+            Action::ToggleWindowKeepBelowById(id) => {
+                let window = self.niri.layout.windows().find(|(_, m)| m.id().get() == id);
+                let window = window.map(|(_, m)| m.window.clone());
+                if let Some(window) = window {
+                    self.niri.layout.toggle_window_keep_below(Some(&window));
+                    // FIXME: granular
+                    self.niri.queue_redraw_all();
+                }
+            }
+            Action::MinimizeWindow => {
+                self.niri.layout.minimize_window(None);
+                // FIXME: granular
+                self.niri.queue_redraw_all();
+            }
+            Action::MinimizeWindowById(id) => {
+                let window = self.niri.layout.windows().find(|(_, m)| m.id().get() == id);
+                let window = window.map(|(_, m)| m.window.clone());
+                if let Some(window) = window {
+                    self.niri.layout.minimize_window(Some(&window));
+                    // FIXME: granular
+                    self.niri.queue_redraw_all();
+                }
+            }
+            Action::RestoreMinimized => {
+                self.niri.layout.restore_minimized(None);
+                self.maybe_warp_cursor_to_focus();
+                // FIXME: granular
+                self.niri.queue_redraw_all();
+            }
+            Action::RestoreMinimizedById(id) => {
+                let mut window = None;
+                self.niri.layout.with_windows(|m, _, _| {
+                    if m.id().get() == id && m.is_minimized() {
+                        window = Some(m.window.clone());
+                    }
+                });
+                if let Some(window) = window {
+                    self.niri.layout.restore_minimized(Some(&window));
+                    self.maybe_warp_cursor_to_focus();
+                    // FIXME: granular
+                    self.niri.queue_redraw_all();
+                }
+            }
             Action::FocusFloating => {
                 self.niri.layout.focus_floating();
                 self.maybe_warp_cursor_to_focus();
@@ -1996,12 +2251,22 @@ impl State {
                 // FIXME: granular
                 self.niri.queue_redraw_all();
             }
+            Action::TileFloatingWindow(area) => {
+                self.niri.layout.tile_floating_window(None, area);
+                // FIXME: granular
+                self.niri.queue_redraw_all();
+            }
+            Action::TileFloatingWindowById { id, area } => {
+                let window = self.niri.layout.windows().find(|(_, m)| m.id().get() == id);
+                let window = window.map(|(_, m)| m.window.clone());
+                if let Some(window) = window {
+                    self.niri.layout.tile_floating_window(Some(&window), area);
+                    // FIXME: granular
+                    self.niri.queue_redraw_all();
+                }
+            }
             Action::ToggleWindowRuleOpacity => {
-                let active_window = self
-                    .niri
-                    .layout
-                    .active_workspace_mut()
-                    .and_then(|ws| ws.active_window_mut());
+                let active_window = self.niri.layout.focus_mut();
                 if let Some(window) = active_window {
                     if window.rules().opacity.is_some_and(|o| o != 1.) {
                         window.toggle_ignore_opacity_window_rule();
@@ -2011,26 +2276,20 @@ impl State {
                 }
             }
             Action::ToggleWindowRuleOpacityById(id) => {
-                let window = self
-                    .niri
-                    .layout
-                    .workspaces_mut()
-                    .find_map(|ws| ws.windows_mut().find(|w| w.id().get() == id));
-                if let Some(window) = window {
-                    if window.rules().opacity.is_some_and(|o| o != 1.) {
+                let mut toggled = false;
+                self.niri.layout.with_windows_mut(|window, _| {
+                    if window.id().get() == id && window.rules().opacity.is_some_and(|o| o != 1.) {
                         window.toggle_ignore_opacity_window_rule();
-                        // FIXME: granular
-                        self.niri.queue_redraw_all();
+                        toggled = true;
                     }
+                });
+                if toggled {
+                    // FIXME: granular
+                    self.niri.queue_redraw_all();
                 }
             }
             Action::SetDynamicCastWindow => {
-                let id = self
-                    .niri
-                    .layout
-                    .active_workspace()
-                    .and_then(|ws| ws.active_window())
-                    .map(|mapped| mapped.id().get());
+                let id = self.niri.layout.focus().map(|mapped| mapped.id().get());
                 if let Some(id) = id {
                     self.set_dynamic_cast_target(CastTarget::Window { id });
                 }
@@ -2051,9 +2310,50 @@ impl State {
                     self.set_dynamic_cast_target(CastTarget::Output(output));
                 }
             }
+            Action::SetDynamicCastWorkspace(reference) => {
+                let layout = &mut self.niri.layout;
+                let id = match reference {
+                    None => layout.active_workspace().map(|ws| ws.id()),
+                    Some(reference) => layout.find_workspace_by_ref(reference).map(|ws| ws.id()),
+                };
+                if let Some(id) = id {
+                    self.set_dynamic_cast_target(CastTarget::Workspace { id: id.get() });
+                }
+            }
+            Action::SetDynamicCastArea => {
+                self.open_screenshot_ui(false, SelectionPurpose::DynamicCast);
+            }
             Action::ClearDynamicCastTarget => {
                 self.set_dynamic_cast_target(CastTarget::Nothing);
             }
+            Action::RecordScreen(output) => {
+                let output = match output {
+                    None => self.niri.layout.active_output(),
+                    Some(name) => self.niri.output_by_name_match(&name),
+                };
+                if let Some(output) = output {
+                    let output = output.downgrade();
+                    self.start_recording(CastTarget::Output(output));
+                }
+            }
+            Action::RecordWindow => {
+                let id = self.niri.layout.focus().map(|mapped| mapped.id().get());
+                if let Some(id) = id {
+                    self.start_recording(CastTarget::Window { id });
+                }
+            }
+            Action::RecordWindowById(id) => {
+                let layout = &self.niri.layout;
+                if layout.windows().any(|(_, mapped)| mapped.id().get() == id) {
+                    self.start_recording(CastTarget::Window { id });
+                }
+            }
+            Action::RecordArea => {
+                self.open_screenshot_ui(false, SelectionPurpose::Recording);
+            }
+            Action::StopRecording => {
+                self.stop_recording();
+            }
             Action::ToggleOverview => {
                 self.niri.layout.toggle_overview();
                 self.niri.queue_redraw_all();
@@ -2069,46 +2369,155 @@ impl State {
                 }
             }
             Action::ToggleWindowUrgent(id) => {
-                let window = self
-                    .niri
-                    .layout
-                    .workspaces_mut()
-                    .find_map(|ws| ws.windows_mut().find(|w| w.id().get() == id));
-                if let Some(window) = window {
-                    let urgent = window.is_urgent();
-                    window.set_urgent(!urgent);
-                }
+                self.niri.layout.with_windows_mut(|window, _| {
+                    if window.id().get() == id {
+                        let urgent = window.is_urgent();
+                        window.set_urgent(!urgent);
+                    }
+                });
                 self.niri.queue_redraw_all();
             }
             Action::SetWindowUrgent(id) => {
-                let window = self
+                self.niri.layout.with_windows_mut(|window, _| {
+                    if window.id().get() == id {
+                        window.set_urgent(true);
+                    }
+                });
+                self.niri.queue_redraw_all();
+            }
+            Action::GlobalShortcut(shortcut) => {
+                if let Some(key) = parse_global_shortcut(&shortcut) {
+                    let ids = self.global_shortcut_ids(key);
+                    let time = get_monotonic_time().as_millis() as u32;
+                    self.niri.notify_global_shortcuts(&ids, true, time);
+                    self.niri.notify_global_shortcuts(&ids, false, time);
+                }
+            }
+            Action::ToggleNightLight => {
+                if self.niri.night_light.toggle() {
+                    self.niri.queue_redraw_all();
+                }
+            }
+            Action::SetNightLightTemperature(temperature) => {
+                if self.niri.night_light.set_temperature(temperature) {
+                    self.niri.queue_redraw_all();
+                }
+            }
+            Action::ResetNightLight => {
+                if self.niri.night_light.reset() {
+                    self.niri.queue_redraw_all();
+                }
+            }
+            Action::StopCastSession(session_id) => {
+                self.niri.stop_cast(session_id as usize);
+            }
+            Action::UnsetWindowUrgent(id) => {
+                self.niri.layout.with_windows_mut(|window, _| {
+                    if window.id().get() == id {
+                        window.set_urgent(false);
+                    }
+                });
+                self.niri.queue_redraw_all();
+            }
+        }
+    }
+
+    /// Opens or advances the window switcher.
+    ///
+    /// When invoked with modifiers held, the switcher stays open until they are released, at which
+    /// point the selected window is focused. Otherwise, the next window is focused right away.
+    fn switch_window(
+        &mut self,
+        forward: bool,
+        scope: WindowSwitcherScope,
+        filter: WindowSwitcherFilter,
+    ) {
+        if self.niri.window_switcher.is_open() {
+            self.niri.window_switcher.advance(forward);
+            self.niri.queue_redraw_all();
+            return;
+        }
+
+        let windows = self.niri.window_switcher_windows(scope, filter);
+        if windows.len() < 2 {
+            return;
+        }
+
+        let selected = if forward { 1 } else { windows.len() - 1 };
+
+        let mods = self.niri.seat.get_keyboard().unwrap().modifier_state();
+        let trigger_mods = window_switcher::trigger_mods(modifiers_from_state(mods));
+
+        let output = self.niri.layout.active_output().cloned();
+        match output {
+            Some(output) if !trigger_mods.is_empty() => {
+                self.niri
+                    .window_switcher
+                    .open(windows, selected, output, trigger_mods);
+                self.niri.queue_redraw_all();
+            }
+            _ => self.focus_window(&windows[selected]),
+        }
+    }
+
+    /// Closes the window switcher and focuses the selected window.
+    fn commit_window_switcher(&mut self) {
+        if let Some(window) = self.niri.window_switcher.close() {
+            self.focus_window(&window);
+        }
+    }
+
+    /// Acts on the window chosen through window hints.
+    fn do_window_hint_action(&mut self, window: &Window, action: WindowHintAction) {
+        match action {
+            WindowHintAction::Focus => self.focus_window(window),
+            WindowHintAction::Close => {
+                let mapped = self
                     .niri
                     .layout
-                    .workspaces_mut()
-                    .find_map(|ws| ws.windows_mut().find(|w| w.id().get() == id));
-                if let Some(window) = window {
-                    window.set_urgent(true);
+                    .windows()
+                    .find(|(_, m)| m.window == *window);
+                if let Some((_, mapped)) = mapped {
+                    mapped.toplevel().send_close();
                 }
-                self.niri.queue_redraw_all();
             }
-            Action::UnsetWindowUrgent(id) => {
-                let window = self
+            WindowHintAction::Move => {
+                let Some(active_output) = self.niri.layout.active_output().cloned() else {
+                    return;
+                };
+                let mon = self
                     .niri
                     .layout
-                    .workspaces_mut()
-                    .find_map(|ws| ws.windows_mut().find(|w| w.id().get() == id));
-                if let Some(window) = window {
-                    window.set_urgent(false);
+                    .windows()
+                    .find(|(_, m)| m.window == *window);
+                let Some((Some(mon), _)) = mon else {
+                    return;
+                };
+                let is_on_active_output = *mon.output() == active_output;
+
+                let mon = self.niri.layout.monitor_for_output(&active_output).unwrap();
+                let idx = mon.active_workspace_idx();
+
+                if is_on_active_output {
+                    self.niri
+                        .layout
+                        .move_to_workspace(Some(window), idx, ActivateWindow::Yes);
+                } else {
+                    self.niri.layout.move_to_output(
+                        Some(window),
+                        &active_output,
+                        Some(idx),
+                        ActivateWindow::Yes,
+                    );
                 }
-                self.niri.queue_redraw_all();
+                self.focus_window(window);
             }
         }
     }
 
     fn on_pointer_motion<I: InputBackend>(&mut self, event: I::PointerMotionEvent) {
-        let was_inside_hot_corner = self.niri.pointer_inside_hot_corner;
         // Any of the early returns here mean that the pointer is not inside the hot corner.
-        self.niri.pointer_inside_hot_corner = false;
+        let prev_hot_corner = self.niri.hot_corner.take();
 
         // We need an output to be able to move the pointer.
         if self.niri.global_space.outputs().next().is_none() {
@@ -2286,21 +2695,7 @@ impl State {
 
         pointer.frame(self);
 
-        // contents_under() will return no surface when the hot corner should trigger.
-        let hot_corners = self.niri.config.borrow().gestures.hot_corners;
-        if !hot_corners.off
-            && pointer.current_focus().is_none()
-            && !self.niri.screenshot_ui.is_open()
-        {
-            let hot_corner = Rectangle::from_size(Size::from((1., 1.)));
-            if let Some((_, pos_within_output)) = self.niri.output_under(pos) {
-                let inside_hot_corner = hot_corner.contains(pos_within_output);
-                if inside_hot_corner && !was_inside_hot_corner {
-                    self.niri.layout.toggle_overview();
-                }
-                self.niri.pointer_inside_hot_corner = inside_hot_corner;
-            }
-        }
+        self.update_hot_corner(prev_hot_corner, new_pos, Some(event.delta()));
 
         // Activate a new confinement if necessary.
         self.niri.maybe_activate_pointer_constraint();
@@ -2315,6 +2710,8 @@ impl State {
                 let output = output.clone();
                 self.niri.layout.dnd_update(output, pos_within_output);
             }
+
+            self.toplevel_drag_motion(new_pos);
         }
 
         // Redraw to update the cursor position.
@@ -2322,13 +2719,103 @@ impl State {
         self.niri.queue_redraw_all();
     }
 
+    /// Updates the hot corner under the pointer and activates it once it's ready.
+    ///
+    /// `delta` is the relative pointer motion used to measure the pressure into the corner.
+    fn update_hot_corner(
+        &mut self,
+        prev: Option<HotCornerState>,
+        pos: Point<f64, Logical>,
+        delta: Option<Point<f64, Logical>>,
+    ) {
+        // contents_under() will return no surface when the hot corner should trigger.
+        let pointer = self.niri.seat.get_pointer().unwrap();
+        if pointer.current_focus().is_some() || self.niri.screenshot_ui.is_open() {
+            return;
+        }
+
+        let Some((output, pos_within_output)) = self.niri.output_under(pos) else {
+            return;
+        };
+        let Some((corner, action)) = self.niri.hot_corner_under(output, pos_within_output) else {
+            return;
+        };
+        let output = output.clone();
+
+        let config = self.niri.config.borrow();
+        let delay = Duration::from_millis(u64::from(config.gestures.hot_corners.delay_ms));
+        let pressure = config.gestures.hot_corners.pressure.0;
+        drop(config);
+
+        let mut hot_corner = match prev {
+            Some(prev) if prev.output == output && prev.corner == corner => prev,
+            _ => {
+                let entered_at = get_monotonic_time();
+                if !delay.is_zero() {
+                    let timer = Timer::from_duration(delay);
+                    self.niri
+                        .event_loop
+                        .insert_source(timer, move |_, _, state| {
+                            // Check that the pointer didn't leave the corner in the meantime.
+                            if let Some(hot_corner) = &mut state.niri.hot_corner {
+                                if hot_corner.entered_at == entered_at {
+                                    hot_corner.delay_passed = true;
+                                    state.maybe_activate_hot_corner();
+                                }
+                            }
+                            TimeoutAction::Drop
+                        })
+                        .unwrap();
+                }
+
+                HotCornerState {
+                    output,
+                    corner,
+                    action,
+                    entered_at,
+                    delay_passed: delay.is_zero(),
+                    pressure: 0.,
+                    pressure_reached: false,
+                    triggered: false,
+                }
+            }
+        };
+
+        if let Some(delta) = delta {
+            let (x, y) = match corner {
+                HotCorner::TopLeft => (-delta.x, -delta.y),
+                HotCorner::TopRight => (delta.x, -delta.y),
+                HotCorner::BottomLeft => (-delta.x, delta.y),
+                HotCorner::BottomRight => (delta.x, delta.y),
+            };
+            hot_corner.pressure += f64::max(0., x) + f64::max(0., y);
+        }
+        // Absolute devices like tablets cannot push into the corner, so they skip the pressure.
+        hot_corner.pressure_reached |= delta.is_none() || hot_corner.pressure >= pressure;
+
+        self.niri.hot_corner = Some(hot_corner);
+        self.maybe_activate_hot_corner();
+    }
+
+    fn maybe_activate_hot_corner(&mut self) {
+        let Some(hot_corner) = &mut self.niri.hot_corner else {
+            return;
+        };
+        if hot_corner.triggered || !hot_corner.delay_passed || !hot_corner.pressure_reached {
+            return;
+        }
+
+        hot_corner.triggered = true;
+        let action = hot_corner.action.clone();
+        self.do_action(action, false);
+    }
+
     fn on_pointer_motion_absolute<I: InputBackend>(
         &mut self,
         event: I::PointerMotionAbsoluteEvent,
     ) {
-        let was_inside_hot_corner = self.niri.pointer_inside_hot_corner;
         // Any of the early returns here mean that the pointer is not inside the hot corner.
-        self.niri.pointer_inside_hot_corner = false;
+        let prev_hot_corner = self.niri.hot_corner.take();
 
         let Some(pos) = self.compute_absolute_location(&event, None).or_else(|| {
             self.global_bounding_rectangle().map(|output_geo| {
@@ -2375,21 +2862,7 @@ impl State {
 
         pointer.frame(self);
 
-        // contents_under() will return no surface when the hot corner should trigger.
-        let hot_corners = self.niri.config.borrow().gestures.hot_corners;
-        if !hot_corners.off
-            && pointer.current_focus().is_none()
-            && !self.niri.screenshot_ui.is_open()
-        {
-            let hot_corner = Rectangle::from_size(Size::from((1., 1.)));
-            if let Some((_, pos_within_output)) = self.niri.output_under(pos) {
-                let inside_hot_corner = hot_corner.contains(pos_within_output);
-                if inside_hot_corner && !was_inside_hot_corner {
-                    self.niri.layout.toggle_overview();
-                }
-                self.niri.pointer_inside_hot_corner = inside_hot_corner;
-            }
-        }
+        self.update_hot_corner(prev_hot_corner, pos, None);
 
         self.niri.maybe_activate_pointer_constraint();
 
@@ -2409,6 +2882,8 @@ impl State {
                 let output = output.clone();
                 self.niri.layout.dnd_update(output, pos_within_output);
             }
+
+            self.toplevel_drag_motion(pos);
         }
 
         // Redraw to update the cursor position.
@@ -3343,6 +3818,7 @@ impl State {
     fn on_gesture_swipe_begin<I: InputBackend>(&mut self, event: I::GestureSwipeBeginEvent) {
         if event.fingers() == 3 {
             self.niri.gesture_swipe_3f_cumulative = Some((0., 0.));
+            self.niri.gesture_swipe_3f_transposed = false;
 
             // We handled this event.
             return;
@@ -3409,18 +3885,24 @@ impl State {
                 self.niri.gesture_swipe_3f_cumulative = None;
 
                 if let Some(output) = self.niri.output_under_cursor() {
-                    if cx.abs() > cy.abs() {
-                        let output_ws = if is_overview_open {
-                            self.niri.workspace_under_cursor(true)
-                        } else {
-                            // We don't want to accidentally "catch" the wrong workspace during
-                            // animations.
-                            self.niri.output_under_cursor().and_then(|output| {
-                                let mon = self.niri.layout.monitor_for_output(&output)?;
-                                Some((output, mon.active_workspace_ref()))
-                            })
-                        };
+                    let output_ws = if is_overview_open {
+                        self.niri.workspace_under_cursor(true)
+                    } else {
+                        // We don't want to accidentally "catch" the wrong workspace during
+                        // animations.
+                        self.niri.output_under_cursor().and_then(|output| {
+                            let mon = self.niri.layout.monitor_for_output(&output)?;
+                            Some((output, mon.active_workspace_ref()))
+                        })
+                    };
+
+                    // On a vertical scrolling layout, vertical swipes scroll the view, and
+                    // horizontal swipes switch workspaces.
+                    let transposed = output_ws
+                        .as_ref()
+                        .is_some_and(|(_, ws)| ws.is_scrolling_vertical());
 
+                    if (cx.abs() > cy.abs()) != transposed {
                         if let Some((output, ws)) = output_ws {
                             let ws_idx = self.niri.layout.find_workspace_by_id(ws.id()).unwrap().0;
                             self.niri
@@ -3432,10 +3914,16 @@ impl State {
                             .layout
                             .workspace_switch_gesture_begin(&output, true);
                     }
+
+                    self.niri.gesture_swipe_3f_transposed = transposed;
                 }
             }
         }
 
+        if self.niri.gesture_swipe_3f_transposed {
+            mem::swap(&mut delta_x, &mut delta_y);
+        }
+
         let timestamp = Duration::from_micros(event.time());
 
         let mut handled = false;
@@ -3494,6 +3982,7 @@ impl State {
 
     fn on_gesture_swipe_end<I: InputBackend>(&mut self, event: I::GestureSwipeEndEvent) {
         self.niri.gesture_swipe_3f_cumulative = None;
+        self.niri.gesture_swipe_3f_transposed = false;
 
         let mut handled = false;
         let res = self.niri.layout.workspace_switch_gesture_end(Some(true));
@@ -3846,6 +4335,8 @@ impl State {
                 let output = output.clone();
                 self.niri.layout.dnd_update(output, pos_within_output);
             }
+
+            self.toplevel_drag_motion(pos);
         }
     }
     fn on_touch_frame<I: InputBackend>(&mut self, _evt: I::TouchFrameEvent) {
@@ -3930,22 +4421,25 @@ fn should_intercept_key(
 
         if use_screenshot_ui_action {
             if let Some(raw) = raw {
-                final_bind = screenshot_ui.action(raw, mods).map(|action| Bind {
-                    key: Key {
-                        trigger: Trigger::Keysym(raw),
-                        // Not entirely correct but it doesn't matter in how we currently use it.
-                        modifiers: Modifiers::empty(),
-                    },
-                    action,
-                    repeat: true,
-                    cooldown: None,
-                    allow_when_locked: false,
-                    // The screenshot UI owns the focus anyway, so this doesn't really matter.
-                    // But logically, nothing can inhibit its actions. Only opening it can be
-                    // inhibited.
-                    allow_inhibiting: false,
-                    hotkey_overlay_title: None,
-                });
+                final_bind = screenshot_ui
+                    .action(raw, modified, mods)
+                    .map(|action| Bind {
+                        key: Key {
+                            trigger: Trigger::Keysym(raw),
+                            // Not entirely correct but it doesn't matter in how we currently use
+                            // it.
+                            modifiers: Modifiers::empty(),
+                        },
+                        action,
+                        repeat: true,
+                        cooldown: None,
+                        allow_when_locked: false,
+                        // The screenshot UI owns the focus anyway, so this doesn't really matter.
+                        // But logically, nothing can inhibit its actions. Only opening it can be
+                        // inhibited.
+                        allow_inhibiting: false,
+                        hotkey_overlay_title: None,
+                    });
             }
         }
     }
@@ -4025,26 +4519,14 @@ fn find_configured_bind(
     mods: ModifiersState,
 ) -> Option<Bind> {
     // Handle configured binds.
-    let mut modifiers = modifiers_from_state(mods);
-
-    let mod_down = modifiers_from_state(mods).contains(mod_key.to_modifiers());
-    if mod_down {
-        modifiers |= Modifiers::COMPOSITOR;
-    }
+    let modifiers = normalize_modifiers(modifiers_from_state(mods), mod_key);
 
     for bind in &bindings.0 {
         if bind.key.trigger != trigger {
             continue;
         }
 
-        let mut bind_modifiers = bind.key.modifiers;
-        if bind_modifiers.contains(Modifiers::COMPOSITOR) {
-            bind_modifiers |= mod_key.to_modifiers();
-        } else if bind_modifiers.contains(mod_key.to_modifiers()) {
-            bind_modifiers |= Modifiers::COMPOSITOR;
-        }
-
-        if bind_modifiers == modifiers {
+        if normalize_modifiers(bind.key.modifiers, mod_key) == modifiers {
             return Some(bind.clone());
         }
     }
@@ -4052,6 +4534,26 @@ fn find_configured_bind(
     None
 }
 
+/// Makes the compositor mod and the modifier it stands for imply each other.
+fn normalize_modifiers(mut modifiers: Modifiers, mod_key: ModKey) -> Modifiers {
+    if modifiers.contains(Modifiers::COMPOSITOR) {
+        modifiers |= mod_key.to_modifiers();
+    } else if modifiers.contains(mod_key.to_modifiers()) {
+        modifiers |= Modifiers::COMPOSITOR;
+    }
+    modifiers
+}
+
+fn parse_global_shortcut(shortcut: &str) -> Option<Key> {
+    match Key::from_str(shortcut) {
+        Ok(key) => Some(key),
+        Err(err) => {
+            warn!("error parsing global shortcut {shortcut:?}: {err:?}");
+            None
+        }
+    }
+}
+
 fn find_configured_switch_action(
     bindings: &SwitchBinds,
     switch: Switch,
@@ -4249,6 +4751,43 @@ fn hardcoded_overview_bind(raw: Keysym, mods: ModifiersState) -> Option<Bind> {
     })
 }
 
+/// Handles typing into the overview search.
+///
+/// Returns whether the key was consumed.
+fn handle_overview_search_key(
+    layout: &mut crate::layout::Layout<Mapped>,
+    raw: Keysym,
+    modified: Keysym,
+    mods: ModifiersState,
+) -> bool {
+    if mods.ctrl || mods.alt || mods.logo {
+        return false;
+    }
+
+    let is_searching = !layout.overview_search().is_empty();
+    match raw {
+        Keysym::BackSpace => layout.overview_search_pop(),
+        // Escape clears the search first, and closes the overview only when pressed again.
+        Keysym::Escape if is_searching => layout.clear_overview_search(),
+        Keysym::Left | Keysym::Up if is_searching => {
+            layout.overview_search_focus_next(false);
+            true
+        }
+        Keysym::Right | Keysym::Down if is_searching => {
+            layout.overview_search_focus_next(true);
+            true
+        }
+        _ => match modified.key_char() {
+            // Don't start a search with whitespace.
+            Some(c) if !c.is_control() && (is_searching || !c.is_whitespace()) => {
+                layout.overview_search_push(c);
+                true
+            }
+            _ => false,
+        },
+    }
+}
+
 pub fn apply_libinput_settings(config: &niri_config::Input, device: &mut input::Device) {
     // According to Mutter code, this setting is specific to touchpads.
     let is_touchpad = device.config_tap_finger_count() > 0;
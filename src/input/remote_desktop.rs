@@ -0,0 +1,755 @@
+use smithay::backend::input::{
+    AbsolutePositionEvent, Axis, AxisRelativeDirection, AxisSource, ButtonState, Device,
+    DeviceCapability, Event, InputBackend, InputEvent, KeyState, KeyboardKeyEvent, Keycode,
+    PointerAxisEvent, PointerButtonEvent, PointerMotionAbsoluteEvent, PointerMotionEvent,
+    TouchDownEvent, TouchEvent, TouchFrameEvent, TouchMotionEvent, TouchSlot, TouchUpEvent,
+    UnusedEvent,
+};
+use smithay::input::keyboard::{xkb, Keysym};
+use smithay::output::Output;
+use smithay::utils::{Logical, Physical, Point};
+
+use crate::dbus::mutter_remote_desktop::RemoteDesktopInput;
+use crate::dbus::mutter_screen_cast::StreamTargetId;
+use crate::niri::State;
+use crate::utils::{get_monotonic_time, output_size};
+
+/// Evdev code of the left Shift key.
+const KEY_LEFTSHIFT: u32 = 42;
+
+/// Evdev code of the right Alt key, which is AltGr in most layouts.
+const KEY_RIGHTALT: u32 = 100;
+
+/// Scroll distance of one wheel detent, matching libinput.
+const WHEEL_STEP: f64 = 15.;
+
+pub struct RemoteDesktopInputBackend;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RemoteDesktopDevice {
+    session_id: usize,
+    // Output that absolute positions are relative to.
+    output: Option<Output>,
+}
+
+impl RemoteDesktopDevice {
+    pub fn output(&self) -> Option<&Output> {
+        self.output.as_ref()
+    }
+}
+
+impl Device for RemoteDesktopDevice {
+    fn id(&self) -> String {
+        format!("remote desktop {}", self.session_id)
+    }
+
+    fn name(&self) -> String {
+        String::from("remote desktop")
+    }
+
+    fn has_capability(&self, capability: DeviceCapability) -> bool {
+        matches!(
+            capability,
+            DeviceCapability::Keyboard | DeviceCapability::Pointer | DeviceCapability::Touch
+        )
+    }
+
+    fn usb_id(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    fn syspath(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+}
+
+pub struct RemoteDesktopKeyboardKeyEvent {
+    device: RemoteDesktopDevice,
+    time: u64,
+    key_code: Keycode,
+    state: KeyState,
+}
+
+impl Event<RemoteDesktopInputBackend> for RemoteDesktopKeyboardKeyEvent {
+    fn time(&self) -> u64 {
+        self.time
+    }
+
+    fn device(&self) -> RemoteDesktopDevice {
+        self.device.clone()
+    }
+}
+
+impl KeyboardKeyEvent<RemoteDesktopInputBackend> for RemoteDesktopKeyboardKeyEvent {
+    fn key_code(&self) -> Keycode {
+        self.key_code
+    }
+
+    fn state(&self) -> KeyState {
+        self.state
+    }
+
+    fn count(&self) -> u32 {
+        match self.state {
+            KeyState::Released => 0,
+            KeyState::Pressed => 1,
+        }
+    }
+}
+
+pub struct RemoteDesktopPointerMotionEvent {
+    device: RemoteDesktopDevice,
+    time: u64,
+    delta: Point<f64, Logical>,
+}
+
+impl Event<RemoteDesktopInputBackend> for RemoteDesktopPointerMotionEvent {
+    fn time(&self) -> u64 {
+        self.time
+    }
+
+    fn device(&self) -> RemoteDesktopDevice {
+        self.device.clone()
+    }
+}
+
+impl PointerMotionEvent<RemoteDesktopInputBackend> for RemoteDesktopPointerMotionEvent {
+    fn delta_x(&self) -> f64 {
+        self.delta.x
+    }
+
+    fn delta_y(&self) -> f64 {
+        self.delta.y
+    }
+
+    fn delta_x_unaccel(&self) -> f64 {
+        self.delta.x
+    }
+
+    fn delta_y_unaccel(&self) -> f64 {
+        self.delta.y
+    }
+}
+
+/// Absolute pointer motion or touch event.
+pub struct RemoteDesktopAbsoluteEvent {
+    device: RemoteDesktopDevice,
+    time: u64,
+    slot: TouchSlot,
+    // Position normalized to 0..1 on the untransformed output.
+    position: Point<f64, Logical>,
+}
+
+impl Event<RemoteDesktopInputBackend> for RemoteDesktopAbsoluteEvent {
+    fn time(&self) -> u64 {
+        self.time
+    }
+
+    fn device(&self) -> RemoteDesktopDevice {
+        self.device.clone()
+    }
+}
+
+impl AbsolutePositionEvent<RemoteDesktopInputBackend> for RemoteDesktopAbsoluteEvent {
+    fn x(&self) -> f64 {
+        self.position.x
+    }
+
+    fn y(&self) -> f64 {
+        self.position.y
+    }
+
+    fn x_transformed(&self, width: i32) -> f64 {
+        self.position.x * f64::from(width)
+    }
+
+    fn y_transformed(&self, height: i32) -> f64 {
+        self.position.y * f64::from(height)
+    }
+}
+
+impl PointerMotionAbsoluteEvent<RemoteDesktopInputBackend> for RemoteDesktopAbsoluteEvent {}
+
+impl TouchEvent<RemoteDesktopInputBackend> for RemoteDesktopAbsoluteEvent {
+    fn slot(&self) -> TouchSlot {
+        self.slot
+    }
+}
+
+impl TouchDownEvent<RemoteDesktopInputBackend> for RemoteDesktopAbsoluteEvent {}
+impl TouchMotionEvent<RemoteDesktopInputBackend> for RemoteDesktopAbsoluteEvent {}
+
+/// Touch up or frame event.
+pub struct RemoteDesktopTouchEvent {
+    device: RemoteDesktopDevice,
+    time: u64,
+    slot: TouchSlot,
+}
+
+impl Event<RemoteDesktopInputBackend> for RemoteDesktopTouchEvent {
+    fn time(&self) -> u64 {
+        self.time
+    }
+
+    fn device(&self) -> RemoteDesktopDevice {
+        self.device.clone()
+    }
+}
+
+impl TouchEvent<RemoteDesktopInputBackend> for RemoteDesktopTouchEvent {
+    fn slot(&self) -> TouchSlot {
+        self.slot
+    }
+}
+
+impl TouchUpEvent<RemoteDesktopInputBackend> for RemoteDesktopTouchEvent {}
+impl TouchFrameEvent<RemoteDesktopInputBackend> for RemoteDesktopTouchEvent {}
+
+pub struct RemoteDesktopPointerButtonEvent {
+    device: RemoteDesktopDevice,
+    time: u64,
+    button: u32,
+    state: ButtonState,
+}
+
+impl Event<RemoteDesktopInputBackend> for RemoteDesktopPointerButtonEvent {
+    fn time(&self) -> u64 {
+        self.time
+    }
+
+    fn device(&self) -> RemoteDesktopDevice {
+        self.device.clone()
+    }
+}
+
+impl PointerButtonEvent<RemoteDesktopInputBackend> for RemoteDesktopPointerButtonEvent {
+    fn button_code(&self) -> u32 {
+        self.button
+    }
+
+    fn state(&self) -> ButtonState {
+        self.state
+    }
+}
+
+pub struct RemoteDesktopPointerAxisEvent {
+    device: RemoteDesktopDevice,
+    time: u64,
+    source: AxisSource,
+    // Horizontal, vertical.
+    amount: (f64, f64),
+    amount_v120: Option<(f64, f64)>,
+}
+
+impl Event<RemoteDesktopInputBackend> for RemoteDesktopPointerAxisEvent {
+    fn time(&self) -> u64 {
+        self.time
+    }
+
+    fn device(&self) -> RemoteDesktopDevice {
+        self.device.clone()
+    }
+}
+
+impl PointerAxisEvent<RemoteDesktopInputBackend> for RemoteDesktopPointerAxisEvent {
+    fn amount(&self, axis: Axis) -> Option<f64> {
+        match axis {
+            Axis::Horizontal => Some(self.amount.0),
+            Axis::Vertical => Some(self.amount.1),
+        }
+    }
+
+    fn amount_v120(&self, axis: Axis) -> Option<f64> {
+        self.amount_v120.map(|(h, v)| match axis {
+            Axis::Horizontal => h,
+            Axis::Vertical => v,
+        })
+    }
+
+    fn source(&self) -> AxisSource {
+        self.source
+    }
+
+    fn relative_direction(&self, _axis: Axis) -> AxisRelativeDirection {
+        AxisRelativeDirection::Identical
+    }
+}
+
+impl InputBackend for RemoteDesktopInputBackend {
+    type Device = RemoteDesktopDevice;
+
+    type KeyboardKeyEvent = RemoteDesktopKeyboardKeyEvent;
+    type PointerAxisEvent = RemoteDesktopPointerAxisEvent;
+    type PointerButtonEvent = RemoteDesktopPointerButtonEvent;
+    type PointerMotionEvent = RemoteDesktopPointerMotionEvent;
+    type PointerMotionAbsoluteEvent = RemoteDesktopAbsoluteEvent;
+
+    type GestureSwipeBeginEvent = UnusedEvent;
+    type GestureSwipeUpdateEvent = UnusedEvent;
+    type GestureSwipeEndEvent = UnusedEvent;
+    type GesturePinchBeginEvent = UnusedEvent;
+    type GesturePinchUpdateEvent = UnusedEvent;
+    type GesturePinchEndEvent = UnusedEvent;
+    type GestureHoldBeginEvent = UnusedEvent;
+    type GestureHoldEndEvent = UnusedEvent;
+
+    type TouchDownEvent = RemoteDesktopAbsoluteEvent;
+    type TouchUpEvent = RemoteDesktopTouchEvent;
+    type TouchMotionEvent = RemoteDesktopAbsoluteEvent;
+    type TouchCancelEvent = UnusedEvent;
+    type TouchFrameEvent = RemoteDesktopTouchEvent;
+    type TabletToolAxisEvent = UnusedEvent;
+    type TabletToolProximityEvent = UnusedEvent;
+    type TabletToolTipEvent = UnusedEvent;
+    type TabletToolButtonEvent = UnusedEvent;
+
+    type SwitchToggleEvent = UnusedEvent;
+
+    type SpecialEvent = UnusedEvent;
+}
+
+type RemoteDesktopEvent = InputEvent<RemoteDesktopInputBackend>;
+
+impl State {
+    /// Injects input from a remote desktop session into the seat.
+    pub fn process_remote_desktop_input(&mut self, session_id: usize, input: RemoteDesktopInput) {
+        let _span = tracy_client::span!("State::process_remote_desktop_input");
+
+        let time = get_monotonic_time().as_micros() as u64;
+        let mut device = RemoteDesktopDevice {
+            session_id,
+            output: None,
+        };
+
+        match input {
+            RemoteDesktopInput::KeyboardKeycode { keycode, pressed } => {
+                self.process_remote_desktop_key(device, time, keycode + 8, pressed);
+            }
+            RemoteDesktopInput::KeyboardKeysym { keysym, pressed } => {
+                let Some((keycode, mods)) = self.keycode_for_keysym(Keysym::new(keysym)) else {
+                    debug!("no keycode for keysym {keysym:#x}");
+                    return;
+                };
+
+                if pressed {
+                    for code in &mods {
+                        self.process_remote_desktop_key(device.clone(), time, code + 8, true);
+                    }
+                }
+                self.process_remote_desktop_key(device.clone(), time, keycode.raw(), pressed);
+                if !pressed {
+                    for code in mods.iter().rev() {
+                        self.process_remote_desktop_key(device.clone(), time, code + 8, false);
+                    }
+                }
+            }
+            RemoteDesktopInput::PointerButton { button, pressed } => {
+                let state = if pressed {
+                    ButtonState::Pressed
+                } else {
+                    ButtonState::Released
+                };
+                let event = RemoteDesktopPointerButtonEvent {
+                    device,
+                    time,
+                    button,
+                    state,
+                };
+                self.process_input_event(RemoteDesktopEvent::PointerButton { event });
+            }
+            RemoteDesktopInput::PointerAxis {
+                dx,
+                dy,
+                source,
+                finish,
+            } => {
+                // A zero-amount event stops kinetic scrolling.
+                let amount = if finish { (0., 0.) } else { (dx, dy) };
+                let event = RemoteDesktopPointerAxisEvent {
+                    device,
+                    time,
+                    source,
+                    amount,
+                    amount_v120: None,
+                };
+                self.process_input_event(RemoteDesktopEvent::PointerAxis { event });
+            }
+            RemoteDesktopInput::PointerAxisDiscrete { horizontal, steps } => {
+                let steps = f64::from(steps);
+                let (amount, amount_v120) = if horizontal {
+                    ((steps * WHEEL_STEP, 0.), (steps * 120., 0.))
+                } else {
+                    ((0., steps * WHEEL_STEP), (0., steps * 120.))
+                };
+                let event = RemoteDesktopPointerAxisEvent {
+                    device,
+                    time,
+                    source: AxisSource::Wheel,
+                    amount,
+                    amount_v120: Some(amount_v120),
+                };
+                self.process_input_event(RemoteDesktopEvent::PointerAxis { event });
+            }
+            RemoteDesktopInput::PointerMotionRelative { dx, dy } => {
+                let event = RemoteDesktopPointerMotionEvent {
+                    device,
+                    time,
+                    delta: Point::from((dx, dy)),
+                };
+                self.process_input_event(RemoteDesktopEvent::PointerMotion { event });
+            }
+            RemoteDesktopInput::PointerMotionAbsolute { stream, x, y } => {
+                let Some((output, position)) = self.remote_desktop_position(&stream, x, y) else {
+                    return;
+                };
+                device.output = Some(output);
+                let event = RemoteDesktopAbsoluteEvent {
+                    device,
+                    time,
+                    slot: TouchSlot::from(None),
+                    position,
+                };
+                self.process_input_event(RemoteDesktopEvent::PointerMotionAbsolute { event });
+            }
+            RemoteDesktopInput::TouchDown { stream, slot, x, y } => {
+                let Some((output, position)) = self.remote_desktop_position(&stream, x, y) else {
+                    return;
+                };
+                device.output = Some(output);
+                let event = RemoteDesktopAbsoluteEvent {
+                    device: device.clone(),
+                    time,
+                    slot: TouchSlot::from(Some(slot)),
+                    position,
+                };
+                self.process_input_event(RemoteDesktopEvent::TouchDown { event });
+                self.process_remote_desktop_touch_frame(device, time);
+            }
+            RemoteDesktopInput::TouchMotion { stream, slot, x, y } => {
+                let Some((output, position)) = self.remote_desktop_position(&stream, x, y) else {
+                    return;
+                };
+                device.output = Some(output);
+                let event = RemoteDesktopAbsoluteEvent {
+                    device: device.clone(),
+                    time,
+                    slot: TouchSlot::from(Some(slot)),
+                    position,
+                };
+                self.process_input_event(RemoteDesktopEvent::TouchMotion { event });
+                self.process_remote_desktop_touch_frame(device, time);
+            }
+            RemoteDesktopInput::TouchUp { slot } => {
+                let event = RemoteDesktopTouchEvent {
+                    device: device.clone(),
+                    time,
+                    slot: TouchSlot::from(Some(slot)),
+                };
+                self.process_input_event(RemoteDesktopEvent::TouchUp { event });
+                self.process_remote_desktop_touch_frame(device, time);
+            }
+        }
+    }
+
+    fn process_remote_desktop_key(
+        &mut self,
+        device: RemoteDesktopDevice,
+        time: u64,
+        keycode: u32,
+        pressed: bool,
+    ) {
+        let state = if pressed {
+            KeyState::Pressed
+        } else {
+            KeyState::Released
+        };
+        let event = RemoteDesktopKeyboardKeyEvent {
+            device,
+            time,
+            key_code: Keycode::from(keycode),
+            state,
+        };
+        self.process_input_event(RemoteDesktopEvent::Keyboard { event });
+    }
+
+    fn process_remote_desktop_touch_frame(&mut self, device: RemoteDesktopDevice, time: u64) {
+        let event = RemoteDesktopTouchEvent {
+            device,
+            time,
+            slot: TouchSlot::from(None),
+        };
+        self.process_input_event(RemoteDesktopEvent::TouchFrame { event });
+    }
+
+    /// Finds a key producing the keysym in the active layout.
+    ///
+    /// Returns the keycode and the evdev codes of the modifier keys that must be held for it.
+    fn keycode_for_keysym(&mut self, keysym: Keysym) -> Option<(Keycode, Vec<u32>)> {
+        let keyboard = self.niri.seat.get_keyboard().unwrap();
+        keyboard.with_xkb_state(self, |context| {
+            let xkb = context.xkb().lock().unwrap();
+            let layout = xkb.active_layout();
+            // SAFETY: we only read from the keymap.
+            let keymap = unsafe { xkb.keymap() };
+            find_keycode(keymap, layout.0, keysym)
+        })
+    }
+
+    /// Converts a position in stream buffer coordinates into an output and a position normalized
+    /// to the untransformed output.
+    fn remote_desktop_position(
+        &self,
+        stream: &StreamTargetId,
+        x: f64,
+        y: f64,
+    ) -> Option<(Output, Point<f64, Logical>)> {
+        let (name, offset) = match stream {
+            StreamTargetId::Output { name } => (name, Point::from((0., 0.))),
+            StreamTargetId::Area { output, rect } => (output, rect.loc.to_f64()),
+            StreamTargetId::Window { .. } => {
+                debug!("absolute input on window streams is not supported");
+                return None;
+            }
+        };
+
+        let global_space = &self.niri.global_space;
+        let output = global_space.outputs().find(|out| out.name() == *name)?;
+        let position = normalized_position(output, Point::from((x, y)), offset);
+
+        Some((output.clone(), position))
+    }
+}
+
+/// Finds a key producing the keysym in the layout of the keymap.
+///
+/// The lookup runs on a scratch state holding only the returned modifier keys, so it ignores the
+/// modifiers currently held on the seat. This matches a physical keyboard: modifiers that the
+/// remote client holds with separate key events apply on top of the injected key, and modifiers
+/// we press for the keysym are released right after it.
+fn find_keycode(
+    keymap: &xkb::Keymap,
+    layout: xkb::LayoutIndex,
+    keysym: Keysym,
+) -> Option<(Keycode, Vec<u32>)> {
+    // Modifier keys reaching all shift levels of the usual key types, fewest first.
+    let combinations: [&[u32]; 4] = [
+        &[],
+        &[KEY_LEFTSHIFT],
+        &[KEY_RIGHTALT],
+        &[KEY_LEFTSHIFT, KEY_RIGHTALT],
+    ];
+
+    for mods in combinations {
+        // Hold the modifier keys in a scratch state rather than guessing which real modifiers
+        // the layout maps them to. Lock the layout first since key actions depend on it.
+        let mut state = xkb::State::new(keymap);
+        state.update_mask(0, 0, 0, 0, 0, layout);
+        for code in mods {
+            state.update_key(Keycode::from(code + 8), xkb::KeyDirection::Down);
+        }
+
+        let mut rv = None;
+        keymap.key_for_each(|_, keycode| {
+            if rv.is_none() && state.key_get_syms(keycode).contains(&keysym) {
+                rv = Some(keycode);
+            }
+        });
+
+        if let Some(keycode) = rv {
+            return Some((keycode, mods.to_vec()));
+        }
+    }
+
+    None
+}
+
+/// Converts a position in stream buffer coordinates, with the stream at the offset within the
+/// output, into a position normalized to the untransformed output.
+fn normalized_position(
+    output: &Output,
+    pos: Point<f64, Physical>,
+    offset: Point<f64, Logical>,
+) -> Point<f64, Logical> {
+    let scale = output.current_scale().fractional_scale();
+    let pos = pos.to_logical(scale) + offset;
+
+    let size = output_size(output);
+    let transform = output.current_transform();
+    let raw = transform.invert().transform_point_in(pos, &size);
+    let raw_size = transform.invert().transform_size(size);
+    Point::from((raw.x / raw_size.w, raw.y / raw_size.h))
+}
+
+#[cfg(test)]
+mod tests {
+    use smithay::output::{Mode, PhysicalProperties, Scale, Subpixel};
+    use smithay::utils::Transform;
+
+    use super::*;
+
+    const KEY_2: u32 = 3;
+    const KEY_Q: u32 = 16;
+    const KEY_A: u32 = 30;
+
+    fn keymap(layout: &str) -> xkb::Keymap {
+        let context = xkb::Context::new(xkb::CONTEXT_NO_FLAGS);
+        xkb::Keymap::new_from_names(
+            &context,
+            "",
+            "",
+            layout,
+            "",
+            None,
+            xkb::KEYMAP_COMPILE_NO_FLAGS,
+        )
+        .unwrap()
+    }
+
+    fn key(code: u32, mods: &[u32]) -> Option<(Keycode, Vec<u32>)> {
+        Some((Keycode::from(code + 8), mods.to_vec()))
+    }
+
+    fn output(size: (i32, i32), transform: Transform, scale: f64) -> Output {
+        let output = Output::new(
+            String::from("test"),
+            PhysicalProperties {
+                size: (0, 0).into(),
+                subpixel: Subpixel::Unknown,
+                make: String::new(),
+                model: String::new(),
+            },
+        );
+        let mode = Mode {
+            size: size.into(),
+            refresh: 60_000,
+        };
+        output.change_current_state(
+            Some(mode),
+            Some(transform),
+            Some(Scale::Fractional(scale)),
+            None,
+        );
+        output
+    }
+
+    // Maps a normalized position back into the output the same way as absolute input events.
+    fn output_position(output: &Output, pos: Point<f64, Logical>) -> Point<f64, Logical> {
+        let transform = output.current_transform();
+        let size = transform.invert().transform_size(output_size(output));
+        let pos = Point::from((pos.x * size.w, pos.y * size.h));
+        transform.transform_point_in(pos, &size)
+    }
+
+    fn assert_close(a: Point<f64, Logical>, b: Point<f64, Logical>) {
+        assert!(
+            (a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9,
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn keysym_without_modifiers() {
+        let keymap = keymap("us");
+        assert_eq!(find_keycode(&keymap, 0, Keysym::a), key(KEY_A, &[]));
+    }
+
+    #[test]
+    fn keysym_needing_shift() {
+        let keymap = keymap("us");
+        assert_eq!(
+            find_keycode(&keymap, 0, Keysym::A),
+            key(KEY_A, &[KEY_LEFTSHIFT])
+        );
+        assert_eq!(
+            find_keycode(&keymap, 0, Keysym::at),
+            key(KEY_2, &[KEY_LEFTSHIFT])
+        );
+    }
+
+    #[test]
+    fn keysym_needing_altgr() {
+        let keymap = keymap("de");
+        assert_eq!(
+            find_keycode(&keymap, 0, Keysym::at),
+            key(KEY_Q, &[KEY_RIGHTALT])
+        );
+        assert_eq!(
+            find_keycode(&keymap, 0, Keysym::Greek_OMEGA),
+            key(KEY_Q, &[KEY_LEFTSHIFT, KEY_RIGHTALT])
+        );
+    }
+
+    #[test]
+    fn keysym_in_second_layout() {
+        let keymap = keymap("us,de");
+        assert_eq!(
+            find_keycode(&keymap, 0, Keysym::at),
+            key(KEY_2, &[KEY_LEFTSHIFT])
+        );
+        assert_eq!(
+            find_keycode(&keymap, 1, Keysym::at),
+            key(KEY_Q, &[KEY_RIGHTALT])
+        );
+    }
+
+    #[test]
+    fn missing_keysym() {
+        let keymap = keymap("us");
+        assert_eq!(find_keycode(&keymap, 0, Keysym::Greek_OMEGA), None);
+    }
+
+    #[test]
+    fn position_on_output() {
+        let output = output((1920, 1080), Transform::Normal, 1.);
+        let pos = normalized_position(&output, Point::from((960., 270.)), Point::from((0., 0.)));
+        assert_close(pos, Point::from((0.5, 0.25)));
+    }
+
+    #[test]
+    fn position_at_fractional_scale() {
+        // 2048×1280 logical.
+        let output = output((2560, 1600), Transform::Normal, 1.25);
+        let pos = normalized_position(&output, Point::from((1280., 400.)), Point::from((0., 0.)));
+        assert_close(pos, Point::from((0.5, 0.25)));
+        assert_close(output_position(&output, pos), Point::from((1024., 320.)));
+    }
+
+    #[test]
+    fn position_on_rotated_output() {
+        // 1080×1920 logical.
+        let output = output((1920, 1080), Transform::_90, 1.);
+
+        let pos = normalized_position(&output, Point::from((540., 960.)), Point::from((0., 0.)));
+        assert_close(pos, Point::from((0.5, 0.5)));
+
+        // The stream is upright, so the position must land on the same point in the output.
+        for (x, y) in [(270., 480.), (0., 1920.), (1080., 0.), (100., 1500.)] {
+            let pos = normalized_position(&output, Point::from((x, y)), Point::from((0., 0.)));
+            assert!((0. ..=1.).contains(&pos.x) && (0. ..=1.).contains(&pos.y));
+            assert_close(output_position(&output, pos), Point::from((x, y)));
+        }
+    }
+
+    #[test]
+    fn position_in_area() {
+        // 960×540 logical, with the area stream at (100, 50).
+        let output = output((1920, 1080), Transform::Normal, 2.);
+        let offset = Point::from((100., 50.));
+
+        let pos = normalized_position(&output, Point::from((0., 0.)), offset);
+        assert_close(pos, Point::from((100. / 960., 50. / 540.)));
+
+        let pos = normalized_position(&output, Point::from((200., 100.)), offset);
+        assert_close(output_position(&output, pos), Point::from((200., 100.)));
+    }
+
+    #[test]
+    fn position_in_area_on_rotated_output() {
+        // 540×960 logical.
+        let output = output((1920, 1080), Transform::_90, 2.);
+        let offset = Point::from((100., 200.));
+
+        let pos = normalized_position(&output, Point::from((50., 60.)), offset);
+        assert_close(output_position(&output, pos), Point::from((125., 230.)));
+    }
+}
@@ -81,13 +81,26 @@ impl PointerGrab<State> for SpatialMovementGrab {
         handle.motion(data, None, event);
 
         let timestamp = Duration::from_millis(u64::from(event.time));
-        let delta = event.location - self.last_location;
+        let mut delta = event.location - self.last_location;
         self.last_location = event.location;
 
         let layout = &mut data.niri.layout;
+
+        // On a vertical scrolling layout, vertical movement scrolls the view, and horizontal
+        // movement switches workspaces.
+        let transposed = layout
+            .find_workspace_by_id(self.workspace_id)
+            .is_some_and(|(_, ws)| ws.is_scrolling_vertical());
+        if transposed {
+            delta = Point::from((delta.y, delta.x));
+        }
+
         let res = match self.gesture {
             GestureState::Recognizing => {
-                let c = event.location - self.start_data.location;
+                let mut c = event.location - self.start_data.location;
+                if transposed {
+                    c = Point::from((c.y, c.x));
+                }
 
                 // Check if the gesture moved far enough to decide. Threshold copied from GTK 4.
                 if c.x * c.x + c.y * c.y >= 8. * 8. {
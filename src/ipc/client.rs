@@ -6,8 +6,8 @@ use anyhow::{anyhow, bail, Context};
 use niri_config::OutputName;
 use niri_ipc::socket::Socket;
 use niri_ipc::{
-    Event, KeyboardLayouts, LogicalOutput, Mode, Output, OutputConfigChanged, Overview, Request,
-    Response, Transform, Window,
+    Cast, CastTarget, Event, KeyboardLayouts, LayoutKind, LogicalOutput, Mode, Output,
+    OutputConfigChanged, Overview, Request, Response, Transform, Window,
 };
 use serde_json::json;
 
@@ -34,6 +34,23 @@ pub fn handle_msg(msg: Msg, json: bool) -> anyhow::Result<()> {
         Msg::EventStream => Request::EventStream,
         Msg::RequestError => Request::ReturnError,
         Msg::OverviewState => Request::OverviewState,
+        Msg::WindowIcon { id, path, size } => {
+            // The path is interpreted by the compositor, so make it absolute.
+            let path = match path {
+                Some(path) => {
+                    let path = std::path::absolute(path).context("error resolving icon path")?;
+                    let path = path.into_os_string().into_string();
+                    Some(path.map_err(|_| anyhow!("icon path must be valid UTF-8"))?)
+                }
+                None => None,
+            };
+            Request::WindowIcon {
+                id: *id,
+                path,
+                size: *size,
+            }
+        }
+        Msg::Casts => Request::Casts,
     };
 
     let mut socket = Socket::connect().context("error connecting to the niri socket")?;
@@ -369,7 +386,12 @@ pub fn handle_msg(msg: Msg, json: bool) -> anyhow::Result<()> {
                 } else {
                     String::new()
                 };
-                println!("{is_active}{idx}{name}");
+                let layout_kind = match ws.layout_kind {
+                    LayoutKind::Scrolling => "",
+                    LayoutKind::MasterStack => " (master-stack)",
+                    LayoutKind::Grid => " (grid)",
+                };
+                println!("{is_active}{idx}{name}{layout_kind}");
             }
         }
         Msg::KeyboardLayouts => {
@@ -456,6 +478,22 @@ pub fn handle_msg(msg: Msg, json: bool) -> anyhow::Result<()> {
                     Event::OverviewOpenedOrClosed { is_open: opened } => {
                         println!("Overview toggled: {opened}");
                     }
+                    Event::Bell { window_id } => {
+                        if let Some(id) = window_id {
+                            println!("Bell rang for window {id}");
+                        } else {
+                            println!("Bell rang");
+                        }
+                    }
+                    Event::CastsChanged { casts } => {
+                        println!("Casts changed: {casts:?}");
+                    }
+                    Event::CastStartedOrChanged { cast } => {
+                        println!("Cast started or changed: {cast:?}");
+                    }
+                    Event::CastStopped { stream_id } => {
+                        println!("Cast stopped: {stream_id}");
+                    }
                 }
             }
         }
@@ -478,6 +516,71 @@ pub fn handle_msg(msg: Msg, json: bool) -> anyhow::Result<()> {
                 println!("Overview is closed.");
             }
         }
+        Msg::WindowIcon { path, .. } => {
+            let Response::WindowIcon(response) = response else {
+                bail!("unexpected response: expected WindowIcon, got {response:?}");
+            };
+
+            if json {
+                let response =
+                    serde_json::to_string(&response).context("error formatting response")?;
+                println!("{response}");
+                return Ok(());
+            }
+
+            let Some(icon) = response else {
+                println!("The window has no icon.");
+                return Ok(());
+            };
+
+            if let Some(name) = &icon.name {
+                println!("Icon name: \"{name}\"");
+            } else {
+                println!("Icon name: (unset)");
+            }
+
+            if icon.buffer_sizes.is_empty() {
+                println!("Icon buffers: (none)");
+            } else {
+                let sizes: Vec<_> = icon
+                    .buffer_sizes
+                    .iter()
+                    .map(|size| format!("{size}x{size}"))
+                    .collect();
+                println!("Icon buffers: {}", sizes.join(", "));
+            }
+
+            if let Some(path) = path {
+                if let Some(size) = icon.saved_size {
+                    println!("Saved {size}x{size} icon to {}", path.display());
+                } else {
+                    println!("No icon buffer to save.");
+                }
+            }
+        }
+        Msg::Casts => {
+            let Response::Casts(mut casts) = response else {
+                bail!("unexpected response: expected Casts, got {response:?}");
+            };
+
+            if json {
+                let casts = serde_json::to_string(&casts).context("error formatting response")?;
+                println!("{casts}");
+                return Ok(());
+            }
+
+            if casts.is_empty() {
+                println!("No active screencasts.");
+                return Ok(());
+            }
+
+            casts.sort_unstable_by(|a, b| a.stream_id.cmp(&b.stream_id));
+
+            for cast in casts {
+                print_cast(&cast);
+                println!();
+            }
+        }
     }
 
     Ok(())
@@ -579,6 +682,52 @@ fn print_output(output: Output) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn print_cast(cast: &Cast) {
+    let active = if cast.is_active { "" } else { " (paused)" };
+    println!("Cast stream ID {}:{active}", cast.stream_id);
+    println!("  Session ID: {}", cast.session_id);
+
+    match &cast.target {
+        CastTarget::Nothing {} => println!("  Target: (nothing)"),
+        CastTarget::Output { name } => println!("  Target: output \"{name}\""),
+        CastTarget::Area {
+            output,
+            x,
+            y,
+            width,
+            height,
+        } => println!("  Target: area {width}x{height} at {x},{y} on output \"{output}\""),
+        CastTarget::Window { id } => println!("  Target: window ID {id}"),
+        CastTarget::Workspace { id } => println!("  Target: workspace ID {id}"),
+    }
+
+    println!(
+        "  Is dynamic target: {}",
+        if cast.is_dynamic_target { "yes" } else { "no" }
+    );
+
+    let (width, height) = cast.size;
+    println!("  Size: {width}x{height}");
+
+    if let Some(name) = &cast.client_bus_name {
+        println!("  Client bus name: {name}");
+    } else {
+        println!("  Client bus name: (unknown)");
+    }
+
+    if let Some(pid) = cast.client_pid {
+        println!("  Client PID: {pid}");
+    } else {
+        println!("  Client PID: (unknown)");
+    }
+
+    if let Some(app_id) = &cast.client_app_id {
+        println!("  Client app ID: \"{app_id}\"");
+    } else {
+        println!("  Client app ID: (unknown)");
+    }
+}
+
 fn print_window(window: &Window) {
     let focused = if window.is_focused { " (focused)" } else { "" };
     let urgent = if window.is_urgent { " (urgent)" } else { "" };
@@ -600,6 +749,22 @@ fn print_window(window: &Window) {
         "  Is floating: {}",
         if window.is_floating { "yes" } else { "no" }
     );
+    println!(
+        "  Is pinned: {}",
+        if window.is_pinned { "yes" } else { "no" }
+    );
+    println!(
+        "  Is kept above: {}",
+        if window.is_kept_above { "yes" } else { "no" }
+    );
+    println!(
+        "  Is kept below: {}",
+        if window.is_kept_below { "yes" } else { "no" }
+    );
+    println!(
+        "  Is minimized: {}",
+        if window.is_minimized { "yes" } else { "no" }
+    );
 
     if let Some(pid) = window.pid {
         println!("  PID: {pid}");
@@ -612,4 +777,8 @@ fn print_window(window: &Window) {
     } else {
         println!("  Workspace ID: (none)");
     }
+
+    if let Some(icon_name) = &window.icon_name {
+        println!("  Icon name: \"{icon_name}\"");
+    }
 }
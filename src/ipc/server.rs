@@ -5,7 +5,7 @@ use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
-use std::{env, io, process};
+use std::{env, io, process, thread};
 
 use anyhow::Context;
 use async_channel::{Receiver, Sender, TrySendError};
@@ -17,9 +17,10 @@ use futures_util::{select_biased, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, Fu
 use niri_config::OutputName;
 use niri_ipc::state::{EventStreamState, EventStreamStatePart as _};
 use niri_ipc::{
-    Event, KeyboardLayouts, OutputConfigChanged, Overview, Reply, Request, Response, Workspace,
+    Event, KeyboardLayouts, OutputConfigChanged, Overview, Reply, Request, Response, WindowIcon,
+    Workspace,
 };
-use smithay::desktop::layer_map_for_output;
+use smithay::desktop::{layer_map_for_output, Window};
 use smithay::input::pointer::{
     CursorIcon, CursorImageStatus, Focus, GrabStartData as PointerGrabStartData,
 };
@@ -32,8 +33,12 @@ use smithay::wayland::shell::wlr_layer::{KeyboardInteractivity, Layer};
 use crate::backend::IpcOutputMap;
 use crate::input::pick_window_grab::PickWindowGrab;
 use crate::layout::workspace::WorkspaceId;
+#[cfg(feature = "xdp-gnome-screencast")]
+use crate::niri::CastTarget;
 use crate::niri::State;
-use crate::utils::{version, with_toplevel_role};
+#[cfg(feature = "xdp-gnome-screencast")]
+use crate::pw_utils::Cast;
+use crate::utils::{version, with_toplevel_role, write_png_rgba8};
 use crate::window::Mapped;
 
 // If an event stream client fails to read events fast enough that we accumulate more than this
@@ -440,11 +445,66 @@ async fn process(ctx: &ClientCtx, request: Request) -> Reply {
             Response::FocusedOutput(output)
         }
         Request::EventStream => Response::Handled,
+        Request::Casts => {
+            let state = ctx.event_stream_state.borrow();
+            let casts = state.casts.casts.values().cloned().collect();
+            Response::Casts(casts)
+        }
         Request::OverviewState => {
             let state = ctx.event_stream_state.borrow();
             let is_open = state.overview.is_open;
             Response::OverviewState(Overview { is_open })
         }
+        Request::WindowIcon { id, path, size } => {
+            let (tx, rx) = async_channel::bounded(1);
+            ctx.event_loop.insert_idle(move |state| {
+                let mut windows = state.niri.layout.windows();
+                let icon = windows
+                    .find(|(_, m)| m.id().get() == id)
+                    .map(|(_, m)| m.icon().cloned());
+                let _ = tx.send_blocking(icon);
+            });
+            let result = rx.recv().await;
+            let icon = result.map_err(|_| String::from("error getting window icon"))?;
+            let icon = icon.ok_or_else(|| String::from("window not found"))?;
+            let Some(icon) = icon else {
+                return Ok(Response::WindowIcon(None));
+            };
+
+            let buffer_sizes = icon.buffers.iter().map(|b| b.size() as u32).collect();
+
+            let mut saved_size = None;
+            if let Some(path) = path {
+                let size = size.map_or(i32::MAX, |size| size.min(i32::MAX as u32) as i32);
+                if let Some((_, buffer)) = icon.best_buffer(size) {
+                    let size = buffer.size() as u32;
+                    let pixels = buffer.to_rgba8();
+
+                    // Encoding and writing can take a while, so do it off the IPC executor.
+                    let (tx, rx) = async_channel::bounded(1);
+                    thread::spawn(move || {
+                        let res = (|| {
+                            let mut png = vec![];
+                            write_png_rgba8(&mut png, size, size, &pixels)
+                                .map_err(|err| format!("error encoding icon image: {err}"))?;
+                            std::fs::write(&path, png)
+                                .map_err(|err| format!("error saving icon image: {err}"))
+                        })();
+                        let _ = tx.send_blocking(res);
+                    });
+                    let result = rx.recv().await;
+                    result.map_err(|_| String::from("error saving icon image"))??;
+
+                    saved_size = Some(size);
+                }
+            }
+
+            Response::WindowIcon(Some(WindowIcon {
+                name: icon.name.clone(),
+                buffer_sizes,
+                saved_size,
+            }))
+        }
     };
 
     Ok(response)
@@ -486,10 +546,55 @@ fn make_ipc_window(mapped: &Mapped, workspace_id: Option<WorkspaceId>) -> niri_i
         workspace_id: workspace_id.map(|id| id.get()),
         is_focused: mapped.is_focused(),
         is_floating: mapped.is_floating(),
+        is_pinned: mapped.is_pinned(),
+        is_kept_above: mapped.is_kept_above(),
+        is_kept_below: mapped.is_kept_below(),
+        is_minimized: mapped.is_minimized(),
         is_urgent: mapped.is_urgent(),
+        icon_name: mapped.icon().and_then(|icon| icon.name.clone()),
     })
 }
 
+#[cfg(feature = "xdp-gnome-screencast")]
+fn make_ipc_cast(cast: &Cast) -> niri_ipc::Cast {
+    let size = cast.size();
+    niri_ipc::Cast {
+        stream_id: cast.stream_id as u64,
+        session_id: cast.session_id as u64,
+        target: make_ipc_cast_target(&cast.target),
+        is_dynamic_target: cast.dynamic_target,
+        is_active: cast.is_active.get(),
+        size: (size.w, size.h),
+        client_bus_name: cast.client.sender.clone(),
+        client_pid: cast.client.pid,
+        client_app_id: cast.client.app_id.clone(),
+    }
+}
+
+#[cfg(feature = "xdp-gnome-screencast")]
+fn make_ipc_cast_target(target: &CastTarget) -> niri_ipc::CastTarget {
+    let target = match target {
+        CastTarget::Nothing => None,
+        CastTarget::Output(output) => output.upgrade().map(|output| niri_ipc::CastTarget::Output {
+            name: output.name(),
+        }),
+        CastTarget::Area { output, rect } => {
+            output.upgrade().map(|output| niri_ipc::CastTarget::Area {
+                output: output.name(),
+                x: rect.loc.x,
+                y: rect.loc.y,
+                width: rect.size.w,
+                height: rect.size.h,
+            })
+        }
+        CastTarget::Window { id } => Some(niri_ipc::CastTarget::Window { id: *id }),
+        CastTarget::Workspace { id } => Some(niri_ipc::CastTarget::Workspace { id: *id }),
+    };
+
+    // Casts of a removed output are about to be stopped.
+    target.unwrap_or(niri_ipc::CastTarget::Nothing {})
+}
+
 impl State {
     pub fn ipc_keyboard_layouts_changed(&mut self) {
         let keyboard = self.niri.seat.get_keyboard().unwrap();
@@ -577,6 +682,7 @@ impl State {
             if ipc_ws.idx != u8::try_from(ws_idx + 1).unwrap_or(u8::MAX)
                 || ipc_ws.name.as_ref() != ws.name()
                 || ipc_ws.output.as_ref() != output_name
+                || ipc_ws.layout_kind != ws.layout_kind()
             {
                 need_workspaces_changed = true;
                 break;
@@ -631,6 +737,7 @@ impl State {
                         is_active: mon.is_some_and(|mon| mon.active_workspace_idx() == ws_idx),
                         is_focused: Some(id) == focused_ws_id,
                         active_window_id: ws.active_window().map(|win| win.id().get()),
+                        layout_kind: ws.layout_kind(),
                     }
                 })
                 .collect();
@@ -677,6 +784,13 @@ impl State {
             let workspace_id = ws_id.map(|id| id.get());
             let mut changed =
                 ipc_win.workspace_id != workspace_id || ipc_win.is_floating != mapped.is_floating();
+            changed |= ipc_win.is_pinned != mapped.is_pinned();
+            changed |= ipc_win.is_kept_above != mapped.is_kept_above();
+            changed |= ipc_win.is_kept_below != mapped.is_kept_below();
+            changed |= ipc_win.is_minimized != mapped.is_minimized();
+
+            let icon_name = mapped.icon().and_then(|icon| icon.name.as_ref());
+            changed |= ipc_win.icon_name.as_ref() != icon_name;
 
             changed |= with_toplevel_role(mapped.toplevel(), |role| {
                 ipc_win.title != role.title || ipc_win.app_id != role.app_id
@@ -722,6 +836,28 @@ impl State {
         }
     }
 
+    /// Sends a window update even if none of its IPC fields changed.
+    pub fn ipc_window_changed(&mut self, window: &Window) {
+        let Some(server) = &self.niri.ipc_server else {
+            return;
+        };
+
+        let mut ipc_window = None;
+        self.niri.layout.with_windows(|mapped, _, ws_id| {
+            if mapped.window == *window {
+                ipc_window = Some(make_ipc_window(mapped, ws_id));
+            }
+        });
+        let Some(window) = ipc_window else {
+            return;
+        };
+
+        let mut state = server.event_stream_state.borrow_mut();
+        let event = Event::WindowOpenedOrChanged { window };
+        state.windows.apply(event.clone());
+        server.send_event(event);
+    }
+
     pub fn ipc_refresh_overview(&mut self) {
         let Some(server) = &self.niri.ipc_server else {
             return;
@@ -739,4 +875,143 @@ impl State {
         state.apply(event.clone());
         server.send_event(event);
     }
+
+    #[cfg(not(feature = "xdp-gnome-screencast"))]
+    pub fn ipc_refresh_casts(&mut self) {}
+
+    #[cfg(feature = "xdp-gnome-screencast")]
+    pub fn ipc_refresh_casts(&mut self) {
+        let Some(server) = &self.niri.ipc_server else {
+            return;
+        };
+
+        let _span = tracy_client::span!("State::ipc_refresh_casts");
+
+        let mut state = server.event_stream_state.borrow_mut();
+        let state = &mut state.casts;
+
+        let mut events = Vec::new();
+
+        // Check for stopped casts.
+        let mut seen = HashSet::new();
+        for cast in &self.niri.casts {
+            seen.insert(cast.stream_id as u64);
+        }
+
+        for id in state.casts.keys() {
+            if !seen.contains(id) {
+                events.push(Event::CastStopped { stream_id: *id });
+            }
+        }
+
+        // Check for started and changed casts.
+        for cast in &self.niri.casts {
+            let cast = make_ipc_cast(cast);
+            if state.casts.get(&cast.stream_id) != Some(&cast) {
+                events.push(Event::CastStartedOrChanged { cast });
+            }
+        }
+
+        for event in events {
+            state.apply(event.clone());
+            server.send_event(event);
+        }
+    }
+
+    pub fn ipc_bell(&mut self, window_id: Option<u64>) {
+        let Some(server) = &self.niri.ipc_server else {
+            return;
+        };
+
+        // The bell is a one-off event, so there's no state to update.
+        server.send_event(Event::Bell { window_id });
+    }
+}
+
+#[cfg(all(test, feature = "xdp-gnome-screencast"))]
+mod tests {
+    use smithay::output::{Output, PhysicalProperties, Subpixel};
+    use smithay::utils::Rectangle;
+
+    use super::*;
+
+    fn output(name: &str) -> Output {
+        Output::new(
+            String::from(name),
+            PhysicalProperties {
+                size: (0, 0).into(),
+                subpixel: Subpixel::Unknown,
+                make: String::new(),
+                model: String::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn cast_target_nothing() {
+        assert_eq!(
+            make_ipc_cast_target(&CastTarget::Nothing),
+            niri_ipc::CastTarget::Nothing {}
+        );
+    }
+
+    #[test]
+    fn cast_target_output() {
+        let output = output("DP-1");
+        let target = CastTarget::Output(output.downgrade());
+        assert_eq!(
+            make_ipc_cast_target(&target),
+            niri_ipc::CastTarget::Output {
+                name: String::from("DP-1")
+            }
+        );
+
+        // A removed output leaves nothing to cast.
+        drop(output);
+        assert_eq!(
+            make_ipc_cast_target(&target),
+            niri_ipc::CastTarget::Nothing {}
+        );
+    }
+
+    #[test]
+    fn cast_target_area() {
+        let output = output("DP-1");
+        let target = CastTarget::Area {
+            output: output.downgrade(),
+            rect: Rectangle::new((10, 20).into(), (300, 400).into()),
+        };
+        assert_eq!(
+            make_ipc_cast_target(&target),
+            niri_ipc::CastTarget::Area {
+                output: String::from("DP-1"),
+                x: 10,
+                y: 20,
+                width: 300,
+                height: 400,
+            }
+        );
+
+        drop(output);
+        assert_eq!(
+            make_ipc_cast_target(&target),
+            niri_ipc::CastTarget::Nothing {}
+        );
+    }
+
+    #[test]
+    fn cast_target_window() {
+        assert_eq!(
+            make_ipc_cast_target(&CastTarget::Window { id: 5 }),
+            niri_ipc::CastTarget::Window { id: 5 }
+        );
+    }
+
+    #[test]
+    fn cast_target_workspace() {
+        assert_eq!(
+            make_ipc_cast_target(&CastTarget::Workspace { id: 3 }),
+            niri_ipc::CastTarget::Workspace { id: 3 }
+        );
+    }
 }
@@ -12,6 +12,9 @@ use super::ResolvedLayerRules;
 use crate::animation::Clock;
 use crate::layout::shadow::Shadow;
 use crate::niri_render_elements;
+use crate::render_helpers::color_transform::{
+    color_transform_surface_elements, ColorTransformRenderElement,
+};
 use crate::render_helpers::renderer::NiriRenderer;
 use crate::render_helpers::shadow::ShadowRenderElement;
 use crate::render_helpers::solid_color::{SolidColorBuffer, SolidColorRenderElement};
@@ -45,6 +48,7 @@ pub struct MappedLayer {
 niri_render_elements! {
     LayerSurfaceRenderElement<R> => {
         Wayland = WaylandSurfaceRenderElement<R>,
+        ColorTransform = ColorTransformRenderElement<WaylandSurfaceRenderElement<R>>,
         SolidColor = SolidColorRenderElement,
         Shadow = ShadowRenderElement,
     }
@@ -189,17 +193,22 @@ impl MappedLayer {
                 // Layer surfaces don't have extra geometry like windows.
                 let offset = popup_offset - popup.geometry().loc;
 
-                rv.popups.extend(render_elements_from_surface_tree(
+                let elements = render_elements_from_surface_tree(
                     renderer,
                     popup.wl_surface(),
                     (buf_pos + offset.to_f64()).to_physical_precise_round(scale),
                     scale,
                     alpha,
                     Kind::Unspecified,
+                );
+                rv.popups.extend(color_transform_surface_elements(
+                    renderer,
+                    popup.wl_surface(),
+                    elements,
                 ));
             }
 
-            rv.normal = render_elements_from_surface_tree(
+            let elements = render_elements_from_surface_tree(
                 renderer,
                 surface,
                 buf_pos.to_physical_precise_round(scale),
@@ -207,6 +216,7 @@ impl MappedLayer {
                 alpha,
                 Kind::Unspecified,
             );
+            rv.normal = color_transform_surface_elements(renderer, surface, elements).collect();
         }
 
         let location = location.to_physical_precise_round(scale).to_logical(scale);
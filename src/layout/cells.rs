@@ -0,0 +1,196 @@
+//! Cell arrangement for the non-scrolling layout kinds.
+//!
+//! In the master-stack and grid layout kinds, every column of a scrolling space fills one cell of
+//! the working area, and the view stays put. The space keeps a [`Cells`] for these kinds and asks
+//! it where columns go, which column neighbors which, and where a dropped window would land.
+
+use std::cmp::min;
+use std::iter;
+
+use niri_ipc::LayoutKind;
+use smithay::utils::{Logical, Point, Rectangle, Size};
+
+/// Direction to a neighboring column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Cells of a space in a non-scrolling layout kind.
+#[derive(Debug)]
+pub struct Cells {
+    /// Layout kind, never [`LayoutKind::Scrolling`].
+    kind: LayoutKind,
+
+    /// Cell for every column, in column order.
+    ///
+    /// Neighboring cells overlap by one gap, so that columns filling their cells have a single gap
+    /// between each other.
+    rects: Vec<Rectangle<f64, Logical>>,
+
+    /// Gaps the cells were computed with.
+    gaps: f64,
+}
+
+impl Cells {
+    /// Creates cells for the layout kind, or `None` for the scrolling layout.
+    pub fn new(kind: LayoutKind) -> Option<Self> {
+        (kind != LayoutKind::Scrolling).then_some(Self {
+            kind,
+            rects: Vec::new(),
+            gaps: 0.,
+        })
+    }
+
+    pub fn kind(&self) -> LayoutKind {
+        self.kind
+    }
+
+    /// Recomputes the cells for a number of columns in the working area.
+    pub fn update(&mut self, area: Rectangle<f64, Logical>, gaps: f64, count: usize) {
+        self.rects = compute_cells(self.kind, area, gaps, count);
+        self.gaps = gaps;
+    }
+
+    pub fn get(&self, idx: usize) -> Option<Rectangle<f64, Logical>> {
+        self.rects.get(idx).copied()
+    }
+
+    /// Returns the index of the cell under the position, or one past the last cell.
+    pub fn index_at(&self, pos: Point<f64, Logical>) -> usize {
+        self.rects
+            .iter()
+            .position(|cell| cell.contains(pos))
+            .unwrap_or(self.rects.len())
+    }
+
+    /// Returns the area that a new column inserted at `idx` will take.
+    ///
+    /// The new column takes the cell of the column currently there, or the whole working area if
+    /// there's no such column.
+    pub fn insert_hint_area(
+        &self,
+        idx: usize,
+        working_area: Rectangle<f64, Logical>,
+    ) -> Rectangle<f64, Logical> {
+        let gaps = self.gaps;
+        let area = self.get(idx).unwrap_or(working_area);
+        let loc = area.loc + Point::from((gaps, gaps));
+        let size = Size::from((
+            f64::max(area.size.w - gaps * 2., 0.),
+            f64::max(area.size.h - gaps * 2., 0.),
+        ));
+        Rectangle::new(loc, size)
+    }
+
+    /// Returns the cell closest to the one at `idx` in the given direction.
+    pub fn neighbor(&self, idx: usize, direction: Direction) -> Option<usize> {
+        let active = self.get(idx)?;
+        let gaps = self.gaps;
+
+        let overlaps =
+            |a: f64, a_len: f64, b: f64, b_len: f64| a < b + b_len - gaps && b < a + a_len - gaps;
+        let center = |rect: Rectangle<f64, Logical>| {
+            Point::<f64, Logical>::from((
+                rect.loc.x + rect.size.w / 2.,
+                rect.loc.y + rect.size.h / 2.,
+            ))
+        };
+        let active_center = center(active);
+
+        self.rects
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != idx)
+            .filter_map(|(other, cell)| {
+                let center = center(*cell);
+
+                let (dist, side) = match direction {
+                    Direction::Left | Direction::Right => {
+                        if !overlaps(cell.loc.y, cell.size.h, active.loc.y, active.size.h) {
+                            return None;
+                        }
+                        (center.x - active_center.x, center.y - active_center.y)
+                    }
+                    Direction::Up | Direction::Down => {
+                        if !overlaps(cell.loc.x, cell.size.w, active.loc.x, active.size.w) {
+                            return None;
+                        }
+                        (center.y - active_center.y, center.x - active_center.x)
+                    }
+                };
+
+                let dist = match direction {
+                    Direction::Left | Direction::Up => -dist,
+                    Direction::Right | Direction::Down => dist,
+                };
+                (dist > 0.).then_some((other, (dist, side.abs())))
+            })
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(other, _)| other)
+    }
+
+    #[cfg(test)]
+    pub fn verify_invariants(&self, area: Rectangle<f64, Logical>, gaps: f64, count: usize) {
+        assert_ne!(self.kind, LayoutKind::Scrolling);
+        assert_eq!(self.gaps, gaps);
+        assert_eq!(
+            self.rects,
+            compute_cells(self.kind, area, gaps, count),
+            "cells must be up to date"
+        );
+    }
+}
+
+fn compute_cells(
+    kind: LayoutKind,
+    area: Rectangle<f64, Logical>,
+    gaps: f64,
+    count: usize,
+) -> Vec<Rectangle<f64, Logical>> {
+    // Returns the position and length of the part at idx out of the equal parts of a span.
+    let split = |start: f64, len: f64, parts: usize, idx: usize| {
+        let step = (len - gaps) / parts as f64;
+        (start + step * idx as f64, step + gaps)
+    };
+    let cell = |(x, w): (f64, f64), (y, h): (f64, f64)| {
+        Rectangle::new(Point::from((x, y)), Size::from((w, h)))
+    };
+    let full_x = (area.loc.x, area.size.w);
+    let full_y = (area.loc.y, area.size.h);
+
+    match kind {
+        LayoutKind::Scrolling => Vec::new(),
+        LayoutKind::MasterStack => {
+            if count <= 1 {
+                return vec![area; count];
+            }
+
+            let master = cell(split(full_x.0, full_x.1, 2, 0), full_y);
+            let stack_x = split(full_x.0, full_x.1, 2, 1);
+            let stack = (0..count - 1).map(|idx| {
+                let y = split(full_y.0, full_y.1, count - 1, idx);
+                cell(stack_x, y)
+            });
+            iter::once(master).chain(stack).collect()
+        }
+        LayoutKind::Grid => {
+            let cols = (count as f64).sqrt().ceil() as usize;
+            let rows = count.div_ceil(cols.max(1));
+
+            (0..count)
+                .map(|idx| {
+                    let row = idx / cols;
+                    // The last row can have fewer columns that stretch to fill it.
+                    let in_row = min(cols, count - row * cols);
+                    let x = split(full_x.0, full_x.1, in_row, idx % cols);
+                    let y = split(full_y.0, full_y.1, rows, row);
+                    cell(x, y)
+                })
+                .collect()
+        }
+    }
+}
@@ -184,7 +184,7 @@ impl ClosingWindow {
                 let elem = RescaleRenderElement::from_element(elem, Point::from((0, 0)), 1.);
 
                 let mut location = self.pos + offset;
-                location.x -= view_rect.loc.x;
+                location -= view_rect.loc;
                 let elem = RelocateRenderElement::from_element(
                     elem,
                     location.to_physical_precise_round(scale),
@@ -263,7 +263,7 @@ impl ClosingWindow {
         );
 
         let mut location = self.pos + offset;
-        location.x -= view_rect.loc.x;
+        location -= view_rect.loc;
         let elem = RelocateRenderElement::from_element(
             elem,
             location.to_physical_precise_round(scale),
@@ -3,7 +3,7 @@ use std::iter::zip;
 use std::rc::Rc;
 
 use niri_config::{PresetSize, RelativeTo};
-use niri_ipc::{PositionChange, SizeChange};
+use niri_ipc::{FloatingTileArea, PositionChange, SizeChange};
 use smithay::backend::renderer::gles::GlesRenderer;
 use smithay::utils::{Logical, Point, Rectangle, Scale, Serial, Size};
 
@@ -28,6 +28,9 @@ use crate::window::ResolvedWindowRules;
 /// By how many logical pixels the directional move commands move floating windows.
 pub const DIRECTIONAL_MOVE_PX: f64 = 50.;
 
+/// How close to the view edge in logical pixels the pointer must be to trigger edge tiling.
+const EDGE_TILING_THRESHOLD: f64 = 8.;
+
 /// Space for floating windows.
 #[derive(Debug)]
 pub struct FloatingSpace<W: LayoutElement> {
@@ -392,6 +395,9 @@ impl<W: LayoutElement> FloatingSpace<W> {
     fn add_tile_at(&mut self, mut idx: usize, mut tile: Tile<W>, activate: bool) {
         tile.update_config(self.view_size, self.scale, self.options.clone());
 
+        // Tiles go below the ones in higher stacking layers, e.g. below the ones kept above.
+        idx = max(idx, self.top_idx_for(&tile));
+
         // Restore the previous floating window size, and in case the tile is fullscreen,
         // unfullscreen it.
         let floating_size = tile.floating_window_size;
@@ -556,13 +562,82 @@ impl<W: LayoutElement> FloatingSpace<W> {
             return false;
         };
 
-        self.raise_window(idx, 0);
+        // Windows are raised only up to the top of their stacking layer.
+        let to_idx = self.top_idx_for(&self.tiles[idx]);
+
+        self.raise_window(idx, to_idx);
         self.active_window_id = Some(id.clone());
-        self.bring_up_descendants_of(0);
+        self.bring_up_descendants_of(to_idx);
 
         true
     }
 
+    pub fn is_window_kept_above(&self, id: &W::Id) -> bool {
+        self.idx_of(id)
+            .is_some_and(|idx| self.tiles[idx].keep_above)
+    }
+
+    pub fn is_window_kept_below(&self, id: &W::Id) -> bool {
+        self.idx_of(id)
+            .is_some_and(|idx| self.tiles[idx].keep_below)
+    }
+
+    pub fn set_window_keep_above(&mut self, id: &W::Id, keep_above: bool) {
+        let idx = self.idx_of(id).unwrap();
+        if self.tiles[idx].keep_above == keep_above {
+            return;
+        }
+
+        self.restack_tile(idx, |tile| {
+            tile.keep_above = keep_above;
+            tile.keep_below &= !keep_above;
+        });
+    }
+
+    pub fn set_window_keep_below(&mut self, id: &W::Id, keep_below: bool) {
+        let idx = self.idx_of(id).unwrap();
+        if self.tiles[idx].keep_below == keep_below {
+            return;
+        }
+
+        self.restack_tile(idx, |tile| {
+            tile.keep_below = keep_below;
+            tile.keep_above &= !keep_below;
+        });
+    }
+
+    /// Changes the stacking layer of a tile and puts it on top of its new layer.
+    fn restack_tile(&mut self, idx: usize, change: impl FnOnce(&mut Tile<W>)) {
+        let mut tile = self.tiles.remove(idx);
+        let data = self.data.remove(idx);
+        change(&mut tile);
+
+        // Make sure the tile doesn't end up below its parent.
+        let mut new_idx = self.top_idx_for(&tile);
+        for (i, tile_above) in self.tiles.iter().enumerate().take(new_idx) {
+            if tile.window().is_child_of(tile_above.window()) {
+                new_idx = i;
+                break;
+            }
+        }
+
+        self.tiles.insert(new_idx, tile);
+        self.data.insert(new_idx, data);
+        self.bring_up_descendants_of(new_idx);
+    }
+
+    /// Returns the topmost index that the tile can be raised to.
+    ///
+    /// Tiles in higher stacking layers stay above it, except when they have children in lower
+    /// layers.
+    fn top_idx_for(&self, tile: &Tile<W>) -> usize {
+        let layer = stacking_layer(tile);
+        self.tiles
+            .iter()
+            .take_while(|other| stacking_layer(other) > layer)
+            .count()
+    }
+
     fn raise_window(&mut self, from_idx: usize, to_idx: usize) {
         assert!(to_idx <= from_idx);
 
@@ -937,6 +1012,162 @@ impl<W: LayoutElement> FloatingSpace<W> {
         self.move_to(idx, new_pos, true);
     }
 
+    pub fn tile_window_to_area(
+        &mut self,
+        id: Option<&W::Id>,
+        area: FloatingTileArea,
+        animate: bool,
+    ) {
+        let Some(id) = id.or(self.active_window_id.as_ref()) else {
+            return;
+        };
+        let idx = self.idx_of(id).unwrap();
+        let rect = self.tile_area_rect(area);
+
+        let tile = &mut self.tiles[idx];
+        tile.floating_preset_width_idx = None;
+        tile.floating_preset_height_idx = None;
+
+        let win_width = tile.window_width_for_tile_width(rect.size.w);
+        let win_width = win_width.round().max(1.) as i32;
+        let win_height = tile.window_height_for_tile_height(rect.size.h);
+        let win_height = win_height.round().max(1.) as i32;
+
+        let win = tile.window_mut();
+        let min_size = win.min_size();
+        let max_size = win.max_size();
+        let win_width = ensure_min_max_size(win_width, min_size.w, max_size.w);
+        let win_height = ensure_min_max_size(win_height, min_size.h, max_size.h);
+
+        let win_size = Size::from((win_width, win_height));
+        win.request_size_once(win_size, animate);
+
+        self.move_to(idx, rect.loc, animate);
+    }
+
+    /// Returns the tile rectangle for a window tiled to the given area.
+    pub fn tile_area_rect(&self, area: FloatingTileArea) -> Rectangle<f64, Logical> {
+        let gaps = self.options.gaps;
+        let working_area = self.working_area;
+
+        let full_w = f64::max(working_area.size.w - gaps * 2., 0.);
+        let full_h = f64::max(working_area.size.h - gaps * 2., 0.);
+        let half_w = f64::max((full_w - gaps) / 2., 0.);
+        let half_h = f64::max((full_h - gaps) / 2., 0.);
+
+        let left = working_area.loc.x + gaps;
+        let right = left + half_w + gaps;
+        let top = working_area.loc.y + gaps;
+        let bottom = top + half_h + gaps;
+
+        let (loc, size) = match area {
+            FloatingTileArea::Left => ((left, top), (half_w, full_h)),
+            FloatingTileArea::Right => ((right, top), (half_w, full_h)),
+            FloatingTileArea::Top => ((left, top), (full_w, half_h)),
+            FloatingTileArea::Bottom => ((left, bottom), (full_w, half_h)),
+            FloatingTileArea::TopLeft => ((left, top), (half_w, half_h)),
+            FloatingTileArea::TopRight => ((right, top), (half_w, half_h)),
+            FloatingTileArea::BottomLeft => ((left, bottom), (half_w, half_h)),
+            FloatingTileArea::BottomRight => ((right, bottom), (half_w, half_h)),
+        };
+        Rectangle::new(Point::from(loc), Size::from(size))
+    }
+
+    /// Returns the area to tile a window to when dropping it with the pointer at `pos`.
+    pub fn edge_tile_area_under(&self, pos: Point<f64, Logical>) -> Option<FloatingTileArea> {
+        let config = self.options.floating_snap;
+        if config.off || config.disable_edge_tiling {
+            return None;
+        }
+
+        let size = self.view_size;
+        let at_left = pos.x < EDGE_TILING_THRESHOLD;
+        let at_right = size.w - EDGE_TILING_THRESHOLD < pos.x;
+        let at_top = pos.y < EDGE_TILING_THRESHOLD;
+        let at_bottom = size.h - EDGE_TILING_THRESHOLD < pos.y;
+
+        // Close to the ends of an edge, tile to a quarter rather than a half.
+        let near_left = pos.x < size.w / 4.;
+        let near_right = size.w * 3. / 4. < pos.x;
+        let near_top = pos.y < size.h / 4.;
+        let near_bottom = size.h * 3. / 4. < pos.y;
+
+        let area = if at_left || at_right {
+            match (at_left, near_top, near_bottom) {
+                (true, true, _) => FloatingTileArea::TopLeft,
+                (true, _, true) => FloatingTileArea::BottomLeft,
+                (true, _, _) => FloatingTileArea::Left,
+                (false, true, _) => FloatingTileArea::TopRight,
+                (false, _, true) => FloatingTileArea::BottomRight,
+                (false, _, _) => FloatingTileArea::Right,
+            }
+        } else if at_top || at_bottom {
+            match (at_top, near_left, near_right) {
+                (true, true, _) => FloatingTileArea::TopLeft,
+                (true, _, true) => FloatingTileArea::TopRight,
+                (true, _, _) => FloatingTileArea::Top,
+                (false, true, _) => FloatingTileArea::BottomLeft,
+                (false, _, true) => FloatingTileArea::BottomRight,
+                (false, _, _) => FloatingTileArea::Bottom,
+            }
+        } else {
+            return None;
+        };
+
+        Some(area)
+    }
+
+    /// Snaps a tile position to the view and working area edges and to the edges of other tiles.
+    ///
+    /// Both axes snap independently to the closest edge within the configured distance.
+    pub fn snap_tile_pos(
+        &self,
+        pos: Point<f64, Logical>,
+        size: Size<f64, Logical>,
+    ) -> Point<f64, Logical> {
+        let config = self.options.floating_snap;
+        let distance = config.distance.0;
+        if config.off || distance == 0. {
+            return pos;
+        }
+
+        let working_area = self.working_area;
+        let mut xs = vec![
+            0.,
+            self.view_size.w,
+            working_area.loc.x,
+            working_area.loc.x + working_area.size.w,
+        ];
+        let mut ys = vec![
+            0.,
+            self.view_size.h,
+            working_area.loc.y,
+            working_area.loc.y + working_area.size.h,
+        ];
+
+        for data in &self.data {
+            let other = Rectangle::new(data.logical_pos, data.size);
+
+            // Only snap to the edges of tiles that are close on the other axis.
+            if pos.y < other.loc.y + other.size.h + distance
+                && other.loc.y < pos.y + size.h + distance
+            {
+                xs.push(other.loc.x);
+                xs.push(other.loc.x + other.size.w);
+            }
+            if pos.x < other.loc.x + other.size.w + distance
+                && other.loc.x < pos.x + size.w + distance
+            {
+                ys.push(other.loc.y);
+                ys.push(other.loc.y + other.size.h);
+            }
+        }
+
+        let x = snap_to_closest_edge(pos.x, size.w, &xs, distance);
+        let y = snap_to_closest_edge(pos.y, size.h, &ys, distance);
+        Point::from((x, y))
+    }
+
     pub fn descendants_added(&mut self, id: &W::Id) -> bool {
         let Some(idx) = self.idx_of(id) else {
             return false;
@@ -1093,10 +1324,16 @@ impl<W: LayoutElement> FloatingSpace<W> {
     pub fn refresh(&mut self, is_active: bool, is_focused: bool) {
         let active = self.active_window_id.clone();
         for tile in &mut self.tiles {
+            let (is_pinned, keep_above, keep_below) =
+                (tile.is_pinned, tile.keep_above, tile.keep_below);
             let win = tile.window_mut();
 
             win.set_active_in_column(true);
             win.set_floating(true);
+            win.set_pinned(is_pinned);
+            win.set_kept_above(keep_above);
+            win.set_kept_below(keep_below);
+            win.set_minimized(false);
 
             let mut is_active = is_active && Some(win.id()) == active.as_ref();
             if self.options.deactivate_unfocused_windows {
@@ -1330,6 +1567,32 @@ fn compute_toplevel_bounds(
     .to_i32_floor()
 }
 
+/// Returns the stacking layer of a tile; tiles in higher layers are stacked above.
+///
+/// The tiles kept above go above the regular ones, and the ones kept below go below.
+fn stacking_layer<W: LayoutElement>(tile: &Tile<W>) -> i8 {
+    if tile.keep_above {
+        1
+    } else if tile.keep_below {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Moves the `pos..pos + size` span so that one of its ends lands on the closest edge.
+fn snap_to_closest_edge(pos: f64, size: f64, edges: &[f64], distance: f64) -> f64 {
+    let mut best: Option<f64> = None;
+    for edge in edges {
+        for delta in [edge - pos, edge - (pos + size)] {
+            if delta.abs() <= distance && best.map_or(true, |best| delta.abs() < best.abs()) {
+                best = Some(delta);
+            }
+        }
+    }
+    pos + best.unwrap_or(0.)
+}
+
 fn resolve_preset_size(preset: PresetSize, view_size: f64) -> ResolvedSize {
     match preset {
         PresetSize::Proportion(proportion) => ResolvedSize::Tile(view_size * proportion),
@@ -39,10 +39,10 @@ use std::time::Duration;
 
 use monitor::{InsertHint, InsertPosition, InsertWorkspace, MonitorAddWindowTarget};
 use niri_config::{
-    CenterFocusedColumn, Config, CornerRadius, FloatOrInt, NewColumnLocation, PresetSize, Struts,
-    Workspace as WorkspaceConfig, WorkspaceReference,
+    CenterFocusedColumn, Config, CornerRadius, FloatOrInt, NewColumnLocation, PresetSize,
+    ScrollOrientation, Struts, Workspace as WorkspaceConfig, WorkspaceReference,
 };
-use niri_ipc::{ColumnDisplay, PositionChange, SizeChange};
+use niri_ipc::{ColumnDisplay, FloatingTileArea, LayoutKind, PositionChange, SizeChange};
 use scrolling::{Column, ColumnWidth};
 use smithay::backend::renderer::element::surface::WaylandSurfaceRenderElement;
 use smithay::backend::renderer::element::utils::RescaleRenderElement;
@@ -60,7 +60,9 @@ use crate::animation::{Animation, Clock};
 use crate::input::swipe_tracker::SwipeTracker;
 use crate::layout::scrolling::ScrollDirection;
 use crate::niri_render_elements;
+use crate::render_helpers::color_transform::ColorTransformRenderElement;
 use crate::render_helpers::offscreen::OffscreenData;
+use crate::render_helpers::primary_gpu_texture::PrimaryGpuTextureRenderElement;
 use crate::render_helpers::renderer::NiriRenderer;
 use crate::render_helpers::snapshot::RenderSnapshot;
 use crate::render_helpers::solid_color::{SolidColorBuffer, SolidColorRenderElement};
@@ -74,6 +76,7 @@ use crate::utils::{
 };
 use crate::window::ResolvedWindowRules;
 
+pub mod cells;
 pub mod closing_window;
 pub mod floating;
 pub mod focus_ring;
@@ -98,6 +101,9 @@ const INTERACTIVE_MOVE_START_THRESHOLD: f64 = 256. * 256.;
 /// Opacity of interactively moved tiles targeting the scrolling layout.
 const INTERACTIVE_MOVE_ALPHA: f64 = 0.75;
 
+/// Opacity of windows that don't match the overview search.
+const OVERVIEW_SEARCH_DIM_ALPHA: f64 = 0.3;
+
 /// Amount of touchpad movement to toggle the overview.
 const OVERVIEW_GESTURE_MOVEMENT: f64 = 300.;
 
@@ -112,6 +118,7 @@ pub struct SizeFrac;
 niri_render_elements! {
     LayoutElementRenderElement<R> => {
         Wayland = WaylandSurfaceRenderElement<R>,
+        ColorTransform = ColorTransformRenderElement<WaylandSurfaceRenderElement<R>>,
         SolidColor = SolidColorRenderElement,
     }
 }
@@ -179,6 +186,21 @@ pub trait LayoutElement {
         self.render(renderer, location, scale, alpha, target).popups
     }
 
+    /// Renders the icon of the element, if it has one.
+    ///
+    /// The icon should fill a square with the given top-left location and logical size.
+    fn render_icon<R: NiriRenderer>(
+        &self,
+        renderer: &mut R,
+        location: Point<f64, Logical>,
+        size: f64,
+        scale: Scale<f64>,
+        alpha: f32,
+    ) -> Option<PrimaryGpuTextureRenderElement> {
+        let _ = (renderer, location, size, scale, alpha);
+        None
+    }
+
     /// Requests the element to change its size.
     ///
     /// The size request is stored and will be continuously sent to the element on any further
@@ -207,11 +229,21 @@ pub trait LayoutElement {
     fn set_activated(&mut self, active: bool);
     fn set_active_in_column(&mut self, active: bool);
     fn set_floating(&mut self, floating: bool);
+    fn set_pinned(&mut self, pinned: bool);
+    fn set_kept_above(&mut self, kept_above: bool);
+    fn set_kept_below(&mut self, kept_below: bool);
+    fn set_minimized(&mut self, minimized: bool);
     fn set_bounds(&self, bounds: Size<i32, Logical>);
     fn is_ignoring_opacity_window_rule(&self) -> bool;
 
     fn is_urgent(&self) -> bool;
 
+    /// Title of the element, used for searching in the overview.
+    fn title(&self) -> Option<String>;
+
+    /// App ID of the element, used for searching in the overview.
+    fn app_id(&self) -> Option<String>;
+
     fn configure_intent(&self) -> ConfigureIntent;
     fn send_pending_configure(&mut self);
 
@@ -265,6 +297,13 @@ pub trait LayoutElement {
 
     fn is_child_of(&self, parent: &Self) -> bool;
 
+    /// Whether the element is a modal dialog for its parent.
+    ///
+    /// Modal dialogs block input to their parent and follow it between workspaces.
+    fn is_modal(&self) -> bool {
+        false
+    }
+
     fn rules(&self) -> &ResolvedWindowRules;
 
     /// Runs periodic clean-up tasks.
@@ -312,6 +351,12 @@ pub struct Layout<W: LayoutElement> {
     overview_open: bool,
     /// The overview zoom progress.
     overview_progress: Option<OverviewProgress>,
+    /// Text typed to search for windows in the overview.
+    overview_search: String,
+    /// Modal dialogs of parent windows as `(parent, modal)` pairs.
+    ///
+    /// This avoids scanning all windows for modal dialogs on every pointer motion.
+    modals: Vec<(W::Id, W::Id)>,
     /// Configurable properties of the layout.
     options: Rc<Options>,
 }
@@ -345,8 +390,14 @@ pub struct Options {
     pub shadow: niri_config::Shadow,
     pub tab_indicator: niri_config::TabIndicator,
     pub insert_hint: niri_config::InsertHint,
+    pub floating_snap: niri_config::FloatingSnap,
     pub center_focused_column: CenterFocusedColumn,
     pub new_column_location: NewColumnLocation,
+    pub scroll_orientation: ScrollOrientation,
+    /// Scroll orientation overrides from the output configs, by output name.
+    pub output_scroll_orientations: Vec<(String, ScrollOrientation)>,
+    /// Scroll orientation overrides from the named workspace configs, by workspace name.
+    pub workspace_scroll_orientations: Vec<(String, ScrollOrientation)>,
     pub always_center_single_column: bool,
     pub empty_workspace_above_first: bool,
     pub default_column_display: ColumnDisplay,
@@ -375,8 +426,12 @@ impl Default for Options {
             shadow: Default::default(),
             tab_indicator: Default::default(),
             insert_hint: Default::default(),
+            floating_snap: Default::default(),
             center_focused_column: Default::default(),
             new_column_location: Default::default(),
+            scroll_orientation: Default::default(),
+            output_scroll_orientations: Vec::new(),
+            workspace_scroll_orientations: Vec::new(),
             always_center_single_column: false,
             empty_workspace_above_first: false,
             default_column_display: ColumnDisplay::Normal,
@@ -437,6 +492,8 @@ struct InteractiveMoveData<W: LayoutElement> {
     ///
     /// This helps the pointer remain inside the window as it resizes.
     pub(self) pointer_ratio_within_window: (f64, f64),
+    /// Offset that snaps the floating window to nearby edges, in workspace coordinates.
+    pub(self) snap_offset: Point<f64, Logical>,
 }
 
 #[derive(Debug)]
@@ -575,7 +632,8 @@ impl<W: LayoutElement> InteractiveMoveData<W> {
         ));
         let pos = self.pointer_pos_within_output
             - (pointer_offset_within_window + self.tile.window_loc() - self.tile.render_offset())
-                .upscale(zoom);
+                .upscale(zoom)
+            + self.snap_offset.upscale(zoom);
         // Round to physical pixels.
         pos.to_physical_precise_round(scale).to_logical(scale)
     }
@@ -635,6 +693,18 @@ impl Options {
             layout.preset_window_heights.clone()
         };
 
+        let output_scroll_orientations = config
+            .outputs
+            .0
+            .iter()
+            .filter_map(|o| Some((o.name.clone(), o.scroll_orientation?)))
+            .collect();
+        let workspace_scroll_orientations = config
+            .workspaces
+            .iter()
+            .filter_map(|ws| Some((ws.name.0.clone(), ws.scroll_orientation?)))
+            .collect();
+
         // Missing default_column_width maps to Some(PresetSize::Proportion(0.5)),
         // while present, but empty, maps to None.
         let default_column_width = layout
@@ -651,15 +721,19 @@ impl Options {
             shadow: layout.shadow,
             tab_indicator: layout.tab_indicator,
             insert_hint: layout.insert_hint,
+            floating_snap: layout.floating_snap,
             center_focused_column: layout.center_focused_column,
             new_column_location: layout.new_column_location,
+            scroll_orientation: layout.scroll_orientation,
+            output_scroll_orientations,
+            workspace_scroll_orientations,
             always_center_single_column: layout.always_center_single_column,
             empty_workspace_above_first: layout.empty_workspace_above_first,
             default_column_display: layout.default_column_display,
             preset_column_widths,
             default_column_width,
             animations: config.animations.clone(),
-            gestures: config.gestures,
+            gestures: config.gestures.clone(),
             overview: config.overview,
             disable_resize_throttling: config.debug.disable_resize_throttling,
             disable_transactions: config.debug.disable_transactions,
@@ -708,6 +782,8 @@ impl<W: LayoutElement> Layout<W> {
             update_render_elements_time: Duration::ZERO,
             overview_open: false,
             overview_progress: None,
+            overview_search: String::new(),
+            modals: Vec::new(),
             options: Rc::new(options),
         }
     }
@@ -733,6 +809,8 @@ impl<W: LayoutElement> Layout<W> {
             update_render_elements_time: Duration::ZERO,
             overview_open: false,
             overview_progress: None,
+            overview_search: String::new(),
+            modals: Vec::new(),
             options: opts,
         }
     }
@@ -899,13 +977,34 @@ impl<W: LayoutElement> Layout<W> {
                     .iter()
                     .position(|mon| &mon.output == output)
                     .expect("trying to remove non-existing output");
-                let monitor = monitors.remove(idx);
+                let mut monitor = monitors.remove(idx);
 
                 self.last_active_workspace_id.insert(
                     monitor.output_name().clone(),
                     monitor.workspaces[monitor.active_workspace_idx].id(),
                 );
 
+                let pinned_ids =
+                    Vec::from_iter(monitor.pinned.tiles().map(|t| t.window().id().clone()));
+                let mut pinned =
+                    Vec::from_iter(pinned_ids.iter().map(|id| monitor.remove_pinned_tile(id)));
+
+                if monitors.is_empty() {
+                    // Without outputs, there's nothing to pin to, so pinned windows stay on the
+                    // active workspace.
+                    let ws = &mut monitor.workspaces[monitor.active_workspace_idx];
+                    for removed in pinned.drain(..) {
+                        ws.add_tile(
+                            removed.tile,
+                            WorkspaceAddWindowTarget::Auto,
+                            ActivateWindow::No,
+                            removed.width,
+                            removed.is_full_width,
+                            true,
+                        );
+                    }
+                }
+
                 let mut workspaces = monitor.workspaces;
 
                 for ws in &mut workspaces {
@@ -970,6 +1069,19 @@ impl<W: LayoutElement> Layout<W> {
                         primary.clean_up_workspaces();
                     }
 
+                    // Pinned windows stay pinned, now on the primary monitor.
+                    for removed in pinned {
+                        primary.add_tile(
+                            removed.tile,
+                            MonitorAddWindowTarget::Auto,
+                            ActivateWindow::No,
+                            false,
+                            removed.width,
+                            removed.is_full_width,
+                            true,
+                        );
+                    }
+
                     MonitorSet::Normal {
                         monitors,
                         primary_idx,
@@ -1020,6 +1132,18 @@ impl<W: LayoutElement> Layout<W> {
         is_floating: bool,
         activate: ActivateWindow,
     ) -> Option<&Output> {
+        // The window may be a modal dialog, or the parent of modal dialogs if it was remapped.
+        let mut modals = Vec::new();
+        for win in self.windows_with_minimized() {
+            if window.is_modal() && window.is_child_of(win) {
+                modals.push((win.id().clone(), window.id().clone()));
+            }
+            if win.is_modal() && win.is_child_of(&window) {
+                modals.push((window.id().clone(), win.id().clone()));
+            }
+        }
+        self.modals.extend(modals);
+
         let scrolling_width = self.resolve_scrolling_width(&window, width);
         let scrolling_height = height.map(SizeChange::from);
         let id = window.id().clone();
@@ -1078,9 +1202,7 @@ impl<W: LayoutElement> Layout<W> {
                         } else {
                             let mon_idx = monitors
                                 .iter()
-                                .position(|mon| {
-                                    mon.workspaces.iter().any(|ws| ws.has_window(next_to))
-                                })
+                                .position(|mon| mon.has_window(next_to))
                                 .unwrap();
                             (mon_idx, MonitorAddWindowTarget::NextTo(next_to))
                         }
@@ -1186,6 +1308,9 @@ impl<W: LayoutElement> Layout<W> {
         window: &W::Id,
         transaction: Transaction,
     ) -> Option<RemovedTile<W>> {
+        self.modals
+            .retain(|(parent, modal)| parent != window && modal != window);
+
         if let Some(state) = &self.interactive_move {
             match state {
                 InteractiveMoveState::Starting { window_id, .. } => {
@@ -1226,8 +1351,12 @@ impl<W: LayoutElement> Layout<W> {
         match &mut self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    if mon.pinned.has_window(window) {
+                        return Some(mon.remove_pinned_tile(window));
+                    }
+
                     for (idx, ws) in mon.workspaces.iter_mut().enumerate() {
-                        if ws.has_window(window) {
+                        if ws.has_window(window) || ws.has_minimized_window(window) {
                             let removed = ws.remove_tile(window, transaction);
 
                             // Clean up empty workspaces that are not active and not last.
@@ -1261,7 +1390,7 @@ impl<W: LayoutElement> Layout<W> {
             }
             MonitorSet::NoOutputs { workspaces, .. } => {
                 for (idx, ws) in workspaces.iter_mut().enumerate() {
-                    if ws.has_window(window) {
+                    if ws.has_window(window) || ws.has_minimized_window(window) {
                         let removed = ws.remove_tile(window, transaction);
 
                         // Clean up empty workspaces.
@@ -1279,6 +1408,12 @@ impl<W: LayoutElement> Layout<W> {
     }
 
     pub fn descendants_added(&mut self, id: &W::Id) -> bool {
+        for mon in self.monitors_mut() {
+            if mon.pinned.descendants_added(id) {
+                return true;
+            }
+        }
+
         for ws in self.workspaces_mut() {
             if ws.descendants_added(id) {
                 return true;
@@ -1288,6 +1423,163 @@ impl<W: LayoutElement> Layout<W> {
         false
     }
 
+    /// Updates the modal dialog state of the window.
+    ///
+    /// This should be called when the parent or the modal hint of the window changes.
+    pub fn update_modal(&mut self, window: &W::Id) {
+        self.modals.retain(|(_, modal)| modal != window);
+
+        let Some(win) = self.windows_with_minimized().find(|win| win.id() == window) else {
+            return;
+        };
+        if !win.is_modal() {
+            return;
+        }
+        let Some(parent) = self
+            .windows_with_minimized()
+            .find(|parent| win.is_child_of(parent))
+        else {
+            return;
+        };
+
+        let pair = (parent.id().clone(), window.clone());
+        self.modals.push(pair);
+    }
+
+    /// Returns the modal dialog that blocks input to this window, if any.
+    ///
+    /// If the modal dialog itself has a modal dialog, returns the innermost one.
+    pub fn blocking_modal(&self, window: &W::Id) -> Option<&W::Id> {
+        let mut modal = None;
+        let mut current = window;
+        // Limit the depth in case the client made a parent cycle.
+        for _ in 0..16 {
+            let Some((_, child)) = self.modals.iter().find(|(parent, _)| parent == current) else {
+                break;
+            };
+            modal = Some(child);
+            current = child;
+        }
+        modal
+    }
+
+    /// Returns the window that the chain of modal dialogs starting at this window belongs to.
+    ///
+    /// `None` refers to the focused window. Returns `None` if the window is not a modal dialog.
+    fn modal_root(&self, window: Option<&W::Id>) -> Option<W::Id> {
+        let mut current = match window {
+            Some(window) => window,
+            None => self.focus()?.id(),
+        };
+        let mut root = None;
+        for _ in 0..16 {
+            let Some((parent, _)) = self.modals.iter().find(|(_, modal)| modal == current) else {
+                break;
+            };
+            root = Some(parent.clone());
+            current = parent;
+        }
+        root
+    }
+
+    /// Redirects a move of a modal dialog to its parent window.
+    ///
+    /// The dialog then follows its parent in [`Layout::move_modals_to_parents()`].
+    fn redirect_modal_move(
+        &self,
+        window: Option<&W::Id>,
+        activate: ActivateWindow,
+    ) -> Option<(W::Id, ActivateWindow)> {
+        let root = self.modal_root(window)?;
+
+        // The heuristic checks whether the moved window is active, which is never true for the
+        // parent of a modal dialog, so resolve it here against the dialog itself.
+        let activate = activate.map_smart(|| {
+            window.map_or(true, |window| {
+                self.focus().map(|win| win.id()) == Some(window)
+            })
+        });
+        let activate = if activate {
+            ActivateWindow::Yes
+        } else {
+            ActivateWindow::No
+        };
+
+        Some((root, activate))
+    }
+
+    /// Moves modal dialogs to the workspaces of their parents.
+    ///
+    /// This should be called after moving windows between workspaces.
+    fn move_modals_to_parents(&mut self) {
+        let MonitorSet::Normal { monitors, .. } = &mut self.monitor_set else {
+            return;
+        };
+
+        let mut moved = false;
+
+        // Moving a dialog may make its own modal dialogs misplaced, hence the loop.
+        for _ in 0..16 {
+            let mut misplaced = Vec::new();
+            for ws in monitors.iter().flat_map(|mon| &mon.workspaces) {
+                for modal in ws.windows().filter(|win| win.is_modal()) {
+                    // Dialogs of pinned windows go to the pinned windows.
+                    let parent = monitors
+                        .iter()
+                        .flat_map(|mon| mon.windows())
+                        .find(|win| modal.is_child_of(win));
+                    if let Some(parent) = parent {
+                        if !ws.has_window(parent.id()) {
+                            misplaced.push((modal.id().clone(), parent.id().clone()));
+                        }
+                    }
+                }
+            }
+
+            if misplaced.is_empty() {
+                break;
+            }
+
+            for (modal, parent) in misplaced {
+                let Some(parent_mon_idx) = monitors.iter().position(|mon| mon.has_window(&parent))
+                else {
+                    continue;
+                };
+                let Some(ws) = monitors
+                    .iter_mut()
+                    .flat_map(|mon| &mut mon.workspaces)
+                    .find(|ws| ws.has_window(&modal))
+                else {
+                    continue;
+                };
+                let mut removed = ws.remove_tile(&modal, Transaction::new());
+                removed.tile.stop_move_animations();
+                moved = true;
+
+                // Smart activation will focus the dialog if its parent is active.
+                monitors[parent_mon_idx].add_tile(
+                    removed.tile,
+                    MonitorAddWindowTarget::NextTo(&parent),
+                    ActivateWindow::Smart,
+                    false,
+                    removed.width,
+                    removed.is_full_width,
+                    removed.is_floating,
+                );
+            }
+        }
+
+        if !moved {
+            return;
+        }
+
+        for mon in monitors {
+            if mon.workspace_switch.is_none() {
+                mon.clean_up_workspaces();
+            }
+        }
+    }
+
     pub fn update_window(&mut self, window: &W::Id, serial: Option<Serial>) {
         if let Some(InteractiveMoveState::Moving(move_)) = &mut self.interactive_move {
             if move_.tile.window().id() == window {
@@ -1304,8 +1596,12 @@ impl<W: LayoutElement> Layout<W> {
         match &mut self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    if mon.pinned.update_window(window, serial) {
+                        return;
+                    }
+
                     for ws in &mut mon.workspaces {
-                        if ws.has_window(window) {
+                        if ws.has_window(window) || ws.has_minimized_window(window) {
                             ws.update_window(window, serial);
                             return;
                         }
@@ -1314,7 +1610,7 @@ impl<W: LayoutElement> Layout<W> {
             }
             MonitorSet::NoOutputs { workspaces, .. } => {
                 for ws in workspaces {
-                    if ws.has_window(window) {
+                    if ws.has_window(window) || ws.has_minimized_window(window) {
                         ws.update_window(window, serial);
                         return;
                     }
@@ -1449,6 +1745,11 @@ impl<W: LayoutElement> Layout<W> {
         match &self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    let mut pinned = mon.pinned.tiles().map(Tile::window);
+                    if let Some(window) = pinned.find(|win| win.is_wl_surface(wl_surface)) {
+                        return Some((window, Some(&mon.output)));
+                    }
+
                     for ws in &mon.workspaces {
                         if let Some(window) = ws.find_wl_surface(wl_surface) {
                             return Some((window, Some(&mon.output)));
@@ -1481,6 +1782,11 @@ impl<W: LayoutElement> Layout<W> {
         match &mut self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    let mut pinned = mon.pinned.tiles_mut().map(Tile::window_mut);
+                    if let Some(window) = pinned.find(|win| win.is_wl_surface(wl_surface)) {
+                        return Some((window, Some(&mon.output)));
+                    }
+
                     for ws in &mut mon.workspaces {
                         if let Some(window) = ws.find_wl_surface_mut(wl_surface) {
                             return Some((window, Some(&mon.output)));
@@ -1519,11 +1825,58 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        if let Some(rect) = self
+            .monitors()
+            .find_map(|mon| mon.pinned.popup_target_rect(window))
+        {
+            return rect;
+        }
+
         self.workspaces()
             .find_map(|(_, _, ws)| ws.popup_target_rect(window))
             .unwrap()
     }
 
+    /// Returns the output and the output-local visual rectangle of the window.
+    ///
+    /// Returns `None` if the window is not currently visible on a workspace.
+    pub fn window_visual_rect(&self, window: &W::Id) -> Option<(&Output, Rectangle<f64, Logical>)> {
+        let MonitorSet::Normal { monitors, .. } = &self.monitor_set else {
+            return None;
+        };
+
+        for mon in monitors {
+            let mut pinned = mon.pinned.tiles_with_render_positions();
+            let Some((tile, tile_offset)) = pinned.find(|(tile, _)| tile.window().id() == window)
+            else {
+                continue;
+            };
+
+            let zoom = mon.overview_zoom();
+            let loc = mon.pinned_render_geo().loc + (tile_offset + tile.window_loc()).upscale(zoom);
+            let size = tile.window_size().upscale(zoom);
+            return Some((mon.output(), Rectangle::new(loc, size)));
+        }
+
+        let (mon, (ws, ws_geo)) = monitors.iter().find_map(|mon| {
+            mon.workspaces_with_render_geo()
+                .find(|(ws, _)| ws.has_window(window))
+                .map(|rv| (mon, rv))
+        })?;
+
+        let (tile, tile_offset, visible) = ws
+            .tiles_with_render_positions()
+            .find(|(tile, _, _)| tile.window().id() == window)?;
+        if !visible {
+            return None;
+        }
+
+        let zoom = mon.overview_zoom();
+        let loc = ws_geo.loc + (tile_offset + tile.window_loc()).upscale(zoom);
+        let size = tile.window_size().upscale(zoom);
+        Some((mon.output(), Rectangle::new(loc, size)))
+    }
+
     pub fn update_output_size(&mut self, output: &Output) {
         let _span = tracy_client::span!("Layout::update_output_size");
 
@@ -1574,6 +1927,11 @@ impl<W: LayoutElement> Layout<W> {
             return true;
         };
 
+        // Pinned windows are on every workspace.
+        if monitors.iter().any(|mon| mon.pinned.has_window(window)) {
+            return true;
+        }
+
         let (mon, ws_idx) = monitors
             .iter()
             .find_map(|mon| {
@@ -1609,9 +1967,16 @@ impl<W: LayoutElement> Layout<W> {
         };
 
         for (monitor_idx, mon) in monitors.iter_mut().enumerate() {
+            if mon.pinned.activate_window(window) {
+                *active_monitor_idx = monitor_idx;
+                mon.pinned_is_active = true;
+                return;
+            }
+
             for (workspace_idx, ws) in mon.workspaces.iter_mut().enumerate() {
                 if ws.activate_window(window) {
                     *active_monitor_idx = monitor_idx;
+                    mon.pinned_is_active = false;
 
                     // If currently in the middle of a vertical swipe between the target workspace
                     // and some other, don't switch the workspace.
@@ -1645,9 +2010,16 @@ impl<W: LayoutElement> Layout<W> {
         };
 
         for (monitor_idx, mon) in monitors.iter_mut().enumerate() {
+            if mon.pinned.activate_window_without_raising(window) {
+                *active_monitor_idx = monitor_idx;
+                mon.pinned_is_active = true;
+                return;
+            }
+
             for (workspace_idx, ws) in mon.workspaces.iter_mut().enumerate() {
                 if ws.activate_window_without_raising(window) {
                     *active_monitor_idx = monitor_idx;
+                    mon.pinned_is_active = false;
 
                     // If currently in the middle of a vertical swipe between the target workspace
                     // and some other, don't switch the workspace.
@@ -1691,6 +2063,9 @@ impl<W: LayoutElement> Layout<W> {
         Some(&mon.workspaces[mon.active_workspace_idx])
     }
 
+    /// Returns the active workspace to act on.
+    ///
+    /// This moves the focus from the pinned windows to the workspace.
     pub fn active_workspace_mut(&mut self) -> Option<&mut Workspace<W>> {
         let MonitorSet::Normal {
             monitors,
@@ -1701,8 +2076,7 @@ impl<W: LayoutElement> Layout<W> {
             return None;
         };
 
-        let mon = &mut monitors[*active_monitor_idx];
-        Some(&mut mon.workspaces[mon.active_workspace_idx])
+        Some(monitors[*active_monitor_idx].active_workspace())
     }
 
     pub fn windows_for_output(&self, output: &Output) -> impl Iterator<Item = &W> + '_ {
@@ -1719,7 +2093,7 @@ impl<W: LayoutElement> Layout<W> {
             .into_iter();
 
         let mon = monitors.iter().find(|mon| &mon.output == output).unwrap();
-        let mon_windows = mon.workspaces.iter().flat_map(|ws| ws.windows());
+        let mon_windows = mon.windows();
 
         moving_window.chain(mon_windows)
     }
@@ -1741,7 +2115,8 @@ impl<W: LayoutElement> Layout<W> {
             .iter_mut()
             .find(|mon| &mon.output == output)
             .unwrap();
-        let mon_windows = mon.workspaces.iter_mut().flat_map(|ws| ws.windows_mut());
+        let pinned = mon.pinned.tiles_mut().map(Tile::window_mut);
+        let mon_windows = pinned.chain(mon.workspaces.iter_mut().flat_map(|ws| ws.windows_mut()));
 
         moving_window.chain(mon_windows)
     }
@@ -1754,8 +2129,13 @@ impl<W: LayoutElement> Layout<W> {
         match &self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    // Pinned windows are on every workspace.
+                    for win in mon.pinned.tiles().map(Tile::window) {
+                        f(win, Some(&mon.output), None);
+                    }
+
                     for ws in &mon.workspaces {
-                        for win in ws.windows() {
+                        for win in ws.windows().chain(ws.minimized_windows()) {
                             f(win, Some(&mon.output), Some(ws.id()));
                         }
                     }
@@ -1763,7 +2143,7 @@ impl<W: LayoutElement> Layout<W> {
             }
             MonitorSet::NoOutputs { workspaces } => {
                 for ws in workspaces {
-                    for win in ws.windows() {
+                    for win in ws.windows().chain(ws.minimized_windows()) {
                         f(win, None, Some(ws.id()));
                     }
                 }
@@ -1779,10 +2159,17 @@ impl<W: LayoutElement> Layout<W> {
         match &mut self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    for win in mon.pinned.tiles_mut().map(Tile::window_mut) {
+                        f(win, Some(&mon.output));
+                    }
+
                     for ws in &mut mon.workspaces {
                         for win in ws.windows_mut() {
                             f(win, Some(&mon.output));
                         }
+                        for win in ws.minimized_windows_mut() {
+                            f(win, Some(&mon.output));
+                        }
                     }
                 }
             }
@@ -1791,6 +2178,9 @@ impl<W: LayoutElement> Layout<W> {
                     for win in ws.windows_mut() {
                         f(win, None);
                     }
+                    for win in ws.minimized_windows_mut() {
+                        f(win, None);
+                    }
                 }
             }
         }
@@ -1954,6 +2344,11 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        // Pinned windows are floating, so there's no column to consume into or expel from.
+        if self.pinned_monitor_mut(window).is_some() {
+            return;
+        }
+
         let workspace = if let Some(window) = window {
             Some(
                 self.workspaces_mut()
@@ -1977,6 +2372,11 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        // Pinned windows are floating, so there's no column to consume into or expel from.
+        if self.pinned_monitor_mut(window).is_some() {
+            return;
+        }
+
         let workspace = if let Some(window) = window {
             Some(
                 self.workspaces_mut()
@@ -2178,17 +2578,37 @@ impl<W: LayoutElement> Layout<W> {
     }
 
     pub fn move_to_workspace_up(&mut self) {
+        if self.modal_root(None).is_some() {
+            let Some(monitor) = self.active_monitor_ref() else {
+                return;
+            };
+            let idx = monitor.active_workspace_idx().saturating_sub(1);
+            self.move_to_workspace(None, idx, ActivateWindow::Yes);
+            return;
+        }
+
         let Some(monitor) = self.active_monitor() else {
             return;
         };
         monitor.move_to_workspace_up();
+        self.move_modals_to_parents();
     }
 
     pub fn move_to_workspace_down(&mut self) {
+        if self.modal_root(None).is_some() {
+            let Some(monitor) = self.active_monitor_ref() else {
+                return;
+            };
+            let idx = monitor.active_workspace_idx() + 1;
+            self.move_to_workspace(None, idx, ActivateWindow::Yes);
+            return;
+        }
+
         let Some(monitor) = self.active_monitor() else {
             return;
         };
         monitor.move_to_workspace_down();
+        self.move_modals_to_parents();
     }
 
     pub fn move_to_workspace(
@@ -2197,6 +2617,13 @@ impl<W: LayoutElement> Layout<W> {
         idx: usize,
         activate: ActivateWindow,
     ) {
+        // Moving a modal dialog moves its parent instead.
+        let redirect = self.redirect_modal_move(window, activate);
+        let (window, activate) = match &redirect {
+            Some((root, activate)) => (Some(root), *activate),
+            None => (window, activate),
+        };
+
         if let Some(InteractiveMoveState::Moving(move_)) = &mut self.interactive_move {
             if window.is_none() || window == Some(move_.tile.window().id()) {
                 return;
@@ -2220,27 +2647,48 @@ impl<W: LayoutElement> Layout<W> {
             monitor
         };
         monitor.move_to_workspace(window, idx, activate);
+
+        self.move_modals_to_parents();
     }
 
     pub fn move_column_to_workspace_up(&mut self, activate: bool) {
+        // Modal dialogs are floating, and moving them moves their parent instead.
+        if self.modal_root(None).is_some() {
+            self.move_to_workspace_up();
+            return;
+        }
+
         let Some(monitor) = self.active_monitor() else {
             return;
         };
         monitor.move_column_to_workspace_up(activate);
+        self.move_modals_to_parents();
     }
 
     pub fn move_column_to_workspace_down(&mut self, activate: bool) {
+        if self.modal_root(None).is_some() {
+            self.move_to_workspace_down();
+            return;
+        }
+
         let Some(monitor) = self.active_monitor() else {
             return;
         };
         monitor.move_column_to_workspace_down(activate);
+        self.move_modals_to_parents();
     }
 
     pub fn move_column_to_workspace(&mut self, idx: usize, activate: bool) {
+        if self.modal_root(None).is_some() {
+            self.move_to_workspace(None, idx, ActivateWindow::Smart);
+            return;
+        }
+
         let Some(monitor) = self.active_monitor() else {
             return;
         };
         monitor.move_column_to_workspace(idx, activate);
+        self.move_modals_to_parents();
     }
 
     pub fn switch_workspace_up(&mut self) {
@@ -2314,6 +2762,11 @@ impl<W: LayoutElement> Layout<W> {
     }
 
     pub fn center_column(&mut self) {
+        if let Some(mon) = self.pinned_monitor_mut(None) {
+            mon.pinned.center_window(None);
+            return;
+        }
+
         let Some(workspace) = self.active_workspace_mut() else {
             return;
         };
@@ -2327,6 +2780,11 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        if let Some(mon) = self.pinned_monitor_mut(id) {
+            mon.pinned.center_window(id);
+            return;
+        }
+
         let workspace = if let Some(id) = id {
             Some(self.workspaces_mut().find(|ws| ws.has_window(id)).unwrap())
         } else {
@@ -2350,6 +2808,27 @@ impl<W: LayoutElement> Layout<W> {
         self.focus_with_output().map(|(win, _out)| win)
     }
 
+    pub fn focus_mut(&mut self) -> Option<&mut W> {
+        if let Some(InteractiveMoveState::Moving(move_)) = &mut self.interactive_move {
+            return Some(move_.tile.window_mut());
+        }
+
+        let MonitorSet::Normal {
+            monitors,
+            active_monitor_idx,
+            ..
+        } = &mut self.monitor_set
+        else {
+            return None;
+        };
+
+        let mon = &mut monitors[*active_monitor_idx];
+        if mon.pinned_is_active {
+            return mon.pinned.active_window_mut();
+        }
+        mon.workspaces[mon.active_workspace_idx].active_window_mut()
+    }
+
     pub fn focus_with_output(&self) -> Option<(&W, &Output)> {
         if let Some(InteractiveMoveState::Moving(move_)) = &self.interactive_move {
             return Some((move_.tile.window(), &move_.output));
@@ -2463,6 +2942,25 @@ impl<W: LayoutElement> Layout<W> {
 
         let zoom = self.overview_zoom();
 
+        for win in self.windows_with_minimized() {
+            let parent = if win.is_modal() {
+                self.windows_with_minimized()
+                    .find(|parent| win.is_child_of(parent))
+            } else {
+                None
+            };
+            let expected = parent.map(|parent| parent.id());
+            let actual = self.modals.iter().find(|(_, modal)| modal == win.id());
+            let actual = actual.map(|(parent, _)| parent);
+            assert_eq!(actual, expected, "modal dialog map must match the windows");
+        }
+        for (_, modal) in &self.modals {
+            assert!(
+                self.windows_with_minimized().any(|win| win.id() == modal),
+                "modal dialogs in the map must be in the layout"
+            );
+        }
+
         let mut move_win_id = None;
         if let Some(state) = &self.interactive_move {
             match state {
@@ -2605,6 +3103,29 @@ impl<W: LayoutElement> Layout<W> {
                 monitor.overview_progress_value()
             );
 
+            let pinned = &monitor.pinned;
+            assert_eq!(monitor.view_size(), pinned.view_size());
+            assert_eq!(monitor.working_area(), pinned.working_area());
+            assert_eq!(&self.clock, pinned.clock());
+            let scale = monitor.scale().fractional_scale();
+            let options = Options::clone(&self.options).adjusted_for_scale(scale);
+            assert_eq!(
+                &**pinned.options(),
+                &options,
+                "pinned options must be base options adjusted for output scale"
+            );
+            pinned.verify_invariants();
+
+            for tile in pinned.tiles() {
+                assert!(tile.is_pinned, "tiles on the monitor must be pinned");
+            }
+            if monitor.pinned_is_active {
+                assert!(
+                    !pinned.is_empty(),
+                    "pinned windows cannot be active when there are none"
+                );
+            }
+
             if let Some(WorkspaceSwitch::Animation(anim)) = &monitor.workspace_switch {
                 let before_idx = anim.from() as usize;
                 let after_idx = anim.to() as usize;
@@ -3043,6 +3564,9 @@ impl<W: LayoutElement> Layout<W> {
 
         let _span = tracy_client::span!("Layout::update_insert_hint::update");
 
+        // Edge tiling makes no sense with zoomed out workspaces.
+        let edge_tiling = !self.overview_open;
+
         if let Some(mon) = self.monitor_for_output_mut(&move_.output) {
             let zoom = mon.overview_zoom();
             let (insert_ws, geo) = mon.insert_position(move_.pointer_pos_within_output);
@@ -3056,7 +3580,7 @@ impl<W: LayoutElement> Layout<W> {
                     let pos_within_workspace =
                         (move_.pointer_pos_within_output - geo.loc).downscale(zoom);
                     let position = if move_.is_floating {
-                        InsertPosition::Floating
+                        ws.floating_insert_position(pos_within_workspace, edge_tiling)
                     } else {
                         ws.scrolling_insert_position(pos_within_workspace)
                     };
@@ -3179,6 +3703,11 @@ impl<W: LayoutElement> Layout<W> {
     }
 
     pub fn toggle_width(&mut self) {
+        if let Some(mon) = self.pinned_monitor_mut(None) {
+            mon.pinned.toggle_window_width(None);
+            return;
+        }
+
         let Some(workspace) = self.active_workspace_mut() else {
             return;
         };
@@ -3192,6 +3721,11 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        if let Some(mon) = self.pinned_monitor_mut(window) {
+            mon.pinned.toggle_window_width(window);
+            return;
+        }
+
         let workspace = if let Some(window) = window {
             Some(
                 self.workspaces_mut()
@@ -3215,6 +3749,11 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        if let Some(mon) = self.pinned_monitor_mut(window) {
+            mon.pinned.toggle_window_height(window);
+            return;
+        }
+
         let workspace = if let Some(window) = window {
             Some(
                 self.workspaces_mut()
@@ -3232,6 +3771,10 @@ impl<W: LayoutElement> Layout<W> {
     }
 
     pub fn toggle_full_width(&mut self) {
+        if self.pinned_monitor_mut(None).is_some() {
+            return;
+        }
+
         let Some(workspace) = self.active_workspace_mut() else {
             return;
         };
@@ -3239,6 +3782,11 @@ impl<W: LayoutElement> Layout<W> {
     }
 
     pub fn set_column_width(&mut self, change: SizeChange) {
+        if let Some(mon) = self.pinned_monitor_mut(None) {
+            mon.pinned.set_window_width(None, change, true);
+            return;
+        }
+
         let Some(workspace) = self.active_workspace_mut() else {
             return;
         };
@@ -3252,6 +3800,11 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        if let Some(mon) = self.pinned_monitor_mut(window) {
+            mon.pinned.set_window_width(window, change, true);
+            return;
+        }
+
         let workspace = if let Some(window) = window {
             Some(
                 self.workspaces_mut()
@@ -3275,6 +3828,11 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        if let Some(mon) = self.pinned_monitor_mut(window) {
+            mon.pinned.set_window_height(window, change, true);
+            return;
+        }
+
         let workspace = if let Some(window) = window {
             Some(
                 self.workspaces_mut()
@@ -3298,6 +3856,11 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        // Floating windows have no automatic height to reset to.
+        if self.pinned_monitor_mut(window).is_some() {
+            return;
+        }
+
         let workspace = if let Some(window) = window {
             Some(
                 self.workspaces_mut()
@@ -3363,6 +3926,9 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        // Pinned windows are floating, so unpin the window first.
+        self.unpin_window(window);
+
         let workspace = if let Some(window) = window {
             Some(
                 self.workspaces_mut()
@@ -3389,6 +3955,12 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        if !floating {
+            self.unpin_window(window);
+        } else if self.pinned_monitor_mut(window).is_some() {
+            return;
+        }
+
         let workspace = if let Some(window) = window {
             Some(
                 self.workspaces_mut()
@@ -3405,6 +3977,140 @@ impl<W: LayoutElement> Layout<W> {
         workspace.set_window_floating(window, floating);
     }
 
+    pub fn toggle_window_pinned(&mut self, window: Option<&W::Id>) {
+        if let Some(InteractiveMoveState::Moving(move_)) = &self.interactive_move {
+            if window.is_none() || window == Some(move_.tile.window().id()) {
+                return;
+            }
+        }
+
+        let mon = if let Some(window) = window {
+            self.monitors_mut().find(|mon| mon.has_window(window))
+        } else {
+            self.active_monitor()
+        };
+
+        // Without outputs there's nothing to pin the window to.
+        let Some(mon) = mon else {
+            return;
+        };
+        mon.toggle_window_pinned(window);
+    }
+
+    /// Unpins the window to the active workspace of its monitor, if it is pinned.
+    ///
+    /// `None` refers to the focused window.
+    fn unpin_window(&mut self, window: Option<&W::Id>) {
+        if let Some(mon) = self.pinned_monitor_mut(window) {
+            mon.toggle_window_pinned(window);
+        }
+    }
+
+    pub fn minimize_window(&mut self, window: Option<&W::Id>) {
+        if let Some(InteractiveMoveState::Moving(move_)) = &self.interactive_move {
+            if window.is_none() || window == Some(move_.tile.window().id()) {
+                return;
+            }
+        }
+
+        // The window may be already minimized, in which case it's not found here.
+        self.unpin_window(window);
+
+        let workspace = if let Some(window) = window {
+            self.workspaces_mut().find(|ws| ws.has_window(window))
+        } else {
+            self.active_workspace_mut()
+        };
+
+        let Some(workspace) = workspace else {
+            return;
+        };
+        workspace.minimize_window(window);
+    }
+
+    pub fn restore_minimized(&mut self, window: Option<&W::Id>) {
+        if let Some(window) = window {
+            // Activating a minimized window restores it in place.
+            self.activate_window(window);
+            return;
+        }
+
+        let Some(workspace) = self.active_workspace_mut() else {
+            return;
+        };
+        workspace.restore_minimized(None);
+    }
+
+    pub fn toggle_window_keep_above(&mut self, window: Option<&W::Id>) {
+        if let Some(InteractiveMoveState::Moving(move_)) = &self.interactive_move {
+            if window.is_none() || window == Some(move_.tile.window().id()) {
+                return;
+            }
+        }
+
+        if let Some(mon) = self.pinned_monitor_mut(window) {
+            let Some(id) = window
+                .or(mon.pinned.active_window().map(|win| win.id()))
+                .cloned()
+            else {
+                return;
+            };
+            let keep_above = mon.pinned.is_window_kept_above(&id);
+            mon.pinned.set_window_keep_above(&id, !keep_above);
+            return;
+        }
+
+        let workspace = if let Some(window) = window {
+            Some(
+                self.workspaces_mut()
+                    .find(|ws| ws.has_window(window))
+                    .unwrap(),
+            )
+        } else {
+            self.active_workspace_mut()
+        };
+
+        let Some(workspace) = workspace else {
+            return;
+        };
+        workspace.toggle_window_keep_above(window);
+    }
+
+    pub fn toggle_window_keep_below(&mut self, window: Option<&W::Id>) {
+        if let Some(InteractiveMoveState::Moving(move_)) = &self.interactive_move {
+            if window.is_none() || window == Some(move_.tile.window().id()) {
+                return;
+            }
+        }
+
+        if let Some(mon) = self.pinned_monitor_mut(window) {
+            let Some(id) = window
+                .or(mon.pinned.active_window().map(|win| win.id()))
+                .cloned()
+            else {
+                return;
+            };
+            let keep_below = mon.pinned.is_window_kept_below(&id);
+            mon.pinned.set_window_keep_below(&id, !keep_below);
+            return;
+        }
+
+        let workspace = if let Some(window) = window {
+            Some(
+                self.workspaces_mut()
+                    .find(|ws| ws.has_window(window))
+                    .unwrap(),
+            )
+        } else {
+            self.active_workspace_mut()
+        };
+
+        let Some(workspace) = workspace else {
+            return;
+        };
+        workspace.toggle_window_keep_below(window);
+    }
+
     pub fn focus_floating(&mut self) {
         let Some(workspace) = self.active_workspace_mut() else {
             return;
@@ -3439,6 +4145,11 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        if let Some(mon) = self.pinned_monitor_mut(id) {
+            mon.pinned.move_window(id, x, y, animate);
+            return;
+        }
+
         let workspace = if let Some(id) = id {
             Some(self.workspaces_mut().find(|ws| ws.has_window(id)).unwrap())
         } else {
@@ -3451,6 +4162,30 @@ impl<W: LayoutElement> Layout<W> {
         workspace.move_floating_window(id, x, y, animate);
     }
 
+    pub fn tile_floating_window(&mut self, id: Option<&W::Id>, area: FloatingTileArea) {
+        if let Some(InteractiveMoveState::Moving(move_)) = &self.interactive_move {
+            if id.is_none() || id == Some(move_.tile.window().id()) {
+                return;
+            }
+        }
+
+        if let Some(mon) = self.pinned_monitor_mut(id) {
+            mon.pinned.tile_window_to_area(id, area, true);
+            return;
+        }
+
+        let workspace = if let Some(id) = id {
+            Some(self.workspaces_mut().find(|ws| ws.has_window(id)).unwrap())
+        } else {
+            self.active_workspace_mut()
+        };
+
+        let Some(workspace) = workspace else {
+            return;
+        };
+        workspace.tile_floating_window(id, area);
+    }
+
     pub fn focus_output(&mut self, output: &Output) {
         if let MonitorSet::Normal {
             monitors,
@@ -3474,6 +4209,13 @@ impl<W: LayoutElement> Layout<W> {
         target_ws_idx: Option<usize>,
         activate: ActivateWindow,
     ) {
+        // Moving a modal dialog moves its parent instead.
+        let redirect = self.redirect_modal_move(window, activate);
+        let (window, activate) = match &redirect {
+            Some((root, activate)) => (Some(root), *activate),
+            None => (window, activate),
+        };
+
         if let Some(InteractiveMoveState::Moving(move_)) = &mut self.interactive_move {
             if window.is_none() || window == Some(move_.tile.window().id()) {
                 return;
@@ -3491,25 +4233,31 @@ impl<W: LayoutElement> Layout<W> {
                 .position(|mon| &mon.output == output)
                 .unwrap();
 
+            // Pinned windows have no workspace.
             let (mon_idx, ws_idx) = if let Some(window) = window {
                 monitors
                     .iter()
                     .enumerate()
                     .find_map(|(mon_idx, mon)| {
+                        if mon.pinned.has_window(window) {
+                            return Some((mon_idx, None));
+                        }
+
                         mon.workspaces
                             .iter()
                             .position(|ws| ws.has_window(window))
-                            .map(|ws_idx| (mon_idx, ws_idx))
+                            .map(|ws_idx| (mon_idx, Some(ws_idx)))
                     })
                     .unwrap()
             } else {
                 let mon_idx = *active_monitor_idx;
                 let mon = &monitors[mon_idx];
-                (mon_idx, mon.active_workspace_idx)
+                let ws_idx = (!mon.pinned_is_active).then_some(mon.active_workspace_idx);
+                (mon_idx, ws_idx)
             };
 
             let workspace_idx = target_ws_idx.unwrap_or(monitors[new_idx].active_workspace_idx);
-            if mon_idx == new_idx && ws_idx == workspace_idx {
+            if mon_idx == new_idx && ws_idx.map_or(true, |ws_idx| ws_idx == workspace_idx) {
                 return;
             }
 
@@ -3533,14 +4281,21 @@ impl<W: LayoutElement> Layout<W> {
                 ActivateWindow::No
             };
 
-            let ws = &mut mon.workspaces[ws_idx];
-            let transaction = Transaction::new();
-            let mut removed = if let Some(window) = window {
-                ws.remove_tile(window, transaction)
-            } else if let Some(removed) = ws.remove_active_tile(transaction) {
-                removed
+            let mut removed = if let Some(ws_idx) = ws_idx {
+                let ws = &mut mon.workspaces[ws_idx];
+                let transaction = Transaction::new();
+                if let Some(window) = window {
+                    ws.remove_tile(window, transaction)
+                } else if let Some(removed) = ws.remove_active_tile(transaction) {
+                    removed
+                } else {
+                    return;
+                }
             } else {
-                return;
+                // The tile stays pinned and goes to the pinned windows of the new monitor.
+                let id = window.or(mon.pinned.active_window().map(|win| win.id()));
+                let id = id.unwrap().clone();
+                mon.remove_pinned_tile(&id)
             };
 
             removed.tile.stop_move_animations();
@@ -3560,6 +4315,11 @@ impl<W: LayoutElement> Layout<W> {
             );
             if activate.map_smart(|| false) {
                 *active_monitor_idx = new_idx;
+
+                // Pinned windows are on every workspace, so only the focus moves there.
+                if ws_idx.is_none() && target_ws_idx.is_some() {
+                    mon.switch_workspace(workspace_idx);
+                }
             }
 
             let mon = &mut monitors[mon_idx];
@@ -3567,6 +4327,8 @@ impl<W: LayoutElement> Layout<W> {
                 monitors[mon_idx].clean_up_workspaces();
             }
         }
+
+        self.move_modals_to_parents();
     }
 
     pub fn move_column_to_output(
@@ -3587,8 +4349,12 @@ impl<W: LayoutElement> Layout<W> {
                 .unwrap();
 
             let current = &mut monitors[*active_monitor_idx];
-            let ws = current.active_workspace();
+            if current.pinned_is_active {
+                self.move_to_output(None, output, None, ActivateWindow::Smart);
+                return;
+            }
 
+            let ws = current.active_workspace();
             if ws.floating_is_active() {
                 self.move_to_output(None, output, None, ActivateWindow::Smart);
                 return;
@@ -3603,6 +4369,8 @@ impl<W: LayoutElement> Layout<W> {
                 .min(monitors[new_idx].workspaces.len() - 1);
             self.add_column_by_idx(new_idx, workspace_idx, column, activate);
         }
+
+        self.move_modals_to_parents();
     }
 
     pub fn move_workspace_to_output(&mut self, output: &Output) -> bool {
@@ -3784,6 +4552,11 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        // Pinned windows go fullscreen on the active workspace.
+        if is_fullscreen {
+            self.unpin_window(Some(id));
+        }
+
         for ws in self.workspaces_mut() {
             if ws.has_window(id) {
                 ws.set_fullscreen(id, is_fullscreen);
@@ -3799,6 +4572,9 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        // Pinned windows go fullscreen on the active workspace.
+        self.unpin_window(Some(id));
+
         for ws in self.workspaces_mut() {
             if ws.has_window(id) {
                 ws.toggle_fullscreen(id);
@@ -4040,15 +4816,7 @@ impl<W: LayoutElement> Layout<W> {
             return false;
         }
 
-        let MonitorSet::Normal { monitors, .. } = &mut self.monitor_set else {
-            return false;
-        };
-
-        let Some((mon, (ws, ws_geo))) = monitors.iter().find_map(|mon| {
-            mon.workspaces_with_render_geo()
-                .find(|(ws, _)| ws.has_window(&window_id))
-                .map(|rv| (mon, rv))
-        }) else {
+        let Some((mon, tile, tile_pos, is_floating)) = self.tile_with_render_pos(&window_id) else {
             return false;
         };
 
@@ -4057,16 +4825,8 @@ impl<W: LayoutElement> Layout<W> {
         }
 
         let zoom = mon.overview_zoom();
-
-        let is_floating = ws.is_floating(&window_id);
-        let (tile, tile_offset, _visible) = ws
-            .tiles_with_render_positions()
-            .find(|(tile, _, _)| tile.window().id() == &window_id)
-            .unwrap();
         let window_offset = tile.window_loc();
 
-        let tile_pos = ws_geo.loc + tile_offset.upscale(zoom);
-
         let pointer_offset_within_window =
             start_pos_within_output - tile_pos - window_offset.upscale(zoom);
         let window_size = tile.window_size().upscale(zoom);
@@ -4097,6 +4857,73 @@ impl<W: LayoutElement> Layout<W> {
         true
     }
 
+    /// Returns the window's tile with its monitor, output-local render position and whether it
+    /// is floating.
+    fn tile_with_render_pos(
+        &self,
+        window: &W::Id,
+    ) -> Option<(&Monitor<W>, &Tile<W>, Point<f64, Logical>, bool)> {
+        self.monitors().find_map(|mon| {
+            let zoom = mon.overview_zoom();
+
+            let geo = mon.pinned_render_geo();
+            let mut pinned = mon.pinned.tiles_with_render_positions();
+            if let Some((tile, offset)) = pinned.find(|(tile, _)| tile.window().id() == window) {
+                return Some((mon, tile, geo.loc + offset.upscale(zoom), true));
+            }
+
+            let (ws, geo) = mon
+                .workspaces_with_render_geo()
+                .find(|(ws, _)| ws.has_window(window))?;
+            let (tile, offset, _visible) = ws
+                .tiles_with_render_positions()
+                .find(|(tile, _, _)| tile.window().id() == window)
+                .unwrap();
+            Some((
+                mon,
+                tile,
+                geo.loc + offset.upscale(zoom),
+                ws.is_floating(window),
+            ))
+        })
+    }
+
+    /// Begins an interactive move with the pointer at the given offset within the window.
+    ///
+    /// The offset is relative to the window geometry. This is used when the client knows where the
+    /// window should be grabbed, for example when tearing a tab off into a new window.
+    pub fn interactive_move_begin_at_offset(
+        &mut self,
+        window_id: W::Id,
+        output: &Output,
+        start_pos_within_output: Point<f64, Logical>,
+        offset: Point<f64, Logical>,
+    ) -> bool {
+        let Some((_, window)) = self.windows().find(|(_, win)| win.id() == &window_id) else {
+            return false;
+        };
+        let window_size = window.size().to_f64();
+
+        if !self.interactive_move_begin(window_id, output, start_pos_within_output) {
+            return false;
+        }
+
+        if let Some(InteractiveMoveState::Starting {
+            pointer_ratio_within_window,
+            ..
+        }) = &mut self.interactive_move
+        {
+            if window_size.w > 0. && window_size.h > 0. {
+                *pointer_ratio_within_window = (
+                    f64::clamp(offset.x / window_size.w, 0., 1.),
+                    f64::clamp(offset.y / window_size.h, 0., 1.),
+                );
+            }
+        }
+
+        true
+    }
+
     pub fn interactive_move_update(
         &mut self,
         window: &W::Id,
@@ -4137,18 +4964,25 @@ impl<W: LayoutElement> Layout<W> {
                 }
                 .band(sq_dist / INTERACTIVE_MOVE_START_THRESHOLD);
 
-                let (is_floating, tile) = self
-                    .workspaces_mut()
-                    .find(|ws| ws.has_window(&window_id))
-                    .map(|ws| {
-                        (
-                            ws.is_floating(&window_id),
-                            ws.tiles_mut()
-                                .find(|tile| *tile.window().id() == window_id)
-                                .unwrap(),
-                        )
-                    })
-                    .unwrap();
+                let (is_floating, tile) = if let Some(mon) = self.pinned_monitor_mut(window) {
+                    let tile = mon
+                        .pinned
+                        .tiles_mut()
+                        .find(|tile| tile.window().id() == window);
+                    (true, tile.unwrap())
+                } else {
+                    self.workspaces_mut()
+                        .find(|ws| ws.has_window(&window_id))
+                        .map(|ws| {
+                            (
+                                ws.is_floating(&window_id),
+                                ws.tiles_mut()
+                                    .find(|tile| *tile.window().id() == window_id)
+                                    .unwrap(),
+                            )
+                        })
+                        .unwrap()
+                };
                 tile.interactive_move_offset = pointer_delta.upscale(factor);
 
                 // Put it back to be able to easily return.
@@ -4170,21 +5004,9 @@ impl<W: LayoutElement> Layout<W> {
                 // FIXME: when and if the layout code knows about monitor positions, this will be
                 // potentially animatable.
                 let mut tile_pos = None;
-                if let MonitorSet::Normal { monitors, .. } = &self.monitor_set {
-                    if let Some((mon, (ws, ws_geo))) = monitors.iter().find_map(|mon| {
-                        mon.workspaces_with_render_geo()
-                            .find(|(ws, _)| ws.has_window(window))
-                            .map(|rv| (mon, rv))
-                    }) {
-                        if mon.output() == &output {
-                            let (_, tile_offset, _) = ws
-                                .tiles_with_render_positions()
-                                .find(|(tile, _, _)| tile.window().id() == window)
-                                .unwrap();
-
-                            let zoom = mon.overview_zoom();
-                            tile_pos = Some((ws_geo.loc + tile_offset.upscale(zoom), zoom));
-                        }
+                if let Some((mon, _, pos, _)) = self.tile_with_render_pos(window) {
+                    if mon.output() == &output {
+                        tile_pos = Some((pos, mon.overview_zoom()));
                     }
                 }
 
@@ -4192,12 +5014,15 @@ impl<W: LayoutElement> Layout<W> {
                 // in the middle of interactive_move_update() and the confusion that causes.
                 self.interactive_move = None;
 
+                // The window stays in the layout, so keep its modal dialog state.
+                let modals = mem::take(&mut self.modals);
                 let RemovedTile {
                     mut tile,
                     width,
                     is_full_width,
                     mut is_floating,
                 } = self.remove_window(window, Transaction::new()).unwrap();
+                self.modals = modals;
 
                 tile.stop_move_animations();
                 tile.interactive_move_offset = Point::from((0., 0.));
@@ -4266,6 +5091,7 @@ impl<W: LayoutElement> Layout<W> {
                     is_full_width,
                     is_floating,
                     pointer_ratio_within_window,
+                    snap_offset: Point::from((0., 0.)),
                 };
 
                 if let Some((tile_pos, zoom)) = tile_pos {
@@ -4302,6 +5128,24 @@ impl<W: LayoutElement> Layout<W> {
 
                 move_.pointer_pos_within_output = pointer_pos_within_output;
 
+                // Snap floating windows to nearby edges.
+                move_.snap_offset = Point::from((0., 0.));
+                if move_.is_floating && !self.overview_open {
+                    if let Some(mon) = self.monitor_for_output(&move_.output) {
+                        let zoom = mon.overview_zoom();
+                        let (insert_ws, geo) = mon.insert_position(pointer_pos_within_output);
+                        if let InsertWorkspace::Existing(ws_id) = insert_ws {
+                            let ws = mon.workspaces.iter().find(|ws| ws.id() == ws_id).unwrap();
+                            let tile_pos = (move_.tile_render_location(zoom) - geo.loc)
+                                .downscale(zoom)
+                                - move_.tile.render_offset();
+                            let size = move_.tile.tile_size();
+                            let snapped = ws.floating_snap_tile_pos(tile_pos, size);
+                            move_.snap_offset = snapped - tile_pos;
+                        }
+                    }
+                }
+
                 self.interactive_move = Some(InteractiveMoveState::Moving(move_));
             }
         }
@@ -4332,6 +5176,14 @@ impl<W: LayoutElement> Layout<W> {
                     }
                 }
 
+                if let Some(mon) = self.pinned_monitor_mut(Some(&window_id)) {
+                    let tile = mon.pinned.tiles_mut();
+                    let tile = tile.find(|tile| *tile.window().id() == window_id).unwrap();
+                    let offset = tile.interactive_move_offset;
+                    tile.interactive_move_offset = Point::from((0., 0.));
+                    tile.animate_move_from(offset);
+                }
+
                 let mut ws_id = None;
                 for ws in self.workspaces_mut() {
                     let id = ws.id();
@@ -4363,8 +5215,10 @@ impl<W: LayoutElement> Layout<W> {
                 // that if we "just click" then we end up in this branch with state == Starting.
                 // Close the overview in this case.
                 if self.overview_open {
-                    let ws_id = ws_id.unwrap();
-                    if let MonitorSet::Normal { monitors, .. } = &mut self.monitor_set {
+                    // Pinned windows are shown on the active workspace, which stays.
+                    if let (Some(ws_id), MonitorSet::Normal { monitors, .. }) =
+                        (ws_id, &mut self.monitor_set)
+                    {
                         for mon in monitors {
                             if let Some(ws_idx) =
                                 mon.workspaces.iter().position(|ws| ws.id() == ws_id)
@@ -4417,8 +5271,66 @@ impl<W: LayoutElement> Layout<W> {
 
         // Dragging in the overview shouldn't switch the workspace and so on.
         let allow_to_activate_workspace = !self.overview_open;
+        // Edge tiling makes no sense with zoomed out workspaces.
+        let edge_tiling = !self.overview_open;
 
         match &mut self.monitor_set {
+            MonitorSet::Normal {
+                monitors,
+                active_monitor_idx,
+                ..
+            } if move_.is_floating && move_.tile.is_pinned => {
+                // Pinned windows stay pinned wherever they are dropped.
+                let on_output = monitors.iter().any(|mon| mon.output == move_.output);
+                let mon = if on_output {
+                    monitors
+                        .iter_mut()
+                        .find(|mon| mon.output == move_.output)
+                        .unwrap()
+                } else {
+                    &mut monitors[*active_monitor_idx]
+                };
+                let zoom = mon.overview_zoom();
+                let geo = mon.pinned_render_geo();
+
+                let win_id = move_.tile.window().id().clone();
+                let tile_render_loc = move_.tile_render_location(zoom);
+                let window_render_loc = tile_render_loc + move_.tile.window_loc();
+
+                let mut tile = move_.tile;
+                tile.floating_pos = None;
+                // No point in trying to use the pointer position on the wrong output.
+                if on_output {
+                    let pos = (tile_render_loc - geo.loc).downscale(zoom);
+                    tile.floating_pos = Some(mon.pinned.logical_to_size_frac(pos));
+                }
+
+                // Set the floating size so it takes into account any window resizing that took
+                // place during the move.
+                if let Some(size) = tile.window().expected_size() {
+                    tile.floating_window_size = Some(size);
+                }
+
+                mon.add_tile(
+                    tile,
+                    MonitorAddWindowTarget::Auto,
+                    ActivateWindow::Yes,
+                    false,
+                    move_.width,
+                    move_.is_full_width,
+                    true,
+                );
+
+                let (tile, tile_render_loc) = mon
+                    .pinned
+                    .tiles_with_render_positions_mut(false)
+                    .find(|(tile, _)| tile.window().id() == &win_id)
+                    .unwrap();
+                let new_window_render_loc =
+                    geo.loc + (tile_render_loc + tile.window_loc()).upscale(zoom);
+
+                tile.animate_move_from((window_render_loc - new_window_render_loc).downscale(zoom));
+            }
             MonitorSet::Normal {
                 monitors,
                 active_monitor_idx,
@@ -4437,12 +5349,12 @@ impl<W: LayoutElement> Layout<W> {
                                     .position(|ws| ws.id() == ws_id)
                                     .unwrap();
 
+                                let pos_within_workspace =
+                                    (move_.pointer_pos_within_output - geo.loc).downscale(zoom);
+                                let ws = &mon.workspaces[ws_idx];
                                 let position = if move_.is_floating {
-                                    InsertPosition::Floating
+                                    ws.floating_insert_position(pos_within_workspace, edge_tiling)
                                 } else {
-                                    let pos_within_workspace =
-                                        (move_.pointer_pos_within_output - geo.loc).downscale(zoom);
-                                    let ws = &mut mon.workspaces[ws_idx];
                                     ws.scrolling_insert_position(pos_within_workspace)
                                 };
 
@@ -4526,7 +5438,7 @@ impl<W: LayoutElement> Layout<W> {
                             allow_to_activate_workspace,
                         );
                     }
-                    InsertPosition::Floating => {
+                    InsertPosition::Floating | InsertPosition::FloatingEdgeTile(_) => {
                         let tile_render_loc = move_.tile_render_location(zoom);
 
                         let mut tile = move_.tile;
@@ -4571,6 +5483,11 @@ impl<W: LayoutElement> Layout<W> {
                             move_.is_full_width,
                             true,
                         );
+
+                        if let InsertPosition::FloatingEdgeTile(area) = position {
+                            let ws = mon.workspaces.iter_mut().find(|ws| ws.id() == ws_id);
+                            ws.unwrap().tile_floating_window(Some(&win_id), area);
+                        }
                     }
                 }
 
@@ -4608,6 +5525,9 @@ impl<W: LayoutElement> Layout<W> {
                 );
             }
         }
+
+        // The window may have been moved to another workspace.
+        self.move_modals_to_parents();
     }
 
     pub fn interactive_move_is_moving_above_output(&self, output: &Output) -> bool {
@@ -4662,6 +5582,10 @@ impl<W: LayoutElement> Layout<W> {
         match &mut self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    if mon.pinned.has_window(&window) {
+                        return mon.pinned.interactive_resize_begin(window, edges);
+                    }
+
                     for ws in &mut mon.workspaces {
                         if ws.has_window(&window) {
                             return ws.interactive_resize_begin(window, edges);
@@ -4695,6 +5619,10 @@ impl<W: LayoutElement> Layout<W> {
         match &mut self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    if mon.pinned.has_window(window) {
+                        return mon.pinned.interactive_resize_update(window, delta);
+                    }
+
                     for ws in &mut mon.workspaces {
                         if ws.has_window(window) {
                             return ws.interactive_resize_update(window, delta);
@@ -4724,6 +5652,11 @@ impl<W: LayoutElement> Layout<W> {
         match &mut self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    if mon.pinned.has_window(window) {
+                        mon.pinned.interactive_resize_end(Some(window));
+                        return;
+                    }
+
                     for ws in &mut mon.workspaces {
                         if ws.has_window(window) {
                             ws.interactive_resize_end(Some(window));
@@ -4804,6 +5737,7 @@ impl<W: LayoutElement> Layout<W> {
         };
 
         ws.name.replace(name);
+        ws.refresh_scroll_orientation();
 
         let wsid = ws.id();
 
@@ -4852,6 +5786,23 @@ impl<W: LayoutElement> Layout<W> {
         self.unname_workspace_by_id(id);
     }
 
+    pub fn set_workspace_layout_kind(
+        &mut self,
+        kind: LayoutKind,
+        reference: Option<WorkspaceReference>,
+    ) {
+        let ws = if let Some(reference) = reference {
+            self.find_workspace_by_ref(reference)
+        } else {
+            self.active_workspace_mut()
+        };
+        let Some(ws) = ws else {
+            return;
+        };
+
+        ws.set_layout_kind(kind);
+    }
+
     pub fn set_monitors_overview_state(&mut self) {
         let MonitorSet::Normal { monitors, .. } = &mut self.monitor_set else {
             return;
@@ -4861,6 +5812,10 @@ impl<W: LayoutElement> Layout<W> {
             mon.overview_open = self.overview_open;
             mon.set_overview_progress(self.overview_progress.as_ref());
         }
+
+        if !self.overview_open {
+            self.clear_overview_search();
+        }
     }
 
     pub fn toggle_overview(&mut self) {
@@ -4906,6 +5861,148 @@ impl<W: LayoutElement> Layout<W> {
         self.toggle_overview();
     }
 
+    pub fn overview_search(&self) -> &str {
+        &self.overview_search
+    }
+
+    /// Appends a character to the overview search and focuses the best matching window.
+    pub fn overview_search_push(&mut self, c: char) {
+        if !self.overview_open {
+            return;
+        }
+
+        self.overview_search.push(c);
+        self.update_overview_search(true);
+    }
+
+    /// Removes the last character from the overview search.
+    pub fn overview_search_pop(&mut self) -> bool {
+        if self.overview_search.pop().is_none() {
+            return false;
+        }
+
+        self.update_overview_search(true);
+        true
+    }
+
+    pub fn clear_overview_search(&mut self) -> bool {
+        if self.overview_search.is_empty() {
+            return false;
+        }
+
+        self.overview_search.clear();
+        self.update_overview_search(false);
+        true
+    }
+
+    /// Focuses the next or the previous window matching the overview search.
+    pub fn overview_search_focus_next(&mut self, forward: bool) {
+        let matches = self.overview_search_matches();
+        if matches.is_empty() {
+            return;
+        }
+
+        let focus = self.focus().map(|win| win.id().clone());
+        let idx = focus.and_then(|focus| matches.iter().position(|(id, _)| *id == focus));
+        let idx = match idx {
+            Some(idx) if forward => (idx + 1) % matches.len(),
+            Some(idx) => (idx + matches.len() - 1) % matches.len(),
+            None => 0,
+        };
+
+        let id = matches[idx].0.clone();
+        self.activate_window(&id);
+    }
+
+    /// Returns the windows matching the overview search, along with how well they match.
+    fn overview_search_matches(&self) -> Vec<(W::Id, u8)> {
+        let query = self.overview_search.to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let score = |text: Option<String>| {
+            let text = text?.to_lowercase();
+            if text == query {
+                Some(3)
+            } else if text.starts_with(&query) {
+                Some(2)
+            } else if text.contains(&query) {
+                Some(1)
+            } else {
+                None
+            }
+        };
+
+        let pinned = self
+            .monitors()
+            .flat_map(|mon| mon.pinned.tiles().map(Tile::window));
+        let rest = self.workspaces().flat_map(|(_, _, ws)| ws.windows());
+
+        let mut rv = Vec::new();
+        for win in pinned.chain(rest) {
+            if let Some(score) = Option::max(score(win.app_id()), score(win.title())) {
+                rv.push((win.id().clone(), score));
+            }
+        }
+        rv
+    }
+
+    /// Dims the windows that don't match the overview search.
+    ///
+    /// If `activate_best` is set, also focuses the best matching window, unless the focused window
+    /// already matches as well.
+    fn update_overview_search(&mut self, activate_best: bool) {
+        let matches = self.overview_search_matches();
+        self.update_overview_search_dim(&matches);
+
+        if !activate_best {
+            return;
+        }
+
+        let Some(best) = matches.iter().map(|(_, score)| *score).max() else {
+            return;
+        };
+
+        let focus = self.focus().map(|win| win.id().clone());
+        if let Some(focus) = focus {
+            if matches.contains(&(focus, best)) {
+                return;
+            }
+        }
+
+        let id = matches
+            .iter()
+            .find(|(_, score)| *score == best)
+            .unwrap()
+            .0
+            .clone();
+        self.activate_window(&id);
+    }
+
+    /// Dims the windows that aren't among the matches while searching, and undims the rest.
+    fn update_overview_search_dim(&mut self, matches: &[(W::Id, u8)]) {
+        let is_searching = !self.overview_search.is_empty();
+        let config = self.options.animations.window_movement.0;
+
+        let mut update = |tile: &mut Tile<W>| {
+            let is_match = matches.iter().any(|(id, _)| id == tile.window().id());
+            let to = if is_searching && !is_match {
+                OVERVIEW_SEARCH_DIM_ALPHA
+            } else {
+                1.
+            };
+            tile.animate_dim(to, config);
+        };
+
+        for mon in self.monitors_mut() {
+            mon.pinned.tiles_mut().for_each(&mut update);
+        }
+        for ws in self.workspaces_mut() {
+            ws.tiles_mut().for_each(&mut update);
+        }
+    }
+
     pub fn start_open_animation_for_window(&mut self, window: &W::Id) {
         if let Some(InteractiveMoveState::Moving(move_)) = &self.interactive_move {
             if move_.tile.window().id() == window {
@@ -4913,6 +6010,12 @@ impl<W: LayoutElement> Layout<W> {
             }
         }
 
+        for mon in self.monitors_mut() {
+            if mon.pinned.start_open_animation(window) {
+                return;
+            }
+        }
+
         for ws in self.workspaces_mut() {
             if ws.start_open_animation(window) {
                 return;
@@ -4933,6 +6036,18 @@ impl<W: LayoutElement> Layout<W> {
         match &mut self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    let view_size = mon.view_size();
+                    let pinned = mon.pinned.tiles_with_render_positions_mut(false);
+                    for (tile, tile_pos) in pinned {
+                        if tile.window().id() == window {
+                            let view_pos = Point::from((-tile_pos.x, -tile_pos.y));
+                            let view_rect = Rectangle::new(view_pos, view_size);
+                            tile.update_render_elements(false, view_rect);
+                            tile.store_unmap_snapshot_if_empty(renderer);
+                            return;
+                        }
+                    }
+
                     for ws in &mut mon.workspaces {
                         if ws.has_window(window) {
                             ws.store_unmap_snapshot_if_empty(renderer, window);
@@ -4963,6 +6078,12 @@ impl<W: LayoutElement> Layout<W> {
         match &mut self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    let mut pinned = mon.pinned.tiles_mut();
+                    if let Some(tile) = pinned.find(|tile| tile.window().id() == window) {
+                        let _ = tile.take_unmap_snapshot();
+                        return;
+                    }
+
                     for ws in &mut mon.workspaces {
                         if ws.has_window(window) {
                             ws.clear_unmap_snapshot(window);
@@ -5024,6 +6145,12 @@ impl<W: LayoutElement> Layout<W> {
         match &mut self.monitor_set {
             MonitorSet::Normal { monitors, .. } => {
                 for mon in monitors {
+                    if mon.pinned.has_window(window) {
+                        mon.pinned
+                            .start_close_animation_for_window(renderer, window, blocker);
+                        return;
+                    }
+
                     for ws in &mut mon.workspaces {
                         if ws.has_window(window) {
                             ws.start_close_animation_for_window(renderer, window, blocker);
@@ -5082,13 +6209,26 @@ impl<W: LayoutElement> Layout<W> {
 
         self.is_active = is_active;
 
+        // Windows open and move around during the search, so keep their dimming up to date.
+        if !self.overview_search.is_empty() {
+            let matches = self.overview_search_matches();
+            self.update_overview_search_dim(&matches);
+        }
+
         let mut ongoing_scrolling_dnd = self.dnd.is_some().then_some(true);
 
         if let Some(InteractiveMoveState::Moving(move_)) = &mut self.interactive_move {
+            let is_pinned = move_.is_floating && move_.tile.is_pinned;
+            let kept_above = move_.is_floating && move_.tile.keep_above;
+            let kept_below = move_.is_floating && move_.tile.keep_below;
             let win = move_.tile.window_mut();
 
             win.set_active_in_column(true);
             win.set_floating(move_.is_floating);
+            win.set_pinned(is_pinned);
+            win.set_kept_above(kept_above);
+            win.set_kept_below(kept_below);
+            win.set_minimized(false);
             win.set_activated(true);
 
             win.set_interactive_resize(None);
@@ -5103,10 +6243,13 @@ impl<W: LayoutElement> Layout<W> {
             &self.interactive_move
         {
             ongoing_scrolling_dnd.get_or_insert_with(|| {
-                let (_, _, ws) = self
+                // Pinned windows are floating.
+                let Some((_, _, ws)) = self
                     .workspaces()
                     .find(|(_, _, ws)| ws.has_window(window_id))
-                    .unwrap();
+                else {
+                    return false;
+                };
                 !ws.is_floating(window_id)
             });
         }
@@ -5122,6 +6265,10 @@ impl<W: LayoutElement> Layout<W> {
                         && idx == *active_monitor_idx
                         && !matches!(self.interactive_move, Some(InteractiveMoveState::Moving(_)));
 
+                    let pinned_is_active = is_active && mon.pinned_is_active;
+                    mon.pinned.refresh(pinned_is_active, pinned_is_active);
+                    let is_active = is_active && !mon.pinned_is_active;
+
                     if ongoing_scrolling_dnd.is_some() && self.overview_open {
                         // Begin the scroll on new monitors and when opening the overview.
                         mon.dnd_scroll_gesture_begin();
@@ -5192,6 +6339,46 @@ impl<W: LayoutElement> Layout<W> {
         iter_normal.chain(iter_no_outputs)
     }
 
+    fn monitors(&self) -> impl Iterator<Item = &Monitor<W>> + '_ {
+        let monitors: &[Monitor<W>] = match &self.monitor_set {
+            MonitorSet::Normal { monitors, .. } => &monitors[..],
+            MonitorSet::NoOutputs { .. } => &[],
+        };
+        monitors.iter()
+    }
+
+    fn monitors_mut(&mut self) -> impl Iterator<Item = &mut Monitor<W>> + '_ {
+        let monitors: &mut [Monitor<W>] = match &mut self.monitor_set {
+            MonitorSet::Normal { monitors, .. } => &mut monitors[..],
+            MonitorSet::NoOutputs { .. } => &mut [],
+        };
+        monitors.iter_mut()
+    }
+
+    /// Returns the monitor with this pinned window.
+    ///
+    /// `None` refers to the focused window.
+    fn pinned_monitor_mut(&mut self, window: Option<&W::Id>) -> Option<&mut Monitor<W>> {
+        let MonitorSet::Normal {
+            monitors,
+            active_monitor_idx,
+            ..
+        } = &mut self.monitor_set
+        else {
+            return None;
+        };
+
+        match window {
+            Some(window) => monitors
+                .iter_mut()
+                .find(|mon| mon.pinned.has_window(window)),
+            None => {
+                let mon = &mut monitors[*active_monitor_idx];
+                mon.pinned_is_active.then_some(mon)
+            }
+        }
+    }
+
     pub fn workspaces_mut(&mut self) -> impl Iterator<Item = &mut Workspace<W>> + '_ {
         let iter_normal;
         let iter_no_outputs;
@@ -5226,17 +6413,31 @@ impl<W: LayoutElement> Layout<W> {
             .map(|move_| (self.monitor_for_output(&move_.output), move_.tile.window()))
             .into_iter();
 
+        let pinned = self.monitors().flat_map(|mon| {
+            mon.pinned
+                .tiles()
+                .map(move |tile| (Some(mon), tile.window()))
+        });
+
         let rest = self
             .workspaces()
             .flat_map(|(mon, _, ws)| ws.windows().map(move |win| (mon, win)));
 
-        moving_window.chain(rest)
+        moving_window.chain(pinned).chain(rest)
     }
 
     pub fn has_window(&self, window: &W::Id) -> bool {
         self.windows().any(|(_, win)| win.id() == window)
     }
 
+    /// Returns all windows, including the minimized ones.
+    fn windows_with_minimized(&self) -> impl Iterator<Item = &W> {
+        let minimized = self
+            .workspaces()
+            .flat_map(|(_, _, ws)| ws.minimized_windows());
+        self.windows().map(|(_, win)| win).chain(minimized)
+    }
+
     pub fn is_overview_open(&self) -> bool {
         self.overview_open
     }
@@ -4,23 +4,26 @@ use std::rc::Rc;
 use std::time::Duration;
 
 use niri_config::CornerRadius;
+use niri_ipc::FloatingTileArea;
 use smithay::backend::renderer::element::utils::{
     CropRenderElement, Relocate, RelocateRenderElement, RescaleRenderElement,
 };
 use smithay::output::Output;
 use smithay::utils::{Logical, Point, Rectangle, Size};
 
+use super::floating::{FloatingSpace, FloatingSpaceRenderElement};
 use super::insert_hint_element::{InsertHintElement, InsertHintRenderElement};
 use super::scrolling::{Column, ColumnWidth};
 use super::tile::Tile;
 use super::workspace::{
-    compute_working_area, OutputId, Workspace, WorkspaceAddWindowTarget, WorkspaceId,
-    WorkspaceRenderElement,
+    compute_working_area, resize_edges_within_tile, OutputId, Workspace, WorkspaceAddWindowTarget,
+    WorkspaceId, WorkspaceRenderElement,
 };
-use super::{compute_overview_zoom, ActivateWindow, HitType, LayoutElement, Options};
+use super::{compute_overview_zoom, ActivateWindow, HitType, LayoutElement, Options, RemovedTile};
 use crate::animation::{Animation, Clock};
 use crate::input::swipe_tracker::SwipeTracker;
 use crate::niri_render_elements;
+use crate::render_helpers::primary_gpu_texture::PrimaryGpuTextureRenderElement;
 use crate::render_helpers::renderer::NiriRenderer;
 use crate::render_helpers::shadow::ShadowRenderElement;
 use crate::render_helpers::RenderTarget;
@@ -43,6 +46,9 @@ const WORKSPACE_GESTURE_RUBBER_BAND: RubberBand = RubberBand {
 /// This constant is tied to the default dnd-edge-workspace-switch max-speed setting.
 const WORKSPACE_DND_EDGE_SCROLL_MOVEMENT: f64 = 1500.;
 
+/// On-screen size of window icons shown in the overview.
+const OVERVIEW_ICON_SIZE: f64 = 48.;
+
 #[derive(Debug)]
 pub struct Monitor<W: LayoutElement> {
     /// Output for this monitor.
@@ -62,6 +68,13 @@ pub struct Monitor<W: LayoutElement> {
     working_area: Rectangle<f64, Logical>,
     // Must always contain at least one.
     pub(super) workspaces: Vec<Workspace<W>>,
+    /// Floating windows pinned to all workspaces of this monitor.
+    ///
+    /// They are rendered and hit-tested above the active workspace, so switching workspaces
+    /// doesn't move them.
+    pub(super) pinned: FloatingSpace<W>,
+    /// Whether the focus is on the pinned windows rather than on the active workspace.
+    pub(super) pinned_is_active: bool,
     /// Index of the currently active workspace.
     pub(super) active_workspace_idx: usize,
     /// ID of the previously active workspace.
@@ -125,6 +138,8 @@ pub(super) enum InsertPosition {
     NewColumn(usize),
     InColumn(usize, usize),
     Floating,
+    /// Floating, tiled to an area of the working area.
+    FloatingEdgeTile(FloatingTileArea),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -172,9 +187,11 @@ pub enum MonitorAddWindowTarget<'a, W: LayoutElement> {
 niri_render_elements! {
     MonitorInnerRenderElement<R> => {
         Workspace = CropRenderElement<WorkspaceRenderElement<R>>,
+        Pinned = FloatingSpaceRenderElement<R>,
         InsertHint = CropRenderElement<InsertHintRenderElement>,
         UncroppedInsertHint = InsertHintRenderElement,
         Shadow = ShadowRenderElement,
+        Icon = PrimaryGpuTextureRenderElement,
     }
 }
 
@@ -283,6 +300,14 @@ impl<W: LayoutElement> Monitor<W> {
         let view_size = output_size(&output);
         let working_area = compute_working_area(&output);
 
+        let pinned = FloatingSpace::new(
+            view_size,
+            working_area,
+            scale.fractional_scale(),
+            clock.clone(),
+            pinned_options(&options, scale.fractional_scale()),
+        );
+
         Self {
             output_name: output.name(),
             output,
@@ -290,6 +315,8 @@ impl<W: LayoutElement> Monitor<W> {
             view_size,
             working_area,
             workspaces,
+            pinned,
+            pinned_is_active: false,
             active_workspace_idx: 0,
             previous_workspace_id: None,
             insert_hint: None,
@@ -335,12 +362,23 @@ impl<W: LayoutElement> Monitor<W> {
         })
     }
 
+    /// Returns the active workspace to act on, moving the focus there from the pinned windows.
     pub fn active_workspace(&mut self) -> &mut Workspace<W> {
+        self.pinned_is_active = false;
         &mut self.workspaces[self.active_workspace_idx]
     }
 
     pub fn windows(&self) -> impl Iterator<Item = &W> {
-        self.workspaces.iter().flat_map(|ws| ws.windows())
+        let pinned = self.pinned.tiles().map(Tile::window);
+        pinned.chain(self.workspaces.iter().flat_map(|ws| ws.windows()))
+    }
+
+    pub fn pinned_windows_mut(&mut self) -> impl Iterator<Item = &mut W> {
+        self.pinned.tiles_mut().map(Tile::window_mut)
+    }
+
+    pub fn has_pinned_window(&self, window: &W::Id) -> bool {
+        self.pinned.has_window(window)
     }
 
     pub fn has_window(&self, window: &W::Id) -> bool {
@@ -485,6 +523,18 @@ impl<W: LayoutElement> Monitor<W> {
         is_full_width: bool,
         is_floating: bool,
     ) {
+        // Floating windows that are pinned or open next to a pinned window go on top of all
+        // workspaces, unless they need to go fullscreen.
+        let next_to_pinned =
+            matches!(target, MonitorAddWindowTarget::NextTo(id) if self.pinned.has_window(id));
+        if is_floating
+            && (next_to_pinned || tile.is_pinned)
+            && !tile.window().is_pending_fullscreen()
+        {
+            self.add_pinned_tile(tile, target, activate);
+            return;
+        }
+
         let (mut workspace_idx, target) = match target {
             MonitorAddWindowTarget::Auto => {
                 (self.active_workspace_idx, WorkspaceAddWindowTarget::Auto)
@@ -498,6 +548,10 @@ impl<W: LayoutElement> Monitor<W> {
                 };
                 (idx, target)
             }
+            // Other windows opening next to a pinned one go to the active workspace.
+            MonitorAddWindowTarget::NextTo(_) if next_to_pinned => {
+                (self.active_workspace_idx, WorkspaceAddWindowTarget::Auto)
+            }
             MonitorAddWindowTarget::NextTo(win_id) => {
                 let idx = self
                     .workspaces
@@ -559,6 +613,102 @@ impl<W: LayoutElement> Monitor<W> {
         }
     }
 
+    fn add_pinned_tile(
+        &mut self,
+        mut tile: Tile<W>,
+        target: MonitorAddWindowTarget<W>,
+        activate: ActivateWindow,
+    ) {
+        let window = tile.window();
+        window.set_preferred_scale_transform(self.scale, self.output.current_transform());
+        window.output_enter(&self.output);
+        tile.is_pinned = true;
+
+        let activate = if let MonitorAddWindowTarget::NextTo(next_to) = target {
+            let activate =
+                activate.map_smart(|| self.active_window().map(|win| win.id()) == Some(next_to));
+            self.pinned.add_tile_above(next_to, tile, activate);
+            activate
+        } else {
+            // Don't steal focus from an active fullscreen window.
+            let activate =
+                activate.map_smart(|| !self.active_workspace_ref().is_active_fullscreen());
+            self.pinned.add_tile(tile, activate);
+            activate
+        };
+
+        if activate {
+            self.pinned_is_active = true;
+        }
+    }
+
+    pub(super) fn remove_pinned_tile(&mut self, window: &W::Id) -> RemovedTile<W> {
+        let mut removed = self.pinned.remove_tile(window);
+        removed.tile.window().output_leave(&self.output);
+        removed.tile.stop_dim_animation();
+
+        if self.pinned.is_empty() {
+            self.pinned_is_active = false;
+        }
+
+        removed
+    }
+
+    /// Pins a window to all workspaces of this monitor, or unpins it to the active workspace.
+    pub fn toggle_window_pinned(&mut self, window: Option<&W::Id>) {
+        let Some(id) = window
+            .cloned()
+            .or_else(|| self.active_window().map(|win| win.id().clone()))
+        else {
+            return;
+        };
+
+        let activate = if self.active_window().map(|win| win.id()) == Some(&id) {
+            ActivateWindow::Yes
+        } else {
+            ActivateWindow::No
+        };
+
+        if self.pinned.has_window(&id) {
+            let mut removed = self.remove_pinned_tile(&id);
+            removed.tile.is_pinned = false;
+            if activate == ActivateWindow::Yes {
+                self.pinned_is_active = false;
+            }
+
+            // The window stays floating, now on the active workspace.
+            self.add_tile(
+                removed.tile,
+                MonitorAddWindowTarget::Auto,
+                activate,
+                true,
+                removed.width,
+                removed.is_full_width,
+                true,
+            );
+            return;
+        }
+
+        // Minimized windows aren't found here and cannot be pinned.
+        let Some(ws) = self.workspaces.iter_mut().find(|ws| ws.has_window(&id)) else {
+            return;
+        };
+
+        // Only floating windows can be pinned.
+        if !ws.is_floating(&id) {
+            ws.toggle_window_floating(Some(&id));
+        }
+        let removed = ws.remove_tile(&id, Transaction::new());
+
+        let mut tile = removed.tile;
+        tile.is_pinned = true;
+        self.add_pinned_tile(tile, MonitorAddWindowTarget::Auto, activate);
+
+        if self.workspace_switch.is_none() {
+            self.clean_up_workspaces();
+        }
+    }
+
     pub fn clean_up_workspaces(&mut self) {
         assert!(self.workspace_switch.is_none());
 
@@ -624,6 +774,12 @@ impl<W: LayoutElement> Monitor<W> {
     }
 
     pub fn move_to_workspace_up(&mut self) {
+        // Pinned windows are on every workspace already, so only the focus moves.
+        if self.pinned_is_active {
+            self.switch_workspace_up();
+            return;
+        }
+
         let source_workspace_idx = self.active_workspace_idx;
 
         let new_idx = source_workspace_idx.saturating_sub(1);
@@ -652,6 +808,12 @@ impl<W: LayoutElement> Monitor<W> {
     }
 
     pub fn move_to_workspace_down(&mut self) {
+        // Pinned windows are on every workspace already, so only the focus moves.
+        if self.pinned_is_active {
+            self.switch_workspace_down();
+            return;
+        }
+
         let source_workspace_idx = self.active_workspace_idx;
 
         let new_idx = min(source_workspace_idx + 1, self.workspaces.len() - 1);
@@ -685,6 +847,22 @@ impl<W: LayoutElement> Monitor<W> {
         idx: usize,
         activate: ActivateWindow,
     ) {
+        let is_pinned = window.map_or(self.pinned_is_active, |window| {
+            self.pinned.has_window(window)
+        });
+        if is_pinned {
+            // Pinned windows are on every workspace already, so only the focus moves.
+            let activate = activate.map_smart(|| {
+                window.map_or(true, |win| {
+                    self.active_window().map(|win| win.id()) == Some(win)
+                })
+            });
+            if activate {
+                self.switch_workspace(idx);
+            }
+            return;
+        }
+
         let source_workspace_idx = if let Some(window) = window {
             self.workspaces
                 .iter()
@@ -747,7 +925,7 @@ impl<W: LayoutElement> Monitor<W> {
         }
 
         let workspace = &mut self.workspaces[source_workspace_idx];
-        if workspace.floating_is_active() {
+        if self.pinned_is_active || workspace.floating_is_active() {
             self.move_to_workspace_up();
             return;
         }
@@ -768,7 +946,7 @@ impl<W: LayoutElement> Monitor<W> {
         }
 
         let workspace = &mut self.workspaces[source_workspace_idx];
-        if workspace.floating_is_active() {
+        if self.pinned_is_active || workspace.floating_is_active() {
             self.move_to_workspace_down();
             return;
         }
@@ -789,7 +967,7 @@ impl<W: LayoutElement> Monitor<W> {
         }
 
         let workspace = &mut self.workspaces[source_workspace_idx];
-        if workspace.floating_is_active() {
+        if self.pinned_is_active || workspace.floating_is_active() {
             self.move_to_workspace(None, idx, ActivateWindow::Smart);
             return;
         }
@@ -857,6 +1035,10 @@ impl<W: LayoutElement> Monitor<W> {
     }
 
     pub fn active_window(&self) -> Option<&W> {
+        if self.pinned_is_active {
+            return self.pinned.active_window();
+        }
+
         self.active_workspace_ref().active_window()
     }
 
@@ -898,6 +1080,7 @@ impl<W: LayoutElement> Monitor<W> {
         for ws in &mut self.workspaces {
             ws.advance_animations();
         }
+        self.pinned.advance_animations();
     }
 
     pub(super) fn are_animations_ongoing(&self) -> bool {
@@ -905,6 +1088,7 @@ impl<W: LayoutElement> Monitor<W> {
             .as_ref()
             .is_some_and(|s| s.is_animation_ongoing())
             || self.workspaces.iter().any(|ws| ws.are_animations_ongoing())
+            || self.pinned.are_animations_ongoing()
     }
 
     pub fn are_transitions_ongoing(&self) -> bool {
@@ -913,6 +1097,7 @@ impl<W: LayoutElement> Monitor<W> {
                 .workspaces
                 .iter()
                 .any(|ws| ws.are_transitions_ongoing())
+            || self.pinned.are_transitions_ongoing()
     }
 
     pub fn update_render_elements(&mut self, is_active: bool) {
@@ -922,8 +1107,13 @@ impl<W: LayoutElement> Monitor<W> {
             .as_ref()
             .and_then(|hint| hint.workspace.existing_id());
 
+        let view_rect = Rectangle::from_size(self.view_size);
+        self.pinned
+            .update_render_elements(is_active && self.pinned_is_active, view_rect);
+
+        let ws_is_active = is_active && !self.pinned_is_active;
         for (ws, geo) in self.workspaces_with_render_geo_mut(true) {
-            ws.update_render_elements(is_active);
+            ws.update_render_elements(ws_is_active);
 
             if Some(ws.id()) == insert_hint_ws_id {
                 insert_hint_ws_geo = Some(geo);
@@ -1021,6 +1211,14 @@ impl<W: LayoutElement> Monitor<W> {
             ws.update_config(options.clone());
         }
 
+        let scale = self.scale.fractional_scale();
+        self.pinned.update_config(
+            self.view_size,
+            self.working_area,
+            scale,
+            pinned_options(&options, scale),
+        );
+
         self.insert_hint_element.update_config(options.insert_hint);
 
         self.options = options;
@@ -1030,6 +1228,7 @@ impl<W: LayoutElement> Monitor<W> {
         for ws in &mut self.workspaces {
             ws.update_shaders();
         }
+        self.pinned.update_shaders();
 
         self.insert_hint_element.update_shaders();
     }
@@ -1042,6 +1241,20 @@ impl<W: LayoutElement> Monitor<W> {
         for ws in &mut self.workspaces {
             ws.update_output_size();
         }
+
+        let scale = self.scale.fractional_scale();
+        self.pinned.update_config(
+            self.view_size,
+            self.working_area,
+            scale,
+            pinned_options(&self.options, scale),
+        );
+
+        let transform = self.output.current_transform();
+        for tile in self.pinned.tiles() {
+            tile.window()
+                .set_preferred_scale_transform(self.scale, transform);
+        }
     }
 
     pub fn move_workspace_down(&mut self) {
@@ -1155,6 +1368,10 @@ impl<W: LayoutElement> Monitor<W> {
             return None;
         }
 
+        if self.pinned_is_active {
+            return self.pinned.active_tile_visual_rectangle();
+        }
+
         self.active_workspace_ref().active_tile_visual_rectangle()
     }
 
@@ -1357,7 +1574,79 @@ impl<W: LayoutElement> Monitor<W> {
             .find_map(|(ws, geo)| geo.contains(pos_within_output).then_some(ws))
     }
 
+    /// Returns the visual geometries of the visible windows, topmost first.
+    pub fn window_visual_geometries(&self) -> Vec<Rectangle<f64, Logical>> {
+        self.windows_with_visual_geometries()
+            .into_iter()
+            .map(|(_, geo)| geo)
+            .collect()
+    }
+
+    /// Returns the visible windows with their visual geometries, topmost first.
+    pub fn windows_with_visual_geometries(&self) -> Vec<(&W, Rectangle<f64, Logical>)> {
+        let zoom = self.overview_zoom();
+
+        let mut rv = Vec::new();
+
+        // Pinned windows are above all workspaces.
+        let geo = self.pinned_render_geo();
+        for (tile, tile_pos) in self.pinned.tiles_with_render_positions() {
+            let loc = (tile_pos + tile.window_loc()).upscale(zoom) + geo.loc;
+            let size = tile.window_size().upscale(zoom);
+            rv.push((tile.window(), Rectangle::new(loc, size)));
+        }
+
+        for (ws, geo) in self.workspaces_with_render_geo() {
+            for (tile, tile_pos, visible) in ws.tiles_with_render_positions() {
+                if !visible {
+                    continue;
+                }
+
+                let loc = (tile_pos + tile.window_loc()).upscale(zoom) + geo.loc;
+                let size = tile.window_size().upscale(zoom);
+                rv.push((tile.window(), Rectangle::new(loc, size)));
+            }
+        }
+        rv
+    }
+
+    /// Returns the geometry of the pinned windows' layer.
+    ///
+    /// Outside the overview it covers the output. In the overview, the pinned windows are shown
+    /// on the active workspace.
+    pub fn pinned_render_geo(&self) -> Rectangle<f64, Logical> {
+        if self.overview_progress.is_some() {
+            self.workspaces_render_geo()
+                .nth(self.active_workspace_idx)
+                .unwrap()
+        } else {
+            Rectangle::from_size(self.view_size)
+        }
+    }
+
+    fn pinned_window_under(&self, pos_within_output: Point<f64, Logical>) -> Option<(&W, HitType)> {
+        let geo = self.pinned_render_geo();
+        let zoom = self.overview_zoom();
+        let pos_within_layer = (pos_within_output - geo.loc).downscale(zoom);
+
+        let (win, hit) = self
+            .pinned
+            .tiles_with_render_positions()
+            .find_map(|(tile, tile_pos)| HitType::hit_tile(tile, tile_pos, pos_within_layer))?;
+
+        if self.overview_progress.is_some() {
+            Some((win, hit.to_activate()))
+        } else {
+            Some((win, hit))
+        }
+    }
+
     pub fn window_under(&self, pos_within_output: Point<f64, Logical>) -> Option<(&W, HitType)> {
+        // Pinned windows are above all workspaces.
+        if let Some(rv) = self.pinned_window_under(pos_within_output) {
+            return Some(rv);
+        }
+
         let (ws, geo) = self.workspace_under(pos_within_output)?;
 
         if self.overview_progress.is_some() {
@@ -1378,6 +1667,13 @@ impl<W: LayoutElement> Monitor<W> {
             return None;
         }
 
+        let pinned = self.pinned.tiles_with_render_positions();
+        if let Some(edges) = pinned.find_map(|(tile, tile_pos)| {
+            resize_edges_within_tile(tile, pos_within_output - tile_pos)
+        }) {
+            return Some(edges);
+        }
+
         let (ws, geo) = self.workspace_under(pos_within_output)?;
         ws.resize_edges_under(pos_within_output - geo.loc)
     }
@@ -1505,6 +1801,7 @@ impl<W: LayoutElement> Monitor<W> {
         };
 
         let zoom = self.overview_zoom();
+        let overview_clamped_progress = self.overview_progress.as_ref().map(|p| p.clamped_value());
 
         // Draw the insert hint.
         let mut insert_hint = None;
@@ -1521,12 +1818,43 @@ impl<W: LayoutElement> Monitor<W> {
         }
 
         self.workspaces_with_render_geo().map(move |(ws, geo)| {
+            // In the overview, draw window icons on top of the bottom edge of the windows. The
+            // icons of tiled windows go below the floating windows, like the tiles themselves.
+            let mut floating_icons = Vec::new();
+            let mut scrolling_icons = Vec::new();
+            if let Some(value) = overview_clamped_progress {
+                let alpha = value.clamp(0., 1.) as f32;
+                // Keep the on-screen size constant regardless of the zoom.
+                let size = OVERVIEW_ICON_SIZE / zoom;
+
+                for (tile, tile_pos, visible) in ws.tiles_with_render_positions() {
+                    if !visible {
+                        continue;
+                    }
+
+                    let tile_size = tile.tile_size();
+                    let loc = tile_pos
+                        + Point::from(((tile_size.w - size) / 2., tile_size.h - size / 2.));
+                    let loc = loc.to_physical_precise_round(scale).to_logical(scale);
+                    let elem = tile
+                        .window()
+                        .render_icon(renderer, loc, size, scale.into(), alpha);
+                    let icons = if ws.is_floating(tile.window().id()) {
+                        &mut floating_icons
+                    } else {
+                        &mut scrolling_icons
+                    };
+                    icons.extend(elem.map(MonitorInnerRenderElement::Icon));
+                }
+            }
+
             let map_ws_contents = move |elem: WorkspaceRenderElement<R>| {
                 let elem = CropRenderElement::from_element(elem, scale, crop_bounds)?;
                 let elem = MonitorInnerRenderElement::Workspace(elem);
                 Some(elem)
             };
 
+            let focus_ring = focus_ring && !self.pinned_is_active;
             let (floating, scrolling) = ws.render_elements(renderer, target, focus_ring);
             let floating = floating.filter_map(map_ws_contents);
             let scrolling = scrolling.filter_map(map_ws_contents);
@@ -1544,7 +1872,12 @@ impl<W: LayoutElement> Monitor<W> {
             };
             let hint = hint.into_iter().flatten();
 
-            let iter = floating.chain(hint).chain(scrolling);
+            let iter = floating_icons
+                .into_iter()
+                .chain(floating)
+                .chain(hint)
+                .chain(scrolling_icons)
+                .chain(scrolling);
 
             let iter = iter.map(move |elem| {
                 let elem = RescaleRenderElement::from_element(elem, Point::from((0, 0)), zoom);
@@ -1562,6 +1895,35 @@ impl<W: LayoutElement> Monitor<W> {
         })
     }
 
+    /// Renders the pinned windows, which go above all workspaces.
+    pub fn render_pinned_elements<'a, R: NiriRenderer>(
+        &'a self,
+        renderer: &'a mut R,
+        target: RenderTarget,
+        focus_ring: bool,
+    ) -> impl Iterator<Item = MonitorRenderElement<R>> + 'a {
+        let _span = tracy_client::span!("Monitor::render_pinned_elements");
+
+        let scale = self.scale.fractional_scale();
+        let zoom = self.overview_zoom();
+        let loc = self
+            .pinned_render_geo()
+            .loc
+            .to_physical_precise_round(scale);
+
+        let view_rect = Rectangle::from_size(self.view_size);
+        let focus_ring = focus_ring && self.pinned_is_active;
+        let elements = self
+            .pinned
+            .render_elements(renderer, view_rect, target, focus_ring);
+
+        elements.into_iter().map(move |elem| {
+            let elem = MonitorInnerRenderElement::Pinned(elem);
+            let elem = RescaleRenderElement::from_element(elem, Point::from((0, 0)), zoom);
+            RelocateRenderElement::from_element(elem, loc, Relocate::Relative)
+        })
+    }
+
     pub fn render_workspace_shadows<'a, R: NiriRenderer>(
         &'a self,
         renderer: &'a mut R,
@@ -1863,3 +2225,7 @@ impl<W: LayoutElement> Monitor<W> {
         self.working_area
     }
 }
+
+fn pinned_options(options: &Options, scale: f64) -> Rc<Options> {
+    Rc::new(Options::clone(options).adjusted_for_scale(scale))
+}
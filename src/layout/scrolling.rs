@@ -3,12 +3,13 @@ use std::iter::{self, zip};
 use std::rc::Rc;
 use std::time::Duration;
 
-use niri_config::{CenterFocusedColumn, NewColumnLocation, PresetSize, Struts};
-use niri_ipc::{ColumnDisplay, SizeChange};
+use niri_config::{CenterFocusedColumn, NewColumnLocation, PresetSize, ScrollOrientation, Struts};
+use niri_ipc::{ColumnDisplay, LayoutKind, SizeChange};
 use ordered_float::NotNan;
 use smithay::backend::renderer::gles::GlesRenderer;
-use smithay::utils::{Logical, Point, Rectangle, Scale, Serial, Size};
+use smithay::utils::{Coordinate, Logical, Point, Rectangle, Scale, Serial, Size};
 
+use super::cells::{Cells, Direction};
 use super::closing_window::{ClosingWindow, ClosingWindowRenderElement};
 use super::monitor::InsertPosition;
 use super::tab_indicator::{TabIndicator, TabIndicatorRenderElement, TabInfo};
@@ -67,15 +68,28 @@ pub struct ScrollingSpace<W: LayoutElement> {
     /// Windows in the closing animation.
     closing_windows: Vec<ClosingWindow>,
 
-    /// View size for this space.
+    /// Cells of the columns in a non-scrolling layout kind.
+    ///
+    /// When set, every column fills its cell of the working area, and the view stays put.
+    cells: Option<Cells>,
+
+    /// Whether columns are laid out top-to-bottom as rows.
+    ///
+    /// A vertical space does all of its layout as if it was horizontal, in layout coordinates
+    /// with the X and Y axes swapped. The view size, working area and parent area below, as well
+    /// as all column and tile positions, are stored in layout coordinates, and are converted back
+    /// to screen coordinates at the boundaries with tiles and with the workspace.
+    vertical: bool,
+
+    /// View size for this space, in layout coordinates.
     view_size: Size<f64, Logical>,
 
-    /// Working area for this space.
+    /// Working area for this space, in layout coordinates.
     ///
     /// Takes into account layer-shell exclusive zones and niri struts.
     working_area: Rectangle<f64, Logical>,
 
-    /// Working area for this space excluding struts.
+    /// Working area for this space excluding struts, in layout coordinates.
     ///
     /// Used for popup unconstraining. Popups can go over struts, but they shouldn't go over
     /// the layer-shell top layer (which renders on top of popups).
@@ -178,10 +192,18 @@ pub struct Column<W: LayoutElement> {
     /// Animation of the render offset during window swapping.
     move_animation: Option<Animation>,
 
-    /// Latest known view size for this column's workspace.
+    /// Whether the column is laid out as a row in a vertical scrolling space.
+    vertical: bool,
+
+    /// Cell that this column fills in a non-scrolling layout kind.
+    ///
+    /// When set, the column sizes itself as if the cell was the working area.
+    cell: Option<Rectangle<f64, Logical>>,
+
+    /// Latest known view size for this column's workspace, in layout coordinates.
     view_size: Size<f64, Logical>,
 
-    /// Latest known working area for this column's workspace.
+    /// Latest known working area for this column's workspace, in layout coordinates.
     working_area: Rectangle<f64, Logical>,
 
     /// Scale of the output the column is on (and rounds its sizes to).
@@ -263,6 +285,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         options: Rc<Options>,
     ) -> Self {
         let working_area = compute_working_area(parent_area, scale, options.struts);
+        let vertical = is_vertical(options.scroll_orientation, view_size);
 
         Self {
             columns: Vec::new(),
@@ -273,9 +296,11 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             activate_prev_column_on_removal: None,
             view_offset_before_fullscreen: None,
             closing_windows: Vec::new(),
-            view_size,
-            working_area,
-            parent_area,
+            cells: None,
+            vertical,
+            view_size: orient(vertical, view_size),
+            working_area: orient(vertical, working_area),
+            parent_area: orient(vertical, parent_area),
             scale,
             clock,
             options,
@@ -291,17 +316,32 @@ impl<W: LayoutElement> ScrollingSpace<W> {
     ) {
         let working_area = compute_working_area(parent_area, scale, options.struts);
 
+        // Non-scrolling layout kinds are always laid out horizontally.
+        let vertical = self.cells.is_none() && is_vertical(options.scroll_orientation, view_size);
+        let view_size = orient(vertical, view_size);
+        let working_area = orient(vertical, working_area);
+        let parent_area = orient(vertical, parent_area);
+
+        if self.vertical != vertical {
+            // The layout axes changed, so any offsets we have are meaningless now.
+            self.view_offset = ViewOffset::Static(self.view_offset.stationary());
+            self.interactive_resize = None;
+        }
+
         for (column, data) in zip(&mut self.columns, &mut self.data) {
-            column.update_config(view_size, working_area, scale, options.clone());
+            column.update_config(vertical, view_size, working_area, scale, options.clone());
             data.update(column);
         }
 
+        self.vertical = vertical;
         self.view_size = view_size;
         self.working_area = working_area;
         self.parent_area = parent_area;
         self.scale = scale;
         self.options = options;
 
+        self.update_arrangement();
+
         // Apply always-center and such right away.
         if !self.columns.is_empty() && !self.view_offset.is_gesture() {
             self.animate_view_offset_to_column(None, self.active_column_idx, None);
@@ -444,7 +484,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
 
         compute_toplevel_bounds(
             border_config,
-            self.working_area.size,
+            orient(self.vertical, self.working_area.size),
             extra_size,
             self.options.gaps,
         )
@@ -467,6 +507,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         } else {
             Size::from((0., 0.))
         };
+        let extra = orient(self.vertical, extra);
 
         let working_size = self.working_area.size;
 
@@ -506,7 +547,141 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             full_height
         };
 
-        Size::from((width, max(height.floor() as i32, 1)))
+        orient(
+            self.vertical,
+            Size::from((width, max(height.floor() as i32, 1))),
+        )
+    }
+
+    /// Returns whether columns are laid out top-to-bottom as rows.
+    pub fn is_vertical(&self) -> bool {
+        self.vertical
+    }
+
+    pub fn layout_kind(&self) -> LayoutKind {
+        self.cells
+            .as_ref()
+            .map_or(LayoutKind::Scrolling, Cells::kind)
+    }
+
+    pub fn set_layout_kind(&mut self, kind: LayoutKind) {
+        if self.layout_kind() == kind {
+            return;
+        }
+
+        self.cells = Cells::new(kind);
+        self.interactive_resize = None;
+        self.view_offset = ViewOffset::Static(0.);
+        self.activate_prev_column_on_removal = None;
+        self.view_offset_before_fullscreen = None;
+
+        // Switching from or to the scrolling layout can change the layout axes, so go through
+        // update_config() with the screen-space sizes.
+        self.update_config(
+            orient(self.vertical, self.view_size),
+            orient(self.vertical, self.parent_area),
+            self.scale,
+            self.options.clone(),
+        );
+    }
+
+    /// Assigns every column its cell in a non-scrolling layout kind.
+    fn update_arrangement(&mut self) {
+        if let Some(cells) = &mut self.cells {
+            cells.update(self.working_area, self.options.gaps, self.columns.len());
+        }
+        let cells = self.cells.as_ref();
+
+        for (idx, (column, data)) in zip(&mut self.columns, &mut self.data).enumerate() {
+            let cell = cells.and_then(|cells| cells.get(idx));
+            if column.cell == cell {
+                continue;
+            }
+
+            // Animate the movement between cells, but not when switching layout kinds.
+            let old_x = column.cell_x();
+            let animate = column.cell.is_some() && cell.is_some();
+            column.set_cell(cell, animate);
+            data.update(column);
+
+            if let (true, Some(old_x), Some(new_x)) = (animate, old_x, column.cell_x()) {
+                column.animate_move_from(old_x - new_x);
+            }
+        }
+    }
+
+    /// Returns the column next to the active one in the given direction.
+    ///
+    /// In the scrolling layout, columns only have neighbors to the left and to the right.
+    fn column_in_direction(&self, direction: Direction) -> Option<usize> {
+        let idx = self.active_column_idx;
+        if let Some(cells) = &self.cells {
+            return cells.neighbor(idx, direction);
+        }
+
+        match direction {
+            Direction::Left => idx.checked_sub(1),
+            Direction::Right => (idx + 1 < self.columns.len()).then_some(idx + 1),
+            Direction::Up | Direction::Down => None,
+        }
+    }
+
+    fn focus_column_in_direction(&mut self, direction: Direction) -> bool {
+        let Some(idx) = self.column_in_direction(direction) else {
+            return false;
+        };
+
+        self.activate_column(idx);
+        true
+    }
+
+    fn move_column_in_direction(&mut self, direction: Direction) -> bool {
+        let Some(idx) = self.column_in_direction(direction) else {
+            return false;
+        };
+
+        if self.cells.is_none() {
+            self.move_column_to(idx);
+            return true;
+        }
+
+        // Swap the columns so that the other column takes the cell that we left.
+        let active_idx = self.active_column_idx;
+        cancel_resize_for_column(&mut self.interactive_resize, &mut self.columns[active_idx]);
+        self.columns.swap(active_idx, idx);
+        self.data.swap(active_idx, idx);
+        self.update_arrangement();
+
+        self.activate_column_with_anim_config(idx, self.options.animations.window_movement.0);
+        true
+    }
+
+    /// Animates the other columns after the column at `idx` changed its width by `delta`.
+    ///
+    /// The columns on the far side of `idx` from the active column shift by `delta`, including the
+    /// column itself if `shift_self` is set. Columns in cells don't shift, as they animate into
+    /// their new cells in update_arrangement() instead.
+    fn animate_columns_shift(
+        &mut self,
+        idx: usize,
+        delta: f64,
+        shift_self: bool,
+        config: niri_config::Animation,
+    ) {
+        if self.cells.is_some() {
+            return;
+        }
+
+        if self.active_column_idx <= idx {
+            for col in &mut self.columns[idx + 1..] {
+                col.animate_move_from_with_config(-delta, config);
+            }
+        } else {
+            let end = if shift_self { idx + 1 } else { idx };
+            for col in &mut self.columns[..end] {
+                col.animate_move_from_with_config(delta, config);
+            }
+        }
     }
 
     pub fn is_centering_focused_column(&self) -> bool {
@@ -766,6 +941,12 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             return InsertPosition::NewColumn(0);
         }
 
+        if let Some(cells) = &self.cells {
+            // Take the place of the column in the cell under the position.
+            return InsertPosition::NewColumn(cells.index_at(pos));
+        }
+
+        let pos = orient(self.vertical, pos);
         let x = pos.x + self.view_pos();
 
         // Aim for the center of the gap.
@@ -828,6 +1009,21 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         }
     }
 
+    pub fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn column_tile_count(&self, col_idx: usize) -> usize {
+        self.columns[col_idx].tiles.len()
+    }
+
+    /// Returns the column and tile indices of the window.
+    pub fn window_position(&self, window: &W::Id) -> Option<(usize, usize)> {
+        let col_idx = self.columns.iter().position(|col| col.contains(window))?;
+        let tile_idx = self.columns[col_idx].position(window).unwrap();
+        Some((col_idx, tile_idx))
+    }
+
     pub fn add_tile(
         &mut self,
         col_idx: Option<usize>,
@@ -839,6 +1035,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
     ) {
         let column = Column::new_with_tile(
             tile,
+            self.vertical,
             self.view_size,
             self.working_area,
             self.scale,
@@ -953,7 +1150,10 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             }
         });
 
+        // The column may come from a space with a different layout kind.
+        column.set_cell(None, false);
         column.update_config(
+            self.vertical,
             self.view_size,
             self.working_area,
             self.scale,
@@ -985,15 +1185,8 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         // Animate movement of other columns.
         let offset = self.column_x(idx + 1) - self.column_x(idx);
         let config = anim_config.unwrap_or(self.options.animations.window_movement.0);
-        if self.active_column_idx <= idx {
-            for col in &mut self.columns[idx + 1..] {
-                col.animate_move_from_with_config(-offset, config);
-            }
-        } else {
-            for col in &mut self.columns[..idx] {
-                col.animate_move_from_with_config(offset, config);
-            }
-        }
+        self.animate_columns_shift(idx, offset, false, config);
+        self.update_arrangement();
     }
 
     pub fn remove_active_tile(&mut self, transaction: Transaction) -> Option<RemovedTile<W>> {
@@ -1050,7 +1243,12 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         // than the others.
         let offset_y = column.tile_offset(tile_idx + 1).y - column.tile_offset(tile_idx).y;
         for tile in &mut column.tiles[tile_idx + 1..] {
-            tile.animate_move_y_from(offset_y);
+            animate_tile_move_y_from(
+                tile,
+                column.vertical,
+                offset_y,
+                self.options.animations.window_movement.0,
+            );
         }
 
         if column.display_mode == ColumnDisplay::Tabbed && tile_idx != column.active_tile_idx {
@@ -1110,15 +1308,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         let offset = prev_width - column.width();
 
         // Animate movement of the other columns.
-        if self.active_column_idx <= column_idx {
-            for col in &mut self.columns[column_idx + 1..] {
-                col.animate_move_from_with_config(offset, movement_config);
-            }
-        } else {
-            for col in &mut self.columns[..=column_idx] {
-                col.animate_move_from_with_config(-offset, movement_config);
-            }
-        }
+        self.animate_columns_shift(column_idx, -offset, true, movement_config);
 
         tile
     }
@@ -1139,18 +1329,11 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         // Animate movement of the other columns.
         let movement_config = anim_config.unwrap_or(self.options.animations.window_movement.0);
         let offset = self.column_x(column_idx + 1) - self.column_x(column_idx);
-        if self.active_column_idx <= column_idx {
-            for col in &mut self.columns[column_idx + 1..] {
-                col.animate_move_from_with_config(offset, movement_config);
-            }
-        } else {
-            for col in &mut self.columns[..column_idx] {
-                col.animate_move_from_with_config(-offset, movement_config);
-            }
-        }
+        self.animate_columns_shift(column_idx, -offset, false, movement_config);
 
         let column = self.columns.remove(column_idx);
         self.data.remove(column_idx);
+        self.update_arrangement();
 
         // Stop interactive resize.
         if let Some(resize) = &self.interactive_resize {
@@ -1251,21 +1434,8 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         let started_resize_anim =
             column.tiles[tile_idx].resize_animation().is_some() && offset != 0.;
         if started_resize_anim {
-            if self.active_column_idx <= col_idx {
-                for col in &mut self.columns[col_idx + 1..] {
-                    col.animate_move_from_with_config(
-                        offset,
-                        self.options.animations.window_resize.anim,
-                    );
-                }
-            } else {
-                for col in &mut self.columns[..=col_idx] {
-                    col.animate_move_from_with_config(
-                        -offset,
-                        self.options.animations.window_resize.anim,
-                    );
-                }
-            }
+            let config = self.options.animations.window_resize.anim;
+            self.animate_columns_shift(col_idx, -offset, true, config);
         }
 
         if col_idx == self.active_column_idx {
@@ -1287,7 +1457,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
                     let new_offset =
                         -(self.working_area.size.w - width) / 2. - self.working_area.loc.x;
                     new_offset - self.view_offset.target()
-                } else if resize.edges.contains(ResizeEdge::LEFT) {
+                } else if orient(self.vertical, resize.edges).contains(ResizeEdge::LEFT) {
                     -offset
                 } else {
                     0.
@@ -1342,7 +1512,8 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             .position(|col| col.contains(window))
             .unwrap();
 
-        if self.active_column_idx == column_idx {
+        // The view never moves in a non-scrolling layout kind.
+        if self.active_column_idx == column_idx || self.cells.is_some() {
             return 0.;
         }
 
@@ -1379,7 +1550,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         window: &W::Id,
         blocker: TransactionBlocker,
     ) {
-        let (tile, mut tile_pos) = self
+        let (tile, tile_pos) = self
             .tiles_with_render_positions_mut(false)
             .find(|(tile, _)| tile.window().id() == window)
             .unwrap();
@@ -1410,6 +1581,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             return;
         }
 
+        let mut tile_pos = orient(self.vertical, tile_pos);
         tile_pos.x += self.view_pos();
 
         if col_idx < self.active_column_idx {
@@ -1431,6 +1603,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             tile_pos.x -= offset;
         }
 
+        let tile_pos = orient(self.vertical, tile_pos);
         self.start_close_animation_for_tile(renderer, snapshot, tile_size, tile_pos, blocker);
     }
 
@@ -1477,20 +1650,11 @@ impl<W: LayoutElement> ScrollingSpace<W> {
     }
 
     pub fn focus_left(&mut self) -> bool {
-        if self.active_column_idx == 0 {
-            return false;
-        }
-        self.activate_column(self.active_column_idx - 1);
-        true
+        self.focus_column_in_direction(Direction::Left)
     }
 
     pub fn focus_right(&mut self) -> bool {
-        if self.active_column_idx + 1 >= self.columns.len() {
-            return false;
-        }
-
-        self.activate_column(self.active_column_idx + 1);
-        true
+        self.focus_column_in_direction(Direction::Right)
     }
 
     pub fn focus_column_first(&mut self) {
@@ -1526,7 +1690,11 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             return false;
         }
 
-        self.columns[self.active_column_idx].focus_down()
+        if self.columns[self.active_column_idx].focus_down() {
+            return true;
+        }
+
+        self.focus_column_in_direction(Direction::Down)
     }
 
     pub fn focus_up(&mut self) -> bool {
@@ -1534,7 +1702,11 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             return false;
         }
 
-        self.columns[self.active_column_idx].focus_up()
+        if self.columns[self.active_column_idx].focus_up() {
+            return true;
+        }
+
+        self.focus_column_in_direction(Direction::Up)
     }
 
     pub fn focus_down_or_left(&mut self) {
@@ -1619,6 +1791,16 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         self.columns.insert(new_idx, column);
         self.data.insert(new_idx, data);
 
+        if self.cells.is_some() {
+            // The columns animate into their new cells.
+            self.update_arrangement();
+            self.activate_column_with_anim_config(
+                new_idx,
+                self.options.animations.window_movement.0,
+            );
+            return;
+        }
+
         // Preserve the camera position when moving to the left.
         let view_offset_delta = -self.column_x(self.active_column_idx) + current_col_x;
         self.view_offset.offset(view_offset_delta);
@@ -1643,22 +1825,11 @@ impl<W: LayoutElement> ScrollingSpace<W> {
     }
 
     pub fn move_left(&mut self) -> bool {
-        if self.active_column_idx == 0 {
-            return false;
-        }
-
-        self.move_column_to(self.active_column_idx - 1);
-        true
+        self.move_column_in_direction(Direction::Left)
     }
 
     pub fn move_right(&mut self) -> bool {
-        let new_idx = self.active_column_idx + 1;
-        if new_idx >= self.columns.len() {
-            return false;
-        }
-
-        self.move_column_to(new_idx);
-        true
+        self.move_column_in_direction(Direction::Right)
     }
 
     pub fn move_column_to_first(&mut self) {
@@ -1679,7 +1850,11 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             return false;
         }
 
-        self.columns[self.active_column_idx].move_down()
+        if self.columns[self.active_column_idx].move_down() {
+            return true;
+        }
+
+        self.move_column_in_direction(Direction::Down)
     }
 
     pub fn move_up(&mut self) -> bool {
@@ -1687,7 +1862,11 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             return false;
         }
 
-        self.columns[self.active_column_idx].move_up()
+        if self.columns[self.active_column_idx].move_up() {
+            return true;
+        }
+
+        self.move_column_in_direction(Direction::Up)
     }
 
     pub fn consume_or_expel_window_left(&mut self, window: Option<&W::Id>) {
@@ -1762,7 +1941,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             offset += prev_off - target_column.tile_offset(target_column.tiles.len() - 1);
 
             let new_tile = target_column.tiles.last_mut().unwrap();
-            new_tile.animate_move_from(offset);
+            new_tile.animate_move_from(orient(self.vertical, offset));
         } else {
             // Move out of column.
             let mut offset = Point::from((source_column.render_offset().x, 0.));
@@ -1794,7 +1973,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
 
             let new_col = &mut self.columns[target_column_idx];
             offset += prev_off - new_col.tile_offset(0);
-            new_col.tiles[0].animate_move_from(offset);
+            new_col.tiles[0].animate_move_from(orient(self.vertical, offset));
         }
     }
 
@@ -1857,7 +2036,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             offset += prev_off - target_column.tile_offset(target_column.tiles.len() - 1);
 
             let new_tile = target_column.tiles.last_mut().unwrap();
-            new_tile.animate_move_from(offset);
+            new_tile.animate_move_from(orient(self.vertical, offset));
         } else {
             // Move out of column.
             let prev_width = self.data[source_col_idx].width;
@@ -1886,7 +2065,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
 
             let new_col = &mut self.columns[target_column_idx];
             offset += prev_off - new_col.tile_offset(0);
-            new_col.tiles[0].animate_move_from(offset);
+            new_col.tiles[0].animate_move_from(orient(self.vertical, offset));
         }
     }
 
@@ -1916,7 +2095,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         offset.x -= target_column.render_offset().x;
 
         let new_tile = target_column.tiles.last_mut().unwrap();
-        new_tile.animate_move_from(offset);
+        new_tile.animate_move_from(orient(self.vertical, offset));
     }
 
     pub fn expel_from_column(&mut self) {
@@ -1954,7 +2133,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
 
         let new_col = &mut self.columns[target_col_idx];
         offset += prev_off - new_col.tile_offset(0);
-        new_col.tiles[0].animate_move_from(offset);
+        new_col.tiles[0].animate_move_from(orient(self.vertical, offset));
     }
 
     pub fn swap_window_in_direction(&mut self, direction: ScrollDirection) {
@@ -2069,7 +2248,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
 
         // Animations
         self.columns[target_column_idx].tiles[target_tile_idx]
-            .animate_move_from(source_pt - target_pt);
+            .animate_move_from(orient(self.vertical, source_pt - target_pt));
         self.columns[target_column_idx].tiles[target_tile_idx].ensure_alpha_animates_to_1();
 
         // FIXME: this stop_move_animations() causes the target tile animation to "reset" when
@@ -2079,7 +2258,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         // cancel all ongoing target tile animations.
         self.columns[source_column_idx].tiles[source_tile_idx].stop_move_animations();
         self.columns[source_column_idx].tiles[source_tile_idx]
-            .animate_move_from(target_pt - source_pt);
+            .animate_move_from(orient(self.vertical, target_pt - source_pt));
         self.columns[source_column_idx].tiles[source_tile_idx].ensure_alpha_animates_to_1();
 
         self.activate_column(target_column_idx);
@@ -2219,11 +2398,20 @@ impl<W: LayoutElement> ScrollingSpace<W> {
     }
 
     pub fn view_pos(&self) -> f64 {
-        self.column_x(self.active_column_idx) + self.view_offset.current()
+        self.view_pos_for_offset(self.view_offset.current())
     }
 
     pub fn target_view_pos(&self) -> f64 {
-        self.column_x(self.active_column_idx) + self.view_offset.target()
+        self.view_pos_for_offset(self.view_offset.target())
+    }
+
+    fn view_pos_for_offset(&self, view_offset: f64) -> f64 {
+        // Columns in cells are positioned in view coordinates.
+        if self.cells.is_some() {
+            return 0.;
+        }
+
+        self.column_x(self.active_column_idx) + view_offset
     }
 
     // HACK: pass a self.data iterator in manually as a workaround for the lack of method partial
@@ -2232,14 +2420,21 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         let gaps = self.options.gaps;
         let mut x = 0.;
 
+        // Columns in cells are positioned by their cells rather than one after another.
+        let cell_xs: Vec<_> = if self.cells.is_some() {
+            self.columns.iter().map(Column::cell_x).collect()
+        } else {
+            Vec::new()
+        };
+
         // Chain with a dummy value to be able to get one past all columns' X.
         let dummy = ColumnData { width: 0. };
         let data = data.chain(iter::once(dummy));
 
-        data.map(move |data| {
+        data.enumerate().map(move |(idx, data)| {
             let rv = x;
             x += data.width + gaps;
-            rv
+            cell_xs.get(idx).copied().flatten().unwrap_or(rv)
         })
     }
 
@@ -2301,6 +2496,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         &self,
     ) -> impl Iterator<Item = (&Tile<W>, Point<f64, Logical>, bool)> {
         let scale = self.scale;
+        let vertical = self.vertical;
         let view_off = Point::from((-self.view_pos(), 0.));
         self.columns_in_render_order()
             .flat_map(move |(col, col_x)| {
@@ -2308,8 +2504,8 @@ impl<W: LayoutElement> ScrollingSpace<W> {
                 let col_render_off = col.render_offset();
                 col.tiles_in_render_order()
                     .map(move |(tile, tile_off, visible)| {
-                        let pos =
-                            view_off + col_off + col_render_off + tile_off + tile.render_offset();
+                        let pos = view_off + col_off + col_render_off + tile_off;
+                        let pos = orient(vertical, pos) + tile.render_offset();
                         // Round to physical pixels.
                         let pos = pos.to_physical_precise_round(scale).to_logical(scale);
                         (tile, pos, visible)
@@ -2322,6 +2518,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         round: bool,
     ) -> impl Iterator<Item = (&mut Tile<W>, Point<f64, Logical>)> {
         let scale = self.scale;
+        let vertical = self.vertical;
         let view_off = Point::from((-self.view_pos(), 0.));
         self.columns_in_render_order_mut()
             .flat_map(move |(col, col_x)| {
@@ -2329,8 +2526,8 @@ impl<W: LayoutElement> ScrollingSpace<W> {
                 let col_render_off = col.render_offset();
                 col.tiles_in_render_order_mut()
                     .map(move |(tile, tile_off)| {
-                        let mut pos =
-                            view_off + col_off + col_render_off + tile_off + tile.render_offset();
+                        let pos = view_off + col_off + col_render_off + tile_off;
+                        let mut pos = orient(vertical, pos) + tile.render_offset();
                         // Round to physical pixels.
                         if round {
                             pos = pos.to_physical_precise_round(scale).to_logical(scale);
@@ -2344,6 +2541,10 @@ impl<W: LayoutElement> ScrollingSpace<W> {
         &self,
         position: InsertPosition,
     ) -> Option<Rectangle<f64, Logical>> {
+        if let (Some(cells), InsertPosition::NewColumn(idx)) = (&self.cells, position) {
+            return Some(cells.insert_hint_area(idx, self.working_area));
+        }
+
         let mut hint_area = match position {
             InsertPosition::NewColumn(column_index) => {
                 if column_index == 0 || column_index == self.columns.len() {
@@ -2410,7 +2611,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
                 // Adjust for place-within-column tab indicator.
                 let origin_x = col.tiles_origin().x;
                 let extra_w = if is_tabbed && !col.is_fullscreen {
-                    col.tab_indicator.extra_size(col.tiles.len(), col.scale).w
+                    col.extra_size().w
                 } else {
                     0.
                 };
@@ -2419,7 +2620,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
                 let loc = Point::from((self.column_x(column_index) + origin_x, y));
                 Rectangle::new(loc, size)
             }
-            InsertPosition::Floating => return None,
+            InsertPosition::Floating | InsertPosition::FloatingEdgeTile(_) => return None,
         };
 
         // First window on an empty workspace will cancel out any view offset. Replicate this
@@ -2435,7 +2636,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             hint_area.loc.x -= self.view_pos();
         }
 
-        Some(hint_area)
+        Some(orient(self.vertical, hint_area))
     }
 
     /// Returns the geometry of the active tile relative to and clamped to the view.
@@ -2444,17 +2645,18 @@ impl<W: LayoutElement> ScrollingSpace<W> {
     pub fn active_tile_visual_rectangle(&self) -> Option<Rectangle<f64, Logical>> {
         let col = self.columns.get(self.active_column_idx)?;
 
-        let final_view_offset = self.view_offset.target();
-        let view_off = Point::from((-final_view_offset, 0.));
+        let col_x = self.column_x(self.active_column_idx);
+        let view_off = Point::from((col_x - self.target_view_pos(), 0.));
 
         let (tile, tile_off) = col.tiles().nth(col.active_tile_idx).unwrap();
 
         let tile_pos = view_off + tile_off;
-        let tile_size = tile.tile_size();
+        let tile_size = orient(self.vertical, tile.tile_size());
         let tile_rect = Rectangle::new(tile_pos, tile_size);
 
         let view = Rectangle::from_size(self.view_size);
-        view.intersection(tile_rect)
+        let rect = view.intersection(tile_rect)?;
+        Some(orient(self.vertical, rect))
     }
 
     pub fn popup_target_rect(&self, id: &W::Id) -> Option<Rectangle<f64, Logical>> {
@@ -2464,16 +2666,16 @@ impl<W: LayoutElement> ScrollingSpace<W> {
                     // In the scrolling layout, we try to position popups horizontally within the
                     // window geometry (so they remain visible even if the window scrolls flush with
                     // the left/right edge of the screen), and vertically wihin the whole parent
-                    // working area.
-                    let width = tile.window_size().w;
+                    // working area. In a vertical space, the directions are swapped.
+                    let width = orient(self.vertical, tile.window_size()).w;
                     let height = self.parent_area.size.h;
 
                     let mut target = Rectangle::from_size(Size::from((width, height)));
                     target.loc.y += self.parent_area.loc.y;
                     target.loc.y -= pos.y;
-                    target.loc.y -= tile.window_loc().y;
+                    target.loc.y -= orient(self.vertical, tile.window_loc()).y;
 
-                    return Some(target);
+                    return Some(orient(self.vertical, target));
                 }
             }
         }
@@ -2746,6 +2948,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             // Create a column manually to disable the resize animation.
             let column = Column::new_with_tile(
                 removed.tile,
+                self.vertical,
                 self.view_size,
                 self.working_area,
                 self.scale,
@@ -2804,6 +3007,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
 
         // Draw the closing windows on top of the other windows.
         let view_rect = Rectangle::new(Point::from((self.view_pos(), 0.)), self.view_size);
+        let view_rect = orient(self.vertical, view_rect);
         for closing in self.closing_windows.iter().rev() {
             let elem = closing.render(renderer.as_gles_renderer(), view_rect, scale, target);
             rv.push(elem.into());
@@ -2823,14 +3027,22 @@ impl<W: LayoutElement> ScrollingSpace<W> {
 
             // Draw the tab indicator on top.
             {
-                let pos = view_off + col_off + col_render_off;
+                let pos = orient(self.vertical, view_off + col_off + col_render_off);
                 let pos = pos.to_physical_precise_round(scale).to_logical(scale);
+                let windows = col.tiles.iter().map(|tile| tile.window());
+                rv.extend(
+                    col.tab_indicator
+                        .render_icons(renderer, pos, windows, scale)
+                        .map(Into::into),
+                );
                 rv.extend(col.tab_indicator.render(renderer, pos).map(Into::into));
             }
 
             for (tile, tile_off, visible) in col.tiles_in_render_order() {
-                let tile_pos =
-                    view_off + col_off + col_render_off + tile_off + tile.render_offset();
+                let tile_pos = orient(
+                    self.vertical,
+                    view_off + col_off + col_render_off + tile_off,
+                ) + tile.render_offset();
                 // Round to physical pixels.
                 let tile_pos = tile_pos.to_physical_precise_round(scale).to_logical(scale);
 
@@ -2870,7 +3082,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
 
             // Hit the tab indicator.
             if col.display_mode == ColumnDisplay::Tabbed && !col.is_fullscreen {
-                let col_pos = view_off + col_off + col_render_off;
+                let col_pos = orient(self.vertical, view_off + col_off + col_render_off);
                 let col_pos = col_pos.to_physical_precise_round(scale).to_logical(scale);
 
                 if let Some(idx) = col.tab_indicator.hit(
@@ -2891,8 +3103,10 @@ impl<W: LayoutElement> ScrollingSpace<W> {
                     continue;
                 }
 
-                let tile_pos =
-                    view_off + col_off + col_render_off + tile_off + tile.render_offset();
+                let tile_pos = orient(
+                    self.vertical,
+                    view_off + col_off + col_render_off + tile_off,
+                ) + tile.render_offset();
                 // Round to physical pixels.
                 let tile_pos = tile_pos.to_physical_precise_round(scale).to_logical(scale);
 
@@ -2906,7 +3120,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
     }
 
     pub fn view_offset_gesture_begin(&mut self, is_touchpad: bool) {
-        if self.columns.is_empty() {
+        if self.columns.is_empty() || self.cells.is_some() {
             return;
         }
 
@@ -2928,6 +3142,10 @@ impl<W: LayoutElement> ScrollingSpace<W> {
     }
 
     pub fn dnd_scroll_gesture_begin(&mut self) {
+        if self.cells.is_some() {
+            return;
+        }
+
         if let ViewOffset::Gesture(ViewGesture {
             dnd_last_event_time: Some(_),
             ..
@@ -3406,6 +3624,11 @@ impl<W: LayoutElement> ScrollingSpace<W> {
 
         let is_centering = self.is_centering_focused_column();
 
+        // Resize in layout coordinates.
+        let edges = orient(self.vertical, resize.data.edges);
+        let original_window_size = orient(self.vertical, resize.original_window_size);
+        let delta = orient(self.vertical, delta);
+
         let col = self
             .columns
             .iter_mut()
@@ -3418,9 +3641,9 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             .position(|tile| tile.window().id() == window)
             .unwrap();
 
-        if resize.data.edges.intersects(ResizeEdge::LEFT_RIGHT) {
+        if edges.intersects(ResizeEdge::LEFT_RIGHT) {
             let mut dx = delta.x;
-            if resize.data.edges.contains(ResizeEdge::LEFT) {
+            if edges.contains(ResizeEdge::LEFT) {
                 dx = -dx;
             };
 
@@ -3428,23 +3651,23 @@ impl<W: LayoutElement> ScrollingSpace<W> {
                 dx *= 2.;
             }
 
-            let window_width = (resize.original_window_size.w + dx).round() as i32;
+            let window_width = (original_window_size.w + dx).round() as i32;
             col.set_column_width(SizeChange::SetFixed(window_width), Some(tile_idx), false);
         }
 
-        if resize.data.edges.intersects(ResizeEdge::TOP_BOTTOM) {
+        if edges.intersects(ResizeEdge::TOP_BOTTOM) {
             // Prevent the simplest case of weird resizing (top edge when this is the topmost
             // window).
-            if !(resize.data.edges.contains(ResizeEdge::TOP) && tile_idx == 0) {
+            if !(edges.contains(ResizeEdge::TOP) && tile_idx == 0) {
                 let mut dy = delta.y;
-                if resize.data.edges.contains(ResizeEdge::TOP) {
+                if edges.contains(ResizeEdge::TOP) {
                     dy = -dy;
                 };
 
                 // FIXME: some smarter height distribution would be nice here so that vertical
                 // resizes work as expected in more cases.
 
-                let window_height = (resize.original_window_size.h + dy).round() as i32;
+                let window_height = (original_window_size.h + dy).round() as i32;
                 col.set_window_height(SizeChange::SetFixed(window_height), Some(tile_idx), false);
             }
         }
@@ -3481,7 +3704,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             }
 
             let is_tabbed = col.display_mode == ColumnDisplay::Tabbed;
-            let extra_size = col.extra_size();
+            let extra_size = orient(self.vertical, col.extra_size());
 
             // If transactions are disabled, also disable combined throttling, for more intuitive
             // behavior. In tabbed display mode, only one window is visible, so individual
@@ -3515,6 +3738,10 @@ impl<W: LayoutElement> ScrollingSpace<W> {
                 let active_in_column = col.active_tile_idx == tile_idx;
                 win.set_active_in_column(active_in_column);
                 win.set_floating(false);
+                win.set_pinned(false);
+                win.set_kept_above(false);
+                win.set_kept_below(false);
+                win.set_minimized(false);
 
                 let mut active = is_active && self.active_column_idx == col_idx;
                 if self.options.deactivate_unfocused_windows {
@@ -3531,7 +3758,7 @@ impl<W: LayoutElement> ScrollingSpace<W> {
                 let border_config = win.rules().border.resolve_against(self.options.border);
                 let bounds = compute_toplevel_bounds(
                     border_config,
-                    self.working_area.size,
+                    orient(self.vertical, self.working_area.size),
                     extra_size,
                     self.options.gaps,
                 );
@@ -3557,12 +3784,12 @@ impl<W: LayoutElement> ScrollingSpace<W> {
 
     #[cfg(test)]
     pub fn view_size(&self) -> Size<f64, Logical> {
-        self.view_size
+        orient(self.vertical, self.view_size)
     }
 
     #[cfg(test)]
     pub fn parent_area(&self) -> Rectangle<f64, Logical> {
-        self.parent_area
+        orient(self.vertical, self.parent_area)
     }
 
     #[cfg(test)]
@@ -3597,11 +3824,30 @@ impl<W: LayoutElement> ScrollingSpace<W> {
             compute_working_area(self.parent_area, self.scale, self.options.struts)
         );
 
+        let cells = self.cells.as_ref();
+        for (idx, column) in self.columns.iter().enumerate() {
+            assert_eq!(
+                column.cell,
+                cells.and_then(|cells| cells.get(idx)),
+                "column cells must be up to date"
+            );
+        }
+
+        if let Some(cells) = cells {
+            cells.verify_invariants(self.working_area, self.options.gaps, self.columns.len());
+            assert!(
+                !self.vertical,
+                "non-scrolling layout kinds must be horizontal"
+            );
+            assert!(!self.view_offset.is_gesture());
+        }
+
         if !self.columns.is_empty() {
             assert!(self.active_column_idx < self.columns.len());
 
             for (column, data) in zip(&self.columns, &self.data) {
                 assert!(Rc::ptr_eq(&self.options, &column.options));
+                assert_eq!(self.vertical, column.vertical);
                 assert_eq!(self.clock, column.clock);
                 assert_eq!(self.scale, column.scale);
                 column.verify_invariants();
@@ -3735,22 +3981,22 @@ impl ColumnData {
 }
 
 impl TileData {
-    pub fn new<W: LayoutElement>(tile: &Tile<W>, height: WindowHeight) -> Self {
+    pub fn new<W: LayoutElement>(tile: &Tile<W>, height: WindowHeight, vertical: bool) -> Self {
         let mut rv = Self {
             height,
             size: Size::default(),
             interactively_resizing_by_left_edge: false,
         };
-        rv.update(tile);
+        rv.update(tile, vertical);
         rv
     }
 
-    pub fn update<W: LayoutElement>(&mut self, tile: &Tile<W>) {
-        self.size = tile.tile_size();
+    pub fn update<W: LayoutElement>(&mut self, tile: &Tile<W>, vertical: bool) {
+        self.size = orient(vertical, tile.tile_size());
         self.interactively_resizing_by_left_edge = tile
             .window()
             .interactive_resize_data()
-            .is_some_and(|data| data.edges.contains(ResizeEdge::LEFT));
+            .is_some_and(|data| orient(vertical, data.edges).contains(ResizeEdge::LEFT));
     }
 }
 
@@ -3773,6 +4019,7 @@ impl<W: LayoutElement> Column<W> {
     #[allow(clippy::too_many_arguments)]
     fn new_with_tile(
         tile: Tile<W>,
+        vertical: bool,
         view_size: Size<f64, Logical>,
         working_area: Rectangle<f64, Logical>,
         scale: f64,
@@ -3799,6 +4046,8 @@ impl<W: LayoutElement> Column<W> {
             display_mode,
             tab_indicator: TabIndicator::new(options.tab_indicator),
             move_animation: None,
+            vertical,
+            cell: None,
             view_size,
             working_area,
             scale,
@@ -3830,6 +4079,7 @@ impl<W: LayoutElement> Column<W> {
 
     fn update_config(
         &mut self,
+        vertical: bool,
         view_size: Size<f64, Logical>,
         working_area: Rectangle<f64, Logical>,
         scale: f64,
@@ -3837,7 +4087,10 @@ impl<W: LayoutElement> Column<W> {
     ) {
         let mut update_sizes = false;
 
-        if self.view_size != view_size || self.working_area != working_area {
+        if self.vertical != vertical
+            || self.view_size != view_size
+            || self.working_area != working_area
+        {
             update_sizes = true;
         }
 
@@ -3867,11 +4120,12 @@ impl<W: LayoutElement> Column<W> {
         }
 
         for (tile, data) in zip(&mut self.tiles, &mut self.data) {
-            tile.update_config(view_size, scale, options.clone());
-            data.update(tile);
+            tile.update_config(orient(vertical, view_size), scale, options.clone());
+            data.update(tile, vertical);
         }
 
         self.tab_indicator.update_config(options.tab_indicator);
+        self.vertical = vertical;
         self.view_size = view_size;
         self.working_area = working_area;
         self.scale = scale;
@@ -3882,6 +4136,41 @@ impl<W: LayoutElement> Column<W> {
         }
     }
 
+    fn set_cell(&mut self, cell: Option<Rectangle<f64, Logical>>, animate: bool) {
+        if self.cell == cell {
+            return;
+        }
+
+        if let (true, Some(old), Some(new)) = (animate, self.cell, cell) {
+            let delta = old.loc.y - new.loc.y;
+            if delta != 0. {
+                for tile in &mut self.tiles {
+                    tile.animate_move_y_from(delta);
+                }
+            }
+        }
+
+        self.cell = cell;
+        self.update_tile_sizes(animate);
+    }
+
+    /// Returns the area that the column lays itself out in.
+    fn area(&self) -> Rectangle<f64, Logical> {
+        self.cell.unwrap_or(self.working_area)
+    }
+
+    /// Returns the X of the column filling its cell, if any.
+    fn cell_x(&self) -> Option<f64> {
+        let cell = self.cell?;
+
+        // Fullscreen columns cover the whole view.
+        Some(if self.is_fullscreen {
+            0.
+        } else {
+            cell.loc.x + self.options.gaps
+        })
+    }
+
     pub fn advance_animations(&mut self) {
         if let Some(anim) = &mut self.move_animation {
             if anim.is_done() {
@@ -3909,12 +4198,16 @@ impl<W: LayoutElement> Column<W> {
     }
 
     pub fn update_render_elements(&mut self, is_active: bool, view_rect: Rectangle<f64, Logical>) {
+        let vertical = self.vertical;
+        // Tiles and the tab indicator work in screen coordinates.
+        let view_rect = orient(vertical, view_rect);
+
         let active_idx = self.active_tile_idx;
         for (tile_idx, (tile, tile_off)) in self.tiles_mut().enumerate() {
             let is_active = is_active && tile_idx == active_idx;
 
             let mut tile_view_rect = view_rect;
-            tile_view_rect.loc -= tile_off + tile.render_offset();
+            tile_view_rect.loc -= orient(vertical, tile_off) + tile.render_offset();
             tile.update_render_elements(is_active, tile_view_rect);
         }
 
@@ -3925,7 +4218,7 @@ impl<W: LayoutElement> Column<W> {
             .map(|(tile_idx, (tile, tile_off))| {
                 let is_active = tile_idx == active_idx;
                 let is_urgent = tile.window().is_urgent();
-                let tile_pos = tile_off + tile.render_offset();
+                let tile_pos = orient(vertical, tile_off) + tile.render_offset();
                 TabInfo::from_tile(tile, tile_pos, is_active, is_urgent, &config)
             });
 
@@ -4011,7 +4304,17 @@ impl<W: LayoutElement> Column<W> {
     }
 
     fn add_tile_at(&mut self, idx: usize, mut tile: Tile<W>, animate: bool) {
-        tile.update_config(self.view_size, self.scale, self.options.clone());
+        // Only floating tiles can be pinned or kept above or below.
+        tile.is_pinned = false;
+        tile.keep_above = false;
+        tile.keep_below = false;
+
+        let vertical = self.vertical;
+        tile.update_config(
+            orient(vertical, self.view_size),
+            self.scale,
+            self.options.clone(),
+        );
 
         // Inserting a tile pushes down all tiles below it, but also in always-centering mode it
         // will affect the X position of all tiles in the column.
@@ -4023,7 +4326,7 @@ impl<W: LayoutElement> Column<W> {
         }
 
         self.data
-            .insert(idx, TileData::new(&tile, WindowHeight::auto_1()));
+            .insert(idx, TileData::new(&tile, WindowHeight::auto_1(), vertical));
         self.tiles.insert(idx, tile);
         self.update_tile_sizes(animate);
 
@@ -4034,7 +4337,7 @@ impl<W: LayoutElement> Column<W> {
                 continue;
             }
 
-            tile.animate_move_from(prev - offset);
+            tile.animate_move_from(orient(vertical, prev - offset));
         }
     }
 
@@ -4046,14 +4349,13 @@ impl<W: LayoutElement> Column<W> {
             .find(|(_, tile)| tile.window().id() == window)
             .unwrap();
 
-        let height = f64::from(tile.window().size().h);
-        let offset = tile
-            .window()
-            .animation_snapshot()
-            .map_or(0., |from| from.size.h - height);
+        let height = orient(self.vertical, tile.window().size()).h;
+        let offset = tile.window().animation_snapshot().map_or(0., |from| {
+            orient(self.vertical, from.size).h - f64::from(height)
+        });
 
         tile.update_window();
-        self.data[tile_idx].update(tile);
+        self.data[tile_idx].update(tile, self.vertical);
 
         let is_tabbed = self.display_mode == ColumnDisplay::Tabbed;
 
@@ -4065,7 +4367,9 @@ impl<W: LayoutElement> Column<W> {
         // non-animated -10 resizes.
         if !is_tabbed && tile.resize_animation().is_some() && offset != 0. {
             for tile in &mut self.tiles[tile_idx + 1..] {
-                tile.animate_move_y_from_with_config(
+                animate_tile_move_y_from(
+                    tile,
+                    self.vertical,
                     offset,
                     self.options.animations.window_resize.anim,
                 );
@@ -4074,9 +4378,12 @@ impl<W: LayoutElement> Column<W> {
     }
 
     /// Extra size taken up by elements in the column such as the tab indicator.
+    ///
+    /// Returned in layout coordinates.
     fn extra_size(&self) -> Size<f64, Logical> {
         if self.display_mode == ColumnDisplay::Tabbed {
-            self.tab_indicator.extra_size(self.tiles.len(), self.scale)
+            let size = self.tab_indicator.extra_size(self.tiles.len(), self.scale);
+            orient(self.vertical, size)
         } else {
             Size::from((0., 0.))
         }
@@ -4089,11 +4396,11 @@ impl<W: LayoutElement> Column<W> {
 
     fn resolve_preset_height(&self, preset: PresetSize) -> ResolvedSize {
         let extra = self.extra_size();
-        resolve_preset_size(preset, &self.options, self.working_area.size.h, extra.h)
+        resolve_preset_size(preset, &self.options, self.area().size.h, extra.h)
     }
 
     fn resolve_column_width(&self, width: ColumnWidth) -> f64 {
-        let working_size = self.working_area.size;
+        let working_size = self.area().size;
         let gaps = self.options.gaps;
         let extra = self.extra_size();
 
@@ -4126,11 +4433,12 @@ impl<W: LayoutElement> Column<W> {
         }
 
         let is_tabbed = self.display_mode == ColumnDisplay::Tabbed;
+        let vertical = self.vertical;
 
         let min_size: Vec<_> = self
             .tiles
             .iter()
-            .map(Tile::min_size_nonfullscreen)
+            .map(|tile| orient(vertical, tile.min_size_nonfullscreen()))
             .map(|mut size| {
                 size.w = size.w.max(1.);
                 size.h = size.h.max(1.);
@@ -4140,7 +4448,7 @@ impl<W: LayoutElement> Column<W> {
         let max_size: Vec<_> = self
             .tiles
             .iter()
-            .map(Tile::max_size_nonfullscreen)
+            .map(|tile| orient(vertical, tile.max_size_nonfullscreen()))
             .collect();
 
         // Compute the column width.
@@ -4165,13 +4473,14 @@ impl<W: LayoutElement> Column<W> {
             .unwrap_or(f64::from(i32::MAX));
         let max_width = f64::max(max_width, min_width);
 
-        let width = if self.is_full_width {
+        // Columns fill their cells in non-scrolling layout kinds.
+        let width = if self.is_full_width || self.cell.is_some() {
             ColumnWidth::Proportion(1.)
         } else {
             self.width
         };
 
-        let working_size = self.working_area.size;
+        let working_size = self.area().size;
         let extra_size = self.extra_size();
 
         let width = self.resolve_column_width(width);
@@ -4384,7 +4693,7 @@ impl<W: LayoutElement> Column<W> {
                 unreachable!()
             };
 
-            let size = Size::from((width, height));
+            let size = orient(vertical, Size::from((width, height)));
 
             // In tabbed mode, only the visible window participates in the transaction.
             let is_active = tile_idx == self.active_tile_idx;
@@ -4408,8 +4717,7 @@ impl<W: LayoutElement> Column<W> {
             .unwrap();
 
         if self.display_mode == ColumnDisplay::Tabbed && !self.is_fullscreen {
-            let extra_size = self.tab_indicator.extra_size(self.tiles.len(), self.scale);
-            tiles_width += extra_size.w;
+            tiles_width += self.extra_size().w;
         }
 
         tiles_width
@@ -4453,8 +4761,11 @@ impl<W: LayoutElement> Column<W> {
 
         // Animate the movement.
         let new_active_y = self.tile_offset(new_idx).y;
-        self.tiles[new_idx].animate_move_y_from(active_y - new_active_y);
-        self.tiles[new_idx + 1].animate_move_y_from(active_y - next_y);
+        let config = self.options.animations.window_movement.0;
+        let tile = &mut self.tiles[new_idx];
+        animate_tile_move_y_from(tile, self.vertical, active_y - new_active_y, config);
+        let tile = &mut self.tiles[new_idx + 1];
+        animate_tile_move_y_from(tile, self.vertical, active_y - next_y, config);
 
         true
     }
@@ -4476,8 +4787,11 @@ impl<W: LayoutElement> Column<W> {
 
         // Animate the movement.
         let new_active_y = self.tile_offset(new_idx).y;
-        self.tiles[new_idx].animate_move_y_from(active_y - new_active_y);
-        self.tiles[new_idx - 1].animate_move_y_from(next_y - active_y);
+        let config = self.options.animations.window_movement.0;
+        let tile = &mut self.tiles[new_idx];
+        animate_tile_move_y_from(tile, self.vertical, active_y - new_active_y, config);
+        let tile = &mut self.tiles[new_idx - 1];
+        animate_tile_move_y_from(tile, self.vertical, next_y - active_y, config);
 
         true
     }
@@ -4495,8 +4809,8 @@ impl<W: LayoutElement> Column<W> {
             (idx + 1) % self.options.preset_column_widths.len()
         } else {
             let tile = &self.tiles[tile_idx];
-            let current_window = tile.window_expected_or_current_size().w;
-            let current_tile = tile.tile_expected_or_current_size().w;
+            let current_window = orient(self.vertical, tile.window_expected_or_current_size()).w;
+            let current_tile = orient(self.vertical, tile.tile_expected_or_current_size()).w;
 
             self.options
                 .preset_column_widths
@@ -4591,12 +4905,14 @@ impl<W: LayoutElement> Column<W> {
         let current = self.data[tile_idx].height;
         let tile = &self.tiles[tile_idx];
         let current_window_px = match current {
-            WindowHeight::Auto { .. } | WindowHeight::Preset(_) => tile.window_size().h,
+            WindowHeight::Auto { .. } | WindowHeight::Preset(_) => {
+                orient(self.vertical, tile.window_size()).h
+            }
             WindowHeight::Fixed(height) => height,
         };
         let current_tile_px = tile.tile_height_for_window_height(current_window_px);
 
-        let working_size = self.working_area.size.h;
+        let working_size = self.area().size.h;
         let gaps = self.options.gaps;
         let extra_size = self.extra_size().h;
         let full = working_size - gaps;
@@ -4631,7 +4947,10 @@ impl<W: LayoutElement> Column<W> {
                 .iter()
                 .enumerate()
                 .filter(|(idx, _)| *idx != tile_idx)
-                .map(|(_, tile)| f64::max(1., tile.min_size_nonfullscreen().h) + gaps)
+                .map(|(_, tile)| {
+                    let min_size = orient(self.vertical, tile.min_size_nonfullscreen());
+                    f64::max(1., min_size.h) + gaps
+                })
                 .sum::<f64>()
         };
         let height_left = working_size - extra_size - gaps - min_height_taken - gaps;
@@ -4640,8 +4959,8 @@ impl<W: LayoutElement> Column<W> {
 
         // Clamp it against the window height constraints.
         let win = &self.tiles[tile_idx].window();
-        let min_h = win.min_size().h;
-        let max_h = win.max_size().h;
+        let min_h = orient(self.vertical, win.min_size()).h;
+        let max_h = orient(self.vertical, win.max_size()).h;
 
         if max_h > 0 {
             window_height = f64::min(window_height, f64::from(max_h));
@@ -4717,7 +5036,11 @@ impl<W: LayoutElement> Column<W> {
     /// One case where apparent heights will not be preserved is when the column is taller than the
     /// working area.
     fn convert_heights_to_auto(&mut self) {
-        let heights: Vec<_> = self.tiles.iter().map(|tile| tile.tile_size().h).collect();
+        let heights: Vec<_> = self
+            .tiles
+            .iter()
+            .map(|tile| orient(self.vertical, tile.tile_size()).h)
+            .collect();
 
         // Weights are invariant to multiplication: a column with weights 2, 2, 1 is equivalent to
         // a column with weights 4, 4, 2. So we find the median window height and use that as 1.
@@ -4763,6 +5086,7 @@ impl<W: LayoutElement> Column<W> {
 
         // When need to walk the tiles in the normal display mode to get the right offsets.
         self.display_mode = ColumnDisplay::Normal;
+        let vertical = self.vertical;
         for (tile, pos) in self.tiles_mut() {
             let mut y_delta = pos.y - prev_origin.y;
 
@@ -4773,7 +5097,7 @@ impl<W: LayoutElement> Column<W> {
 
             let mut delta = origin_delta;
             delta.y += y_delta;
-            tile.animate_move_from(delta);
+            tile.animate_move_from(orient(vertical, delta));
         }
 
         // Animate the opacity.
@@ -4809,12 +5133,13 @@ impl<W: LayoutElement> Column<W> {
             return origin;
         }
 
-        origin.y += self.working_area.loc.y + self.options.gaps;
+        origin.y += self.area().loc.y + self.options.gaps;
 
         if self.display_mode == ColumnDisplay::Tabbed {
-            origin += self
+            let offset = self
                 .tab_indicator
                 .content_offset(self.tiles.len(), self.scale);
+            origin += orient(self.vertical, offset);
         }
 
         origin
@@ -4947,15 +5272,22 @@ impl<W: LayoutElement> Column<W> {
         // fixed. Third, the animation for making a column tabbed moves tiles vertically, and using
         // the active tile's animated size in this case only works for the topmost tile, and looks
         // broken otherwise.
+        //
+        // The tab indicator is drawn in screen coordinates, so in a vertical column the
+        // returned area is converted back from the layout coordinates.
         let mut max_height = 0.;
         for tile in &self.tiles {
-            max_height = f64::max(max_height, tile.tile_size().h);
+            max_height = f64::max(max_height, orient(self.vertical, tile.tile_size()).h);
         }
 
         let tile = &self.tiles[self.active_tile_idx];
-        let area_size = Size::from((tile.animated_tile_size().w, max_height));
+        let width = orient(self.vertical, tile.animated_tile_size()).w;
+        let area_size = Size::from((width, max_height));
 
-        Rectangle::new(self.tiles_origin(), area_size)
+        orient(
+            self.vertical,
+            Rectangle::new(self.tiles_origin(), area_size),
+        )
     }
 
     pub fn start_open_animation(&mut self, id: &W::Id) -> bool {
@@ -5008,7 +5340,7 @@ impl<W: LayoutElement> Column<W> {
             }
         }
 
-        let working_size = self.working_area.size;
+        let working_size = self.area().size;
         let extra_size = self.extra_size();
         let gaps = self.options.gaps;
 
@@ -5020,11 +5352,16 @@ impl<W: LayoutElement> Column<W> {
             assert_eq!(self.clock, tile.clock);
             assert_eq!(self.scale, tile.scale());
             assert_eq!(self.is_fullscreen, tile.window().is_pending_fullscreen());
-            assert_eq!(self.view_size, tile.view_size());
+            assert!(!tile.is_pinned, "only floating tiles can be pinned");
+            assert!(
+                !tile.keep_above && !tile.keep_below,
+                "only floating tiles can be kept above or below"
+            );
+            assert_eq!(orient(self.vertical, self.view_size), tile.view_size());
             tile.verify_invariants();
 
             let mut data2 = *data;
-            data2.update(tile);
+            data2.update(tile, self.vertical);
             assert_eq!(data, &data2, "tile data must be up to date");
 
             if matches!(data.height, WindowHeight::Fixed(_)) {
@@ -5039,10 +5376,11 @@ impl<W: LayoutElement> Column<W> {
                 assert!(self.options.preset_window_heights.len() > idx);
             }
 
-            let requested_size = tile.window().requested_size().unwrap();
+            let requested_size = orient(self.vertical, tile.window().requested_size().unwrap());
             let requested_tile_height =
                 tile.tile_height_for_window_height(f64::from(requested_size.h));
-            let min_tile_height = f64::max(1., tile.min_size_nonfullscreen().h);
+            let min_size = orient(self.vertical, tile.min_size_nonfullscreen());
+            let min_tile_height = f64::max(1., min_size.h);
 
             if !self.is_fullscreen
                 && self.scale.round() == self.scale
@@ -5192,6 +5530,73 @@ fn resolve_preset_size(
     }
 }
 
+fn is_vertical(orientation: ScrollOrientation, view_size: Size<f64, Logical>) -> bool {
+    match orientation {
+        ScrollOrientation::Horizontal => false,
+        ScrollOrientation::Vertical => true,
+        ScrollOrientation::Auto => view_size.w < view_size.h,
+    }
+}
+
+/// Geometry that can be mirrored across the main diagonal by swapping the X and Y axes.
+trait Transpose {
+    fn transpose(self) -> Self;
+}
+
+impl<N: Coordinate> Transpose for Point<N, Logical> {
+    fn transpose(self) -> Self {
+        Point::from((self.y, self.x))
+    }
+}
+
+impl<N: Coordinate> Transpose for Size<N, Logical> {
+    fn transpose(self) -> Self {
+        Size::from((self.h, self.w))
+    }
+}
+
+impl<N: Coordinate> Transpose for Rectangle<N, Logical> {
+    fn transpose(self) -> Self {
+        Rectangle::new(self.loc.transpose(), self.size.transpose())
+    }
+}
+
+impl Transpose for ResizeEdge {
+    fn transpose(self) -> Self {
+        let mut rv = ResizeEdge::empty();
+        rv.set(ResizeEdge::LEFT, self.contains(ResizeEdge::TOP));
+        rv.set(ResizeEdge::RIGHT, self.contains(ResizeEdge::BOTTOM));
+        rv.set(ResizeEdge::TOP, self.contains(ResizeEdge::LEFT));
+        rv.set(ResizeEdge::BOTTOM, self.contains(ResizeEdge::RIGHT));
+        rv
+    }
+}
+
+/// Converts between layout and screen coordinates.
+///
+/// The conversion is its own inverse, so this works in both directions.
+fn orient<T: Transpose>(vertical: bool, value: T) -> T {
+    if vertical {
+        value.transpose()
+    } else {
+        value
+    }
+}
+
+/// Animates the tile movement along the layout Y axis.
+fn animate_tile_move_y_from<W: LayoutElement>(
+    tile: &mut Tile<W>,
+    vertical: bool,
+    from: f64,
+    config: niri_config::Animation,
+) {
+    if vertical {
+        tile.animate_move_x_from_with_config(from, config);
+    } else {
+        tile.animate_move_y_from_with_config(from, config);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use niri_config::FloatOrInt;
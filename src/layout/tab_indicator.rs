@@ -2,13 +2,14 @@ use std::iter::zip;
 use std::mem;
 
 use niri_config::{CornerRadius, Gradient, GradientRelativeTo, TabIndicatorPosition};
-use smithay::utils::{Logical, Point, Rectangle, Size};
+use smithay::utils::{Logical, Point, Rectangle, Scale, Size};
 
 use super::tile::Tile;
 use super::LayoutElement;
 use crate::animation::{Animation, Clock};
 use crate::niri_render_elements;
 use crate::render_helpers::border::BorderRenderElement;
+use crate::render_helpers::primary_gpu_texture::PrimaryGpuTextureRenderElement;
 use crate::render_helpers::renderer::NiriRenderer;
 use crate::utils::{
     floor_logical_in_physical_max1, round_logical_in_physical, round_logical_in_physical_max1,
@@ -18,6 +19,8 @@ use crate::utils::{
 pub struct TabIndicator {
     shader_locs: Vec<Point<f64, Logical>>,
     shaders: Vec<BorderRenderElement>,
+    /// Rectangles for window icons, one per tab, empty when icons are disabled.
+    icon_rects: Vec<Rectangle<f64, Logical>>,
     open_anim: Option<Animation>,
     config: niri_config::TabIndicator,
 }
@@ -33,6 +36,7 @@ pub struct TabInfo {
 niri_render_elements! {
     TabIndicatorRenderElement => {
         Gradient = BorderRenderElement,
+        Icon = PrimaryGpuTextureRenderElement,
     }
 }
 
@@ -41,6 +45,7 @@ impl TabIndicator {
         Self {
             shader_locs: Vec::new(),
             shaders: Vec::new(),
+            icon_rects: Vec::new(),
             open_anim: None,
             config,
         }
@@ -171,6 +176,7 @@ impl TabIndicator {
         if !enabled || self.config.off {
             self.shader_locs.clear();
             self.shaders.clear();
+            self.icon_rects.clear();
             return;
         }
 
@@ -178,11 +184,13 @@ impl TabIndicator {
         if self.config.hide_when_single_tab && count == 1 {
             self.shader_locs.clear();
             self.shaders.clear();
+            self.icon_rects.clear();
             return;
         }
 
         self.shaders.resize_with(count, Default::default);
         self.shader_locs.resize_with(count, Default::default);
+        self.icon_rects.clear();
 
         let position = self.config.position;
         let radius = self.config.corner_radius.0 as f32;
@@ -196,6 +204,17 @@ impl TabIndicator {
         ) {
             *loc = rect.loc;
 
+            if self.config.show_icons {
+                // Center a square icon across the tab thickness.
+                let size = f64::min(rect.size.w, rect.size.h);
+                let offset = (rect.size.to_point() - Point::from((size, size))).downscale(2.);
+                let icon_loc = (rect.loc + offset).to_physical_precise_round(scale);
+                self.icon_rects.push(Rectangle::new(
+                    icon_loc.to_logical(scale),
+                    Size::from((size, size)),
+                ));
+            }
+
             let mut gradient_area = match tab.gradient.relative_to {
                 GradientRelativeTo::Window => tab.geometry,
                 GradientRelativeTo::WorkspaceView => area_view_rect,
@@ -307,6 +326,23 @@ impl TabIndicator {
         Some(rv).into_iter().flatten()
     }
 
+    /// Renders icons of the tabbed windows.
+    ///
+    /// The windows should come in the same order as the tabs.
+    pub fn render_icons<'a, W: LayoutElement, R: NiriRenderer>(
+        &'a self,
+        renderer: &'a mut R,
+        pos: Point<f64, Logical>,
+        windows: impl Iterator<Item = &'a W> + 'a,
+        scale: Scale<f64>,
+    ) -> impl Iterator<Item = TabIndicatorRenderElement> + 'a {
+        zip(&self.icon_rects, windows)
+            .filter_map(move |(rect, win)| {
+                win.render_icon(renderer, pos + rect.loc, rect.size.w, scale, 1.)
+            })
+            .map(TabIndicatorRenderElement::from)
+    }
+
     /// Extra size occupied by the tab indicator.
     pub fn extra_size(&self, tab_count: usize, scale: f64) -> Size<f64, Logical> {
         if self.config.off
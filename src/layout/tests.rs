@@ -21,6 +21,7 @@ impl<W: LayoutElement> Default for Layout<W> {
 struct TestWindowInner {
     id: usize,
     parent_id: Cell<Option<usize>>,
+    is_modal: bool,
     bbox: Cell<Rectangle<i32, Logical>>,
     initial_bbox: Rectangle<i32, Logical>,
     requested_size: Cell<Option<Size<i32, Logical>>>,
@@ -42,6 +43,7 @@ struct TestWindowParams {
     id: usize,
     #[proptest(strategy = "arbitrary_parent_id()")]
     parent_id: Option<usize>,
+    is_modal: bool,
     is_floating: bool,
     #[proptest(strategy = "arbitrary_bbox()")]
     bbox: Rectangle<i32, Logical>,
@@ -54,6 +56,7 @@ impl TestWindowParams {
         Self {
             id,
             parent_id: None,
+            is_modal: false,
             is_floating: false,
             bbox: Rectangle::from_size(Size::from((100, 200))),
             min_max_size: Default::default(),
@@ -66,6 +69,7 @@ impl TestWindow {
         Self(Rc::new(TestWindowInner {
             id: params.id,
             parent_id: Cell::new(params.parent_id),
+            is_modal: params.is_modal,
             bbox: Cell::new(params.bbox),
             initial_bbox: params.bbox,
             requested_size: Cell::new(None),
@@ -205,6 +209,14 @@ impl LayoutElement for TestWindow {
 
     fn set_floating(&mut self, _floating: bool) {}
 
+    fn set_pinned(&mut self, _pinned: bool) {}
+
+    fn set_kept_above(&mut self, _kept_above: bool) {}
+
+    fn set_kept_below(&mut self, _kept_below: bool) {}
+
+    fn set_minimized(&mut self, _minimized: bool) {}
+
     fn is_fullscreen(&self) -> bool {
         if self.0.is_windowed_fullscreen.get() {
             return false;
@@ -237,6 +249,10 @@ impl LayoutElement for TestWindow {
         self.0.parent_id.get() == Some(parent.0.id)
     }
 
+    fn is_modal(&self) -> bool {
+        self.0.is_modal && self.0.parent_id.get().is_some()
+    }
+
     fn refresh(&self) {}
 
     fn rules(&self) -> &ResolvedWindowRules {
@@ -265,6 +281,14 @@ impl LayoutElement for TestWindow {
     fn is_urgent(&self) -> bool {
         false
     }
+
+    fn title(&self) -> Option<String> {
+        Some(format!("window {}", self.0.id))
+    }
+
+    fn app_id(&self) -> Option<String> {
+        None
+    }
 }
 
 fn arbitrary_bbox() -> impl Strategy<Value = Rectangle<i32, Logical>> {
@@ -368,6 +392,14 @@ fn arbitrary_column_display() -> impl Strategy<Value = ColumnDisplay> {
     prop_oneof![Just(ColumnDisplay::Normal), Just(ColumnDisplay::Tabbed)]
 }
 
+fn arbitrary_layout_kind() -> impl Strategy<Value = LayoutKind> {
+    prop_oneof![
+        Just(LayoutKind::Scrolling),
+        Just(LayoutKind::MasterStack),
+        Just(LayoutKind::Grid)
+    ]
+}
+
 #[derive(Debug, Clone, Copy, Arbitrary)]
 enum Op {
     AddOutput(#[proptest(strategy = "1..=5usize")] usize),
@@ -505,6 +537,12 @@ enum Op {
         #[proptest(strategy = "proptest::option::of(1..=5usize)")]
         ws_name: Option<usize>,
     },
+    SetWorkspaceLayoutKind {
+        #[proptest(strategy = "arbitrary_layout_kind()")]
+        kind: LayoutKind,
+        #[proptest(strategy = "proptest::option::of(1..=5usize)")]
+        ws_name: Option<usize>,
+    },
     MoveWindowToOutput {
         #[proptest(strategy = "proptest::option::of(1..=5usize)")]
         window_id: Option<usize>,
@@ -557,6 +595,26 @@ enum Op {
         id: Option<usize>,
         floating: bool,
     },
+    TogglePinWindow {
+        #[proptest(strategy = "proptest::option::of(1..=5usize)")]
+        id: Option<usize>,
+    },
+    ToggleWindowKeepAbove {
+        #[proptest(strategy = "proptest::option::of(1..=5usize)")]
+        id: Option<usize>,
+    },
+    ToggleWindowKeepBelow {
+        #[proptest(strategy = "proptest::option::of(1..=5usize)")]
+        id: Option<usize>,
+    },
+    MinimizeWindow {
+        #[proptest(strategy = "proptest::option::of(1..=5usize)")]
+        id: Option<usize>,
+    },
+    RestoreMinimized {
+        #[proptest(strategy = "proptest::option::of(1..=5usize)")]
+        id: Option<usize>,
+    },
     FocusFloating,
     FocusTiling,
     SwitchFocusFloatingTiling,
@@ -569,6 +627,12 @@ enum Op {
         y: PositionChange,
         animate: bool,
     },
+    TileFloatingWindow {
+        #[proptest(strategy = "proptest::option::of(1..=5usize)")]
+        id: Option<usize>,
+        #[proptest(strategy = "arbitrary_floating_tile_area()")]
+        area: FloatingTileArea,
+    },
     SetParent {
         #[proptest(strategy = "1..=5usize")]
         id: usize,
@@ -631,6 +695,20 @@ enum Op {
         #[proptest(strategy = "-20000f64..20000f64")]
         py: f64,
     },
+    InteractiveMoveBeginAtOffset {
+        #[proptest(strategy = "1..=5usize")]
+        window: usize,
+        #[proptest(strategy = "1..=5usize")]
+        output_idx: usize,
+        #[proptest(strategy = "-20000f64..20000f64")]
+        px: f64,
+        #[proptest(strategy = "-20000f64..20000f64")]
+        py: f64,
+        #[proptest(strategy = "-1000f64..1000f64")]
+        ox: f64,
+        #[proptest(strategy = "-1000f64..1000f64")]
+        oy: f64,
+    },
     InteractiveMoveUpdate {
         #[proptest(strategy = "1..=5usize")]
         window: usize,
@@ -677,6 +755,9 @@ enum Op {
         window: usize,
     },
     ToggleOverview,
+    OverviewSearchPush(#[proptest(strategy = "proptest::char::range('1', '5')")] char),
+    OverviewSearchPop,
+    OverviewSearchFocusNext(bool),
 }
 
 impl Op {
@@ -769,6 +850,8 @@ impl Op {
                 layout.ensure_named_workspace(&WorkspaceConfig {
                     name: WorkspaceName(format!("ws{ws_name}")),
                     open_on_output: output_name.map(|name| format!("output{name}")),
+                    layout_kind: None,
+                    scroll_orientation: None,
                 });
             }
             Op::UnnameWorkspace { ws_name } => {
@@ -787,8 +870,13 @@ impl Op {
                     ws_name.map(|ws_name| WorkspaceReference::Name(format!("ws{ws_name}")));
                 layout.unset_workspace_name(ws_ref);
             }
+            Op::SetWorkspaceLayoutKind { kind, ws_name } => {
+                let ws_ref =
+                    ws_name.map(|ws_name| WorkspaceReference::Name(format!("ws{ws_name}")));
+                layout.set_workspace_layout_kind(kind, ws_ref);
+            }
             Op::AddWindow { mut params } => {
-                if layout.has_window(&params.id) {
+                if layout.has_window(&params.id) || is_minimized(layout, params.id) {
                     return;
                 }
                 if let Some(parent_id) = params.parent_id {
@@ -812,6 +900,10 @@ impl Op {
                 mut params,
                 next_to_id,
             } => {
+                if is_minimized(layout, params.id) {
+                    return;
+                }
+
                 let mut found_next_to = false;
 
                 if let Some(InteractiveMoveState::Moving(move_)) = &layout.interactive_move {
@@ -883,6 +975,10 @@ impl Op {
                 let ws_name = format!("ws{ws_name}");
                 let mut ws_id = None;
 
+                if is_minimized(layout, params.id) {
+                    return;
+                }
+
                 if let Some(InteractiveMoveState::Moving(move_)) = &layout.interactive_move {
                     if move_.tile.window().0.id == params.id {
                         return;
@@ -1227,6 +1323,26 @@ impl Op {
                 let id = id.filter(|id| layout.has_window(id));
                 layout.set_window_floating(id.as_ref(), floating);
             }
+            Op::TogglePinWindow { id } => {
+                let id = id.filter(|id| layout.has_window(id));
+                layout.toggle_window_pinned(id.as_ref());
+            }
+            Op::ToggleWindowKeepAbove { id } => {
+                let id = id.filter(|id| layout.has_window(id));
+                layout.toggle_window_keep_above(id.as_ref());
+            }
+            Op::ToggleWindowKeepBelow { id } => {
+                let id = id.filter(|id| layout.has_window(id));
+                layout.toggle_window_keep_below(id.as_ref());
+            }
+            Op::MinimizeWindow { id } => {
+                let id = id.filter(|id| layout.has_window(id));
+                layout.minimize_window(id.as_ref());
+            }
+            Op::RestoreMinimized { id } => {
+                let id = id.filter(|id| is_minimized(layout, *id));
+                layout.restore_minimized(id.as_ref());
+            }
             Op::FocusFloating => {
                 layout.focus_floating();
             }
@@ -1240,6 +1356,10 @@ impl Op {
                 let id = id.filter(|id| layout.has_window(id));
                 layout.move_floating_window(id.as_ref(), x, y, animate);
             }
+            Op::TileFloatingWindow { id, area } => {
+                let id = id.filter(|id| layout.has_window(id));
+                layout.tile_floating_window(id.as_ref(), area);
+            }
             Op::SetParent {
                 id,
                 mut new_parent_id,
@@ -1294,6 +1414,7 @@ impl Op {
                     if let Some(new_parent_id) = new_parent_id {
                         layout.descendants_added(&new_parent_id);
                     }
+                    layout.update_modal(&id);
                 }
             }
             Op::Communicate(id) => {
@@ -1432,6 +1553,25 @@ impl Op {
                 };
                 layout.interactive_move_begin(window, &output, Point::from((px, py)));
             }
+            Op::InteractiveMoveBeginAtOffset {
+                window,
+                output_idx,
+                px,
+                py,
+                ox,
+                oy,
+            } => {
+                let name = format!("output{output_idx}");
+                let Some(output) = layout.outputs().find(|o| o.name() == name).cloned() else {
+                    return;
+                };
+                layout.interactive_move_begin_at_offset(
+                    window,
+                    &output,
+                    Point::from((px, py)),
+                    Point::from((ox, oy)),
+                );
+            }
             Op::InteractiveMoveUpdate {
                 window,
                 dx,
@@ -1476,6 +1616,15 @@ impl Op {
             Op::ToggleOverview => {
                 layout.toggle_overview();
             }
+            Op::OverviewSearchPush(c) => {
+                layout.overview_search_push(c);
+            }
+            Op::OverviewSearchPop => {
+                layout.overview_search_pop();
+            }
+            Op::OverviewSearchFocusNext(forward) => {
+                layout.overview_search_focus_next(forward);
+            }
         }
     }
 }
@@ -2530,6 +2679,193 @@ fn start_interactive_move_then_remove_window() {
     check_ops(&ops);
 }
 
+#[test]
+fn interactive_move_begin_at_offset_sets_pointer_ratio() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(0),
+        },
+        Op::InteractiveMoveBeginAtOffset {
+            window: 0,
+            output_idx: 1,
+            px: 0.,
+            py: 0.,
+            ox: 25.,
+            oy: 150.,
+        },
+    ];
+
+    let layout = check_ops(&ops);
+
+    let Some(InteractiveMoveState::Starting {
+        window_id,
+        pointer_ratio_within_window,
+        ..
+    }) = &layout.interactive_move
+    else {
+        panic!("interactive move must have started");
+    };
+    assert_eq!(*window_id, 0);
+
+    // The test window is 100×200.
+    assert_eq!(*pointer_ratio_within_window, (0.25, 0.75));
+}
+
+#[test]
+fn interactive_move_begin_at_offset_clamps_to_window() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(0),
+        },
+        Op::InteractiveMoveBeginAtOffset {
+            window: 0,
+            output_idx: 1,
+            px: 0.,
+            py: 0.,
+            ox: -50.,
+            oy: 1000.,
+        },
+    ];
+
+    let layout = check_ops(&ops);
+
+    let Some(InteractiveMoveState::Starting {
+        pointer_ratio_within_window,
+        ..
+    }) = &layout.interactive_move
+    else {
+        panic!("interactive move must have started");
+    };
+    assert_eq!(*pointer_ratio_within_window, (0., 1.));
+}
+
+#[test]
+fn interactive_move_begin_at_offset_unknown_window() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(0),
+        },
+        Op::InteractiveMoveBeginAtOffset {
+            window: 1,
+            output_idx: 1,
+            px: 0.,
+            py: 0.,
+            ox: 0.,
+            oy: 0.,
+        },
+    ];
+
+    let layout = check_ops(&ops);
+    assert!(layout.interactive_move.is_none());
+}
+
+#[test]
+fn interactive_move_at_offset_drop_ends_move() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(0),
+        },
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::InteractiveMoveBeginAtOffset {
+            window: 0,
+            output_idx: 1,
+            px: 0.,
+            py: 0.,
+            ox: 50.,
+            oy: 10.,
+        },
+        Op::InteractiveMoveUpdate {
+            window: 0,
+            dx: 1000.,
+            dy: 0.,
+            output_idx: 1,
+            px: 1000.,
+            py: 0.,
+        },
+    ];
+
+    let mut layout = check_ops(&ops);
+    assert!(matches!(
+        layout.interactive_move,
+        Some(InteractiveMoveState::Moving(_))
+    ));
+
+    // Dropping puts the window back into the layout.
+    Op::InteractiveMoveEnd { window: 0 }.apply(&mut layout);
+    layout.verify_invariants();
+    assert!(layout.interactive_move.is_none());
+    assert!(layout.windows().any(|(_, win)| *win.id() == 0));
+}
+
+#[test]
+fn interactive_move_at_offset_cancel_before_threshold() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(0),
+        },
+        Op::InteractiveMoveBeginAtOffset {
+            window: 0,
+            output_idx: 1,
+            px: 0.,
+            py: 0.,
+            ox: 50.,
+            oy: 10.,
+        },
+        Op::InteractiveMoveUpdate {
+            window: 0,
+            dx: 1.,
+            dy: 0.,
+            output_idx: 1,
+            px: 1.,
+            py: 0.,
+        },
+        // The drag is cancelled before the window starts moving.
+        Op::InteractiveMoveEnd { window: 0 },
+    ];
+
+    let layout = check_ops(&ops);
+    assert!(layout.interactive_move.is_none());
+    assert!(layout.windows().any(|(_, win)| *win.id() == 0));
+}
+
+#[test]
+fn interactive_move_at_offset_window_closed_during_drag() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(0),
+        },
+        Op::InteractiveMoveBeginAtOffset {
+            window: 0,
+            output_idx: 1,
+            px: 0.,
+            py: 0.,
+            ox: 50.,
+            oy: 10.,
+        },
+        Op::InteractiveMoveUpdate {
+            window: 0,
+            dx: 1000.,
+            dy: 0.,
+            output_idx: 1,
+            px: 1000.,
+            py: 0.,
+        },
+        Op::CloseWindow(0),
+        Op::InteractiveMoveEnd { window: 0 },
+    ];
+
+    let layout = check_ops(&ops);
+    assert!(layout.interactive_move.is_none());
+}
+
 #[test]
 fn interactive_move_onto_empty_output() {
     let ops = [
@@ -2932,48 +3268,527 @@ fn windows_on_other_workspaces_remain_activated() {
 }
 
 #[test]
-fn stacking_add_parent_brings_up_child() {
+fn pinned_window_stays_on_monitor_across_workspace_switches() {
     let ops = [
-        Op::AddOutput(0),
+        Op::AddOutput(1),
         Op::AddWindow {
-            params: TestWindowParams {
-                is_floating: true,
-                parent_id: Some(1),
-                ..TestWindowParams::new(0)
-            },
+            params: TestWindowParams::new(1),
         },
         Op::AddWindow {
-            params: TestWindowParams {
-                is_floating: true,
-                ..TestWindowParams::new(1)
-            },
+            params: TestWindowParams::new(2),
         },
+        Op::TogglePinWindow { id: Some(1) },
+        Op::FocusWorkspaceDown,
+        Op::FocusWorkspaceUp,
+        Op::FocusWorkspaceDown,
     ];
 
-    check_ops(&ops);
+    let layout = check_ops(&ops);
+    let mon = layout.active_monitor_ref().unwrap();
+    assert!(mon.has_pinned_window(&1));
+    assert!(!layout.workspaces().any(|(_, _, ws)| ws.has_window(&1)));
+    // The workspace with window 2 and the empty one.
+    assert_eq!(mon.workspaces.len(), 2);
+
+    let mut ws_id = Some(None);
+    layout.with_windows(|win, _, id| {
+        if *win.id() == 1 {
+            ws_id = Some(id);
+        }
+    });
+    assert_eq!(ws_id, Some(None));
 }
 
 #[test]
-fn stacking_add_parent_brings_up_descendants() {
+fn moving_pinned_window_to_workspace_keeps_it_pinned() {
     let ops = [
-        Op::AddOutput(0),
+        Op::AddOutput(1),
         Op::AddWindow {
-            params: TestWindowParams {
-                is_floating: true,
-                parent_id: Some(2),
-                ..TestWindowParams::new(0)
-            },
+            params: TestWindowParams::new(1),
         },
-        Op::AddWindow {
-            params: TestWindowParams {
-                is_floating: true,
-                parent_id: Some(0),
-                ..TestWindowParams::new(1)
-            },
+        Op::TogglePinWindow { id: None },
+        Op::MoveWindowToWorkspaceDown,
+        Op::MoveWindowToWorkspace {
+            window_id: None,
+            workspace_idx: 0,
         },
-        Op::AddWindow {
-            params: TestWindowParams {
-                is_floating: true,
+    ];
+
+    let layout = check_ops(&ops);
+    let mon = layout.active_monitor_ref().unwrap();
+    assert!(mon.has_pinned_window(&1));
+    assert!(mon.pinned_is_active);
+    assert!(!layout.workspaces().any(|(_, _, ws)| ws.has_windows()));
+}
+
+#[test]
+fn unpinning_window_puts_it_on_active_workspace() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::AddWindow {
+            params: TestWindowParams::new(2),
+        },
+        Op::TogglePinWindow { id: Some(1) },
+        Op::FocusWorkspaceDown,
+        Op::TogglePinWindow { id: Some(1) },
+    ];
+
+    let layout = check_ops(&ops);
+    let (mon, idx, ws) = layout
+        .workspaces()
+        .find(|(_, _, ws)| ws.has_window(&1))
+        .unwrap();
+    assert_eq!(idx, mon.unwrap().active_workspace_idx());
+    assert!(ws.is_floating(&1));
+}
+
+#[test]
+fn window_kept_above_stays_on_top_when_activating_others() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_floating: true,
+                ..TestWindowParams::new(1)
+            },
+        },
+        Op::ToggleWindowKeepAbove { id: None },
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_floating: true,
+                ..TestWindowParams::new(2)
+            },
+        },
+        Op::FocusWindow(2),
+    ];
+
+    let layout = check_ops(&ops);
+    let (_, _, ws) = layout
+        .workspaces()
+        .find(|(_, _, ws)| ws.has_window(&1))
+        .unwrap();
+    let ids: Vec<_> = ws
+        .floating()
+        .tiles()
+        .map(|tile| tile.window().0.id)
+        .collect();
+    assert_eq!(ids, [1, 2]);
+    assert_eq!(layout.focus().unwrap().0.id, 2);
+}
+
+#[test]
+fn window_kept_below_stays_under_others() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_floating: true,
+                ..TestWindowParams::new(1)
+            },
+        },
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_floating: true,
+                ..TestWindowParams::new(2)
+            },
+        },
+        Op::ToggleWindowKeepBelow { id: Some(2) },
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_floating: true,
+                ..TestWindowParams::new(3)
+            },
+        },
+        Op::FocusWindow(2),
+        // Keeping below undoes keeping above.
+        Op::ToggleWindowKeepAbove { id: Some(3) },
+        Op::ToggleWindowKeepBelow { id: Some(3) },
+    ];
+
+    let layout = check_ops(&ops);
+    let (_, _, ws) = layout
+        .workspaces()
+        .find(|(_, _, ws)| ws.has_window(&1))
+        .unwrap();
+    let ids: Vec<_> = ws
+        .floating()
+        .tiles()
+        .map(|tile| tile.window().0.id)
+        .collect();
+    assert_eq!(ids, [1, 3, 2]);
+}
+
+#[test]
+fn grid_layout_kind_arranges_columns_in_cells() {
+    let mut ops = vec![Op::AddOutput(1)];
+    for id in 1..=4 {
+        ops.push(Op::AddWindow {
+            params: TestWindowParams::new(id),
+        });
+    }
+    ops.push(Op::SetWorkspaceLayoutKind {
+        kind: LayoutKind::Grid,
+        ws_name: None,
+    });
+    ops.extend((1..=4).map(Op::Communicate));
+    ops.push(Op::AdvanceAnimations { msec_delta: 1000 });
+
+    let mut layout = check_ops(&ops);
+    let ws = layout.active_workspace().unwrap();
+    assert_eq!(ws.layout_kind(), LayoutKind::Grid);
+
+    // The working area is 1280×720 with 16 px gaps, split into a 2×2 grid.
+    let mut tiles: Vec<_> = ws
+        .scrolling()
+        .tiles_with_render_positions()
+        .map(|(tile, pos, _)| (tile.window().0.id, pos, tile.window().requested_size()))
+        .collect();
+    tiles.sort_by_key(|(id, _, _)| *id);
+    let size = Some(Size::from((616, 336)));
+    assert_eq!(
+        tiles,
+        [
+            (1, Point::from((16., 16.)), size),
+            (2, Point::from((648., 16.)), size),
+            (3, Point::from((16., 368.)), size),
+            (4, Point::from((648., 368.)), size),
+        ]
+    );
+
+    // Directional focus follows the grid.
+    let ops = [Op::FocusWindowUp, Op::FocusColumnLeft, Op::FocusWindowDown];
+    let mut expected = [2, 1, 3].into_iter();
+    for op in ops {
+        op.apply(&mut layout);
+        assert_eq!(layout.focus().unwrap().0.id, expected.next().unwrap());
+    }
+}
+
+#[test]
+fn master_stack_layout_kind_moves_columns_into_master() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::AddWindow {
+            params: TestWindowParams::new(2),
+        },
+        Op::AddWindow {
+            params: TestWindowParams::new(3),
+        },
+        Op::SetWorkspaceLayoutKind {
+            kind: LayoutKind::MasterStack,
+            ws_name: None,
+        },
+        // From the bottom of the stack, moving left swaps with the master column.
+        Op::MoveColumnLeft,
+        Op::Communicate(1),
+        Op::Communicate(2),
+        Op::Communicate(3),
+    ];
+
+    let layout = check_ops(&ops);
+    let ws = layout.active_workspace().unwrap();
+    let sizes: Vec<_> = ws
+        .scrolling()
+        .tiles()
+        .map(|tile| (tile.window().0.id, tile.window().requested_size().unwrap()))
+        .collect();
+    assert_eq!(
+        sizes,
+        [
+            (3, Size::from((616, 688))),
+            (2, Size::from((616, 336))),
+            (1, Size::from((616, 336))),
+        ]
+    );
+    assert_eq!(layout.focus().unwrap().0.id, 3);
+}
+
+#[test]
+fn restore_minimized_window_in_place() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::AddWindow {
+            params: TestWindowParams::new(2),
+        },
+        Op::ConsumeWindowIntoColumn,
+        Op::AddWindow {
+            params: TestWindowParams::new(3),
+        },
+        Op::MinimizeWindow { id: Some(1) },
+        Op::FocusColumnLeft,
+        Op::RestoreMinimized { id: Some(1) },
+    ];
+
+    let layout = check_ops(&ops);
+    let ws = layout.active_workspace().unwrap();
+    assert!(!ws.has_minimized_window(&1));
+    assert_eq!(ws.scrolling().window_position(&1), Some((0, 0)));
+    assert_eq!(ws.scrolling().window_position(&2), Some((0, 1)));
+    assert_eq!(ws.scrolling().window_position(&3), Some((1, 0)));
+    assert_eq!(layout.focus().unwrap().0.id, 1);
+}
+
+#[test]
+fn minimized_window_keeps_workspace() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::MinimizeWindow { id: None },
+        // The workspace with the minimized window must not be cleaned up.
+        Op::FocusWorkspaceDown,
+        Op::FocusWorkspaceUp,
+        Op::RestoreMinimized { id: None },
+    ];
+
+    let layout = check_ops(&ops);
+    assert!(!is_minimized(&layout, 1));
+    assert_eq!(layout.focus().unwrap().0.id, 1);
+}
+
+#[test]
+fn overview_search_focuses_best_match() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::AddWindow {
+            params: TestWindowParams::new(2),
+        },
+        Op::AddWindow {
+            params: TestWindowParams::new(3),
+        },
+        Op::ToggleOverview,
+        Op::OverviewSearchPush('2'),
+    ];
+
+    let mut layout = check_ops(&ops);
+    assert_eq!(layout.focus().unwrap().0.id, 2);
+
+    // All windows match "window", so navigation goes through them in order.
+    layout.overview_search_pop();
+    for c in "window".chars() {
+        layout.overview_search_push(c);
+    }
+    layout.overview_search_focus_next(true);
+    assert_eq!(layout.focus().unwrap().0.id, 3);
+
+    // Closing the overview clears the search.
+    layout.toggle_overview();
+    assert_eq!(layout.overview_search(), "");
+}
+
+#[test]
+fn overview_search_keeps_dimming_up_to_date() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::AddWindow {
+            params: TestWindowParams::new(2),
+        },
+        Op::ToggleOverview,
+        Op::OverviewSearchPush('1'),
+        Op::MinimizeWindow { id: Some(2) },
+        Op::AddWindow {
+            params: TestWindowParams::new(3),
+        },
+        Op::Refresh { is_active: true },
+    ];
+
+    let mut layout = check_ops(&ops);
+
+    let dim_target = |layout: &Layout<TestWindow>, id: usize| {
+        layout
+            .workspaces()
+            .flat_map(|(_, _, ws)| ws.tiles())
+            .find(|tile| *tile.window().id() == id)
+            .unwrap()
+            .dim_target()
+    };
+
+    // Windows that open during the search are dimmed too.
+    assert_eq!(dim_target(&layout, 1), 1.);
+    assert_eq!(dim_target(&layout, 3), OVERVIEW_SEARCH_DIM_ALPHA);
+
+    // Windows minimized during the search come back undimmed.
+    layout.toggle_overview();
+    layout.restore_minimized(Some(&2));
+    layout.verify_invariants();
+    assert_eq!(dim_target(&layout, 2), 1.);
+    assert_eq!(dim_target(&layout, 3), 1.);
+}
+
+#[test]
+fn tile_floating_window_to_quarter() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::TileFloatingWindow {
+            id: None,
+            area: FloatingTileArea::BottomRight,
+        },
+        Op::Communicate(1),
+    ];
+
+    let layout = check_ops(&ops);
+    let (_, _, ws) = layout
+        .workspaces()
+        .find(|(_, _, ws)| ws.has_window(&1))
+        .unwrap();
+    assert!(ws.is_floating(&1));
+
+    // The working area is 1280×720 with 16 px gaps.
+    let (tile, pos) = ws.floating().tiles_with_offsets().next().unwrap();
+    assert_eq!(tile.window().requested_size(), Some(Size::from((616, 336))));
+    assert_eq!(pos, Point::from((648., 368.)));
+}
+
+#[test]
+fn interactive_move_floating_window_snaps_to_edges() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::TileFloatingWindow {
+            id: None,
+            area: FloatingTileArea::TopLeft,
+        },
+        Op::Communicate(1),
+        Op::AdvanceAnimations { msec_delta: 1000 },
+        Op::InteractiveMoveBegin {
+            window: 1,
+            output_idx: 1,
+            px: 100.,
+            py: 100.,
+        },
+        Op::InteractiveMoveUpdate {
+            window: 1,
+            dx: -5.,
+            dy: -5.,
+            output_idx: 1,
+            px: 95.,
+            py: 95.,
+        },
+        // The tile ends up 6 px away from the output edges.
+        Op::InteractiveMoveUpdate {
+            window: 1,
+            dx: -5.,
+            dy: -5.,
+            output_idx: 1,
+            px: 90.,
+            py: 90.,
+        },
+        Op::AdvanceAnimations { msec_delta: 1000 },
+        Op::InteractiveMoveEnd { window: 1 },
+    ];
+
+    let layout = check_ops(&ops);
+    let (_, _, ws) = layout
+        .workspaces()
+        .find(|(_, _, ws)| ws.has_window(&1))
+        .unwrap();
+    let (_, pos) = ws.floating().tiles_with_offsets().next().unwrap();
+    assert_eq!(pos, Point::from((0., 0.)));
+}
+
+#[test]
+fn interactive_move_floating_window_to_edge_tiles_it() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_floating: true,
+                ..TestWindowParams::new(1)
+            },
+        },
+        Op::InteractiveMoveBegin {
+            window: 1,
+            output_idx: 1,
+            px: 640.,
+            py: 360.,
+        },
+        Op::InteractiveMoveUpdate {
+            window: 1,
+            dx: -638.,
+            dy: 0.,
+            output_idx: 1,
+            px: 2.,
+            py: 360.,
+        },
+        Op::InteractiveMoveEnd { window: 1 },
+        Op::Communicate(1),
+        Op::AdvanceAnimations { msec_delta: 1000 },
+    ];
+
+    let layout = check_ops(&ops);
+    let (_, _, ws) = layout
+        .workspaces()
+        .find(|(_, _, ws)| ws.has_window(&1))
+        .unwrap();
+    let (tile, pos) = ws.floating().tiles_with_offsets().next().unwrap();
+    assert_eq!(tile.window().requested_size(), Some(Size::from((616, 688))));
+    assert_eq!(pos, Point::from((16., 16.)));
+}
+
+#[test]
+fn stacking_add_parent_brings_up_child() {
+    let ops = [
+        Op::AddOutput(0),
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_floating: true,
+                parent_id: Some(1),
+                ..TestWindowParams::new(0)
+            },
+        },
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_floating: true,
+                ..TestWindowParams::new(1)
+            },
+        },
+    ];
+
+    check_ops(&ops);
+}
+
+#[test]
+fn stacking_add_parent_brings_up_descendants() {
+    let ops = [
+        Op::AddOutput(0),
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_floating: true,
+                parent_id: Some(2),
+                ..TestWindowParams::new(0)
+            },
+        },
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_floating: true,
+                parent_id: Some(0),
+                ..TestWindowParams::new(1)
+            },
+        },
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_floating: true,
                 ..TestWindowParams::new(2)
             },
         },
@@ -3043,6 +3858,175 @@ fn stacking_set_parent_brings_up_child() {
     check_ops(&ops);
 }
 
+fn modal_params(id: usize, parent_id: usize) -> TestWindowParams {
+    TestWindowParams {
+        parent_id: Some(parent_id),
+        is_modal: true,
+        is_floating: true,
+        ..TestWindowParams::new(id)
+    }
+}
+
+fn workspace_id_of(layout: &Layout<TestWindow>, id: usize) -> WorkspaceId {
+    let (_, _, ws) = layout
+        .workspaces()
+        .find(|(_, _, ws)| ws.has_window(&id))
+        .unwrap();
+    ws.id()
+}
+
+#[test]
+fn modal_blocks_parent_and_resolves_root() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::AddWindow {
+            params: modal_params(2, 1),
+        },
+        Op::AddWindow {
+            params: modal_params(3, 2),
+        },
+    ];
+
+    let layout = check_ops(&ops);
+    assert_eq!(layout.blocking_modal(&1), Some(&3));
+    assert_eq!(layout.blocking_modal(&2), Some(&3));
+    assert_eq!(layout.blocking_modal(&3), None);
+
+    assert_eq!(layout.modal_root(Some(&3)), Some(1));
+    assert_eq!(layout.modal_root(Some(&2)), Some(1));
+    assert_eq!(layout.modal_root(Some(&1)), None);
+
+    assert_eq!(
+        layout.redirect_modal_move(Some(&3), ActivateWindow::No),
+        Some((1, ActivateWindow::No))
+    );
+    assert_eq!(
+        layout.redirect_modal_move(Some(&1), ActivateWindow::No),
+        None
+    );
+}
+
+#[test]
+fn modal_follows_parent_to_workspace() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::AddWindow {
+            params: modal_params(2, 1),
+        },
+        Op::MoveWindowToWorkspace {
+            window_id: Some(1),
+            workspace_idx: 1,
+        },
+    ];
+
+    let layout = check_ops(&ops);
+    assert_eq!(workspace_id_of(&layout, 2), workspace_id_of(&layout, 1));
+    assert_eq!(layout.blocking_modal(&1), Some(&2));
+}
+
+#[test]
+fn moving_modal_moves_parent() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::AddWindow {
+            params: modal_params(2, 1),
+        },
+        Op::MoveWindowToWorkspace {
+            window_id: Some(2),
+            workspace_idx: 1,
+        },
+    ];
+
+    let layout = check_ops(&ops);
+    let mon = layout
+        .monitor_for_output(layout.outputs().next().unwrap())
+        .unwrap();
+    assert!(mon.workspaces[1].has_window(&1));
+    assert!(mon.workspaces[1].has_window(&2));
+}
+
+#[test]
+fn modal_follows_parent_to_output() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddOutput(2),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::AddWindow {
+            params: modal_params(2, 1),
+        },
+        Op::MoveWindowToOutput {
+            window_id: Some(1),
+            output_id: 2,
+            target_ws_idx: None,
+        },
+    ];
+
+    let layout = check_ops(&ops);
+    for id in [1, 2] {
+        let (mon, _) = layout.windows().find(|(_, win)| win.0.id == id).unwrap();
+        assert_eq!(mon.unwrap().output_name(), "output2");
+    }
+    assert_eq!(workspace_id_of(&layout, 2), workspace_id_of(&layout, 1));
+}
+
+#[test]
+fn closing_modal_unblocks_parent() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::AddWindow {
+            params: modal_params(2, 1),
+        },
+        Op::CloseWindow(2),
+    ];
+
+    let layout = check_ops(&ops);
+    assert_eq!(layout.blocking_modal(&1), None);
+    assert!(layout.modals.is_empty());
+}
+
+#[test]
+fn set_parent_makes_modal() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddWindow {
+            params: TestWindowParams::new(1),
+        },
+        Op::AddWindow {
+            params: TestWindowParams {
+                is_modal: true,
+                ..TestWindowParams::new(2)
+            },
+        },
+    ];
+
+    let mut layout = check_ops(&ops);
+    assert_eq!(layout.blocking_modal(&1), None);
+
+    let ops = [Op::SetParent {
+        id: 2,
+        new_parent_id: Some(1),
+    }];
+    for op in ops {
+        op.apply(&mut layout);
+        layout.verify_invariants();
+    }
+    assert_eq!(layout.blocking_modal(&1), Some(&2));
+}
+
 #[test]
 fn move_window_to_workspace_with_different_active_output() {
     let ops = [
@@ -3230,6 +4214,229 @@ fn preset_column_width_reset_after_set_width() {
     assert_eq!(win.requested_size().unwrap().w, 500);
 }
 
+#[test]
+fn vertical_scroll_orientation_applies_column_width_to_height() {
+    let ops = [
+        Op::AddOutput(0),
+        Op::AddWindow {
+            params: TestWindowParams::new(0),
+        },
+        Op::SwitchPresetColumnWidth,
+    ];
+
+    let options = Options {
+        preset_column_widths: vec![PresetSize::Fixed(500)],
+        scroll_orientation: ScrollOrientation::Vertical,
+        ..Default::default()
+    };
+    let mut layout = check_ops_with_options(options, &ops);
+
+    let win = layout.windows().next().unwrap().1;
+    assert_eq!(win.requested_size().unwrap().h, 500);
+
+    // Switching back to horizontal applies the same size to the width.
+    let options = Options {
+        preset_column_widths: vec![PresetSize::Fixed(500)],
+        ..Default::default()
+    };
+    layout.update_options(options);
+    let win = layout.windows().next().unwrap().1;
+    assert_eq!(win.requested_size().unwrap().w, 500);
+}
+
+#[track_caller]
+fn vertical_tile_positions(layout: &Layout<TestWindow>) -> Vec<(usize, Rectangle<f64, Logical>)> {
+    let ws = layout.active_workspace().unwrap();
+    let mut tiles: Vec<_> = ws
+        .scrolling()
+        .tiles_with_render_positions()
+        .map(|(tile, pos, _)| (tile.window().0.id, Rectangle::new(pos, tile.tile_size())))
+        .collect();
+    tiles.sort_by_key(|(id, _)| *id);
+    tiles
+}
+
+#[test]
+fn vertical_scroll_orientation_focus_and_move() {
+    let mut ops = vec![Op::AddOutput(1)];
+    for id in 1..=3 {
+        ops.push(Op::AddWindow {
+            params: TestWindowParams::new(id),
+        });
+    }
+    ops.extend((1..=3).map(Op::Communicate));
+    ops.push(Op::AdvanceAnimations { msec_delta: 1000 });
+
+    let options = Options {
+        scroll_orientation: ScrollOrientation::Vertical,
+        ..Default::default()
+    };
+    let mut layout = check_ops_with_options(options, &ops);
+    assert!(layout.active_workspace().unwrap().is_scrolling_vertical());
+
+    // Columns are stacked top-to-bottom.
+    let tiles = vertical_tile_positions(&layout);
+    assert!(tiles.windows(2).all(|w| w[0].1.loc.x == w[1].1.loc.x));
+    assert!(tiles.windows(2).all(|w| w[0].1.loc.y < w[1].1.loc.y));
+
+    // Up and down go across columns, left and right stay within the column.
+    let ops = [
+        Op::FocusWindowUp,
+        Op::FocusWindowUp,
+        Op::FocusColumnRight,
+        Op::FocusWindowDown,
+    ];
+    let mut expected = [2, 1, 1, 2].into_iter();
+    for op in ops {
+        op.apply(&mut layout);
+        layout.verify_invariants();
+        assert_eq!(layout.focus().unwrap().0.id, expected.next().unwrap());
+    }
+
+    // Moving down swaps the focused column with the one below.
+    Op::MoveWindowDown.apply(&mut layout);
+    layout.verify_invariants();
+    let ids: Vec<_> = layout
+        .active_workspace()
+        .unwrap()
+        .scrolling()
+        .tiles()
+        .map(|tile| tile.window().0.id)
+        .collect();
+    assert_eq!(ids, [1, 3, 2]);
+    assert_eq!(layout.focus().unwrap().0.id, 2);
+}
+
+#[test]
+fn vertical_scroll_orientation_centers_focused_column() {
+    let mut ops = vec![Op::AddOutput(1)];
+    for id in 1..=3 {
+        ops.push(Op::AddWindow {
+            params: TestWindowParams::new(id),
+        });
+    }
+    ops.extend((1..=3).map(Op::Communicate));
+    ops.push(Op::FocusWindowUp);
+    ops.push(Op::AdvanceAnimations { msec_delta: 1000 });
+
+    let options = Options {
+        scroll_orientation: ScrollOrientation::Vertical,
+        center_focused_column: CenterFocusedColumn::Always,
+        ..Default::default()
+    };
+    let layout = check_ops_with_options(options, &ops);
+    assert_eq!(layout.focus().unwrap().0.id, 2);
+
+    // The focused column is centered vertically in the 1280×720 output.
+    let (_, tile) = vertical_tile_positions(&layout)[1];
+    assert_eq!(tile.loc.y + tile.size.h / 2., 360.);
+}
+
+#[test]
+fn vertical_scroll_orientation_insert_hint() {
+    let mut ops = vec![Op::AddOutput(1)];
+    for id in 1..=2 {
+        ops.push(Op::AddWindow {
+            params: TestWindowParams::new(id),
+        });
+    }
+    ops.extend((1..=2).map(Op::Communicate));
+    ops.push(Op::AdvanceAnimations { msec_delta: 1000 });
+
+    let options = Options {
+        scroll_orientation: ScrollOrientation::Vertical,
+        ..Default::default()
+    };
+    let layout = check_ops_with_options(options, &ops);
+    let tiles = vertical_tile_positions(&layout);
+    let gap_y = (tiles[0].1.loc.y + tiles[0].1.size.h + tiles[1].1.loc.y) / 2.;
+
+    // A position in the gap between the rows inserts a new column between them.
+    let scrolling = layout.active_workspace().unwrap().scrolling();
+    let position = scrolling.insert_position(Point::from((640., gap_y)));
+    assert_eq!(position, InsertPosition::NewColumn(1));
+
+    // The hint is a horizontal bar across the working area, centered on the gap.
+    let hint = scrolling.insert_hint_area(position).unwrap();
+    assert_eq!(hint.size, Size::from((1280. - 32., 300.)));
+    assert_eq!(hint.loc.x, 16.);
+    assert_eq!(hint.loc.y + hint.size.h / 2., gap_y);
+}
+
+#[test]
+fn scroll_orientation_per_output() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddOutput(2),
+        Op::AddNamedWorkspace {
+            ws_name: 1,
+            output_name: Some(2),
+        },
+    ];
+
+    let options = Options {
+        output_scroll_orientations: vec![(String::from("output2"), ScrollOrientation::Vertical)],
+        ..Default::default()
+    };
+    let mut layout = check_ops_with_options(options, &ops);
+    let is_vertical = |layout: &Layout<_>| {
+        layout
+            .find_workspace_by_name("ws1")
+            .unwrap()
+            .1
+            .is_scrolling_vertical()
+    };
+    assert!(!layout.active_workspace().unwrap().is_scrolling_vertical());
+    assert!(is_vertical(&layout));
+
+    // The workspace follows the orientation of the output it's on.
+    Op::RemoveOutput(2).apply(&mut layout);
+    layout.verify_invariants();
+    assert!(!is_vertical(&layout));
+
+    Op::AddOutput(2).apply(&mut layout);
+    layout.verify_invariants();
+    assert!(is_vertical(&layout));
+}
+
+#[test]
+fn scroll_orientation_per_workspace() {
+    let ops = [
+        Op::AddOutput(1),
+        Op::AddNamedWorkspace {
+            ws_name: 1,
+            output_name: Some(1),
+        },
+    ];
+
+    let options = Options {
+        output_scroll_orientations: vec![(String::from("output1"), ScrollOrientation::Vertical)],
+        workspace_scroll_orientations: vec![(String::from("ws1"), ScrollOrientation::Horizontal)],
+        ..Default::default()
+    };
+    let mut layout = check_ops_with_options(options, &ops);
+    let is_vertical = |layout: &Layout<_>| {
+        layout
+            .find_workspace_by_name("ws1")
+            .map(|(_, ws)| ws.is_scrolling_vertical())
+    };
+
+    // The workspace config takes precedence over the output config.
+    assert!(layout.active_workspace().unwrap().is_scrolling_vertical());
+    assert_eq!(is_vertical(&layout), Some(false));
+
+    // Once unnamed, the workspace goes back to the output orientation.
+    let id = layout.find_workspace_by_name("ws1").unwrap().1.id();
+    Op::UnnameWorkspace { ws_name: 1 }.apply(&mut layout);
+    layout.verify_invariants();
+    let ws = layout
+        .workspaces()
+        .find(|(_, _, ws)| ws.id() == id)
+        .unwrap()
+        .2;
+    assert!(ws.is_scrolling_vertical());
+}
+
 #[test]
 fn disable_tabbed_mode_in_fullscreen() {
     let ops = [
@@ -3534,6 +4741,12 @@ fn unfullscreen_view_offset_not_reset_during_ongoing_gesture() {
     check_ops(&ops);
 }
 
+fn is_minimized(layout: &Layout<TestWindow>, id: usize) -> bool {
+    layout
+        .workspaces()
+        .any(|(_, _, ws)| ws.has_minimized_window(&id))
+}
+
 fn parent_id_causes_loop(layout: &Layout<TestWindow>, id: usize, mut parent_id: usize) -> bool {
     if parent_id == id {
         return true;
@@ -3603,6 +4816,27 @@ fn arbitrary_center_focused_column() -> impl Strategy<Value = CenterFocusedColum
     ]
 }
 
+fn arbitrary_scroll_orientation() -> impl Strategy<Value = ScrollOrientation> {
+    prop_oneof![
+        Just(ScrollOrientation::Horizontal),
+        Just(ScrollOrientation::Vertical),
+        Just(ScrollOrientation::Auto),
+    ]
+}
+
+fn arbitrary_floating_tile_area() -> impl Strategy<Value = FloatingTileArea> {
+    prop_oneof![
+        Just(FloatingTileArea::Left),
+        Just(FloatingTileArea::Right),
+        Just(FloatingTileArea::Top),
+        Just(FloatingTileArea::Bottom),
+        Just(FloatingTileArea::TopLeft),
+        Just(FloatingTileArea::TopRight),
+        Just(FloatingTileArea::BottomLeft),
+        Just(FloatingTileArea::BottomRight),
+    ]
+}
+
 fn arbitrary_tab_indicator_position() -> impl Strategy<Value = TabIndicatorPosition> {
     prop_oneof![
         Just(TabIndicatorPosition::Left),
@@ -3685,6 +4919,7 @@ prop_compose! {
         center_focused_column in arbitrary_center_focused_column(),
         always_center_single_column in any::<bool>(),
         empty_workspace_above_first in any::<bool>(),
+        scroll_orientation in arbitrary_scroll_orientation(),
     ) -> Options {
         Options {
             gaps,
@@ -3692,6 +4927,7 @@ prop_compose! {
             center_focused_column,
             always_center_single_column,
             empty_workspace_above_first,
+            scroll_orientation,
             focus_ring,
             border,
             shadow,
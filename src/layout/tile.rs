@@ -74,6 +74,20 @@ pub struct Tile<W: LayoutElement> {
     /// Currently selected preset height index when this tile is floating.
     pub(super) floating_preset_height_idx: Option<usize>,
 
+    /// Whether this tile is pinned to all workspaces of its output.
+    ///
+    /// Only floating tiles can be pinned. Pinned tiles follow the active workspace of their
+    /// output, and the monitor renders them above all workspaces.
+    pub(super) is_pinned: bool,
+
+    /// Whether this tile is kept above the other floating tiles.
+    ///
+    /// Only floating tiles can be kept above or below, and never both at once.
+    pub(super) keep_above: bool,
+
+    /// Whether this tile is kept below the other floating tiles.
+    pub(super) keep_below: bool,
+
     /// The animation upon opening a window.
     open_animation: Option<OpenAnimation>,
 
@@ -89,6 +103,11 @@ pub struct Tile<W: LayoutElement> {
     /// The animation of the tile's opacity.
     pub(super) alpha_animation: Option<AlphaAnimation>,
 
+    /// The dimming of the tile, for example when it doesn't match the overview search.
+    ///
+    /// This is separate from the alpha animation, which is driven by layout operations.
+    dim_animation: Option<DimAnimation>,
+
     /// Offset during the initial interactive move rubberband.
     pub(super) interactive_move_offset: Point<f64, Logical>,
 
@@ -157,6 +176,12 @@ pub(super) struct AlphaAnimation {
     offscreen: OffscreenBuffer,
 }
 
+#[derive(Debug)]
+struct DimAnimation {
+    anim: Animation,
+    offscreen: OffscreenBuffer,
+}
+
 impl<W: LayoutElement> Tile<W> {
     pub fn new(
         window: W,
@@ -170,6 +195,9 @@ impl<W: LayoutElement> Tile<W> {
         let focus_ring_config = rules.focus_ring.resolve_against(options.focus_ring.into());
         let shadow_config = rules.shadow.resolve_against(options.shadow);
         let is_fullscreen = window.is_fullscreen();
+        let is_pinned = rules.open_pinned == Some(true);
+        let keep_above = rules.open_keep_above == Some(true);
+        let keep_below = !keep_above && rules.open_keep_below == Some(true);
 
         Self {
             window,
@@ -183,11 +211,15 @@ impl<W: LayoutElement> Tile<W> {
             floating_pos: None,
             floating_preset_width_idx: None,
             floating_preset_height_idx: None,
+            is_pinned,
+            keep_above,
+            keep_below,
             open_animation: None,
             resize_animation: None,
             move_x_animation: None,
             move_y_animation: None,
             alpha_animation: None,
+            dim_animation: None,
             interactive_move_offset: Point::from((0., 0.)),
             unmap_snapshot: None,
             rounded_corner_damage: Default::default(),
@@ -329,6 +361,12 @@ impl<W: LayoutElement> Tile<W> {
                 self.alpha_animation = None;
             }
         }
+
+        if let Some(dim) = &mut self.dim_animation {
+            if dim.anim.is_done() && dim.anim.to() == 1. {
+                self.dim_animation = None;
+            }
+        }
     }
 
     pub fn are_animations_ongoing(&self) -> bool {
@@ -344,6 +382,10 @@ impl<W: LayoutElement> Tile<W> {
                 .alpha_animation
                 .as_ref()
                 .is_some_and(|alpha| !alpha.anim.is_done())
+            || self
+                .dim_animation
+                .as_ref()
+                .is_some_and(|dim| !dim.anim.is_done())
     }
 
     pub fn update_render_elements(&mut self, is_active: bool, view_rect: Rectangle<f64, Logical>) {
@@ -517,6 +559,36 @@ impl<W: LayoutElement> Tile<W> {
         }
     }
 
+    /// Animates the tile opacity towards `to`, on top of any alpha animation.
+    ///
+    /// The dimming persists until animated back to 1.
+    pub fn animate_dim(&mut self, to: f64, config: niri_config::Animation) {
+        let to = to.clamp(0., 1.);
+        if self.dim_target() == to {
+            return;
+        }
+
+        let (current, offscreen) = if let Some(dim) = self.dim_animation.take() {
+            (dim.anim.clamped_value(), dim.offscreen)
+        } else {
+            (1., OffscreenBuffer::default())
+        };
+
+        self.dim_animation = Some(DimAnimation {
+            anim: Animation::new(self.clock.clone(), current, to, 0., config),
+            offscreen,
+        });
+    }
+
+    pub fn dim_target(&self) -> f64 {
+        self.dim_animation.as_ref().map_or(1., |dim| dim.anim.to())
+    }
+
+    /// Drops any dimming right away.
+    pub fn stop_dim_animation(&mut self) {
+        self.dim_animation = None;
+    }
+
     pub fn hold_alpha_animation_after_done(&mut self) {
         if let Some(alpha) = &mut self.alpha_animation {
             alpha.hold_after_done = true;
@@ -982,6 +1054,33 @@ impl<W: LayoutElement> Tile<W> {
                     // Otherwise, render it normally.
                     LayoutElementRenderElement::Wayland(elem).into()
                 }
+                LayoutElementRenderElement::ColorTransform(elem) => {
+                    // The clipping shader can convert colors too, so use it in place of the
+                    // color transform element.
+                    if clip_to_geometry {
+                        if let Some(shader) = clip_shader.clone() {
+                            if ClippedSurfaceRenderElement::will_clip(
+                                elem.inner(),
+                                scale,
+                                geo,
+                                radius,
+                            ) {
+                                let description = elem.src();
+                                return ClippedSurfaceRenderElement::new(
+                                    elem.into_inner(),
+                                    scale,
+                                    geo,
+                                    shader.clone(),
+                                    radius,
+                                )
+                                .with_color_description(description)
+                                .into();
+                            }
+                        }
+                    }
+
+                    LayoutElementRenderElement::ColorTransform(elem).into()
+                }
                 LayoutElementRenderElement::SolidColor(elem) => {
                     // In this branch we're rendering a blocked-out window with a solid
                     // color. We need to render it with a rounded corner shader even if
@@ -1062,7 +1161,18 @@ impl<W: LayoutElement> Tile<W> {
         let tile_alpha = self
             .alpha_animation
             .as_ref()
-            .map_or(1., |alpha| alpha.anim.clamped_value()) as f32;
+            .map_or(1., |alpha| alpha.anim.clamped_value());
+        let dim_alpha = self
+            .dim_animation
+            .as_ref()
+            .map_or(1., |dim| dim.anim.clamped_value());
+        let tile_alpha = (tile_alpha * dim_alpha) as f32;
+
+        let offscreen = match (&self.alpha_animation, &self.dim_animation) {
+            (Some(alpha), _) => Some(&alpha.offscreen),
+            (None, Some(dim)) => Some(&dim.offscreen),
+            (None, None) => None,
+        };
 
         let mut open_anim_elem = None;
         let mut alpha_anim_elem = None;
@@ -1090,11 +1200,11 @@ impl<W: LayoutElement> Tile<W> {
                     warn!("error rendering window opening animation: {err:?}");
                 }
             }
-        } else if let Some(alpha) = &self.alpha_animation {
+        } else if let Some(offscreen) = offscreen {
             let renderer = renderer.as_gles_renderer();
             let elements = self.render_inner(renderer, Point::from((0., 0.)), focus_ring, target);
             let elements = elements.collect::<Vec<TileRenderElement<_>>>();
-            match alpha.offscreen.render(renderer, scale, &elements) {
+            match offscreen.render(renderer, scale, &elements) {
                 Ok((elem, _sync, data)) => {
                     let offset = elem.offset();
                     let elem = elem.with_alpha(tile_alpha).with_offset(location + offset);
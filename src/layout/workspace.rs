@@ -3,9 +3,10 @@ use std::rc::Rc;
 use std::time::Duration;
 
 use niri_config::{
-    CenterFocusedColumn, CornerRadius, OutputName, PresetSize, Workspace as WorkspaceConfig,
+    CenterFocusedColumn, CornerRadius, OutputName, PresetSize, ScrollOrientation,
+    Workspace as WorkspaceConfig,
 };
-use niri_ipc::{ColumnDisplay, PositionChange, SizeChange};
+use niri_ipc::{ColumnDisplay, FloatingTileArea, LayoutKind, PositionChange, SizeChange};
 use smithay::backend::renderer::gles::GlesRenderer;
 use smithay::desktop::{layer_map_for_output, Window};
 use smithay::output::Output;
@@ -49,6 +50,9 @@ pub struct Workspace<W: LayoutElement> {
     /// Whether the floating layout is active instead of the scrolling layout.
     floating_is_active: FloatingActive,
 
+    /// Windows minimized on this workspace, the most recently minimized last.
+    minimized: Vec<MinimizedTile<W>>,
+
     /// The original output of this workspace.
     ///
     /// Most of the time this will be the workspace's current output, however, after an output
@@ -170,6 +174,29 @@ enum FloatingActive {
     Yes,
 }
 
+/// Tile that was minimized, along with where it should be restored.
+#[derive(Debug)]
+struct MinimizedTile<W: LayoutElement> {
+    tile: Tile<W>,
+    /// Width of the column the tile was in.
+    width: ColumnWidth,
+    /// Whether the column the tile was in was full-width.
+    is_full_width: bool,
+    /// Where the tile was before minimizing.
+    position: MinimizedPosition,
+}
+
+/// Position of a tile before it was minimized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MinimizedPosition {
+    /// The tile was alone in the scrolling column at this index.
+    Column(usize),
+    /// The tile was in a scrolling column together with other tiles.
+    InColumn { column_idx: usize, tile_idx: usize },
+    /// The tile was floating.
+    Floating,
+}
+
 /// Where to put a newly added window.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum WorkspaceAddWindowTarget<'a, W: LayoutElement> {
@@ -213,19 +240,27 @@ impl<W: LayoutElement> Workspace<W> {
             .unwrap_or(OutputId::new(&output));
 
         let scale = output.current_scale();
-        let options =
-            Rc::new(Options::clone(&base_options).adjusted_for_scale(scale.fractional_scale()));
+        let name = config.as_ref().map(|c| c.name.0.as_str());
+        let options = Rc::new(compute_options(
+            &base_options,
+            scale.fractional_scale(),
+            name,
+            Some(&output),
+        ));
 
         let view_size = output_size(&output);
         let working_area = compute_working_area(&output);
 
-        let scrolling = ScrollingSpace::new(
+        let mut scrolling = ScrollingSpace::new(
             view_size,
             working_area,
             scale.fractional_scale(),
             clock.clone(),
             options.clone(),
         );
+        if let Some(kind) = config.as_ref().and_then(|c| c.layout_kind) {
+            scrolling.set_layout_kind(kind);
+        }
 
         let floating = FloatingSpace::new(
             view_size,
@@ -242,6 +277,7 @@ impl<W: LayoutElement> Workspace<W> {
             scrolling,
             floating,
             floating_is_active: FloatingActive::No,
+            minimized: Vec::new(),
             original_output,
             scale,
             transform: output.current_transform(),
@@ -270,19 +306,27 @@ impl<W: LayoutElement> Workspace<W> {
         );
 
         let scale = smithay::output::Scale::Integer(1);
-        let options =
-            Rc::new(Options::clone(&base_options).adjusted_for_scale(scale.fractional_scale()));
+        let name = config.as_ref().map(|c| c.name.0.as_str());
+        let options = Rc::new(compute_options(
+            &base_options,
+            scale.fractional_scale(),
+            name,
+            None,
+        ));
 
         let view_size = Size::from((1280., 720.));
         let working_area = Rectangle::from_size(Size::from((1280., 720.)));
 
-        let scrolling = ScrollingSpace::new(
+        let mut scrolling = ScrollingSpace::new(
             view_size,
             working_area,
             scale.fractional_scale(),
             clock.clone(),
             options.clone(),
         );
+        if let Some(kind) = config.as_ref().and_then(|c| c.layout_kind) {
+            scrolling.set_layout_kind(kind);
+        }
 
         let floating = FloatingSpace::new(
             view_size,
@@ -299,6 +343,7 @@ impl<W: LayoutElement> Workspace<W> {
             scrolling,
             floating,
             floating_is_active: FloatingActive::No,
+            minimized: Vec::new(),
             output: None,
             scale,
             transform: Transform::Normal,
@@ -326,8 +371,29 @@ impl<W: LayoutElement> Workspace<W> {
         self.name.as_ref()
     }
 
+    pub fn layout_kind(&self) -> LayoutKind {
+        self.scrolling.layout_kind()
+    }
+
+    pub fn set_layout_kind(&mut self, kind: LayoutKind) {
+        self.scrolling.set_layout_kind(kind);
+    }
+
     pub fn unname(&mut self) {
         self.name = None;
+        self.refresh_scroll_orientation();
+    }
+
+    /// Recomputes the options if the name or the output changed the scroll orientation.
+    pub(super) fn refresh_scroll_orientation(&mut self) {
+        let orientation = scroll_orientation(
+            &self.base_options,
+            self.name.as_deref(),
+            self.output.as_ref(),
+        );
+        if orientation != self.options.scroll_orientation {
+            self.update_config(self.base_options.clone());
+        }
     }
 
     pub fn has_windows_or_name(&self) -> bool {
@@ -370,7 +436,12 @@ impl<W: LayoutElement> Workspace<W> {
 
     pub fn update_config(&mut self, base_options: Rc<Options>) {
         let scale = self.scale.fractional_scale();
-        let options = Rc::new(Options::clone(&base_options).adjusted_for_scale(scale));
+        let options = Rc::new(compute_options(
+            &base_options,
+            scale,
+            self.name.as_deref(),
+            self.output.as_ref(),
+        ));
 
         self.scrolling.update_config(
             self.view_size,
@@ -408,6 +479,18 @@ impl<W: LayoutElement> Workspace<W> {
         self.tiles_mut().map(Tile::window_mut)
     }
 
+    pub fn minimized_windows(&self) -> impl Iterator<Item = &W> + '_ {
+        self.minimized
+            .iter()
+            .map(|minimized| minimized.tile.window())
+    }
+
+    pub fn minimized_windows_mut(&mut self) -> impl Iterator<Item = &mut W> + '_ {
+        self.minimized
+            .iter_mut()
+            .map(|minimized| minimized.tile.window_mut())
+    }
+
     pub fn tiles(&self) -> impl Iterator<Item = &Tile<W>> + '_ {
         let scrolling = self.scrolling.tiles();
         let floating = self.floating.tiles();
@@ -468,6 +551,7 @@ impl<W: LayoutElement> Workspace<W> {
             }
 
             self.update_output_size();
+            self.refresh_scroll_orientation();
 
             for win in self.windows() {
                 self.enter_output_for_window(win);
@@ -567,6 +651,8 @@ impl<W: LayoutElement> Workspace<W> {
     ) {
         self.enter_output_for_window(tile.window());
         tile.unfullscreen_to_floating = is_floating;
+        // Pinned tiles live on the monitor rather than on a workspace.
+        tile.is_pinned = false;
 
         match target {
             WorkspaceAddWindowTarget::Auto => {
@@ -692,8 +778,19 @@ impl<W: LayoutElement> Workspace<W> {
     }
 
     pub fn remove_tile(&mut self, id: &W::Id, transaction: Transaction) -> RemovedTile<W> {
+        // Minimized tiles have already left the output.
+        if let Some(idx) = self.minimized_idx(id) {
+            let minimized = self.minimized.remove(idx);
+            return RemovedTile {
+                tile: minimized.tile,
+                width: minimized.width,
+                is_full_width: minimized.is_full_width,
+                is_floating: minimized.position == MinimizedPosition::Floating,
+            };
+        }
+
         let mut from_floating = false;
-        let removed = if self.floating.has_window(id) {
+        let mut removed = if self.floating.has_window(id) {
             from_floating = true;
             self.floating.remove_tile(id)
         } else {
@@ -704,6 +801,9 @@ impl<W: LayoutElement> Workspace<W> {
             removed.tile.window().output_leave(output);
         }
 
+        // The overview search only keeps dimming up to date for tiles in the layout.
+        removed.tile.stop_dim_animation();
+
         self.update_focus_floating_tiling_after_removing(from_floating);
 
         removed
@@ -711,7 +811,7 @@ impl<W: LayoutElement> Workspace<W> {
 
     pub fn remove_active_tile(&mut self, transaction: Transaction) -> Option<RemovedTile<W>> {
         let from_floating = self.floating_is_active.get();
-        let removed = if from_floating {
+        let mut removed = if from_floating {
             self.floating.remove_active_tile()?
         } else {
             self.scrolling.remove_active_tile(transaction)?
@@ -721,6 +821,8 @@ impl<W: LayoutElement> Workspace<W> {
             removed.tile.window().output_leave(output);
         }
 
+        removed.tile.stop_dim_animation();
+
         self.update_focus_floating_tiling_after_removing(from_floating);
 
         Some(removed)
@@ -838,9 +940,13 @@ impl<W: LayoutElement> Workspace<W> {
         });
     }
 
+    // In a vertical scrolling layout, columns are rows stacked top-to-bottom, so the directional
+    // focus and move actions swap their axes to keep following the screen directions.
     pub fn focus_left(&mut self) -> bool {
         if self.floating_is_active.get() {
             self.floating.focus_left()
+        } else if self.scrolling.is_vertical() {
+            self.scrolling.focus_up()
         } else {
             self.scrolling.focus_left()
         }
@@ -849,6 +955,8 @@ impl<W: LayoutElement> Workspace<W> {
     pub fn focus_right(&mut self) -> bool {
         if self.floating_is_active.get() {
             self.floating.focus_right()
+        } else if self.scrolling.is_vertical() {
+            self.scrolling.focus_down()
         } else {
             self.scrolling.focus_right()
         }
@@ -899,6 +1007,8 @@ impl<W: LayoutElement> Workspace<W> {
     pub fn focus_down(&mut self) -> bool {
         if self.floating_is_active.get() {
             self.floating.focus_down()
+        } else if self.scrolling.is_vertical() {
+            self.scrolling.focus_right()
         } else {
             self.scrolling.focus_down()
         }
@@ -907,6 +1017,8 @@ impl<W: LayoutElement> Workspace<W> {
     pub fn focus_up(&mut self) -> bool {
         if self.floating_is_active.get() {
             self.floating.focus_up()
+        } else if self.scrolling.is_vertical() {
+            self.scrolling.focus_left()
         } else {
             self.scrolling.focus_up()
         }
@@ -976,6 +1088,8 @@ impl<W: LayoutElement> Workspace<W> {
         if self.floating_is_active.get() {
             self.floating.move_left();
             true
+        } else if self.scrolling.is_vertical() {
+            self.scrolling.move_up()
         } else {
             self.scrolling.move_left()
         }
@@ -985,6 +1099,8 @@ impl<W: LayoutElement> Workspace<W> {
         if self.floating_is_active.get() {
             self.floating.move_right();
             true
+        } else if self.scrolling.is_vertical() {
+            self.scrolling.move_down()
         } else {
             self.scrolling.move_right()
         }
@@ -1015,6 +1131,8 @@ impl<W: LayoutElement> Workspace<W> {
         if self.floating_is_active.get() {
             self.floating.move_down();
             true
+        } else if self.scrolling.is_vertical() {
+            self.scrolling.move_right()
         } else {
             self.scrolling.move_down()
         }
@@ -1024,6 +1142,8 @@ impl<W: LayoutElement> Workspace<W> {
         if self.floating_is_active.get() {
             self.floating.move_up();
             true
+        } else if self.scrolling.is_vertical() {
+            self.scrolling.move_left()
         } else {
             self.scrolling.move_up()
         }
@@ -1309,6 +1429,146 @@ impl<W: LayoutElement> Workspace<W> {
         self.toggle_window_floating(id);
     }
 
+    pub fn toggle_window_keep_above(&mut self, id: Option<&W::Id>) {
+        let Some(id) = id
+            .cloned()
+            .or_else(|| self.active_window().map(|win| win.id().clone()))
+        else {
+            return;
+        };
+
+        if self.floating.has_window(&id) {
+            let keep_above = self.floating.is_window_kept_above(&id);
+            self.floating.set_window_keep_above(&id, !keep_above);
+        } else {
+            // Only floating windows can be kept above.
+            self.toggle_window_floating(Some(&id));
+            self.floating.set_window_keep_above(&id, true);
+        }
+    }
+
+    pub fn toggle_window_keep_below(&mut self, id: Option<&W::Id>) {
+        let Some(id) = id
+            .cloned()
+            .or_else(|| self.active_window().map(|win| win.id().clone()))
+        else {
+            return;
+        };
+
+        if self.floating.has_window(&id) {
+            let keep_below = self.floating.is_window_kept_below(&id);
+            self.floating.set_window_keep_below(&id, !keep_below);
+        } else {
+            // Only floating windows can be kept below.
+            self.toggle_window_floating(Some(&id));
+            self.floating.set_window_keep_below(&id, true);
+        }
+    }
+
+    pub fn tile_floating_window(&mut self, id: Option<&W::Id>, area: FloatingTileArea) {
+        let Some(id) = id
+            .cloned()
+            .or_else(|| self.active_window().map(|win| win.id().clone()))
+        else {
+            return;
+        };
+
+        // Only floating windows can be tiled to an area.
+        if !self.floating.has_window(&id) {
+            self.toggle_window_floating(Some(&id));
+        }
+        self.floating.tile_window_to_area(Some(&id), area, true);
+    }
+
+    pub fn minimize_window(&mut self, id: Option<&W::Id>) {
+        let Some(id) = id
+            .cloned()
+            .or_else(|| self.active_window().map(|win| win.id().clone()))
+        else {
+            return;
+        };
+
+        let position = if self.floating.has_window(&id) {
+            MinimizedPosition::Floating
+        } else {
+            let (column_idx, tile_idx) = self.scrolling.window_position(&id).unwrap();
+            if self.scrolling.column_tile_count(column_idx) == 1 {
+                MinimizedPosition::Column(column_idx)
+            } else {
+                MinimizedPosition::InColumn {
+                    column_idx,
+                    tile_idx,
+                }
+            }
+        };
+
+        let mut removed = self.remove_tile(&id, Transaction::new());
+        removed.tile.stop_move_animations();
+        removed.tile.window_mut().set_minimized(true);
+        self.minimized.push(MinimizedTile {
+            tile: removed.tile,
+            width: removed.width,
+            is_full_width: removed.is_full_width,
+            position,
+        });
+    }
+
+    /// Restores a minimized window, or the most recently minimized one if `id` is `None`.
+    ///
+    /// Returns `false` if there was no such minimized window.
+    pub fn restore_minimized(&mut self, id: Option<&W::Id>) -> bool {
+        let idx = match id {
+            Some(id) => self.minimized_idx(id),
+            None => self.minimized.len().checked_sub(1),
+        };
+        let Some(idx) = idx else {
+            return false;
+        };
+
+        let MinimizedTile {
+            mut tile,
+            width,
+            is_full_width,
+            position,
+        } = self.minimized.remove(idx);
+        tile.window_mut().set_minimized(false);
+
+        let column_count = self.scrolling.column_count();
+        match position {
+            MinimizedPosition::InColumn {
+                column_idx,
+                tile_idx,
+            } if column_idx < column_count => {
+                let tile_idx = tile_idx.min(self.scrolling.column_tile_count(column_idx));
+                self.add_tile_to_column(column_idx, Some(tile_idx), tile, true);
+            }
+            MinimizedPosition::Column(column_idx)
+            | MinimizedPosition::InColumn { column_idx, .. } => {
+                let column_idx = column_idx.min(column_count);
+                self.add_tile(
+                    tile,
+                    WorkspaceAddWindowTarget::NewColumnAt(column_idx),
+                    ActivateWindow::Yes,
+                    width,
+                    is_full_width,
+                    false,
+                );
+            }
+            MinimizedPosition::Floating => {
+                self.add_tile(
+                    tile,
+                    WorkspaceAddWindowTarget::Auto,
+                    ActivateWindow::Yes,
+                    width,
+                    is_full_width,
+                    true,
+                );
+            }
+        }
+
+        true
+    }
+
     pub fn focus_floating(&mut self) {
         if !self.floating_is_active.get() {
             self.switch_focus_floating_tiling();
@@ -1391,19 +1651,38 @@ impl<W: LayoutElement> Workspace<W> {
     }
 
     pub fn has_windows(&self) -> bool {
-        self.windows().next().is_some()
+        self.windows().next().is_some() || !self.minimized.is_empty()
     }
 
     pub fn has_window(&self, window: &W::Id) -> bool {
         self.windows().any(|win| win.id() == window)
     }
 
+    pub fn has_minimized_window(&self, window: &W::Id) -> bool {
+        self.minimized_idx(window).is_some()
+    }
+
+    fn minimized_idx(&self, window: &W::Id) -> Option<usize> {
+        self.minimized
+            .iter()
+            .position(|minimized| minimized.tile.window().id() == window)
+    }
+
     pub fn find_wl_surface(&self, wl_surface: &WlSurface) -> Option<&W> {
-        self.windows().find(|win| win.is_wl_surface(wl_surface))
+        self.windows()
+            .chain(self.minimized_windows())
+            .find(|win| win.is_wl_surface(wl_surface))
     }
 
     pub fn find_wl_surface_mut(&mut self, wl_surface: &WlSurface) -> Option<&mut W> {
-        self.windows_mut().find(|win| win.is_wl_surface(wl_surface))
+        let minimized = self.minimized.iter_mut().map(|m| m.tile.window_mut());
+        let scrolling = self.scrolling.tiles_mut();
+        let floating = self.floating.tiles_mut();
+        scrolling
+            .chain(floating)
+            .map(Tile::window_mut)
+            .chain(minimized)
+            .find(|win| win.is_wl_surface(wl_surface))
     }
 
     pub fn tiles_with_render_positions(
@@ -1567,26 +1846,7 @@ impl<W: LayoutElement> Workspace<W> {
                     return None;
                 }
 
-                let pos_within_tile = pos - tile_pos;
-
-                if tile.hit(pos_within_tile).is_some() {
-                    let size = tile.tile_size().to_f64();
-
-                    let mut edges = ResizeEdge::empty();
-                    if pos_within_tile.x < size.w / 3. {
-                        edges |= ResizeEdge::LEFT;
-                    } else if 2. * size.w / 3. < pos_within_tile.x {
-                        edges |= ResizeEdge::RIGHT;
-                    }
-                    if pos_within_tile.y < size.h / 3. {
-                        edges |= ResizeEdge::TOP;
-                    } else if 2. * size.h / 3. < pos_within_tile.y {
-                        edges |= ResizeEdge::BOTTOM;
-                    }
-                    return Some(edges);
-                }
-
-                None
+                resize_edges_within_tile(tile, pos - tile_pos)
             })
     }
 
@@ -1595,6 +1855,15 @@ impl<W: LayoutElement> Workspace<W> {
     }
 
     pub fn update_window(&mut self, window: &W::Id, serial: Option<Serial>) {
+        if let Some(idx) = self.minimized_idx(window) {
+            let tile = &mut self.minimized[idx].tile;
+            if let Some(serial) = serial {
+                tile.window_mut().on_commit(serial);
+            }
+            tile.update_window();
+            return;
+        }
+
         if !self.floating.update_window(window, serial) {
             self.scrolling.update_window(window, serial);
         }
@@ -1605,6 +1874,14 @@ impl<W: LayoutElement> Workspace<W> {
             .refresh(is_active && !self.floating_is_active.get(), is_focused);
         self.floating
             .refresh(is_active && self.floating_is_active.get(), is_focused);
+
+        for minimized in &mut self.minimized {
+            let win = minimized.tile.window_mut();
+            win.set_minimized(true);
+            win.set_activated(false);
+            win.send_pending_configure();
+            win.refresh();
+        }
     }
 
     pub fn scroll_amount_to_activate(&self, window: &W::Id) -> f64 {
@@ -1615,11 +1892,18 @@ impl<W: LayoutElement> Workspace<W> {
         self.scrolling.scroll_amount_to_activate(window)
     }
 
+    pub fn is_scrolling_vertical(&self) -> bool {
+        self.scrolling.is_vertical()
+    }
+
     pub fn is_urgent(&self) -> bool {
         self.windows().any(|win| win.is_urgent())
     }
 
     pub fn activate_window(&mut self, window: &W::Id) -> bool {
+        // Activating a minimized window restores it.
+        self.restore_minimized(Some(window));
+
         if self.floating.activate_window(window) {
             self.floating_is_active = FloatingActive::Yes;
             true
@@ -1651,11 +1935,33 @@ impl<W: LayoutElement> Workspace<W> {
         self.scrolling.insert_position(pos)
     }
 
+    pub(super) fn floating_insert_position(
+        &self,
+        pos: Point<f64, Logical>,
+        allow_edge_tiling: bool,
+    ) -> InsertPosition {
+        let area = allow_edge_tiling
+            .then(|| self.floating.edge_tile_area_under(pos))
+            .flatten();
+        area.map_or(InsertPosition::Floating, InsertPosition::FloatingEdgeTile)
+    }
+
     pub(super) fn insert_hint_area(
         &self,
         position: InsertPosition,
     ) -> Option<Rectangle<f64, Logical>> {
-        self.scrolling.insert_hint_area(position)
+        match position {
+            InsertPosition::FloatingEdgeTile(area) => Some(self.floating.tile_area_rect(area)),
+            _ => self.scrolling.insert_hint_area(position),
+        }
+    }
+
+    pub fn floating_snap_tile_pos(
+        &self,
+        pos: Point<f64, Logical>,
+        size: Size<f64, Logical>,
+    ) -> Point<f64, Logical> {
+        self.floating.snap_tile_pos(pos, size)
     }
 
     pub fn view_offset_gesture_begin(&mut self, is_touchpad: bool) {
@@ -1685,8 +1991,11 @@ impl<W: LayoutElement> Workspace<W> {
         let trigger_width = config.trigger_width.0;
 
         // This working area intentionally does not include extra struts from Options.
-        let x = pos.x - self.working_area.loc.x;
-        let width = self.working_area.size.w;
+        let (x, width) = if self.scrolling.is_vertical() {
+            (pos.y - self.working_area.loc.y, self.working_area.size.h)
+        } else {
+            (pos.x - self.working_area.loc.x, self.working_area.size.w)
+        };
 
         let x = x.clamp(0., width);
         let trigger_width = trigger_width.clamp(0., width / 2.);
@@ -1795,6 +2104,18 @@ impl<W: LayoutElement> Workspace<W> {
         assert!(Rc::ptr_eq(&self.options, self.floating.options()));
         self.floating.verify_invariants();
 
+        for tile in self.floating.tiles() {
+            assert!(!tile.is_pinned, "pinned tiles must be on the monitor");
+        }
+
+        for minimized in &self.minimized {
+            let id = minimized.tile.window().id();
+            assert!(
+                !self.has_window(id),
+                "minimized window must not remain in the layout"
+            );
+        }
+
         if self.floating.is_empty() {
             assert!(
                 !self.floating_is_active.get(),
@@ -1833,6 +2154,71 @@ impl<W: LayoutElement> Workspace<W> {
     }
 }
 
+/// Computes the options for a workspace with its per-workspace and per-output overrides.
+/// Returns the edges to resize a tile by when grabbing it at this position.
+///
+/// Returns `None` if the position doesn't hit the tile.
+pub(super) fn resize_edges_within_tile<W: LayoutElement>(
+    tile: &Tile<W>,
+    pos_within_tile: Point<f64, Logical>,
+) -> Option<ResizeEdge> {
+    tile.hit(pos_within_tile)?;
+
+    let size = tile.tile_size().to_f64();
+
+    let mut edges = ResizeEdge::empty();
+    if pos_within_tile.x < size.w / 3. {
+        edges |= ResizeEdge::LEFT;
+    } else if 2. * size.w / 3. < pos_within_tile.x {
+        edges |= ResizeEdge::RIGHT;
+    }
+    if pos_within_tile.y < size.h / 3. {
+        edges |= ResizeEdge::TOP;
+    } else if 2. * size.h / 3. < pos_within_tile.y {
+        edges |= ResizeEdge::BOTTOM;
+    }
+    Some(edges)
+}
+
+fn compute_options(
+    base_options: &Options,
+    scale: f64,
+    name: Option<&str>,
+    output: Option<&Output>,
+) -> Options {
+    let mut options = Options::clone(base_options).adjusted_for_scale(scale);
+    options.scroll_orientation = scroll_orientation(base_options, name, output);
+    options
+}
+
+/// Returns the scroll orientation of a workspace.
+///
+/// The named workspace config takes precedence over the output config, which takes precedence
+/// over the layout config.
+fn scroll_orientation(
+    options: &Options,
+    name: Option<&str>,
+    output: Option<&Output>,
+) -> ScrollOrientation {
+    let for_workspace = name.and_then(|name| {
+        options
+            .workspace_scroll_orientations
+            .iter()
+            .find(|(ws_name, _)| ws_name.eq_ignore_ascii_case(name))
+    });
+    let for_output = || {
+        let output_name = output?.user_data().get::<OutputName>()?;
+        options
+            .output_scroll_orientations
+            .iter()
+            .find(|(name, _)| output_name.matches(name))
+    };
+
+    for_workspace
+        .or_else(for_output)
+        .map_or(options.scroll_orientation, |(_, orientation)| *orientation)
+}
+
 pub(super) fn compute_working_area(output: &Output) -> Rectangle<f64, Logical> {
     layer_map_for_output(output).non_exclusive_zone().to_f64()
 }
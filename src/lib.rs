@@ -9,12 +9,15 @@ pub mod cursor;
 pub mod dbus;
 pub mod frame_clock;
 pub mod handlers;
+pub mod idle;
 pub mod input;
 pub mod ipc;
 pub mod layer;
 pub mod layout;
+pub mod night_light;
 pub mod niri;
 pub mod protocols;
+pub mod recording;
 pub mod render_helpers;
 pub mod rubber_band;
 pub mod ui;
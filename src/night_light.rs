@@ -0,0 +1,407 @@
+//! Built-in night light.
+//!
+//! Shifts the color temperature of all outputs through the same gamma path that
+//! wlr-gamma-control clients use. Outputs with an active gamma control client are left alone.
+
+use std::cell::RefCell;
+use std::f64::consts::PI;
+use std::mem::MaybeUninit;
+use std::rc::Rc;
+
+use niri_config::Config;
+
+use crate::animation::{Animation, Clock};
+
+/// Color temperature that corresponds to the identity gamma ramp.
+pub const NEUTRAL_TEMPERATURE: f64 = 6500.;
+
+const MIN_TEMPERATURE: u32 = 1000;
+const MAX_TEMPERATURE: u32 = 10000;
+
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+pub struct NightLight {
+    /// Whether the schedule says that the night light should be active right now.
+    scheduled: bool,
+    /// Manual override of the schedule, reset at the next scheduled change.
+    forced: Option<bool>,
+    /// Manual override of the configured temperature.
+    temperature_override: Option<u32>,
+    /// Transition to the target temperature.
+    animation: Option<Animation>,
+    /// Current temperature when not animating.
+    current: f64,
+
+    clock: Clock,
+    config: Rc<RefCell<Config>>,
+}
+
+/// Night schedule for the current day, in minutes since local midnight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Schedule {
+    Always,
+    Never,
+    Between { start: i32, end: i32 },
+}
+
+/// Current local time.
+#[derive(Debug, Clone, Copy)]
+struct LocalTime {
+    /// Minutes since local midnight.
+    minute_of_day: i32,
+    /// Day of the year, starting from 1.
+    day_of_year: i32,
+    /// Offset of the local time zone from UTC in minutes.
+    utc_offset: i32,
+}
+
+impl NightLight {
+    pub fn new(clock: Clock, config: Rc<RefCell<Config>>) -> Self {
+        let mut rv = Self {
+            scheduled: false,
+            forced: None,
+            temperature_override: None,
+            animation: None,
+            current: NEUTRAL_TEMPERATURE,
+            clock,
+            config,
+        };
+
+        // Start right at the target temperature rather than transitioning to it.
+        rv.scheduled = rv.compute_scheduled();
+        rv.current = rv.target();
+        rv
+    }
+
+    fn compute_scheduled(&self) -> bool {
+        let config = self.config.borrow();
+        let config = &config.night_light;
+        if !config.on {
+            return false;
+        }
+
+        let Some(now) = LocalTime::now() else {
+            return true;
+        };
+
+        let schedule = match (config.latitude, config.longitude, config.from, config.to) {
+            (Some(latitude), Some(longitude), _, _) => {
+                sun_schedule(now.day_of_year, latitude.0, longitude.0, now.utc_offset)
+            }
+            (_, _, Some(from), Some(to)) => Schedule::Between {
+                start: from.minutes() as i32,
+                end: to.minutes() as i32,
+            },
+            _ => Schedule::Always,
+        };
+
+        schedule.contains(now.minute_of_day)
+    }
+
+    /// Returns whether the night light is currently active.
+    pub fn is_active(&self) -> bool {
+        self.forced.unwrap_or(self.scheduled)
+    }
+
+    fn target(&self) -> f64 {
+        if !self.is_active() {
+            return NEUTRAL_TEMPERATURE;
+        }
+
+        let temperature = self
+            .temperature_override
+            .unwrap_or(self.config.borrow().night_light.temperature);
+        f64::from(temperature.clamp(MIN_TEMPERATURE, MAX_TEMPERATURE))
+    }
+
+    /// Returns the current color temperature.
+    pub fn temperature(&self) -> f64 {
+        match &self.animation {
+            Some(anim) => anim.value(),
+            None => self.current,
+        }
+    }
+
+    /// Starts a transition to the target temperature, if it changed.
+    ///
+    /// Returns `true` if the temperature will change.
+    fn start_transition(&mut self) -> bool {
+        let target = self.target();
+        let current_target = self
+            .animation
+            .as_ref()
+            .map_or(self.current, |anim| anim.to());
+        if target == current_target {
+            return false;
+        }
+
+        let anim_config = self.config.borrow().animations.night_light.0;
+        let from = self.temperature();
+        self.animation = Some(Animation::new(
+            self.clock.clone(),
+            from,
+            target,
+            0.,
+            anim_config,
+        ));
+        true
+    }
+
+    /// Re-evaluates the schedule.
+    ///
+    /// Returns `true` if the temperature will change.
+    pub fn update_schedule(&mut self) -> bool {
+        let scheduled = self.compute_scheduled();
+        if self.scheduled != scheduled {
+            self.scheduled = scheduled;
+            // The schedule changed, so drop the manual override.
+            self.forced = None;
+        }
+
+        self.start_transition()
+    }
+
+    pub fn toggle(&mut self) -> bool {
+        self.forced = Some(!self.is_active());
+        self.start_transition()
+    }
+
+    pub fn set_temperature(&mut self, temperature: u32) -> bool {
+        self.temperature_override = Some(temperature);
+        // Setting the temperature is only useful if the night light is visible.
+        self.forced = Some(true);
+        self.start_transition()
+    }
+
+    pub fn reset(&mut self) -> bool {
+        self.forced = None;
+        self.temperature_override = None;
+        self.start_transition()
+    }
+
+    pub fn advance_animations(&mut self) {
+        if let Some(anim) = &self.animation {
+            if anim.is_done() {
+                self.current = anim.to();
+                self.animation = None;
+            }
+        }
+    }
+
+    pub fn are_animations_ongoing(&self) -> bool {
+        self.animation.is_some()
+    }
+}
+
+impl Schedule {
+    fn contains(self, minute: i32) -> bool {
+        match self {
+            Schedule::Always => true,
+            Schedule::Never => false,
+            Schedule::Between { start, end } => {
+                if start <= end {
+                    start <= minute && minute < end
+                } else {
+                    start <= minute || minute < end
+                }
+            }
+        }
+    }
+}
+
+impl LocalTime {
+    fn now() -> Option<Self> {
+        unsafe {
+            let time = libc::time(std::ptr::null_mut());
+            if time == -1 {
+                return None;
+            }
+
+            let mut tm = MaybeUninit::<libc::tm>::uninit();
+            if libc::localtime_r(&time, tm.as_mut_ptr()).is_null() {
+                return None;
+            }
+            let tm = tm.assume_init();
+
+            Some(Self {
+                minute_of_day: tm.tm_hour * 60 + tm.tm_min,
+                day_of_year: tm.tm_yday + 1,
+                utc_offset: (tm.tm_gmtoff / 60) as i32,
+            })
+        }
+    }
+}
+
+/// Computes the night as the time between sunset and sunrise.
+///
+/// Uses the NOAA approximation, which is accurate to a couple of minutes.
+fn sun_schedule(day_of_year: i32, latitude: f64, longitude: f64, utc_offset: i32) -> Schedule {
+    // Fractional year in radians, at noon.
+    let g = 2. * PI / 365. * f64::from(day_of_year - 1);
+
+    // Equation of time in minutes.
+    let eqtime = 229.18
+        * (0.000075 + 0.001868 * g.cos()
+            - 0.032077 * g.sin()
+            - 0.014615 * (2. * g).cos()
+            - 0.040849 * (2. * g).sin());
+
+    // Solar declination in radians.
+    let decl = 0.006918 - 0.399912 * g.cos() + 0.070257 * g.sin() - 0.006758 * (2. * g).cos()
+        + 0.000907 * (2. * g).sin()
+        - 0.002697 * (3. * g).cos()
+        + 0.00148 * (3. * g).sin();
+
+    // Hour angle of the sunrise, accounting for atmospheric refraction.
+    let lat = latitude.to_radians();
+    let cos_ha = 90.833f64.to_radians().cos() / (lat.cos() * decl.cos()) - lat.tan() * decl.tan();
+    if cos_ha > 1. {
+        // Polar night.
+        return Schedule::Always;
+    }
+    if cos_ha < -1. {
+        // Polar day.
+        return Schedule::Never;
+    }
+    let ha = cos_ha.acos().to_degrees();
+
+    let noon = 720. - 4. * longitude - eqtime + f64::from(utc_offset);
+    let sunrise = (noon - 4. * ha).round() as i32;
+    let sunset = (noon + 4. * ha).round() as i32;
+
+    Schedule::Between {
+        start: sunset.rem_euclid(MINUTES_PER_DAY),
+        end: sunrise.rem_euclid(MINUTES_PER_DAY),
+    }
+}
+
+fn whitepoint_unnormalized(temperature: f64) -> [f64; 3] {
+    // Approximation of the black body color by Tanner Helland.
+    let t = temperature / 100.;
+
+    let r = if t <= 66. {
+        255.
+    } else {
+        329.698727446 * (t - 60.).powf(-0.1332047592)
+    };
+
+    let g = if t <= 66. {
+        99.4708025861 * t.ln() - 161.1195681661
+    } else {
+        288.1221695283 * (t - 60.).powf(-0.0755148492)
+    };
+
+    let b = if t >= 66. {
+        255.
+    } else if t <= 19. {
+        0.
+    } else {
+        138.5177312231 * (t - 10.).ln() - 305.0447927307
+    };
+
+    [r, g, b].map(|x| (x / 255.).clamp(0., 1.))
+}
+
+/// Returns the RGB multipliers for the color temperature.
+///
+/// The neutral temperature maps to white.
+pub fn whitepoint(temperature: f64) -> [f64; 3] {
+    let neutral = whitepoint_unnormalized(NEUTRAL_TEMPERATURE);
+    let color = whitepoint_unnormalized(temperature);
+    [0, 1, 2].map(|i| (color[i] / neutral[i]).min(1.))
+}
+
+/// Computes a gamma ramp in the wlr-gamma-control format (all red, then green, then blue values).
+pub fn gamma_ramp(size: u32, temperature: f64) -> Vec<u16> {
+    let size = size as usize;
+    let whitepoint = whitepoint(temperature);
+
+    let mut ramp = Vec::with_capacity(size * 3);
+    for mult in whitepoint {
+        for i in 0..size {
+            let value = if size > 1 {
+                i as f64 / (size - 1) as f64
+            } else {
+                1.
+            };
+            ramp.push((value * mult * f64::from(u16::MAX)).round() as u16);
+        }
+    }
+    ramp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format_minutes(minutes: i32) -> String {
+        format!("{:02}:{:02}", minutes / 60, minutes % 60)
+    }
+
+    fn format_schedule(schedule: Schedule) -> String {
+        match schedule {
+            Schedule::Always => String::from("always"),
+            Schedule::Never => String::from("never"),
+            Schedule::Between { start, end } => {
+                format!("{} - {}", format_minutes(start), format_minutes(end))
+            }
+        }
+    }
+
+    #[test]
+    fn sun_schedule_berlin() {
+        // Summer solstice, CEST.
+        let schedule = sun_schedule(172, 52.52, 13.405, 120);
+        assert_eq!(format_schedule(schedule), "21:33 - 04:43");
+
+        // Winter solstice, CET.
+        let schedule = sun_schedule(355, 52.52, 13.405, 60);
+        assert_eq!(format_schedule(schedule), "15:54 - 08:15");
+    }
+
+    #[test]
+    fn sun_schedule_polar() {
+        // Tromsø.
+        assert_eq!(sun_schedule(172, 69.65, 18.96, 120), Schedule::Never);
+        assert_eq!(sun_schedule(355, 69.65, 18.96, 60), Schedule::Always);
+    }
+
+    #[test]
+    fn schedule_contains() {
+        let schedule = Schedule::Between {
+            start: 20 * 60,
+            end: 7 * 60,
+        };
+        assert!(schedule.contains(20 * 60));
+        assert!(schedule.contains(23 * 60));
+        assert!(schedule.contains(0));
+        assert!(!schedule.contains(7 * 60));
+        assert!(!schedule.contains(12 * 60));
+
+        let schedule = Schedule::Between {
+            start: 9 * 60,
+            end: 17 * 60,
+        };
+        assert!(schedule.contains(12 * 60));
+        assert!(!schedule.contains(17 * 60));
+        assert!(!schedule.contains(0));
+    }
+
+    #[test]
+    fn whitepoint_neutral_is_white() {
+        assert_eq!(whitepoint(NEUTRAL_TEMPERATURE), [1., 1., 1.]);
+    }
+
+    #[test]
+    fn whitepoint_warm() {
+        let [r, g, b] = whitepoint(3000.);
+        assert_eq!(r, 1.);
+        assert!(b < g && g < 1.);
+    }
+
+    #[test]
+    fn gamma_ramp_identity() {
+        let ramp = gamma_ramp(3, NEUTRAL_TEMPERATURE);
+        assert_eq!(ramp, [0, 32768, 65535, 0, 32768, 65535, 0, 32768, 65535]);
+    }
+}
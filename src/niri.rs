@@ -12,9 +12,10 @@ use std::{env, mem, thread};
 
 use _server_decoration::server::org_kde_kwin_server_decoration_manager::Mode as KdeDecorationsMode;
 use anyhow::{bail, ensure, Context};
+use bitflags::bitflags;
 use calloop::futures::Scheduler;
 use niri_config::{
-    Config, FloatOrInt, Key, Modifiers, OutputName, PreviewRender, TrackLayout,
+    Action, Config, FloatOrInt, HotCorner, Key, Modifiers, OutputName, PreviewRender, TrackLayout,
     WarpMouseToFocusMode, WorkspaceReference, Xkb,
 };
 use smithay::backend::allocator::Fourcc;
@@ -64,6 +65,7 @@ use smithay::reexports::wayland_protocols_wlr::screencopy::v1::server::zwlr_scre
 use smithay::reexports::wayland_server::backend::{
     ClientData, ClientId, DisconnectReason, GlobalId,
 };
+use smithay::reexports::wayland_server::protocol::wl_data_source::WlDataSource;
 use smithay::reexports::wayland_server::protocol::wl_shm;
 use smithay::reexports::wayland_server::protocol::wl_surface::WlSurface;
 use smithay::reexports::wayland_server::{Client, Display, DisplayHandle, Resource};
@@ -117,13 +119,20 @@ use crate::cursor::{CursorManager, CursorTextureCache, RenderCursor, XCursor};
 #[cfg(feature = "dbus")]
 use crate::dbus::freedesktop_locale1::Locale1ToNiri;
 #[cfg(feature = "dbus")]
+use crate::dbus::gnome_shell::{self, ShellToNiri};
+#[cfg(feature = "dbus")]
 use crate::dbus::gnome_shell_introspect::{self, IntrospectToNiri, NiriToIntrospect};
 #[cfg(feature = "dbus")]
 use crate::dbus::gnome_shell_screenshot::{NiriToScreenshot, ScreenshotToNiri};
+#[cfg(feature = "dbus")]
+use crate::dbus::mutter_idle_monitor::{self, IdleMonitorToNiri};
+#[cfg(feature = "xdp-gnome-screencast")]
+use crate::dbus::mutter_remote_desktop::{self, RemoteDesktopToNiri};
 #[cfg(feature = "xdp-gnome-screencast")]
 use crate::dbus::mutter_screen_cast::{self, ScreenCastToNiri};
 use crate::frame_clock::FrameClock;
 use crate::handlers::{configure_lock_surface, XDG_ACTIVATION_TOKEN_TIMEOUT};
+use crate::idle::{IdleTracker, WatchOwner};
 use crate::input::pick_color_grab::PickColorGrab;
 use crate::input::scroll_swipe_gesture::ScrollSwipeGesture;
 use crate::input::scroll_tracker::ScrollTracker;
@@ -135,9 +144,11 @@ use crate::ipc::server::IpcServer;
 use crate::layer::mapped::LayerSurfaceRenderElement;
 use crate::layer::MappedLayer;
 use crate::layout::tile::TileRenderElement;
-use crate::layout::workspace::{Workspace, WorkspaceId};
+use crate::layout::workspace::{Workspace, WorkspaceId, WorkspaceRenderElement};
 use crate::layout::{HitType, Layout, LayoutElement as _, MonitorRenderElement};
+use crate::night_light::{self, NightLight};
 use crate::niri_render_elements;
+use crate::protocols::color_management::ColorManagementState;
 use crate::protocols::ext_workspace::{self, ExtWorkspaceManagerState};
 use crate::protocols::foreign_toplevel::{self, ForeignToplevelManagerState};
 use crate::protocols::gamma_control::GammaControlManagerState;
@@ -145,10 +156,18 @@ use crate::protocols::mutter_x11_interop::MutterX11InteropManagerState;
 use crate::protocols::output_management::OutputManagementManagerState;
 use crate::protocols::screencopy::{Screencopy, ScreencopyBuffer, ScreencopyManagerState};
 use crate::protocols::virtual_pointer::VirtualPointerManagerState;
+use crate::protocols::xdg_dialog::XdgDialogState;
+use crate::protocols::xdg_system_bell::XdgSystemBellState;
+use crate::protocols::xdg_toplevel_drag::XdgToplevelDragManagerState;
+use crate::protocols::xdg_toplevel_icon::XdgToplevelIconManagerState;
 use crate::pw_utils::{Cast, PipeWire};
 #[cfg(feature = "xdp-gnome-screencast")]
 use crate::pw_utils::{CastSizeChange, PwToNiri};
+use crate::recording::Recording;
+use crate::render_helpers::color::ColorDescription;
+use crate::render_helpers::color_transform::ColorTransformRenderElement;
 use crate::render_helpers::debug::draw_opaque_regions;
+use crate::render_helpers::offscreen::OffscreenRenderElement;
 use crate::render_helpers::primary_gpu_texture::PrimaryGpuTextureRenderElement;
 use crate::render_helpers::renderer::NiriRenderer;
 use crate::render_helpers::solid_color::{SolidColorBuffer, SolidColorRenderElement};
@@ -157,20 +176,31 @@ use crate::render_helpers::{
     encompassing_geo, render_to_dmabuf, render_to_encompassing_texture, render_to_shm,
     render_to_texture, render_to_vec, shaders, RenderTarget, SplitElements,
 };
+use crate::ui::bell_flash::BellFlash;
 use crate::ui::config_error_notification::ConfigErrorNotification;
 use crate::ui::exit_confirm_dialog::ExitConfirmDialog;
 use crate::ui::hotkey_overlay::HotkeyOverlay;
+use crate::ui::overview_search::OverviewSearchBar;
+use crate::ui::recording_indicator::RecordingIndicator;
+use crate::ui::remote_desktop_indicator::RemoteDesktopIndicator;
 use crate::ui::screen_transition::{self, ScreenTransition};
-use crate::ui::screenshot_ui::{OutputScreenshot, ScreenshotUi, ScreenshotUiRenderElement};
+use crate::ui::screencast_indicator::ScreencastIndicator;
+use crate::ui::screenshot_countdown::ScreenshotCountdown;
+use crate::ui::screenshot_ui::{
+    OutputScreenshot, ScreenshotUi, ScreenshotUiRenderElement, SelectionPurpose,
+};
+use crate::ui::window_hints::WindowHints;
+use crate::ui::window_switcher::{WindowSwitcher, WindowSwitcherRenderElement};
+use crate::utils::id::IdCounter;
 use crate::utils::scale::{closest_representable_scale, guess_monitor_scale};
 use crate::utils::spawning::{CHILD_DISPLAY, CHILD_ENV};
 use crate::utils::xwayland::satellite::Satellite;
 use crate::utils::{
     center, center_f64, expand_home, get_monotonic_time, ipc_transform_to_smithay, is_mapped,
-    logical_output, make_screenshot_path, output_matches_name, output_size, send_scale_transform,
-    write_png_rgba8, xwayland,
+    logical_output, make_recording_path, make_screenshot_path, output_matches_name, output_size,
+    send_scale_transform, write_png_rgba8, xwayland,
 };
-use crate::window::mapped::MappedId;
+use crate::window::mapped::{MappedId, WindowCastRenderElements};
 use crate::window::{InitialConfigureState, Mapped, ResolvedWindowRules, Unmapped, WindowRef};
 
 const CLEAR_COLOR_LOCKED: [f32; 4] = [0.3, 0.1, 0.1, 1.];
@@ -295,8 +325,13 @@ pub struct Niri {
     pub presentation_state: PresentationState,
     pub security_context_state: SecurityContextState,
     pub gamma_control_manager_state: GammaControlManagerState,
+    pub color_management_state: ColorManagementState,
     pub activation_state: XdgActivationState,
     pub mutter_x11_interop_state: MutterX11InteropManagerState,
+    pub xdg_toplevel_icon_manager_state: XdgToplevelIconManagerState,
+    pub xdg_system_bell_state: XdgSystemBellState,
+    pub xdg_dialog_state: XdgDialogState,
+    pub xdg_toplevel_drag_manager_state: XdgToplevelDragManagerState,
 
     // This will not work as is outside of tests, so it is gated with #[cfg(test)] for now. In
     // particular, shaders will need to learn about the single pixel buffer. Also, it must be
@@ -319,6 +354,9 @@ pub struct Niri {
     pub previously_focused_window: Option<Window>,
     pub idle_inhibiting_surfaces: HashSet<WlSurface>,
     pub is_fdo_idle_inhibited: Arc<AtomicBool>,
+    /// Idle time tracking for the idle config and org.gnome.Mutter.IdleMonitor.
+    pub idle_tracker: IdleTracker,
+    pub idle_timer: Option<RegistrationToken>,
     pub keyboard_shortcuts_inhibiting_surfaces: HashMap<WlSurface, KeyboardShortcutsInhibitor>,
 
     /// Most recent XKB settings from org.freedesktop.locale1.
@@ -328,6 +366,8 @@ pub struct Niri {
     pub cursor_texture_cache: CursorTextureCache,
     pub cursor_shape_manager_state: CursorShapeManagerState,
     pub dnd_icon: Option<DndIcon>,
+    /// Ongoing drag-and-drop with an attached toplevel (xdg-toplevel-drag).
+    pub toplevel_drag: Option<ToplevelDrag>,
     /// Contents under pointer.
     ///
     /// Periodically updated: on motion and other events and in the loop callback. If you require
@@ -354,9 +394,11 @@ pub struct Niri {
     /// Used for limiting the notify to once per iteration, so that it's not spammed with high
     /// resolution mice.
     pub notified_activity_this_iteration: bool,
-    pub pointer_inside_hot_corner: bool,
+    pub hot_corner: Option<HotCornerState>,
     pub tablet_cursor_location: Option<Point<f64, Logical>>,
     pub gesture_swipe_3f_cumulative: Option<(f64, f64)>,
+    /// Whether the ongoing 3-finger swipe has its axes swapped for a vertical scrolling layout.
+    pub gesture_swipe_3f_transposed: bool,
     pub overview_scroll_swipe_gesture: ScrollSwipeGesture,
     pub vertical_wheel_tracker: ScrollTracker,
     pub horizontal_wheel_tracker: ScrollTracker,
@@ -373,8 +415,24 @@ pub struct Niri {
 
     pub screenshot_ui: ScreenshotUi,
     pub config_error_notification: ConfigErrorNotification,
+    pub night_light: NightLight,
+    /// Whether niri applies the night light, reported through org.gnome.Mutter.DisplayConfig.
+    pub is_night_light_supported: Arc<AtomicBool>,
     pub hotkey_overlay: HotkeyOverlay,
+    pub overview_search_bar: OverviewSearchBar,
+    pub window_switcher: WindowSwitcher,
+    pub window_hints: WindowHints,
     pub exit_confirm_dialog: Option<ExitConfirmDialog>,
+    pub remote_desktop_indicator: RemoteDesktopIndicator,
+    pub screencast_indicator: ScreencastIndicator,
+    pub recording_indicator: RecordingIndicator,
+    pub screenshot_countdown: ScreenshotCountdown,
+
+    /// Ongoing built-in recording.
+    pub recording: Option<Recording>,
+
+    /// Screenshot UI waiting for its countdown to open.
+    pub pending_screenshot: Option<PendingScreenshot>,
 
     pub pick_window: Option<async_channel::Sender<Option<MappedId>>>,
     pub pick_color: Option<async_channel::Sender<Option<niri_ipc::PickedColor>>>,
@@ -392,6 +450,12 @@ pub struct Niri {
 
     pub satellite: Option<Satellite>,
 
+    /// Time when the bell last ran its `spawn` command, used to rate-limit it.
+    pub bell_spawn_time: Option<Duration>,
+    /// Number of times the bell ran its `spawn` command.
+    #[cfg(test)]
+    pub bell_spawn_count: usize,
+
     // Casts are dropped before PipeWire to prevent a double-free (yay).
     pub casts: Vec<Cast>,
     pub pipewire: Option<PipeWire>,
@@ -405,6 +469,46 @@ pub struct Niri {
     /// Window ID for the "dynamic cast" special window for the xdp-gnome picker.
     #[cfg(feature = "xdp-gnome-screencast")]
     pub dynamic_cast_id_for_portal: MappedId,
+
+    /// Started remote desktop sessions, mapped to their paired screencast session.
+    pub remote_desktop_sessions: HashMap<usize, Option<usize>>,
+
+    /// Accelerators grabbed by clients through the org.gnome.Shell D-Bus interface.
+    pub global_shortcuts: Vec<GlobalShortcut>,
+    /// Ids of global shortcuts activated by currently held keys, to deactivate on release.
+    pub pressed_global_shortcuts: HashMap<Keycode, Vec<u32>>,
+}
+
+/// Accelerator grabbed by a client, usually through the GlobalShortcuts portal.
+#[derive(Debug)]
+pub struct GlobalShortcut {
+    pub id: u32,
+    pub key: Key,
+    /// Modes in which the accelerator is active.
+    pub modes: ActionModes,
+    /// Unique bus name of the client that grabbed the accelerator.
+    pub sender: String,
+}
+
+static GLOBAL_SHORTCUT_ID_COUNTER: IdCounter = IdCounter::new();
+
+impl GlobalShortcut {
+    /// Returns a new non-zero id, since zero means a failed grab.
+    pub fn next_id() -> u32 {
+        GLOBAL_SHORTCUT_ID_COUNTER.next() as u32
+    }
+}
+
+bitflags! {
+    /// Shell.ActionMode flags of GNOME Shell that niri can be in.
+    ///
+    /// The lock screen, login screen and system modal modes are missing: global shortcuts never
+    /// fire while the session is locked or the screenshot UI is open.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct ActionModes: u32 {
+        const NORMAL   = 1 << 0;
+        const OVERVIEW = 1 << 1;
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -435,6 +539,46 @@ pub struct DndIcon {
     pub offset: Point<i32, Logical>,
 }
 
+pub struct ToplevelDrag {
+    /// Data source of the drag-and-drop operation.
+    pub source: WlDataSource,
+    /// Root surface of the attached toplevel, if any.
+    pub surface: Option<WlSurface>,
+    /// Pointer offset within the attached toplevel's window geometry.
+    pub offset: Point<i32, Logical>,
+    /// Location of the pointer at the last motion.
+    pub last_location: Option<Point<f64, Logical>>,
+    /// Whether the attached toplevel is being moved in the layout.
+    ///
+    /// This becomes `true` once the toplevel is mapped and the pointer moves.
+    pub is_moving: bool,
+}
+
+/// Pointer inside a hot corner.
+pub struct HotCornerState {
+    pub output: Output,
+    pub corner: HotCorner,
+    /// Action to run once the hot corner activates.
+    pub action: Action,
+    /// Monotonic time when the pointer entered the corner.
+    pub entered_at: Duration,
+    /// Whether the activation delay has passed.
+    pub delay_passed: bool,
+    /// Distance that the pointer pushed into the corner so far.
+    pub pressure: f64,
+    /// Whether the pressure threshold was reached.
+    pub pressure_reached: bool,
+    /// Whether the action already ran for this entry.
+    pub triggered: bool,
+}
+
+pub struct PendingScreenshot {
+    pub show_pointer: bool,
+    /// Monotonic time when the screenshot UI opens.
+    pub deadline: Duration,
+    pub timer: RegistrationToken,
+}
+
 pub struct OutputState {
     pub global: GlobalId,
     pub frame_clock: FrameClock,
@@ -472,8 +616,14 @@ pub struct OutputState {
     pub lock_surface: Option<LockSurface>,
     pub lock_color_buffer: SolidColorBuffer,
     screen_transition: Option<ScreenTransition>,
+    /// Ongoing flash in response to the system bell.
+    bell_flash: Option<BellFlash>,
+    /// Night light temperature last applied to the gamma, `None` if the gamma is neutral.
+    pub night_light_temperature: Option<f64>,
     /// Damage tracker used for the debug damage visualization.
     pub debug_damage_tracker: OutputDamageTracker,
+    /// Color encoding that the output expects.
+    pub color_description: ColorDescription,
 }
 
 #[derive(Debug, Default)]
@@ -507,6 +657,7 @@ pub enum KeyboardFocus {
     LayerShell { surface: WlSurface },
     LockScreen { surface: Option<WlSurface> },
     ScreenshotUi,
+    WindowHints,
     Overview,
 }
 
@@ -565,7 +716,28 @@ pub enum CastTarget {
     // Dynamic cast before selecting anything.
     Nothing,
     Output(WeakOutput),
-    Window { id: u64 },
+    // Area of an output, in output-local logical coordinates.
+    Area {
+        output: WeakOutput,
+        rect: Rectangle<i32, Logical>,
+    },
+    Window {
+        id: u64,
+    },
+    // Workspace, following it across outputs.
+    Workspace {
+        id: u64,
+    },
+}
+
+/// Where the workspace targeted by a cast currently is.
+#[derive(Debug, PartialEq)]
+pub enum WorkspaceCastState {
+    // The workspace is gone, so the cast should stop.
+    Removed,
+    // Workspaces without an output can't be rendered, so the cast pauses until they get one.
+    NoOutput,
+    Output(Output),
 }
 
 impl RedrawState {
@@ -604,6 +776,7 @@ impl KeyboardFocus {
             KeyboardFocus::LayerShell { surface } => Some(surface),
             KeyboardFocus::LockScreen { surface } => surface.as_ref(),
             KeyboardFocus::ScreenshotUi => None,
+            KeyboardFocus::WindowHints => None,
             KeyboardFocus::Overview => None,
         }
     }
@@ -614,6 +787,7 @@ impl KeyboardFocus {
             KeyboardFocus::LayerShell { surface } => Some(surface),
             KeyboardFocus::LockScreen { surface } => surface,
             KeyboardFocus::ScreenshotUi => None,
+            KeyboardFocus::WindowHints => None,
             KeyboardFocus::Overview => None,
         }
     }
@@ -735,21 +909,26 @@ impl State {
         self.niri.refresh_pointer_outputs();
         self.niri.global_space.refresh();
         self.niri.refresh_idle_inhibit();
+        self.niri.refresh_preferred_color_descriptions();
         self.refresh_pointer_contents();
         foreign_toplevel::refresh(self);
         ext_workspace::refresh(self);
 
         #[cfg(feature = "xdp-gnome-screencast")]
         self.niri.refresh_mapped_cast_outputs();
+        #[cfg(feature = "xdp-gnome-screencast")]
+        self.niri.refresh_workspace_casts();
         // Should happen before refresh_window_rules(), but after anything that can start or stop
         // screencasts.
         #[cfg(feature = "xdp-gnome-screencast")]
         self.niri.refresh_mapped_cast_window_rules();
 
+        self.refresh_recording();
         self.niri.refresh_window_rules();
         self.refresh_ipc_outputs();
         self.ipc_refresh_layout();
         self.ipc_refresh_keyboard_layout_index();
+        self.ipc_refresh_casts();
     }
 
     fn notify_blocker_cleared(&mut self) {
@@ -1055,6 +1234,17 @@ impl State {
             }
         }
 
+        // Modal dialogs block input to their parent, so move the focus to the dialog.
+        if let Some(modal) = self
+            .niri
+            .layout
+            .focus()
+            .and_then(|win| self.niri.layout.blocking_modal(&win.window))
+        {
+            let window = modal.clone();
+            self.niri.layout.activate_window(&window);
+        }
+
         // Compute the current focus.
         let focus = if self.niri.is_locked() {
             KeyboardFocus::LockScreen {
@@ -1062,6 +1252,8 @@ impl State {
             }
         } else if self.niri.screenshot_ui.is_open() {
             KeyboardFocus::ScreenshotUi
+        } else if self.niri.window_hints.is_open() {
+            KeyboardFocus::WindowHints
         } else if let Some(output) = self.niri.layout.active_output() {
             let mon = self.niri.layout.monitor_for_output(output).unwrap();
             let layers = layer_map_for_output(output);
@@ -1188,6 +1380,7 @@ impl State {
             {
                 if let Some((mapped, _)) = self.niri.layout.find_window_and_output_mut(surface) {
                     mapped.set_is_focused(true);
+                    mapped.set_focus_timestamp(get_monotonic_time());
                 }
             }
 
@@ -1328,6 +1521,23 @@ impl State {
             self.niri.layout.unname_workspace(&name);
         }
 
+        // Apply changed layout kinds of existing named workspaces.
+        let mut changed_layout_kinds = vec![];
+        for ws in &self.niri.config.borrow().workspaces {
+            if let Some(new) = config.workspaces.iter().find(|w| w.name == ws.name) {
+                if new.layout_kind != ws.layout_kind {
+                    let kind = new.layout_kind.unwrap_or_default();
+                    changed_layout_kinds.push((ws.name.0.clone(), kind));
+                }
+            }
+        }
+        for (name, kind) in changed_layout_kinds {
+            let reference = WorkspaceReference::Name(name);
+            self.niri
+                .layout
+                .set_workspace_layout_kind(kind, Some(reference));
+        }
+
         self.niri.layout.update_config(&config);
         for mapped in self.niri.mapped_layer_surfaces.values_mut() {
             mapped.update_config(&config);
@@ -1355,6 +1565,9 @@ impl State {
         let mut shaders_changed = false;
         let mut cursor_inactivity_timeout_changed = false;
         let mut xwls_changed = false;
+        let mut remote_desktop_disabled = false;
+        let mut night_light_changed = false;
+        let mut idle_changed = false;
         let mut old_config = self.niri.config.borrow_mut();
 
         // Reload the cursor.
@@ -1470,6 +1683,18 @@ impl State {
             xwls_changed = true;
         }
 
+        if !config.remote_desktop.on && old_config.remote_desktop.on {
+            remote_desktop_disabled = true;
+        }
+
+        if config.night_light != old_config.night_light {
+            night_light_changed = true;
+        }
+
+        if config.idle != old_config.idle {
+            idle_changed = true;
+        }
+
         *old_config = config;
 
         if let Some(outputs) = preserved_output_config {
@@ -1563,6 +1788,35 @@ impl State {
             *CHILD_DISPLAY.write().unwrap() = display_name;
         }
 
+        if remote_desktop_disabled {
+            self.niri.stop_remote_desktop_sessions();
+        }
+
+        if night_light_changed {
+            self.niri.night_light.update_schedule();
+
+            let config = self.niri.config.borrow();
+            let supported = night_light_supported(&self.backend, &config.night_light);
+            drop(config);
+            let was_supported = self
+                .niri
+                .is_night_light_supported
+                .swap(supported, Ordering::SeqCst);
+            if supported != was_supported {
+                #[cfg(feature = "dbus")]
+                self.niri.on_night_light_supported_changed();
+            }
+        }
+
+        if idle_changed {
+            let config = self.niri.config.borrow();
+            let timeouts = idle_timeouts(&config.idle);
+            self.niri.idle_tracker.set_config_timeouts(timeouts);
+            drop(config);
+
+            self.niri.schedule_idle_timer();
+        }
+
         // Can't really update xdg-decoration settings since we have to hide the globals for CSD
         // due to the SDL2 bug... I don't imagine clients are prepared for the xdg-decoration
         // global suddenly appearing? Either way, right now it's live-reloaded in a sense that new
@@ -1764,7 +2018,7 @@ impl State {
         self.niri.output_management_state.notify_changes(new_config);
     }
 
-    pub fn open_screenshot_ui(&mut self, show_pointer: bool) {
+    pub fn open_screenshot_ui(&mut self, show_pointer: bool, purpose: SelectionPurpose) {
         if self.niri.is_locked() || self.niri.screenshot_ui.is_open() {
             return;
         }
@@ -1785,6 +2039,7 @@ impl State {
         else {
             return;
         };
+        let windows = self.niri.screenshot_window_rects();
 
         // Now that we captured the screenshots, clear grabs like drag-and-drop, etc.
         self.niri.seat.get_pointer().unwrap().unset_grab(
@@ -1797,9 +2052,14 @@ impl State {
         }
 
         self.backend.with_primary_renderer(|renderer| {
-            self.niri
-                .screenshot_ui
-                .open(renderer, screenshots, default_output, show_pointer)
+            self.niri.screenshot_ui.open(
+                renderer,
+                screenshots,
+                windows,
+                default_output,
+                show_pointer,
+                purpose,
+            )
         });
 
         self.niri
@@ -1808,6 +2068,56 @@ impl State {
         self.niri.queue_redraw_all();
     }
 
+    /// Opens the screenshot UI after a countdown, or cancels the countdown if there is one.
+    pub fn open_screenshot_ui_delayed(&mut self, show_pointer: bool, delay: Duration) {
+        if let Some(pending) = self.niri.pending_screenshot.take() {
+            self.niri.event_loop.remove(pending.timer);
+            self.niri.queue_redraw_all();
+            return;
+        }
+
+        if delay.is_zero() {
+            self.open_screenshot_ui(show_pointer, SelectionPurpose::Screenshot);
+            return;
+        }
+
+        if self.niri.is_locked() || self.niri.screenshot_ui.is_open() {
+            return;
+        }
+
+        let timer = Timer::from_duration(until_countdown_tick(delay));
+        let token = self
+            .niri
+            .event_loop
+            .insert_source(timer, |_, _, state| state.on_screenshot_countdown())
+            .unwrap();
+        self.niri.pending_screenshot = Some(PendingScreenshot {
+            show_pointer,
+            deadline: get_monotonic_time() + delay,
+            timer: token,
+        });
+        self.niri.queue_redraw_all();
+    }
+
+    fn on_screenshot_countdown(&mut self) -> TimeoutAction {
+        let Some(pending) = &self.niri.pending_screenshot else {
+            return TimeoutAction::Drop;
+        };
+
+        let remaining = pending.deadline.saturating_sub(get_monotonic_time());
+        if !remaining.is_zero() {
+            // Redraw for the countdown to show the new number.
+            self.niri.queue_redraw_all();
+            return TimeoutAction::ToDuration(until_countdown_tick(remaining));
+        }
+
+        // Take the pending screenshot before opening so that the countdown doesn't end up in it.
+        let pending = self.niri.pending_screenshot.take().unwrap();
+        self.open_screenshot_ui(pending.show_pointer, SelectionPurpose::Screenshot);
+        self.niri.queue_redraw_all();
+        TimeoutAction::Drop
+    }
+
     pub fn handle_pick_color(&mut self, tx: async_channel::Sender<Option<niri_ipc::PickedColor>>) {
         let pointer = self.niri.seat.get_pointer().unwrap();
         let start_data = PointerGrabStartData {
@@ -1829,6 +2139,37 @@ impl State {
             return;
         }
 
+        let purpose = self.niri.screenshot_ui.purpose();
+        if matches!(
+            purpose,
+            Some(SelectionPurpose::DynamicCast | SelectionPurpose::Recording)
+        ) {
+            let (output, rect) = self.niri.screenshot_ui.selection().unwrap();
+            let output = output.clone();
+            let scale = output.current_scale().fractional_scale();
+            let rect = rect.to_f64().to_logical(scale).to_i32_round();
+
+            self.niri.screenshot_ui.close();
+            self.niri
+                .cursor_manager
+                .set_cursor_image(CursorImageStatus::default_named());
+            self.niri.queue_redraw_all();
+
+            let target = CastTarget::Area {
+                output: output.downgrade(),
+                rect,
+            };
+            if purpose == Some(SelectionPurpose::Recording) {
+                self.start_recording(target);
+            } else {
+                self.set_dynamic_cast_target(target);
+            }
+            return;
+        }
+
+        // Don't bake the text caret into the screenshot.
+        self.niri.screenshot_ui.finish_text();
+
         self.backend.with_primary_renderer(|renderer| {
             match self.niri.screenshot_ui.capture(renderer) {
                 Ok((size, pixels)) => {
@@ -1889,11 +2230,16 @@ impl State {
                     }
                 });
             }
-            CastTarget::Output(weak) => {
+            CastTarget::Output(weak) | CastTarget::Area { output: weak, .. } => {
                 if let Some(output) = weak.upgrade() {
                     self.niri.queue_redraw(&output);
                 }
             }
+            CastTarget::Workspace { id } => {
+                if let Some(output) = self.niri.workspace_cast_output(*id) {
+                    self.niri.queue_redraw(&output);
+                }
+            }
             CastTarget::Window { id } => {
                 let mut windows = self.niri.layout.windows();
                 let Some((_, mapped)) = windows.find(|(_, mapped)| mapped.id().get() == *id) else {
@@ -1949,6 +2295,32 @@ impl State {
         }
     }
 
+    pub fn start_recording(&mut self, target: CastTarget) {
+        // Finish the previous recording properly before starting a new one.
+        self.stop_recording();
+        self.niri.start_recording(target);
+    }
+
+    pub fn stop_recording(&mut self) {
+        // The last frame is still being read back from the GPU, write it out too.
+        self.backend.with_primary_renderer(|renderer| {
+            if let Some(recording) = &mut self.niri.recording {
+                if let Err(err) = recording.read_back(renderer) {
+                    warn!("error reading back the last recording frame: {err:?}");
+                }
+            }
+        });
+        self.niri.stop_recording();
+    }
+
+    /// Stops the recording if its target went away.
+    fn refresh_recording(&mut self) {
+        if self.niri.is_recording_target_gone() {
+            debug!("recording target is gone, stopping recording");
+            self.stop_recording();
+        }
+    }
+
     #[cfg(not(feature = "xdp-gnome-screencast"))]
     pub fn set_dynamic_cast_target(&mut self, _target: CastTarget) {}
 
@@ -1961,11 +2333,16 @@ impl State {
             // Leave refresh as is when clearing. Chances are, the next refresh will match it,
             // then we'll avoid reconfiguring.
             CastTarget::Nothing => (),
-            CastTarget::Output(output) => {
+            CastTarget::Output(output) | CastTarget::Area { output, .. } => {
                 if let Some(output) = output.upgrade() {
                     refresh = Some(output.current_mode().unwrap().refresh as u32);
                 }
             }
+            CastTarget::Workspace { id } => {
+                if let Some(output) = self.niri.workspace_cast_output(*id) {
+                    refresh = Some(output.current_mode().unwrap().refresh as u32);
+                }
+            }
             CastTarget::Window { id } => {
                 let mut windows = self.niri.layout.windows();
                 if let Some((_, mapped)) = windows.find(|(_, mapped)| mapped.id().get() == *id) {
@@ -1995,6 +2372,8 @@ impl State {
             to_redraw.push(cast.stream_id);
         }
 
+        self.niri.queue_redraw_screencast_indicator();
+
         for id in to_redraw {
             self.redraw_cast(id);
         }
@@ -2005,6 +2384,7 @@ impl State {
         use smithay::reexports::gbm::Modifier;
 
         use crate::dbus::mutter_screen_cast::StreamTargetId;
+        use crate::utils::with_toplevel_role;
 
         match msg {
             ScreenCastToNiri::StartCast {
@@ -2012,11 +2392,22 @@ impl State {
                 stream_id,
                 target,
                 cursor_mode,
+                mut client,
                 signal_ctx,
             } => {
                 let _span = tracy_client::span!("StartCast");
 
-                debug!(session_id, stream_id, "StartCast");
+                debug!(session_id, stream_id, ?client, "StartCast");
+
+                // The client is usually the portal, but it may be the application itself.
+                client.app_id = client.pid.and_then(|pid| {
+                    self.niri.layout.windows().find_map(|(_, mapped)| {
+                        if mapped.credentials().map(|c| c.pid) != Some(pid) {
+                            return None;
+                        }
+                        with_toplevel_role(mapped.toplevel(), |role| role.app_id.clone())
+                    })
+                });
 
                 let Some(gbm) = self.backend.gbm_device() else {
                     warn!("error starting screencast: no GBM device available");
@@ -2056,6 +2447,23 @@ impl State {
                         let refresh = mode.refresh as u32;
                         (CastTarget::Output(output.downgrade()), size, refresh, false)
                     }
+                    StreamTargetId::Area { output: name, rect } => {
+                        let global_space = &self.niri.global_space;
+                        let output = global_space.outputs().find(|out| out.name() == name);
+                        let Some(output) = output else {
+                            warn!("error starting screencast: requested output is missing");
+                            self.niri.stop_cast(session_id);
+                            return;
+                        };
+
+                        let size = cast_area_crop(output, rect).size;
+                        let refresh = output.current_mode().unwrap().refresh as u32;
+                        let target = CastTarget::Area {
+                            output: output.downgrade(),
+                            rect,
+                        };
+                        (target, size, refresh, false)
+                    }
                     StreamTargetId::Window { id }
                         if id == self.niri.dynamic_cast_id_for_portal.get() =>
                     {
@@ -2118,11 +2526,13 @@ impl State {
                     refresh,
                     alpha,
                     cursor_mode,
+                    client,
                     signal_ctx,
                 );
                 match res {
                     Ok(cast) => {
                         self.niri.casts.push(cast);
+                        self.niri.queue_redraw_screencast_indicator();
                     }
                     Err(err) => {
                         warn!("error starting screencast: {err:?}");
@@ -2134,6 +2544,52 @@ impl State {
         }
     }
 
+    #[cfg(feature = "xdp-gnome-screencast")]
+    pub fn on_remote_desktop_msg(&mut self, msg: RemoteDesktopToNiri) {
+        match msg {
+            RemoteDesktopToNiri::StartSession {
+                session_id,
+                screen_cast_session_id,
+            } => {
+                debug!(session_id, "StartSession");
+
+                if !self.niri.config.borrow().remote_desktop.on {
+                    debug!("remote desktop is disabled in the config, stopping session");
+                    self.niri.stop_remote_desktop_session(session_id);
+                    if let Some(id) = screen_cast_session_id {
+                        self.niri.stop_cast(id);
+                    }
+                    return;
+                }
+
+                self.niri
+                    .remote_desktop_sessions
+                    .insert(session_id, screen_cast_session_id);
+                self.niri.queue_redraw_all();
+            }
+            RemoteDesktopToNiri::StopSession {
+                session_id,
+                screen_cast_session_id,
+            } => {
+                debug!(session_id, "StopSession");
+
+                let paired = self.niri.remote_desktop_sessions.remove(&session_id);
+                if let Some(id) = screen_cast_session_id.or(paired.flatten()) {
+                    self.niri.stop_cast(id);
+                }
+                self.niri.queue_redraw_all();
+            }
+            RemoteDesktopToNiri::Input { session_id, event } => {
+                // Ignore input from sessions that we refused or already stopped.
+                if !self.niri.remote_desktop_sessions.contains_key(&session_id) {
+                    return;
+                }
+
+                self.process_remote_desktop_input(session_id, event);
+            }
+        }
+    }
+
     #[cfg(feature = "dbus")]
     pub fn on_screen_shot_msg(
         &mut self,
@@ -2238,6 +2694,138 @@ impl State {
         }
     }
 
+    fn on_idle_timer(&mut self) {
+        let _span = tracy_client::span!("State::on_idle_timer");
+
+        let fired = self.niri.idle_tracker.fire(get_monotonic_time());
+        for owner in &fired {
+            let WatchOwner::Config(idx) = owner else {
+                continue;
+            };
+
+            let config = self.niri.config.borrow();
+            let Some(timeout) = config.idle.timeouts.get(*idx) else {
+                continue;
+            };
+            let seconds = timeout.seconds;
+            let actions = timeout.actions.clone();
+            drop(config);
+
+            debug!("idle for {seconds}s, running idle timeout actions");
+            for action in actions {
+                self.do_action(action, true);
+            }
+        }
+        self.niri.notify_idle_watches_fired(&fired);
+
+        self.niri.schedule_idle_timer();
+    }
+
+    #[cfg(feature = "dbus")]
+    pub fn on_idle_monitor_msg(&mut self, msg: IdleMonitorToNiri) {
+        let idle = &mut self.niri.idle_tracker;
+
+        match msg {
+            IdleMonitorToNiri::GetIdletime { tx } => {
+                let idle_time = idle.idle_time(get_monotonic_time());
+                if let Err(err) = tx.send_blocking(idle_time.as_millis() as u64) {
+                    warn!("error sending idle time: {err:?}");
+                }
+            }
+            IdleMonitorToNiri::AddIdleWatch {
+                sender,
+                interval_ms,
+                tx,
+            } => {
+                let interval = Duration::from_millis(interval_ms);
+                let id = idle.add_idle_watch(interval, sender);
+                self.niri.schedule_idle_timer();
+
+                if let Err(err) = tx.send_blocking(id) {
+                    warn!("error sending idle watch id: {err:?}");
+                }
+            }
+            IdleMonitorToNiri::AddUserActiveWatch { sender, tx } => {
+                let id = idle.add_user_active_watch(sender);
+                if let Err(err) = tx.send_blocking(id) {
+                    warn!("error sending user active watch id: {err:?}");
+                }
+            }
+            IdleMonitorToNiri::RemoveWatch { sender, id } => {
+                if !idle.remove_watch(id, &sender) {
+                    debug!(id, ?sender, "tried to remove a nonexistent idle watch");
+                }
+                self.niri.schedule_idle_timer();
+            }
+            IdleMonitorToNiri::ClientDisappeared { sender } => {
+                idle.remove_watches_of(&sender);
+                self.niri.schedule_idle_timer();
+            }
+        }
+    }
+
+    #[cfg(feature = "dbus")]
+    pub fn on_shell_msg(&mut self, msg: ShellToNiri) {
+        match msg {
+            ShellToNiri::GrabAccelerators { sender, keys, tx } => {
+                let ids = keys
+                    .into_iter()
+                    .map(|key| {
+                        let Some((key, modes)) = key else {
+                            return 0;
+                        };
+
+                        let id = GlobalShortcut::next_id();
+                        debug!(id, ?sender, ?modes, "grabbing accelerator {key:?}");
+                        self.niri.global_shortcuts.push(GlobalShortcut {
+                            id,
+                            key,
+                            modes,
+                            sender: sender.clone(),
+                        });
+                        id
+                    })
+                    .collect();
+
+                self.niri.on_global_shortcuts_changed();
+
+                if let Err(err) = tx.send_blocking(ids) {
+                    warn!("error sending grabbed accelerator ids: {err:?}");
+                }
+            }
+            ShellToNiri::UngrabAccelerators { sender, ids, tx } => {
+                let mut success = true;
+                for id in ids {
+                    let shortcuts = &mut self.niri.global_shortcuts;
+                    if let Some(idx) = shortcuts
+                        .iter()
+                        .position(|s| s.id == id && s.sender == *sender)
+                    {
+                        debug!(id, ?sender, "ungrabbing accelerator");
+                        shortcuts.remove(idx);
+                    } else {
+                        success = false;
+                    }
+                }
+
+                self.niri.on_global_shortcuts_changed();
+
+                if let Err(err) = tx.send_blocking(success) {
+                    warn!("error sending ungrab accelerators result: {err:?}");
+                }
+            }
+            ShellToNiri::ClientDisappeared { sender } => {
+                let shortcuts = &mut self.niri.global_shortcuts;
+                let len = shortcuts.len();
+                shortcuts.retain(|s| s.sender != sender);
+                if shortcuts.len() != len {
+                    debug!(sender, "ungrabbing accelerators of disappeared client");
+                    self.niri.on_global_shortcuts_changed();
+                }
+            }
+        }
+    }
+
     #[cfg(feature = "dbus")]
     pub fn on_locale1_msg(&mut self, msg: Locale1ToNiri) {
         let Locale1ToNiri::XkbChanged(xkb) = msg;
@@ -2390,6 +2978,8 @@ impl Niri {
             GammaControlManagerState::new::<State, _>(&display_handle, move |client| {
                 is_tty && !client.get_data::<ClientState>().unwrap().restricted
             });
+        let color_management_state =
+            ColorManagementState::new::<State, _>(&display_handle, |_| true);
         let activation_state = XdgActivationState::new::<State>(&display_handle);
         event_loop
             .insert_source(
@@ -2405,6 +2995,12 @@ impl Niri {
 
         let mutter_x11_interop_state =
             MutterX11InteropManagerState::new::<State, _>(&display_handle, move |_| true);
+        let xdg_toplevel_icon_manager_state =
+            XdgToplevelIconManagerState::new::<State, _>(&display_handle, |_| true);
+        let xdg_system_bell_state = XdgSystemBellState::new::<State, _>(&display_handle, |_| true);
+        let xdg_dialog_state = XdgDialogState::new::<State, _>(&display_handle, |_| true);
+        let xdg_toplevel_drag_manager_state =
+            XdgToplevelDragManagerState::new::<State, _>(&display_handle, |_| true);
 
         #[cfg(test)]
         let single_pixel_buffer_state = SinglePixelBufferState::new::<State>(&display_handle);
@@ -2449,6 +3045,11 @@ impl Niri {
         let screenshot_ui = ScreenshotUi::new(animation_clock.clone(), config.clone());
         let config_error_notification =
             ConfigErrorNotification::new(animation_clock.clone(), config.clone());
+        let night_light = NightLight::new(animation_clock.clone(), config.clone());
+        let is_night_light_supported = night_light_supported(backend, &config_.night_light);
+
+        let mut idle_tracker = IdleTracker::new(get_monotonic_time());
+        idle_tracker.set_config_timeouts(idle_timeouts(&config_.idle));
 
         let mut hotkey_overlay = HotkeyOverlay::new(config.clone(), mod_key);
         if !config_.hotkey_overlay.skip_at_startup {
@@ -2519,6 +3120,16 @@ impl Niri {
             })
             .unwrap();
 
+        event_loop
+            .insert_source(
+                Timer::from_duration(Duration::from_secs(60)),
+                |_, _, state| {
+                    state.niri.update_night_light_schedule();
+                    TimeoutAction::ToDuration(Duration::from_secs(60))
+                },
+            )
+            .unwrap();
+
         event_loop
             .insert_source(
                 Timer::from_duration(Duration::from_secs(60)),
@@ -2606,8 +3217,13 @@ impl Niri {
             presentation_state,
             security_context_state,
             gamma_control_manager_state,
+            color_management_state,
             activation_state,
             mutter_x11_interop_state,
+            xdg_toplevel_icon_manager_state,
+            xdg_system_bell_state,
+            xdg_dialog_state,
+            xdg_toplevel_drag_manager_state,
             #[cfg(test)]
             single_pixel_buffer_state,
 
@@ -2617,20 +3233,24 @@ impl Niri {
             previously_focused_window: None,
             idle_inhibiting_surfaces: HashSet::new(),
             is_fdo_idle_inhibited: Arc::new(AtomicBool::new(false)),
+            idle_tracker,
+            idle_timer: None,
             keyboard_shortcuts_inhibiting_surfaces: HashMap::new(),
             xkb_from_locale1: None,
             cursor_manager,
             cursor_texture_cache: Default::default(),
             cursor_shape_manager_state,
             dnd_icon: None,
+            toplevel_drag: None,
             pointer_contents: PointContents::default(),
             pointer_visibility: PointerVisibility::Visible,
             pointer_inactivity_timer: None,
             pointer_inactivity_timer_got_reset: false,
             notified_activity_this_iteration: false,
-            pointer_inside_hot_corner: false,
+            hot_corner: None,
             tablet_cursor_location: None,
             gesture_swipe_3f_cumulative: None,
+            gesture_swipe_3f_transposed: false,
             overview_scroll_swipe_gesture: ScrollSwipeGesture::new(),
             vertical_wheel_tracker: ScrollTracker::new(120),
             horizontal_wheel_tracker: ScrollTracker::new(120),
@@ -2647,8 +3267,21 @@ impl Niri {
 
             screenshot_ui,
             config_error_notification,
+            night_light,
+            is_night_light_supported: Arc::new(AtomicBool::new(is_night_light_supported)),
             hotkey_overlay,
+            overview_search_bar: OverviewSearchBar::new(),
+            window_switcher: WindowSwitcher::new(),
+            window_hints: WindowHints::new(),
             exit_confirm_dialog,
+            remote_desktop_indicator: RemoteDesktopIndicator::new(),
+            screencast_indicator: ScreencastIndicator::new(),
+            recording_indicator: RecordingIndicator::new(),
+            screenshot_countdown: ScreenshotCountdown::new(),
+
+            recording: None,
+
+            pending_screenshot: None,
 
             pick_window: None,
             pick_color: None,
@@ -2665,6 +3298,9 @@ impl Niri {
             ipc_outputs_changed: false,
 
             satellite: None,
+            bell_spawn_time: None,
+            #[cfg(test)]
+            bell_spawn_count: 0,
 
             pipewire: None,
             casts: vec![],
@@ -2676,9 +3312,15 @@ impl Niri {
 
             #[cfg(feature = "xdp-gnome-screencast")]
             dynamic_cast_id_for_portal: MappedId::next(),
+
+            remote_desktop_sessions: HashMap::new(),
+
+            global_shortcuts: Vec::new(),
+            pressed_global_shortcuts: HashMap::new(),
         };
 
         niri.reset_pointer_inactivity_timer();
+        niri.schedule_idle_timer();
 
         niri
     }
@@ -2924,7 +3566,10 @@ impl Niri {
             lock_surface: None,
             lock_color_buffer: SolidColorBuffer::new(size, CLEAR_COLOR_LOCKED),
             screen_transition: None,
+            bell_flash: None,
+            night_light_temperature: None,
             debug_damage_tracker: OutputDamageTracker::from_output(&output),
+            color_description: ColorDescription::SRGB,
         };
         let rv = self.output_state.insert(output.clone(), state);
         assert!(rv.is_none(), "output was already tracked");
@@ -3089,22 +3734,82 @@ impl Niri {
         Some((output, pos_within_output))
     }
 
-    pub fn is_sticky_obscured_under(
+    /// Returns the hot corner at the given position along with its action.
+    ///
+    /// The pointer has to press against a screen edge within the corner, so that the rest of the
+    /// corner area keeps working as usual.
+    pub fn hot_corner_under(
         &self,
         output: &Output,
         pos_within_output: Point<f64, Logical>,
-    ) -> bool {
-        // The ordering here must be consistent with the ordering in render() so that input is
-        // consistent with the visuals.
+    ) -> Option<(HotCorner, Action)> {
+        let config = self.config.borrow();
+        let hot_corners = &config.gestures.hot_corners;
+        if hot_corners.off {
+            return None;
+        }
 
-        // Check if some layer-shell surface is on top.
-        let layers = layer_map_for_output(output);
-        let layer_surface_under = |layer, popup| {
-            layers
-                .layers_on(layer)
-                .rev()
-                .find_map(|layer| {
-                    let mapped = self.mapped_layer_surfaces.get(layer)?;
+        let size = hot_corners.size.0;
+        let output_size = output_size(output);
+        let left = pos_within_output.x < size;
+        let right = pos_within_output.x >= output_size.w - size;
+        let top = pos_within_output.y < size;
+        let bottom = pos_within_output.y >= output_size.h - size;
+
+        let corner = match (left, right, top, bottom) {
+            (true, _, true, _) => HotCorner::TopLeft,
+            (_, true, true, _) => HotCorner::TopRight,
+            (true, _, _, true) => HotCorner::BottomLeft,
+            (_, true, _, true) => HotCorner::BottomRight,
+            _ => return None,
+        };
+
+        // An edge shared with a neighboring output isn't a screen edge, as the pointer crosses it.
+        let output_loc = self.global_space.output_geometry(output)?.loc.to_f64();
+        let pos = output_loc + pos_within_output;
+        let is_screen_edge = |dx: f64, dy: f64| {
+            let beyond = pos + Point::from((dx, dy));
+            self.global_space.output_under(beyond).next().is_none()
+        };
+        let at_left = pos_within_output.x < 1. && is_screen_edge(-1., 0.);
+        let at_right = pos_within_output.x >= output_size.w - 1. && is_screen_edge(1., 0.);
+        let at_top = pos_within_output.y < 1. && is_screen_edge(0., -1.);
+        let at_bottom = pos_within_output.y >= output_size.h - 1. && is_screen_edge(0., 1.);
+
+        let is_pressing = match corner {
+            HotCorner::TopLeft => at_left || at_top,
+            HotCorner::TopRight => at_right || at_top,
+            HotCorner::BottomLeft => at_left || at_bottom,
+            HotCorner::BottomRight => at_right || at_bottom,
+        };
+        if !is_pressing {
+            return None;
+        }
+
+        let output_config = hot_corners
+            .outputs
+            .iter()
+            .find(|x| output_matches_name(output, &x.name));
+        let action = hot_corners.action(output_config, corner)?;
+        Some((corner, action))
+    }
+
+    pub fn is_sticky_obscured_under(
+        &self,
+        output: &Output,
+        pos_within_output: Point<f64, Logical>,
+    ) -> bool {
+        // The ordering here must be consistent with the ordering in render() so that input is
+        // consistent with the visuals.
+
+        // Check if some layer-shell surface is on top.
+        let layers = layer_map_for_output(output);
+        let layer_surface_under = |layer, popup| {
+            layers
+                .layers_on(layer)
+                .rev()
+                .find_map(|layer| {
+                    let mapped = self.mapped_layer_surfaces.get(layer)?;
 
                     let mut layer_pos_within_output =
                         layers.layer_geometry(layer).unwrap().loc.to_f64();
@@ -3132,12 +3837,8 @@ impl Niri {
             return false;
         }
 
-        let hot_corners = self.config.borrow().gestures.hot_corners;
-        if !hot_corners.off {
-            let hot_corner = Rectangle::from_size(Size::from((1., 1.)));
-            if hot_corner.contains(pos_within_output) {
-                return true;
-            }
+        if self.hot_corner_under(output, pos_within_output).is_some() {
+            return true;
         }
 
         if layer_popup_under(Layer::Top) || layer_toplevel_under(Layer::Top) {
@@ -3266,6 +3967,85 @@ impl Niri {
         self.window_under(pos)
     }
 
+    /// Returns the windows to show window hints on.
+    ///
+    /// These are the windows currently visible on any output, including in the overview.
+    pub fn window_hint_windows(&self) -> Vec<Window> {
+        let mut windows = Vec::new();
+        for output in self.global_space.outputs() {
+            let Some(mon) = self.layout.monitor_for_output(output) else {
+                continue;
+            };
+
+            let output_rect = Rectangle::from_size(output_size(output));
+            let mut visible = mon.windows_with_visual_geometries();
+            visible.retain(|(_, geo)| geo.intersection(output_rect).is_some());
+
+            // Assign hints from left to right, so that the labels are easy to predict.
+            visible.sort_by(|(_, a), (_, b)| {
+                f64::total_cmp(&a.loc.x, &b.loc.x).then(f64::total_cmp(&a.loc.y, &b.loc.y))
+            });
+
+            for (mapped, _) in visible {
+                if !windows.contains(&mapped.window) {
+                    windows.push(mapped.window.clone());
+                }
+            }
+        }
+        windows
+    }
+
+    /// Returns the windows to cycle through in the window switcher.
+    ///
+    /// The windows are sorted in most-recently-used order, so the currently focused window comes
+    /// first.
+    pub fn window_switcher_windows(
+        &self,
+        scope: niri_ipc::WindowSwitcherScope,
+        filter: niri_ipc::WindowSwitcherFilter,
+    ) -> Vec<Window> {
+        let active_output = self.layout.active_output();
+        let active_ws_id = self.layout.active_workspace().map(|ws| ws.id());
+
+        let app_id = match filter {
+            niri_ipc::WindowSwitcherFilter::None => None,
+            niri_ipc::WindowSwitcherFilter::AppId => self.layout.focus().and_then(|mapped| {
+                with_toplevel_role(mapped.toplevel(), |role| role.app_id.clone())
+            }),
+        };
+
+        let mut windows = Vec::new();
+        self.layout.with_windows(|mapped, output, ws_id| {
+            let in_scope = match scope {
+                niri_ipc::WindowSwitcherScope::All => true,
+                niri_ipc::WindowSwitcherScope::Workspace => {
+                    ws_id.is_some() && ws_id == active_ws_id
+                }
+                niri_ipc::WindowSwitcherScope::Output => {
+                    output.is_some() && output == active_output
+                }
+            };
+            if !in_scope {
+                return;
+            }
+
+            if let Some(app_id) = &app_id {
+                let matches = with_toplevel_role(mapped.toplevel(), |role| {
+                    role.app_id.as_ref() == Some(app_id)
+                });
+                if !matches {
+                    return;
+                }
+            }
+
+            windows.push((mapped.focus_timestamp(), mapped.window.clone()));
+        });
+
+        // Windows that were never focused go last.
+        windows.sort_by(|(a, _), (b, _)| b.cmp(a));
+        windows.into_iter().map(|(_, window)| window).collect()
+    }
+
     /// Returns contents under the given point.
     ///
     /// We don't have a proper global space for all windows, so this function converts window
@@ -3360,23 +4140,36 @@ impl Niri {
 
         let mapped_hit_data = |(mapped, hit): (&Mapped, HitType)| {
             let window = &mapped.window;
-            let surface_and_pos = if let HitType::Input { win_pos } = hit {
-                let win_pos_within_output = win_pos;
-                window
-                    .surface_under(
-                        pos_within_output - win_pos_within_output,
-                        WindowSurfaceType::ALL,
-                    )
-                    .map(|(s, pos_within_window)| {
-                        (s, pos_within_window.to_f64() + win_pos_within_output)
-                    })
-            } else {
-                None
+            // Modal dialogs block input to their parent, but clicking the parent still activates
+            // it, which moves the focus to the dialog.
+            let is_blocked = self.layout.blocking_modal(window).is_some();
+            let surface_and_pos = match hit {
+                HitType::Input { win_pos } if !is_blocked => {
+                    let win_pos_within_output = win_pos;
+                    window
+                        .surface_under(
+                            pos_within_output - win_pos_within_output,
+                            WindowSurfaceType::ALL,
+                        )
+                        .map(|(s, pos_within_window)| {
+                            (s, pos_within_window.to_f64() + win_pos_within_output)
+                        })
+                }
+                _ => None,
             };
             (surface_and_pos, (Some((window.clone(), hit)), None))
         };
 
         let interactive_moved_window_under = || {
+            // The window attached to a toplevel drag must not become the drop target.
+            if self
+                .toplevel_drag
+                .as_ref()
+                .is_some_and(|drag| drag.is_moving)
+            {
+                return None;
+            }
+
             self.layout
                 .interactive_moved_window_under(output, pos_within_output)
                 .map(mapped_hit_data)
@@ -3407,12 +4200,8 @@ impl Niri {
                 .or_else(|| layer_toplevel_under(Layer::Bottom))
                 .or_else(|| layer_toplevel_under(Layer::Background));
         } else {
-            let hot_corners = self.config.borrow().gestures.hot_corners;
-            if !hot_corners.off {
-                let hot_corner = Rectangle::from_size(Size::from((1., 1.)));
-                if hot_corner.contains(pos_within_output) {
-                    return rv;
-                }
+            if self.hot_corner_under(output, pos_within_output).is_some() {
+                return rv;
             }
 
             under = under
@@ -3914,6 +4703,7 @@ impl Niri {
             // layer-shell, the layout will briefly draw as active, despite never having focus.
             KeyboardFocus::LockScreen { .. } => true,
             KeyboardFocus::ScreenshotUi => true,
+            KeyboardFocus::WindowHints => true,
             KeyboardFocus::Overview => true,
         };
 
@@ -3932,6 +4722,44 @@ impl Niri {
                 })
             });
         self.idle_notifier_state.set_is_inhibited(is_inhibited);
+
+        if self
+            .idle_tracker
+            .set_inhibited(is_inhibited, get_monotonic_time())
+        {
+            self.schedule_idle_timer();
+        }
+    }
+
+    pub fn refresh_preferred_color_descriptions(&mut self) {
+        let _span = tracy_client::span!("Niri::refresh_preferred_color_descriptions");
+
+        let output_state = &self.output_state;
+        self.color_management_state
+            .refresh_preferred(|surface| preferred_color_description(output_state, surface));
+    }
+
+    /// Returns the color encoding that the surface should preferably use.
+    pub fn preferred_color_description(&self, surface: &WlSurface) -> ColorDescription {
+        preferred_color_description(&self.output_state, surface)
+    }
+
+    /// Sets the color encoding that the output expects.
+    pub fn set_output_color_description(&mut self, output: &Output, desc: ColorDescription) {
+        let Some(state) = self.output_state.get_mut(output) else {
+            error!("missing output state for {}", output.name());
+            return;
+        };
+
+        if state.color_description == desc {
+            return;
+        }
+
+        debug!("setting {} color description to {desc:?}", output.name());
+        state.color_description = desc;
+        self.color_management_state
+            .output_description_changed(output);
+        self.queue_redraw(output);
     }
 
     pub fn refresh_window_states(&mut self) {
@@ -4039,12 +4867,71 @@ impl Niri {
         }
     }
 
+    #[cfg(feature = "xdp-gnome-screencast")]
+    pub fn refresh_workspace_casts(&mut self) {
+        let mut removed = vec![];
+        let mut to_stop = vec![];
+
+        for cast in &mut self.casts {
+            let CastTarget::Workspace { id } = cast.target else {
+                continue;
+            };
+
+            let output = match workspace_cast_state(&self.layout, id) {
+                WorkspaceCastState::Removed => {
+                    removed.push(id);
+                    continue;
+                }
+                WorkspaceCastState::NoOutput => continue,
+                WorkspaceCastState::Output(output) => output,
+            };
+
+            // Follow the refresh rate of the output that the workspace is on.
+            let refresh = output.current_mode().unwrap().refresh as u32;
+            if let Err(err) = cast.set_refresh(refresh) {
+                warn!("error changing cast FPS: {err:?}");
+                to_stop.push(cast.session_id);
+            }
+        }
+
+        for session_id in to_stop {
+            self.stop_cast(session_id);
+        }
+
+        for id in removed {
+            self.stop_casts_for_target(CastTarget::Workspace { id });
+        }
+    }
+
+    /// Returns the output that the workspace targeted by a cast is currently on.
+    pub fn workspace_cast_output(&self, id: u64) -> Option<Output> {
+        match workspace_cast_state(&self.layout, id) {
+            WorkspaceCastState::Output(output) => Some(output),
+            WorkspaceCastState::Removed | WorkspaceCastState::NoOutput => None,
+        }
+    }
+
+    /// Returns the ids of the workspaces on this output targeted by active casts.
+    #[cfg(feature = "xdp-gnome-screencast")]
+    fn cast_workspace_ids(&self, output: &Output) -> Vec<u64> {
+        self.casts
+            .iter()
+            .filter(|cast| cast.is_active.get())
+            .filter_map(|cast| match cast.target {
+                CastTarget::Workspace { id } => Some(id),
+                _ => None,
+            })
+            .filter(|id| self.workspace_cast_output(*id).as_ref() == Some(output))
+            .collect()
+    }
+
     pub fn advance_animations(&mut self) {
         let _span = tracy_client::span!("Niri::advance_animations");
 
         self.layout.advance_animations();
         self.config_error_notification.advance_animations();
         self.screenshot_ui.advance_animations();
+        self.night_light.advance_animations();
 
         for state in self.output_state.values_mut() {
             if let Some(transition) = &mut state.screen_transition {
@@ -4052,6 +4939,12 @@ impl Niri {
                     state.screen_transition = None;
                 }
             }
+
+            if let Some(flash) = &mut state.bell_flash {
+                if flash.is_done() {
+                    state.bell_flash = None;
+                }
+            }
         }
     }
 
@@ -4067,6 +4960,23 @@ impl Niri {
                     transition.update_render_elements(scale, transform);
                 }
 
+                if let Some(flash) = &mut state.bell_flash {
+                    let mut area = Rectangle::from_size(output_size(out));
+                    if let Some(window) = flash.window() {
+                        // Hide the flash while the window is not visible on this output.
+                        let mon = self.layout.monitor_for_output(out);
+                        let geo = mon.and_then(|mon| {
+                            let windows = mon.windows_with_visual_geometries();
+                            windows
+                                .into_iter()
+                                .find(|(mapped, _)| mapped.window == *window)
+                                .map(|(_, geo)| geo)
+                        });
+                        area = geo.unwrap_or_default();
+                    }
+                    flash.update_render_elements(area);
+                }
+
                 let layer_map = layer_map_for_output(out);
                 for surface in layer_map.layers() {
                     let Some(mapped) = self.mapped_layer_surfaces.get_mut(surface) else {
@@ -4122,6 +5032,11 @@ impl Niri {
             if let Some(transition) = &state.screen_transition {
                 elements.push(transition.render(target).into());
             }
+
+            // The bell flash goes right below.
+            if let Some(flash) = &state.bell_flash {
+                elements.push(flash.render().into());
+            }
         }
 
         // Next, the exit confirm dialog.
@@ -4136,6 +5051,43 @@ impl Niri {
             elements.push(element.into());
         }
 
+        // The remote desktop indicator is only for the local user, so keep it out of casts.
+        if !self.remote_desktop_sessions.is_empty() && target == RenderTarget::Output {
+            if let Some(element) = self.remote_desktop_indicator.render(renderer, output) {
+                elements.push(element.into());
+            }
+        }
+
+        // Same for the screencast indicator, which would otherwise end up in the cast itself.
+        if target == RenderTarget::Output && !self.is_locked() {
+            let config = self.config.borrow();
+            let config = &config.screencast_indicator;
+            if config.on {
+                let rects = self.screencast_indicator_rects(output);
+                let scale = output_scale.x;
+                let indicator = self.screencast_indicator.render(config, &rects, scale);
+                elements.extend(indicator.into_iter().map(OutputRenderElements::from));
+            }
+        }
+
+        // And for the recording indicator.
+        if self.recording.is_some() && target == RenderTarget::Output {
+            if let Some(element) = self.recording_indicator.render(renderer, output) {
+                elements.push(element.into());
+            }
+        }
+
+        // And for the screenshot countdown, which must not end up in the screenshot either.
+        if let Some(pending) = &self.pending_screenshot {
+            if target == RenderTarget::Output {
+                let remaining = pending.deadline.saturating_sub(get_monotonic_time());
+                let seconds = remaining.as_millis().div_ceil(1000).max(1) as u64;
+                if let Some(element) = self.screenshot_countdown.render(renderer, output, seconds) {
+                    elements.push(element.into());
+                }
+            }
+        }
+
         // If the session is locked, draw the lock surface.
         if self.is_locked() {
             let state = self.output_state.get(output).unwrap();
@@ -4188,7 +5140,7 @@ impl Niri {
         if self.screenshot_ui.is_open() {
             elements.extend(
                 self.screenshot_ui
-                    .render_output(output, target)
+                    .render_output(renderer, output, target)
                     .into_iter()
                     .map(OutputRenderElements::from),
             );
@@ -4207,6 +5159,31 @@ impl Niri {
             elements.push(element.into());
         }
 
+        // Draw the overview search query on the active output.
+        if self.layout.is_overview_open() && self.layout.active_output() == Some(output) {
+            let query = self.layout.overview_search();
+            if let Some(element) = self.overview_search_bar.render(renderer, output, query) {
+                elements.push(element.into());
+            }
+        }
+
+        // Draw the window switcher and window hints, but keep them out of screencasts and
+        // screenshots.
+        if target == RenderTarget::Output {
+            elements.extend(
+                self.window_hints
+                    .render(renderer, output, &self.layout)
+                    .into_iter()
+                    .map(OutputRenderElements::from),
+            );
+            elements.extend(
+                self.window_switcher
+                    .render(renderer, output, &self.layout)
+                    .into_iter()
+                    .map(OutputRenderElements::from),
+            );
+        }
+
         // Don't draw the focus ring on the workspaces while interactively moving above those
         // workspaces, since the interactively-moved window already has a focus ring.
         let focus_ring = !self.layout.interactive_move_is_moving_above_output(output);
@@ -4218,6 +5195,8 @@ impl Niri {
             mon.render_elements(renderer, target, focus_ring)
                 .map(|(geo, iter)| (geo, Vec::from_iter(iter))),
         );
+        let pinned_elements =
+            Vec::from_iter(mon.render_pinned_elements(renderer, target, focus_ring));
         let workspace_shadow_elements = Vec::from_iter(mon.render_workspace_shadows(renderer));
         let insert_hint_elements = mon.render_insert_hint_between_workspaces(renderer);
         let int_move_elements: Vec<_> = self
@@ -4255,6 +5234,7 @@ impl Niri {
                     .into_iter()
                     .map(OutputRenderElements::from),
             );
+            elements.extend(pinned_elements.into_iter().map(OutputRenderElements::from));
             elements.extend(
                 insert_hint_elements
                     .into_iter()
@@ -4286,6 +5266,8 @@ impl Niri {
                     .map(OutputRenderElements::from),
             );
 
+            elements.extend(pinned_elements.into_iter().map(OutputRenderElements::from));
+
             elements.extend(
                 insert_hint_elements
                     .into_iter()
@@ -4389,12 +5371,16 @@ impl Niri {
 
         let mut res = RenderResult::Skipped;
         if self.monitors_active {
+            self.apply_night_light(backend, output);
+
             let state = self.output_state.get_mut(output).unwrap();
             state.unfinished_animations_remain = self.layout.are_animations_ongoing(Some(output));
             state.unfinished_animations_remain |=
                 self.config_error_notification.are_animations_ongoing();
             state.unfinished_animations_remain |= self.screenshot_ui.are_animations_ongoing();
             state.unfinished_animations_remain |= state.screen_transition.is_some();
+            state.unfinished_animations_remain |= state.bell_flash.is_some();
+            state.unfinished_animations_remain |= self.night_light.are_animations_ongoing();
 
             // Also keep redrawing if the current cursor is animated.
             state.unfinished_animations_remain |= self
@@ -4491,8 +5477,12 @@ impl Niri {
                 // unimplemented, but happens to work by chance, since output
                 // redrawing is more eager than it should be.
                 self.render_windows_for_screen_cast(renderer, output, target_presentation_time);
+
+                self.render_workspaces_for_screen_cast(renderer, output, target_presentation_time);
             }
 
+            self.render_for_recording(renderer, output, target_presentation_time);
+
             self.render_for_screencopy_with_damage(renderer, output);
         });
     }
@@ -4757,16 +5747,7 @@ impl Niri {
         let state = self.output_state.get(output).unwrap();
         let sequence = state.frame_callback_sequence;
 
-        let should_send = |surface: &WlSurface, states: &SurfaceData| {
-            // Do the standard primary scanout output check. For pointer surfaces it deduplicates
-            // the frame callbacks across potentially multiple outputs, and for regular windows and
-            // layer-shell surfaces it avoids sending frame callbacks to invisible surfaces.
-            let current_primary_output = surface_primary_scanout_output(surface, states);
-            if current_primary_output.as_ref() != Some(output) {
-                return None;
-            }
-
-            // Next, check the throttling status.
+        let throttle = |states: &SurfaceData| {
             let frame_throttling_state = states
                 .data_map
                 .get_or_insert(SurfaceFrameThrottlingState::default);
@@ -4790,6 +5771,19 @@ impl Niri {
             }
         };
 
+        let should_send = |surface: &WlSurface, states: &SurfaceData| {
+            // Do the standard primary scanout output check. For pointer surfaces it deduplicates
+            // the frame callbacks across potentially multiple outputs, and for regular windows and
+            // layer-shell surfaces it avoids sending frame callbacks to invisible surfaces.
+            let current_primary_output = surface_primary_scanout_output(surface, states);
+            if current_primary_output.as_ref() != Some(output) {
+                return None;
+            }
+
+            // Next, check the throttling status.
+            throttle(states)
+        };
+
         let frame_callback_time = get_monotonic_time();
 
         for mapped in self.layout.windows_for_output_mut(output) {
@@ -4801,6 +5795,29 @@ impl Niri {
             );
         }
 
+        // Windows on hidden workspaces aren't scanned out anywhere, but when such a workspace is
+        // being cast, they should still draw at the output refresh rate for the cast.
+        #[cfg(feature = "xdp-gnome-screencast")]
+        {
+            let ids = self.cast_workspace_ids(output);
+            if !ids.is_empty() {
+                let workspaces = self
+                    .layout
+                    .workspaces_mut()
+                    .filter(|ws| ids.contains(&ws.id().get()));
+                for ws in workspaces {
+                    for mapped in ws.windows_mut() {
+                        mapped.send_frame(
+                            output,
+                            frame_callback_time,
+                            FRAME_CALLBACK_THROTTLE,
+                            |_, states| throttle(states),
+                        );
+                    }
+                }
+            }
+        }
+
         for surface in layer_map_for_output(output).layers() {
             surface.send_frame(
                 output,
@@ -4981,7 +5998,7 @@ impl Niri {
     ) {
         let _span = tracy_client::span!("Niri::render_for_screen_cast");
 
-        let target = CastTarget::Output(output.downgrade());
+        let weak = output.downgrade();
 
         let size = output.current_mode().unwrap().size;
         let transform = output.current_transform();
@@ -5002,11 +6019,16 @@ impl Niri {
                 continue;
             }
 
-            if cast.target != target {
-                continue;
-            }
+            let crop = match &cast.target {
+                CastTarget::Output(o) if *o == weak => None,
+                CastTarget::Area { output: o, rect } if *o == weak => {
+                    Some(cast_area_crop(output, *rect))
+                }
+                _ => continue,
+            };
+            let cast_size = crop.map_or(size, |crop| crop.size);
 
-            match cast.ensure_size(size) {
+            match cast.ensure_size(cast_size) {
                 Ok(CastSizeChange::Ready) => (),
                 Ok(CastSizeChange::Pending) => continue,
                 Err(err) => {
@@ -5024,7 +6046,19 @@ impl Niri {
                 self.render(renderer, output, true, RenderTarget::Screencast)
             });
 
-            if cast.dequeue_buffer_and_render(renderer, elements, size, scale, wait_for_sync) {
+            let rendered = if let Some(crop) = crop {
+                cast.dequeue_buffer_and_render_cropped(
+                    renderer,
+                    elements,
+                    crop.loc,
+                    crop.size,
+                    scale,
+                    wait_for_sync,
+                )
+            } else {
+                cast.dequeue_buffer_and_render(renderer, elements, size, scale, wait_for_sync)
+            };
+            if rendered {
                 cast.last_frame_time = target_presentation_time;
             }
         }
@@ -5100,58 +6134,178 @@ impl Niri {
         }
     }
 
-    pub fn render_for_screencopy_with_damage(
+    #[cfg(feature = "xdp-gnome-screencast")]
+    fn render_workspaces_for_screen_cast(
         &mut self,
         renderer: &mut GlesRenderer,
         output: &Output,
+        target_presentation_time: Duration,
     ) {
-        let _span = tracy_client::span!("Niri::render_for_screencopy_with_damage");
+        let _span = tracy_client::span!("Niri::render_workspaces_for_screen_cast");
 
-        let mut screencopy_state = mem::take(&mut self.screencopy_state);
-        let elements = OnceCell::new();
+        let size = output.current_mode().unwrap().size;
+        let transform = output.current_transform();
+        let size = transform.transform_size(size);
 
-        for queue in screencopy_state.queues_mut() {
-            let (damage_tracker, screencopy) = queue.split();
-            if let Some(screencopy) = screencopy {
-                if screencopy.output() == output {
-                    let elements = elements.get_or_init(|| {
-                        self.render(renderer, output, true, RenderTarget::ScreenCapture)
-                    });
-                    // FIXME: skip elements if not including pointers
-                    let render_result = Self::render_for_screencopy_internal(
-                        renderer,
-                        output,
-                        elements,
-                        true,
-                        damage_tracker,
-                        screencopy,
-                    );
-                    match render_result {
-                        Ok((sync, damages)) => {
-                            if let Some(damages) = damages {
-                                // Convert from Physical coordinates back to Buffer coordinates.
-                                let transform = output.current_transform();
-                                let physical_size =
-                                    transform.transform_size(screencopy.buffer_size());
-                                let damages = damages.iter().map(|dmg| {
-                                    dmg.to_logical(1).to_buffer(
-                                        1,
-                                        transform.invert(),
-                                        &physical_size.to_logical(1),
-                                    )
-                                });
+        let scale = Scale::from(output.current_scale().fractional_scale());
 
-                                screencopy.damage(damages);
-                                queue.pop().submit_after_sync(false, sync, &self.event_loop);
-                            } else {
-                                trace!("no damage found, waiting till next redraw");
-                            }
-                        }
-                        Err(err) => {
-                            // Recreate damage tracker to report full damage next check.
-                            *damage_tracker =
-                                OutputDamageTracker::new((0, 0), 1.0, Transform::Normal);
-                            queue.pop();
+        let config = self.config.borrow();
+        let wait_for_sync = config.debug.wait_for_frame_completion_in_pipewire;
+        drop(config);
+
+        let mut casts_to_stop = vec![];
+
+        let mut casts = mem::take(&mut self.casts);
+        for cast in &mut casts {
+            if !cast.is_active.get() {
+                continue;
+            }
+
+            let CastTarget::Workspace { id } = cast.target else {
+                continue;
+            };
+
+            let mut workspaces = self.layout.workspaces();
+            let Some((Some(mon), _, ws)) = workspaces.find(|(_, _, ws)| ws.id().get() == id) else {
+                continue;
+            };
+            if mon.output() != output {
+                continue;
+            }
+
+            match cast.ensure_size(size) {
+                Ok(CastSizeChange::Ready) => (),
+                Ok(CastSizeChange::Pending) => continue,
+                Err(err) => {
+                    warn!("error updating stream size, stopping screencast: {err:?}");
+                    casts_to_stop.push(cast.session_id);
+                }
+            }
+
+            if cast.check_time_and_schedule(&self.event_loop, output, target_presentation_time) {
+                continue;
+            }
+
+            // Render the workspace on its own, regardless of whether it's currently visible on
+            // the output and of the overview zoom.
+            //
+            // FIXME: Hidden / embedded / metadata cursor
+            let mut elements: Vec<WorkspaceCastRenderElements<_>> = vec![];
+            if self.output_under_cursor().as_ref() == Some(output) {
+                elements.extend(
+                    self.pointer_element(renderer, output)
+                        .into_iter()
+                        .map(WorkspaceCastRenderElements::from),
+                );
+            }
+
+            let (floating, scrolling) =
+                ws.render_elements(renderer, RenderTarget::Screencast, false);
+            let ws_elements = floating
+                .chain(scrolling)
+                .map(WorkspaceCastRenderElements::from)
+                .collect::<Vec<_>>();
+
+            let layer_map = layer_map_for_output(output);
+            let mut extend_from_layer = |elements: &mut Vec<_>, layer| {
+                let mut layer_elems = SplitElements::default();
+                let target = RenderTarget::Screencast;
+                self.render_layer(renderer, target, &layer_map, layer, &mut layer_elems, false);
+                elements.extend(
+                    layer_elems
+                        .into_iter()
+                        .map(WorkspaceCastRenderElements::from),
+                );
+            };
+
+            extend_from_layer(&mut elements, Layer::Overlay);
+
+            // A fullscreen window goes above the top layer, like on the output itself.
+            let above_top_layer = ws.render_above_top_layer();
+            if !above_top_layer {
+                extend_from_layer(&mut elements, Layer::Top);
+            }
+
+            elements.extend(ws_elements);
+
+            if above_top_layer {
+                extend_from_layer(&mut elements, Layer::Top);
+            }
+            extend_from_layer(&mut elements, Layer::Bottom);
+            extend_from_layer(&mut elements, Layer::Background);
+
+            let state = self.output_state.get(output).unwrap();
+            let background = SolidColorRenderElement::from_buffer(
+                &state.background_buffer,
+                (0., 0.),
+                1.,
+                Kind::Unspecified,
+            );
+            elements.push(background.into());
+
+            if cast.dequeue_buffer_and_render(renderer, &elements, size, scale, wait_for_sync) {
+                cast.last_frame_time = target_presentation_time;
+            }
+        }
+        self.casts = casts;
+
+        for id in casts_to_stop {
+            self.stop_cast(id);
+        }
+    }
+
+    pub fn render_for_screencopy_with_damage(
+        &mut self,
+        renderer: &mut GlesRenderer,
+        output: &Output,
+    ) {
+        let _span = tracy_client::span!("Niri::render_for_screencopy_with_damage");
+
+        let mut screencopy_state = mem::take(&mut self.screencopy_state);
+        let elements = OnceCell::new();
+
+        for queue in screencopy_state.queues_mut() {
+            let (damage_tracker, screencopy) = queue.split();
+            if let Some(screencopy) = screencopy {
+                if screencopy.output() == output {
+                    let elements = elements.get_or_init(|| {
+                        self.render(renderer, output, true, RenderTarget::ScreenCapture)
+                    });
+                    // FIXME: skip elements if not including pointers
+                    let render_result = Self::render_for_screencopy_internal(
+                        renderer,
+                        output,
+                        elements,
+                        true,
+                        damage_tracker,
+                        screencopy,
+                    );
+                    match render_result {
+                        Ok((sync, damages)) => {
+                            if let Some(damages) = damages {
+                                // Convert from Physical coordinates back to Buffer coordinates.
+                                let transform = output.current_transform();
+                                let physical_size =
+                                    transform.transform_size(screencopy.buffer_size());
+                                let damages = damages.iter().map(|dmg| {
+                                    dmg.to_logical(1).to_buffer(
+                                        1,
+                                        transform.invert(),
+                                        &physical_size.to_logical(1),
+                                    )
+                                });
+
+                                screencopy.damage(damages);
+                                queue.pop().submit_after_sync(false, sync, &self.event_loop);
+                            } else {
+                                trace!("no damage found, waiting till next redraw");
+                            }
+                        }
+                        Err(err) => {
+                            // Recreate damage tracker to report full damage next check.
+                            *damage_tracker =
+                                OutputDamageTracker::new((0, 0), 1.0, Transform::Normal);
+                            queue.pop();
                             warn!("error rendering for screencopy: {err:?}");
                         }
                     }
@@ -5272,8 +6426,11 @@ impl Niri {
         Ok((sync, damages))
     }
 
+    #[cfg(not(feature = "xdp-gnome-screencast"))]
+    pub fn stop_cast(&mut self, _session_id: usize) {}
+
     #[cfg(feature = "xdp-gnome-screencast")]
-    fn stop_cast(&mut self, session_id: usize) {
+    pub fn stop_cast(&mut self, session_id: usize) {
         let _span = tracy_client::span!("Niri::stop_cast");
 
         debug!(session_id, "StopCast");
@@ -5290,6 +6447,8 @@ impl Niri {
             }
         }
 
+        self.queue_redraw_screencast_indicator();
+
         let dbus = &self.dbus.as_ref().unwrap();
         let server = dbus.conn_screen_cast.as_ref().unwrap().object_server();
         let path = format!("/org/gnome/Mutter/ScreenCast/Session/u{session_id}");
@@ -5303,6 +6462,214 @@ impl Niri {
                     .await
             });
         }
+
+        // Stopping the screencast also stops the remote desktop session that it's paired with.
+        let remote_desktop = self
+            .remote_desktop_sessions
+            .iter()
+            .find(|(_, screen_cast)| **screen_cast == Some(session_id))
+            .map(|(id, _)| *id);
+        if let Some(id) = remote_desktop {
+            self.stop_remote_desktop_session(id);
+        }
+    }
+
+    #[cfg(feature = "xdp-gnome-screencast")]
+    fn stop_remote_desktop_session(&mut self, session_id: usize) {
+        let _span = tracy_client::span!("Niri::stop_remote_desktop_session");
+
+        debug!(session_id, "StopSession");
+
+        if let Some(Some(screen_cast)) = self.remote_desktop_sessions.remove(&session_id) {
+            self.stop_cast(screen_cast);
+        }
+        self.queue_redraw_all();
+
+        let dbus = &self.dbus.as_ref().unwrap();
+        let server = dbus.conn_remote_desktop.as_ref().unwrap().object_server();
+        let path = format!("/org/gnome/Mutter/RemoteDesktop/Session/u{session_id}");
+        if let Ok(iface) = server.interface::<_, mutter_remote_desktop::Session>(path) {
+            let _span = tracy_client::span!("invoking Session::stop");
+
+            async_io::block_on(async move {
+                iface
+                    .get()
+                    .stop(server.inner(), iface.signal_emitter().clone())
+                    .await
+            });
+        }
+    }
+
+    #[cfg(not(feature = "xdp-gnome-screencast"))]
+    pub fn stop_remote_desktop_sessions(&mut self) {}
+
+    #[cfg(feature = "xdp-gnome-screencast")]
+    pub fn stop_remote_desktop_sessions(&mut self) {
+        let ids: Vec<_> = self.remote_desktop_sessions.keys().copied().collect();
+        for id in ids {
+            self.stop_remote_desktop_session(id);
+        }
+    }
+
+    pub fn update_night_light_schedule(&mut self) {
+        if self.night_light.update_schedule() {
+            self.queue_redraw_all();
+        }
+    }
+
+    fn apply_night_light(&mut self, backend: &mut Backend, output: &Output) {
+        let Some(tty) = backend.tty_checked() else {
+            return;
+        };
+
+        // Don't fight with wlr-gamma-control clients.
+        if self.gamma_control_manager_state.has_gamma_control(output) {
+            return;
+        }
+
+        let temperature = self.night_light.temperature();
+        let temperature = (temperature != night_light::NEUTRAL_TEMPERATURE).then_some(temperature);
+
+        let state = self.output_state.get_mut(output).unwrap();
+        if state.night_light_temperature == temperature {
+            return;
+        }
+        state.night_light_temperature = temperature;
+
+        let _span = tracy_client::span!("Niri::apply_night_light");
+
+        let ramp = match temperature {
+            Some(temperature) => match tty.get_gamma_size(output) {
+                Ok(0) => return,
+                Ok(size) => Some(night_light::gamma_ramp(size, temperature)),
+                Err(err) => {
+                    debug!("error getting gamma size for {}: {err:?}", output.name());
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        if let Err(err) = tty.set_gamma(output, ramp) {
+            warn!(
+                "error setting night light gamma for {}: {err:?}",
+                output.name()
+            );
+        }
+    }
+
+    pub fn on_global_shortcuts_changed(&mut self) {
+        let keys = self.global_shortcuts.iter().map(|s| s.key).collect();
+        self.hotkey_overlay.set_global_shortcuts(keys);
+        if self.hotkey_overlay.is_open() {
+            self.queue_redraw_all();
+        }
+    }
+
+    #[cfg(not(feature = "dbus"))]
+    pub fn notify_global_shortcuts(&self, _ids: &[u32], _activated: bool, _time: u32) {}
+
+    #[cfg(feature = "dbus")]
+    pub fn notify_global_shortcuts(&self, ids: &[u32], activated: bool, time: u32) {
+        let _span = tracy_client::span!("Niri::notify_global_shortcuts");
+
+        let Some(dbus) = &self.dbus else {
+            return;
+        };
+        let Some(conn) = &dbus.conn_shell else {
+            return;
+        };
+
+        let server = conn.object_server();
+        let Ok(iface) = server.interface::<_, gnome_shell::Shell>("/org/gnome/Shell") else {
+            return;
+        };
+
+        let action_mode = self.action_mode();
+        for &id in ids {
+            // The shortcut may have been ungrabbed while the key was held.
+            let Some(shortcut) = self.global_shortcuts.iter().find(|s| s.id == id) else {
+                continue;
+            };
+            let Ok(sender) = zbus::names::UniqueName::try_from(shortcut.sender.clone()) else {
+                continue;
+            };
+
+            let params = HashMap::from([
+                ("timestamp", zbus::zvariant::Value::U32(time)),
+                (
+                    "action-mode",
+                    zbus::zvariant::Value::U32(action_mode.bits()),
+                ),
+            ]);
+
+            // Only the client that grabbed the accelerator should learn about the key press.
+            let emitter = iface
+                .signal_emitter()
+                .clone()
+                .set_destination(sender.into());
+            let res = async_io::block_on(async move {
+                if activated {
+                    gnome_shell::Shell::accelerator_activated(&emitter, id, params).await
+                } else {
+                    gnome_shell::Shell::accelerator_deactivated(&emitter, id, params).await
+                }
+            });
+            if let Err(err) = res {
+                warn!("error emitting global shortcut signal: {err:?}");
+            }
+        }
+    }
+
+    #[cfg(not(feature = "xdp-gnome-screencast"))]
+    fn screencast_indicator_rects(&self, _output: &Output) -> Vec<Rectangle<f64, Logical>> {
+        Vec::new()
+    }
+
+    /// Returns the output-local rectangles that are being screencast on this output.
+    #[cfg(feature = "xdp-gnome-screencast")]
+    fn screencast_indicator_rects(&self, output: &Output) -> Vec<Rectangle<f64, Logical>> {
+        let weak = output.downgrade();
+
+        let mut rects = Vec::new();
+        for cast in &self.casts {
+            let rect = match &cast.target {
+                CastTarget::Nothing => None,
+                CastTarget::Output(o) => {
+                    (*o == weak).then(|| Rectangle::from_size(output_size(output)))
+                }
+                CastTarget::Area { output: o, rect } => (*o == weak).then(|| rect.to_f64()),
+                CastTarget::Workspace { id } => {
+                    self.layout.monitor_for_output(output).and_then(|mon| {
+                        mon.workspaces_with_render_geo()
+                            .find(|(ws, _)| ws.id().get() == *id)
+                            .map(|(_, geo)| geo)
+                    })
+                }
+                CastTarget::Window { id } => {
+                    let mut windows = self.layout.windows();
+                    windows
+                        .find(|(_, mapped)| mapped.id().get() == *id)
+                        .and_then(|(_, mapped)| self.layout.window_visual_rect(&mapped.id()))
+                        .and_then(|(o, rect)| (o == output).then_some(rect))
+                }
+            };
+
+            if let Some(rect) = rect {
+                if !rects.contains(&rect) {
+                    rects.push(rect);
+                }
+            }
+        }
+
+        rects
+    }
+
+    /// Redraws the outputs if the screencast indicator may have changed.
+    pub fn queue_redraw_screencast_indicator(&mut self) {
+        if self.config.borrow().screencast_indicator.on {
+            self.queue_redraw_all();
+        }
     }
 
     #[cfg(not(feature = "xdp-gnome-screencast"))]
@@ -5316,7 +6683,12 @@ impl Niri {
         let mut saw_dynamic = false;
         let mut ids = Vec::new();
         for cast in &self.casts {
-            if cast.target != target {
+            // Stopping an output also stops the area casts on it.
+            let matches = match (&cast.target, &target) {
+                (CastTarget::Area { output, .. }, CastTarget::Output(target)) => output == target,
+                (cast_target, target) => cast_target == target,
+            };
+            if !matches {
                 continue;
             }
 
@@ -5358,6 +6730,35 @@ impl Niri {
         self.queue_redraw_all();
     }
 
+    /// Returns the geometries of the visible windows on every output, topmost first.
+    ///
+    /// The geometries are in output-local physical coordinates, like the screenshot UI selection.
+    pub fn screenshot_window_rects(&self) -> HashMap<Output, Vec<Rectangle<i32, Physical>>> {
+        self.global_space
+            .outputs()
+            .map(|output| {
+                let size = output.current_mode().unwrap().size;
+                let transform = output.current_transform();
+                let output_rect = Rectangle::from_size(transform.transform_size(size));
+                let scale = output.current_scale().fractional_scale();
+
+                let rects = self
+                    .layout
+                    .monitor_for_output(output)
+                    .map(|mon| mon.window_visual_geometries())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|geo| {
+                        geo.to_physical_precise_round(scale)
+                            .intersection(output_rect)
+                    })
+                    .filter(|rect| !rect.size.is_empty())
+                    .collect();
+                (output.clone(), rects)
+            })
+            .collect()
+    }
+
     pub fn capture_screenshots<'a>(
         &'a self,
         renderer: &'a mut GlesRenderer,
@@ -5426,6 +6827,197 @@ impl Niri {
         })
     }
 
+    pub fn start_recording(&mut self, target: CastTarget) {
+        self.stop_recording();
+
+        let config = self.config.borrow();
+        let max_fps = config.recording.max_fps;
+        let dir = match make_recording_path(&config) {
+            Ok(dir) => dir,
+            Err(err) => {
+                warn!("error making recording path: {err:?}");
+                return;
+            }
+        };
+        drop(config);
+
+        match Recording::start(target, dir, max_fps) {
+            Ok(recording) => {
+                info!("started recording to {:?}", recording.dir);
+                self.recording = Some(recording);
+            }
+            Err(err) => {
+                warn!("error starting recording: {err:?}");
+                return;
+            }
+        }
+
+        // Redraw to show the indicator and to capture the first frame.
+        self.queue_redraw_all();
+    }
+
+    pub fn stop_recording(&mut self) {
+        let Some(recording) = self.recording.take() else {
+            return;
+        };
+
+        info!("stopped recording to {:?}", recording.dir);
+        self.queue_redraw_all();
+    }
+
+    /// Returns whether the target of the ongoing recording went away.
+    pub fn is_recording_target_gone(&self) -> bool {
+        let Some(recording) = &self.recording else {
+            return false;
+        };
+
+        let alive = match &recording.target {
+            CastTarget::Output(output) | CastTarget::Area { output, .. } => output
+                .upgrade()
+                .is_some_and(|output| self.output_state.contains_key(&output)),
+            CastTarget::Window { id } => self
+                .layout
+                .windows()
+                .any(|(_, mapped)| mapped.id().get() == *id),
+            CastTarget::Nothing | CastTarget::Workspace { .. } => false,
+        };
+
+        !alive
+    }
+
+    fn render_for_recording(
+        &mut self,
+        renderer: &mut GlesRenderer,
+        output: &Output,
+        target_presentation_time: Duration,
+    ) {
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+
+        let _span = tracy_client::span!("Niri::render_for_recording");
+
+        // The previous frame has been rendered by now, so this won't wait for the GPU.
+        let mut res = recording.read_back(renderer);
+
+        let recording = self.recording.as_ref().unwrap();
+        if res.is_ok() && recording.should_capture(target_presentation_time) {
+            let target = recording.target.clone();
+
+            let size = output.current_mode().unwrap().size;
+            let transform = output.current_transform();
+            let size = transform.transform_size(size);
+
+            let scale = Scale::from(output.current_scale().fractional_scale());
+            let weak = output.downgrade();
+
+            res = match &target {
+                CastTarget::Output(o) if *o == weak => {
+                    let elements = self.render(renderer, output, true, RenderTarget::Screencast);
+                    let recording = self.recording.as_mut().unwrap();
+                    recording.render_frame(
+                        renderer,
+                        target_presentation_time,
+                        &elements,
+                        size,
+                        scale,
+                    )
+                }
+                CastTarget::Area { output: o, rect } if *o == weak => {
+                    let crop = cast_area_crop(output, *rect);
+                    let elements = self.render(renderer, output, true, RenderTarget::Screencast);
+                    let elements = elements
+                        .iter()
+                        .map(|elem| {
+                            RelocateRenderElement::from_element(
+                                elem,
+                                crop.loc.upscale(-1),
+                                Relocate::Relative,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    let recording = self.recording.as_mut().unwrap();
+                    recording.render_frame(
+                        renderer,
+                        target_presentation_time,
+                        &elements,
+                        crop.size,
+                        scale,
+                    )
+                }
+                CastTarget::Window { id } => self.render_window_for_recording(
+                    renderer,
+                    output,
+                    *id,
+                    target_presentation_time,
+                ),
+                _ => Ok(()),
+            };
+        }
+
+        if let Err(err) = res {
+            warn!("error rendering recording frame, stopping recording: {err:?}");
+            self.stop_recording();
+        }
+    }
+
+    fn render_window_for_recording(
+        &mut self,
+        renderer: &mut GlesRenderer,
+        output: &Output,
+        id: u64,
+        target_presentation_time: Duration,
+    ) -> anyhow::Result<()> {
+        let Some(mon) = self.layout.monitor_for_output(output) else {
+            return Ok(());
+        };
+        let mut windows = self.layout.windows_for_output(output);
+        let Some(mapped) = windows.find(|win| win.id().get() == id) else {
+            return Ok(());
+        };
+
+        let scale = Scale::from(output.current_scale().fractional_scale());
+        let bbox = mapped.window.bbox_with_popups();
+
+        let mut elements = vec![];
+
+        // Draw the pointer over the window if it's visible.
+        let geo = mon
+            .windows_with_visual_geometries()
+            .into_iter()
+            .find_map(|(win, geo)| (win.id() == mapped.id()).then_some(geo));
+        if let Some(geo) = geo {
+            if self.output_under_cursor().as_ref() == Some(output) {
+                // Location of the window bounding box within the output.
+                let window_loc = mapped.window.geometry().loc - bbox.loc;
+                let origin: Point<i32, Physical> =
+                    (geo.loc - window_loc.to_f64()).to_physical_precise_round(scale);
+                elements.extend(
+                    self.pointer_element(renderer, output)
+                        .into_iter()
+                        .map(|elem| {
+                            let elem = RelocateRenderElement::from_element(
+                                elem,
+                                origin.upscale(-1),
+                                Relocate::Relative,
+                            );
+                            WindowRecordingRenderElements::from(elem)
+                        }),
+                );
+            }
+        }
+
+        elements.extend(
+            mapped
+                .render_for_screen_cast(renderer, scale)
+                .map(WindowRecordingRenderElements::from),
+        );
+
+        let size = bbox.to_physical_precise_up(scale).size;
+        let recording = self.recording.as_mut().unwrap();
+        recording.render_frame(renderer, target_presentation_time, &elements, size, scale)
+    }
+
     pub fn screenshot(
         &mut self,
         renderer: &mut GlesRenderer,
@@ -5655,6 +7247,15 @@ impl Niri {
         Ok(())
     }
 
+    /// Returns the Shell action mode that global shortcuts are matched against.
+    pub fn action_mode(&self) -> ActionModes {
+        if self.layout.is_overview_open() {
+            ActionModes::OVERVIEW
+        } else {
+            ActionModes::NORMAL
+        }
+    }
+
     pub fn is_locked(&self) -> bool {
         match self.lock_state {
             LockState::Unlocked | LockState::WaitingForSurfaces { .. } => false,
@@ -6006,6 +7607,35 @@ impl Niri {
         }
     }
 
+    #[cfg(feature = "dbus")]
+    pub fn on_night_light_supported_changed(&self) {
+        let _span = tracy_client::span!("Niri::on_night_light_supported_changed");
+
+        let Some(dbus) = &self.dbus else { return };
+        let Some(conn) = &dbus.conn_display_config else {
+            return;
+        };
+
+        use crate::dbus::mutter_display_config::DisplayConfig;
+        let server = conn.object_server();
+        let Ok(iface) = server.interface::<_, DisplayConfig>("/org/gnome/Mutter/DisplayConfig")
+        else {
+            return;
+        };
+
+        let res = async_io::block_on(async move {
+            let emitter = iface.signal_emitter();
+            iface
+                .get()
+                .await
+                .night_light_supported_changed(emitter)
+                .await
+        });
+        if let Err(err) = res {
+            warn!("error emitting NightLightSupported change: {err:?}");
+        }
+    }
+
     pub fn handle_focus_follows_mouse(&mut self, new_focus: &PointContents) {
         let Some(ffm) = self.config.borrow().input.focus_follows_mouse else {
             return;
@@ -6061,6 +7691,16 @@ impl Niri {
         }
     }
 
+    /// Flashes the output, or only the given window on it.
+    pub fn flash_output(&mut self, output: &Output, window: Option<Window>) {
+        let Some(state) = self.output_state.get_mut(output) else {
+            return;
+        };
+
+        state.bell_flash = Some(BellFlash::new(self.clock.clone(), window));
+        self.queue_redraw(output);
+    }
+
     pub fn do_screen_transition(&mut self, renderer: &mut GlesRenderer, delay_ms: Option<u16>) {
         let _span = tracy_client::span!("Niri::do_screen_transition");
 
@@ -6240,8 +7880,81 @@ impl Niri {
 
         self.idle_notifier_state.notify_activity(&self.seat);
 
+        let fired = self.idle_tracker.notify_activity(get_monotonic_time());
+        self.notify_idle_watches_fired(&fired);
+
+        // Activity only pushes the idle deadlines back, so a scheduled timer can stay: it will
+        // reschedule itself when it fires.
+        if self.idle_timer.is_none() {
+            self.schedule_idle_timer();
+        }
+
         self.notified_activity_this_iteration = true;
     }
+
+    /// Schedules the idle timer for the next idle deadline.
+    pub fn schedule_idle_timer(&mut self) {
+        if let Some(token) = self.idle_timer.take() {
+            self.event_loop.remove(token);
+        }
+
+        let Some(deadline) = self.idle_tracker.next_deadline(get_monotonic_time()) else {
+            return;
+        };
+
+        let timer = Timer::from_duration(deadline);
+        let token = self
+            .event_loop
+            .insert_source(timer, move |_, _, state| {
+                state.niri.idle_timer = None;
+                state.on_idle_timer();
+                TimeoutAction::Drop
+            })
+            .unwrap();
+        self.idle_timer = Some(token);
+    }
+
+    #[cfg(not(feature = "dbus"))]
+    pub fn notify_idle_watches_fired(&self, _owners: &[WatchOwner]) {}
+
+    #[cfg(feature = "dbus")]
+    pub fn notify_idle_watches_fired(&self, owners: &[WatchOwner]) {
+        let Some(dbus) = &self.dbus else {
+            return;
+        };
+        let Some(conn) = &dbus.conn_idle_monitor else {
+            return;
+        };
+
+        let watches = owners.iter().filter_map(|owner| match owner {
+            WatchOwner::DBus { id, sender } => Some((*id, sender)),
+            WatchOwner::Config(_) => None,
+        });
+
+        let server = conn.object_server();
+        let path = "/org/gnome/Mutter/IdleMonitor/Core";
+        let Ok(iface) = server.interface::<_, mutter_idle_monitor::IdleMonitor>(path) else {
+            return;
+        };
+
+        for (id, sender) in watches {
+            let Ok(sender) = zbus::names::UniqueName::try_from(sender.clone()) else {
+                continue;
+            };
+
+            // Only the client that added the watch should learn that it fired.
+            let emitter = iface
+                .signal_emitter()
+                .clone()
+                .set_destination(sender.into());
+            let res = async_io::block_on(async move {
+                mutter_idle_monitor::IdleMonitor::watch_fired(&emitter, id).await
+            });
+            if let Err(err) = res {
+                warn!("error emitting idle watch signal: {err:?}");
+            }
+        }
+    }
 }
 
 pub struct NewClient {
@@ -6277,6 +7990,74 @@ fn scale_relocate_crop<E: Element>(
     CropRenderElement::from_element(elem, output_scale, ws_geo)
 }
 
+fn idle_timeouts(config: &niri_config::Idle) -> impl Iterator<Item = Duration> + '_ {
+    config
+        .timeouts
+        .iter()
+        .map(|timeout| Duration::from_secs(u64::from(timeout.seconds)))
+}
+
+/// Finds the workspace targeted by a cast.
+pub fn workspace_cast_state(layout: &Layout<Mapped>, id: u64) -> WorkspaceCastState {
+    let Some((mon, _, _)) = layout.workspaces().find(|(_, _, ws)| ws.id().get() == id) else {
+        return WorkspaceCastState::Removed;
+    };
+
+    match mon {
+        Some(mon) => WorkspaceCastState::Output(mon.output().clone()),
+        None => WorkspaceCastState::NoOutput,
+    }
+}
+
+/// Computes the physical crop of an area cast, clamped to the output.
+fn cast_area_crop(output: &Output, rect: Rectangle<i32, Logical>) -> Rectangle<i32, Physical> {
+    let scale = output.current_scale().fractional_scale();
+    let size = output.current_mode().unwrap().size;
+    let size = output.current_transform().transform_size(size);
+
+    let crop = rect.to_physical_precise_round(scale);
+    crop.intersection(Rectangle::new(Point::from((0, 0)), size))
+        // The area could end up outside the output after a mode change.
+        .unwrap_or_else(|| Rectangle::new(Point::from((0, 0)), Size::from((1, 1))))
+}
+
+/// Returns whether niri applies the night light with this config.
+///
+/// Only the tty backend can set output gamma.
+fn night_light_supported(backend: &Backend, config: &niri_config::NightLight) -> bool {
+    matches!(backend, Backend::Tty(_)) && config.on
+}
+
+/// Returns the color encoding of the output that the surface is primarily shown on.
+fn preferred_color_description(
+    output_state: &HashMap<Output, OutputState>,
+    surface: &WlSurface,
+) -> ColorDescription {
+    let output = with_states(surface, |states| {
+        surface_primary_scanout_output(surface, states)
+    });
+    output
+        .and_then(|output| output_state.get(&output))
+        .map_or(ColorDescription::SRGB, |state| state.color_description)
+}
+
+niri_render_elements! {
+    WindowRecordingRenderElements<R> => {
+        Window = WindowCastRenderElements<R>,
+        Pointer = RelocateRenderElement<OutputRenderElements<R>>,
+    }
+}
+
+niri_render_elements! {
+    WorkspaceCastRenderElements<R> => {
+        Workspace = WorkspaceRenderElement<R>,
+        LayerSurface = LayerSurfaceRenderElement<R>,
+        SolidColor = SolidColorRenderElement,
+        // Used for the pointer.
+        Output = OutputRenderElements<R>,
+    }
+}
+
 niri_render_elements! {
     OutputRenderElements<R> => {
         Monitor = MonitorRenderElement<R>,
@@ -6292,8 +8073,129 @@ niri_render_elements! {
             SolidColorRenderElement
         >>>,
         ScreenshotUi = ScreenshotUiRenderElement,
+        WindowSwitcher = WindowSwitcherRenderElement,
         Texture = PrimaryGpuTextureRenderElement,
         // Used for the CPU-rendered panels.
         RelocatedMemoryBuffer = RelocateRenderElement<MemoryRenderBufferRenderElement<R>>,
+        // Used for the final conversion on HDR outputs.
+        ColorTransformedOffscreen = ColorTransformRenderElement<OffscreenRenderElement>,
+    }
+}
+
+/// Returns how long until the displayed number of seconds of a countdown changes.
+fn until_countdown_tick(remaining: Duration) -> Duration {
+    let secs = remaining.as_millis().div_ceil(1000) as u64;
+    remaining.saturating_sub(Duration::from_secs(secs.saturating_sub(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(size: (i32, i32), scale: f64, transform: Transform) -> Output {
+        let output = Output::new(
+            String::from("output"),
+            PhysicalProperties {
+                size: (0, 0).into(),
+                subpixel: Subpixel::Unknown,
+                make: String::new(),
+                model: String::new(),
+            },
+        );
+        let mode = output::Mode {
+            size: Size::from(size),
+            refresh: 60000,
+        };
+        output.change_current_state(
+            Some(mode),
+            Some(transform),
+            Some(output::Scale::Fractional(scale)),
+            None,
+        );
+        output
+    }
+
+    fn rect<Kind>(x: i32, y: i32, w: i32, h: i32) -> Rectangle<i32, Kind> {
+        Rectangle::new(Point::from((x, y)), Size::from((w, h)))
+    }
+
+    #[test]
+    fn area_crop_fractional_scale() {
+        let out = output((1920, 1080), 1.5, Transform::Normal);
+        assert_eq!(
+            cast_area_crop(&out, rect(10, 20, 100, 50)),
+            rect(15, 30, 150, 75)
+        );
+
+        // Edges round to the nearest physical pixel.
+        let out = output((1920, 1080), 1.75, Transform::Normal);
+        assert_eq!(cast_area_crop(&out, rect(3, 3, 5, 5)), rect(5, 5, 9, 9));
+
+        // The whole output at a fractional scale.
+        let out = output((2560, 1600), 1.25, Transform::Normal);
+        assert_eq!(
+            cast_area_crop(&out, rect(0, 0, 2048, 1280)),
+            rect(0, 0, 2560, 1600)
+        );
+    }
+
+    #[test]
+    fn area_crop_clamps_to_output() {
+        let out = output((1920, 1080), 1.5, Transform::Normal);
+
+        // Past the bottom-right corner.
+        assert_eq!(
+            cast_area_crop(&out, rect(1200, 700, 200, 100)),
+            rect(1800, 1050, 120, 30)
+        );
+        // Past the top-left corner.
+        assert_eq!(
+            cast_area_crop(&out, rect(-100, -50, 200, 100)),
+            rect(0, 0, 150, 75)
+        );
+        // Larger than the output.
+        assert_eq!(
+            cast_area_crop(&out, rect(-10, -10, 2000, 2000)),
+            rect(0, 0, 1920, 1080)
+        );
+        // Entirely outside the output.
+        assert_eq!(
+            cast_area_crop(&out, rect(2000, 0, 10, 10)),
+            rect(0, 0, 1, 1)
+        );
+    }
+
+    #[test]
+    fn area_crop_transformed_output() {
+        let out = output((1920, 1080), 1., Transform::_90);
+        assert_eq!(
+            cast_area_crop(&out, rect(0, 1200, 100, 200)),
+            rect(0, 1200, 100, 200)
+        );
+        assert_eq!(
+            cast_area_crop(&out, rect(1000, 0, 200, 100)),
+            rect(1000, 0, 80, 100)
+        );
+    }
+
+    #[test]
+    fn countdown_tick() {
+        let ms = Duration::from_millis;
+
+        // On a whole second, the number changes in one second.
+        assert_eq!(until_countdown_tick(ms(3000)), ms(1000));
+        assert_eq!(until_countdown_tick(ms(1000)), ms(1000));
+        // Just past a whole second, it changes almost immediately.
+        assert_eq!(until_countdown_tick(ms(2001)), ms(1));
+        assert_eq!(until_countdown_tick(ms(1999)), ms(999));
+        assert_eq!(until_countdown_tick(ms(2500)), ms(500));
+        // In the last second, the countdown ends with the tick.
+        assert_eq!(until_countdown_tick(ms(1)), ms(1));
+        assert_eq!(until_countdown_tick(Duration::ZERO), Duration::ZERO);
+        // Sub-millisecond remainders are kept.
+        assert_eq!(
+            until_countdown_tick(Duration::from_micros(1_000_500)),
+            Duration::from_micros(500)
+        );
     }
 }
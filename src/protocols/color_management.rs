@@ -0,0 +1,872 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use smithay::output::Output;
+use smithay::reexports::wayland_protocols::wp::color_management::v1::server::{
+    wp_color_management_output_v1, wp_color_management_surface_feedback_v1,
+    wp_color_management_surface_v1, wp_color_manager_v1, wp_image_description_creator_icc_v1,
+    wp_image_description_creator_params_v1, wp_image_description_info_v1, wp_image_description_v1,
+};
+use smithay::reexports::wayland_server::protocol::wl_output::WlOutput;
+use smithay::reexports::wayland_server::protocol::wl_surface::WlSurface;
+use smithay::reexports::wayland_server::{
+    Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, New, Resource, Weak,
+};
+use smithay::wayland::compositor::{with_states, Cacheable, SurfaceData};
+use wp_color_management_output_v1::WpColorManagementOutputV1;
+use wp_color_management_surface_feedback_v1::WpColorManagementSurfaceFeedbackV1;
+use wp_color_management_surface_v1::WpColorManagementSurfaceV1;
+use wp_color_manager_v1::WpColorManagerV1;
+use wp_image_description_creator_icc_v1::WpImageDescriptionCreatorIccV1;
+use wp_image_description_creator_params_v1::WpImageDescriptionCreatorParamsV1;
+use wp_image_description_info_v1::WpImageDescriptionInfoV1;
+use wp_image_description_v1::WpImageDescriptionV1;
+
+use crate::render_helpers::color::{ColorDescription, Luminances, Primaries, TransferFunction};
+
+const VERSION: u32 = 1;
+
+pub struct ColorManagementState {
+    outputs: Vec<WpColorManagementOutputV1>,
+    feedbacks: Vec<WpColorManagementSurfaceFeedbackV1>,
+    /// Identities of the compositor-provided image descriptions.
+    ///
+    /// These are reused so that clients can recognize the same description, for example in
+    /// preferred_changed.
+    identities: HashMap<ColorDescription, u32>,
+    next_identity: u32,
+}
+
+pub struct ColorManagementGlobalData {
+    filter: Box<dyn for<'c> Fn(&'c Client) -> bool + Send + Sync>,
+}
+
+pub trait ColorManagementHandler {
+    fn color_management_state(&mut self) -> &mut ColorManagementState;
+    /// Returns the image description of the output, or `None` if the output is gone.
+    fn output_description(&mut self, output: &Output) -> Option<ColorDescription>;
+    /// Returns the image description that the surface should preferably use.
+    fn preferred_description(&mut self, surface: &WlSurface) -> ColorDescription;
+}
+
+pub struct OutputUserData {
+    wl_output: WlOutput,
+}
+
+pub struct SurfaceUserData {
+    surface: Weak<WlSurface>,
+}
+
+pub struct FeedbackUserData {
+    surface: Weak<WlSurface>,
+    /// Identity of the preferred description that the client knows about.
+    preferred_identity: Mutex<u32>,
+}
+
+#[derive(Default)]
+pub struct ParamsCreatorUserData {
+    inner: Mutex<ParamsCreator>,
+}
+
+#[derive(Default)]
+struct ParamsCreator {
+    tf: Option<TransferFunction>,
+    primaries: Option<Primaries>,
+    luminances: Option<Luminances>,
+}
+
+pub struct ImageDescriptionUserData {
+    /// The description, or `None` if creating it failed.
+    description: Option<ColorDescription>,
+    /// Whether get_information is allowed.
+    allow_info: bool,
+}
+
+/// Color management object of a surface, stored in the surface data.
+#[derive(Default)]
+struct ColorManagementSurfaceData {
+    object: Mutex<Option<Weak<WpColorManagementSurfaceV1>>>,
+}
+
+/// Double-buffered image description of a surface.
+#[derive(Debug, Default, Clone, Copy)]
+struct CachedState {
+    description: Option<ColorDescription>,
+}
+
+impl Cacheable for CachedState {
+    fn commit(&mut self, _dh: &DisplayHandle) -> Self {
+        *self
+    }
+
+    fn merge_into(self, into: &mut Self, _dh: &DisplayHandle) {
+        *into = self;
+    }
+}
+
+/// Returns the image description that the client has set on the surface.
+pub fn surface_description(states: &SurfaceData) -> Option<ColorDescription> {
+    states
+        .cached_state
+        .get::<CachedState>()
+        .current()
+        .description
+}
+
+impl ColorManagementState {
+    pub fn new<D, F>(display: &DisplayHandle, filter: F) -> Self
+    where
+        D: GlobalDispatch<WpColorManagerV1, ColorManagementGlobalData>,
+        D: Dispatch<WpColorManagerV1, ()>,
+        D: ColorManagementHandler,
+        D: 'static,
+        F: for<'c> Fn(&'c Client) -> bool + Send + Sync + 'static,
+    {
+        let global_data = ColorManagementGlobalData {
+            filter: Box::new(filter),
+        };
+        display.create_global::<D, WpColorManagerV1, _>(VERSION, global_data);
+
+        Self {
+            outputs: Vec::new(),
+            feedbacks: Vec::new(),
+            identities: HashMap::new(),
+            next_identity: 1,
+        }
+    }
+
+    /// Notifies clients that the image description of the output has changed.
+    pub fn output_description_changed(&mut self, output: &Output) {
+        self.outputs.retain(|obj| obj.is_alive());
+
+        for obj in &self.outputs {
+            let data = obj.data::<OutputUserData>().unwrap();
+            if !output.owns(&data.wl_output) {
+                continue;
+            }
+
+            obj.image_description_changed();
+            if data.wl_output.version() >= 2 {
+                data.wl_output.done();
+            }
+        }
+    }
+
+    /// Sends preferred_changed to surfaces whose preferred description has changed.
+    pub fn refresh_preferred(&mut self, mut preferred: impl FnMut(&WlSurface) -> ColorDescription) {
+        self.feedbacks.retain(|obj| obj.is_alive());
+
+        let feedbacks = self.feedbacks.clone();
+        for obj in feedbacks {
+            let data = obj.data::<FeedbackUserData>().unwrap();
+            let Ok(surface) = data.surface.upgrade() else {
+                continue;
+            };
+
+            let identity = self.identity(preferred(&surface));
+            let mut known = data.preferred_identity.lock().unwrap();
+            if *known != identity {
+                *known = identity;
+                obj.preferred_changed(identity);
+            }
+        }
+    }
+
+    /// Returns the identity of a compositor-provided description.
+    fn identity(&mut self, description: ColorDescription) -> u32 {
+        if let Some(identity) = self.identities.get(&description) {
+            return *identity;
+        }
+
+        let identity = self.new_identity();
+        self.identities.insert(description, identity);
+        identity
+    }
+
+    fn new_identity(&mut self) -> u32 {
+        let identity = self.next_identity;
+        // Zero is reserved.
+        self.next_identity = self.next_identity.checked_add(1).unwrap_or(1);
+        identity
+    }
+}
+
+impl<D> GlobalDispatch<WpColorManagerV1, ColorManagementGlobalData, D> for ColorManagementState
+where
+    D: GlobalDispatch<WpColorManagerV1, ColorManagementGlobalData>,
+    D: Dispatch<WpColorManagerV1, ()>,
+    D: ColorManagementHandler,
+    D: 'static,
+{
+    fn bind(
+        _state: &mut D,
+        _handle: &DisplayHandle,
+        _client: &Client,
+        resource: New<WpColorManagerV1>,
+        _global_data: &ColorManagementGlobalData,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        let manager = data_init.init(resource, ());
+
+        manager.supported_intent(wp_color_manager_v1::RenderIntent::Perceptual);
+
+        manager.supported_feature(wp_color_manager_v1::Feature::Parametric);
+        manager.supported_feature(wp_color_manager_v1::Feature::SetPrimaries);
+        manager.supported_feature(wp_color_manager_v1::Feature::SetLuminances);
+
+        for tf in [
+            wp_color_manager_v1::TransferFunction::Srgb,
+            wp_color_manager_v1::TransferFunction::Gamma22,
+            wp_color_manager_v1::TransferFunction::St2084Pq,
+            wp_color_manager_v1::TransferFunction::Hlg,
+            wp_color_manager_v1::TransferFunction::ExtLinear,
+        ] {
+            manager.supported_tf_named(tf);
+        }
+
+        for primaries in [
+            wp_color_manager_v1::Primaries::Srgb,
+            wp_color_manager_v1::Primaries::Bt2020,
+            wp_color_manager_v1::Primaries::DisplayP3,
+        ] {
+            manager.supported_primaries_named(primaries);
+        }
+
+        manager.done();
+    }
+
+    fn can_view(client: Client, global_data: &ColorManagementGlobalData) -> bool {
+        (global_data.filter)(&client)
+    }
+}
+
+impl<D> Dispatch<WpColorManagerV1, (), D> for ColorManagementState
+where
+    D: Dispatch<WpColorManagerV1, ()>,
+    D: Dispatch<WpColorManagementOutputV1, OutputUserData>,
+    D: Dispatch<WpColorManagementSurfaceV1, SurfaceUserData>,
+    D: Dispatch<WpColorManagementSurfaceFeedbackV1, FeedbackUserData>,
+    D: Dispatch<WpImageDescriptionCreatorIccV1, ()>,
+    D: Dispatch<WpImageDescriptionCreatorParamsV1, ParamsCreatorUserData>,
+    D: Dispatch<WpImageDescriptionV1, ImageDescriptionUserData>,
+    D: ColorManagementHandler,
+    D: 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        resource: &WpColorManagerV1,
+        request: <WpColorManagerV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            wp_color_manager_v1::Request::Destroy => (),
+            wp_color_manager_v1::Request::GetOutput { id, output } => {
+                let obj = data_init.init(id, OutputUserData { wl_output: output });
+                state.color_management_state().outputs.push(obj);
+            }
+            wp_color_manager_v1::Request::GetSurface { id, surface } => {
+                let obj = data_init.init(
+                    id,
+                    SurfaceUserData {
+                        surface: surface.downgrade(),
+                    },
+                );
+
+                let exists = with_states(&surface, |states| {
+                    states
+                        .data_map
+                        .insert_if_missing_threadsafe(ColorManagementSurfaceData::default);
+                    let data = states.data_map.get::<ColorManagementSurfaceData>().unwrap();
+                    let mut object = data.object.lock().unwrap();
+
+                    if object.as_ref().is_some_and(|obj| obj.upgrade().is_ok()) {
+                        return true;
+                    }
+
+                    *object = Some(obj.downgrade());
+                    false
+                });
+
+                if exists {
+                    resource.post_error(
+                        wp_color_manager_v1::Error::SurfaceExists,
+                        "the wl_surface already has a wp_color_management_surface_v1",
+                    );
+                }
+            }
+            wp_color_manager_v1::Request::GetSurfaceFeedback { id, surface } => {
+                let identity = state.preferred_identity(&surface);
+                let obj = data_init.init(
+                    id,
+                    FeedbackUserData {
+                        surface: surface.downgrade(),
+                        preferred_identity: Mutex::new(identity),
+                    },
+                );
+                state.color_management_state().feedbacks.push(obj);
+            }
+            wp_color_manager_v1::Request::CreateIccCreator { obj } => {
+                data_init.init(obj, ());
+                resource.post_error(
+                    wp_color_manager_v1::Error::UnsupportedFeature,
+                    "ICC profiles are not supported",
+                );
+            }
+            wp_color_manager_v1::Request::CreateParametricCreator { obj } => {
+                data_init.init(obj, ParamsCreatorUserData::default());
+            }
+            wp_color_manager_v1::Request::CreateWindowsScrgb { image_description } => {
+                data_init.init(
+                    image_description,
+                    ImageDescriptionUserData {
+                        description: None,
+                        allow_info: false,
+                    },
+                );
+                resource.post_error(
+                    wp_color_manager_v1::Error::UnsupportedFeature,
+                    "Windows-scRGB is not supported",
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Helpers for sending compositor-provided image descriptions.
+trait ColorManagementHandlerExt {
+    fn preferred_identity(&mut self, surface: &WlSurface) -> u32;
+    fn init_description<D>(
+        &mut self,
+        data_init: &mut DataInit<'_, D>,
+        id: New<WpImageDescriptionV1>,
+        description: Option<ColorDescription>,
+    ) where
+        D: Dispatch<WpImageDescriptionV1, ImageDescriptionUserData>,
+        D: 'static;
+}
+
+impl<T: ColorManagementHandler> ColorManagementHandlerExt for T {
+    fn preferred_identity(&mut self, surface: &WlSurface) -> u32 {
+        let description = self.preferred_description(surface);
+        self.color_management_state().identity(description)
+    }
+
+    fn init_description<D>(
+        &mut self,
+        data_init: &mut DataInit<'_, D>,
+        id: New<WpImageDescriptionV1>,
+        description: Option<ColorDescription>,
+    ) where
+        D: Dispatch<WpImageDescriptionV1, ImageDescriptionUserData>,
+        D: 'static,
+    {
+        let obj = data_init.init(
+            id,
+            ImageDescriptionUserData {
+                description,
+                allow_info: true,
+            },
+        );
+
+        match description {
+            Some(description) => {
+                let identity = self.color_management_state().identity(description);
+                obj.ready(identity);
+            }
+            None => {
+                obj.failed(
+                    wp_image_description_v1::Cause::NoOutput,
+                    String::from("the output no longer exists"),
+                );
+            }
+        }
+    }
+}
+
+impl<D> Dispatch<WpColorManagementOutputV1, OutputUserData, D> for ColorManagementState
+where
+    D: Dispatch<WpColorManagementOutputV1, OutputUserData>,
+    D: Dispatch<WpImageDescriptionV1, ImageDescriptionUserData>,
+    D: ColorManagementHandler,
+    D: 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        _resource: &WpColorManagementOutputV1,
+        request: <WpColorManagementOutputV1 as Resource>::Request,
+        data: &OutputUserData,
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            wp_color_management_output_v1::Request::Destroy => (),
+            wp_color_management_output_v1::Request::GetImageDescription { image_description } => {
+                let description = Output::from_resource(&data.wl_output)
+                    .and_then(|output| state.output_description(&output));
+                state.init_description(data_init, image_description, description);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<D> Dispatch<WpColorManagementSurfaceV1, SurfaceUserData, D> for ColorManagementState
+where
+    D: Dispatch<WpColorManagementSurfaceV1, SurfaceUserData>,
+    D: ColorManagementHandler,
+    D: 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        resource: &WpColorManagementSurfaceV1,
+        request: <WpColorManagementSurfaceV1 as Resource>::Request,
+        data: &SurfaceUserData,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        let Ok(surface) = data.surface.upgrade() else {
+            if !matches!(request, wp_color_management_surface_v1::Request::Destroy) {
+                resource.post_error(
+                    wp_color_management_surface_v1::Error::Inert,
+                    "the wl_surface was destroyed",
+                );
+            }
+            return;
+        };
+
+        let description = match request {
+            wp_color_management_surface_v1::Request::Destroy => None,
+            wp_color_management_surface_v1::Request::SetImageDescription {
+                image_description,
+                render_intent,
+            } => {
+                let intent = render_intent.into_result();
+                if !matches!(intent, Ok(wp_color_manager_v1::RenderIntent::Perceptual)) {
+                    resource.post_error(
+                        wp_color_management_surface_v1::Error::RenderIntent,
+                        "unsupported rendering intent",
+                    );
+                    return;
+                }
+
+                let desc_data = image_description
+                    .data::<ImageDescriptionUserData>()
+                    .unwrap();
+                let Some(description) = desc_data.description else {
+                    resource.post_error(
+                        wp_color_management_surface_v1::Error::ImageDescription,
+                        "the image description is not ready",
+                    );
+                    return;
+                };
+
+                Some(description)
+            }
+            wp_color_management_surface_v1::Request::UnsetImageDescription => None,
+            _ => unreachable!(),
+        };
+
+        with_states(&surface, |states| {
+            states
+                .cached_state
+                .get::<CachedState>()
+                .pending()
+                .description = description;
+        });
+    }
+}
+
+impl<D> Dispatch<WpColorManagementSurfaceFeedbackV1, FeedbackUserData, D> for ColorManagementState
+where
+    D: Dispatch<WpColorManagementSurfaceFeedbackV1, FeedbackUserData>,
+    D: Dispatch<WpImageDescriptionV1, ImageDescriptionUserData>,
+    D: ColorManagementHandler,
+    D: 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        resource: &WpColorManagementSurfaceFeedbackV1,
+        request: <WpColorManagementSurfaceFeedbackV1 as Resource>::Request,
+        data: &FeedbackUserData,
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        let image_description = match request {
+            wp_color_management_surface_feedback_v1::Request::Destroy => return,
+            wp_color_management_surface_feedback_v1::Request::GetPreferred {
+                image_description,
+            } => image_description,
+            wp_color_management_surface_feedback_v1::Request::GetPreferredParametric {
+                image_description,
+            } => image_description,
+            _ => unreachable!(),
+        };
+
+        let Ok(surface) = data.surface.upgrade() else {
+            data_init.init(
+                image_description,
+                ImageDescriptionUserData {
+                    description: None,
+                    allow_info: false,
+                },
+            );
+            resource.post_error(
+                wp_color_management_surface_feedback_v1::Error::Inert,
+                "the wl_surface was destroyed",
+            );
+            return;
+        };
+
+        let description = state.preferred_description(&surface);
+        state.init_description(data_init, image_description, Some(description));
+    }
+}
+
+impl<D> Dispatch<WpImageDescriptionCreatorIccV1, (), D> for ColorManagementState
+where
+    D: Dispatch<WpImageDescriptionCreatorIccV1, ()>,
+    D: Dispatch<WpImageDescriptionV1, ImageDescriptionUserData>,
+    D: 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        _resource: &WpImageDescriptionCreatorIccV1,
+        request: <WpImageDescriptionCreatorIccV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        // The creator can't be obtained without a protocol error, but the requests may still
+        // arrive before the client is disconnected.
+        if let wp_image_description_creator_icc_v1::Request::Create { image_description } = request
+        {
+            data_init.init(
+                image_description,
+                ImageDescriptionUserData {
+                    description: None,
+                    allow_info: false,
+                },
+            );
+        }
+    }
+}
+
+impl<D> Dispatch<WpImageDescriptionCreatorParamsV1, ParamsCreatorUserData, D>
+    for ColorManagementState
+where
+    D: Dispatch<WpImageDescriptionCreatorParamsV1, ParamsCreatorUserData>,
+    D: Dispatch<WpImageDescriptionV1, ImageDescriptionUserData>,
+    D: ColorManagementHandler,
+    D: 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        resource: &WpImageDescriptionCreatorParamsV1,
+        request: <WpImageDescriptionCreatorParamsV1 as Resource>::Request,
+        data: &ParamsCreatorUserData,
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        use wp_image_description_creator_params_v1::{Error, Request};
+
+        let mut params = data.inner.lock().unwrap();
+
+        let already_set = |resource: &WpImageDescriptionCreatorParamsV1| {
+            resource.post_error(Error::AlreadySet, "the property was already set");
+        };
+
+        match request {
+            Request::Create { image_description } => {
+                let (Some(tf), Some(primaries)) = (params.tf, params.primaries) else {
+                    data_init.init(
+                        image_description,
+                        ImageDescriptionUserData {
+                            description: None,
+                            allow_info: false,
+                        },
+                    );
+                    resource.post_error(
+                        Error::IncompleteSet,
+                        "the transfer function and primaries must be set",
+                    );
+                    return;
+                };
+
+                let description = primaries.is_valid().then(|| ColorDescription {
+                    tf,
+                    primaries,
+                    luminances: params.luminances.unwrap_or(Luminances::for_tf(tf)),
+                });
+
+                let obj = data_init.init(
+                    image_description,
+                    ImageDescriptionUserData {
+                        description,
+                        // Client-created parametric descriptions don't allow get_information.
+                        allow_info: false,
+                    },
+                );
+
+                if description.is_some() {
+                    let identity = state.color_management_state().new_identity();
+                    obj.ready(identity);
+                } else {
+                    obj.failed(
+                        wp_image_description_v1::Cause::Unsupported,
+                        String::from("the primaries don't describe a valid color volume"),
+                    );
+                }
+            }
+            Request::SetTfNamed { tf } => {
+                if params.tf.is_some() {
+                    already_set(resource);
+                    return;
+                }
+
+                let tf = match tf.into_result() {
+                    Ok(wp_color_manager_v1::TransferFunction::Srgb) => TransferFunction::Srgb,
+                    Ok(wp_color_manager_v1::TransferFunction::Gamma22) => TransferFunction::Gamma22,
+                    Ok(wp_color_manager_v1::TransferFunction::St2084Pq) => TransferFunction::Pq,
+                    Ok(wp_color_manager_v1::TransferFunction::Hlg) => TransferFunction::Hlg,
+                    Ok(wp_color_manager_v1::TransferFunction::ExtLinear) => {
+                        TransferFunction::Linear
+                    }
+                    _ => {
+                        resource.post_error(Error::InvalidTf, "unsupported transfer function");
+                        return;
+                    }
+                };
+                params.tf = Some(tf);
+            }
+            Request::SetTfPower { .. } => {
+                resource.post_error(
+                    Error::UnsupportedFeature,
+                    "power transfer functions are not supported",
+                );
+            }
+            Request::SetPrimariesNamed { primaries } => {
+                if params.primaries.is_some() {
+                    already_set(resource);
+                    return;
+                }
+
+                let primaries = match primaries.into_result() {
+                    Ok(wp_color_manager_v1::Primaries::Srgb) => Primaries::SRGB,
+                    Ok(wp_color_manager_v1::Primaries::Bt2020) => Primaries::BT2020,
+                    Ok(wp_color_manager_v1::Primaries::DisplayP3) => Primaries::DISPLAY_P3,
+                    _ => {
+                        resource.post_error(
+                            Error::InvalidPrimariesNamed,
+                            "unsupported named primaries",
+                        );
+                        return;
+                    }
+                };
+                params.primaries = Some(primaries);
+            }
+            Request::SetPrimaries {
+                r_x,
+                r_y,
+                g_x,
+                g_y,
+                b_x,
+                b_y,
+                w_x,
+                w_y,
+            } => {
+                if params.primaries.is_some() {
+                    already_set(resource);
+                    return;
+                }
+
+                params.primaries = Some(Primaries {
+                    r: (r_x, r_y),
+                    g: (g_x, g_y),
+                    b: (b_x, b_y),
+                    w: (w_x, w_y),
+                });
+            }
+            Request::SetLuminances {
+                min_lum,
+                max_lum,
+                reference_lum,
+            } => {
+                if params.luminances.is_some() {
+                    already_set(resource);
+                    return;
+                }
+
+                let min = f64::from(min_lum) / 10000.;
+                if f64::from(max_lum) <= min || f64::from(reference_lum) <= min {
+                    resource.post_error(
+                        Error::InvalidLuminance,
+                        "max and reference luminance must be greater than min luminance",
+                    );
+                    return;
+                }
+
+                params.luminances = Some(Luminances {
+                    min: min_lum,
+                    max: max_lum,
+                    reference: reference_lum,
+                });
+            }
+            Request::SetMasteringDisplayPrimaries { .. }
+            | Request::SetMasteringLuminance { .. } => {
+                resource.post_error(
+                    Error::UnsupportedFeature,
+                    "mastering display properties are not supported",
+                );
+            }
+            // These are optional hints that we don't use.
+            Request::SetMaxCll { .. } | Request::SetMaxFall { .. } => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<D> Dispatch<WpImageDescriptionV1, ImageDescriptionUserData, D> for ColorManagementState
+where
+    D: Dispatch<WpImageDescriptionV1, ImageDescriptionUserData>,
+    D: Dispatch<WpImageDescriptionInfoV1, ()>,
+    D: 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        resource: &WpImageDescriptionV1,
+        request: <WpImageDescriptionV1 as Resource>::Request,
+        data: &ImageDescriptionUserData,
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            wp_image_description_v1::Request::Destroy => (),
+            wp_image_description_v1::Request::GetInformation { information } => {
+                let info = data_init.init(information, ());
+
+                let Some(description) = data.description else {
+                    resource.post_error(
+                        wp_image_description_v1::Error::NotReady,
+                        "the image description is not ready",
+                    );
+                    return;
+                };
+
+                if !data.allow_info {
+                    resource.post_error(
+                        wp_image_description_v1::Error::NoInformation,
+                        "get_information is not allowed for this image description",
+                    );
+                    return;
+                }
+
+                send_information(&info, &description);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<D> Dispatch<WpImageDescriptionInfoV1, (), D> for ColorManagementState
+where
+    D: Dispatch<WpImageDescriptionInfoV1, ()>,
+    D: 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        _resource: &WpImageDescriptionInfoV1,
+        _request: <WpImageDescriptionInfoV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        unreachable!()
+    }
+}
+
+fn send_information(info: &WpImageDescriptionInfoV1, description: &ColorDescription) {
+    let p = description.primaries;
+    info.primaries(p.r.0, p.r.1, p.g.0, p.g.1, p.b.0, p.b.1, p.w.0, p.w.1);
+
+    let named = if p == Primaries::SRGB {
+        Some(wp_color_manager_v1::Primaries::Srgb)
+    } else if p == Primaries::BT2020 {
+        Some(wp_color_manager_v1::Primaries::Bt2020)
+    } else if p == Primaries::DISPLAY_P3 {
+        Some(wp_color_manager_v1::Primaries::DisplayP3)
+    } else {
+        None
+    };
+    if let Some(named) = named {
+        info.primaries_named(named);
+    }
+
+    info.tf_named(match description.tf {
+        TransferFunction::Srgb => wp_color_manager_v1::TransferFunction::Srgb,
+        TransferFunction::Gamma22 => wp_color_manager_v1::TransferFunction::Gamma22,
+        TransferFunction::Pq => wp_color_manager_v1::TransferFunction::St2084Pq,
+        TransferFunction::Hlg => wp_color_manager_v1::TransferFunction::Hlg,
+        TransferFunction::Linear => wp_color_manager_v1::TransferFunction::ExtLinear,
+    });
+
+    let l = description.luminances;
+    info.luminances(l.min, l.max, l.reference);
+
+    // We don't track the target color volume separately.
+    info.target_primaries(p.r.0, p.r.1, p.g.0, p.g.1, p.b.0, p.b.1, p.w.0, p.w.1);
+    info.target_luminance(l.min, l.max);
+
+    info.done();
+}
+
+#[macro_export]
+macro_rules! delegate_color_management {
+    ($(@<$( $lt:tt $( : $clt:tt $(+ $dlt:tt )* )? ),+>)? $ty: ty) => {
+        smithay::reexports::wayland_server::delegate_global_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::wp::color_management::v1::server::wp_color_manager_v1::WpColorManagerV1: $crate::protocols::color_management::ColorManagementGlobalData
+        ] => $crate::protocols::color_management::ColorManagementState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::wp::color_management::v1::server::wp_color_manager_v1::WpColorManagerV1: ()
+        ] => $crate::protocols::color_management::ColorManagementState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::wp::color_management::v1::server::wp_color_management_output_v1::WpColorManagementOutputV1: $crate::protocols::color_management::OutputUserData
+        ] => $crate::protocols::color_management::ColorManagementState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::wp::color_management::v1::server::wp_color_management_surface_v1::WpColorManagementSurfaceV1: $crate::protocols::color_management::SurfaceUserData
+        ] => $crate::protocols::color_management::ColorManagementState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::wp::color_management::v1::server::wp_color_management_surface_feedback_v1::WpColorManagementSurfaceFeedbackV1: $crate::protocols::color_management::FeedbackUserData
+        ] => $crate::protocols::color_management::ColorManagementState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::wp::color_management::v1::server::wp_image_description_creator_icc_v1::WpImageDescriptionCreatorIccV1: ()
+        ] => $crate::protocols::color_management::ColorManagementState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::wp::color_management::v1::server::wp_image_description_creator_params_v1::WpImageDescriptionCreatorParamsV1: $crate::protocols::color_management::ParamsCreatorUserData
+        ] => $crate::protocols::color_management::ColorManagementState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::wp::color_management::v1::server::wp_image_description_v1::WpImageDescriptionV1: $crate::protocols::color_management::ImageDescriptionUserData
+        ] => $crate::protocols::color_management::ColorManagementState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::wp::color_management::v1::server::wp_image_description_info_v1::WpImageDescriptionInfoV1: ()
+        ] => $crate::protocols::color_management::ColorManagementState);
+    };
+}
@@ -35,12 +35,14 @@ pub trait ForeignToplevelHandler {
     fn close(&mut self, wl_surface: WlSurface);
     fn set_fullscreen(&mut self, wl_surface: WlSurface, wl_output: Option<WlOutput>);
     fn unset_fullscreen(&mut self, wl_surface: WlSurface);
+    fn set_minimized(&mut self, wl_surface: WlSurface);
+    fn unset_minimized(&mut self, wl_surface: WlSurface);
 }
 
 struct ToplevelData {
     title: Option<String>,
     app_id: Option<String>,
-    states: ArrayVec<u32, 3>,
+    states: ArrayVec<u32, 4>,
     output: Option<Output>,
     instances: HashMap<ZwlrForeignToplevelHandleV1, Vec<WlOutput>>,
     // FIXME: parent.
@@ -97,20 +99,29 @@ pub fn refresh(state: &mut State) {
         let toplevel = mapped.toplevel();
         let wl_surface = toplevel.wl_surface();
         with_toplevel_role(toplevel, |role| {
+            let is_minimized = mapped.is_minimized();
             if state.niri.keyboard_focus.surface() == Some(wl_surface) {
-                focused = Some((mapped.window.clone(), output.cloned()));
+                focused = Some((mapped.window.clone(), output.cloned(), is_minimized));
             } else {
-                refresh_toplevel(protocol_state, wl_surface, role, output, false);
+                refresh_toplevel(
+                    protocol_state,
+                    wl_surface,
+                    role,
+                    output,
+                    false,
+                    is_minimized,
+                );
             }
         });
     });
 
     // Finally, refresh the focused window.
-    if let Some((window, output)) = focused {
+    if let Some((window, output, is_minimized)) = focused {
         let toplevel = window.toplevel().expect("no X11 support");
         let wl_surface = toplevel.wl_surface();
         with_toplevel_role(toplevel, |role| {
-            refresh_toplevel(protocol_state, wl_surface, role, output.as_ref(), true);
+            let output = output.as_ref();
+            refresh_toplevel(protocol_state, wl_surface, role, output, true, is_minimized);
         });
     }
 }
@@ -146,8 +157,9 @@ fn refresh_toplevel(
     role: &XdgToplevelSurfaceRoleAttributes,
     output: Option<&Output>,
     has_focus: bool,
+    is_minimized: bool,
 ) {
-    let states = to_state_vec(&role.current.states, has_focus);
+    let states = to_state_vec(&role.current.states, has_focus, is_minimized);
 
     match protocol_state.toplevels.entry(wl_surface.clone()) {
         Entry::Occupied(entry) => {
@@ -377,8 +389,12 @@ where
         match request {
             zwlr_foreign_toplevel_handle_v1::Request::SetMaximized => (),
             zwlr_foreign_toplevel_handle_v1::Request::UnsetMaximized => (),
-            zwlr_foreign_toplevel_handle_v1::Request::SetMinimized => (),
-            zwlr_foreign_toplevel_handle_v1::Request::UnsetMinimized => (),
+            zwlr_foreign_toplevel_handle_v1::Request::SetMinimized => {
+                state.set_minimized(surface);
+            }
+            zwlr_foreign_toplevel_handle_v1::Request::UnsetMinimized => {
+                state.unset_minimized(surface);
+            }
             zwlr_foreign_toplevel_handle_v1::Request::Activate { .. } => {
                 state.activate(surface);
             }
@@ -410,11 +426,18 @@ where
     }
 }
 
-fn to_state_vec(states: &ToplevelStateSet, has_focus: bool) -> ArrayVec<u32, 3> {
+fn to_state_vec(
+    states: &ToplevelStateSet,
+    has_focus: bool,
+    is_minimized: bool,
+) -> ArrayVec<u32, 4> {
     let mut rv = ArrayVec::new();
     if states.contains(xdg_toplevel::State::Maximized) {
         rv.push(zwlr_foreign_toplevel_handle_v1::State::Maximized as u32);
     }
+    if is_minimized {
+        rv.push(zwlr_foreign_toplevel_handle_v1::State::Minimized as u32);
+    }
     if states.contains(xdg_toplevel::State::Fullscreen) {
         rv.push(zwlr_foreign_toplevel_handle_v1::State::Fullscreen as u32);
     }
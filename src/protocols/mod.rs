@@ -1,3 +1,4 @@
+pub mod color_management;
 pub mod ext_workspace;
 pub mod foreign_toplevel;
 pub mod gamma_control;
@@ -5,5 +6,9 @@ pub mod mutter_x11_interop;
 pub mod output_management;
 pub mod screencopy;
 pub mod virtual_pointer;
+pub mod xdg_dialog;
+pub mod xdg_system_bell;
+pub mod xdg_toplevel_drag;
+pub mod xdg_toplevel_icon;
 
 pub mod raw;
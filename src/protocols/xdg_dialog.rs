@@ -0,0 +1,237 @@
+use std::sync::Mutex;
+
+use smithay::reexports::wayland_protocols::xdg::dialog::v1::server::{
+    xdg_dialog_v1, xdg_wm_dialog_v1,
+};
+use smithay::reexports::wayland_protocols::xdg::shell::server::xdg_toplevel::XdgToplevel;
+use smithay::reexports::wayland_server::protocol::wl_surface::WlSurface;
+use smithay::reexports::wayland_server::{
+    Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, New, Resource, Weak,
+};
+use smithay::wayland::compositor::with_states;
+use xdg_dialog_v1::XdgDialogV1;
+use xdg_wm_dialog_v1::XdgWmDialogV1;
+
+const VERSION: u32 = 1;
+
+pub struct XdgDialogState {}
+
+pub struct XdgDialogGlobalData {
+    filter: Box<dyn for<'c> Fn(&'c Client) -> bool + Send + Sync>,
+}
+
+pub trait XdgDialogHandler {
+    fn xdg_dialog_state(&mut self) -> &mut XdgDialogState;
+    /// Returns the surface of the given toplevel, if it is still alive.
+    fn toplevel_surface(&mut self, toplevel: &XdgToplevel) -> Option<WlSurface>;
+    /// Called when the modal hint of the toplevel changes.
+    fn modal_changed(&mut self, toplevel: &XdgToplevel);
+}
+
+/// Dialog state stored on the toplevel surface.
+#[derive(Default)]
+struct XdgDialogSurfaceData {
+    inner: Mutex<XdgDialogSurfaceDataInner>,
+}
+
+#[derive(Default)]
+struct XdgDialogSurfaceDataInner {
+    /// Toplevel that the dialog object was created for.
+    toplevel: Option<Weak<XdgToplevel>>,
+    /// Whether the client has hinted that the dialog is modal.
+    modal: bool,
+}
+
+/// User data of an xdg_dialog_v1 object.
+pub struct XdgDialogUserData {
+    toplevel: Weak<XdgToplevel>,
+}
+
+/// Returns whether the client has hinted that this toplevel surface is a modal dialog.
+///
+/// Note that the hint only makes sense for toplevels that have a parent, which this function
+/// doesn't check.
+pub fn is_modal(surface: &WlSurface) -> bool {
+    with_states(surface, |states| {
+        let Some(data) = states.data_map.get::<XdgDialogSurfaceData>() else {
+            return false;
+        };
+        let inner = data.inner.lock().unwrap();
+        let alive = inner
+            .toplevel
+            .as_ref()
+            .is_some_and(|toplevel| toplevel.upgrade().is_ok());
+        alive && inner.modal
+    })
+}
+
+impl XdgDialogState {
+    pub fn new<D, F>(display: &DisplayHandle, filter: F) -> Self
+    where
+        D: GlobalDispatch<XdgWmDialogV1, XdgDialogGlobalData>,
+        D: Dispatch<XdgWmDialogV1, ()>,
+        D: Dispatch<XdgDialogV1, XdgDialogUserData>,
+        D: XdgDialogHandler,
+        D: 'static,
+        F: for<'c> Fn(&'c Client) -> bool + Send + Sync + 'static,
+    {
+        let global_data = XdgDialogGlobalData {
+            filter: Box::new(filter),
+        };
+        display.create_global::<D, XdgWmDialogV1, _>(VERSION, global_data);
+
+        Self {}
+    }
+}
+
+impl<D> GlobalDispatch<XdgWmDialogV1, XdgDialogGlobalData, D> for XdgDialogState
+where
+    D: GlobalDispatch<XdgWmDialogV1, XdgDialogGlobalData>,
+    D: Dispatch<XdgWmDialogV1, ()>,
+    D: Dispatch<XdgDialogV1, XdgDialogUserData>,
+    D: XdgDialogHandler,
+    D: 'static,
+{
+    fn bind(
+        _state: &mut D,
+        _handle: &DisplayHandle,
+        _client: &Client,
+        resource: New<XdgWmDialogV1>,
+        _global_data: &XdgDialogGlobalData,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        data_init.init(resource, ());
+    }
+
+    fn can_view(client: Client, global_data: &XdgDialogGlobalData) -> bool {
+        (global_data.filter)(&client)
+    }
+}
+
+impl<D> Dispatch<XdgWmDialogV1, (), D> for XdgDialogState
+where
+    D: Dispatch<XdgWmDialogV1, ()>,
+    D: Dispatch<XdgDialogV1, XdgDialogUserData>,
+    D: XdgDialogHandler,
+    D: 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        resource: &XdgWmDialogV1,
+        request: <XdgWmDialogV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            xdg_wm_dialog_v1::Request::GetXdgDialog { id, toplevel } => {
+                let data = XdgDialogUserData {
+                    toplevel: toplevel.downgrade(),
+                };
+
+                let Some(surface) = state.toplevel_surface(&toplevel) else {
+                    // The toplevel is already dead, so the dialog is inert from the start.
+                    data_init.init(id, data);
+                    return;
+                };
+
+                let already_used = with_states(&surface, |states| {
+                    states
+                        .data_map
+                        .insert_if_missing_threadsafe(XdgDialogSurfaceData::default);
+                    let data = states.data_map.get::<XdgDialogSurfaceData>().unwrap();
+                    let mut inner = data.inner.lock().unwrap();
+
+                    let existing = inner.toplevel.as_ref().and_then(|t| t.upgrade().ok());
+                    if existing.as_ref() == Some(&toplevel) {
+                        return true;
+                    }
+
+                    inner.toplevel = Some(toplevel.downgrade());
+                    inner.modal = false;
+                    false
+                });
+
+                data_init.init(id, data);
+
+                if already_used {
+                    resource.post_error(
+                        xdg_wm_dialog_v1::Error::AlreadyUsed,
+                        "the xdg_toplevel already has an xdg_dialog_v1",
+                    );
+                }
+            }
+            xdg_wm_dialog_v1::Request::Destroy => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<D> Dispatch<XdgDialogV1, XdgDialogUserData, D> for XdgDialogState
+where
+    D: Dispatch<XdgDialogV1, XdgDialogUserData>,
+    D: XdgDialogHandler,
+    D: 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        _resource: &XdgDialogV1,
+        request: <XdgDialogV1 as Resource>::Request,
+        data: &XdgDialogUserData,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        let (modal, destroy) = match request {
+            xdg_dialog_v1::Request::SetModal => (true, false),
+            xdg_dialog_v1::Request::UnsetModal => (false, false),
+            // Destroying the dialog object unapplies its effects.
+            xdg_dialog_v1::Request::Destroy => (false, true),
+            _ => unreachable!(),
+        };
+
+        let Ok(toplevel) = data.toplevel.upgrade() else {
+            // The dialog is inert.
+            return;
+        };
+        let Some(surface) = state.toplevel_surface(&toplevel) else {
+            return;
+        };
+
+        let changed = with_states(&surface, |states| {
+            let Some(data) = states.data_map.get::<XdgDialogSurfaceData>() else {
+                return false;
+            };
+            let mut inner = data.inner.lock().unwrap();
+
+            let changed = inner.modal != modal;
+            inner.modal = modal;
+            if destroy {
+                inner.toplevel = None;
+            }
+            changed
+        });
+
+        if changed {
+            state.modal_changed(&toplevel);
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! delegate_xdg_dialog {
+    ($(@<$( $lt:tt $( : $clt:tt $(+ $dlt:tt )* )? ),+>)? $ty: ty) => {
+        smithay::reexports::wayland_server::delegate_global_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::xdg::dialog::v1::server::xdg_wm_dialog_v1::XdgWmDialogV1: $crate::protocols::xdg_dialog::XdgDialogGlobalData
+        ] => $crate::protocols::xdg_dialog::XdgDialogState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::xdg::dialog::v1::server::xdg_wm_dialog_v1::XdgWmDialogV1: ()
+        ] => $crate::protocols::xdg_dialog::XdgDialogState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::xdg::dialog::v1::server::xdg_dialog_v1::XdgDialogV1: $crate::protocols::xdg_dialog::XdgDialogUserData
+        ] => $crate::protocols::xdg_dialog::XdgDialogState);
+    };
+}
@@ -0,0 +1,99 @@
+use smithay::reexports::wayland_protocols::xdg::system_bell::v1::server::xdg_system_bell_v1;
+use smithay::reexports::wayland_server::protocol::wl_surface::WlSurface;
+use smithay::reexports::wayland_server::{
+    Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, New, Resource,
+};
+use xdg_system_bell_v1::XdgSystemBellV1;
+
+const VERSION: u32 = 1;
+
+pub struct XdgSystemBellState {}
+
+pub struct XdgSystemBellGlobalData {
+    filter: Box<dyn for<'c> Fn(&'c Client) -> bool + Send + Sync>,
+}
+
+pub trait XdgSystemBellHandler {
+    fn xdg_system_bell_state(&mut self) -> &mut XdgSystemBellState;
+    /// Rings the system bell, optionally on behalf of a surface.
+    fn ring(&mut self, surface: Option<WlSurface>);
+}
+
+impl XdgSystemBellState {
+    pub fn new<D, F>(display: &DisplayHandle, filter: F) -> Self
+    where
+        D: GlobalDispatch<XdgSystemBellV1, XdgSystemBellGlobalData>,
+        D: Dispatch<XdgSystemBellV1, ()>,
+        D: XdgSystemBellHandler,
+        D: 'static,
+        F: for<'c> Fn(&'c Client) -> bool + Send + Sync + 'static,
+    {
+        let global_data = XdgSystemBellGlobalData {
+            filter: Box::new(filter),
+        };
+        display.create_global::<D, XdgSystemBellV1, _>(VERSION, global_data);
+
+        Self {}
+    }
+}
+
+impl<D> GlobalDispatch<XdgSystemBellV1, XdgSystemBellGlobalData, D> for XdgSystemBellState
+where
+    D: GlobalDispatch<XdgSystemBellV1, XdgSystemBellGlobalData>,
+    D: Dispatch<XdgSystemBellV1, ()>,
+    D: XdgSystemBellHandler,
+    D: 'static,
+{
+    fn bind(
+        _state: &mut D,
+        _handle: &DisplayHandle,
+        _client: &Client,
+        resource: New<XdgSystemBellV1>,
+        _global_data: &XdgSystemBellGlobalData,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        data_init.init(resource, ());
+    }
+
+    fn can_view(client: Client, global_data: &XdgSystemBellGlobalData) -> bool {
+        (global_data.filter)(&client)
+    }
+}
+
+impl<D> Dispatch<XdgSystemBellV1, (), D> for XdgSystemBellState
+where
+    D: Dispatch<XdgSystemBellV1, ()>,
+    D: XdgSystemBellHandler,
+    D: 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        _resource: &XdgSystemBellV1,
+        request: <XdgSystemBellV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            xdg_system_bell_v1::Request::Ring { surface } => {
+                state.ring(surface);
+            }
+            xdg_system_bell_v1::Request::Destroy => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! delegate_xdg_system_bell {
+    ($(@<$( $lt:tt $( : $clt:tt $(+ $dlt:tt )* )? ),+>)? $ty: ty) => {
+        smithay::reexports::wayland_server::delegate_global_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::xdg::system_bell::v1::server::xdg_system_bell_v1::XdgSystemBellV1: $crate::protocols::xdg_system_bell::XdgSystemBellGlobalData
+        ] => $crate::protocols::xdg_system_bell::XdgSystemBellState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::xdg::system_bell::v1::server::xdg_system_bell_v1::XdgSystemBellV1: ()
+        ] => $crate::protocols::xdg_system_bell::XdgSystemBellState);
+    };
+}
@@ -0,0 +1,239 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use smithay::reexports::wayland_protocols::xdg::shell::server::xdg_toplevel::XdgToplevel;
+use smithay::reexports::wayland_protocols::xdg::toplevel_drag::v1::server::{
+    xdg_toplevel_drag_manager_v1, xdg_toplevel_drag_v1,
+};
+use smithay::reexports::wayland_server::protocol::wl_data_source::WlDataSource;
+use smithay::reexports::wayland_server::{
+    Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, New, Resource, Weak,
+};
+use smithay::utils::{Logical, Point};
+use xdg_toplevel_drag_manager_v1::XdgToplevelDragManagerV1;
+use xdg_toplevel_drag_v1::XdgToplevelDragV1;
+
+const VERSION: u32 = 1;
+
+pub struct XdgToplevelDragManagerState {
+    drags: Vec<XdgToplevelDragV1>,
+}
+
+pub struct XdgToplevelDragManagerGlobalData {
+    filter: Box<dyn for<'c> Fn(&'c Client) -> bool + Send + Sync>,
+}
+
+pub trait XdgToplevelDragHandler {
+    fn xdg_toplevel_drag_manager_state(&mut self) -> &mut XdgToplevelDragManagerState;
+    /// Called when the client attaches a toplevel to the drag of the data source.
+    ///
+    /// The offset is the position of the pointer relative to the toplevel window geometry.
+    fn toplevel_attached(
+        &mut self,
+        source: &WlDataSource,
+        toplevel: &XdgToplevel,
+        offset: Point<i32, Logical>,
+    );
+    /// Returns whether the toplevel is currently mapped.
+    fn is_toplevel_mapped(&mut self, toplevel: &XdgToplevel) -> bool;
+}
+
+pub struct XdgToplevelDragUserData {
+    source: WlDataSource,
+    attached: Mutex<Option<(Weak<XdgToplevel>, Point<i32, Logical>)>>,
+    /// Whether the drag of the data source has started and hasn't ended yet.
+    ongoing: AtomicBool,
+}
+
+impl XdgToplevelDragManagerState {
+    pub fn new<D, F>(display: &DisplayHandle, filter: F) -> Self
+    where
+        D: GlobalDispatch<XdgToplevelDragManagerV1, XdgToplevelDragManagerGlobalData>,
+        D: Dispatch<XdgToplevelDragManagerV1, ()>,
+        D: Dispatch<XdgToplevelDragV1, XdgToplevelDragUserData>,
+        D: XdgToplevelDragHandler,
+        D: 'static,
+        F: for<'c> Fn(&'c Client) -> bool + Send + Sync + 'static,
+    {
+        let global_data = XdgToplevelDragManagerGlobalData {
+            filter: Box::new(filter),
+        };
+        display.create_global::<D, XdgToplevelDragManagerV1, _>(VERSION, global_data);
+
+        Self { drags: Vec::new() }
+    }
+
+    /// Returns whether the data source has a toplevel drag object.
+    pub fn is_toplevel_drag(&mut self, source: &WlDataSource) -> bool {
+        self.drag_for_source(source).is_some()
+    }
+
+    /// Returns the toplevel attached to the drag of the data source, and its offset.
+    pub fn attached_toplevel(
+        &mut self,
+        source: &WlDataSource,
+    ) -> Option<(XdgToplevel, Point<i32, Logical>)> {
+        let drag = self.drag_for_source(source)?;
+        let data = drag.data::<XdgToplevelDragUserData>().unwrap();
+        let attached = data.attached.lock().unwrap();
+        let (toplevel, offset) = attached.as_ref()?;
+        Some((toplevel.upgrade().ok()?, *offset))
+    }
+
+    /// Marks the drag of the data source as started or ended.
+    ///
+    /// The client may only destroy the toplevel drag object once the drag has ended.
+    pub fn set_drag_ongoing(&mut self, source: &WlDataSource, ongoing: bool) {
+        if let Some(drag) = self.drag_for_source(source) {
+            let data = drag.data::<XdgToplevelDragUserData>().unwrap();
+            data.ongoing.store(ongoing, Ordering::SeqCst);
+        }
+    }
+
+    fn drag_for_source(&mut self, source: &WlDataSource) -> Option<&XdgToplevelDragV1> {
+        self.drags.retain(|drag| drag.is_alive());
+        self.drags.iter().find(|drag| {
+            let data = drag.data::<XdgToplevelDragUserData>().unwrap();
+            data.source == *source
+        })
+    }
+}
+
+impl<D> GlobalDispatch<XdgToplevelDragManagerV1, XdgToplevelDragManagerGlobalData, D>
+    for XdgToplevelDragManagerState
+where
+    D: GlobalDispatch<XdgToplevelDragManagerV1, XdgToplevelDragManagerGlobalData>,
+    D: Dispatch<XdgToplevelDragManagerV1, ()>,
+    D: Dispatch<XdgToplevelDragV1, XdgToplevelDragUserData>,
+    D: XdgToplevelDragHandler,
+    D: 'static,
+{
+    fn bind(
+        _state: &mut D,
+        _handle: &DisplayHandle,
+        _client: &Client,
+        resource: New<XdgToplevelDragManagerV1>,
+        _global_data: &XdgToplevelDragManagerGlobalData,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        data_init.init(resource, ());
+    }
+
+    fn can_view(client: Client, global_data: &XdgToplevelDragManagerGlobalData) -> bool {
+        (global_data.filter)(&client)
+    }
+}
+
+impl<D> Dispatch<XdgToplevelDragManagerV1, (), D> for XdgToplevelDragManagerState
+where
+    D: Dispatch<XdgToplevelDragManagerV1, ()>,
+    D: Dispatch<XdgToplevelDragV1, XdgToplevelDragUserData>,
+    D: XdgToplevelDragHandler,
+    D: 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        resource: &XdgToplevelDragManagerV1,
+        request: <XdgToplevelDragManagerV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            xdg_toplevel_drag_manager_v1::Request::GetXdgToplevelDrag { id, data_source } => {
+                let manager_state = state.xdg_toplevel_drag_manager_state();
+                let already_used = manager_state.is_toplevel_drag(&data_source);
+
+                let drag = data_init.init(
+                    id,
+                    XdgToplevelDragUserData {
+                        source: data_source,
+                        attached: Mutex::new(None),
+                        ongoing: AtomicBool::new(false),
+                    },
+                );
+
+                if already_used {
+                    resource.post_error(
+                        xdg_toplevel_drag_manager_v1::Error::InvalidSource,
+                        "the data source already has an xdg_toplevel_drag_v1",
+                    );
+                    return;
+                }
+
+                manager_state.drags.push(drag);
+            }
+            xdg_toplevel_drag_manager_v1::Request::Destroy => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<D> Dispatch<XdgToplevelDragV1, XdgToplevelDragUserData, D> for XdgToplevelDragManagerState
+where
+    D: Dispatch<XdgToplevelDragV1, XdgToplevelDragUserData>,
+    D: XdgToplevelDragHandler,
+    D: 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        resource: &XdgToplevelDragV1,
+        request: <XdgToplevelDragV1 as Resource>::Request,
+        data: &XdgToplevelDragUserData,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            xdg_toplevel_drag_v1::Request::Attach {
+                toplevel,
+                x_offset,
+                y_offset,
+            } => {
+                // An unmapped toplevel is detached automatically, so the client may attach again.
+                let attached = data.attached.lock().unwrap().clone();
+                let still_attached = attached
+                    .and_then(|(toplevel, _)| toplevel.upgrade().ok())
+                    .is_some_and(|toplevel| state.is_toplevel_mapped(&toplevel));
+                if still_attached {
+                    resource.post_error(
+                        xdg_toplevel_drag_v1::Error::ToplevelAttached,
+                        "a mapped toplevel is already attached",
+                    );
+                    return;
+                }
+
+                let offset = Point::from((x_offset, y_offset));
+                *data.attached.lock().unwrap() = Some((toplevel.downgrade(), offset));
+                state.toplevel_attached(&data.source, &toplevel, offset);
+            }
+            xdg_toplevel_drag_v1::Request::Destroy => {
+                if data.ongoing.load(Ordering::SeqCst) {
+                    resource.post_error(
+                        xdg_toplevel_drag_v1::Error::OngoingDrag,
+                        "the drag has not ended yet",
+                    );
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! delegate_xdg_toplevel_drag {
+    ($(@<$( $lt:tt $( : $clt:tt $(+ $dlt:tt )* )? ),+>)? $ty: ty) => {
+        smithay::reexports::wayland_server::delegate_global_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::xdg::toplevel_drag::v1::server::xdg_toplevel_drag_manager_v1::XdgToplevelDragManagerV1: $crate::protocols::xdg_toplevel_drag::XdgToplevelDragManagerGlobalData
+        ] => $crate::protocols::xdg_toplevel_drag::XdgToplevelDragManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::xdg::toplevel_drag::v1::server::xdg_toplevel_drag_manager_v1::XdgToplevelDragManagerV1: ()
+        ] => $crate::protocols::xdg_toplevel_drag::XdgToplevelDragManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::xdg::toplevel_drag::v1::server::xdg_toplevel_drag_v1::XdgToplevelDragV1: $crate::protocols::xdg_toplevel_drag::XdgToplevelDragUserData
+        ] => $crate::protocols::xdg_toplevel_drag::XdgToplevelDragManagerState);
+    };
+}
@@ -0,0 +1,453 @@
+use std::sync::{Arc, Mutex};
+
+use smithay::backend::allocator::Fourcc;
+use smithay::reexports::wayland_protocols::xdg::shell::server::xdg_toplevel::XdgToplevel;
+use smithay::reexports::wayland_protocols::xdg::toplevel_icon::v1::server::{
+    xdg_toplevel_icon_manager_v1, xdg_toplevel_icon_v1,
+};
+use smithay::reexports::wayland_server::protocol::wl_shm;
+use smithay::reexports::wayland_server::protocol::wl_surface::WlSurface;
+use smithay::reexports::wayland_server::{
+    Client, DataInit, Dispatch, DisplayHandle, GlobalDispatch, New, Resource,
+};
+use smithay::utils::Transform;
+use smithay::wayland::compositor::{with_states, Cacheable};
+use smithay::wayland::shm::{with_buffer_contents, BufferData};
+use xdg_toplevel_icon_manager_v1::XdgToplevelIconManagerV1;
+use xdg_toplevel_icon_v1::XdgToplevelIconV1;
+
+use crate::render_helpers::memory::MemoryBuffer;
+
+const VERSION: u32 = 1;
+
+/// Icon sizes that we advertise to clients, in logical pixels.
+const PREFERRED_ICON_SIZES: [i32; 2] = [32, 64];
+
+pub struct XdgToplevelIconManagerState {}
+
+pub struct XdgToplevelIconManagerGlobalData {
+    filter: Box<dyn for<'c> Fn(&'c Client) -> bool + Send + Sync>,
+}
+
+pub trait XdgToplevelIconHandler {
+    fn xdg_toplevel_icon_manager_state(&mut self) -> &mut XdgToplevelIconManagerState;
+    /// Returns the surface of the given toplevel, if it is still alive.
+    fn toplevel_surface(&mut self, toplevel: &XdgToplevel) -> Option<WlSurface>;
+}
+
+/// Toplevel icon as set by the client.
+#[derive(Debug, Clone, Default)]
+pub struct ToplevelIcon {
+    /// Icon name in the XDG icon theme.
+    pub name: Option<String>,
+    /// Pixel buffers, sorted by size in pixels.
+    pub buffers: Vec<ToplevelIconBuffer>,
+}
+
+/// Pixel data of a toplevel icon.
+#[derive(Clone)]
+pub struct ToplevelIconBuffer {
+    /// Scale the client has specified for this buffer.
+    pub scale: i32,
+    /// Copy of the pixel data, in either ARGB8888 or XRGB8888.
+    pub buffer: MemoryBuffer,
+}
+
+/// Double-buffered toplevel icon state of a surface.
+#[derive(Debug, Clone, Default)]
+pub struct ToplevelIconCachedState {
+    pub icon: Option<Arc<ToplevelIcon>>,
+}
+
+/// Icon being built by the client.
+#[derive(Default)]
+pub struct ToplevelIconUserData {
+    inner: Mutex<ToplevelIconUserDataInner>,
+}
+
+#[derive(Default)]
+struct ToplevelIconUserDataInner {
+    icon: ToplevelIcon,
+    /// Whether the icon was assigned to a toplevel and cannot be changed anymore.
+    immutable: bool,
+}
+
+impl ToplevelIcon {
+    /// Returns whether the icon has neither a name nor any pixel data.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.buffers.is_empty()
+    }
+
+    /// Picks the smallest buffer at least `size` pixels large, falling back to the largest one.
+    ///
+    /// Returns the buffer along with its index.
+    pub fn best_buffer(&self, size: i32) -> Option<(usize, &ToplevelIconBuffer)> {
+        let idx = self.buffers.iter().position(|b| b.size() >= size);
+        let idx = idx.or_else(|| self.buffers.len().checked_sub(1))?;
+        Some((idx, &self.buffers[idx]))
+    }
+
+    /// Adds a buffer, keeping the buffers sorted by size.
+    ///
+    /// Data from the last request overrides a buffer of the same size and scale.
+    fn add_buffer(&mut self, buffer: ToplevelIconBuffer) {
+        let buffers = &mut self.buffers;
+        buffers.retain(|b| b.size() != buffer.size() || b.scale != buffer.scale);
+        let idx = buffers.partition_point(|b| b.size() < buffer.size());
+        buffers.insert(idx, buffer);
+    }
+}
+
+impl ToplevelIconBuffer {
+    /// Returns the size in pixels of this buffer.
+    pub fn size(&self) -> i32 {
+        self.buffer.size().w
+    }
+
+    /// Returns the pixel data converted to non-premultiplied RGBA8888, suitable for PNG encoding.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let has_alpha = self.buffer.format() == Fourcc::Argb8888;
+
+        let mut pixels = self.buffer.data().to_vec();
+        for px in pixels.chunks_exact_mut(4) {
+            // Little-endian ARGB8888 is BGRA in memory.
+            let [b, g, r, a] = [px[0], px[1], px[2], px[3]];
+            let a = if has_alpha { a } else { u8::MAX };
+
+            let unpremultiply = |c: u8| {
+                if a == 0 {
+                    0
+                } else {
+                    ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8
+                }
+            };
+            px.copy_from_slice(&[unpremultiply(r), unpremultiply(g), unpremultiply(b), a]);
+        }
+
+        pixels
+    }
+}
+
+impl std::fmt::Debug for ToplevelIconBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToplevelIconBuffer")
+            .field("size", &self.size())
+            .field("scale", &self.scale)
+            .field("format", &self.buffer.format())
+            .finish()
+    }
+}
+
+impl Cacheable for ToplevelIconCachedState {
+    fn commit(&mut self, _dh: &DisplayHandle) -> Self {
+        self.clone()
+    }
+
+    fn merge_into(self, into: &mut Self, _dh: &DisplayHandle) {
+        *into = self;
+    }
+}
+
+/// Returns the current icon of a toplevel surface.
+pub fn toplevel_icon(surface: &WlSurface) -> Option<Arc<ToplevelIcon>> {
+    with_states(surface, |states| {
+        let mut guard = states.cached_state.get::<ToplevelIconCachedState>();
+        guard.current().icon.clone()
+    })
+}
+
+impl XdgToplevelIconManagerState {
+    pub fn new<D, F>(display: &DisplayHandle, filter: F) -> Self
+    where
+        D: GlobalDispatch<XdgToplevelIconManagerV1, XdgToplevelIconManagerGlobalData>,
+        D: Dispatch<XdgToplevelIconManagerV1, ()>,
+        D: Dispatch<XdgToplevelIconV1, ToplevelIconUserData>,
+        D: XdgToplevelIconHandler,
+        D: 'static,
+        F: for<'c> Fn(&'c Client) -> bool + Send + Sync + 'static,
+    {
+        let global_data = XdgToplevelIconManagerGlobalData {
+            filter: Box::new(filter),
+        };
+        display.create_global::<D, XdgToplevelIconManagerV1, _>(VERSION, global_data);
+
+        Self {}
+    }
+}
+
+impl<D> GlobalDispatch<XdgToplevelIconManagerV1, XdgToplevelIconManagerGlobalData, D>
+    for XdgToplevelIconManagerState
+where
+    D: GlobalDispatch<XdgToplevelIconManagerV1, XdgToplevelIconManagerGlobalData>,
+    D: Dispatch<XdgToplevelIconManagerV1, ()>,
+    D: Dispatch<XdgToplevelIconV1, ToplevelIconUserData>,
+    D: XdgToplevelIconHandler,
+    D: 'static,
+{
+    fn bind(
+        _state: &mut D,
+        _handle: &DisplayHandle,
+        _client: &Client,
+        manager: New<XdgToplevelIconManagerV1>,
+        _manager_state: &XdgToplevelIconManagerGlobalData,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        let manager = data_init.init(manager, ());
+
+        for size in PREFERRED_ICON_SIZES {
+            manager.icon_size(size);
+        }
+        manager.done();
+    }
+
+    fn can_view(client: Client, global_data: &XdgToplevelIconManagerGlobalData) -> bool {
+        (global_data.filter)(&client)
+    }
+}
+
+impl<D> Dispatch<XdgToplevelIconManagerV1, (), D> for XdgToplevelIconManagerState
+where
+    D: Dispatch<XdgToplevelIconManagerV1, ()>,
+    D: Dispatch<XdgToplevelIconV1, ToplevelIconUserData>,
+    D: XdgToplevelIconHandler,
+    D: 'static,
+{
+    fn request(
+        state: &mut D,
+        _client: &Client,
+        _resource: &XdgToplevelIconManagerV1,
+        request: <XdgToplevelIconManagerV1 as Resource>::Request,
+        _data: &(),
+        _dhandle: &DisplayHandle,
+        data_init: &mut DataInit<'_, D>,
+    ) {
+        match request {
+            xdg_toplevel_icon_manager_v1::Request::CreateIcon { id } => {
+                data_init.init(id, ToplevelIconUserData::default());
+            }
+            xdg_toplevel_icon_manager_v1::Request::SetIcon { toplevel, icon } => {
+                let Some(surface) = state.toplevel_surface(&toplevel) else {
+                    return;
+                };
+
+                let icon = icon.and_then(|icon| {
+                    let data = icon.data::<ToplevelIconUserData>().unwrap();
+                    let mut inner = data.inner.lock().unwrap();
+                    inner.immutable = true;
+
+                    // An icon with neither buffers nor a name resets the toplevel icon.
+                    (!inner.icon.is_empty()).then(|| Arc::new(inner.icon.clone()))
+                });
+
+                with_states(&surface, |states| {
+                    let mut guard = states.cached_state.get::<ToplevelIconCachedState>();
+                    guard.pending().icon = icon;
+                });
+            }
+            xdg_toplevel_icon_manager_v1::Request::Destroy => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<D> Dispatch<XdgToplevelIconV1, ToplevelIconUserData, D> for XdgToplevelIconManagerState
+where
+    D: Dispatch<XdgToplevelIconV1, ToplevelIconUserData>,
+    D: XdgToplevelIconHandler,
+    D: 'static,
+{
+    fn request(
+        _state: &mut D,
+        _client: &Client,
+        resource: &XdgToplevelIconV1,
+        request: <XdgToplevelIconV1 as Resource>::Request,
+        data: &ToplevelIconUserData,
+        _dhandle: &DisplayHandle,
+        _data_init: &mut DataInit<'_, D>,
+    ) {
+        let mut inner = data.inner.lock().unwrap();
+
+        match request {
+            xdg_toplevel_icon_v1::Request::SetName { icon_name } => {
+                if inner.immutable {
+                    resource.post_error(
+                        xdg_toplevel_icon_v1::Error::Immutable,
+                        "icon was already assigned to a toplevel",
+                    );
+                    return;
+                }
+
+                inner.icon.name = Some(icon_name);
+            }
+            xdg_toplevel_icon_v1::Request::AddBuffer { buffer, scale } => {
+                if inner.immutable {
+                    resource.post_error(
+                        xdg_toplevel_icon_v1::Error::Immutable,
+                        "icon was already assigned to a toplevel",
+                    );
+                    return;
+                }
+
+                // The buffer contents must not change after this request, so copy them right
+                // away rather than keeping the buffer around.
+                let res = with_buffer_contents(&buffer, |ptr, len, data| {
+                    let BufferData {
+                        offset,
+                        width,
+                        height,
+                        stride,
+                        format,
+                    } = data;
+
+                    if width != height || width <= 0 {
+                        return Err("icon buffer must be square");
+                    }
+
+                    let fourcc = match format {
+                        wl_shm::Format::Argb8888 => Fourcc::Argb8888,
+                        wl_shm::Format::Xrgb8888 => Fourcc::Xrgb8888,
+                        _ => return Err("unsupported icon buffer format"),
+                    };
+
+                    let row_len = width as usize * 4;
+                    let (offset, stride) = (offset as usize, stride as usize);
+                    if stride < row_len || offset + stride * (height as usize - 1) + row_len > len {
+                        return Err("icon buffer is out of bounds");
+                    }
+
+                    // SAFETY: we checked the bounds above.
+                    let src = unsafe { std::slice::from_raw_parts(ptr, len) };
+                    let mut pixels = Vec::with_capacity(row_len * height as usize);
+                    for row in 0..height as usize {
+                        let start = offset + row * stride;
+                        pixels.extend_from_slice(&src[start..start + row_len]);
+                    }
+
+                    Ok(MemoryBuffer::new(
+                        pixels,
+                        fourcc,
+                        (width, height),
+                        f64::from(scale.max(1)),
+                        Transform::Normal,
+                    ))
+                });
+
+                let buffer = match res {
+                    Ok(Ok(buffer)) => buffer,
+                    Ok(Err(msg)) => {
+                        resource.post_error(xdg_toplevel_icon_v1::Error::InvalidBuffer, msg);
+                        return;
+                    }
+                    Err(_) => {
+                        resource.post_error(
+                            xdg_toplevel_icon_v1::Error::InvalidBuffer,
+                            "icon buffer must be a wl_shm buffer",
+                        );
+                        return;
+                    }
+                };
+
+                inner.icon.add_buffer(ToplevelIconBuffer { scale, buffer });
+            }
+            xdg_toplevel_icon_v1::Request::Destroy => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! delegate_xdg_toplevel_icon {
+    ($(@<$( $lt:tt $( : $clt:tt $(+ $dlt:tt )* )? ),+>)? $ty: ty) => {
+        smithay::reexports::wayland_server::delegate_global_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::xdg::toplevel_icon::v1::server::xdg_toplevel_icon_manager_v1::XdgToplevelIconManagerV1: $crate::protocols::xdg_toplevel_icon::XdgToplevelIconManagerGlobalData
+        ] => $crate::protocols::xdg_toplevel_icon::XdgToplevelIconManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::xdg::toplevel_icon::v1::server::xdg_toplevel_icon_manager_v1::XdgToplevelIconManagerV1: ()
+        ] => $crate::protocols::xdg_toplevel_icon::XdgToplevelIconManagerState);
+
+        smithay::reexports::wayland_server::delegate_dispatch!($(@< $( $lt $( : $clt $(+ $dlt )* )? ),+ >)? $ty: [
+            smithay::reexports::wayland_protocols::xdg::toplevel_icon::v1::server::xdg_toplevel_icon_v1::XdgToplevelIconV1: $crate::protocols::xdg_toplevel_icon::ToplevelIconUserData
+        ] => $crate::protocols::xdg_toplevel_icon::XdgToplevelIconManagerState);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(size: i32, scale: i32, fourcc: Fourcc, pixel: [u8; 4]) -> ToplevelIconBuffer {
+        let data = pixel.repeat((size * size) as usize);
+        ToplevelIconBuffer {
+            scale,
+            buffer: MemoryBuffer::new(
+                data,
+                fourcc,
+                (size, size),
+                f64::from(scale),
+                Transform::Normal,
+            ),
+        }
+    }
+
+    fn icon(sizes: &[i32]) -> ToplevelIcon {
+        let mut icon = ToplevelIcon::default();
+        for &size in sizes {
+            icon.add_buffer(buffer(size, 1, Fourcc::Argb8888, [0; 4]));
+        }
+        icon
+    }
+
+    #[test]
+    fn best_buffer_picks_smallest_large_enough() {
+        let icon = icon(&[64, 16, 32]);
+
+        let size = |size| icon.best_buffer(size).map(|(idx, b)| (idx, b.size()));
+        assert_eq!(size(1), Some((0, 16)));
+        assert_eq!(size(16), Some((0, 16)));
+        assert_eq!(size(17), Some((1, 32)));
+        assert_eq!(size(48), Some((2, 64)));
+        // Falls back to the largest buffer.
+        assert_eq!(size(128), Some((2, 64)));
+    }
+
+    #[test]
+    fn best_buffer_of_empty_icon() {
+        assert!(ToplevelIcon::default().best_buffer(32).is_none());
+    }
+
+    #[test]
+    fn add_buffer_replaces_same_size_and_scale() {
+        let mut icon = ToplevelIcon::default();
+        icon.add_buffer(buffer(32, 1, Fourcc::Argb8888, [1, 1, 1, 255]));
+        icon.add_buffer(buffer(16, 1, Fourcc::Argb8888, [0; 4]));
+        icon.add_buffer(buffer(32, 2, Fourcc::Argb8888, [3, 3, 3, 255]));
+        icon.add_buffer(buffer(32, 1, Fourcc::Argb8888, [2, 2, 2, 255]));
+
+        let buffers: Vec<_> = icon
+            .buffers
+            .iter()
+            .map(|b| (b.size(), b.scale, b.buffer.data()[0]))
+            .collect();
+        assert_eq!(buffers, [(16, 1, 0), (32, 2, 3), (32, 1, 2)]);
+    }
+
+    #[test]
+    fn to_rgba8_unpremultiplies() {
+        // Little-endian ARGB8888 is BGRA in memory.
+        let rgba = |fourcc, pixel| buffer(1, 1, fourcc, pixel).to_rgba8();
+
+        assert_eq!(rgba(Fourcc::Argb8888, [30, 20, 10, 0]), [0, 0, 0, 0]);
+        assert_eq!(
+            rgba(Fourcc::Argb8888, [0, 64, 128, 128]),
+            [255, 128, 0, 128]
+        );
+        assert_eq!(rgba(Fourcc::Argb8888, [30, 20, 10, 255]), [10, 20, 30, 255]);
+    }
+
+    #[test]
+    fn to_rgba8_treats_xrgb_as_opaque() {
+        let rgba = buffer(1, 1, Fourcc::Xrgb8888, [30, 20, 10, 0]).to_rgba8();
+        assert_eq!(rgba, [10, 20, 30, 255]);
+    }
+}
@@ -34,16 +34,17 @@ use smithay::backend::allocator::gbm::{GbmBuffer, GbmBufferFlags, GbmDevice};
 use smithay::backend::allocator::{Format, Fourcc};
 use smithay::backend::drm::DrmDeviceFd;
 use smithay::backend::renderer::damage::OutputDamageTracker;
+use smithay::backend::renderer::element::utils::{Relocate, RelocateRenderElement};
 use smithay::backend::renderer::element::RenderElement;
 use smithay::backend::renderer::gles::GlesRenderer;
 use smithay::output::{Output, OutputModeSource};
 use smithay::reexports::calloop::generic::Generic;
 use smithay::reexports::calloop::{Interest, LoopHandle, Mode, PostAction};
 use smithay::reexports::gbm::Modifier;
-use smithay::utils::{Physical, Scale, Size, Transform};
+use smithay::utils::{Physical, Point, Scale, Size, Transform};
 use zbus::object_server::SignalEmitter;
 
-use crate::dbus::mutter_screen_cast::{self, CursorMode};
+use crate::dbus::mutter_screen_cast::{self, CastClient, CursorMode};
 use crate::niri::{CastTarget, State};
 use crate::render_helpers::{clear_dmabuf, render_to_dmabuf};
 use crate::utils::get_monotonic_time;
@@ -77,6 +78,7 @@ pub struct Cast {
     refresh: Rc<Cell<u32>>,
     offer_alpha: bool,
     pub cursor_mode: CursorMode,
+    pub client: CastClient,
     pub last_frame_time: Duration,
     min_time_between_frames: Rc<Cell<Duration>>,
     dmabufs: Rc<RefCell<HashMap<i64, Dmabuf>>>,
@@ -193,6 +195,7 @@ impl PipeWire {
         refresh: u32,
         alpha: bool,
         cursor_mode: CursorMode,
+        client: CastClient,
         signal_ctx: SignalEmitter<'static>,
     ) -> anyhow::Result<Cast> {
         let _span = tracy_client::span!("PipeWire::start_cast");
@@ -660,6 +663,7 @@ impl PipeWire {
             refresh,
             offer_alpha: alpha,
             cursor_mode,
+            client,
             last_frame_time: Duration::ZERO,
             min_time_between_frames,
             dmabufs,
@@ -670,6 +674,11 @@ impl PipeWire {
 }
 
 impl Cast {
+    /// Returns the current (or the pending) size of the stream.
+    pub fn size(&self) -> Size<u32, Physical> {
+        self.state.borrow().expected_format_size()
+    }
+
     pub fn ensure_size(&self, size: Size<i32, Physical>) -> anyhow::Result<CastSizeChange> {
         let new_size = Size::from((size.w as u32, size.h as u32));
 
@@ -893,6 +902,27 @@ impl Cast {
         true
     }
 
+    /// Renders the part of `elements` starting at `offset` and spanning `size`.
+    ///
+    /// Used for area casts, where `elements` are rendered for the whole output.
+    pub fn dequeue_buffer_and_render_cropped(
+        &mut self,
+        renderer: &mut GlesRenderer,
+        elements: &[impl RenderElement<GlesRenderer>],
+        offset: Point<i32, Physical>,
+        size: Size<i32, Physical>,
+        scale: Scale<f64>,
+        wait_for_sync: bool,
+    ) -> bool {
+        let elements = elements
+            .iter()
+            .map(|elem| {
+                RelocateRenderElement::from_element(elem, offset.upscale(-1), Relocate::Relative)
+            })
+            .collect::<Vec<_>>();
+        self.dequeue_buffer_and_render(renderer, &elements, size, scale, wait_for_sync)
+    }
+
     pub fn dequeue_buffer_and_clear(
         &mut self,
         renderer: &mut GlesRenderer,
@@ -0,0 +1,300 @@
+//! Built-in screen recording.
+//!
+//! Frames are produced the same way as for screencasts, but instead of going to PipeWire, they
+//! are written into a directory as numbered PNG images. Next to the images goes an ffconcat
+//! playlist with the frame timings, so the recording can be turned into a video with
+//! `ffmpeg -i frames.ffconcat recording.mp4`.
+//!
+//! Frames are rendered into a texture, and the download from the GPU is only mapped on the next
+//! redraw, by which point it has completed, so the main thread doesn't wait for the GPU. All
+//! frames have the size of the first one; later content of a different size is letterboxed.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::Context as _;
+use smithay::backend::allocator::Fourcc;
+use smithay::backend::renderer::damage::OutputDamageTracker;
+use smithay::backend::renderer::element::utils::{
+    Relocate, RelocateRenderElement, RescaleRenderElement,
+};
+use smithay::backend::renderer::element::RenderElement;
+use smithay::backend::renderer::gles::{GlesMapping, GlesRenderer};
+use smithay::backend::renderer::ExportMem as _;
+use smithay::output::OutputModeSource;
+use smithay::utils::{Physical, Point, Scale, Size, Transform};
+
+use crate::niri::CastTarget;
+use crate::render_helpers::render_and_download;
+use crate::utils::write_png_rgba8;
+
+/// Name of the playlist file inside the recording directory.
+const PLAYLIST: &str = "frames.ffconcat";
+
+/// Number of frames that can wait for encoding before we start dropping new frames.
+const QUEUE_SIZE: usize = 4;
+
+pub struct Recording {
+    /// What is being recorded.
+    pub target: CastTarget,
+    /// Directory that the frames are written to.
+    pub dir: PathBuf,
+    /// Size of all frames, set by the first frame.
+    size: Option<Size<i32, Physical>>,
+    damage_tracker: Option<OutputDamageTracker>,
+    /// Frame that was rendered, but not yet read back from the GPU.
+    in_flight: Option<InFlightFrame>,
+    /// Presentation time of the first frame.
+    start_time: Option<Duration>,
+    /// Presentation time of the last captured frame.
+    last_frame_time: Option<Duration>,
+    min_time_between_frames: Duration,
+    frame_idx: u64,
+    to_writer: mpsc::SyncSender<Frame>,
+}
+
+struct InFlightFrame {
+    time: Duration,
+    mapping: GlesMapping,
+}
+
+struct Frame {
+    idx: u64,
+    /// Time since the start of the recording.
+    time: Duration,
+    size: Size<i32, Physical>,
+    /// RGBA8 pixels.
+    pixels: Vec<u8>,
+}
+
+impl Recording {
+    pub fn start(target: CastTarget, dir: PathBuf, max_fps: u32) -> anyhow::Result<Self> {
+        fs::create_dir_all(&dir).context("error creating recording directory")?;
+
+        let playlist = File::create(dir.join(PLAYLIST)).context("error creating playlist")?;
+
+        let (to_writer, from_niri) = mpsc::sync_channel(QUEUE_SIZE);
+
+        let writer_dir = dir.clone();
+        thread::Builder::new()
+            .name("Recording Writer".to_owned())
+            .spawn(move || {
+                if let Err(err) = write_frames(&writer_dir, playlist, from_niri) {
+                    warn!("error writing recording: {err:?}");
+                }
+            })
+            .context("error spawning recording writer thread")?;
+
+        Ok(Self {
+            target,
+            dir,
+            size: None,
+            damage_tracker: None,
+            in_flight: None,
+            start_time: None,
+            last_frame_time: None,
+            min_time_between_frames: Duration::from_secs(1) / max_fps.max(1),
+            frame_idx: 0,
+            to_writer,
+        })
+    }
+
+    /// Returns whether a frame presented at this time should be recorded.
+    pub fn should_capture(&self, time: Duration) -> bool {
+        // Allow a small margin so that frames at exactly the max frame rate aren't dropped.
+        let margin = Duration::from_micros(500);
+        self.last_frame_time.map_or(true, |last| {
+            time + margin >= last + self.min_time_between_frames
+        })
+    }
+
+    /// Renders a frame and starts reading it back from the GPU.
+    ///
+    /// The elements go front to back and cover `content_size`, which is letterboxed into the
+    /// recording size.
+    pub fn render_frame(
+        &mut self,
+        renderer: &mut GlesRenderer,
+        time: Duration,
+        elements: &[impl RenderElement<GlesRenderer>],
+        content_size: Size<i32, Physical>,
+        scale: Scale<f64>,
+    ) -> anyhow::Result<()> {
+        let _span = tracy_client::span!("Recording::render_frame");
+
+        // Finish the previous frame first to keep the frames in order.
+        self.read_back(renderer)?;
+
+        let size = *self.size.get_or_insert(content_size);
+        let (offset, zoom) = letterbox(content_size, size);
+        let elements = elements
+            .iter()
+            .map(|elem| {
+                let elem = RescaleRenderElement::from_element(elem, Point::from((0, 0)), zoom);
+                RelocateRenderElement::from_element(elem, offset, Relocate::Relative)
+            })
+            .collect::<Vec<_>>();
+
+        let damage_tracker = self
+            .damage_tracker
+            .get_or_insert_with(|| OutputDamageTracker::new(size, scale, Transform::Normal));
+
+        // The size is fixed, but the scale can change, so check it here.
+        let OutputModeSource::Static { scale: t_scale, .. } = damage_tracker.mode() else {
+            unreachable!();
+        };
+        if *t_scale != scale {
+            *damage_tracker = OutputDamageTracker::new(size, scale, Transform::Normal);
+        }
+
+        let (damage, _states) = damage_tracker.damage_output(1, &elements).unwrap();
+        if damage.is_none() {
+            trace!("no damage, skipping recording frame");
+            return Ok(());
+        }
+
+        let mapping = render_and_download(
+            renderer,
+            size,
+            scale,
+            Transform::Normal,
+            Fourcc::Abgr8888,
+            elements.iter().rev(),
+        )?;
+
+        self.start_time.get_or_insert(time);
+        self.last_frame_time = Some(time);
+        self.in_flight = Some(InFlightFrame { time, mapping });
+
+        Ok(())
+    }
+
+    /// Maps the frame read back from the GPU and sends it to be written.
+    pub fn read_back(&mut self, renderer: &mut GlesRenderer) -> anyhow::Result<()> {
+        let Some(InFlightFrame { time, mapping }) = self.in_flight.take() else {
+            return Ok(());
+        };
+
+        let _span = tracy_client::span!("Recording::read_back");
+
+        let pixels = renderer
+            .map_texture(&mapping)
+            .context("error mapping texture")?
+            .to_vec();
+
+        let frame = Frame {
+            idx: self.frame_idx,
+            time: time.saturating_sub(self.start_time.unwrap_or(time)),
+            size: self.size.unwrap(),
+            pixels,
+        };
+
+        match self.to_writer.try_send(frame) {
+            Ok(()) => self.frame_idx += 1,
+            Err(mpsc::TrySendError::Full(_)) => {
+                debug!("recording writer is lagging behind, dropping frame");
+            }
+            Err(mpsc::TrySendError::Disconnected(_)) => {
+                warn!("recording writer has stopped, dropping frame");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Fits content into a frame, keeping its aspect ratio and centering it.
+///
+/// Returns the offset and the scale for the content. Content is only ever scaled down.
+fn letterbox(
+    content: Size<i32, Physical>,
+    frame: Size<i32, Physical>,
+) -> (Point<i32, Physical>, f64) {
+    let scale_w = f64::from(frame.w) / f64::from(content.w.max(1));
+    let scale_h = f64::from(frame.h) / f64::from(content.h.max(1));
+    let scale = f64::min(scale_w, scale_h).min(1.);
+
+    let scaled = content.to_f64().upscale(scale).to_i32_round::<i32>();
+    let offset = Point::from(((frame.w - scaled.w) / 2, (frame.h - scaled.h) / 2));
+    (offset, scale)
+}
+
+fn frame_name(idx: u64) -> String {
+    format!("frame-{idx:06}.png")
+}
+
+fn write_frames(
+    dir: &Path,
+    playlist: File,
+    from_niri: mpsc::Receiver<Frame>,
+) -> anyhow::Result<()> {
+    let _span = tracy_client::span!("write_frames");
+
+    let mut playlist = BufWriter::new(playlist);
+    writeln!(playlist, "ffconcat version 1.0")?;
+
+    // The playlist stores the duration of every frame, which we only know once the next frame
+    // arrives.
+    let mut last: Option<(u64, Duration)> = None;
+
+    // Runs until the Recording is dropped.
+    while let Ok(frame) = from_niri.recv() {
+        let path = dir.join(frame_name(frame.idx));
+        let file = File::create(&path).context("error creating frame file")?;
+        let w = BufWriter::new(file);
+        write_png_rgba8(w, frame.size.w as u32, frame.size.h as u32, &frame.pixels)
+            .context("error encoding frame")?;
+
+        if let Some((idx, time)) = last {
+            let duration = frame.time.saturating_sub(time);
+            writeln!(playlist, "file '{}'", frame_name(idx))?;
+            writeln!(playlist, "duration {:.6}", duration.as_secs_f64())?;
+            playlist.flush()?;
+        }
+
+        last = Some((frame.idx, frame.time));
+    }
+
+    if let Some((idx, _)) = last {
+        // ffconcat ignores the duration of the last entry, so the last frame goes in twice.
+        writeln!(playlist, "file '{}'", frame_name(idx))?;
+        writeln!(playlist, "file '{}'", frame_name(idx))?;
+    }
+    playlist.flush()?;
+
+    debug!("finished writing recording to {dir:?}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letterbox_same_size() {
+        let size = Size::from((1920, 1080));
+        assert_eq!(letterbox(size, size), (Point::from((0, 0)), 1.));
+    }
+
+    #[test]
+    fn letterbox_smaller_content_is_centered() {
+        let (offset, scale) = letterbox(Size::from((800, 600)), Size::from((1000, 1000)));
+        assert_eq!(offset, Point::from((100, 200)));
+        assert_eq!(scale, 1.);
+    }
+
+    #[test]
+    fn letterbox_larger_content_is_scaled_down() {
+        let (offset, scale) = letterbox(Size::from((2000, 500)), Size::from((1000, 1000)));
+        assert_eq!(offset, Point::from((0, 375)));
+        assert_eq!(scale, 0.5);
+
+        let (offset, scale) = letterbox(Size::from((500, 4000)), Size::from((1000, 1000)));
+        assert_eq!(offset, Point::from((437, 0)));
+        assert_eq!(scale, 0.25);
+    }
+}
@@ -8,9 +8,10 @@ use smithay::backend::renderer::gles::{
 use smithay::backend::renderer::utils::{CommitCounter, DamageSet, OpaqueRegions};
 use smithay::utils::{Buffer, Logical, Physical, Point, Rectangle, Scale, Size, Transform};
 
+use super::color::{ColorDescription, ColorTransform};
 use super::damage::ExtraDamage;
 use super::renderer::{AsGlesFrame as _, NiriRenderer};
-use super::shaders::{mat3_uniform, Shaders};
+use super::shaders::{color_uniforms, mat3_uniform, Shaders};
 use crate::backend::tty::{TtyFrame, TtyRenderer, TtyRendererError};
 
 #[derive(Debug)]
@@ -20,6 +21,8 @@ pub struct ClippedSurfaceRenderElement<R: NiriRenderer> {
     corner_radius: CornerRadius,
     geometry: Rectangle<f64, Logical>,
     uniforms: Vec<Uniform<'static>>,
+    /// Color encoding of the surface contents, if the client has set one.
+    description: Option<ColorDescription>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -83,9 +86,27 @@ impl<R: NiriRenderer> ClippedSurfaceRenderElement<R> {
             corner_radius,
             geometry,
             uniforms,
+            description: None,
         }
     }
 
+    /// Converts the surface contents from the given color encoding when drawing.
+    pub fn with_color_description(mut self, description: ColorDescription) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    fn uniforms(&self) -> Vec<Uniform<'static>> {
+        // We always blend in sRGB.
+        let transform = self
+            .description
+            .and_then(|desc| ColorTransform::new(&desc, &ColorDescription::SRGB));
+
+        let mut uniforms = self.uniforms.clone();
+        uniforms.extend(color_uniforms(transform.as_ref()));
+        uniforms
+    }
+
     pub fn shader(renderer: &mut R) -> Option<&GlesTexProgram> {
         Shaders::get(renderer).clipped_surface.as_ref()
     }
@@ -224,7 +245,7 @@ impl RenderElement<GlesRenderer> for ClippedSurfaceRenderElement<GlesRenderer> {
         damage: &[Rectangle<i32, Physical>],
         opaque_regions: &[Rectangle<i32, Physical>],
     ) -> Result<(), GlesError> {
-        frame.override_default_tex_program(self.program.clone(), self.uniforms.clone());
+        frame.override_default_tex_program(self.program.clone(), self.uniforms());
         RenderElement::<GlesRenderer>::draw(&self.inner, frame, src, dst, damage, opaque_regions)?;
         frame.clear_tex_program_override();
         Ok(())
@@ -250,7 +271,7 @@ impl<'render> RenderElement<TtyRenderer<'render>>
     ) -> Result<(), TtyRendererError<'render>> {
         frame
             .as_gles_frame()
-            .override_default_tex_program(self.program.clone(), self.uniforms.clone());
+            .override_default_tex_program(self.program.clone(), self.uniforms());
         RenderElement::draw(&self.inner, frame, src, dst, damage, opaque_regions)?;
         frame.as_gles_frame().clear_tex_program_override();
         Ok(())
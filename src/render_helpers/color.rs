@@ -0,0 +1,418 @@
+//! Color encodings and conversions between them.
+//!
+//! The conversions here mirror the ones done in the color shaders, so they can be used to check
+//! the math on the CPU, and to compute the uniforms passed to the shaders.
+
+use glam::{DMat3, DVec3, Mat3};
+use niri_config::{HdrTransferFunction, OutputHdr};
+
+/// Transfer characteristic of a color encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransferFunction {
+    /// Piece-wise sRGB transfer function.
+    Srgb,
+    /// Pure power 2.2 transfer function.
+    Gamma22,
+    /// SMPTE ST 2084 (perceptual quantizer).
+    Pq,
+    /// ARIB STD-B67 (hybrid log-gamma).
+    Hlg,
+    /// Linear transfer function.
+    Linear,
+}
+
+/// Chromaticities of the primaries and white point in CIE 1931 xy, multiplied by 1 000 000.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Primaries {
+    pub r: (i32, i32),
+    pub g: (i32, i32),
+    pub b: (i32, i32),
+    pub w: (i32, i32),
+}
+
+/// Luminances of a color encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Luminances {
+    /// Minimum luminance in cd/m², multiplied by 10 000.
+    pub min: u32,
+    /// Maximum luminance in cd/m².
+    pub max: u32,
+    /// Reference white luminance in cd/m².
+    pub reference: u32,
+}
+
+/// Parametric description of a color encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColorDescription {
+    pub tf: TransferFunction,
+    pub primaries: Primaries,
+    pub luminances: Luminances,
+}
+
+/// Conversion from one color encoding to another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorTransform {
+    pub src_tf: TransferFunction,
+    pub dst_tf: TransferFunction,
+    /// Matrix converting linear source RGB into linear destination RGB.
+    ///
+    /// Includes both the gamut conversion and the luminance scaling.
+    pub matrix: DMat3,
+}
+
+// SMPTE ST 2084 constants.
+const PQ_M1: f64 = 2610. / 16384.;
+const PQ_M2: f64 = 2523. / 4096. * 128.;
+const PQ_C1: f64 = 3424. / 4096.;
+const PQ_C2: f64 = 2413. / 4096. * 32.;
+const PQ_C3: f64 = 2392. / 4096. * 32.;
+
+// ARIB STD-B67 constants.
+const HLG_A: f64 = 0.17883277;
+const HLG_B: f64 = 0.28466892;
+const HLG_C: f64 = 0.55991073;
+
+impl TransferFunction {
+    /// Converts a signal value into a relative optical value.
+    ///
+    /// The optical value of 1 corresponds to the peak luminance of the signal, see
+    /// [`ColorDescription::signal_peak()`].
+    pub fn eotf(self, signal: f64) -> f64 {
+        match self {
+            TransferFunction::Srgb => mirrored(signal, |v| {
+                if v <= 0.04045 {
+                    v / 12.92
+                } else {
+                    ((v + 0.055) / 1.055).powf(2.4)
+                }
+            }),
+            TransferFunction::Gamma22 => mirrored(signal, |v| v.powf(2.2)),
+            TransferFunction::Pq => {
+                let p = signal.max(0.).powf(1. / PQ_M2);
+                ((p - PQ_C1).max(0.) / (PQ_C2 - PQ_C3 * p)).powf(1. / PQ_M1)
+            }
+            TransferFunction::Hlg => {
+                let v = signal.max(0.);
+                if v <= 0.5 {
+                    v * v / 3.
+                } else {
+                    (((v - HLG_C) / HLG_A).exp() + HLG_B) / 12.
+                }
+            }
+            TransferFunction::Linear => signal,
+        }
+    }
+
+    /// Converts a relative optical value into a signal value.
+    ///
+    /// This is the inverse of [`TransferFunction::eotf()`].
+    pub fn inverse_eotf(self, optical: f64) -> f64 {
+        match self {
+            TransferFunction::Srgb => mirrored(optical, |v| {
+                if v <= 0.0031308 {
+                    v * 12.92
+                } else {
+                    1.055 * v.powf(1. / 2.4) - 0.055
+                }
+            }),
+            TransferFunction::Gamma22 => mirrored(optical, |v| v.powf(1. / 2.2)),
+            TransferFunction::Pq => {
+                let v = optical.max(0.).powf(PQ_M1);
+                ((PQ_C1 + PQ_C2 * v) / (1. + PQ_C3 * v)).powf(PQ_M2)
+            }
+            TransferFunction::Hlg => {
+                let v = optical.max(0.);
+                if v <= 1. / 12. {
+                    (3. * v).sqrt()
+                } else {
+                    HLG_A * (12. * v - HLG_B).ln() + HLG_C
+                }
+            }
+            TransferFunction::Linear => optical,
+        }
+    }
+
+    /// Returns the value identifying this transfer function in the color shaders.
+    pub fn shader_value(self) -> f32 {
+        match self {
+            TransferFunction::Srgb => 0.,
+            TransferFunction::Gamma22 => 1.,
+            TransferFunction::Pq => 2.,
+            TransferFunction::Hlg => 3.,
+            TransferFunction::Linear => 4.,
+        }
+    }
+}
+
+/// Applies `f` to the absolute value, preserving the sign.
+///
+/// This is how the extended-range SDR transfer functions handle negative values.
+fn mirrored(v: f64, f: impl Fn(f64) -> f64) -> f64 {
+    f(v.abs()).copysign(v)
+}
+
+impl Primaries {
+    /// ITU-R BT.709 primaries, also used by sRGB.
+    pub const SRGB: Self = Self {
+        r: (640_000, 330_000),
+        g: (300_000, 600_000),
+        b: (150_000, 60_000),
+        w: (312_700, 329_000),
+    };
+
+    /// ITU-R BT.2020 primaries.
+    pub const BT2020: Self = Self {
+        r: (708_000, 292_000),
+        g: (170_000, 797_000),
+        b: (131_000, 46_000),
+        w: (312_700, 329_000),
+    };
+
+    /// Display P3 primaries.
+    pub const DISPLAY_P3: Self = Self {
+        r: (680_000, 320_000),
+        g: (265_000, 690_000),
+        b: (150_000, 60_000),
+        w: (312_700, 329_000),
+    };
+
+    /// Returns whether the chromaticities describe a valid color volume.
+    pub fn is_valid(&self) -> bool {
+        [self.r, self.g, self.b, self.w]
+            .into_iter()
+            .all(|(x, y)| 0 <= x && 0 < y && x + y <= 1_000_000)
+            && self.rgb_to_xyz().determinant().abs() > 1e-9
+    }
+
+    /// Returns the matrix converting linear RGB with these primaries into CIE 1931 XYZ.
+    pub fn rgb_to_xyz(&self) -> DMat3 {
+        fn xyz((x, y): (i32, i32)) -> DVec3 {
+            let x = f64::from(x) / 1_000_000.;
+            let y = f64::from(y) / 1_000_000.;
+            DVec3::new(x / y, 1., (1. - x - y) / y)
+        }
+
+        let primaries = DMat3::from_cols(xyz(self.r), xyz(self.g), xyz(self.b));
+        let scale = primaries.inverse() * xyz(self.w);
+        primaries * DMat3::from_diagonal(scale)
+    }
+}
+
+impl Luminances {
+    /// Default luminances of sRGB and the other SDR encodings.
+    pub const SDR: Self = Self {
+        min: 2000,
+        max: 80,
+        reference: 80,
+    };
+
+    /// Default luminances of SMPTE ST 2084.
+    pub const PQ: Self = Self {
+        min: 50,
+        max: 10000,
+        reference: 203,
+    };
+
+    /// Default luminances of ARIB STD-B67.
+    pub const HLG: Self = Self {
+        min: 50,
+        max: 1000,
+        reference: 203,
+    };
+
+    /// Returns the default luminances for the transfer function.
+    pub fn for_tf(tf: TransferFunction) -> Self {
+        match tf {
+            TransferFunction::Pq => Self::PQ,
+            TransferFunction::Hlg => Self::HLG,
+            TransferFunction::Srgb | TransferFunction::Gamma22 | TransferFunction::Linear => {
+                Self::SDR
+            }
+        }
+    }
+}
+
+impl ColorDescription {
+    /// The sRGB encoding, assumed for surfaces without an image description.
+    pub const SRGB: Self = Self {
+        tf: TransferFunction::Srgb,
+        primaries: Primaries::SRGB,
+        luminances: Luminances::SDR,
+    };
+
+    /// Creates a description with default luminances for the transfer function.
+    pub fn new(tf: TransferFunction, primaries: Primaries) -> Self {
+        Self {
+            tf,
+            primaries,
+            luminances: Luminances::for_tf(tf),
+        }
+    }
+
+    /// Creates a BT.2100 HDR description for an output.
+    pub fn bt2100(tf: TransferFunction, reference: u32, max: u32) -> Self {
+        let mut desc = Self::new(tf, Primaries::BT2020);
+        desc.luminances.reference = reference;
+        desc.luminances.max = max;
+        desc
+    }
+
+    /// Returns the description of an output with the given HDR config.
+    pub fn for_output(hdr: Option<&OutputHdr>) -> Self {
+        let Some(hdr) = hdr else {
+            return Self::SRGB;
+        };
+
+        let tf = match hdr.transfer_function {
+            HdrTransferFunction::Pq => TransferFunction::Pq,
+            HdrTransferFunction::Hlg => TransferFunction::Hlg,
+        };
+        Self::bt2100(tf, hdr.reference_luminance, hdr.max_luminance)
+    }
+
+    /// Returns the luminance in cd/m² that corresponds to the optical value of 1.
+    pub fn signal_peak(&self) -> f64 {
+        match self.tf {
+            // PQ is an absolute encoding.
+            TransferFunction::Pq => 10000.,
+            _ => f64::from(self.luminances.max),
+        }
+    }
+}
+
+impl ColorTransform {
+    /// Computes the transform from `src` to `dst`.
+    ///
+    /// Returns `None` if the encodings match and no conversion is needed.
+    ///
+    /// Content is anchored at the reference white: reference white in the source maps to
+    /// reference white in the destination. Luminances outside of the destination range are not
+    /// tone mapped.
+    pub fn new(src: &ColorDescription, dst: &ColorDescription) -> Option<Self> {
+        if src == dst {
+            return None;
+        }
+
+        let gamut = dst.primaries.rgb_to_xyz().inverse() * src.primaries.rgb_to_xyz();
+        let src_scale = src.signal_peak() / f64::from(src.luminances.reference);
+        let dst_scale = f64::from(dst.luminances.reference) / dst.signal_peak();
+
+        Some(Self {
+            src_tf: src.tf,
+            dst_tf: dst.tf,
+            matrix: gamut * (src_scale * dst_scale),
+        })
+    }
+
+    /// Converts a non-premultiplied signal RGB value.
+    pub fn apply(&self, rgb: [f64; 3]) -> [f64; 3] {
+        let linear = DVec3::from(rgb.map(|v| self.src_tf.eotf(v)));
+        let converted = self.matrix * linear;
+        converted.to_array().map(|v| self.dst_tf.inverse_eotf(v))
+    }
+
+    /// Returns the matrix in the form passed to the shaders.
+    pub fn shader_matrix(&self) -> Mat3 {
+        self.matrix.as_mat3()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-4, "{a} != {b}");
+    }
+
+    #[test]
+    fn transfer_function_round_trip() {
+        let tfs = [
+            TransferFunction::Srgb,
+            TransferFunction::Gamma22,
+            TransferFunction::Pq,
+            TransferFunction::Hlg,
+            TransferFunction::Linear,
+        ];
+
+        for tf in tfs {
+            for v in [0., 0.01, 0.2, 0.5, 0.75, 1.] {
+                assert_close(tf.inverse_eotf(tf.eotf(v)), v);
+            }
+        }
+    }
+
+    #[test]
+    fn pq_known_values() {
+        // 100 cd/m² is roughly 0.508 in PQ.
+        assert_close(TransferFunction::Pq.inverse_eotf(100. / 10000.), 0.5081);
+        assert_close(TransferFunction::Pq.eotf(1.), 1.);
+        assert_close(TransferFunction::Pq.eotf(0.), 0.);
+    }
+
+    #[test]
+    fn srgb_extended_range() {
+        let tf = TransferFunction::Srgb;
+        assert_close(tf.eotf(-0.5), -tf.eotf(0.5));
+        assert!(tf.inverse_eotf(2.) > 1.);
+    }
+
+    #[test]
+    fn identical_descriptions_need_no_transform() {
+        let desc = ColorDescription::SRGB;
+        assert_eq!(ColorTransform::new(&desc, &desc), None);
+    }
+
+    #[test]
+    fn primaries_keep_white_point() {
+        for primaries in [Primaries::SRGB, Primaries::BT2020, Primaries::DISPLAY_P3] {
+            assert!(primaries.is_valid());
+
+            let white = primaries.rgb_to_xyz() * DVec3::ONE;
+            assert_close(white.y, 1.);
+        }
+
+        let src = ColorDescription::SRGB;
+        let dst = ColorDescription::new(TransferFunction::Srgb, Primaries::BT2020);
+        let transform = ColorTransform::new(&src, &dst).unwrap();
+        for v in transform.apply([1., 1., 1.]) {
+            assert_close(v, 1.);
+        }
+    }
+
+    #[test]
+    fn srgb_white_maps_to_pq_reference() {
+        let src = ColorDescription::SRGB;
+        let dst = ColorDescription::bt2100(TransferFunction::Pq, 203, 1000);
+        let transform = ColorTransform::new(&src, &dst).unwrap();
+
+        let [r, g, b] = transform.apply([1., 1., 1.]);
+        let expected = TransferFunction::Pq.inverse_eotf(203. / 10000.);
+        assert_close(r, expected);
+        assert_close(g, expected);
+        assert_close(b, expected);
+
+        // And back.
+        let back = ColorTransform::new(&dst, &src).unwrap();
+        for v in back.apply([r, g, b]) {
+            assert_close(v, 1.);
+        }
+    }
+
+    #[test]
+    fn invalid_primaries() {
+        let collinear = Primaries {
+            r: (100_000, 100_000),
+            g: (200_000, 200_000),
+            b: (300_000, 300_000),
+            w: (312_700, 329_000),
+        };
+        assert!(!collinear.is_valid());
+
+        let out_of_range = Primaries {
+            r: (900_000, 900_000),
+            ..Primaries::SRGB
+        };
+        assert!(!out_of_range.is_valid());
+    }
+}
@@ -0,0 +1,233 @@
+use smithay::backend::renderer::element::surface::WaylandSurfaceRenderElement;
+use smithay::backend::renderer::element::{Element, Id, Kind, RenderElement, UnderlyingStorage};
+use smithay::backend::renderer::gles::{GlesError, GlesFrame, GlesRenderer, GlesTexProgram};
+use smithay::backend::renderer::utils::{CommitCounter, DamageSet, OpaqueRegions};
+use smithay::reexports::wayland_server::protocol::wl_surface::WlSurface;
+use smithay::utils::{Buffer, Physical, Rectangle, Scale, Transform};
+use smithay::wayland::compositor::{with_surface_tree_downward, TraversalAction};
+
+use super::color::{ColorDescription, ColorTransform};
+use super::renderer::{AsGlesFrame as _, NiriRenderer};
+use super::shaders::{color_uniforms, Shaders};
+use crate::backend::tty::{TtyFrame, TtyRenderer, TtyRendererError};
+use crate::protocols::color_management;
+
+/// Element that converts its contents from one color encoding to another.
+///
+/// The inner element must draw with the default texture program.
+#[derive(Debug)]
+pub struct ColorTransformRenderElement<E> {
+    inner: E,
+    program: GlesTexProgram,
+    /// Encoding of the inner element contents.
+    src: ColorDescription,
+    /// Conversion to apply, or `None` if the encodings match.
+    transform: Option<ColorTransform>,
+}
+
+impl<E> ColorTransformRenderElement<E> {
+    pub fn new(
+        inner: E,
+        program: GlesTexProgram,
+        src: ColorDescription,
+        dst: ColorDescription,
+    ) -> Self {
+        Self {
+            inner,
+            program,
+            src,
+            transform: ColorTransform::new(&src, &dst),
+        }
+    }
+
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+
+    /// Encoding of the inner element contents.
+    pub fn src(&self) -> ColorDescription {
+        self.src
+    }
+}
+
+impl<E: Element> Element for ColorTransformRenderElement<E> {
+    fn id(&self) -> &Id {
+        self.inner.id()
+    }
+
+    fn current_commit(&self) -> CommitCounter {
+        self.inner.current_commit()
+    }
+
+    fn geometry(&self, scale: Scale<f64>) -> Rectangle<i32, Physical> {
+        self.inner.geometry(scale)
+    }
+
+    fn src(&self) -> Rectangle<f64, Buffer> {
+        self.inner.src()
+    }
+
+    fn transform(&self) -> Transform {
+        self.inner.transform()
+    }
+
+    fn damage_since(
+        &self,
+        scale: Scale<f64>,
+        commit: Option<CommitCounter>,
+    ) -> DamageSet<i32, Physical> {
+        self.inner.damage_since(scale, commit)
+    }
+
+    fn opaque_regions(&self, scale: Scale<f64>) -> OpaqueRegions<i32, Physical> {
+        self.inner.opaque_regions(scale)
+    }
+
+    fn alpha(&self) -> f32 {
+        self.inner.alpha()
+    }
+
+    fn kind(&self) -> Kind {
+        self.inner.kind()
+    }
+}
+
+impl<E: RenderElement<GlesRenderer>> RenderElement<GlesRenderer>
+    for ColorTransformRenderElement<E>
+{
+    fn draw(
+        &self,
+        frame: &mut GlesFrame<'_, '_>,
+        src: Rectangle<f64, Buffer>,
+        dst: Rectangle<i32, Physical>,
+        damage: &[Rectangle<i32, Physical>],
+        opaque_regions: &[Rectangle<i32, Physical>],
+    ) -> Result<(), GlesError> {
+        let Some(transform) = &self.transform else {
+            return RenderElement::<GlesRenderer>::draw(
+                &self.inner,
+                frame,
+                src,
+                dst,
+                damage,
+                opaque_regions,
+            );
+        };
+
+        let uniforms = color_uniforms(Some(transform)).to_vec();
+        frame.override_default_tex_program(self.program.clone(), uniforms);
+        let res = RenderElement::<GlesRenderer>::draw(
+            &self.inner,
+            frame,
+            src,
+            dst,
+            damage,
+            opaque_regions,
+        );
+        frame.clear_tex_program_override();
+        res
+    }
+
+    fn underlying_storage(&self, renderer: &mut GlesRenderer) -> Option<UnderlyingStorage> {
+        // Scanning out the contents directly would skip the conversion.
+        if self.transform.is_some() {
+            return None;
+        }
+
+        self.inner.underlying_storage(renderer)
+    }
+}
+
+impl<'render, E: RenderElement<TtyRenderer<'render>>> RenderElement<TtyRenderer<'render>>
+    for ColorTransformRenderElement<E>
+{
+    fn draw(
+        &self,
+        frame: &mut TtyFrame<'render, '_, '_>,
+        src: Rectangle<f64, Buffer>,
+        dst: Rectangle<i32, Physical>,
+        damage: &[Rectangle<i32, Physical>],
+        opaque_regions: &[Rectangle<i32, Physical>],
+    ) -> Result<(), TtyRendererError<'render>> {
+        let Some(transform) = &self.transform else {
+            return self.inner.draw(frame, src, dst, damage, opaque_regions);
+        };
+
+        let uniforms = color_uniforms(Some(transform)).to_vec();
+        frame
+            .as_gles_frame()
+            .override_default_tex_program(self.program.clone(), uniforms);
+        let res = self.inner.draw(frame, src, dst, damage, opaque_regions);
+        frame.as_gles_frame().clear_tex_program_override();
+        res
+    }
+
+    fn underlying_storage(&self, renderer: &mut TtyRenderer<'render>) -> Option<UnderlyingStorage> {
+        // Scanning out the contents directly would skip the conversion.
+        if self.transform.is_some() {
+            return None;
+        }
+
+        self.inner.underlying_storage(renderer)
+    }
+}
+
+/// Returns the color descriptions of the surfaces in the tree that have one set.
+fn surface_tree_descriptions(surface: &WlSurface) -> Vec<(Id, ColorDescription)> {
+    let mut rv = Vec::new();
+
+    with_surface_tree_downward(
+        surface,
+        (),
+        |_, _, _| TraversalAction::DoChildren(()),
+        |surface, states, _| {
+            if let Some(desc) = color_management::surface_description(states) {
+                rv.push((Id::from_wayland_resource(surface), desc));
+            }
+        },
+        |_, _, _| true,
+    );
+
+    rv
+}
+
+/// Wraps elements of surfaces that have a color description to convert their colors.
+///
+/// The elements must come from the surface tree of `surface`.
+pub fn color_transform_surface_elements<R, C>(
+    renderer: &mut R,
+    surface: &WlSurface,
+    elements: Vec<WaylandSurfaceRenderElement<R>>,
+) -> impl Iterator<Item = C>
+where
+    R: NiriRenderer,
+    C: From<WaylandSurfaceRenderElement<R>>
+        + From<ColorTransformRenderElement<WaylandSurfaceRenderElement<R>>>,
+{
+    let program = Shaders::get(renderer).color_transform.clone();
+    let descriptions = if program.is_some() {
+        surface_tree_descriptions(surface)
+    } else {
+        Vec::new()
+    };
+
+    elements.into_iter().map(move |elem| {
+        let desc = descriptions
+            .iter()
+            .find(|(id, _)| id == elem.id())
+            .map(|(_, desc)| *desc);
+
+        match (desc, &program) {
+            (Some(desc), Some(program)) => {
+                // We always blend in sRGB.
+                let dst = ColorDescription::SRGB;
+                ColorTransformRenderElement::new(elem, program.clone(), desc, dst).into()
+            }
+            _ => elem.into(),
+        }
+    })
+}
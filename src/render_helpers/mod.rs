@@ -20,6 +20,8 @@ use self::texture::{TextureBuffer, TextureRenderElement};
 
 pub mod border;
 pub mod clipped_surface;
+pub mod color;
+pub mod color_transform;
 pub mod damage;
 pub mod debug;
 pub mod memory;
@@ -26,6 +26,9 @@ use crate::backend::tty::{TtyFrame, TtyRenderer, TtyRendererError};
 pub struct OffscreenBuffer {
     id: Id,
 
+    /// Format of the texture buffer.
+    format: Fourcc,
+
     /// The cached texture buffer.
     ///
     /// Lazily created when `render` is called. Recreated when necessary.
@@ -68,6 +71,14 @@ pub struct OffscreenData {
 }
 
 impl OffscreenBuffer {
+    /// Creates a buffer that renders into a texture of the given format.
+    pub fn with_format(format: Fourcc) -> Self {
+        Self {
+            format,
+            ..Self::default()
+        }
+    }
+
     pub fn render(
         &self,
         renderer: &mut GlesRenderer,
@@ -126,7 +137,7 @@ impl OffscreenBuffer {
             span.emit_text(reason);
 
             let texture: GlesTexture = renderer
-                .create_buffer(Fourcc::Abgr8888, src_size)
+                .create_buffer(self.format, src_size)
                 .context("error creating texture")?;
 
             let buffer_size = src_size.to_logical(1, Transform::Normal).to_physical(1);
@@ -201,8 +212,9 @@ impl OffscreenBuffer {
 impl Default for OffscreenBuffer {
     fn default() -> Self {
         OffscreenBuffer {
-            inner: RefCell::new(None),
             id: Id::new(),
+            format: Fourcc::Abgr8888,
+            inner: RefCell::new(None),
         }
     }
 }
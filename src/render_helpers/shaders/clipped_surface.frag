@@ -26,6 +26,8 @@ uniform vec2 geo_size;
 uniform vec4 corner_radius;
 uniform mat3 input_to_geo;
 
+//_COLOR_FUNCTIONS_
+
 float rounding_alpha(vec2 coords, vec2 size) {
     vec2 center;
     float radius;
@@ -60,6 +62,8 @@ void main() {
     color = vec4(color.rgb, 1.0);
 #endif
 
+    color = niri_convert_color(color);
+
     if (coords_geo.x < 0.0 || 1.0 < coords_geo.x || coords_geo.y < 0.0 || 1.0 < coords_geo.y) {
         // Clip outside geometry.
         color = vec4(0.0);
@@ -0,0 +1,88 @@
+// Conversion between color encodings, see render_helpers/color.rs.
+//
+// Transfer functions: 0 = sRGB, 1 = gamma 2.2, 2 = PQ, 3 = HLG, 4 = linear.
+
+uniform float niri_color_convert;
+uniform float niri_color_src_tf;
+uniform float niri_color_dst_tf;
+uniform mat3 niri_color_matrix;
+
+float niri_srgb_eotf(float v) {
+    float a = abs(v);
+    float r = a <= 0.04045 ? a / 12.92 : pow((a + 0.055) / 1.055, 2.4);
+    return sign(v) * r;
+}
+
+float niri_srgb_inv_eotf(float v) {
+    float a = abs(v);
+    float r = a <= 0.0031308 ? a * 12.92 : 1.055 * pow(a, 1.0 / 2.4) - 0.055;
+    return sign(v) * r;
+}
+
+float niri_pq_eotf(float v) {
+    float p = pow(max(v, 0.0), 1.0 / 78.84375);
+    return pow(max(p - 0.8359375, 0.0) / (18.8515625 - 18.6875 * p), 1.0 / 0.1593017578125);
+}
+
+float niri_pq_inv_eotf(float v) {
+    float p = pow(max(v, 0.0), 0.1593017578125);
+    return pow((0.8359375 + 18.8515625 * p) / (1.0 + 18.6875 * p), 78.84375);
+}
+
+float niri_hlg_eotf(float v) {
+    v = max(v, 0.0);
+    if (v <= 0.5)
+        return v * v / 3.0;
+    return (exp((v - 0.55991073) / 0.17883277) + 0.28466892) / 12.0;
+}
+
+float niri_hlg_inv_eotf(float v) {
+    v = max(v, 0.0);
+    if (v <= 1.0 / 12.0)
+        return sqrt(3.0 * v);
+    return 0.17883277 * log(12.0 * v - 0.28466892) + 0.55991073;
+}
+
+float niri_eotf(float tf, float v) {
+    if (tf == 0.0)
+        return niri_srgb_eotf(v);
+    if (tf == 1.0)
+        return sign(v) * pow(abs(v), 2.2);
+    if (tf == 2.0)
+        return niri_pq_eotf(v);
+    if (tf == 3.0)
+        return niri_hlg_eotf(v);
+    return v;
+}
+
+float niri_inv_eotf(float tf, float v) {
+    if (tf == 0.0)
+        return niri_srgb_inv_eotf(v);
+    if (tf == 1.0)
+        return sign(v) * pow(abs(v), 1.0 / 2.2);
+    if (tf == 2.0)
+        return niri_pq_inv_eotf(v);
+    if (tf == 3.0)
+        return niri_hlg_inv_eotf(v);
+    return v;
+}
+
+// Converts a premultiplied color.
+vec4 niri_convert_color(vec4 color) {
+    if (niri_color_convert == 0.0 || color.a <= 0.0)
+        return color;
+
+    vec3 rgb = color.rgb / color.a;
+    rgb = vec3(
+        niri_eotf(niri_color_src_tf, rgb.r),
+        niri_eotf(niri_color_src_tf, rgb.g),
+        niri_eotf(niri_color_src_tf, rgb.b)
+    );
+    rgb = niri_color_matrix * rgb;
+    rgb = vec3(
+        niri_inv_eotf(niri_color_dst_tf, rgb.r),
+        niri_inv_eotf(niri_color_dst_tf, rgb.g),
+        niri_inv_eotf(niri_color_dst_tf, rgb.b)
+    );
+    return vec4(rgb * color.a, color.a);
+}
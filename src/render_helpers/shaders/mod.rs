@@ -6,6 +6,7 @@ use smithay::backend::renderer::gles::{
     UniformValue,
 };
 
+use super::color::ColorTransform;
 use super::renderer::NiriRenderer;
 use super::shader_element::ShaderProgram;
 
@@ -13,6 +14,7 @@ pub struct Shaders {
     pub border: Option<ShaderProgram>,
     pub shadow: Option<ShaderProgram>,
     pub clipped_surface: Option<GlesTexProgram>,
+    pub color_transform: Option<GlesTexProgram>,
     pub resize: Option<ShaderProgram>,
     pub custom_resize: RefCell<Option<ShaderProgram>>,
     pub custom_close: RefCell<Option<ShaderProgram>>,
@@ -77,12 +79,16 @@ impl Shaders {
 
         let clipped_surface = renderer
             .compile_custom_texture_shader(
-                include_str!("clipped_surface.frag"),
+                &with_color_functions(include_str!("clipped_surface.frag")),
                 &[
                     UniformName::new("niri_scale", UniformType::_1f),
                     UniformName::new("geo_size", UniformType::_2f),
                     UniformName::new("corner_radius", UniformType::_4f),
                     UniformName::new("input_to_geo", UniformType::Matrix3x3),
+                    UniformName::new("niri_color_convert", UniformType::_1f),
+                    UniformName::new("niri_color_src_tf", UniformType::_1f),
+                    UniformName::new("niri_color_dst_tf", UniformType::_1f),
+                    UniformName::new("niri_color_matrix", UniformType::Matrix3x3),
                 ],
             )
             .map_err(|err| {
@@ -90,6 +96,21 @@ impl Shaders {
             })
             .ok();
 
+        let color_transform = renderer
+            .compile_custom_texture_shader(
+                &with_color_functions(include_str!("color_transform.frag")),
+                &[
+                    UniformName::new("niri_color_convert", UniformType::_1f),
+                    UniformName::new("niri_color_src_tf", UniformType::_1f),
+                    UniformName::new("niri_color_dst_tf", UniformType::_1f),
+                    UniformName::new("niri_color_matrix", UniformType::Matrix3x3),
+                ],
+            )
+            .map_err(|err| {
+                warn!("error compiling color transform shader: {err:?}");
+            })
+            .ok();
+
         let resize = compile_resize_program(renderer, include_str!("resize.frag"))
             .map_err(|err| {
                 warn!("error compiling resize shader: {err:?}");
@@ -100,6 +121,7 @@ impl Shaders {
             border,
             shadow,
             clipped_surface,
+            color_transform,
             resize,
             custom_resize: RefCell::new(None),
             custom_close: RefCell::new(None),
@@ -164,6 +186,11 @@ pub fn init(renderer: &mut GlesRenderer) {
     }
 }
 
+/// Inserts the color conversion functions into a shader.
+fn with_color_functions(src: &str) -> String {
+    src.replace("//_COLOR_FUNCTIONS_\n", include_str!("color.frag"))
+}
+
 fn compile_resize_program(
     renderer: &mut GlesRenderer,
     src: &str,
@@ -306,3 +333,26 @@ pub fn mat3_uniform(name: &str, mat: Mat3) -> Uniform {
         },
     )
 }
+
+/// Returns the uniforms for the color conversion functions.
+///
+/// These must be passed on every draw with a program that uses the color functions, since
+/// uniform values persist in the program.
+pub fn color_uniforms(transform: Option<&ColorTransform>) -> [Uniform<'static>; 4] {
+    let (convert, src_tf, dst_tf, matrix) = match transform {
+        Some(t) => (
+            1.,
+            t.src_tf.shader_value(),
+            t.dst_tf.shader_value(),
+            t.shader_matrix(),
+        ),
+        None => (0., 0., 0., Mat3::IDENTITY),
+    };
+
+    [
+        Uniform::new("niri_color_convert", convert),
+        Uniform::new("niri_color_src_tf", src_tf),
+        Uniform::new("niri_color_dst_tf", dst_tf),
+        mat3_uniform("niri_color_matrix", matrix),
+    ]
+}
@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use client::ClientId;
+use niri_config::{BellSpawn, Config};
+use wayland_client::protocol::wl_surface::WlSurface;
+
+use super::*;
+use crate::utils::with_toplevel_role;
+
+fn set_up(config: Config) -> (Fixture, ClientId) {
+    let mut f = Fixture::with_config(config);
+    f.add_output(1, (1920, 1080));
+
+    let id = f.add_client();
+    // Bind the globals.
+    f.roundtrip(id);
+
+    (f, id)
+}
+
+fn map_window(f: &mut Fixture, id: ClientId, title: &str) -> WlSurface {
+    let window = f.client(id).create_window();
+    let surface = window.surface.clone();
+    window.set_title(title);
+    window.commit();
+    f.roundtrip(id);
+
+    let window = f.client(id).window(&surface);
+    window.attach_new_buffer();
+    window.set_size(100, 100);
+    window.ack_last_and_commit();
+    f.double_roundtrip(id);
+
+    surface
+}
+
+fn is_urgent(f: &mut Fixture, title: &str) -> bool {
+    let niri = f.niri();
+    let (_, mapped) = niri
+        .layout
+        .windows()
+        .find(|(_, mapped)| {
+            with_toplevel_role(mapped.toplevel(), |role| {
+                role.title.as_deref() == Some(title)
+            })
+        })
+        .unwrap();
+    mapped.is_urgent()
+}
+
+#[test]
+fn bell_marks_window_urgent() {
+    let (mut f, id) = set_up(Config::default());
+
+    let a = map_window(&mut f, id, "a");
+    // Opening the second window focuses it.
+    let _b = map_window(&mut f, id, "b");
+
+    assert!(!is_urgent(&mut f, "a"));
+    assert!(!is_urgent(&mut f, "b"));
+
+    let bell = f.client(id).state.system_bell.clone().unwrap();
+    bell.ring(Some(&a));
+    f.double_roundtrip(id);
+
+    assert!(is_urgent(&mut f, "a"));
+    assert!(!is_urgent(&mut f, "b"));
+}
+
+#[test]
+fn bell_skip_urgent() {
+    let mut config = Config::default();
+    config.bell.skip_urgent = true;
+    let (mut f, id) = set_up(config);
+
+    let a = map_window(&mut f, id, "a");
+    let _b = map_window(&mut f, id, "b");
+
+    let bell = f.client(id).state.system_bell.clone().unwrap();
+    bell.ring(Some(&a));
+    f.double_roundtrip(id);
+
+    assert!(!is_urgent(&mut f, "a"));
+}
+
+#[test]
+fn bell_spawn_is_rate_limited() {
+    let mut config = Config::default();
+    config.bell.spawn = Some(BellSpawn {
+        command: vec![String::from("true")],
+    });
+    let (mut f, id) = set_up(config);
+
+    let bell = f.client(id).state.system_bell.clone().unwrap();
+
+    // Two rings in quick succession spawn the command only once.
+    bell.ring(None);
+    bell.ring(None);
+    f.double_roundtrip(id);
+    assert_eq!(f.niri().bell_spawn_count, 1);
+
+    // Once the interval passes, the bell spawns the command again.
+    let niri = f.niri();
+    niri.bell_spawn_time = niri
+        .bell_spawn_time
+        .map(|time| time.saturating_sub(Duration::from_secs(1)));
+    bell.ring(None);
+    f.double_roundtrip(id);
+    assert_eq!(f.niri().bell_spawn_count, 2);
+}
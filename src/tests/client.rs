@@ -10,12 +10,18 @@ use std::time::Duration;
 use calloop::EventLoop;
 use calloop_wayland_source::WaylandSource;
 use single_pixel_buffer::v1::client::wp_single_pixel_buffer_manager_v1::WpSinglePixelBufferManagerV1;
+use smithay::reexports::wayland_protocols::wp::color_management::v1::client::{
+    wp_color_management_output_v1, wp_color_management_surface_feedback_v1,
+    wp_color_management_surface_v1, wp_color_manager_v1, wp_image_description_creator_icc_v1,
+    wp_image_description_creator_params_v1, wp_image_description_info_v1, wp_image_description_v1,
+};
 use smithay::reexports::wayland_protocols::wp::single_pixel_buffer;
 use smithay::reexports::wayland_protocols::wp::viewporter::client::wp_viewport::WpViewport;
 use smithay::reexports::wayland_protocols::wp::viewporter::client::wp_viewporter::WpViewporter;
 use smithay::reexports::wayland_protocols::xdg::shell::client::xdg_surface::{self, XdgSurface};
 use smithay::reexports::wayland_protocols::xdg::shell::client::xdg_toplevel::{self, XdgToplevel};
 use smithay::reexports::wayland_protocols::xdg::shell::client::xdg_wm_base::{self, XdgWmBase};
+use smithay::reexports::wayland_protocols::xdg::system_bell::v1::client::xdg_system_bell_v1::XdgSystemBellV1;
 use smithay::reexports::wayland_protocols_wlr::layer_shell::v1::client::zwlr_layer_shell_v1::{
     self, ZwlrLayerShellV1,
 };
@@ -23,6 +29,7 @@ use smithay::reexports::wayland_protocols_wlr::layer_shell::v1::client::zwlr_lay
     self, ZwlrLayerSurfaceV1,
 };
 use wayland_backend::client::Backend;
+use wayland_backend::protocol::ProtocolError;
 use wayland_client::globals::Global;
 use wayland_client::protocol::wl_buffer::{self, WlBuffer};
 use wayland_client::protocol::wl_callback::{self, WlCallback};
@@ -32,6 +39,14 @@ use wayland_client::protocol::wl_output::{self, WlOutput};
 use wayland_client::protocol::wl_registry::{self, WlRegistry};
 use wayland_client::protocol::wl_surface::{self, WlSurface};
 use wayland_client::{Connection, Dispatch, Proxy as _, QueueHandle};
+use wp_color_management_output_v1::WpColorManagementOutputV1;
+use wp_color_management_surface_feedback_v1::WpColorManagementSurfaceFeedbackV1;
+use wp_color_management_surface_v1::WpColorManagementSurfaceV1;
+use wp_color_manager_v1::WpColorManagerV1;
+use wp_image_description_creator_icc_v1::WpImageDescriptionCreatorIccV1;
+use wp_image_description_creator_params_v1::WpImageDescriptionCreatorParamsV1;
+use wp_image_description_info_v1::WpImageDescriptionInfoV1;
+use wp_image_description_v1::WpImageDescriptionV1;
 
 use crate::utils::id::IdCounter;
 
@@ -55,6 +70,13 @@ pub struct State {
     pub layer_shell: Option<ZwlrLayerShellV1>,
     pub spbm: Option<WpSinglePixelBufferManagerV1>,
     pub viewporter: Option<WpViewporter>,
+    pub color_manager: Option<WpColorManagerV1>,
+    pub system_bell: Option<XdgSystemBellV1>,
+
+    pub color_features: Vec<wp_color_manager_v1::Feature>,
+    pub image_descriptions: HashMap<WpImageDescriptionV1, ImageDescriptionStatus>,
+    pub image_description_infos: HashMap<WpImageDescriptionInfoV1, ImageDescriptionInfo>,
+    pub preferred_changes: HashMap<WpColorManagementSurfaceFeedbackV1, u32>,
 
     pub windows: Vec<Window>,
     pub layers: Vec<LayerSurface>,
@@ -119,6 +141,20 @@ pub struct LayerConfigureProps {
     pub exclusive_edge: Option<zwlr_layer_surface_v1::Anchor>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageDescriptionStatus {
+    Ready(u32),
+    Failed(wp_image_description_v1::Cause),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImageDescriptionInfo {
+    pub primaries_named: Option<wp_color_manager_v1::Primaries>,
+    pub tf_named: Option<wp_color_manager_v1::TransferFunction>,
+    pub luminances: Option<(u32, u32, u32)>,
+    pub done: bool,
+}
+
 #[derive(Default)]
 pub struct SyncData {
     pub done: AtomicBool,
@@ -181,6 +217,12 @@ impl Client {
             layer_shell: None,
             spbm: None,
             viewporter: None,
+            color_manager: None,
+            system_bell: None,
+            color_features: Vec::new(),
+            image_descriptions: HashMap::new(),
+            image_description_infos: HashMap::new(),
+            preferred_changes: HashMap::new(),
             windows: Vec::new(),
             layers: Vec::new(),
         };
@@ -196,9 +238,14 @@ impl Client {
     }
 
     pub fn dispatch(&mut self) {
-        self.event_loop
-            .dispatch(Duration::ZERO, &mut self.state)
-            .unwrap();
+        if let Err(err) = self.event_loop.dispatch(Duration::ZERO, &mut self.state) {
+            // Tests may provoke protocol errors on purpose; anything else is a bug.
+            assert!(self.protocol_error().is_some(), "{err:?}");
+        }
+    }
+
+    pub fn protocol_error(&self) -> Option<ProtocolError> {
+        self.connection.protocol_error()
     }
 
     pub fn send_sync(&self) -> Arc<SyncData> {
@@ -357,6 +404,10 @@ impl Window {
         self.xdg_toplevel.set_title(title.to_owned());
     }
 
+    pub fn set_app_id(&self, app_id: &str) {
+        self.xdg_toplevel.set_app_id(app_id.to_owned());
+    }
+
     pub fn recent_configures(&mut self) -> impl Iterator<Item = &Configure> {
         let start = self.configures_looked_at;
         self.configures_looked_at = self.configures_received.len();
@@ -498,6 +549,12 @@ impl Dispatch<WlRegistry, ()> for State {
                 } else if interface == WpViewporter::interface().name {
                     let version = min(version, WpViewporter::interface().version);
                     state.viewporter = Some(registry.bind(name, version, qh, ()));
+                } else if interface == WpColorManagerV1::interface().name {
+                    let version = min(version, WpColorManagerV1::interface().version);
+                    state.color_manager = Some(registry.bind(name, version, qh, ()));
+                } else if interface == XdgSystemBellV1::interface().name {
+                    let version = min(version, XdgSystemBellV1::interface().version);
+                    state.system_bell = Some(registry.bind(name, version, qh, ()));
                 } else if interface == WlOutput::interface().name {
                     let version = min(version, WlOutput::interface().version);
                     let output = registry.bind(name, version, qh, ());
@@ -755,3 +812,172 @@ impl Dispatch<WpViewport, ()> for State {
         unreachable!()
     }
 }
+
+impl Dispatch<XdgSystemBellV1, ()> for State {
+    fn event(
+        _state: &mut Self,
+        _proxy: &XdgSystemBellV1,
+        _event: <XdgSystemBellV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        unreachable!()
+    }
+}
+
+impl Dispatch<WpColorManagerV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _proxy: &WpColorManagerV1,
+        event: <WpColorManagerV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            wp_color_manager_v1::Event::SupportedIntent { .. } => (),
+            wp_color_manager_v1::Event::SupportedFeature { feature } => {
+                if let Ok(feature) = feature.into_result() {
+                    state.color_features.push(feature);
+                }
+            }
+            wp_color_manager_v1::Event::SupportedTfNamed { .. } => (),
+            wp_color_manager_v1::Event::SupportedPrimariesNamed { .. } => (),
+            wp_color_manager_v1::Event::Done => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Dispatch<WpColorManagementOutputV1, ()> for State {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WpColorManagementOutputV1,
+        event: <WpColorManagementOutputV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            wp_color_management_output_v1::Event::ImageDescriptionChanged => (),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Dispatch<WpColorManagementSurfaceV1, ()> for State {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WpColorManagementSurfaceV1,
+        _event: <WpColorManagementSurfaceV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        unreachable!()
+    }
+}
+
+impl Dispatch<WpColorManagementSurfaceFeedbackV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        feedback: &WpColorManagementSurfaceFeedbackV1,
+        event: <WpColorManagementSurfaceFeedbackV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            wp_color_management_surface_feedback_v1::Event::PreferredChanged { identity } => {
+                state.preferred_changes.insert(feedback.clone(), identity);
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Dispatch<WpImageDescriptionCreatorIccV1, ()> for State {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WpImageDescriptionCreatorIccV1,
+        _event: <WpImageDescriptionCreatorIccV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        unreachable!()
+    }
+}
+
+impl Dispatch<WpImageDescriptionCreatorParamsV1, ()> for State {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WpImageDescriptionCreatorParamsV1,
+        _event: <WpImageDescriptionCreatorParamsV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        unreachable!()
+    }
+}
+
+impl Dispatch<WpImageDescriptionV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        description: &WpImageDescriptionV1,
+        event: <WpImageDescriptionV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        let status = match event {
+            wp_image_description_v1::Event::Ready { identity } => {
+                ImageDescriptionStatus::Ready(identity)
+            }
+            wp_image_description_v1::Event::Failed { cause, .. } => {
+                ImageDescriptionStatus::Failed(cause.into_result().unwrap())
+            }
+            _ => unreachable!(),
+        };
+        state.image_descriptions.insert(description.clone(), status);
+    }
+}
+
+impl Dispatch<WpImageDescriptionInfoV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        proxy: &WpImageDescriptionInfoV1,
+        event: <WpImageDescriptionInfoV1 as wayland_client::Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        let info = state
+            .image_description_infos
+            .entry(proxy.clone())
+            .or_default();
+
+        match event {
+            wp_image_description_info_v1::Event::PrimariesNamed { primaries } => {
+                info.primaries_named = primaries.into_result().ok();
+            }
+            wp_image_description_info_v1::Event::TfNamed { tf } => {
+                info.tf_named = tf.into_result().ok();
+            }
+            wp_image_description_info_v1::Event::Luminances {
+                min_lum,
+                max_lum,
+                reference_lum,
+            } => {
+                info.luminances = Some((min_lum, max_lum, reference_lum));
+            }
+            wp_image_description_info_v1::Event::Done => info.done = true,
+            wp_image_description_info_v1::Event::Primaries { .. } => (),
+            wp_image_description_info_v1::Event::TargetPrimaries { .. } => (),
+            wp_image_description_info_v1::Event::TargetLuminance { .. } => (),
+            _ => unreachable!(),
+        }
+    }
+}
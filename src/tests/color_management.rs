@@ -0,0 +1,372 @@
+use client::{ClientId, ImageDescriptionStatus};
+use niri_config::Config;
+use smithay::reexports::wayland_protocols::wp::color_management::v1::client::{
+    wp_color_manager_v1, wp_image_description_creator_params_v1, wp_image_description_v1,
+};
+use smithay::wayland::compositor::with_states;
+use wp_color_manager_v1::{
+    Error as ManagerError, Feature, Primaries as PrimariesNamed, RenderIntent,
+    TransferFunction as TfNamed, WpColorManagerV1,
+};
+use wp_image_description_creator_params_v1::Error as ParamsError;
+use wp_image_description_v1::WpImageDescriptionV1;
+
+use super::*;
+use crate::protocols::color_management::surface_description;
+use crate::render_helpers::color::{ColorDescription, Primaries, TransferFunction};
+
+#[test]
+fn output_color_description_from_config() {
+    let config = r##"
+output "headless-1" {
+    hdr {
+        transfer-function "pq"
+        max-luminance 600
+    }
+}
+"##;
+    let config = Config::parse("test.kdl", config).unwrap();
+    let mut f = Fixture::with_config(config);
+    f.add_output(1, (1920, 1080));
+    f.add_output(2, (1280, 720));
+
+    let hdr_output = f.niri_output(1);
+    let sdr_output = f.niri_output(2);
+    let niri = f.niri();
+
+    let hdr = niri.output_state[&hdr_output].color_description;
+    assert_eq!(hdr.tf, TransferFunction::Pq);
+    assert_eq!(hdr.primaries, Primaries::BT2020);
+    assert_eq!(hdr.luminances.reference, 203);
+    assert_eq!(hdr.luminances.max, 600);
+
+    let sdr = niri.output_state[&sdr_output].color_description;
+    assert_eq!(sdr, ColorDescription::SRGB);
+}
+
+const HDR_CONFIG: &str = r##"
+output "headless-1" {
+    hdr {
+        transfer-function "pq"
+        max-luminance 600
+    }
+}
+"##;
+
+fn set_up() -> (Fixture, ClientId) {
+    let config = Config::parse("test.kdl", HDR_CONFIG).unwrap();
+    let mut f = Fixture::with_config(config);
+    f.add_output(1, (1920, 1080));
+    f.add_output(2, (1280, 720));
+
+    let id = f.add_client();
+    // Receive the events sent upon binding the globals.
+    f.roundtrip(id);
+
+    (f, id)
+}
+
+fn manager(f: &mut Fixture, id: ClientId) -> WpColorManagerV1 {
+    f.client(id).state.color_manager.clone().unwrap()
+}
+
+fn status(f: &mut Fixture, id: ClientId, desc: &WpImageDescriptionV1) -> ImageDescriptionStatus {
+    f.client(id).state.image_descriptions[desc]
+}
+
+fn assert_protocol_error(f: &mut Fixture, id: ClientId, interface: &str, code: u32) {
+    let err = f
+        .client(id)
+        .protocol_error()
+        .expect("expected a protocol error");
+    assert_eq!(err.object_interface, interface, "{err:?}");
+    assert_eq!(err.code, code, "{err:?}");
+}
+
+fn output_description(f: &mut Fixture, id: ClientId, name: &str) -> WpImageDescriptionV1 {
+    let qh = f.client(id).qh.clone();
+    let output = f.client(id).output(name);
+    let cm_output = manager(f, id).get_output(&output, &qh, ());
+    let desc = cm_output.get_image_description(&qh, ());
+    f.roundtrip(id);
+    desc
+}
+
+#[test]
+fn manager_advertises_supported_features() {
+    let (mut f, id) = set_up();
+
+    let features = &f.client(id).state.color_features;
+    assert!(features.contains(&Feature::Parametric));
+    assert!(features.contains(&Feature::SetPrimaries));
+    assert!(features.contains(&Feature::SetLuminances));
+    assert!(!features.contains(&Feature::IccV2V4));
+    assert!(!features.contains(&Feature::SetTfPower));
+}
+
+#[test]
+fn parametric_description_becomes_ready() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let creator = manager(&mut f, id).create_parametric_creator(&qh, ());
+    creator.set_tf_named(TfNamed::Gamma22);
+    creator.set_primaries_named(PrimariesNamed::Srgb);
+    let desc = creator.create(&qh, ());
+    f.roundtrip(id);
+
+    assert!(f.client(id).protocol_error().is_none());
+    assert!(matches!(
+        status(&mut f, id, &desc),
+        ImageDescriptionStatus::Ready(identity) if identity != 0
+    ));
+}
+
+#[test]
+fn parametric_description_with_degenerate_primaries_fails() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let creator = manager(&mut f, id).create_parametric_creator(&qh, ());
+    creator.set_tf_named(TfNamed::Srgb);
+    // All primaries at the same point don't span a color volume.
+    let p = 300_000;
+    creator.set_primaries(p, p, p, p, p, p, 312_700, 329_000);
+    let desc = creator.create(&qh, ());
+    f.roundtrip(id);
+
+    assert!(f.client(id).protocol_error().is_none());
+    assert_eq!(
+        status(&mut f, id, &desc),
+        ImageDescriptionStatus::Failed(wp_image_description_v1::Cause::Unsupported)
+    );
+}
+
+#[test]
+fn incomplete_parametric_description_is_error() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let creator = manager(&mut f, id).create_parametric_creator(&qh, ());
+    creator.set_tf_named(TfNamed::Srgb);
+    let _desc = creator.create(&qh, ());
+    f.roundtrip(id);
+
+    assert_protocol_error(
+        &mut f,
+        id,
+        "wp_image_description_creator_params_v1",
+        ParamsError::IncompleteSet as u32,
+    );
+}
+
+#[test]
+fn setting_parameter_twice_is_error() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let creator = manager(&mut f, id).create_parametric_creator(&qh, ());
+    creator.set_tf_named(TfNamed::Srgb);
+    creator.set_tf_named(TfNamed::Gamma22);
+    f.roundtrip(id);
+
+    assert_protocol_error(
+        &mut f,
+        id,
+        "wp_image_description_creator_params_v1",
+        ParamsError::AlreadySet as u32,
+    );
+}
+
+#[test]
+fn power_transfer_function_is_unsupported() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let creator = manager(&mut f, id).create_parametric_creator(&qh, ());
+    creator.set_tf_power(22000);
+    f.roundtrip(id);
+
+    assert_protocol_error(
+        &mut f,
+        id,
+        "wp_image_description_creator_params_v1",
+        ParamsError::UnsupportedFeature as u32,
+    );
+}
+
+#[test]
+fn icc_creator_is_unsupported() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let _creator = manager(&mut f, id).create_icc_creator(&qh, ());
+    f.roundtrip(id);
+
+    assert_protocol_error(
+        &mut f,
+        id,
+        "wp_color_manager_v1",
+        ManagerError::UnsupportedFeature as u32,
+    );
+}
+
+#[test]
+fn windows_scrgb_is_unsupported() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let _desc = manager(&mut f, id).create_windows_scrgb(&qh, ());
+    f.roundtrip(id);
+
+    assert_protocol_error(
+        &mut f,
+        id,
+        "wp_color_manager_v1",
+        ManagerError::UnsupportedFeature as u32,
+    );
+}
+
+#[test]
+fn output_description_reports_information() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let hdr = output_description(&mut f, id, "headless-1");
+    assert!(matches!(
+        status(&mut f, id, &hdr),
+        ImageDescriptionStatus::Ready(_)
+    ));
+
+    let info = hdr.get_information(&qh, ());
+    f.roundtrip(id);
+
+    let info = f.client(id).state.image_description_infos[&info].clone();
+    assert!(info.done);
+    assert_eq!(info.tf_named, Some(TfNamed::St2084Pq));
+    assert_eq!(info.primaries_named, Some(PrimariesNamed::Bt2020));
+    assert_eq!(info.luminances, Some((50, 600, 203)));
+}
+
+#[test]
+fn parametric_description_has_no_information() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let creator = manager(&mut f, id).create_parametric_creator(&qh, ());
+    creator.set_tf_named(TfNamed::Srgb);
+    creator.set_primaries_named(PrimariesNamed::Srgb);
+    let desc = creator.create(&qh, ());
+    f.roundtrip(id);
+
+    let _info = desc.get_information(&qh, ());
+    f.roundtrip(id);
+
+    assert_protocol_error(
+        &mut f,
+        id,
+        "wp_image_description_v1",
+        wp_image_description_v1::Error::NoInformation as u32,
+    );
+}
+
+#[test]
+fn surface_feedback_reuses_output_identity() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let hdr = output_description(&mut f, id, "headless-1");
+    let sdr = output_description(&mut f, id, "headless-2");
+    let ImageDescriptionStatus::Ready(hdr_identity) = status(&mut f, id, &hdr) else {
+        panic!("the HDR output description should be ready");
+    };
+    let ImageDescriptionStatus::Ready(sdr_identity) = status(&mut f, id, &sdr) else {
+        panic!("the SDR output description should be ready");
+    };
+    assert_ne!(hdr_identity, sdr_identity);
+
+    // A surface that isn't shown anywhere prefers sRGB, which is what the SDR output uses.
+    let surface = f.client(id).create_window().surface.clone();
+    let feedback = manager(&mut f, id).get_surface_feedback(&surface, &qh, ());
+    let preferred = feedback.get_preferred(&qh, ());
+    f.roundtrip(id);
+
+    assert_eq!(
+        status(&mut f, id, &preferred),
+        ImageDescriptionStatus::Ready(sdr_identity)
+    );
+
+    // Nothing changed, so there should be no preferred_changed.
+    f.niri().refresh_preferred_color_descriptions();
+    f.roundtrip(id);
+    assert!(!f.client(id).state.preferred_changes.contains_key(&feedback));
+}
+
+#[test]
+fn surface_description_applies_on_commit() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let window = f.client(id).create_window();
+    let surface = window.surface.clone();
+    window.commit();
+    f.roundtrip(id);
+
+    let window = f.client(id).window(&surface);
+    window.attach_new_buffer();
+    window.ack_last_and_commit();
+    f.double_roundtrip(id);
+
+    let creator = manager(&mut f, id).create_parametric_creator(&qh, ());
+    creator.set_tf_named(TfNamed::St2084Pq);
+    creator.set_primaries_named(PrimariesNamed::Bt2020);
+    let desc = creator.create(&qh, ());
+    f.roundtrip(id);
+
+    let cm_surface = manager(&mut f, id).get_surface(&surface, &qh, ());
+    cm_surface.set_image_description(&desc, RenderIntent::Perceptual);
+    f.roundtrip(id);
+
+    let niri_surface = |f: &mut Fixture| {
+        let (_, mapped) = f.niri().layout.windows().next().unwrap();
+        mapped.toplevel().wl_surface().clone()
+    };
+    let description = |f: &mut Fixture| {
+        let surface = niri_surface(f);
+        with_states(&surface, surface_description)
+    };
+
+    // The description is double-buffered.
+    assert_eq!(description(&mut f), None);
+
+    f.client(id).window(&surface).commit();
+    f.roundtrip(id);
+    let expected = ColorDescription::new(TransferFunction::Pq, Primaries::BT2020);
+    assert_eq!(description(&mut f), Some(expected));
+
+    cm_surface.unset_image_description();
+    f.client(id).window(&surface).commit();
+    f.roundtrip(id);
+    assert_eq!(description(&mut f), None);
+}
+
+#[test]
+fn second_color_management_surface_is_error() {
+    let (mut f, id) = set_up();
+    let qh = f.client(id).qh.clone();
+
+    let surface = f.client(id).create_window().surface.clone();
+    let _first = manager(&mut f, id).get_surface(&surface, &qh, ());
+    f.roundtrip(id);
+    assert!(f.client(id).protocol_error().is_none());
+
+    let _second = manager(&mut f, id).get_surface(&surface, &qh, ());
+    f.roundtrip(id);
+
+    assert_protocol_error(
+        &mut f,
+        id,
+        "wp_color_manager_v1",
+        ManagerError::SurfaceExists as u32,
+    );
+}
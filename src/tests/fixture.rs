@@ -122,6 +122,12 @@ impl Fixture {
         let data = client.send_sync();
         while !data.done.load(Ordering::Relaxed) {
             self.dispatch();
+
+            // The server stops processing requests after a protocol error, so the sync will never
+            // complete.
+            if self.state.client(id).protocol_error().is_some() {
+                break;
+            }
         }
     }
 
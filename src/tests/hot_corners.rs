@@ -0,0 +1,82 @@
+use niri_config::{Action, Config, FloatOrInt, HotCorner, HotCornerAction};
+use smithay::reexports::wayland_protocols_wlr::layer_shell::v1::client::zwlr_layer_shell_v1::Layer;
+use smithay::reexports::wayland_protocols_wlr::layer_shell::v1::client::zwlr_layer_surface_v1::Anchor;
+use smithay::utils::Point;
+
+use super::*;
+use crate::tests::client::LayerConfigureProps;
+
+fn set_up() -> Fixture {
+    let mut config = Config::default();
+    let hot_corners = &mut config.gestures.hot_corners;
+    hot_corners.size = FloatOrInt(16.);
+    hot_corners.top_left = Some(HotCornerAction(Action::ToggleOverview));
+    hot_corners.top_right = Some(HotCornerAction(Action::ToggleOverview));
+
+    let mut f = Fixture::with_config(config);
+    f.add_output(1, (1920, 1080));
+    f.add_output(2, (1280, 720));
+    f
+}
+
+#[test]
+fn corner_needs_pressing_into_screen_edge() {
+    let mut f = set_up();
+    let output = f.niri_output(1);
+    let second = f.niri_output(2);
+
+    let niri = f.niri();
+    let second_geo = niri.global_space.output_geometry(&second).unwrap();
+    assert_eq!(second_geo.loc, Point::from((1920, 0)));
+
+    let corner = |x: f64, y: f64| {
+        niri.hot_corner_under(&output, Point::from((x, y)))
+            .map(|(corner, _)| corner)
+    };
+
+    // Pressing against either edge within the corner square.
+    assert_eq!(corner(0., 0.), Some(HotCorner::TopLeft));
+    assert_eq!(corner(8., 0.), Some(HotCorner::TopLeft));
+    assert_eq!(corner(0., 8.), Some(HotCorner::TopLeft));
+
+    // Inside the square but away from the edges.
+    assert_eq!(corner(8., 8.), None);
+
+    // Along the edge but outside the square.
+    assert_eq!(corner(100., 0.), None);
+
+    // The right edge of the first output leads to the second output, so only the top edge counts.
+    assert_eq!(corner(1912., 0.), Some(HotCorner::TopRight));
+    assert_eq!(corner(1919.5, 8.), None);
+}
+
+#[test]
+fn corner_takes_pointer_only_at_screen_edge() {
+    let mut f = set_up();
+    f.niri_focus_output(1);
+    let id = f.add_client();
+
+    // A top layer surface covering the whole first output.
+    let layer = f.client(id).create_layer(None, Layer::Top, "");
+    let surface = layer.surface.clone();
+    layer.set_configure_props(LayerConfigureProps {
+        anchor: Some(Anchor::Left | Anchor::Right | Anchor::Top | Anchor::Bottom),
+        ..Default::default()
+    });
+    layer.commit();
+    f.roundtrip(id);
+
+    let layer = f.client(id).layer(&surface);
+    layer.attach_new_buffer();
+    layer.set_size(1920, 1080);
+    layer.ack_last_and_commit();
+    f.double_roundtrip(id);
+
+    let niri = f.niri();
+    let has_layer = |x: f64, y: f64| niri.contents_under(Point::from((x, y))).layer.is_some();
+
+    assert!(has_layer(8., 8.));
+    assert!(has_layer(100., 0.));
+    assert!(!has_layer(0., 0.));
+    assert!(!has_layer(8., 0.));
+}
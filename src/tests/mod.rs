@@ -4,8 +4,13 @@ mod client;
 mod fixture;
 mod server;
 
+mod bell;
+mod color_management;
 mod floating;
 mod fullscreen;
+mod hot_corners;
 mod layer_shell;
+mod screencast;
 mod transactions;
 mod window_opening;
+mod window_switcher;
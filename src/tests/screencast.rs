@@ -0,0 +1,93 @@
+use niri_config::Config;
+
+use super::*;
+use crate::niri::{workspace_cast_state, WorkspaceCastState};
+
+const CONFIG: &str = r##"
+workspace "cast" {
+    open-on-output "headless-1"
+}
+"##;
+
+fn set_up() -> (Fixture, u64) {
+    let config = Config::parse("test.kdl", CONFIG).unwrap();
+    let mut f = Fixture::with_config(config);
+    f.add_output(1, (1920, 1080));
+    f.add_output(2, (1280, 720));
+
+    let (_, ws) = f.niri().layout.find_workspace_by_name("cast").unwrap();
+    let id = ws.id().get();
+    (f, id)
+}
+
+#[test]
+fn workspace_cast_on_its_output() {
+    let (mut f, id) = set_up();
+    let output = f.niri_output(1);
+
+    let niri = f.niri();
+    assert_eq!(
+        workspace_cast_state(&niri.layout, id),
+        WorkspaceCastState::Output(output.clone())
+    );
+    assert_eq!(niri.workspace_cast_output(id), Some(output));
+}
+
+#[test]
+fn workspace_cast_follows_workspace_across_outputs() {
+    let (mut f, id) = set_up();
+    let output1 = f.niri_output(1);
+    let output2 = f.niri_output(2);
+
+    f.niri().remove_output(&output1);
+    assert_eq!(f.niri().workspace_cast_output(id), Some(output2));
+
+    // The workspace goes back to its original output once it reappears.
+    f.add_output(1, (1920, 1080));
+    let niri = f.niri();
+    let output1 = niri
+        .layout
+        .outputs()
+        .find(|o| o.name() == "headless-1")
+        .cloned();
+    assert!(output1.is_some());
+    assert_eq!(niri.workspace_cast_output(id), output1);
+}
+
+#[test]
+fn workspace_cast_pauses_without_outputs() {
+    let (mut f, id) = set_up();
+    let output1 = f.niri_output(1);
+    let output2 = f.niri_output(2);
+
+    f.niri().remove_output(&output1);
+    f.niri().remove_output(&output2);
+
+    let niri = f.niri();
+    assert_eq!(
+        workspace_cast_state(&niri.layout, id),
+        WorkspaceCastState::NoOutput
+    );
+    assert_eq!(niri.workspace_cast_output(id), None);
+}
+
+#[test]
+fn workspace_cast_of_removed_workspace() {
+    let (mut f, id) = set_up();
+
+    let niri = f.niri();
+    let missing = niri
+        .layout
+        .workspaces()
+        .map(|(_, _, ws)| ws.id().get())
+        .max()
+        .unwrap()
+        + 1;
+    assert_ne!(missing, id);
+
+    assert_eq!(
+        workspace_cast_state(&niri.layout, missing),
+        WorkspaceCastState::Removed
+    );
+    assert_eq!(niri.workspace_cast_output(missing), None);
+}
@@ -0,0 +1,227 @@
+use client::ClientId;
+use niri_config::{Config, Modifiers};
+use niri_ipc::{WindowSwitcherFilter, WindowSwitcherScope};
+use smithay::desktop::Window;
+
+use super::*;
+use crate::ui::window_switcher::{trigger_mods, WindowSwitcher};
+use crate::utils::with_toplevel_role;
+
+const CONFIG: &str = r##"
+workspace "one"
+workspace "two"
+
+window-rule {
+    match title="never"
+    open-focused false
+}
+
+window-rule {
+    match title="other-ws"
+    open-on-workspace "two"
+    open-focused false
+}
+
+window-rule {
+    match title="other-output"
+    open-on-output "headless-2"
+    open-focused false
+}
+"##;
+
+fn set_up() -> (Fixture, ClientId) {
+    let config = Config::parse("test.kdl", CONFIG).unwrap();
+    let mut f = Fixture::with_config(config);
+    f.add_output(1, (1920, 1080));
+    f.add_output(2, (1280, 720));
+    f.niri_focus_output(1);
+
+    let id = f.add_client();
+
+    map_window(&mut f, id, "a", "foo");
+    map_window(&mut f, id, "b", "bar");
+    map_window(&mut f, id, "c", "foo");
+    map_window(&mut f, id, "never", "foo");
+    map_window(&mut f, id, "other-ws", "foo");
+    map_window(&mut f, id, "other-output", "foo");
+
+    // Focus the windows in order A → B → C.
+    for title in ["a", "b", "c"] {
+        let window = window(&mut f, title);
+        f.niri().layout.activate_window(&window);
+        f.double_roundtrip(id);
+    }
+
+    (f, id)
+}
+
+fn map_window(f: &mut Fixture, id: ClientId, title: &str, app_id: &str) {
+    let window = f.client(id).create_window();
+    let surface = window.surface.clone();
+    window.set_title(title);
+    window.set_app_id(app_id);
+    window.commit();
+    f.roundtrip(id);
+
+    let window = f.client(id).window(&surface);
+    window.attach_new_buffer();
+    window.set_size(100, 100);
+    window.ack_last_and_commit();
+    f.double_roundtrip(id);
+}
+
+fn title(window: &Window) -> String {
+    with_toplevel_role(window.toplevel().unwrap(), |role| {
+        role.title.clone().unwrap()
+    })
+}
+
+fn window(f: &mut Fixture, title_: &str) -> Window {
+    let niri = f.niri();
+    let (_, mapped) = niri
+        .layout
+        .windows()
+        .find(|(_, mapped)| title(&mapped.window) == title_)
+        .unwrap();
+    mapped.window.clone()
+}
+
+fn switcher_titles(
+    f: &mut Fixture,
+    scope: WindowSwitcherScope,
+    filter: WindowSwitcherFilter,
+) -> Vec<String> {
+    let windows = f.niri().window_switcher_windows(scope, filter);
+    windows.iter().map(title).collect()
+}
+
+#[test]
+fn mru_order() {
+    let (mut f, _id) = set_up();
+
+    let titles = switcher_titles(&mut f, WindowSwitcherScope::All, WindowSwitcherFilter::None);
+    assert_eq!(titles[..3], ["c", "b", "a"]);
+
+    // Never-focused windows sort last.
+    let mut rest = titles[3..].to_vec();
+    rest.sort();
+    assert_eq!(rest, ["never", "other-output", "other-ws"]);
+}
+
+#[test]
+fn workspace_scope() {
+    let (mut f, _id) = set_up();
+
+    let titles = switcher_titles(
+        &mut f,
+        WindowSwitcherScope::Workspace,
+        WindowSwitcherFilter::None,
+    );
+    assert_eq!(titles, ["c", "b", "a", "never"]);
+}
+
+#[test]
+fn output_scope() {
+    let (mut f, _id) = set_up();
+
+    let titles = switcher_titles(
+        &mut f,
+        WindowSwitcherScope::Output,
+        WindowSwitcherFilter::None,
+    );
+    assert_eq!(titles[..3], ["c", "b", "a"]);
+
+    // Includes the other workspace on the same output, but not the other output.
+    let mut rest = titles[3..].to_vec();
+    rest.sort();
+    assert_eq!(rest, ["never", "other-ws"]);
+}
+
+#[test]
+fn app_id_filter() {
+    let (mut f, _id) = set_up();
+
+    // The focused window C has the "foo" app ID.
+    let titles = switcher_titles(
+        &mut f,
+        WindowSwitcherScope::Workspace,
+        WindowSwitcherFilter::AppId,
+    );
+    assert_eq!(titles, ["c", "a", "never"]);
+
+    let titles = switcher_titles(
+        &mut f,
+        WindowSwitcherScope::All,
+        WindowSwitcherFilter::AppId,
+    );
+    assert!(!titles.contains(&String::from("b")));
+    assert_eq!(titles.len(), 5);
+}
+
+#[test]
+fn advance_wraps_around() {
+    let (mut f, _id) = set_up();
+    let output = f.niri_output(1);
+    let windows = f
+        .niri()
+        .window_switcher_windows(WindowSwitcherScope::Workspace, WindowSwitcherFilter::None);
+    assert_eq!(windows.len(), 4);
+
+    let mut switcher = WindowSwitcher::new();
+    switcher.open(windows.clone(), 3, output.clone(), Modifiers::ALT);
+    switcher.advance(true);
+    assert_eq!(switcher.close(), Some(windows[0].clone()));
+
+    switcher.open(windows.clone(), 0, output.clone(), Modifiers::ALT);
+    switcher.advance(false);
+    assert_eq!(switcher.close(), Some(windows[3].clone()));
+
+    switcher.open(windows.clone(), 1, output, Modifiers::ALT);
+    switcher.advance(true);
+    switcher.advance(true);
+    switcher.advance(true);
+    switcher.advance(false);
+    assert_eq!(switcher.close(), Some(windows[3].clone()));
+    assert!(!switcher.is_open());
+}
+
+#[test]
+fn releasing_trigger_commits() {
+    let (mut f, _id) = set_up();
+    let output = f.niri_output(1);
+    let windows = f
+        .niri()
+        .window_switcher_windows(WindowSwitcherScope::All, WindowSwitcherFilter::None);
+
+    // Opened with Alt+Shift+Tab, so only Alt keeps the switcher open.
+    let mods = trigger_mods(Modifiers::ALT | Modifiers::SHIFT);
+    assert_eq!(mods, Modifiers::ALT);
+
+    let mut switcher = WindowSwitcher::new();
+    assert!(!switcher.is_trigger_held(Modifiers::ALT));
+
+    switcher.open(windows.clone(), 1, output, mods);
+    // Releasing Shift keeps the switcher open.
+    assert!(switcher.is_trigger_held(Modifiers::ALT | Modifiers::SHIFT));
+    assert!(switcher.is_trigger_held(Modifiers::ALT));
+    // Releasing Alt commits the selection, even with Shift still held.
+    assert!(!switcher.is_trigger_held(Modifiers::SHIFT));
+    assert!(!switcher.is_trigger_held(Modifiers::empty()));
+    assert_eq!(switcher.close(), Some(windows[1].clone()));
+    assert_eq!(switcher.close(), None);
+}
+
+#[test]
+fn trigger_mods_drop_shift() {
+    assert_eq!(trigger_mods(Modifiers::ALT), Modifiers::ALT);
+    assert_eq!(
+        trigger_mods(Modifiers::SUPER | Modifiers::SHIFT),
+        Modifiers::SUPER
+    );
+    assert_eq!(
+        trigger_mods(Modifiers::CTRL | Modifiers::ALT),
+        Modifiers::CTRL | Modifiers::ALT
+    );
+    // With Shift alone, there's nothing else to hold.
+    assert_eq!(trigger_mods(Modifiers::SHIFT), Modifiers::SHIFT);
+}
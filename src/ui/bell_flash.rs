@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use smithay::backend::renderer::element::Kind;
+use smithay::desktop::Window;
+use smithay::utils::{Logical, Point, Rectangle};
+
+use crate::animation::Clock;
+use crate::render_helpers::solid_color::{SolidColorBuffer, SolidColorRenderElement};
+
+pub const DURATION: Duration = Duration::from_millis(300);
+
+/// Opacity of the flash at its peak.
+const PEAK_ALPHA: f32 = 0.25;
+
+#[derive(Debug)]
+pub struct BellFlash {
+    /// Buffer covering the flashed area.
+    buffer: SolidColorBuffer,
+    /// Location of the flashed area within the output.
+    location: Point<f64, Logical>,
+    /// Window to flash, or `None` to flash the whole output.
+    window: Option<Window>,
+    /// Monotonic time when the flash started.
+    start_at: Duration,
+    /// Clock to drive animations.
+    clock: Clock,
+}
+
+impl BellFlash {
+    pub fn new(clock: Clock, window: Option<Window>) -> Self {
+        Self {
+            buffer: SolidColorBuffer::new((0., 0.), [0., 0., 0., 0.]),
+            location: Point::from((0., 0.)),
+            window,
+            start_at: clock.now_unadjusted(),
+            clock,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.start_at + DURATION <= self.clock.now_unadjusted()
+    }
+
+    pub fn window(&self) -> Option<&Window> {
+        self.window.as_ref()
+    }
+
+    pub fn update_render_elements(&mut self, area: Rectangle<f64, Logical>) {
+        // Like the screen transition, the flash ignores animation slowdown.
+        let now = self.clock.now_unadjusted();
+        let progress = (now.saturating_sub(self.start_at)).as_secs_f32() / DURATION.as_secs_f32();
+        let alpha = PEAK_ALPHA * (1. - progress.clamp(0., 1.));
+
+        // Update the color rather than the element alpha so that damage tracking picks it up.
+        self.buffer.update(area.size, [alpha, alpha, alpha, alpha]);
+        self.location = area.loc;
+    }
+
+    pub fn render(&self) -> SolidColorRenderElement {
+        SolidColorRenderElement::from_buffer(&self.buffer, self.location, 1., Kind::Unspecified)
+    }
+}
@@ -30,6 +30,8 @@ pub struct HotkeyOverlay {
     is_open: bool,
     config: Rc<RefCell<Config>>,
     mod_key: ModKey,
+    /// Keys of the global shortcuts registered by applications.
+    global_shortcuts: Vec<Key>,
     buffers: RefCell<HashMap<WeakOutput, RenderedOverlay>>,
 }
 
@@ -43,6 +45,7 @@ impl HotkeyOverlay {
             is_open: false,
             config,
             mod_key,
+            global_shortcuts: Vec::new(),
             buffers: RefCell::new(HashMap::new()),
         }
     }
@@ -74,6 +77,13 @@ impl HotkeyOverlay {
         self.buffers.borrow_mut().clear();
     }
 
+    pub fn set_global_shortcuts(&mut self, keys: Vec<Key>) {
+        if self.global_shortcuts != keys {
+            self.global_shortcuts = keys;
+            self.buffers.borrow_mut().clear();
+        }
+    }
+
     pub fn render<R: NiriRenderer>(
         &self,
         renderer: &mut R,
@@ -101,8 +111,14 @@ impl HotkeyOverlay {
 
         let rendered = buffers.entry(weak).or_insert_with(|| {
             let renderer = renderer.as_gles_renderer();
-            render(renderer, &self.config.borrow(), self.mod_key, scale)
-                .unwrap_or_else(|_| RenderedOverlay { buffer: None })
+            render(
+                renderer,
+                &self.config.borrow(),
+                self.mod_key,
+                &self.global_shortcuts,
+                scale,
+            )
+            .unwrap_or_else(|_| RenderedOverlay { buffer: None })
         });
         let buffer = rendered.buffer.as_ref()?;
 
@@ -171,6 +187,7 @@ fn render(
     renderer: &mut GlesRenderer,
     config: &Config,
     mod_key: ModKey,
+    global_shortcuts: &[Key],
     scale: f64,
 ) -> anyhow::Result<RenderedOverlay> {
     let _span = tracy_client::span!("hotkey_overlay::render");
@@ -253,7 +270,7 @@ fn render(
     // Screenshot is not as important, can omit if not bound.
     if let Some(bind) = binds
         .iter()
-        .find(|bind| matches!(bind.action, Action::Screenshot(_)))
+        .find(|bind| matches!(bind.action, Action::Screenshot(..)))
     {
         actions.push(&bind.action);
     }
@@ -290,11 +307,25 @@ fn render(
         actions.retain(|&action| binds.iter().any(|bind| bind.action == *action))
     }
 
-    let strings = actions
+    let mut strings = actions
         .into_iter()
         .filter_map(|action| format_bind(binds, mod_key, action))
         .collect::<Vec<_>>();
 
+    // Add the global shortcuts registered by applications.
+    let mut seen = Vec::new();
+    for key in global_shortcuts {
+        if seen.contains(key) {
+            continue;
+        }
+        seen.push(*key);
+
+        strings.push((
+            format!(" {} ", key_name(mod_key, key)),
+            String::from("Application Global Shortcut"),
+        ));
+    }
+
     let mut font = FontDescription::from_string(FONT);
     font.set_absolute_size(to_physical_precise_round(scale, font.size()));
 
@@ -442,7 +473,11 @@ fn action_name(action: &Action) -> String {
             String::from("Switch Focus Between Floating and Tiling")
         }
         Action::ToggleOverview => String::from("Open the Overview"),
-        Action::Screenshot(_) => String::from("Take a Screenshot"),
+        Action::Screenshot(..) => String::from("Take a Screenshot"),
+        Action::GlobalShortcut(shortcut) => format!(
+            "Global Shortcut <span face='monospace' bgcolor='#000000'>{}</span>",
+            shortcut
+        ),
         Action::Spawn(args) => format!(
             "Spawn <span face='monospace' bgcolor='#000000'>{}</span>",
             args.first().unwrap_or(&String::new())
@@ -568,7 +603,7 @@ mod tests {
     #[test]
     fn test_format_bind() {
         // Not bound.
-        assert_snapshot!(check("", Action::Screenshot(true)), @" (not bound) : Take a Screenshot");
+        assert_snapshot!(check("", Action::Screenshot(true, None)), @" (not bound) : Take a Screenshot");
 
         // Bound with a default title.
         assert_snapshot!(
@@ -576,7 +611,7 @@ mod tests {
                 r#"binds {
                     Mod+P { screenshot; }
                 }"#,
-                Action::Screenshot(true),
+                Action::Screenshot(true, None),
             ),
             @" Super + P : Take a Screenshot"
         );
@@ -587,7 +622,7 @@ mod tests {
                 r#"binds {
                     Mod+P hotkey-overlay-title="Hello" { screenshot; }
                 }"#,
-                Action::Screenshot(true),
+                Action::Screenshot(true, None),
             ),
             @" Super + P : Hello"
         );
@@ -599,7 +634,7 @@ mod tests {
                     Mod+P { screenshot; }
                     Print { screenshot; }
                 }"#,
-                Action::Screenshot(true),
+                Action::Screenshot(true, None),
             ),
             @" Super + P : Take a Screenshot"
         );
@@ -611,7 +646,7 @@ mod tests {
                     Mod+P { screenshot; }
                     Print hotkey-overlay-title="My Cool Bind" { screenshot; }
                 }"#,
-                Action::Screenshot(true),
+                Action::Screenshot(true, None),
             ),
             @" PrtSc : My Cool Bind"
         );
@@ -623,7 +658,7 @@ mod tests {
                     Mod+P hotkey-overlay-title="First" { screenshot; }
                     Print hotkey-overlay-title="My Cool Bind" { screenshot; }
                 }"#,
-                Action::Screenshot(true),
+                Action::Screenshot(true, None),
             ),
             @" Super + P : First"
         );
@@ -635,7 +670,7 @@ mod tests {
                     Mod+P { screenshot; }
                     Print hotkey-overlay-title=null { screenshot; }
                 }"#,
-                Action::Screenshot(true),
+                Action::Screenshot(true, None),
             ),
             @"None"
         );
@@ -647,7 +682,7 @@ mod tests {
                     Mod+P hotkey-overlay-title="Hello" { screenshot; }
                     Print hotkey-overlay-title=null { screenshot; }
                 }"#,
-                Action::Screenshot(true),
+                Action::Screenshot(true, None),
             ),
             @" Super + P : Hello"
         );
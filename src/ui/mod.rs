@@ -1,5 +1,13 @@
+pub mod bell_flash;
 pub mod config_error_notification;
 pub mod exit_confirm_dialog;
 pub mod hotkey_overlay;
+pub mod overview_search;
+pub mod recording_indicator;
+pub mod remote_desktop_indicator;
 pub mod screen_transition;
+pub mod screencast_indicator;
+pub mod screenshot_countdown;
 pub mod screenshot_ui;
+pub mod window_hints;
+pub mod window_switcher;
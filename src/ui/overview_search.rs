@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+
+use ordered_float::NotNan;
+use pangocairo::cairo::{self, ImageSurface};
+use pangocairo::pango::FontDescription;
+use smithay::backend::renderer::element::Kind;
+use smithay::output::Output;
+use smithay::reexports::gbm::Format as Fourcc;
+use smithay::utils::{Point, Transform};
+
+use crate::render_helpers::memory::MemoryBuffer;
+use crate::render_helpers::primary_gpu_texture::PrimaryGpuTextureRenderElement;
+use crate::render_helpers::renderer::NiriRenderer;
+use crate::render_helpers::texture::{TextureBuffer, TextureRenderElement};
+use crate::utils::{output_size, to_physical_precise_round};
+
+const PADDING: i32 = 12;
+const MARGIN: f64 = 32.;
+const FONT: &str = "sans 20px";
+const BORDER: i32 = 4;
+
+/// Bar showing the text typed to search windows in the overview.
+pub struct OverviewSearchBar {
+    /// The last rendered query and scale.
+    buffer: RefCell<Option<(String, NotNan<f64>, Option<MemoryBuffer>)>>,
+}
+
+impl OverviewSearchBar {
+    pub fn new() -> Self {
+        Self {
+            buffer: RefCell::new(None),
+        }
+    }
+
+    pub fn render<R: NiriRenderer>(
+        &self,
+        renderer: &mut R,
+        output: &Output,
+        query: &str,
+    ) -> Option<PrimaryGpuTextureRenderElement> {
+        if query.is_empty() {
+            return None;
+        }
+
+        let scale = output.current_scale().fractional_scale();
+        let output_size = output_size(output);
+
+        let mut buffer = self.buffer.borrow_mut();
+        let key_scale = NotNan::new(scale).unwrap();
+        if !matches!(&*buffer, Some((q, s, _)) if q == query && *s == key_scale) {
+            *buffer = Some((String::from(query), key_scale, render(query, scale).ok()));
+        }
+        let buffer = buffer.as_ref().unwrap().2.as_ref()?;
+
+        let size = buffer.logical_size();
+        let buffer = TextureBuffer::from_memory_buffer(renderer.as_gles_renderer(), buffer).ok()?;
+
+        // Show the bar at the top center of the output.
+        let location = Point::from(((output_size.w - size.w) / 2., MARGIN));
+        let mut location = location.to_physical_precise_round(scale).to_logical(scale);
+        location.x = f64::max(0., location.x);
+
+        let elem = TextureRenderElement::from_texture_buffer(
+            buffer,
+            location,
+            1.,
+            None,
+            None,
+            Kind::Unspecified,
+        );
+        Some(PrimaryGpuTextureRenderElement(elem))
+    }
+}
+
+impl Default for OverviewSearchBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn render(query: &str, scale: f64) -> anyhow::Result<MemoryBuffer> {
+    let _span = tracy_client::span!("overview_search::render");
+
+    let padding: i32 = to_physical_precise_round(scale, PADDING);
+
+    let mut font = FontDescription::from_string(FONT);
+    font.set_absolute_size(to_physical_precise_round(scale, font.size()));
+
+    let surface = ImageSurface::create(cairo::Format::ARgb32, 0, 0)?;
+    let cr = cairo::Context::new(&surface)?;
+    let layout = pangocairo::functions::create_layout(&cr);
+    layout.context().set_round_glyph_positions(false);
+    layout.set_font_description(Some(&font));
+    layout.set_text(query);
+
+    let (mut width, mut height) = layout.pixel_size();
+    width += padding * 2;
+    height += padding * 2;
+
+    let surface = ImageSurface::create(cairo::Format::ARgb32, width, height)?;
+    let cr = cairo::Context::new(&surface)?;
+    cr.set_source_rgb(0.1, 0.1, 0.1);
+    cr.paint()?;
+
+    cr.move_to(padding.into(), padding.into());
+    let layout = pangocairo::functions::create_layout(&cr);
+    layout.context().set_round_glyph_positions(false);
+    layout.set_font_description(Some(&font));
+    layout.set_text(query);
+
+    cr.set_source_rgb(1., 1., 1.);
+    pangocairo::functions::show_layout(&cr, &layout);
+
+    cr.move_to(0., 0.);
+    cr.line_to(width.into(), 0.);
+    cr.line_to(width.into(), height.into());
+    cr.line_to(0., height.into());
+    cr.line_to(0., 0.);
+    cr.set_source_rgb(0.5, 0.8, 1.0);
+    // Keep the border width even to avoid blurry edges.
+    cr.set_line_width((f64::from(BORDER) / 2. * scale).round() * 2.);
+    cr.stroke()?;
+    drop(cr);
+
+    let data = surface.take_data().unwrap();
+    let buffer = MemoryBuffer::new(
+        data.to_vec(),
+        Fourcc::Argb8888,
+        (width, height),
+        scale,
+        Transform::Normal,
+    );
+
+    Ok(buffer)
+}
@@ -0,0 +1,78 @@
+use std::cell::RefCell;
+
+use niri_config::ScreencastIndicator as Config;
+use smithay::backend::renderer::element::Kind;
+use smithay::utils::{Logical, Point, Rectangle, Size};
+
+use crate::render_helpers::solid_color::{SolidColorBuffer, SolidColorRenderElement};
+use crate::utils::round_logical_in_physical_max1;
+
+/// Border drawn on top of what is being screencast.
+///
+/// The border is drawn inside the edges of the cast rectangles, so that it remains visible for
+/// casts of a whole output.
+#[derive(Default)]
+pub struct ScreencastIndicator {
+    /// Top, bottom, left and right edges of every rectangle.
+    ///
+    /// The buffers are reused across frames to keep their ids stable for damage tracking.
+    borders: RefCell<Vec<[SolidColorBuffer; 4]>>,
+}
+
+impl ScreencastIndicator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn render(
+        &self,
+        config: &Config,
+        rects: &[Rectangle<f64, Logical>],
+        scale: f64,
+    ) -> Vec<SolidColorRenderElement> {
+        let mut elements = Vec::new();
+
+        let mut borders = self.borders.borrow_mut();
+        if borders.len() < rects.len() {
+            borders.resize_with(rects.len(), Default::default);
+        }
+
+        let color = config.color.to_array_premul();
+        let width = round_logical_in_physical_max1(scale, config.width.0);
+
+        for (rect, buffers) in rects.iter().zip(borders.iter_mut()) {
+            let width = width.min(rect.size.w / 2.).min(rect.size.h / 2.);
+            if width <= 0. {
+                continue;
+            }
+
+            let horizontal = Size::from((rect.size.w, width));
+            let vertical = Size::from((width, rect.size.h - width * 2.));
+
+            let edges = [
+                (rect.loc, horizontal),
+                (
+                    rect.loc + Point::from((0., rect.size.h - width)),
+                    horizontal,
+                ),
+                (rect.loc + Point::from((0., width)), vertical),
+                (
+                    rect.loc + Point::from((rect.size.w - width, width)),
+                    vertical,
+                ),
+            ];
+
+            for (buffer, (loc, size)) in buffers.iter_mut().zip(edges) {
+                buffer.update(size, color);
+                elements.push(SolidColorRenderElement::from_buffer(
+                    buffer,
+                    loc,
+                    1.,
+                    Kind::Unspecified,
+                ));
+            }
+        }
+
+        elements
+    }
+}
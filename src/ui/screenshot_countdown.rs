@@ -0,0 +1,127 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use ordered_float::NotNan;
+use pangocairo::cairo::{self, ImageSurface};
+use pangocairo::pango::FontDescription;
+use smithay::backend::renderer::element::Kind;
+use smithay::backend::renderer::gles::{GlesRenderer, GlesTexture};
+use smithay::output::Output;
+use smithay::reexports::gbm::Format as Fourcc;
+use smithay::utils::{Point, Transform};
+
+use crate::render_helpers::primary_gpu_texture::PrimaryGpuTextureRenderElement;
+use crate::render_helpers::renderer::NiriRenderer;
+use crate::render_helpers::texture::{TextureBuffer, TextureRenderElement};
+use crate::utils::{output_size, to_physical_precise_round};
+
+const PADDING: i32 = 12;
+const MARGIN: i32 = 16;
+const FONT: &str = "sans bold 16px";
+const BACKGROUND: (f64, f64, f64) = (0.1, 0.1, 0.1);
+
+/// Countdown shown at the top of every output before a delayed screenshot.
+#[derive(Default)]
+pub struct ScreenshotCountdown {
+    /// Buffers by scale, along with the number of seconds they show.
+    buffers: RefCell<HashMap<NotNan<f64>, (u64, Option<TextureBuffer<GlesTexture>>)>>,
+}
+
+impl ScreenshotCountdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn render<R: NiriRenderer>(
+        &self,
+        renderer: &mut R,
+        output: &Output,
+        seconds: u64,
+    ) -> Option<PrimaryGpuTextureRenderElement> {
+        let scale = output.current_scale().fractional_scale();
+        let output_size = output_size(output);
+
+        let mut buffers = self.buffers.borrow_mut();
+        let (buf_seconds, buffer) = buffers
+            .entry(NotNan::new(scale).unwrap())
+            .or_insert((0, None));
+        if *buf_seconds != seconds || buffer.is_none() {
+            *buf_seconds = seconds;
+            *buffer = render(renderer.as_gles_renderer(), scale, seconds)
+                .map_err(|err| warn!("error rendering screenshot countdown: {err:?}"))
+                .ok();
+        }
+        let buffer = buffer.clone()?;
+
+        let size = buffer.logical_size();
+        let x = ((output_size.w - size.w) / 2.).max(0.);
+        let y = f64::from(MARGIN);
+
+        let location = Point::from((x, y));
+        let location = location.to_physical_precise_round(scale).to_logical(scale);
+
+        let elem = TextureRenderElement::from_texture_buffer(
+            buffer,
+            location,
+            0.9,
+            None,
+            None,
+            Kind::Unspecified,
+        );
+        Some(PrimaryGpuTextureRenderElement(elem))
+    }
+}
+
+fn render(
+    renderer: &mut GlesRenderer,
+    scale: f64,
+    seconds: u64,
+) -> anyhow::Result<TextureBuffer<GlesTexture>> {
+    let _span = tracy_client::span!("screenshot_countdown::render");
+
+    let text = format!("Screenshot in {seconds}…");
+    let padding: i32 = to_physical_precise_round(scale, PADDING);
+
+    let mut font = FontDescription::from_string(FONT);
+    font.set_absolute_size(to_physical_precise_round(scale, font.size()));
+
+    let surface = ImageSurface::create(cairo::Format::ARgb32, 0, 0)?;
+    let cr = cairo::Context::new(&surface)?;
+    let layout = pangocairo::functions::create_layout(&cr);
+    layout.context().set_round_glyph_positions(false);
+    layout.set_font_description(Some(&font));
+    layout.set_text(&text);
+
+    let (mut width, mut height) = layout.pixel_size();
+    width += padding * 2;
+    height += padding * 2;
+
+    let surface = ImageSurface::create(cairo::Format::ARgb32, width, height)?;
+    let cr = cairo::Context::new(&surface)?;
+    cr.set_source_rgb(BACKGROUND.0, BACKGROUND.1, BACKGROUND.2);
+    cr.paint()?;
+
+    cr.move_to(padding.into(), padding.into());
+    let layout = pangocairo::functions::create_layout(&cr);
+    layout.context().set_round_glyph_positions(false);
+    layout.set_font_description(Some(&font));
+    layout.set_text(&text);
+
+    cr.set_source_rgb(1., 1., 1.);
+    pangocairo::functions::show_layout(&cr, &layout);
+    drop(cr);
+
+    let data = surface.take_data().unwrap();
+    let buffer = TextureBuffer::from_memory(
+        renderer,
+        &data,
+        Fourcc::Argb8888,
+        (width, height),
+        false,
+        scale,
+        Transform::Normal,
+        Vec::new(),
+    )?;
+
+    Ok(buffer)
+}
@@ -1,13 +1,13 @@
 use std::cell::RefCell;
 use std::cmp::{max, min};
 use std::collections::HashMap;
-use std::f64::consts::TAU;
+use std::f64::consts::{PI, TAU};
 use std::iter::zip;
 use std::rc::Rc;
 
 use anyhow::Context;
 use arrayvec::ArrayVec;
-use niri_config::{Action, Config};
+use niri_config::{Action, Config, ScreenshotAnnotations, ScreenshotMode, ScreenshotTool};
 use niri_ipc::SizeChange;
 use pango::{Alignment, FontDescription};
 use pangocairo::cairo::{self, ImageSurface};
@@ -25,6 +25,7 @@ use crate::animation::{Animation, Clock};
 use crate::layout::floating::DIRECTIONAL_MOVE_PX;
 use crate::niri_render_elements;
 use crate::render_helpers::primary_gpu_texture::PrimaryGpuTextureRenderElement;
+use crate::render_helpers::renderer::NiriRenderer;
 use crate::render_helpers::solid_color::{SolidColorBuffer, SolidColorRenderElement};
 use crate::render_helpers::texture::{TextureBuffer, TextureRenderElement};
 use crate::render_helpers::{render_to_texture, RenderTarget};
@@ -38,10 +39,35 @@ const FONT: &str = "sans 14px";
 const BORDER: i32 = 4;
 const TEXT_HIDE_P: &str =
     "Press <span face='mono' bgcolor='#2C2C2C'> Space </span> to save the screenshot.\n\
-     Press <span face='mono' bgcolor='#2C2C2C'> P </span> to hide the pointer.";
+     Press <span face='mono' bgcolor='#2C2C2C'> P </span> to hide the pointer.\n\
+     Press <span face='mono' bgcolor='#2C2C2C'> R </span> \
+     <span face='mono' bgcolor='#2C2C2C'> A </span> \
+     <span face='mono' bgcolor='#2C2C2C'> D </span> \
+     <span face='mono' bgcolor='#2C2C2C'> T </span> \
+     <span face='mono' bgcolor='#2C2C2C'> X </span> to annotate.\n\
+     Press <span face='mono' bgcolor='#2C2C2C'> W </span> to pick a window, \
+     <span face='mono' bgcolor='#2C2C2C'> O </span> to pick the whole screen.";
 const TEXT_SHOW_P: &str =
     "Press <span face='mono' bgcolor='#2C2C2C'> Space </span> to save the screenshot.\n\
-     Press <span face='mono' bgcolor='#2C2C2C'> P </span> to show the pointer.";
+     Press <span face='mono' bgcolor='#2C2C2C'> P </span> to show the pointer.\n\
+     Press <span face='mono' bgcolor='#2C2C2C'> R </span> \
+     <span face='mono' bgcolor='#2C2C2C'> A </span> \
+     <span face='mono' bgcolor='#2C2C2C'> D </span> \
+     <span face='mono' bgcolor='#2C2C2C'> T </span> \
+     <span face='mono' bgcolor='#2C2C2C'> X </span> to annotate.\n\
+     Press <span face='mono' bgcolor='#2C2C2C'> W </span> to pick a window, \
+     <span face='mono' bgcolor='#2C2C2C'> O </span> to pick the whole screen.";
+const TEXT_CAST: &str =
+    "Press <span face='mono' bgcolor='#2C2C2C'> Space </span> to cast the selected area.\n\
+     Press <span face='mono' bgcolor='#2C2C2C'> W </span> to pick a window, \
+     <span face='mono' bgcolor='#2C2C2C'> O </span> to pick the whole screen.";
+const TEXT_RECORD: &str =
+    "Press <span face='mono' bgcolor='#2C2C2C'> Space </span> to record the selected area.\n\
+     Press <span face='mono' bgcolor='#2C2C2C'> W </span> to pick a window, \
+     <span face='mono' bgcolor='#2C2C2C'> O </span> to pick the whole screen.";
+
+const ANNOTATION_FONT: &str = "sans bold 24px";
+const PIXELATE_BLOCK: i32 = 12;
 
 // Ideally the screenshot UI should support cross-output selections. However, that poses some
 // technical challenges when the outputs have different scales and such. So, this implementation
@@ -60,12 +86,26 @@ pub enum ScreenshotUi {
         output_data: HashMap<Output, OutputData>,
         button: Button,
         show_pointer: bool,
+        purpose: SelectionPurpose,
+        mode: ScreenshotMode,
+        tool: ScreenshotTool,
+        annotations: Annotations,
         open_anim: Animation,
         clock: Clock,
         config: Rc<RefCell<Config>>,
     },
 }
 
+/// What the selection will be used for once confirmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionPurpose {
+    Screenshot,
+    /// Set the area as the dynamic cast target.
+    DynamicCast,
+    /// Start recording the area.
+    Recording,
+}
+
 /// State for moving the selection (as opposed to just drawing).
 pub struct MoveState {
     // Cursor offset from selection.1 when starting the move.
@@ -80,11 +120,32 @@ pub enum Button {
     Down {
         touch_slot: Option<TouchSlot>,
         on_capture_button: bool,
+        /// Whether this press is drawing the last annotation rather than the selection.
+        drawing: bool,
         last_pos: (Output, Point<i32, Physical>),
         move_state: Option<MoveState>,
     },
 }
 
+/// Annotations drawn over the screenshot.
+#[derive(Default)]
+pub struct Annotations {
+    /// Annotations in the order they were drawn, along with the output they are on.
+    items: Vec<(Output, Annotation)>,
+    /// Whether the last annotation is text that is being typed.
+    typing: bool,
+}
+
+/// Annotation drawn over the screenshot, in output-local physical coordinates.
+#[derive(Debug, PartialEq)]
+pub enum Annotation {
+    Rectangle(Point<i32, Physical>, Point<i32, Physical>),
+    Arrow(Point<i32, Physical>, Point<i32, Physical>),
+    Freehand(Vec<Point<i32, Physical>>),
+    Text(Point<i32, Physical>, String),
+    Pixelate(Point<i32, Physical>, Point<i32, Physical>),
+}
+
 pub struct OutputData {
     size: Size<i32, Physical>,
     scale: f64,
@@ -94,12 +155,22 @@ pub struct OutputData {
     buffers: [SolidColorBuffer; 8],
     locations: [Point<i32, Physical>; 8],
     panel: Option<(TextureBuffer<GlesTexture>, TextureBuffer<GlesTexture>)>,
+    /// Geometries of the windows at the time of the screenshot, topmost first.
+    windows: Vec<Rectangle<i32, Physical>>,
+    /// Annotations rendered on top of each screenshot.
+    ///
+    /// The outer `None` means that the overlay needs to be rendered again.
+    annotation_overlays: RefCell<[Option<Option<PrimaryGpuTextureRenderElement>>; 3]>,
 }
 
 pub struct OutputScreenshot {
     texture: GlesTexture,
     buffer: PrimaryGpuTextureRenderElement,
     pointer: Option<PrimaryGpuTextureRenderElement>,
+    /// RGBA8 pixels of the texture, read back for pixelating.
+    pixels: RefCell<Option<Vec<u8>>>,
+    /// Average colors of the pixelate blocks, by block index.
+    blocks: RefCell<HashMap<(i32, i32), [f64; 3]>>,
 }
 
 niri_render_elements! {
@@ -119,6 +190,7 @@ impl Button {
             self,
             Self::Down {
                 on_capture_button: false,
+                drawing: false,
                 ..
             }
         )
@@ -139,8 +211,10 @@ impl ScreenshotUi {
         renderer: &mut GlesRenderer,
         // Output, screencast, screen capture.
         screenshots: HashMap<Output, [OutputScreenshot; 3]>,
+        mut windows: HashMap<Output, Vec<Rectangle<i32, Physical>>>,
         default_output: Output,
         show_pointer: bool,
+        purpose: SelectionPurpose,
     ) -> bool {
         if screenshots.is_empty() {
             return false;
@@ -205,9 +279,20 @@ impl ScreenshotUi {
                         .map_err(|err| warn!("error rendering help panel: {err:?}"))
                         .ok()
                 };
-                let panel_show = render_panel_(TEXT_SHOW_P);
-                let panel_hide = render_panel_(TEXT_HIDE_P);
-                let panel = Option::zip(panel_show, panel_hide);
+                let panel = match purpose {
+                    SelectionPurpose::Screenshot => {
+                        let panel_show = render_panel_(TEXT_SHOW_P);
+                        let panel_hide = render_panel_(TEXT_HIDE_P);
+                        Option::zip(panel_show, panel_hide)
+                    }
+                    // The pointer toggle doesn't apply to casts, so show the same text.
+                    SelectionPurpose::DynamicCast => {
+                        render_panel_(TEXT_CAST).map(|panel| (panel.clone(), panel))
+                    }
+                    SelectionPurpose::Recording => {
+                        render_panel_(TEXT_RECORD).map(|panel| (panel.clone(), panel))
+                    }
+                };
 
                 let data = OutputData {
                     size,
@@ -217,6 +302,8 @@ impl ScreenshotUi {
                     buffers,
                     locations,
                     panel,
+                    windows: windows.remove(&output).unwrap_or_default(),
+                    annotation_overlays: RefCell::default(),
                 };
                 (output, data)
             })
@@ -232,6 +319,10 @@ impl ScreenshotUi {
             output_data,
             button: Button::Up,
             show_pointer,
+            purpose,
+            mode: ScreenshotMode::Area,
+            tool: ScreenshotTool::Select,
+            annotations: Annotations::default(),
             open_anim,
             clock: clock.clone(),
             config: config.clone(),
@@ -277,7 +368,130 @@ impl ScreenshotUi {
         matches!(self, ScreenshotUi::Open { .. })
     }
 
+    /// Switches to the mode, or back to the area mode if the mode is already active.
+    pub fn set_mode(&mut self, new_mode: ScreenshotMode) {
+        let Self::Open {
+            selection,
+            output_data,
+            button,
+            mode,
+            ..
+        } = self
+        else {
+            return;
+        };
+
+        // Don't switch modes in the middle of a drag.
+        if button.is_down() {
+            return;
+        }
+
+        *mode = if *mode == new_mode {
+            ScreenshotMode::Area
+        } else {
+            new_mode
+        };
+
+        // Select the current output right away. Windows are picked by clicking on them.
+        if *mode == ScreenshotMode::Output {
+            let size = output_data[&selection.0].size;
+            selection.1 = Point::from((0, 0));
+            selection.2 = Point::from((size.w - 1, size.h - 1));
+            self.update_buffers();
+        }
+    }
+
+    /// Switches to the tool, or back to selecting if the tool is already active.
+    pub fn set_tool(&mut self, new_tool: ScreenshotTool) {
+        self.finish_text();
+
+        let Self::Open { tool, button, .. } = self else {
+            return;
+        };
+
+        // Don't switch tools in the middle of a drag.
+        if button.is_down() {
+            return;
+        }
+
+        *tool = if *tool == new_tool {
+            ScreenshotTool::Select
+        } else {
+            new_tool
+        };
+    }
+
+    pub fn undo(&mut self) {
+        let Self::Open {
+            annotations,
+            output_data,
+            button,
+            ..
+        } = self
+        else {
+            return;
+        };
+
+        if button.is_down() {
+            return;
+        }
+
+        if let Some(output) = annotations.undo() {
+            invalidate_annotations(output_data, &output);
+        }
+    }
+
+    pub fn is_typing(&self) -> bool {
+        matches!(self, Self::Open { annotations, .. } if annotations.typing)
+    }
+
+    pub fn insert_text(&mut self, c: char) {
+        self.edit_text(|text| text.push(c));
+    }
+
+    pub fn delete_text(&mut self) {
+        self.edit_text(|text| {
+            text.pop();
+        });
+    }
+
+    fn edit_text(&mut self, f: impl FnOnce(&mut String)) {
+        let Self::Open {
+            annotations,
+            output_data,
+            ..
+        } = self
+        else {
+            return;
+        };
+
+        if let Some(output) = annotations.edit_text(f) {
+            invalidate_annotations(output_data, output);
+        }
+    }
+
+    /// Stops typing the text annotation, removing it if it's empty.
+    pub fn finish_text(&mut self) {
+        let Self::Open {
+            annotations,
+            output_data,
+            ..
+        } = self
+        else {
+            return;
+        };
+
+        if let Some(output) = annotations.finish_text() {
+            invalidate_annotations(output_data, &output);
+        }
+    }
+
     pub fn set_space_down(&mut self, down: bool) {
+        // Space goes into the text.
+        if self.is_typing() {
+            return;
+        }
+
         if let Self::Open {
             selection,
             button:
@@ -398,6 +612,7 @@ impl ScreenshotUi {
         let Self::Open {
             selection,
             output_data,
+            mode,
             ..
         } = self
         else {
@@ -448,6 +663,13 @@ impl ScreenshotUi {
             Size::from((new_width, new_height)),
         );
 
+        // In the output mode, the selection always covers the whole output.
+        let new_rect = if *mode == ScreenshotMode::Output {
+            Rectangle::from_size(target_data.size)
+        } else {
+            new_rect
+        };
+
         *selection = (
             new_output,
             new_rect.loc,
@@ -616,11 +838,12 @@ impl ScreenshotUi {
         }
     }
 
-    pub fn render_output(
+    pub fn render_output<R: NiriRenderer>(
         &self,
+        renderer: &mut R,
         output: &Output,
         target: RenderTarget,
-    ) -> ArrayVec<ScreenshotUiRenderElement, 11> {
+    ) -> ArrayVec<ScreenshotUiRenderElement, 12> {
         let _span = tracy_client::span!("ScreenshotUi::render_output");
 
         let Self::Open {
@@ -685,6 +908,12 @@ impl ScreenshotUi {
         };
         let screenshot = &output_data.screenshot[index];
 
+        // The annotations go right on top of it.
+        let renderer = renderer.as_gles_renderer();
+        if let Some(overlay) = self.annotation_overlay(renderer, output, output_data, index) {
+            elements.push(overlay.into());
+        }
+
         if *show_pointer {
             if let Some(pointer) = screenshot.pointer.clone() {
                 elements.push(pointer.into());
@@ -716,35 +945,42 @@ impl ScreenshotUi {
 
         let screenshot = &data.screenshot[0];
 
-        // Composite the pointer on top if needed.
-        let mut tex_rect = None;
+        // Composite the annotations and the pointer on top if needed.
+        let mut elements = ArrayVec::<_, 3>::new();
+        if let Some(overlay) = self.annotation_overlay(renderer, &selection.0, data, 0) {
+            elements.push(overlay);
+        }
         if *show_pointer {
             if let Some(pointer) = screenshot.pointer.clone() {
-                let scale = pointer.0.buffer().texture_scale();
-                let offset = rect.loc.upscale(-1);
-
-                let mut elements = ArrayVec::<_, 2>::new();
                 elements.push(pointer);
-                elements.push(screenshot.buffer.clone());
-                let elements = elements.iter().rev().map(|elem| {
-                    RelocateRenderElement::from_element(elem, offset, Relocate::Relative)
-                });
+            }
+        }
 
-                let res = render_to_texture(
-                    renderer,
-                    rect.size,
-                    scale,
-                    Transform::Normal,
-                    Fourcc::Abgr8888,
-                    elements,
-                );
-                match res {
-                    Ok((texture, _)) => {
-                        tex_rect = Some((texture, Rectangle::from_size(rect.size)));
-                    }
-                    Err(err) => {
-                        warn!("error compositing pointer onto screenshot: {err:?}");
-                    }
+        let mut tex_rect = None;
+        if !elements.is_empty() {
+            let scale = screenshot.buffer.0.buffer().texture_scale();
+            let offset = rect.loc.upscale(-1);
+
+            elements.push(screenshot.buffer.clone());
+            let elements = elements
+                .iter()
+                .rev()
+                .map(|elem| RelocateRenderElement::from_element(elem, offset, Relocate::Relative));
+
+            let res = render_to_texture(
+                renderer,
+                rect.size,
+                scale,
+                Transform::Normal,
+                Fourcc::Abgr8888,
+                elements,
+            );
+            match res {
+                Ok((texture, _)) => {
+                    tex_rect = Some((texture, Rectangle::from_size(rect.size)));
+                }
+                Err(err) => {
+                    warn!("error compositing annotations onto screenshot: {err:?}");
                 }
             }
         }
@@ -765,19 +1001,72 @@ impl ScreenshotUi {
         Ok((rect.size, copy.to_vec()))
     }
 
-    pub fn action(&self, raw: Keysym, mods: ModifiersState) -> Option<Action> {
-        let Self::Open { button, .. } = self else {
+    pub fn action(&self, raw: Keysym, modified: Keysym, mods: ModifiersState) -> Option<Action> {
+        let Self::Open {
+            button,
+            purpose,
+            annotations,
+            ..
+        } = self
+        else {
             return None;
         };
 
+        if annotations.typing {
+            return text_action(raw, modified, mods);
+        }
+
         // Pressing Space while the button is down goes into origin moving rather than capture.
         if matches!(button, Button::Down { .. }) && raw == Keysym::space {
             return None;
         }
 
+        // Annotations only make sense for screenshots.
+        if *purpose == SelectionPurpose::Screenshot {
+            if let Some(action) = annotation_action(raw, mods) {
+                return Some(action);
+            }
+        }
+
         action(raw, mods)
     }
 
+    /// Returns the annotation overlay for the screenshot at `index`, rendering it if needed.
+    fn annotation_overlay(
+        &self,
+        renderer: &mut GlesRenderer,
+        output: &Output,
+        data: &OutputData,
+        index: usize,
+    ) -> Option<PrimaryGpuTextureRenderElement> {
+        let Self::Open {
+            annotations,
+            config,
+            ..
+        } = self
+        else {
+            return None;
+        };
+
+        let mut overlays = data.annotation_overlays.borrow_mut();
+        let overlay = overlays[index].get_or_insert_with(|| {
+            let caret = annotations.has_caret_on(output);
+            let annotations: Vec<_> = annotations.on_output(output).collect();
+            if annotations.is_empty() {
+                return None;
+            }
+
+            let config = config.borrow();
+            let style = &config.screenshot_annotations;
+            let screenshot = &data.screenshot[index];
+            render_annotations(renderer, data, screenshot, &annotations, caret, style)
+                .map_err(|err| warn!("error rendering screenshot annotations: {err:?}"))
+                .ok()
+                .flatten()
+        });
+        overlay.clone()
+    }
+
     pub fn selection_output(&self) -> Option<&Output> {
         if let Self::Open {
             selection: (output, _, _),
@@ -790,6 +1079,24 @@ impl ScreenshotUi {
         }
     }
 
+    pub fn purpose(&self) -> Option<SelectionPurpose> {
+        if let Self::Open { purpose, .. } = self {
+            Some(*purpose)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the current selection in output-local physical coordinates.
+    pub fn selection(&self) -> Option<(&Output, Rectangle<i32, Physical>)> {
+        if let Self::Open { selection, .. } = self {
+            let rect = rect_from_corner_points(selection.1, selection.2);
+            Some((&selection.0, rect))
+        } else {
+            None
+        }
+    }
+
     pub fn output_size(&self, output: &Output) -> Option<(Size<i32, Physical>, f64, Transform)> {
         if let Self::Open { output_data, .. } = self {
             let data = output_data.get(output)?;
@@ -808,9 +1115,11 @@ impl ScreenshotUi {
                 Button::Down {
                     touch_slot,
                     on_capture_button,
+                    drawing,
                     last_pos,
                     move_state,
                 },
+            annotations,
             ..
         } = self
         else {
@@ -827,6 +1136,13 @@ impl ScreenshotUi {
             return;
         }
 
+        if *drawing {
+            if let Some(output) = annotations.drag_to(point) {
+                invalidate_annotations(output_data, output);
+            }
+            return;
+        }
+
         if let Some(move_state) = move_state {
             // The cursor offset is relative to selection.1.
             let delta = point - (selection.1 + move_state.pointer_offset);
@@ -856,6 +1172,8 @@ impl ScreenshotUi {
             output_data,
             show_pointer,
             button,
+            mode,
+            tool,
             ..
         } = self
         else {
@@ -866,6 +1184,7 @@ impl ScreenshotUi {
         if let Some(new_slot) = slot {
             if let Button::Down {
                 on_capture_button: false,
+                drawing: false,
                 move_state,
                 last_pos,
                 ..
@@ -897,6 +1216,7 @@ impl ScreenshotUi {
                 *button = Button::Down {
                     touch_slot: slot,
                     on_capture_button: true,
+                    drawing: false,
                     last_pos: (output, point),
                     move_state: None,
                 };
@@ -904,9 +1224,66 @@ impl ScreenshotUi {
             }
         }
 
+        if *tool != ScreenshotTool::Select {
+            let tool = *tool;
+            let switch_output = selection.0 != output;
+
+            *button = Button::Down {
+                touch_slot: slot,
+                on_capture_button: false,
+                drawing: true,
+                last_pos: (output.clone(), point),
+                move_state: None,
+            };
+
+            self.finish_text();
+
+            let Self::Open {
+                output_data,
+                annotations,
+                ..
+            } = self
+            else {
+                unreachable!()
+            };
+            annotations.start(output.clone(), tool, point);
+            invalidate_annotations(output_data, &output);
+
+            // Annotations are drawn relative to the selection output, so switch to it.
+            if switch_output {
+                self.move_to_output(output);
+            }
+
+            return true;
+        }
+
+        match *mode {
+            ScreenshotMode::Area => (),
+            ScreenshotMode::Window => {
+                let Some((a, b)) = window_selection(&output_data.windows, point) else {
+                    return false;
+                };
+
+                *selection = (output, a, b);
+                self.update_buffers();
+                return true;
+            }
+            ScreenshotMode::Output => {
+                let size = output_data.size;
+                *selection = (
+                    output,
+                    Point::from((0, 0)),
+                    Point::from((size.w - 1, size.h - 1)),
+                );
+                self.update_buffers();
+                return true;
+            }
+        }
+
         *button = Button::Down {
             touch_slot: slot,
             on_capture_button: false,
+            drawing: false,
             last_pos: (output.clone(), point),
             move_state: None,
         };
@@ -932,6 +1309,7 @@ impl ScreenshotUi {
         let Button::Down {
             touch_slot,
             on_capture_button,
+            drawing,
             ref last_pos,
             ref mut move_state,
             ..
@@ -955,6 +1333,22 @@ impl ScreenshotUi {
         let last_pos = last_pos.clone();
         *button = Button::Up;
 
+        if drawing {
+            // Drop shapes that were clicked without dragging. Text is still being typed.
+            let Self::Open {
+                annotations,
+                output_data,
+                ..
+            } = self
+            else {
+                unreachable!()
+            };
+            if let Some(output) = annotations.end_drag() {
+                invalidate_annotations(output_data, &output);
+            }
+            return Some(false);
+        }
+
         // Check if we released still on the capture button.
         if on_capture_button {
             let (output, point) = last_pos;
@@ -997,6 +1391,116 @@ impl ScreenshotUi {
     }
 }
 
+impl Annotations {
+    /// Starts drawing a new annotation with the tool.
+    fn start(&mut self, output: Output, tool: ScreenshotTool, point: Point<i32, Physical>) {
+        self.items.push((output, Annotation::new(tool, point)));
+        self.typing = tool == ScreenshotTool::Text;
+    }
+
+    /// Drags the annotation being drawn, returning its output.
+    fn drag_to(&mut self, point: Point<i32, Physical>) -> Option<&Output> {
+        let (output, annotation) = self.items.last_mut()?;
+        annotation.drag_to(point);
+        Some(output)
+    }
+
+    /// Finishes drawing, returning the output of the annotation.
+    ///
+    /// Shapes that were clicked without dragging are dropped. Text is still being typed.
+    fn end_drag(&mut self) -> Option<Output> {
+        let (output, annotation) = self.items.last()?;
+        let output = output.clone();
+        if !matches!(annotation, Annotation::Text(..)) && annotation.is_empty() {
+            self.items.pop();
+        }
+        Some(output)
+    }
+
+    /// Removes the last annotation, returning its output.
+    fn undo(&mut self) -> Option<Output> {
+        self.typing = false;
+        self.items.pop().map(|(output, _)| output)
+    }
+
+    /// Edits the text being typed, returning its output.
+    fn edit_text(&mut self, f: impl FnOnce(&mut String)) -> Option<&Output> {
+        if !self.typing {
+            return None;
+        }
+
+        match self.items.last_mut()? {
+            (output, Annotation::Text(_, text)) => {
+                f(text);
+                Some(output)
+            }
+            _ => None,
+        }
+    }
+
+    /// Stops typing, removing the text if it's empty, and returns its output.
+    fn finish_text(&mut self) -> Option<Output> {
+        if !self.typing {
+            return None;
+        }
+        self.typing = false;
+
+        let (output, annotation) = self.items.last()?;
+        let output = output.clone();
+        if annotation.is_empty() {
+            self.items.pop();
+        }
+        Some(output)
+    }
+
+    fn on_output<'a>(&'a self, output: &'a Output) -> impl Iterator<Item = &'a Annotation> + 'a {
+        self.items
+            .iter()
+            .filter(move |(o, _)| o == output)
+            .map(|(_, annotation)| annotation)
+    }
+
+    /// Returns whether the text being typed is on this output.
+    fn has_caret_on(&self, output: &Output) -> bool {
+        self.typing && self.items.last().is_some_and(|(o, _)| o == output)
+    }
+}
+
+impl Annotation {
+    fn new(tool: ScreenshotTool, point: Point<i32, Physical>) -> Self {
+        match tool {
+            ScreenshotTool::Select => unreachable!("select is not an annotation tool"),
+            ScreenshotTool::Rectangle => Self::Rectangle(point, point),
+            ScreenshotTool::Arrow => Self::Arrow(point, point),
+            ScreenshotTool::Freehand => Self::Freehand(vec![point]),
+            ScreenshotTool::Text => Self::Text(point, String::new()),
+            ScreenshotTool::Pixelate => Self::Pixelate(point, point),
+        }
+    }
+
+    fn drag_to(&mut self, point: Point<i32, Physical>) {
+        match self {
+            Self::Rectangle(_, b) | Self::Arrow(_, b) | Self::Pixelate(_, b) => *b = point,
+            Self::Freehand(points) => {
+                if points.last() != Some(&point) {
+                    points.push(point);
+                }
+            }
+            // Dragging with the text tool places the text.
+            Self::Text(loc, _) => *loc = point,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Rectangle(a, b) | Self::Pixelate(a, b) => a.x == b.x || a.y == b.y,
+            Self::Arrow(a, b) => a == b,
+            Self::Freehand(points) => points.len() < 2,
+            Self::Text(_, text) => text.trim().is_empty(),
+        }
+    }
+}
+
 impl OutputScreenshot {
     pub fn from_textures(
         renderer: &mut GlesRenderer,
@@ -1040,6 +1544,8 @@ impl OutputScreenshot {
             texture,
             buffer,
             pointer,
+            pixels: RefCell::new(None),
+            blocks: RefCell::new(HashMap::new()),
         }
     }
 }
@@ -1068,9 +1574,59 @@ fn action(raw: Keysym, mods: ModifiersState) -> Option<Action> {
         return Some(Action::ScreenshotTogglePointer);
     }
 
+    if !mods.ctrl && raw == Keysym::w {
+        return Some(Action::ScreenshotSetMode(ScreenshotMode::Window));
+    }
+    if !mods.ctrl && raw == Keysym::o {
+        return Some(Action::ScreenshotSetMode(ScreenshotMode::Output));
+    }
+
     None
 }
 
+fn annotation_action(raw: Keysym, mods: ModifiersState) -> Option<Action> {
+    if mods.alt || mods.shift {
+        return None;
+    }
+
+    if mods.ctrl {
+        return (raw == Keysym::z).then_some(Action::ScreenshotUndo);
+    }
+
+    let tool = match raw {
+        Keysym::r => ScreenshotTool::Rectangle,
+        Keysym::a => ScreenshotTool::Arrow,
+        Keysym::d => ScreenshotTool::Freehand,
+        Keysym::t => ScreenshotTool::Text,
+        Keysym::x => ScreenshotTool::Pixelate,
+        _ => return None,
+    };
+    Some(Action::ScreenshotSetTool(tool))
+}
+
+fn text_action(raw: Keysym, modified: Keysym, mods: ModifiersState) -> Option<Action> {
+    if matches!(raw, Keysym::Escape | Keysym::Return | Keysym::KP_Enter) {
+        return Some(Action::ScreenshotFinishText);
+    }
+
+    if raw == Keysym::BackSpace {
+        return Some(Action::ScreenshotDeleteText);
+    }
+
+    if mods.ctrl || mods.alt || mods.logo {
+        return None;
+    }
+
+    let c = modified.key_char().filter(|c| !c.is_control())?;
+    Some(Action::ScreenshotInsertText(c))
+}
+
+fn invalidate_annotations(output_data: &HashMap<Output, OutputData>, output: &Output) {
+    if let Some(data) = output_data.get(output) {
+        *data.annotation_overlays.borrow_mut() = Default::default();
+    }
+}
+
 pub fn rect_from_corner_points(
     a: Point<i32, Physical>,
     b: Point<i32, Physical>,
@@ -1204,3 +1760,458 @@ fn render_panel(
 
     Ok(buffer)
 }
+
+fn render_annotations(
+    renderer: &mut GlesRenderer,
+    output_data: &OutputData,
+    screenshot: &OutputScreenshot,
+    annotations: &[&Annotation],
+    caret: bool,
+    style: &ScreenshotAnnotations,
+) -> anyhow::Result<Option<PrimaryGpuTextureRenderElement>> {
+    let _span = tracy_client::span!("screenshot_ui::render_annotations");
+
+    let scale = output_data.scale;
+    let line_width: f64 = to_physical_precise_round(scale, style.width.0);
+    let block: i32 = max(1, to_physical_precise_round(scale, PIXELATE_BLOCK));
+
+    let mut font = FontDescription::from_string(ANNOTATION_FONT);
+    font.set_absolute_size(to_physical_precise_round(scale, font.size()));
+
+    let surface = ImageSurface::create(cairo::Format::ARgb32, 0, 0)?;
+    let cr = cairo::Context::new(&surface)?;
+    let layout = pangocairo::functions::create_layout(&cr);
+    layout.context().set_round_glyph_positions(false);
+    layout.set_font_description(Some(&font));
+
+    let text_of = |idx: usize, text: &str| {
+        if caret && idx + 1 == annotations.len() {
+            format!("{text}|")
+        } else {
+            text.to_owned()
+        }
+    };
+
+    // Compute the area covered by the annotations, leaving room for the line caps and the
+    // arrowheads.
+    let margin = (line_width * 4.).ceil() as i32;
+    let mut bounds: Option<Rectangle<i32, Physical>> = None;
+    for (idx, annotation) in annotations.iter().enumerate() {
+        let mut rect = match annotation {
+            Annotation::Rectangle(a, b) | Annotation::Arrow(a, b) | Annotation::Pixelate(a, b) => {
+                rect_from_corner_points(*a, *b)
+            }
+            Annotation::Freehand(points) => points
+                .iter()
+                .map(|p| rect_from_corner_points(*p, *p))
+                .reduce(|a, b| a.merge(b))
+                .unwrap_or_default(),
+            Annotation::Text(loc, text) => {
+                layout.set_text(&text_of(idx, text));
+                let (w, h) = layout.pixel_size();
+                Rectangle::new(*loc, Size::from((w, h)))
+            }
+        };
+        rect.loc -= Point::from((margin, margin));
+        rect.size += Size::from((margin * 2, margin * 2));
+        bounds = Some(bounds.map_or(rect, |bounds| bounds.merge(rect)));
+    }
+
+    let Some(bounds) = bounds.and_then(|b| b.intersection(Rectangle::from_size(output_data.size)))
+    else {
+        return Ok(None);
+    };
+    if bounds.size.is_empty() {
+        return Ok(None);
+    }
+
+    let surface = ImageSurface::create(cairo::Format::ARgb32, bounds.size.w, bounds.size.h)?;
+    let cr = cairo::Context::new(&surface)?;
+    cr.translate(-f64::from(bounds.loc.x), -f64::from(bounds.loc.y));
+    cr.set_line_width(line_width);
+    cr.set_line_cap(cairo::LineCap::Round);
+    cr.set_line_join(cairo::LineJoin::Round);
+
+    let [red, green, blue, alpha] = style.color.to_array_unpremul().map(f64::from);
+    let to_f64 = |p: &Point<i32, Physical>| (f64::from(p.x), f64::from(p.y));
+
+    for (idx, annotation) in annotations.iter().enumerate() {
+        cr.set_source_rgba(red, green, blue, alpha);
+
+        match annotation {
+            Annotation::Rectangle(a, b) => {
+                let rect = rect_from_corner_points(*a, *b);
+                cr.rectangle(
+                    f64::from(rect.loc.x),
+                    f64::from(rect.loc.y),
+                    f64::from(rect.size.w),
+                    f64::from(rect.size.h),
+                );
+                cr.stroke()?;
+            }
+            Annotation::Arrow(a, b) => {
+                let (x1, y1) = to_f64(a);
+                let (x2, y2) = to_f64(b);
+                cr.move_to(x1, y1);
+                cr.line_to(x2, y2);
+
+                let angle = (y2 - y1).atan2(x2 - x1);
+                let len = line_width * 4.;
+                for side in [-PI / 6., PI / 6.] {
+                    cr.move_to(x2, y2);
+                    cr.line_to(
+                        x2 - len * (angle + side).cos(),
+                        y2 - len * (angle + side).sin(),
+                    );
+                }
+                cr.stroke()?;
+            }
+            Annotation::Freehand(points) => {
+                let mut points = points.iter().map(to_f64);
+                if let Some((x, y)) = points.next() {
+                    cr.move_to(x, y);
+                    for (x, y) in points {
+                        cr.line_to(x, y);
+                    }
+                    cr.stroke()?;
+                }
+            }
+            Annotation::Text(loc, text) => {
+                let (x, y) = to_f64(loc);
+                cr.move_to(x, y);
+                let layout = pangocairo::functions::create_layout(&cr);
+                layout.context().set_round_glyph_positions(false);
+                layout.set_font_description(Some(&font));
+                layout.set_text(&text_of(idx, text));
+                pangocairo::functions::show_layout(&cr, &layout);
+                cr.new_path();
+            }
+            Annotation::Pixelate(a, b) => {
+                let Some(rect) = rect_from_corner_points(*a, *b)
+                    .intersection(Rectangle::from_size(output_data.size))
+                else {
+                    continue;
+                };
+                pixelate(renderer, &cr, screenshot, rect, block)?;
+            }
+        }
+    }
+    drop(cr);
+
+    let data = surface.take_data().unwrap();
+    let buffer = TextureBuffer::from_memory(
+        renderer,
+        &data,
+        Fourcc::Argb8888,
+        (bounds.size.w, bounds.size.h),
+        false,
+        scale,
+        Transform::Normal,
+        Vec::new(),
+    )?;
+
+    Ok(Some(PrimaryGpuTextureRenderElement(
+        TextureRenderElement::from_texture_buffer(
+            buffer,
+            bounds.loc.to_f64().to_logical(scale),
+            1.,
+            None,
+            None,
+            Kind::Unspecified,
+        ),
+    )))
+}
+
+/// Fills the rectangle with the average colors of the screenshot in blocks of the given size.
+///
+/// The blocks are aligned to the output, so their colors are cached in the screenshot and only
+/// computed once, and the screenshot itself is only read back from the GPU once.
+fn pixelate(
+    renderer: &mut GlesRenderer,
+    cr: &cairo::Context,
+    screenshot: &OutputScreenshot,
+    rect: Rectangle<i32, Physical>,
+    block: i32,
+) -> anyhow::Result<()> {
+    if rect.size.is_empty() {
+        return Ok(());
+    }
+
+    let size = screenshot.texture.size();
+    let size = Size::<i32, Physical>::from((size.w, size.h));
+
+    let mut pixels = screenshot.pixels.borrow_mut();
+    let pixels = match &mut *pixels {
+        Some(pixels) => pixels,
+        None => {
+            let _span = tracy_client::span!("screenshot_ui::pixelate read back");
+
+            let mapping = renderer
+                .copy_texture(
+                    &screenshot.texture,
+                    Rectangle::from_size(screenshot.texture.size()),
+                    Fourcc::Abgr8888,
+                )
+                .context("error copying texture")?;
+            let data = renderer
+                .map_texture(&mapping)
+                .context("error mapping texture")?;
+            pixels.insert(data.to_vec())
+        }
+    };
+
+    let mut blocks = screenshot.blocks.borrow_mut();
+    let first = Point::<i32, Physical>::from((rect.loc.x / block, rect.loc.y / block));
+    let last = rect.loc + rect.size.to_point() - Point::from((1, 1));
+    let last = Point::<i32, Physical>::from((last.x / block, last.y / block));
+    for by in first.y..=last.y {
+        for bx in first.x..=last.x {
+            let block_rect = Rectangle::new(
+                Point::from((bx * block, by * block)),
+                Size::from((block, block)),
+            );
+            let Some(block_rect) = block_rect.intersection(Rectangle::from_size(size)) else {
+                continue;
+            };
+            let Some(visible) = block_rect.intersection(rect) else {
+                continue;
+            };
+
+            let [r, g, b] = *blocks
+                .entry((bx, by))
+                .or_insert_with(|| block_average(pixels, size.w, block_rect));
+            cr.set_source_rgb(r, g, b);
+            cr.rectangle(
+                f64::from(visible.loc.x),
+                f64::from(visible.loc.y),
+                f64::from(visible.size.w),
+                f64::from(visible.size.h),
+            );
+            cr.fill()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Computes the average color of the rectangle in RGBA8 pixels of the given width.
+fn block_average(pixels: &[u8], width: i32, rect: Rectangle<i32, Physical>) -> [f64; 3] {
+    let stride = width as usize * 4;
+
+    let mut sum = [0u64; 3];
+    for y in rect.loc.y..rect.loc.y + rect.size.h {
+        let row = y as usize * stride;
+        for x in rect.loc.x..rect.loc.x + rect.size.w {
+            let px = &pixels[row + x as usize * 4..][..3];
+            for (sum, c) in zip(&mut sum, px) {
+                *sum += u64::from(*c);
+            }
+        }
+    }
+
+    let count = f64::from(max(1, rect.size.w * rect.size.h)) * 255.;
+    sum.map(|c| c as f64 / count)
+}
+
+/// Returns the selection corners snapped to the topmost window under the point.
+///
+/// The windows are ordered topmost first.
+fn window_selection(
+    windows: &[Rectangle<i32, Physical>],
+    point: Point<i32, Physical>,
+) -> Option<(Point<i32, Physical>, Point<i32, Physical>)> {
+    let rect = windows.iter().find(|rect| rect.contains(point))?;
+    Some((rect.loc, rect.loc + rect.size - Size::from((1, 1))))
+}
+
+#[cfg(test)]
+mod tests {
+    use smithay::output::{PhysicalProperties, Subpixel};
+
+    use super::*;
+
+    fn output(name: &str) -> Output {
+        Output::new(
+            name.to_owned(),
+            PhysicalProperties {
+                size: (0, 0).into(),
+                subpixel: Subpixel::Unknown,
+                make: String::new(),
+                model: String::new(),
+            },
+        )
+    }
+
+    fn p(x: i32, y: i32) -> Point<i32, Physical> {
+        Point::from((x, y))
+    }
+
+    #[test]
+    fn click_without_drag_drops_shape() {
+        let out = output("a");
+        let mut annotations = Annotations::default();
+
+        annotations.start(out.clone(), ScreenshotTool::Rectangle, p(10, 10));
+        assert_eq!(annotations.end_drag(), Some(out.clone()));
+        assert!(annotations.items.is_empty());
+
+        annotations.start(out.clone(), ScreenshotTool::Arrow, p(10, 10));
+        annotations.drag_to(p(20, 30));
+        annotations.end_drag();
+        assert_eq!(annotations.items.len(), 1);
+    }
+
+    #[test]
+    fn undo_removes_last_annotation() {
+        let a = output("a");
+        let b = output("b");
+        let mut annotations = Annotations::default();
+
+        annotations.start(a.clone(), ScreenshotTool::Rectangle, p(0, 0));
+        annotations.drag_to(p(5, 5));
+        annotations.end_drag();
+        annotations.start(b.clone(), ScreenshotTool::Freehand, p(0, 0));
+        annotations.drag_to(p(5, 5));
+        annotations.end_drag();
+
+        assert_eq!(annotations.undo(), Some(b.clone()));
+        assert_eq!(annotations.on_output(&b).count(), 0);
+        assert_eq!(
+            annotations.on_output(&a).collect::<Vec<_>>(),
+            [&Annotation::Rectangle(p(0, 0), p(5, 5))]
+        );
+
+        assert_eq!(annotations.undo(), Some(a));
+        assert_eq!(annotations.undo(), None);
+    }
+
+    #[test]
+    fn undo_stops_typing() {
+        let out = output("a");
+        let mut annotations = Annotations::default();
+
+        annotations.start(out.clone(), ScreenshotTool::Text, p(0, 0));
+        annotations.end_drag();
+        annotations.edit_text(|text| text.push('a'));
+        assert!(annotations.typing);
+
+        assert_eq!(annotations.undo(), Some(out));
+        assert!(!annotations.typing);
+        assert!(annotations.edit_text(|text| text.push('b')).is_none());
+        assert!(annotations.items.is_empty());
+    }
+
+    #[test]
+    fn text_editing() {
+        let a = output("a");
+        let b = output("b");
+        let mut annotations = Annotations::default();
+
+        annotations.start(a.clone(), ScreenshotTool::Text, p(0, 0));
+        // Dragging moves the text, and releasing doesn't drop the empty text.
+        annotations.drag_to(p(3, 4));
+        annotations.end_drag();
+        assert!(annotations.typing);
+        assert!(annotations.has_caret_on(&a));
+        assert!(!annotations.has_caret_on(&b));
+
+        annotations.edit_text(|text| text.push_str("hi!"));
+        annotations.edit_text(|text| {
+            text.pop();
+        });
+        assert_eq!(
+            annotations.on_output(&a).collect::<Vec<_>>(),
+            [&Annotation::Text(p(3, 4), String::from("hi"))]
+        );
+
+        assert_eq!(annotations.finish_text(), Some(a.clone()));
+        assert!(!annotations.typing);
+        assert!(!annotations.has_caret_on(&a));
+        assert_eq!(annotations.items.len(), 1);
+
+        // Finishing again does nothing.
+        assert_eq!(annotations.finish_text(), None);
+        assert_eq!(annotations.items.len(), 1);
+    }
+
+    #[test]
+    fn finishing_empty_text_removes_it() {
+        let out = output("a");
+        let mut annotations = Annotations::default();
+
+        annotations.start(out.clone(), ScreenshotTool::Text, p(0, 0));
+        annotations.edit_text(|text| text.push_str("  "));
+        assert_eq!(annotations.finish_text(), Some(out));
+        assert!(annotations.items.is_empty());
+    }
+
+    #[test]
+    fn edit_text_needs_typing() {
+        let out = output("a");
+        let mut annotations = Annotations::default();
+
+        annotations.start(out, ScreenshotTool::Rectangle, p(0, 0));
+        annotations.drag_to(p(5, 5));
+        annotations.end_drag();
+        assert!(annotations.edit_text(|text| text.push('a')).is_none());
+    }
+
+    #[test]
+    fn window_selection_picks_topmost() {
+        let top = Rectangle::new(p(50, 50), Size::from((100, 100)));
+        let bottom = Rectangle::new(p(0, 0), Size::from((120, 80)));
+        let windows = [top, bottom];
+
+        // Inside both windows.
+        assert_eq!(
+            window_selection(&windows, p(60, 60)),
+            Some((p(50, 50), p(149, 149)))
+        );
+        // Only inside the bottom window.
+        assert_eq!(
+            window_selection(&windows, p(10, 10)),
+            Some((p(0, 0), p(119, 79)))
+        );
+        // Only inside the top window, below the bottom one.
+        assert_eq!(
+            window_selection(&windows, p(60, 100)),
+            Some((p(50, 50), p(149, 149)))
+        );
+        // Outside of all windows.
+        assert_eq!(window_selection(&windows, p(200, 10)), None);
+        assert_eq!(window_selection(&[], p(0, 0)), None);
+    }
+
+    #[test]
+    fn window_selection_edges() {
+        let windows = [Rectangle::new(p(10, 10), Size::from((5, 5)))];
+
+        // The selection includes the last pixel of the window.
+        assert_eq!(
+            window_selection(&windows, p(10, 10)),
+            Some((p(10, 10), p(14, 14)))
+        );
+        assert_eq!(
+            window_selection(&windows, p(14, 14)),
+            Some((p(10, 10), p(14, 14)))
+        );
+        // The rectangle end is exclusive.
+        assert_eq!(window_selection(&windows, p(15, 10)), None);
+        assert_eq!(window_selection(&windows, p(10, 15)), None);
+    }
+
+    #[test]
+    fn block_average_of_rect() {
+        // 2x2 image: red, green / blue, white.
+        let pixels = [
+            255, 0, 0, 255, 0, 255, 0, 255, //
+            0, 0, 255, 255, 255, 255, 255, 255,
+        ];
+
+        let all = block_average(&pixels, 2, Rectangle::from_size(Size::from((2, 2))));
+        assert_eq!(all, [0.5, 0.5, 0.5]);
+
+        let right = block_average(&pixels, 2, Rectangle::new(p(1, 0), Size::from((1, 2))));
+        assert_eq!(right, [0.5, 1., 0.5]);
+    }
+}
@@ -0,0 +1,296 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use niri_ipc::WindowHintAction;
+use ordered_float::NotNan;
+use pangocairo::cairo::{self, ImageSurface};
+use pangocairo::pango::FontDescription;
+use smithay::backend::renderer::element::Kind;
+use smithay::desktop::Window;
+use smithay::output::Output;
+use smithay::reexports::gbm::Format as Fourcc;
+use smithay::utils::{Rectangle, Transform};
+
+use crate::layout::Layout;
+use crate::render_helpers::memory::MemoryBuffer;
+use crate::render_helpers::primary_gpu_texture::PrimaryGpuTextureRenderElement;
+use crate::render_helpers::renderer::NiriRenderer;
+use crate::render_helpers::texture::{TextureBuffer, TextureRenderElement};
+use crate::utils::{output_size, to_physical_precise_round};
+use crate::window::Mapped;
+
+/// Letters used for the hint labels, the easiest to reach first.
+const ALPHABET: &str = "asdfghjklqwertyuiopzxcvbnm";
+const PADDING: i32 = 6;
+const FONT: &str = "monospace bold 20px";
+const BORDER: i32 = 2;
+
+/// Letter labels on the visible windows for choosing one with the keyboard.
+pub struct WindowHints {
+    state: Option<OpenState>,
+    /// Rendered labels keyed by label, number of typed letters and scale.
+    buffers: RefCell<HashMap<(String, usize, NotNan<f64>), Option<MemoryBuffer>>>,
+}
+
+struct OpenState {
+    /// What to do with the chosen window.
+    action: WindowHintAction,
+    hints: Vec<Hint>,
+    /// Letters typed so far.
+    typed: String,
+}
+
+struct Hint {
+    label: String,
+    window: Window,
+}
+
+impl WindowHints {
+    pub fn new() -> Self {
+        Self {
+            state: None,
+            buffers: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Opens the hints on the given windows.
+    ///
+    /// Returns `false` if there are no windows to show the hints on.
+    pub fn open(&mut self, action: WindowHintAction, windows: Vec<Window>) -> bool {
+        if windows.is_empty() {
+            return false;
+        }
+
+        let labels = labels(windows.len());
+        let hints = labels
+            .into_iter()
+            .zip(windows)
+            .map(|(label, window)| Hint { label, window })
+            .collect();
+
+        self.state = Some(OpenState {
+            action,
+            hints,
+            typed: String::new(),
+        });
+        true
+    }
+
+    pub fn close(&mut self) -> bool {
+        if self.state.take().is_some() {
+            self.buffers.borrow_mut().clear();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state.is_some()
+    }
+
+    /// Handles a typed letter.
+    ///
+    /// Returns the chosen window and what to do with it once a label is typed in full, closing
+    /// the hints. Letters that don't continue any label are ignored.
+    pub fn input(&mut self, c: char) -> Option<(Window, WindowHintAction)> {
+        let state = self.state.as_mut()?;
+
+        let mut typed = state.typed.clone();
+        typed.push(c.to_ascii_lowercase());
+
+        if !state
+            .hints
+            .iter()
+            .any(|hint| hint.label.starts_with(&typed))
+        {
+            return None;
+        }
+
+        let chosen = state.hints.iter().find(|hint| hint.label == typed);
+        if let Some(hint) = chosen {
+            let rv = (hint.window.clone(), state.action);
+            self.close();
+            return Some(rv);
+        }
+
+        state.typed = typed;
+        None
+    }
+
+    /// Removes the last typed letter.
+    pub fn backspace(&mut self) -> bool {
+        let Some(state) = &mut self.state else {
+            return false;
+        };
+        state.typed.pop().is_some()
+    }
+
+    pub fn render<R: NiriRenderer>(
+        &self,
+        renderer: &mut R,
+        output: &Output,
+        layout: &Layout<Mapped>,
+    ) -> Vec<PrimaryGpuTextureRenderElement> {
+        let _span = tracy_client::span!("WindowHints::render");
+
+        let mut elements = Vec::new();
+
+        let Some(state) = &self.state else {
+            return elements;
+        };
+        let Some(mon) = layout.monitor_for_output(output) else {
+            return elements;
+        };
+
+        let scale = output.current_scale().fractional_scale();
+        let output_rect = Rectangle::from_size(output_size(output));
+
+        let mut buffers = self.buffers.borrow_mut();
+        for (mapped, geo) in mon.windows_with_visual_geometries() {
+            let Some(hint) = state.hints.iter().find(|hint| hint.window == mapped.window) else {
+                continue;
+            };
+            if !hint.label.starts_with(&state.typed) {
+                continue;
+            }
+
+            // Center the label on the visible part of the window.
+            let Some(geo) = geo.intersection(output_rect) else {
+                continue;
+            };
+
+            let typed = state.typed.len();
+            let buffer = buffers
+                .entry((hint.label.clone(), typed, NotNan::new(scale).unwrap()))
+                .or_insert_with(|| render(&hint.label, typed, scale).ok());
+            let Some(buffer) = buffer else {
+                continue;
+            };
+
+            let size = buffer.logical_size();
+            let location = geo.loc + (geo.size.to_point() - size.to_point()).downscale(2.);
+            let location = location.to_physical_precise_round(scale).to_logical(scale);
+
+            let Ok(buffer) = TextureBuffer::from_memory_buffer(renderer.as_gles_renderer(), buffer)
+            else {
+                continue;
+            };
+
+            let elem = TextureRenderElement::from_texture_buffer(
+                buffer,
+                location,
+                1.,
+                None,
+                None,
+                Kind::Unspecified,
+            );
+            elements.push(PrimaryGpuTextureRenderElement(elem));
+        }
+
+        elements
+    }
+}
+
+impl Default for WindowHints {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Generates `count` labels of equal length, so that no label is a prefix of another.
+fn labels(count: usize) -> Vec<String> {
+    let alphabet: Vec<char> = ALPHABET.chars().collect();
+
+    let mut len = 1;
+    while alphabet.len().pow(len) < count {
+        len += 1;
+    }
+
+    (0..count)
+        .map(|mut idx| {
+            let mut label = vec![' '; len as usize];
+            for c in label.iter_mut().rev() {
+                *c = alphabet[idx % alphabet.len()];
+                idx /= alphabet.len();
+            }
+            label.into_iter().collect()
+        })
+        .collect()
+}
+
+fn render(label: &str, typed: usize, scale: f64) -> anyhow::Result<MemoryBuffer> {
+    let _span = tracy_client::span!("window_hints::render");
+
+    let padding: i32 = to_physical_precise_round(scale, PADDING);
+
+    let mut font = FontDescription::from_string(FONT);
+    font.set_absolute_size(to_physical_precise_round(scale, font.size()));
+
+    // Dim the letters that were already typed.
+    let (typed, rest) = label.split_at(typed);
+    let markup = format!("<span foreground='#808080'>{typed}</span>{rest}");
+
+    let surface = ImageSurface::create(cairo::Format::ARgb32, 0, 0)?;
+    let cr = cairo::Context::new(&surface)?;
+    let layout = pangocairo::functions::create_layout(&cr);
+    layout.context().set_round_glyph_positions(false);
+    layout.set_font_description(Some(&font));
+    layout.set_markup(&markup);
+
+    let (mut width, mut height) = layout.pixel_size();
+    width += padding * 2;
+    height += padding * 2;
+
+    let surface = ImageSurface::create(cairo::Format::ARgb32, width, height)?;
+    let cr = cairo::Context::new(&surface)?;
+    cr.set_source_rgb(0.1, 0.1, 0.1);
+    cr.paint()?;
+
+    cr.move_to(padding.into(), padding.into());
+    let layout = pangocairo::functions::create_layout(&cr);
+    layout.context().set_round_glyph_positions(false);
+    layout.set_font_description(Some(&font));
+    layout.set_markup(&markup);
+
+    cr.set_source_rgb(1., 1., 1.);
+    pangocairo::functions::show_layout(&cr, &layout);
+
+    cr.move_to(0., 0.);
+    cr.line_to(width.into(), 0.);
+    cr.line_to(width.into(), height.into());
+    cr.line_to(0., height.into());
+    cr.line_to(0., 0.);
+    cr.set_source_rgb(0.5, 0.8, 1.0);
+    // Keep the border width even to avoid blurry edges.
+    cr.set_line_width((f64::from(BORDER) / 2. * scale).round() * 2.);
+    cr.stroke()?;
+    drop(cr);
+
+    let data = surface.take_data().unwrap();
+    let buffer = MemoryBuffer::new(
+        data.to_vec(),
+        Fourcc::Argb8888,
+        (width, height),
+        scale,
+        Transform::Normal,
+    );
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_are_prefix_free() {
+        assert_eq!(labels(3), ["a", "s", "d"]);
+
+        let labels = labels(30);
+        assert_eq!(labels[0], "aa");
+        assert_eq!(labels[1], "as");
+        assert_eq!(labels[26], "sa");
+        assert!(labels.iter().all(|label| label.len() == 2));
+    }
+}
@@ -0,0 +1,407 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use anyhow::Context as _;
+use niri_config::Modifiers;
+use ordered_float::NotNan;
+use pangocairo::cairo::{self, ImageSurface};
+use pangocairo::pango::{Alignment, EllipsizeMode, FontDescription};
+use smithay::backend::allocator::Fourcc;
+use smithay::backend::renderer::element::utils::RescaleRenderElement;
+use smithay::backend::renderer::element::Kind;
+use smithay::backend::renderer::gles::{GlesRenderer, GlesTexture};
+use smithay::desktop::Window;
+use smithay::output::Output;
+use smithay::utils::{Logical, Point, Scale, Size, Transform};
+
+use crate::layout::{Layout, LayoutElement as _};
+use crate::niri_render_elements;
+use crate::render_helpers::memory::MemoryBuffer;
+use crate::render_helpers::offscreen::{OffscreenBuffer, OffscreenRenderElement};
+use crate::render_helpers::primary_gpu_texture::PrimaryGpuTextureRenderElement;
+use crate::render_helpers::renderer::NiriRenderer;
+use crate::render_helpers::solid_color::{SolidColorBuffer, SolidColorRenderElement};
+use crate::render_helpers::texture::{TextureBuffer, TextureRenderElement};
+use crate::render_helpers::RenderTarget;
+use crate::utils::{output_size, to_physical_precise_round, with_toplevel_role};
+use crate::window::Mapped;
+
+const THUMBNAIL_WIDTH: f64 = 240.;
+const THUMBNAIL_HEIGHT: f64 = 160.;
+/// Thumbnails don't shrink below this width; more windows wrap into further rows instead.
+const MIN_THUMBNAIL_WIDTH: f64 = 120.;
+const PADDING: f64 = 16.;
+const TITLE_GAP: f64 = 8.;
+const FONT: &str = "sans 14px";
+const BACKGROUND_COLOR: [f32; 4] = [0.1, 0.1, 0.1, 1.];
+const SELECTION_COLOR: [f32; 4] = [0.3, 0.3, 0.3, 1.];
+
+/// Alt-Tab style window switcher.
+pub struct WindowSwitcher {
+    state: Option<OpenState>,
+    /// Window thumbnails, re-rendered only when their window commits.
+    thumbnails: RefCell<HashMap<Window, Thumbnail>>,
+    /// Rendered window titles keyed by title, scale and width.
+    titles: RefCell<HashMap<(String, NotNan<f64>, i32), Option<TextureBuffer<GlesTexture>>>>,
+    background: RefCell<SolidColorBuffer>,
+    selection: RefCell<SolidColorBuffer>,
+}
+
+struct OpenState {
+    /// Windows in most-recently-used order.
+    windows: Vec<Window>,
+    /// Index of the currently selected window.
+    selected: usize,
+    /// Output where the switcher is shown.
+    output: Output,
+    /// Modifiers that keep the switcher open while held.
+    trigger_mods: Modifiers,
+}
+
+#[derive(Debug, Default)]
+struct Thumbnail {
+    /// Buffer that the downscaled window renders into.
+    buffer: OffscreenBuffer,
+    /// Last rendered thumbnail along with the scale and the maximum size it was rendered for.
+    ///
+    /// Reset when the window commits.
+    rendered: Option<(OffscreenRenderElement, NotNan<f64>, Size<f64, Logical>)>,
+}
+
+niri_render_elements! {
+    WindowSwitcherRenderElement => {
+        Texture = PrimaryGpuTextureRenderElement,
+        Offscreen = OffscreenRenderElement,
+        SolidColor = SolidColorRenderElement,
+    }
+}
+
+impl WindowSwitcher {
+    pub fn new() -> Self {
+        Self {
+            state: None,
+            thumbnails: RefCell::new(HashMap::new()),
+            titles: RefCell::new(HashMap::new()),
+            background: RefCell::new(SolidColorBuffer::new((0., 0.), BACKGROUND_COLOR)),
+            selection: RefCell::new(SolidColorBuffer::new((0., 0.), SELECTION_COLOR)),
+        }
+    }
+
+    pub fn open(
+        &mut self,
+        windows: Vec<Window>,
+        selected: usize,
+        output: Output,
+        trigger_mods: Modifiers,
+    ) {
+        assert!(selected < windows.len());
+
+        self.state = Some(OpenState {
+            windows,
+            selected,
+            output,
+            trigger_mods,
+        });
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state.is_some()
+    }
+
+    /// Returns whether the switcher should stay open with the given modifiers held.
+    pub fn is_trigger_held(&self, mods: Modifiers) -> bool {
+        self.state
+            .as_ref()
+            .is_some_and(|state| mods.intersects(state.trigger_mods))
+    }
+
+    /// Moves the selection forward or backward, wrapping around.
+    pub fn advance(&mut self, forward: bool) {
+        let Some(state) = &mut self.state else {
+            return;
+        };
+
+        let len = state.windows.len();
+        state.selected = if forward {
+            (state.selected + 1) % len
+        } else {
+            (state.selected + len - 1) % len
+        };
+    }
+
+    /// Closes the switcher without selecting anything.
+    pub fn cancel(&mut self) -> bool {
+        self.close().is_some()
+    }
+
+    /// Closes the switcher, returning the selected window.
+    pub fn close(&mut self) -> Option<Window> {
+        let state = self.state.take()?;
+        self.thumbnails.get_mut().clear();
+        self.titles.get_mut().clear();
+        Some(state.windows[state.selected].clone())
+    }
+
+    /// Invalidates the thumbnail of a window that committed.
+    ///
+    /// Returns the output that the switcher is shown on if it needs to redraw.
+    pub fn window_committed(&mut self, window: &Window) -> Option<&Output> {
+        let state = self.state.as_ref()?;
+        let thumbnail = self.thumbnails.get_mut().get_mut(window)?;
+        thumbnail.rendered = None;
+        Some(&state.output)
+    }
+
+    pub fn render<R: NiriRenderer>(
+        &self,
+        renderer: &mut R,
+        output: &Output,
+        layout: &Layout<Mapped>,
+    ) -> Vec<WindowSwitcherRenderElement> {
+        let _span = tracy_client::span!("WindowSwitcher::render");
+
+        let mut elements = Vec::new();
+
+        let Some(state) = &self.state else {
+            return elements;
+        };
+        if state.output != *output {
+            return elements;
+        }
+
+        let scale = output.current_scale().fractional_scale();
+        let output_size = output_size(output);
+        let renderer = renderer.as_gles_renderer();
+
+        let mapped = state
+            .windows
+            .iter()
+            .filter_map(|window| {
+                let toplevel = window.toplevel()?;
+                let (mapped, _) = layout.find_window_and_output(toplevel.wl_surface())?;
+                Some(mapped)
+            })
+            .collect::<Vec<_>>();
+        if mapped.is_empty() {
+            return elements;
+        }
+
+        // Fit as many cells into a row as possible without going below the minimum width.
+        let count = mapped.len();
+        let max_columns = (output_size.w - PADDING) / (MIN_THUMBNAIL_WIDTH + PADDING);
+        let columns = count.min(max_columns.max(1.) as usize);
+        let rows = count.div_ceil(columns);
+
+        let max_width = (output_size.w - PADDING * (columns as f64 + 1.)) / columns as f64;
+        let cell_width = f64::max(MIN_THUMBNAIL_WIDTH, f64::min(THUMBNAIL_WIDTH, max_width));
+        let cell_width = cell_width.floor();
+        let thumbnail_height = (cell_width * THUMBNAIL_HEIGHT / THUMBNAIL_WIDTH).floor();
+        let thumbnail_size = Size::from((cell_width, thumbnail_height));
+
+        let mut thumbnails = self.thumbnails.borrow_mut();
+        let mut titles = self.titles.borrow_mut();
+
+        let mut cells = Vec::new();
+        let mut title_height: f64 = 0.;
+        for mapped in mapped {
+            let thumbnail = thumbnails.entry(mapped.window.clone()).or_default();
+            let thumbnail = render_thumbnail(renderer, thumbnail, mapped, scale, thumbnail_size)
+                .map_err(|err| warn!("error rendering window thumbnail: {err:?}"))
+                .ok();
+
+            let title = with_toplevel_role(mapped.toplevel(), |role| role.title.clone());
+            let title = title.unwrap_or_default();
+            let title = titles
+                .entry((title, NotNan::new(scale).unwrap(), cell_width as i32))
+                .or_insert_with_key(|(title, _, width)| {
+                    let buffer = render_title(title, *width, scale).ok()?;
+                    TextureBuffer::from_memory_buffer(renderer, &buffer).ok()
+                })
+                .clone();
+            if let Some(title) = &title {
+                title_height = f64::max(title_height, title.logical_size().h);
+            }
+
+            cells.push((&mapped.window, thumbnail, title));
+        }
+
+        let cell_height = thumbnail_height + TITLE_GAP + title_height;
+        let panel_size = Size::from((
+            cell_width * columns as f64 + PADDING * (columns as f64 + 1.),
+            cell_height * rows as f64 + PADDING * (rows as f64 + 1.),
+        ));
+        let panel_loc = (output_size.to_point() - panel_size.to_point()).downscale(2.);
+        let mut panel_loc = panel_loc.to_physical_precise_round(scale).to_logical(scale);
+        panel_loc.x = f64::max(0., panel_loc.x);
+        panel_loc.y = f64::max(0., panel_loc.y);
+
+        let selected = &state.windows[state.selected];
+        let mut selection_loc = None;
+
+        for (idx, (window, thumbnail, title)) in cells.into_iter().enumerate() {
+            let column = (idx % columns) as f64;
+            let row = (idx / columns) as f64;
+            let cell_loc = panel_loc
+                + Point::from((
+                    PADDING + (cell_width + PADDING) * column,
+                    PADDING + (cell_height + PADDING) * row,
+                ));
+
+            if let Some(elem) = thumbnail {
+                let size = elem.logical_size();
+                let loc = cell_loc + (thumbnail_size.to_point() - size.to_point()).downscale(2.);
+                let loc = loc.to_physical_precise_round(scale).to_logical(scale);
+
+                let offset = elem.offset();
+                elements.push(elem.with_offset(loc + offset).into());
+            }
+
+            if let Some(buffer) = title {
+                let size = buffer.logical_size();
+                let loc = cell_loc
+                    + Point::from(((cell_width - size.w) / 2., thumbnail_height + TITLE_GAP));
+                let loc = loc.to_physical_precise_round(scale).to_logical(scale);
+
+                let elem = TextureRenderElement::from_texture_buffer(
+                    buffer,
+                    loc,
+                    1.,
+                    None,
+                    None,
+                    Kind::Unspecified,
+                );
+                elements.push(PrimaryGpuTextureRenderElement(elem).into());
+            }
+
+            if window == selected {
+                selection_loc = Some(cell_loc);
+            }
+        }
+
+        if let Some(loc) = selection_loc {
+            let mut selection = self.selection.borrow_mut();
+            selection.resize((cell_width + PADDING, cell_height + PADDING));
+            let loc = loc - Point::from((PADDING, PADDING)).downscale(2.);
+            elements.push(
+                SolidColorRenderElement::from_buffer(&selection, loc, 1., Kind::Unspecified).into(),
+            );
+        }
+
+        let mut background = self.background.borrow_mut();
+        background.resize(panel_size);
+        elements.push(
+            SolidColorRenderElement::from_buffer(&background, panel_loc, 1., Kind::Unspecified)
+                .into(),
+        );
+
+        elements
+    }
+}
+
+/// Returns the modifiers that keep the switcher open, given the ones held when opening it.
+pub fn trigger_mods(mods: Modifiers) -> Modifiers {
+    // Shift is commonly used to go backwards, so it shouldn't be required to stay held.
+    if mods == Modifiers::SHIFT {
+        mods
+    } else {
+        mods - Modifiers::SHIFT
+    }
+}
+
+impl Default for WindowSwitcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the window thumbnail downscaled to fit the size, re-rendering it if needed.
+fn render_thumbnail(
+    renderer: &mut GlesRenderer,
+    thumbnail: &mut Thumbnail,
+    mapped: &Mapped,
+    scale: f64,
+    max_size: Size<f64, Logical>,
+) -> anyhow::Result<OffscreenRenderElement> {
+    let scale = NotNan::new(scale).unwrap();
+    if let Some((elem, rendered_scale, rendered_size)) = &thumbnail.rendered {
+        if *rendered_scale == scale && *rendered_size == max_size {
+            return Ok(elem.clone());
+        }
+    }
+
+    let _span = tracy_client::span!("window_switcher::render_thumbnail");
+
+    // Drop the old thumbnail so that the buffer can reuse its texture.
+    thumbnail.rendered = None;
+
+    let size = mapped.size().to_f64();
+    let factor = f64::min(max_size.w / size.w, max_size.h / size.h);
+    let factor = f64::min(factor, 1.);
+
+    let elements = mapped
+        .render_normal(
+            renderer,
+            Point::from((0., 0.)),
+            Scale::from(*scale),
+            1.,
+            RenderTarget::Output,
+        )
+        .into_iter()
+        .map(|elem| RescaleRenderElement::from_element(elem, Point::from((0, 0)), factor))
+        .collect::<Vec<_>>();
+
+    let (elem, _sync_point, _data) = thumbnail
+        .buffer
+        .render(renderer, Scale::from(*scale), &elements)
+        .context("error rendering to offscreen")?;
+
+    thumbnail.rendered = Some((elem.clone(), scale, max_size));
+    Ok(elem)
+}
+
+fn render_title(title: &str, width: i32, scale: f64) -> anyhow::Result<MemoryBuffer> {
+    let _span = tracy_client::span!("window_switcher::render_title");
+
+    let width: i32 = to_physical_precise_round(scale, width);
+
+    let mut font = FontDescription::from_string(FONT);
+    font.set_absolute_size(to_physical_precise_round(scale, font.size()));
+
+    let surface = ImageSurface::create(cairo::Format::ARgb32, 0, 0)?;
+    let cr = cairo::Context::new(&surface)?;
+    let layout = pangocairo::functions::create_layout(&cr);
+    layout.context().set_round_glyph_positions(false);
+    layout.set_font_description(Some(&font));
+    layout.set_alignment(Alignment::Center);
+    layout.set_ellipsize(EllipsizeMode::End);
+    layout.set_width(width * pangocairo::pango::SCALE);
+    layout.set_text(title);
+
+    let (text_width, height) = layout.pixel_size();
+    let text_width = i32::max(1, i32::min(text_width, width));
+    let height = i32::max(1, height);
+
+    let surface = ImageSurface::create(cairo::Format::ARgb32, text_width, height)?;
+    let cr = cairo::Context::new(&surface)?;
+    let layout = pangocairo::functions::create_layout(&cr);
+    layout.context().set_round_glyph_positions(false);
+    layout.set_font_description(Some(&font));
+    layout.set_ellipsize(EllipsizeMode::End);
+    layout.set_width(text_width * pangocairo::pango::SCALE);
+    layout.set_text(title);
+
+    cr.set_source_rgb(1., 1., 1.);
+    pangocairo::functions::show_layout(&cr, &layout);
+    drop(cr);
+
+    let data = surface.take_data().unwrap();
+    let buffer = MemoryBuffer::new(
+        data.to_vec(),
+        Fourcc::Argb8888,
+        (text_width, height),
+        scale,
+        Transform::Normal,
+    );
+
+    Ok(buffer)
+}
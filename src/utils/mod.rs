@@ -216,7 +216,16 @@ pub fn make_screenshot_path(config: &Config) -> anyhow::Result<Option<PathBuf>>
         return Ok(None);
     };
 
-    let format = CString::new(path.clone()).context("path must not contain nul bytes")?;
+    make_time_path(path).map(Some)
+}
+
+pub fn make_recording_path(config: &Config) -> anyhow::Result<PathBuf> {
+    make_time_path(&config.recording.path)
+}
+
+/// Formats the path with strftime() and expands the `~`.
+fn make_time_path(path: &str) -> anyhow::Result<PathBuf> {
+    let format = CString::new(path).context("path must not contain nul bytes")?;
 
     let mut buf = [0u8; 2048];
     let mut path;
@@ -237,7 +246,7 @@ pub fn make_screenshot_path(config: &Config) -> anyhow::Result<Option<PathBuf>>
         path = expanded;
     }
 
-    Ok(Some(path))
+    Ok(path)
 }
 
 pub fn write_png_rgba8(
@@ -1,10 +1,11 @@
 use std::cell::{Cell, Ref, RefCell};
+use std::sync::Arc;
 use std::time::Duration;
 
 use niri_config::{Color, CornerRadius, GradientInterpolation, WindowRule};
 use smithay::backend::renderer::element::surface::render_elements_from_surface_tree;
 use smithay::backend::renderer::element::Kind;
-use smithay::backend::renderer::gles::GlesRenderer;
+use smithay::backend::renderer::gles::{GlesRenderer, GlesTexture};
 use smithay::desktop::space::SpaceElement as _;
 use smithay::desktop::{PopupManager, Window};
 use smithay::output::{self, Output};
@@ -25,12 +26,16 @@ use crate::layout::{
     LayoutElementRenderSnapshot,
 };
 use crate::niri_render_elements;
+use crate::protocols::xdg_toplevel_icon::{toplevel_icon, ToplevelIcon};
 use crate::render_helpers::border::BorderRenderElement;
+use crate::render_helpers::color_transform::color_transform_surface_elements;
 use crate::render_helpers::offscreen::OffscreenData;
+use crate::render_helpers::primary_gpu_texture::PrimaryGpuTextureRenderElement;
 use crate::render_helpers::renderer::NiriRenderer;
 use crate::render_helpers::snapshot::RenderSnapshot;
 use crate::render_helpers::solid_color::{SolidColorBuffer, SolidColorRenderElement};
 use crate::render_helpers::surface::render_snapshot_from_surface_tree;
+use crate::render_helpers::texture::{TextureBuffer, TextureRenderElement};
 use crate::render_helpers::{BakedBuffer, RenderTarget, SplitElements};
 use crate::utils::id::IdCounter;
 use crate::utils::transaction::Transaction;
@@ -89,15 +94,38 @@ pub struct Mapped {
     /// Whether this window is floating.
     is_floating: bool,
 
+    /// Whether this window is pinned to all workspaces of its output.
+    is_pinned: bool,
+
+    /// Whether this window is kept above the other floating windows.
+    is_kept_above: bool,
+
+    /// Whether this window is kept below the other floating windows.
+    is_kept_below: bool,
+
+    /// Whether this window is minimized.
+    is_minimized: bool,
+
     /// Whether this window is a target of a window cast.
     is_window_cast_target: bool,
 
+    /// Monotonic time when this window last received the keyboard focus.
+    ///
+    /// Used for most-recently-used ordering in the window switcher.
+    focus_timestamp: Option<Duration>,
+
     /// Whether this window should ignore opacity set through window rules.
     ignore_opacity_window_rule: bool,
 
     /// Buffer to draw instead of the window when it should be blocked out.
     block_out_buffer: RefCell<SolidColorBuffer>,
 
+    /// Icon set by the client through xdg-toplevel-icon.
+    icon: Option<Arc<ToplevelIcon>>,
+
+    /// Texture of the last rendered icon buffer, along with the buffer index.
+    icon_texture: RefCell<Option<(usize, TextureBuffer<GlesTexture>)>>,
+
     /// Whether the next configure should be animated, if the configured state changed.
     animate_next_configure: bool,
 
@@ -224,6 +252,7 @@ impl Mapped {
     pub fn new(window: Window, rules: ResolvedWindowRules, hook: HookId) -> Self {
         let surface = window.wl_surface().expect("no X11 support");
         let credentials = get_credentials_for_surface(&surface);
+        let icon = toplevel_icon(&surface);
 
         Self {
             window,
@@ -239,9 +268,16 @@ impl Mapped {
             is_focused: false,
             is_active_in_column: true,
             is_floating: false,
+            is_pinned: false,
+            is_kept_above: false,
+            is_kept_below: false,
+            is_minimized: false,
             is_window_cast_target: false,
+            focus_timestamp: None,
             ignore_opacity_window_rule: false,
             block_out_buffer: RefCell::new(SolidColorBuffer::new((0., 0.), [0., 0., 0., 1.])),
+            icon,
+            icon_texture: RefCell::new(None),
             animate_next_configure: false,
             animate_serials: Vec::new(),
             animation_snapshot: None,
@@ -319,10 +355,34 @@ impl Mapped {
         self.is_floating
     }
 
+    pub fn is_pinned(&self) -> bool {
+        self.is_pinned
+    }
+
+    pub fn is_kept_above(&self) -> bool {
+        self.is_kept_above
+    }
+
+    pub fn is_kept_below(&self) -> bool {
+        self.is_kept_below
+    }
+
+    pub fn is_minimized(&self) -> bool {
+        self.is_minimized
+    }
+
     pub fn is_window_cast_target(&self) -> bool {
         self.is_window_cast_target
     }
 
+    pub fn focus_timestamp(&self) -> Option<Duration> {
+        self.focus_timestamp
+    }
+
+    pub fn set_focus_timestamp(&mut self, timestamp: Duration) {
+        self.focus_timestamp = Some(timestamp);
+    }
+
     pub fn toggle_ignore_opacity_window_rule(&mut self) {
         self.ignore_opacity_window_rule = !self.ignore_opacity_window_rule;
     }
@@ -530,6 +590,27 @@ impl Mapped {
     pub fn is_urgent(&self) -> bool {
         self.is_urgent
     }
+
+    pub fn icon(&self) -> Option<&Arc<ToplevelIcon>> {
+        self.icon.as_ref()
+    }
+
+    /// Picks up the icon committed by the client and returns whether it changed.
+    pub fn update_icon(&mut self) -> bool {
+        let icon = toplevel_icon(self.toplevel().wl_surface());
+
+        let changed = match (&self.icon, &icon) {
+            (Some(a), Some(b)) => !Arc::ptr_eq(a, b),
+            (None, None) => false,
+            _ => true,
+        };
+        if changed {
+            self.icon = icon;
+            self.icon_texture.take();
+        }
+
+        changed
+    }
 }
 
 impl Drop for Mapped {
@@ -581,17 +662,22 @@ impl LayoutElement for Mapped {
             for (popup, popup_offset) in PopupManager::popups_for_surface(surface) {
                 let offset = self.window.geometry().loc + popup_offset - popup.geometry().loc;
 
-                rv.popups.extend(render_elements_from_surface_tree(
+                let elements = render_elements_from_surface_tree(
                     renderer,
                     popup.wl_surface(),
                     (buf_pos + offset.to_f64()).to_physical_precise_round(scale),
                     scale,
                     alpha,
                     Kind::Unspecified,
+                );
+                rv.popups.extend(color_transform_surface_elements(
+                    renderer,
+                    popup.wl_surface(),
+                    elements,
                 ));
             }
 
-            rv.normal = render_elements_from_surface_tree(
+            let elements = render_elements_from_surface_tree(
                 renderer,
                 surface,
                 buf_pos.to_physical_precise_round(scale),
@@ -599,6 +685,7 @@ impl LayoutElement for Mapped {
                 alpha,
                 Kind::Unspecified,
             );
+            rv.normal = color_transform_surface_elements(renderer, surface, elements).collect();
         }
 
         rv
@@ -621,14 +708,15 @@ impl LayoutElement for Mapped {
         } else {
             let buf_pos = location - self.window.geometry().loc.to_f64();
             let surface = self.toplevel().wl_surface();
-            render_elements_from_surface_tree(
+            let elements = render_elements_from_surface_tree(
                 renderer,
                 surface,
                 buf_pos.to_physical_precise_round(scale),
                 scale,
                 alpha,
                 Kind::Unspecified,
-            )
+            );
+            color_transform_surface_elements(renderer, surface, elements).collect()
         }
     }
 
@@ -650,13 +738,18 @@ impl LayoutElement for Mapped {
             for (popup, popup_offset) in PopupManager::popups_for_surface(surface) {
                 let offset = self.window.geometry().loc + popup_offset - popup.geometry().loc;
 
-                rv.extend(render_elements_from_surface_tree(
+                let elements = render_elements_from_surface_tree(
                     renderer,
                     popup.wl_surface(),
                     (buf_pos + offset.to_f64()).to_physical_precise_round(scale),
                     scale,
                     alpha,
                     Kind::Unspecified,
+                );
+                rv.extend(color_transform_surface_elements(
+                    renderer,
+                    popup.wl_surface(),
+                    elements,
                 ));
             }
 
@@ -664,6 +757,42 @@ impl LayoutElement for Mapped {
         }
     }
 
+    fn render_icon<R: NiriRenderer>(
+        &self,
+        renderer: &mut R,
+        location: Point<f64, Logical>,
+        size: f64,
+        scale: Scale<f64>,
+        alpha: f32,
+    ) -> Option<PrimaryGpuTextureRenderElement> {
+        let icon = self.icon.as_ref()?;
+
+        let size_px = (size * scale.x).ceil() as i32;
+        let (idx, buffer) = icon.best_buffer(size_px)?;
+
+        let mut cached = self.icon_texture.borrow_mut();
+        let texture = match &*cached {
+            Some((cached_idx, texture)) if *cached_idx == idx => texture.clone(),
+            _ => {
+                let texture =
+                    TextureBuffer::from_memory_buffer(renderer.as_gles_renderer(), &buffer.buffer)
+                        .ok()?;
+                *cached = Some((idx, texture.clone()));
+                texture
+            }
+        };
+
+        let elem = TextureRenderElement::from_texture_buffer(
+            texture,
+            location,
+            alpha,
+            None,
+            Some(Size::from((size, size))),
+            Kind::Unspecified,
+        );
+        Some(PrimaryGpuTextureRenderElement(elem))
+    }
+
     fn request_size(
         &mut self,
         size: Size<i32, Logical>,
@@ -854,6 +983,14 @@ impl LayoutElement for Mapped {
         self.is_urgent
     }
 
+    fn title(&self) -> Option<String> {
+        with_toplevel_role(self.toplevel(), |role| role.title.clone())
+    }
+
+    fn app_id(&self) -> Option<String> {
+        with_toplevel_role(self.toplevel(), |role| role.app_id.clone())
+    }
+
     fn set_activated(&mut self, active: bool) {
         let changed = self.toplevel().with_pending_state(|state| {
             if active {
@@ -877,6 +1014,22 @@ impl LayoutElement for Mapped {
         self.need_to_recompute_rules |= changed;
     }
 
+    fn set_pinned(&mut self, pinned: bool) {
+        self.is_pinned = pinned;
+    }
+
+    fn set_kept_above(&mut self, kept_above: bool) {
+        self.is_kept_above = kept_above;
+    }
+
+    fn set_kept_below(&mut self, kept_below: bool) {
+        self.is_kept_below = kept_below;
+    }
+
+    fn set_minimized(&mut self, minimized: bool) {
+        self.is_minimized = minimized;
+    }
+
     fn set_bounds(&self, bounds: Size<i32, Logical>) {
         self.toplevel().with_pending_state(|state| {
             state.bounds = Some(bounds);
@@ -1166,6 +1319,10 @@ impl LayoutElement for Mapped {
         self.toplevel().parent().as_ref() == Some(parent.toplevel().wl_surface())
     }
 
+    fn is_modal(&self) -> bool {
+        WindowRef::Mapped(self).is_modal()
+    }
+
     fn refresh(&self) {
         self.window.refresh();
     }
@@ -12,6 +12,7 @@ use smithay::wayland::shell::xdg::{
     SurfaceCachedState, ToplevelSurface, XdgToplevelSurfaceRoleAttributes,
 };
 
+use crate::protocols::xdg_dialog;
 use crate::utils::with_toplevel_role;
 
 pub mod mapped;
@@ -65,6 +66,15 @@ pub struct ResolvedWindowRules {
     /// Whether the window should open floating.
     pub open_floating: Option<bool>,
 
+    /// Whether the window should open pinned.
+    pub open_pinned: Option<bool>,
+
+    /// Whether the window should open kept above the other floating windows.
+    pub open_keep_above: Option<bool>,
+
+    /// Whether the window should open kept below the other floating windows.
+    pub open_keep_below: Option<bool>,
+
     /// Whether the window should open focused.
     pub open_focused: Option<bool>,
 
@@ -160,6 +170,12 @@ impl<'a> WindowRef<'a> {
         }
     }
 
+    pub fn is_modal(self) -> bool {
+        // The dialog hint has no effect on toplevels without a parent.
+        let toplevel = self.toplevel();
+        toplevel.parent().is_some() && xdg_dialog::is_modal(toplevel.wl_surface())
+    }
+
     pub fn is_window_cast_target(self) -> bool {
         match self {
             WindowRef::Unmapped(_) => false,
@@ -180,6 +196,9 @@ impl ResolvedWindowRules {
             open_maximized: None,
             open_fullscreen: None,
             open_floating: None,
+            open_pinned: None,
+            open_keep_above: None,
+            open_keep_below: None,
             open_focused: None,
             min_width: None,
             min_height: None,
@@ -306,6 +325,18 @@ impl ResolvedWindowRules {
                     resolved.open_floating = Some(x);
                 }
 
+                if let Some(x) = rule.open_pinned {
+                    resolved.open_pinned = Some(x);
+                }
+
+                if let Some(x) = rule.open_keep_above {
+                    resolved.open_keep_above = Some(x);
+                }
+
+                if let Some(x) = rule.open_keep_below {
+                    resolved.open_keep_below = Some(x);
+                }
+
                 if let Some(x) = rule.open_focused {
                     resolved.open_focused = Some(x);
                 }
@@ -409,6 +440,14 @@ impl ResolvedWindowRules {
     }
 
     pub fn compute_open_floating(&self, toplevel: &ToplevelSurface) -> bool {
+        // Only floating windows can be pinned or kept above or below.
+        if self.open_pinned == Some(true)
+            || self.open_keep_above == Some(true)
+            || self.open_keep_below == Some(true)
+        {
+            return true;
+        }
+
         if let Some(res) = self.open_floating {
             return res;
         }
@@ -486,6 +525,12 @@ fn window_matches(window: WindowRef, role: &XdgToplevelSurfaceRoleAttributes, m:
         }
     }
 
+    if let Some(is_modal) = m.is_modal {
+        if window.is_modal() != is_modal {
+            return false;
+        }
+    }
+
     if let Some(is_window_cast_target) = m.is_window_cast_target {
         if window.is_window_cast_target() != is_window_cast_target {
             return false;